@@ -5,6 +5,19 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+////////////
+/// Library info
+////////////
+// Check `streams_abi_version() == STREAMS_ABI_VERSION` when loading the library dynamically
+#define STREAMS_ABI_VERSION 2
+
+#define STREAMS_FEATURE_STD (1u << 0)
+#define STREAMS_FEATURE_SYNC_CLIENT (1u << 1)
+#define STREAMS_FEATURE_BRANCHING (1u << 2)
+
+extern uint32_t streams_abi_version();
+extern uint32_t streams_features();
+
 typedef struct Address address_t;
 extern void drop_address(address_t const *);
 
@@ -23,12 +36,18 @@ extern void drop_unwrapped_message(unwrapped_message_t const *);
 typedef struct UnwrappedMessages unwrapped_messages_t;
 extern void drop_unwrapped_messages(unwrapped_messages_t const *);
 
+typedef struct UserState user_state_t;
+extern void drop_user_state(user_state_t const *);
+
 typedef struct MessageLinks {
   address_t const *msg_link;
   address_t const *seq_link;
 } message_links_t;
 
 extern void drop_links(message_links_t);
+// `<msg_link>[;<seq_link>]`, drop with `drop_str`
+extern char const *message_links_to_string(message_links_t links);
+extern message_links_t message_links_from_string(char const *links_str);
 
 typedef struct Buffer {
   uint8_t const *ptr;
@@ -52,8 +71,17 @@ typedef struct Transport transport_t;
 extern transport_t *tsp_new();
 extern void tsp_drop(transport_t *);
 #ifdef IOTA_STREAMS_CHANNELS_CLIENT
+typedef struct SendOptions {
+  uint8_t depth;
+  uint8_t mwm;
+  uint8_t local_pow;
+  size_t threads;
+} send_options_t;
+
 extern transport_t *tsp_client_new_from_url(char const *url);
 extern void tsp_client_set_mwm(transport_t *tsp, uint8_t mwm);
+extern send_options_t tsp_get_send_options(transport_t const *tsp);
+extern void tsp_set_send_options(transport_t *tsp, send_options_t opts);
 #endif
 
 ////////////
@@ -61,7 +89,10 @@ extern void tsp_client_set_mwm(transport_t *tsp, uint8_t mwm);
 ////////////
 typedef struct Author author_t;
 
+// Constructors return NULL if the seed is invalid: 8 to 81 characters A-Z and 9, or 16 to 64 bytes,
+// or if multi_branching is set and the library is built without STREAMS_FEATURE_BRANCHING.
 extern author_t *auth_new(char const *seed, char const *encoding, size_t payload_length, uint8_t multi_branching, transport_t *tsp);
+extern author_t *auth_new_from_seed_bytes(uint8_t const *seed_ptr, size_t seed_size, char const *encoding, size_t payload_length, uint8_t multi_branching, transport_t *tsp);
 extern void auth_drop(author_t *);
 
 extern channel_address_t const *auth_channel_address(author_t const *user);
@@ -91,12 +122,15 @@ extern unwrapped_message_t const *auth_receive_msg(author_t *author, address_t c
 // Fetching/Syncing
 extern unwrapped_messages_t const *auth_fetch_next_msgs(author_t *author);
 extern unwrapped_messages_t const *auth_sync_state(author_t *author);
+extern user_state_t const *auth_fetch_state(author_t *author);
 
 /////////////
 // Subscriber
 /////////////
 typedef struct Subscriber subscriber_t;
+// Constructors return NULL if the seed is invalid: 8 to 81 characters A-Z and 9, or 16 to 64 bytes.
 extern subscriber_t *sub_new(char const *seed, char const *encoding, size_t payload_length, transport_t *tsp);
+extern subscriber_t *sub_new_from_seed_bytes(uint8_t const *seed_ptr, size_t seed_size, char const *encoding, size_t payload_length, transport_t *tsp);
 extern void sub_drop(subscriber_t *);
 
 extern channel_address_t const *sub_channel_address(subscriber_t const *user);
@@ -128,6 +162,7 @@ extern unwrapped_message_t const *sub_receive_msg(subscriber_t *subscriber, addr
 // Fetching/Syncing
 extern unwrapped_messages_t const *sub_fetch_next_msgs(subscriber_t *subscriber);
 extern unwrapped_messages_t const *sub_sync_state(subscriber_t *subscriber);
+extern user_state_t const *sub_fetch_state(subscriber_t *subscriber);
 
 /////////////
 /// Utility
@@ -139,8 +174,58 @@ extern char const *get_msgid_str(msgid_t const *msgid);
 
 extern char const *get_address_inst_str(address_t const *address);
 extern char const *get_address_id_str(address_t const *address);
+// `<appinst>:<msgid>:<checksum>` in trytes, drop with `drop_str`
+extern char const *address_to_string(address_t const *address);
+// Parse `<appinst>:<msgid>:<checksum>` in trytes, drop with `drop_address`
+extern address_t const *address_from_string(char const *addr_str);
 
 extern packet_payloads_t get_payload(unwrapped_message_t const *message);
 extern packet_payloads_t get_indexed_payload(unwrapped_messages_t const *messages, size_t index);
+// Sender public key of signed messages, drop with `drop_buffer`
+extern buffer_t get_payload_pk(unwrapped_message_t const *message);
+extern buffer_t get_indexed_payload_pk(unwrapped_messages_t const *messages, size_t index);
+
+// Caller-allocated variants: query the size of a payload part, then copy it into a buffer
+// of at least that size; `*_copy_into` returns the size of the part and copies nothing if
+// it exceeds `cap`
+#define PAYLOAD_PART_PUBLIC 0
+#define PAYLOAD_PART_MASKED 1
+#define PAYLOAD_PART_PK 2
+extern size_t get_payload_size(unwrapped_message_t const *message, uint8_t part);
+extern size_t get_payload_copy_into(unwrapped_message_t const *message, uint8_t part, uint8_t *ptr, size_t cap);
+extern size_t get_indexed_payload_size(unwrapped_messages_t const *messages, size_t index, uint8_t part);
+extern size_t get_indexed_payload_copy_into(unwrapped_messages_t const *messages, size_t index, uint8_t part, uint8_t *ptr, size_t cap);
+
+extern address_t const *get_link_from_state(user_state_t const *state, public_key_t const *pub_key);
+
+// Identifier of the 32-byte pre-shared key `psk` written into the 16-byte buffer `pskid`
+extern void pskid_from_psk(uint8_t const *psk, uint8_t *pskid);
+
+/////////////
+/// Logging
+/////////////
+// `level`: 0 off, 1 error, 2 warn, 3 info, 4 debug, 5 trace; `msg` is valid during the call only
+typedef void (*streams_log_callback_t)(uint8_t level, char const *msg);
+extern void streams_set_logger(uint8_t level, streams_log_callback_t callback);
+
+/////////////
+/// Arena
+/////////////
+// Values passed through `streams_arena_*` functions are owned by the arena and must not be
+// dropped individually, they are all freed by `streams_arena_free_all` or `streams_arena_drop`.
+typedef struct Arena arena_t;
+extern arena_t *streams_arena_new();
+extern void streams_arena_free_all(arena_t *arena);
+extern void streams_arena_drop(arena_t *arena);
+
+extern address_t const *streams_arena_address(arena_t *arena, address_t const *address);
+extern message_links_t streams_arena_links(arena_t *arena, message_links_t links);
+extern buffer_t streams_arena_buffer(arena_t *arena, buffer_t buffer);
+extern packet_payloads_t streams_arena_payloads(arena_t *arena, packet_payloads_t payloads);
+extern char const *streams_arena_str(arena_t *arena, char const *str);
+extern next_msg_ids_t const *streams_arena_next_msg_ids(arena_t *arena, next_msg_ids_t const *ids);
+extern unwrapped_message_t const *streams_arena_unwrapped_message(arena_t *arena, unwrapped_message_t const *message);
+extern unwrapped_messages_t const *streams_arena_unwrapped_messages(arena_t *arena, unwrapped_messages_t const *messages);
+extern user_state_t const *streams_arena_user_state(arena_t *arena, user_state_t const *state);
 
 #endif //IOTA_STREAMS_CHANNELS_H
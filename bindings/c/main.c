@@ -4,7 +4,7 @@
 
 void rand_seed(char *seed, size_t n)
 {
-  static char const alphabet[] = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%^&*()-=_+";
+  static char const alphabet[] = "ABCDEFGHIJKLMNOPQRSTUVWXYZ9";
   srand((unsigned int)time(NULL));
 
   if (seed && n)
@@ -21,7 +21,7 @@ int main()
   printf("Starting c bindings test\n\n");
   transport_t *tsp = NULL;
   uint8_t multi_branching = 1;
-  char seed[] = "bindings test seed";
+  char seed[] = "BINDINGS9TEST9SEED";
   char const encoding[] = "utf-8";
   const size_t size = 1024;
 
@@ -38,6 +38,10 @@ int main()
 #endif
   printf("Making author with %s\n", seed);
   author_t *auth = auth_new(seed, encoding, size, multi_branching, tsp);
+  if (!auth) {
+    printf("Invalid seed\n");
+    return 1;
+  }
   printf("Made an author... ");
 
   // Fetch Application instance
@@ -59,7 +63,7 @@ int main()
   // Subscriber
   char const sub_seed_a[] = "SUBSCRIBERA9SEED";
   printf("Making Sub A with %s\n", sub_seed_a);
-  subscriber_t *subA = sub_new("sub_seed_a", encoding, size, tsp);
+  subscriber_t *subA = sub_new(sub_seed_a, encoding, size, tsp);
   printf("Made an sub A... \n");
 
   printf("Unwrapping announcement packet... \n");
@@ -0,0 +1,121 @@
+use super::*;
+
+/// Owner of values returned by the bindings, frees them all at once.
+pub struct Arena {
+    drops: Vec<Box<dyn FnOnce()>>,
+}
+
+impl Arena {
+    fn own<T: 'static>(&mut self, ptr: *const T) -> *const T {
+        if ptr != null() {
+            self.drops.push(Box::new(move || unsafe { drop(Box::from_raw(ptr as *mut T)); }));
+        }
+        ptr
+    }
+
+    fn free_all(&mut self) {
+        for d in self.drops.drain(..) {
+            d();
+        }
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        self.free_all()
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn streams_arena_new() -> *mut Arena {
+    Box::into_raw(Box::new(Arena { drops: Vec::new() }))
+}
+
+/// Free all values owned by the arena, the arena can be reused afterwards
+#[no_mangle]
+pub extern "C" fn streams_arena_free_all(arena: *mut Arena) {
+    unsafe { arena.as_mut().map_or((), |arena| arena.free_all()) }
+}
+
+/// Free all values owned by the arena and the arena itself
+#[no_mangle]
+pub extern "C" fn streams_arena_drop(arena: *mut Arena) {
+    if arena.is_null() {
+        return;
+    }
+    unsafe { drop(Box::from_raw(arena)); }
+}
+
+#[no_mangle]
+pub extern "C" fn streams_arena_address(arena: *mut Arena, addr: *const Address) -> *const Address {
+    unsafe { arena.as_mut().map_or(addr, |arena| arena.own(addr)) }
+}
+
+#[no_mangle]
+pub extern "C" fn streams_arena_links(arena: *mut Arena, links: MessageLinks) -> MessageLinks {
+    unsafe {
+        arena.as_mut().map_or(links, |arena| MessageLinks {
+            msg_link: arena.own(links.msg_link),
+            seq_link: arena.own(links.seq_link),
+        })
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn streams_arena_buffer(arena: *mut Arena, b: Buffer) -> Buffer {
+    unsafe {
+        arena.as_mut().map_or(b, |arena| {
+            if b.ptr != null() {
+                let (ptr, size, cap) = (b.ptr, b.size, b.cap);
+                arena.drops.push(Box::new(move || Buffer { ptr, size, cap }.drop()));
+            }
+            Buffer { ptr: b.ptr, size: b.size, cap: b.cap }
+        })
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn streams_arena_payloads(arena: *mut Arena, payloads: PacketPayloads) -> PacketPayloads {
+    PacketPayloads {
+        public_payload: streams_arena_buffer(arena, payloads.public_payload),
+        masked_payload: streams_arena_buffer(arena, payloads.masked_payload),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn streams_arena_str(arena: *mut Arena, s: *const c_char) -> *const c_char {
+    unsafe {
+        arena.as_mut().map_or(s, |arena| {
+            if s != null() {
+                arena.drops.push(Box::new(move || drop_str(s)));
+            }
+            s
+        })
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn streams_arena_next_msg_ids(arena: *mut Arena, m: *const NextMsgIds) -> *const NextMsgIds {
+    unsafe { arena.as_mut().map_or(m, |arena| arena.own(m)) }
+}
+
+#[no_mangle]
+pub extern "C" fn streams_arena_unwrapped_message(
+    arena: *mut Arena,
+    m: *const UnwrappedMessage,
+) -> *const UnwrappedMessage {
+    unsafe { arena.as_mut().map_or(m, |arena| arena.own(m)) }
+}
+
+#[no_mangle]
+pub extern "C" fn streams_arena_unwrapped_messages(
+    arena: *mut Arena,
+    ms: *const UnwrappedMessages,
+) -> *const UnwrappedMessages {
+    unsafe { arena.as_mut().map_or(ms, |arena| arena.own(ms)) }
+}
+
+#[no_mangle]
+pub extern "C" fn streams_arena_user_state(arena: *mut Arena, s: *const UserState) -> *const UserState {
+    unsafe { arena.as_mut().map_or(s, |arena| arena.own(s)) }
+}
@@ -2,7 +2,7 @@ use super::*;
 
 pub type Author = iota_streams::app_channels::api::tangle::Author<TransportWrap>;
 
-/// Generate a new Author Instance
+/// Generate a new Author Instance, null if the seed is invalid or multi-branching is not built in
 #[no_mangle]
 pub extern "C" fn auth_new(
     c_seed: *const c_char,
@@ -13,8 +13,40 @@ pub extern "C" fn auth_new(
 ) -> *mut Author {
     let seed = unsafe { CStr::from_ptr(c_seed).to_str().unwrap() };
     let encoding = unsafe { CStr::from_ptr(c_encoding).to_str().unwrap() };
+    if multi_branching != 0 && !cfg!(feature = "branching") {
+        return core::ptr::null_mut();
+    }
+    let tsp = unsafe { (*transport).clone() };
+    let seed = match seed.parse::<Seed>() {
+        Ok(seed) => seed,
+        Err(_) => return core::ptr::null_mut(),
+    };
+    let user = Author::new(&seed, encoding, payload_length, multi_branching != 0, tsp);
+    Box::into_raw(Box::new(user))
+}
+
+/// Generate a new Author Instance from a binary seed, null if the seed is invalid or multi-branching
+/// is not built in
+#[no_mangle]
+pub extern "C" fn auth_new_from_seed_bytes(
+    seed_ptr: *const uint8_t,
+    seed_size: size_t,
+    c_encoding: *const c_char,
+    payload_length: size_t,
+    multi_branching: uint8_t,
+    transport: *mut TransportWrap,
+) -> *mut Author {
+    let seed = unsafe { core::slice::from_raw_parts(seed_ptr, seed_size) };
+    let encoding = unsafe { CStr::from_ptr(c_encoding).to_str().unwrap() };
+    if multi_branching != 0 && !cfg!(feature = "branching") {
+        return core::ptr::null_mut();
+    }
     let tsp = unsafe { (*transport).clone() };
-    let user = Author::new(seed, encoding, payload_length, multi_branching != 0, tsp);
+    let seed = match Seed::from_bytes(seed) {
+        Ok(seed) => seed,
+        Err(_) => return core::ptr::null_mut(),
+    };
+    let user = Author::new(&seed, encoding, payload_length, multi_branching != 0, tsp);
     Box::into_raw(Box::new(user))
 }
 
@@ -54,7 +86,13 @@ pub extern "C" fn auth_get_public_key(user: *const Author) -> *const PublicKey {
 pub extern "C" fn auth_send_announce(user: *mut Author) -> *const Address {
     unsafe {
         user.as_mut().map_or(null(), |user| {
-            user.send_announce().map_or(null(), |a| Box::into_raw(Box::new(a)))
+            match user.send_announce() {
+                Ok(a) => Box::into_raw(Box::new(a)),
+                Err(e) => {
+                    log::error!("Failed to send announce: {}", e);
+                    null()
+                }
+            }
         })
     }
 }
@@ -245,18 +283,20 @@ pub extern "C" fn auth_fetch_next_msgs(user: *mut Author) -> *const UnwrappedMes
     }
 }
 
+#[no_mangle]
+pub extern "C" fn auth_fetch_state(user: *mut Author) -> *const UserState {
+    unsafe {
+        user.as_mut().map_or(null(), |user| {
+            user.fetch_state().map_or(null(), |state| Box::into_raw(Box::new(state)))
+        })
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn auth_sync_state(user: *mut Author) -> *const UnwrappedMessages {
     unsafe {
         user.as_mut().map_or(null(), |user| {
-            let mut ms = Vec::new();
-            loop {
-                let m = user.fetch_next_msgs();
-                if m.is_empty() {
-                    break;
-                }
-                ms.extend(m);
-            }
+            let ms = user.sync_state();
             Box::into_raw(Box::new(ms))
         })
     }
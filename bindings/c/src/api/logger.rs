@@ -0,0 +1,70 @@
+use super::*;
+
+use core::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
+use log::{
+    LevelFilter,
+    Log,
+    Metadata,
+    Record,
+};
+
+/// Host-provided log output function, receives the level (1 error .. 5 trace) and the message.
+pub type LogCallback = extern "C" fn(level: uint8_t, msg: *const c_char);
+
+/// Address of the `LogCallback`, 0 if none, as callbacks may be set while other threads log.
+static LOG_CALLBACK: AtomicUsize = AtomicUsize::new(0);
+
+fn log_callback() -> Option<LogCallback> {
+    match LOG_CALLBACK.load(Ordering::Acquire) {
+        0 => None,
+        // Only addresses of `LogCallback`s are stored.
+        addr => Some(unsafe { core::mem::transmute::<usize, LogCallback>(addr) }),
+    }
+}
+
+struct CLogger;
+
+static LOGGER: CLogger = CLogger;
+
+impl Log for CLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if let Some(callback) = log_callback() {
+            if let Ok(msg) = CString::new(record.args().to_string()) {
+                callback(record.level() as uint8_t, msg.as_ptr());
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn level_filter(level: uint8_t) -> LevelFilter {
+    match level {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Error,
+        2 => LevelFilter::Warn,
+        3 => LevelFilter::Info,
+        4 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Route log output with level up to `level` (0 off, 1 error .. 5 trace) to `callback`.
+/// Passing a null callback disables logging.
+#[no_mangle]
+pub extern "C" fn streams_set_logger(level: uint8_t, callback: Option<LogCallback>) {
+    LOG_CALLBACK.store(callback.map_or(0, |callback| callback as usize), Ordering::Release);
+    // Fails if the logger is already set, which is fine as it is always the same one.
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(if callback.is_some() { level_filter(level) } else { LevelFilter::Off });
+}
@@ -5,7 +5,10 @@ use iota_streams::{
     },
     app::{
         message::Cursor,
-        transport::tangle::MsgId,
+        transport::tangle::{
+            AddressFormat,
+            MsgId,
+        },
     },
     app_channels::api::tangle::*,
 };
@@ -18,11 +21,40 @@ use cstr_core::{
 use cty::{
     c_char,
     size_t,
+    uint32_t,
     uint8_t,
 };
 
 use iota::client::bytes_to_trytes;
 
+/// Version of the binary interface, incremented on incompatible changes of `channels.h`.
+pub const STREAMS_ABI_VERSION: uint32_t = 2;
+
+pub const STREAMS_FEATURE_STD: uint32_t = 1 << 0;
+pub const STREAMS_FEATURE_SYNC_CLIENT: uint32_t = 1 << 1;
+pub const STREAMS_FEATURE_BRANCHING: uint32_t = 1 << 2;
+
+#[no_mangle]
+pub extern "C" fn streams_abi_version() -> uint32_t {
+    STREAMS_ABI_VERSION
+}
+
+/// Bitflags of the features the library was built with.
+#[no_mangle]
+pub extern "C" fn streams_features() -> uint32_t {
+    let mut features = 0;
+    if cfg!(feature = "std") {
+        features |= STREAMS_FEATURE_STD;
+    }
+    if cfg!(feature = "sync-client") {
+        features |= STREAMS_FEATURE_SYNC_CLIENT;
+    }
+    if cfg!(feature = "branching") {
+        features |= STREAMS_FEATURE_BRANCHING;
+    }
+    features
+}
+
 #[no_mangle]
 pub extern "C" fn drop_address(addr: *const Address) {
     unsafe { Box::from_raw(addr as *mut Address); }
@@ -44,6 +76,39 @@ pub extern "C" fn drop_unwrapped_message(m: *const UnwrappedMessage) {
     unsafe { Box::from_raw(m as *mut UnwrappedMessage); }
 }
 
+pub type UserState = Vec<(PublicKey, Cursor<Address>)>;
+#[no_mangle]
+pub extern "C" fn drop_user_state(s: *const UserState) {
+    unsafe { Box::from_raw(s as *mut UserState); }
+}
+
+/// Latest link of the publisher `pub_key` in `state`, the returned link is owned by `state`
+#[no_mangle]
+pub extern "C" fn get_link_from_state(state: *const UserState, pub_key: *const PublicKey) -> *const Address {
+    unsafe {
+        state.as_ref().map_or(null(), |state| {
+            pub_key.as_ref().map_or(null(), |pub_key| {
+                state.iter()
+                    .find(|(pk, _)| pk == pub_key)
+                    .map_or(null(), |(_, cursor)| &cursor.link as *const Address)
+            })
+        })
+    }
+}
+
+/// Write the identifier of the pre-shared key `psk` (`PSK_SIZE` bytes) into `pskid` (`PSKID_SIZE` bytes)
+#[no_mangle]
+pub extern "C" fn pskid_from_psk(psk: *const uint8_t, pskid: *mut uint8_t) {
+    unsafe {
+        if psk.is_null() || pskid.is_null() {
+            return;
+        }
+        let psk = Psk::clone_from_slice(core::slice::from_raw_parts(psk, psk::PSK_SIZE));
+        let id = iota_streams::app_channels::api::tangle::pskid_from_psk(&psk);
+        core::slice::from_raw_parts_mut(pskid, psk::PSKID_SIZE).copy_from_slice(id.as_slice());
+    }
+}
+
 pub type UnwrappedMessages = Vec<UnwrappedMessage>;
 #[no_mangle]
 pub extern "C" fn drop_unwrapped_messages(ms: *const UnwrappedMessages) {
@@ -76,6 +141,62 @@ pub extern "C" fn tsp_client_new_from_url(c_url: *const c_char) -> *mut Transpor
     }
 }
 
+#[cfg(feature = "sync-client")]
+use iota_streams::app::transport::tangle::client::SendTrytesOptions;
+
+#[cfg(feature = "sync-client")]
+#[repr(C)]
+pub struct SendOptions {
+    pub depth: uint8_t,
+    pub mwm: uint8_t,
+    pub local_pow: uint8_t,
+    pub threads: size_t,
+}
+
+#[cfg(feature = "sync-client")]
+impl From<SendOptions> for SendTrytesOptions {
+    fn from(opts: SendOptions) -> Self {
+        Self {
+            depth: opts.depth,
+            min_weight_magnitude: opts.mwm,
+            local_pow: opts.local_pow != 0,
+            threads: opts.threads,
+        }
+    }
+}
+
+#[cfg(feature = "sync-client")]
+impl From<SendTrytesOptions> for SendOptions {
+    fn from(opts: SendTrytesOptions) -> Self {
+        Self {
+            depth: opts.depth,
+            mwm: opts.min_weight_magnitude,
+            local_pow: if opts.local_pow { 1 } else { 0 },
+            threads: opts.threads,
+        }
+    }
+}
+
+#[cfg(feature = "sync-client")]
+#[no_mangle]
+pub extern "C" fn tsp_get_send_options(tsp: *const TransportWrap) -> SendOptions {
+    unsafe {
+        tsp.as_ref().map_or(SendTrytesOptions::default().into(), |tsp| {
+            tsp.get_send_options().into()
+        })
+    }
+}
+
+#[cfg(feature = "sync-client")]
+#[no_mangle]
+pub extern "C" fn tsp_set_send_options(tsp: *mut TransportWrap, opts: SendOptions) {
+    unsafe {
+        tsp.as_mut().map_or((), |tsp| {
+            tsp.set_send_options(opts.into());
+        })
+    }
+}
+
 #[cfg(feature = "sync-client")]
 #[no_mangle]
 pub extern "C" fn tsp_client_set_mwm(tsp: *mut TransportWrap, mwm: uint8_t) {
@@ -141,6 +262,50 @@ pub extern "C" fn drop_links(links: MessageLinks) {
     links.drop()
 }
 
+/// Encode links as `<msg_link>` or `<msg_link>;<seq_link>`, addresses in the format of
+/// `address_from_string`
+#[no_mangle]
+pub extern "C" fn message_links_to_string(links: MessageLinks) -> *const c_char {
+    unsafe {
+        links.msg_link.as_ref().map_or(null(), |msg_link| {
+            let mut s = match msg_link.to_string_with(AddressFormat::Trytes) {
+                Ok(s) => s,
+                Err(_) => return null(),
+            };
+            if let Some(seq_link) = links.seq_link.as_ref() {
+                match seq_link.to_string_with(AddressFormat::Trytes) {
+                    Ok(seq_str) => {
+                        s.push(';');
+                        s.push_str(&seq_str);
+                    }
+                    Err(_) => return null(),
+                }
+            }
+            CString::new(s).map_or(null(), |s| s.into_raw())
+        })
+    }
+}
+
+/// Decode links encoded with `message_links_to_string`, drop with `drop_links`
+#[no_mangle]
+pub extern "C" fn message_links_from_string(c_links: *const c_char) -> MessageLinks {
+    unsafe {
+        CStr::from_ptr(c_links).to_str().ok().map_or(MessageLinks::default(), |links_str| {
+            let mut parts = links_str.splitn(2, ';');
+            let msg_link = parts.next().map(|s| Address::from_str_with(s, AddressFormat::Trytes));
+            let seq_link = parts.next().map(|s| Address::from_str_with(s, AddressFormat::Trytes));
+            match (msg_link, seq_link) {
+                (Some(Ok(msg_link)), None) => (msg_link, None).into(),
+                (Some(Ok(msg_link)), Some(Ok(seq_link))) => (msg_link, Some(seq_link)).into(),
+                _ => {
+                    log::error!("Bad message links: {}", links_str);
+                    MessageLinks::default()
+                }
+            }
+        })
+    }
+}
+
 #[repr(C)]
 pub struct Buffer {
     ptr: *const uint8_t,
@@ -282,6 +447,34 @@ pub extern "C" fn get_address_id_str(address: *mut Address) -> *mut c_char {
     }
 }
 
+/// Encode an address as `<appinst>:<msgid>:<checksum>` in trytes, drop with `drop_str`
+#[no_mangle]
+pub extern "C" fn address_to_string(address: *const Address) -> *const c_char {
+    unsafe {
+        address.as_ref().map_or(null(), |address| match address.to_string_with(AddressFormat::Trytes) {
+            Ok(s) => CString::new(s).map_or(null(), |s| s.into_raw()),
+            Err(_) => null(),
+        })
+    }
+}
+
+/// Parse an address from `<appinst>:<msgid>:<checksum>` in trytes, as returned by
+/// `address_to_string`
+#[no_mangle]
+pub extern "C" fn address_from_string(c_addr: *const c_char) -> *const Address {
+    unsafe {
+        CStr::from_ptr(c_addr).to_str().ok().map_or(null(), |addr_str| {
+            match Address::from_str_with(addr_str, AddressFormat::Trytes) {
+                Ok(addr) => Box::into_raw(Box::new(addr)),
+                Err(e) => {
+                    log::error!("Bad address {}: {}", addr_str, e);
+                    null()
+                }
+            }
+        })
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn get_payload(msg: *const UnwrappedMessage) -> PacketPayloads {
     unsafe {
@@ -298,12 +491,123 @@ pub extern "C" fn get_indexed_payload(msgs: *const UnwrappedMessages, index: siz
     }
 }
 
+/// Public key bytes of the sender of a signed message, empty buffer for unsigned messages
+#[no_mangle]
+pub extern "C" fn get_payload_pk(msg: *const UnwrappedMessage) -> Buffer {
+    unsafe {
+        msg.as_ref().map_or(Buffer::default(), handle_message_pk)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn get_indexed_payload_pk(msgs: *const UnwrappedMessages, index: size_t) -> Buffer {
+    unsafe {
+        msgs.as_ref().map_or(Buffer::default(), |msgs| {
+            msgs.get(index).map_or(Buffer::default(), handle_message_pk)
+        })
+    }
+}
+
+fn handle_message_pk(m: &UnwrappedMessage) -> Buffer {
+    match &m.body {
+        MessageContent::SignedPacket { pk, .. } => Bytes(pk.as_bytes().to_vec()).into(),
+
+        MessageContent::PrivatePacket { pk, .. } => Bytes(pk.as_bytes().to_vec()).into(),
+
+        _ => Buffer::default(),
+    }
+}
+
+pub const PAYLOAD_PART_PUBLIC: uint8_t = 0;
+pub const PAYLOAD_PART_MASKED: uint8_t = 1;
+pub const PAYLOAD_PART_PK: uint8_t = 2;
+
+/// Size of the payload `part` of the message, see `get_payload_copy_into`
+#[no_mangle]
+pub extern "C" fn get_payload_size(msg: *const UnwrappedMessage, part: uint8_t) -> size_t {
+    unsafe {
+        msg.as_ref().map_or(0, |msg| {
+            handle_message_part(msg, part, |bytes| bytes.len())
+        })
+    }
+}
+
+/// Copy the payload `part` of the message into caller-allocated `ptr` of `cap` bytes.
+/// Returns the size of the part, nothing is copied if it exceeds `cap`
+#[no_mangle]
+pub extern "C" fn get_payload_copy_into(
+    msg: *const UnwrappedMessage,
+    part: uint8_t,
+    ptr: *mut uint8_t,
+    cap: size_t,
+) -> size_t {
+    unsafe {
+        msg.as_ref().map_or(0, |msg| {
+            handle_message_part(msg, part, |bytes| copy_into(bytes, ptr, cap))
+        })
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn get_indexed_payload_size(msgs: *const UnwrappedMessages, index: size_t, part: uint8_t) -> size_t {
+    unsafe {
+        msgs.as_ref().map_or(0, |msgs| {
+            msgs.get(index)
+                .map_or(0, |msg| handle_message_part(msg, part, |bytes| bytes.len()))
+        })
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn get_indexed_payload_copy_into(
+    msgs: *const UnwrappedMessages,
+    index: size_t,
+    part: uint8_t,
+    ptr: *mut uint8_t,
+    cap: size_t,
+) -> size_t {
+    unsafe {
+        msgs.as_ref().map_or(0, |msgs| {
+            msgs.get(index).map_or(0, |msg| {
+                handle_message_part(msg, part, |bytes| copy_into(bytes, ptr, cap))
+            })
+        })
+    }
+}
+
+unsafe fn copy_into(bytes: &[u8], ptr: *mut uint8_t, cap: size_t) -> size_t {
+    if bytes.len() <= cap && ptr != core::ptr::null_mut() {
+        core::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+    }
+    bytes.len()
+}
+
+fn handle_message_part<R: Default>(m: &UnwrappedMessage, part: uint8_t, f: impl FnOnce(&[u8]) -> R) -> R {
+    let (pk, p, m) = match &m.body {
+        MessageContent::TaggedPacket { public_payload: p, masked_payload: m, } => (None, p, m),
+
+        MessageContent::SignedPacket { pk, public_payload: p, masked_payload: m } => (Some(pk), p, m),
+
+        MessageContent::PrivatePacket { pk, public_payload: p, masked_payload: m, .. } => (Some(pk), p, m),
+
+        _ => return R::default(),
+    };
+    match part {
+        PAYLOAD_PART_PUBLIC => f(&p.0),
+        PAYLOAD_PART_MASKED => f(&m.0),
+        PAYLOAD_PART_PK => pk.map_or(R::default(), |pk| f(pk.as_bytes())),
+        _ => R::default(),
+    }
+}
+
 fn handle_message_contents(m: &UnwrappedMessage) -> PacketPayloads {
     match &m.body {
         MessageContent::TaggedPacket { public_payload: p, masked_payload: m, } => (p, m).into(),
 
         MessageContent::SignedPacket { pk: _, public_payload: p, masked_payload: m } => (p, m).into(),
 
+        MessageContent::PrivatePacket { public_payload: p, masked_payload: m, .. } => (p, m).into(),
+
         _ => PacketPayloads::default(),
     }
 }
@@ -313,3 +617,9 @@ pub use auth::*;
 
 mod sub;
 pub use sub::*;
+
+mod arena;
+pub use arena::*;
+
+mod logger;
+pub use logger::*;
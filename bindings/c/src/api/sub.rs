@@ -2,7 +2,7 @@ use super::*;
 
 pub type Subscriber = iota_streams::app_channels::api::tangle::Subscriber<TransportWrap>;
 
-/// Create a new subscriber
+/// Create a new subscriber, null if the seed is invalid
 #[no_mangle]
 pub extern "C" fn sub_new(
     c_seed: *const c_char,
@@ -13,7 +13,31 @@ pub extern "C" fn sub_new(
     let seed = unsafe { CStr::from_ptr(c_seed).to_str().unwrap() };
     let encoding = unsafe { CStr::from_ptr(c_encoding).to_str().unwrap() };
     let tsp = unsafe { (*transport).clone() };
-    let subscriber = Subscriber::new(seed, encoding, payload_length, tsp);
+    let seed = match seed.parse::<Seed>() {
+        Ok(seed) => seed,
+        Err(_) => return core::ptr::null_mut(),
+    };
+    let subscriber = Subscriber::new(&seed, encoding, payload_length, tsp);
+    Box::into_raw(Box::new(subscriber))
+}
+
+/// Create a new subscriber from a binary seed, null if the seed is invalid
+#[no_mangle]
+pub extern "C" fn sub_new_from_seed_bytes(
+    seed_ptr: *const uint8_t,
+    seed_size: size_t,
+    c_encoding: *const c_char,
+    payload_length: size_t,
+    transport: *mut TransportWrap,
+) -> *mut Subscriber {
+    let seed = unsafe { core::slice::from_raw_parts(seed_ptr, seed_size) };
+    let encoding = unsafe { CStr::from_ptr(c_encoding).to_str().unwrap() };
+    let tsp = unsafe { (*transport).clone() };
+    let seed = match Seed::from_bytes(seed) {
+        Ok(seed) => seed,
+        Err(_) => return core::ptr::null_mut(),
+    };
+    let subscriber = Subscriber::new(&seed, encoding, payload_length, tsp);
     Box::into_raw(Box::new(subscriber))
 }
 
@@ -181,15 +205,17 @@ pub extern "C" fn sub_fetch_next_msgs(user: *mut Subscriber) -> *const Unwrapped
 pub extern "C" fn sub_sync_state(user: *mut Subscriber) -> *const UnwrappedMessages {
     unsafe {
         user.as_mut().map_or(null(), |user| {
-            let mut ms = Vec::new();
-            loop {
-                let m = user.fetch_next_msgs();
-                if m.is_empty() {
-                    break;
-                }
-                ms.extend(m);
-            }
+            let ms = user.sync_state();
             Box::into_raw(Box::new(ms))
         })
     }
 }
+
+#[no_mangle]
+pub extern "C" fn sub_fetch_state(user: *mut Subscriber) -> *const UserState {
+    unsafe {
+        user.as_mut().map_or(null(), |user| {
+            user.fetch_state().map_or(null(), |state| Box::into_raw(Box::new(state)))
+        })
+    }
+}
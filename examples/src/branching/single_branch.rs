@@ -34,12 +34,12 @@ pub fn example<T: Transport>(
 ) -> Result<()>
 {
     let encoding = "utf-8";
-    let mut author = Author::new(seed, encoding, PAYLOAD_BYTES, multi_branching, transport.clone());
+    let mut author = Author::new(&seed.parse()?, encoding, PAYLOAD_BYTES, multi_branching, transport.clone());
     println!("Author multi branching?: {}", author.is_multi_branching());
 
-    let mut subscriberA = Subscriber::new("SUBSCRIBERA9SEED", encoding, PAYLOAD_BYTES, transport.clone());
-    let mut subscriberB = Subscriber::new("SUBSCRIBERB9SEED", encoding, PAYLOAD_BYTES, transport.clone());
-    let mut subscriberC = Subscriber::new("SUBSCRIBERC9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberA = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberB = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberC = Subscriber::new(&"SUBSCRIBERC9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
 
     let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
     let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
@@ -0,0 +1,18 @@
+//! Wrapping time of keyloads by number of public key recipients, compare with and without the
+//! `parallel` feature: `cargo bench --bench keyload --features bench[,parallel]`.
+
+#[macro_use]
+extern crate criterion;
+
+use criterion::Criterion;
+use iota_streams_app_channels::api::{
+    bench,
+    tangle::DefaultF,
+};
+
+fn keyload_benchmark(c: &mut Criterion) {
+    bench::keyload_scaling::<DefaultF>(c, "KeccakF1600", &[10, 100, 500]).unwrap();
+}
+
+criterion_group!(benches, keyload_benchmark);
+criterion_main!(benches);
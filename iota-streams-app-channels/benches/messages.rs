@@ -0,0 +1,19 @@
+//! Throughput of the PRP and wrapping and unwrapping time of each message type:
+//! `cargo bench --bench messages --features bench`.
+
+#[macro_use]
+extern crate criterion;
+
+use criterion::Criterion;
+use iota_streams_app_channels::api::{
+    bench,
+    tangle::DefaultF,
+};
+
+fn messages_benchmark(c: &mut Criterion) {
+    bench::permutation::<DefaultF>(c, "KeccakF1600");
+    bench::messages::<DefaultF>(c, "KeccakF1600", &[32, 256, 1024]).unwrap();
+}
+
+criterion_group!(benches, messages_benchmark);
+criterion_main!(benches);
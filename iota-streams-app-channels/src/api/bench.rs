@@ -0,0 +1,176 @@
+//! Microbenchmarks of channels measured with criterion on the target hardware.
+//!
+//! Integrators register the entry points in their own criterion harness, generic over the PRP,
+//! to compare candidate PRPs, payload sizes and recipient counts, eg. in a bench target with
+//! `harness = false`:
+//!
+//! ```ignore
+//! use criterion::{criterion_group, criterion_main, Criterion};
+//! use iota_streams_app_channels::api::{bench, tangle::DefaultF};
+//!
+//! fn streams(c: &mut Criterion) {
+//!     bench::permutation::<DefaultF>(c, "KeccakF1600");
+//!     bench::messages::<DefaultF>(c, "KeccakF1600", &[32, 1024]).unwrap();
+//!     bench::keyload_scaling::<DefaultF>(c, "KeccakF1600", &[10, 100, 500]).unwrap();
+//! }
+//!
+//! criterion_group!(benches, streams);
+//! criterion_main!(benches);
+//! ```
+//!
+//! Messages are wrapped and unwrapped in memory, transports are not involved.
+
+use anyhow::Result;
+use criterion::{
+    BenchmarkId,
+    Criterion,
+    Throughput,
+};
+
+use iota_streams_app::{
+    message::HasLink as _,
+    transport::tangle::{
+        DefaultTangleLinkGenerator,
+        MsgId,
+        TangleAddress,
+        PAYLOAD_BYTES,
+    },
+};
+use iota_streams_core::{
+    prelude::{
+        typenum::Unsigned as _,
+        Vec,
+    },
+    prng,
+    sponge::prp::PRP,
+};
+use iota_streams_ddml::{
+    link_store::DefaultLinkStore,
+    types::Bytes,
+};
+
+use super::{
+    tangle::{
+        MsgInfo,
+        PkStore,
+        PskStore,
+    },
+    user::User,
+};
+
+type BenchUser<F> =
+    User<F, TangleAddress, DefaultTangleLinkGenerator<F>, DefaultLinkStore<F, MsgId, MsgInfo>, PkStore, PskStore>;
+
+fn bench_user<F: PRP>(seed: &[u8]) -> BenchUser<F> {
+    BenchUser::<F>::gen(
+        prng::from_seed_bytes("IOTA Streams Channels bench user", seed),
+        b"BENCHUSERNONCE".to_vec(),
+        0,
+        b"utf-8".to_vec(),
+        PAYLOAD_BYTES,
+    )
+}
+
+/// Throughput of the PRP transform, `name` identifies the PRP in reports.
+pub fn permutation<F: PRP>(c: &mut Criterion, name: &str) {
+    let mut group = c.benchmark_group("PRP transform");
+    group.throughput(Throughput::Bytes(F::RateSize::USIZE as u64));
+    let mut prp = F::default();
+    group.bench_function(name, |b| b.iter(|| prp.transform()));
+    group.finish();
+}
+
+/// Wrapping and unwrapping time of each message type, packets with public and masked payloads
+/// of each of `payload_sizes` bytes. `name` identifies the PRP in reports.
+pub fn messages<F: PRP>(c: &mut Criterion, name: &str, payload_sizes: &[usize]) -> Result<()> {
+    let mut author = bench_user::<F>(b"AUTHOR");
+    let mut subscriber = bench_user::<F>(b"SUBSCRIBER");
+    author.create_channel(0)?;
+    let announcement = author.announce()?;
+    subscriber.handle_announcement(announcement.message.clone(), MsgInfo::Announce)?;
+    let announcement_link = author.commit_wrapped(announcement.wrapped, MsgInfo::Announce)?;
+    let subscribe = subscriber.subscribe(announcement_link.rel())?;
+    author.handle_subscribe(subscribe.message.clone(), MsgInfo::Subscribe)?;
+    let keyload = author.share_keyload_for_everyone(announcement_link.rel())?;
+    subscriber.handle_keyload(keyload.message.clone(), MsgInfo::Keyload)?;
+    let keyload_link = author.commit_wrapped(keyload.wrapped, MsgInfo::Keyload)?;
+
+    let mut packets = Vec::with_capacity(payload_sizes.len());
+    let mut group = c.benchmark_group(format!("Wrap, {}", name));
+    group.bench_function("announce", |b| b.iter(|| author.announce().unwrap()));
+    group.bench_function("subscribe", |b| {
+        b.iter(|| subscriber.subscribe(announcement_link.rel()).unwrap())
+    });
+    group.bench_function("keyload", |b| {
+        b.iter(|| author.share_keyload_for_everyone(announcement_link.rel()).unwrap())
+    });
+    for size in payload_sizes {
+        let payload = Bytes(vec![0; *size]);
+        group.throughput(Throughput::Bytes(2 * *size as u64));
+        group.bench_with_input(BenchmarkId::new("signed packet", size), &payload, |b, payload| {
+            b.iter(|| author.sign_packet(keyload_link.rel(), payload, payload).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("tagged packet", size), &payload, |b, payload| {
+            b.iter(|| author.tag_packet(keyload_link.rel(), payload, payload).unwrap())
+        });
+        let signed = author.sign_packet(keyload_link.rel(), &payload, &payload)?.message;
+        let tagged = author.tag_packet(keyload_link.rel(), &payload, &payload)?.message;
+        packets.push((size, signed, tagged));
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group(format!("Unwrap, {}", name));
+    group.bench_function("announce", |b| {
+        b.iter(|| {
+            subscriber
+                .unwrap_announcement(announcement.message.parse_header().unwrap())
+                .unwrap()
+        })
+    });
+    group.bench_function("subscribe", |b| {
+        b.iter(|| {
+            author
+                .unwrap_subscribe(subscribe.message.parse_header().unwrap())
+                .unwrap()
+        })
+    });
+    group.bench_function("keyload", |b| {
+        b.iter(|| {
+            subscriber
+                .unwrap_keyload(keyload.message.parse_header().unwrap())
+                .unwrap()
+        })
+    });
+    for (size, signed, tagged) in packets.iter() {
+        group.throughput(Throughput::Bytes(2 * **size as u64));
+        group.bench_with_input(BenchmarkId::new("signed packet", size), signed, |b, msg| {
+            b.iter(|| subscriber.unwrap_signed_packet(msg.parse_header().unwrap()).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("tagged packet", size), tagged, |b, msg| {
+            b.iter(|| subscriber.unwrap_tagged_packet(msg.parse_header().unwrap()).unwrap())
+        });
+    }
+    group.finish();
+    Ok(())
+}
+
+/// Wrapping time of keyloads for everyone by number of public key recipients, compare with and
+/// without the `parallel` feature. `name` identifies the PRP in reports.
+pub fn keyload_scaling<F: PRP>(c: &mut Criterion, name: &str, recipient_counts: &[usize]) -> Result<()> {
+    let mut group = c.benchmark_group(format!("Wrap keyload for everyone, {}", name));
+    group.sample_size(10);
+    for recipients in recipient_counts {
+        let mut author = bench_user::<F>(b"AUTHOR");
+        author.create_channel(0)?;
+        let announcement = author.announce()?;
+        let announcement_link = author.commit_wrapped(announcement.wrapped, MsgInfo::Announce)?;
+        for i in 0..*recipients as u64 {
+            author.insert_recipient(bench_user::<F>(&i.to_be_bytes()).sig_kp.public)?;
+        }
+        group.bench_with_input(BenchmarkId::from_parameter(recipients), recipients, |b, _| {
+            b.iter(|| author.share_keyload_for_everyone(announcement_link.rel()).unwrap())
+        });
+    }
+    group.finish();
+    Ok(())
+}
@@ -0,0 +1,40 @@
+/// Branch policy byte of keyloads: messages of the branch are sequenced as announced for the channel.
+pub const BRANCH_CHANNEL: u8 = 0;
+
+/// Branch policy byte of keyloads: messages of the branch follow each other linearly.
+pub const BRANCH_LINEAR: u8 = 1;
+
+/// Sequencing of messages linked to a keyload, declared by the keyload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BranchPolicy {
+    /// Messages are sequenced as announced for the channel.
+    Channel,
+    /// Messages are not sequenced by sequence messages. Each message of the branch follows the
+    /// previous one regardless of its publisher, as in single-branch channels, so publishers
+    /// must be in sync with the branch before publishing. Only differs from `Channel` in
+    /// multi-branch channels.
+    Linear,
+}
+
+impl BranchPolicy {
+    pub fn from_u8(policy: u8) -> Self {
+        if policy == BRANCH_LINEAR {
+            BranchPolicy::Linear
+        } else {
+            BranchPolicy::Channel
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            BranchPolicy::Channel => BRANCH_CHANNEL,
+            BranchPolicy::Linear => BRANCH_LINEAR,
+        }
+    }
+}
+
+impl Default for BranchPolicy {
+    fn default() -> Self {
+        BranchPolicy::Channel
+    }
+}
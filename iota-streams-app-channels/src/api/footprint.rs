@@ -0,0 +1,32 @@
+/// Approximate memory used by the state of a user, see `User::memory_footprint`.
+///
+/// Sizes are in bytes and count stored entries only, allocator and hash table overhead is
+/// not included. Comparing footprints over time tells whether state keeps growing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryFootprint {
+    /// Number of links in the link store.
+    pub links: usize,
+    /// Size of links together with their spongos states and infos.
+    pub link_store: usize,
+    /// Number of known publishers.
+    pub cursors: usize,
+    /// Size of publisher keys together with their sequencing cursors.
+    pub pk_store: usize,
+    /// Number of pre-shared keys.
+    pub psks: usize,
+    /// Size of pre-shared keys and their identifiers.
+    pub psk_store: usize,
+    /// Size of spongos states of cached keyloads.
+    pub session_cache: usize,
+    /// Capacity of pooled message buffers.
+    pub buffer_pool: usize,
+    /// Size of delegations, read-only lists, rate and sequence tracking.
+    pub tracking: usize,
+}
+
+impl MemoryFootprint {
+    /// Total size in bytes.
+    pub fn total(&self) -> usize {
+        self.link_store + self.pk_store + self.psk_store + self.session_cache + self.buffer_pool + self.tracking
+    }
+}
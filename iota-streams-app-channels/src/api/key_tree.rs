@@ -0,0 +1,466 @@
+//! Tree-based group keying of large subscriber sets.
+//!
+//! Keyloads list every recipient, so their size and wrap time grow linearly with the number of
+//! subscribers. With a key tree the author shares keyloads with a single pre-shared key instead,
+//! the group key, which members derive from a logical key hierarchy as in TreeKEM:
+//!
+//! * members are the leaves of a complete binary tree, every node has a key and a member knows
+//!   the keys of the nodes on the path from its leaf to the root, the root key is the group key;
+//! * adding or removing a member refreshes the keys on the path of its leaf: the new key of a
+//!   node is encrypted under the keys of its children having members, so a `TreeUpdate` holds at
+//!   most two entries per level, ie. it's logarithmic in the number of members;
+//! * a new member is welcomed with its leaf key and decrypts its path from the update, a removed
+//!   member knows none of the keys the new path keys are encrypted under.
+//!
+//! Nodes are numbered as in a binary heap: the root is 1, the children of node `i` are `2i` and
+//! `2i + 1`, and leaves are numbered from `capacity` to `2 capacity - 1`.
+//!
+//! ```text
+//! update = epoch: u32 || count: u32 || count * (node: u32 || under: u32 || ciphertext: [u8; 32] || tag: [u8; 16])
+//! welcome = leaf: u32 || epoch: u32 || leaf key: [u8; 32]
+//! ```
+//!
+//! Trees and member keys are persisted with the state of their user, encoded as follows; the
+//! encodings hold secret keys and must not be published:
+//!
+//! ```text
+//! tree = capacity: u32 || epoch: u32 || 2 capacity * key: [u8; 32] || count: u32 || count * (pk: [u8; 32] || leaf: u32)
+//! member keys = leaf: u32 || epoch: u32 || count: u32 || count * (node: u32 || key: [u8; 32])
+//! ```
+
+use anyhow::{
+    anyhow,
+    ensure,
+    Result,
+};
+
+use iota_streams_core::{
+    prelude::{
+        HashMap,
+        Vec,
+    },
+    prng,
+    psk::Psk,
+    sponge::{
+        prp::PRP,
+        spongos::Spongos,
+    },
+};
+use iota_streams_core_edsig::signature::ed25519;
+
+/// Largest number of members of a key tree.
+pub const MAX_TREE_CAPACITY: usize = 1 << 16;
+
+/// Size of node keys.
+const NODE_KEY_SIZE: usize = 32;
+
+/// Size of entry tags.
+const ENTRY_TAG_SIZE: usize = 16;
+
+/// Size of an encoded update entry.
+const ENTRY_SIZE: usize = 4 + 4 + NODE_KEY_SIZE + ENTRY_TAG_SIZE;
+
+/// Size of an encoded welcome.
+const WELCOME_SIZE: usize = 4 + 4 + NODE_KEY_SIZE;
+
+/// Size of an encoded member of a tree.
+const MEMBER_SIZE: usize = ed25519::PUBLIC_KEY_LENGTH + 4;
+
+/// Size of an encoded node key of member keys.
+const NODE_SIZE: usize = 4 + NODE_KEY_SIZE;
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Spongos keyed by the key of node `under` for entries of `epoch`.
+fn entry_spongos<F: PRP>(key: &Psk, epoch: u32, node: u32, under: u32) -> Spongos<F> {
+    let mut s = Spongos::<F>::init();
+    s.absorb(key);
+    s.absorb(&epoch.to_be_bytes());
+    s.absorb(&node.to_be_bytes());
+    s.absorb(&under.to_be_bytes());
+    s.commit();
+    s
+}
+
+/// New key of a node encrypted under the key of one of its children.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TreeEntry {
+    /// Node of the new key.
+    pub node: u32,
+    /// Child node whose key encrypts the new key.
+    pub under: u32,
+    /// Encrypted key.
+    pub ciphertext: Vec<u8>,
+    /// Authentication tag of the key.
+    pub tag: Vec<u8>,
+}
+
+/// New path keys of a membership change, entries are ordered from the leaves up.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct TreeUpdate {
+    /// Epoch of the tree after the update.
+    pub epoch: u32,
+    pub entries: Vec<TreeEntry>,
+}
+
+impl TreeUpdate {
+    /// Encode the update, see the module docs.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + ENTRY_SIZE * self.entries.len());
+        bytes.extend_from_slice(&self.epoch.to_be_bytes());
+        bytes.extend_from_slice(&(self.entries.len() as u32).to_be_bytes());
+        for entry in &self.entries {
+            bytes.extend_from_slice(&entry.node.to_be_bytes());
+            bytes.extend_from_slice(&entry.under.to_be_bytes());
+            bytes.extend_from_slice(&entry.ciphertext);
+            bytes.extend_from_slice(&entry.tag);
+        }
+        bytes
+    }
+
+    /// Decode the update encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(bytes.len() >= 8, "Bad tree update length: {}", bytes.len());
+        let epoch = read_u32(&bytes[..4]);
+        let count = read_u32(&bytes[4..8]) as usize;
+        ensure!(
+            count.checked_mul(ENTRY_SIZE) == Some(bytes.len() - 8),
+            "Bad tree update length: {}",
+            bytes.len()
+        );
+        let entries = bytes[8..]
+            .chunks(ENTRY_SIZE)
+            .map(|entry| TreeEntry {
+                node: read_u32(&entry[..4]),
+                under: read_u32(&entry[4..8]),
+                ciphertext: entry[8..8 + NODE_KEY_SIZE].to_vec(),
+                tag: entry[8 + NODE_KEY_SIZE..].to_vec(),
+            })
+            .collect();
+        Ok(Self { epoch, entries })
+    }
+}
+
+/// Leaf key handed to a new member together with the update adding it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TreeWelcome {
+    /// Leaf of the member.
+    pub leaf: u32,
+    /// Epoch of the tree before the update adding the member.
+    pub epoch: u32,
+    /// Key of the leaf.
+    pub key: Psk,
+}
+
+impl TreeWelcome {
+    /// Encode the welcome, see the module docs.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(WELCOME_SIZE);
+        bytes.extend_from_slice(&self.leaf.to_be_bytes());
+        bytes.extend_from_slice(&self.epoch.to_be_bytes());
+        bytes.extend_from_slice(&self.key);
+        bytes
+    }
+
+    /// Decode the welcome encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(bytes.len() == WELCOME_SIZE, "Bad tree welcome length: {}", bytes.len());
+        Ok(Self {
+            leaf: read_u32(&bytes[..4]),
+            epoch: read_u32(&bytes[4..8]),
+            key: Psk::clone_from_slice(&bytes[8..]),
+        })
+    }
+}
+
+/// Key tree of a group, kept by the author.
+pub struct KeyTree {
+    capacity: usize,
+    epoch: u32,
+    /// Keys of the nodes, indexed by node number; index 0 is unused.
+    keys: Vec<Psk>,
+    /// Number of members below each node.
+    members: Vec<u32>,
+    /// Leaves of the members.
+    leaves: HashMap<ed25519::PublicKeyWrap, u32>,
+}
+
+impl KeyTree {
+    /// Empty tree of `capacity` members, rounded up to a power of two.
+    pub fn new(capacity: usize) -> Result<Self> {
+        ensure!(
+            0 < capacity && capacity <= MAX_TREE_CAPACITY,
+            "Key tree capacity must be between 1 and {}.",
+            MAX_TREE_CAPACITY
+        );
+        let capacity = capacity.next_power_of_two().max(2);
+        Ok(Self {
+            capacity,
+            epoch: 0,
+            keys: (0..2 * capacity).map(|_| prng::random_key()).collect(),
+            members: vec![0; 2 * capacity],
+            leaves: HashMap::new(),
+        })
+    }
+
+    /// Largest number of members.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of updates of the tree.
+    pub fn epoch(&self) -> u32 {
+        self.epoch
+    }
+
+    /// Number of members.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Whether the tree has no members.
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Whether `pk` is a member.
+    pub fn contains(&self, pk: &ed25519::PublicKey) -> bool {
+        self.leaves.contains_key(pk.into())
+    }
+
+    /// Group key: the key of the root.
+    pub fn group_key(&self) -> &Psk {
+        &self.keys[1]
+    }
+
+    /// Add member `pk` to a free leaf, returns the welcome of the member and the update of the
+    /// other members.
+    pub fn add<F: PRP>(&mut self, pk: &ed25519::PublicKey) -> Result<(TreeWelcome, TreeUpdate)> {
+        ensure!(!self.contains(pk), "Already a member of the key tree.");
+        let leaf = (self.capacity..2 * self.capacity)
+            .find(|leaf| self.members[*leaf] == 0)
+            .ok_or_else(|| anyhow!("Key tree is full."))?;
+        self.keys[leaf] = prng::random_key();
+        let mut node = leaf;
+        while node > 0 {
+            self.members[node] += 1;
+            node /= 2;
+        }
+        self.leaves.insert(pk.clone().into(), leaf as u32);
+        let welcome = TreeWelcome {
+            leaf: leaf as u32,
+            epoch: self.epoch,
+            key: self.keys[leaf],
+        };
+        Ok((welcome, self.rekey_path::<F>(leaf)))
+    }
+
+    /// Remove member `pk`, returns the update of the other members.
+    pub fn remove<F: PRP>(&mut self, pk: &ed25519::PublicKey) -> Result<TreeUpdate> {
+        let leaf = self
+            .leaves
+            .remove(pk.into())
+            .ok_or_else(|| anyhow!("Not a member of the key tree."))? as usize;
+        let mut node = leaf;
+        while node > 0 {
+            self.members[node] -= 1;
+            node /= 2;
+        }
+        self.keys[leaf] = prng::random_key();
+        Ok(self.rekey_path::<F>(leaf))
+    }
+
+    /// Encode the tree, see the module docs.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12 + NODE_KEY_SIZE * self.keys.len() + MEMBER_SIZE * self.leaves.len());
+        bytes.extend_from_slice(&(self.capacity as u32).to_be_bytes());
+        bytes.extend_from_slice(&self.epoch.to_be_bytes());
+        for key in &self.keys {
+            bytes.extend_from_slice(key);
+        }
+        bytes.extend_from_slice(&(self.leaves.len() as u32).to_be_bytes());
+        for (pk, leaf) in &self.leaves {
+            bytes.extend_from_slice(pk.0.as_bytes());
+            bytes.extend_from_slice(&leaf.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Decode the tree encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(bytes.len() >= 8, "Bad key tree length: {}", bytes.len());
+        let capacity = read_u32(&bytes[..4]) as usize;
+        ensure!(
+            capacity.is_power_of_two() && 2 <= capacity && capacity <= MAX_TREE_CAPACITY,
+            "Bad key tree capacity: {}",
+            capacity
+        );
+        let epoch = read_u32(&bytes[4..8]);
+        let keys_end = 8 + 2 * capacity * NODE_KEY_SIZE;
+        ensure!(bytes.len() >= keys_end + 4, "Bad key tree length: {}", bytes.len());
+        let keys = bytes[8..keys_end]
+            .chunks(NODE_KEY_SIZE)
+            .map(Psk::clone_from_slice)
+            .collect();
+        let count = read_u32(&bytes[keys_end..keys_end + 4]) as usize;
+        ensure!(
+            count.checked_mul(MEMBER_SIZE) == Some(bytes.len() - keys_end - 4),
+            "Bad key tree length: {}",
+            bytes.len()
+        );
+        let mut members = vec![0; 2 * capacity];
+        let mut leaves = HashMap::new();
+        for member in bytes[keys_end + 4..].chunks(MEMBER_SIZE) {
+            let (pk, leaf) = member.split_at(ed25519::PUBLIC_KEY_LENGTH);
+            let pk = ed25519::PublicKey::from_bytes(pk).map_err(|e| anyhow!("Bad key tree member: {}", e))?;
+            let leaf = read_u32(leaf) as usize;
+            ensure!(
+                capacity <= leaf && leaf < 2 * capacity && members[leaf] == 0,
+                "Bad key tree leaf: {}",
+                leaf
+            );
+            let mut node = leaf;
+            while node > 0 {
+                members[node] += 1;
+                node /= 2;
+            }
+            ensure!(
+                leaves.insert(pk.into(), leaf as u32).is_none(),
+                "Duplicate key tree member."
+            );
+        }
+        Ok(Self {
+            capacity,
+            epoch,
+            keys,
+            members,
+            leaves,
+        })
+    }
+
+    /// Refresh the keys of the ancestors of `leaf` and encrypt them under their children with members.
+    fn rekey_path<F: PRP>(&mut self, leaf: usize) -> TreeUpdate {
+        self.epoch += 1;
+        let mut entries = Vec::new();
+        let mut node = leaf / 2;
+        while node > 0 {
+            self.keys[node] = prng::random_key();
+            for under in [2 * node, 2 * node + 1].iter().copied() {
+                if self.members[under] == 0 {
+                    continue;
+                }
+                let mut s = entry_spongos::<F>(&self.keys[under], self.epoch, node as u32, under as u32);
+                let ciphertext = s.encrypt_n(&self.keys[node]);
+                s.commit();
+                entries.push(TreeEntry {
+                    node: node as u32,
+                    under: under as u32,
+                    ciphertext,
+                    tag: s.squeeze_n(ENTRY_TAG_SIZE),
+                });
+            }
+            node /= 2;
+        }
+        TreeUpdate {
+            epoch: self.epoch,
+            entries,
+        }
+    }
+}
+
+/// Path keys of a member of a key tree.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MemberKeys {
+    /// Leaf of the member.
+    pub leaf: u32,
+    /// Epoch of the last applied update.
+    pub epoch: u32,
+    /// Known keys by node.
+    keys: HashMap<u32, Psk>,
+}
+
+impl MemberKeys {
+    /// Keys of a member welcomed with `welcome`, apply the update adding the member next.
+    pub fn new(welcome: &TreeWelcome) -> Self {
+        let mut keys = HashMap::new();
+        keys.insert(welcome.leaf, welcome.key);
+        Self {
+            leaf: welcome.leaf,
+            epoch: welcome.epoch,
+            keys,
+        }
+    }
+
+    /// Group key, `None` until an update was applied.
+    pub fn group_key(&self) -> Option<&Psk> {
+        self.keys.get(&1)
+    }
+
+    /// Encode the member keys, see the module docs.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12 + NODE_SIZE * self.keys.len());
+        bytes.extend_from_slice(&self.leaf.to_be_bytes());
+        bytes.extend_from_slice(&self.epoch.to_be_bytes());
+        bytes.extend_from_slice(&(self.keys.len() as u32).to_be_bytes());
+        for (node, key) in &self.keys {
+            bytes.extend_from_slice(&node.to_be_bytes());
+            bytes.extend_from_slice(key);
+        }
+        bytes
+    }
+
+    /// Decode the member keys encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(bytes.len() >= 12, "Bad member keys length: {}", bytes.len());
+        let count = read_u32(&bytes[8..12]) as usize;
+        ensure!(
+            count.checked_mul(NODE_SIZE) == Some(bytes.len() - 12),
+            "Bad member keys length: {}",
+            bytes.len()
+        );
+        let keys = bytes[12..]
+            .chunks(NODE_SIZE)
+            .map(|node| (read_u32(&node[..4]), Psk::clone_from_slice(&node[4..])))
+            .collect();
+        Ok(Self {
+            leaf: read_u32(&bytes[..4]),
+            epoch: read_u32(&bytes[4..8]),
+            keys,
+        })
+    }
+
+    /// Decrypt the new keys of the path of the member from `update`, updates must be applied in
+    /// order. Keys of rekeyed nodes the member can't decrypt are forgotten, returns whether the
+    /// member still knows the group key, ie. wasn't removed.
+    pub fn apply<F: PRP>(&mut self, update: &TreeUpdate) -> Result<bool> {
+        ensure!(
+            update.epoch == self.epoch + 1,
+            "Key tree update of epoch {} doesn't follow epoch {}.",
+            update.epoch,
+            self.epoch
+        );
+        let rekeyed = |node: u32| update.entries.iter().any(|entry| entry.node == node);
+        let mut fresh = HashMap::new();
+        for entry in &update.entries {
+            let key = if rekeyed(entry.under) {
+                fresh.get(&entry.under)
+            } else {
+                self.keys.get(&entry.under)
+            };
+            if let Some(key) = key {
+                let mut s = entry_spongos::<F>(key, update.epoch, entry.node, entry.under);
+                let key = s.decrypt_n(&entry.ciphertext);
+                s.commit();
+                if s.squeeze_n(ENTRY_TAG_SIZE) == entry.tag {
+                    fresh.insert(entry.node, Psk::clone_from_slice(&key));
+                }
+            }
+        }
+        self.keys.retain(|node, _| !rekeyed(*node));
+        self.keys.extend(fresh);
+        self.epoch = update.epoch;
+        Ok(self.group_key().is_some())
+    }
+}
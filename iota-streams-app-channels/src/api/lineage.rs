@@ -0,0 +1,47 @@
+use iota_streams_app::message::HasLink;
+use iota_streams_core::prelude::Vec;
+use iota_streams_core_edsig::signature::ed25519;
+
+/// How a message of a lineage was authenticated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Authentication {
+    /// Signature verified with the public key.
+    Signature(ed25519::PublicKey),
+    /// MAC verified with the session key of the linked keyload.
+    Mac,
+    /// Message is bound to the linked message by the joined spongos state only.
+    Linked,
+}
+
+/// Message of a lineage, unwrapped and authenticated again.
+pub struct LineageStep<Link: HasLink> {
+    pub link: Link,
+    pub content_type: u8,
+    /// Link of the message this message is linked to, `None` for the announcement.
+    pub link_to: Option<<Link as HasLink>::Rel>,
+    pub authentication: Authentication,
+}
+
+/// Chain of messages from a message back to the channel announcement.
+pub struct LineageProof<Link: HasLink> {
+    /// Verified messages, from the message the proof is made for to the announcement.
+    pub steps: Vec<LineageStep<Link>>,
+}
+
+impl<Link: HasLink> LineageProof<Link> {
+    /// Public keys of all signatures verified along the lineage.
+    pub fn signers(&self) -> Vec<&ed25519::PublicKey> {
+        self.steps
+            .iter()
+            .filter_map(|step| match &step.authentication {
+                Authentication::Signature(pk) => Some(pk),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Number of messages in the lineage.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+}
@@ -1,8 +1,21 @@
+pub mod branch_policy;
+pub mod footprint;
+pub mod key_tree;
+pub mod lineage;
 pub mod pk_store;
 pub mod psk_store;
+pub mod rate_limit;
+pub mod revocation;
+pub mod sequence_policy;
+pub mod session_cache;
+pub mod state;
 
 pub mod user;
 
+/// Criterion microbenchmarks of PRPs and messages.
+#[cfg(all(feature = "bench", not(feature = "async")))]
+pub mod bench;
+
 /// Tangle-specific Channel API.
 #[cfg(all(feature = "tangle"))]
 pub mod tangle;
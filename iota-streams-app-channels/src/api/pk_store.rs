@@ -17,6 +17,8 @@ pub trait PublicKeyStore<Info>: Default {
     fn get_mut(&mut self, pk: &ed25519::PublicKey) -> Option<&mut Info>;
     fn get_ke_pk(&self, pk: &ed25519::PublicKey) -> Option<&x25519::PublicKey>;
     fn insert(&mut self, pk: ed25519::PublicKey, info: Info);
+    /// Forget publisher `pk`, returns its sequence state if it was known
+    fn remove(&mut self, pk: &ed25519::PublicKey) -> Option<Info>;
     fn keys(&self) -> Vec<(&ed25519::PublicKey, &x25519::PublicKey)>;
     fn iter(&self) -> Vec<(&ed25519::PublicKey, &Info)>;
     fn iter_mut(&mut self) -> Vec<(&ed25519::PublicKey, &mut Info)>;
@@ -60,6 +62,9 @@ impl<Info> PublicKeyStore<Info> for PublicKeyMap<Info> {
         let xpk = x25519::public_from_ed25519(&pk);
         self.pks.insert(pk.into(), (xpk, info));
     }
+    fn remove(&mut self, pk: &ed25519::PublicKey) -> Option<Info> {
+        self.pks.remove(pk.into()).map(|(_x, i)| i)
+    }
     fn keys(&self) -> Vec<(&ed25519::PublicKey, &x25519::PublicKey)> {
         self.pks.iter().map(|(k, (x, _i))| (&k.0, x)).collect()
     }
@@ -1,3 +1,5 @@
+use core::fmt;
+
 use iota_streams_core::{
     prelude::{
         HashMap,
@@ -18,6 +20,15 @@ pub struct PresharedKeyMap {
     psks: HashMap<psk::PskId, psk::Psk>,
 }
 
+/// Pre-shared keys are not printed, only their identifiers.
+impl fmt::Debug for PresharedKeyMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.psks.keys().map(|pskid| format_args!("{}", hex::encode(pskid))))
+            .finish()
+    }
+}
+
 impl PresharedKeyStore for PresharedKeyMap {
     fn insert(&mut self, pskid: psk::PskId, psk: psk::Psk) {
         self.psks.insert(pskid, psk);
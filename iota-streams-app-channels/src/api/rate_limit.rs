@@ -0,0 +1,182 @@
+use anyhow::{
+    bail,
+    Result,
+};
+
+use iota_streams_core::prelude::Vec;
+use iota_streams_core_edsig::signature::ed25519;
+
+/// Maximal number of messages a publisher may send within a period, declared by the author in keyloads.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RatePolicy {
+    /// Maximal number of messages per period, 0 means no limit.
+    pub max_messages: u32,
+    /// Length of the period in milliseconds.
+    pub period_ms: u64,
+}
+
+impl RatePolicy {
+    pub fn new(max_messages: u32, period_ms: u64) -> Self {
+        Self {
+            max_messages,
+            period_ms,
+        }
+    }
+
+    pub fn is_limited(&self) -> bool {
+        self.max_messages != 0 && self.period_ms != 0
+    }
+}
+
+/// Treatment of messages exceeding the rate policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateAction {
+    /// Accept the message, but remember its publisher as flagged.
+    Flag,
+    /// Reject the message.
+    Drop,
+}
+
+impl Default for RateAction {
+    fn default() -> Self {
+        RateAction::Flag
+    }
+}
+
+/// Sliding window counters of messages per publisher in each branch, limited by the rate policy
+/// declared in the keyload of the branch.
+pub struct RateLimiter<Rel> {
+    /// Rate policy declared in own keyloads.
+    pub policy: RatePolicy,
+    pub action: RateAction,
+    /// Limited branches: keyload link and declared rate policy.
+    branches: Vec<(Rel, RatePolicy)>,
+    /// Packets in limited branches: packet link and keyload link.
+    packets: Vec<(Rel, Rel)>,
+    /// Timestamps of recent messages of each publisher by keyload link.
+    windows: Vec<(Rel, ed25519::PublicKey, Vec<u64>)>,
+    /// Publishers which exceeded the policy.
+    flagged: Vec<ed25519::PublicKey>,
+}
+
+impl<Rel> Default for RateLimiter<Rel> {
+    fn default() -> Self {
+        Self {
+            policy: RatePolicy::default(),
+            action: RateAction::default(),
+            branches: Vec::new(),
+            packets: Vec::new(),
+            windows: Vec::new(),
+            flagged: Vec::new(),
+        }
+    }
+}
+
+impl<Rel: Clone + PartialEq> RateLimiter<Rel> {
+    /// Limit messages in the branch of keyload `keyload` to `policy` declared in it.
+    pub fn register(&mut self, keyload: &Rel, policy: RatePolicy) {
+        self.branches.retain(|(k, _)| k != keyload);
+        if policy.is_limited() {
+            self.branches.push((keyload.clone(), policy));
+        }
+    }
+
+    /// Rate policy declared in keyload `keyload`, `None` if its branch is not limited.
+    pub fn branch_policy(&self, keyload: &Rel) -> Option<&RatePolicy> {
+        self.branches
+            .iter()
+            .find(|(k, _)| k == keyload)
+            .map(|(_, policy)| policy)
+    }
+
+    /// Track the packet `link` linked to `link_to` if it's in a limited branch, so that it and
+    /// packets linked to it are accounted in the branch.
+    pub fn track(&mut self, link: &Rel, link_to: &Rel) {
+        if self.packets.iter().any(|(packet, _)| packet == link) {
+            return;
+        }
+        let keyload = self
+            .packets
+            .iter()
+            .find(|(packet, _)| packet == link_to)
+            .map_or(link_to, |(_, keyload)| keyload)
+            .clone();
+        if self.branch_policy(&keyload).is_some() {
+            self.packets.push((link.clone(), keyload));
+        }
+    }
+
+    /// Account the tracked packet `link` of `pk` sent at `timestamp` (ms) in its branch. Returns
+    /// `false` if the message exceeds the policy of the branch and the action is `Flag`, fails if
+    /// the action is `Drop`.
+    pub fn record(&mut self, pk: &ed25519::PublicKey, link: &Rel, timestamp: u64) -> Result<bool> {
+        let keyload = match self.packets.iter().find(|(packet, _)| packet == link) {
+            Some((_, keyload)) => keyload.clone(),
+            None => return Ok(true),
+        };
+        let policy = match self.branch_policy(&keyload) {
+            Some(policy) => *policy,
+            None => return Ok(true),
+        };
+
+        let i = match self.windows.iter().position(|(k, p, _)| *k == keyload && p == pk) {
+            Some(i) => i,
+            None => {
+                self.windows.push((keyload, pk.clone(), Vec::new()));
+                self.windows.len() - 1
+            }
+        };
+        let window = &mut self.windows[i].2;
+        window.retain(|t| t.saturating_add(policy.period_ms) > timestamp);
+        window.push(timestamp);
+        if window.len() <= policy.max_messages as usize {
+            return Ok(true);
+        }
+
+        if !self.flagged.contains(pk) {
+            self.flagged.push(pk.clone());
+        }
+        match self.action {
+            RateAction::Flag => Ok(false),
+            RateAction::Drop => bail!(
+                "Publisher exceeded rate of {} messages per {} ms.",
+                policy.max_messages,
+                policy.period_ms
+            ),
+        }
+    }
+
+    /// Forget packets `keep` returns `false` for and branches without kept keyload or packets.
+    pub fn retain<K: Fn(&Rel) -> bool>(&mut self, keep: K) {
+        self.packets.retain(|(packet, _)| keep(packet));
+        let packets = &self.packets;
+        self.branches
+            .retain(|(keyload, _)| keep(keyload) || packets.iter().any(|(_, k)| k == keyload));
+        let branches = &self.branches;
+        self.windows
+            .retain(|(keyload, _, _)| branches.iter().any(|(k, _)| k == keyload));
+    }
+}
+
+impl<Rel> RateLimiter<Rel> {
+    /// Publishers which exceeded the policy.
+    pub fn flagged(&self) -> &[ed25519::PublicKey] {
+        &self.flagged
+    }
+
+    /// Size of branches, message history and flags in bytes.
+    pub fn footprint(&self) -> usize {
+        let timestamps: usize = self.windows.iter().map(|(_, _, window)| window.len()).sum();
+        self.branches.len() * core::mem::size_of::<(Rel, RatePolicy)>()
+            + self.packets.len() * core::mem::size_of::<(Rel, Rel)>()
+            + self.windows.len() * core::mem::size_of::<(Rel, ed25519::PublicKey, Vec<u64>)>()
+            + timestamps * core::mem::size_of::<u64>()
+            + self.flagged.len() * core::mem::size_of::<ed25519::PublicKey>()
+    }
+
+    /// Forget message history and flags.
+    pub fn reset(&mut self) {
+        self.windows.clear();
+        self.flagged.clear();
+    }
+}
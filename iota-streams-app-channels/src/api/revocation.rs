@@ -0,0 +1,165 @@
+//! Revocation of subscribers with a grace window.
+//!
+//! A revoked subscriber keeps access during a grace window: keyloads shared with it remain in
+//! use until the author sent a number of packets or until some time passed since the revocation,
+//! then the subscriber is removed and the next keyload for everyone excludes it (see auto rekey).
+//! The author announces the revocation in a signed packet so the subscriber learns its access
+//! window, eg. to decommission a device in stages:
+//!
+//! ```text
+//! revocation = pk: [u8; 32] || kind: u8 || grace: u64
+//! ```
+//!
+//! `kind` is 0 for a number of packets and 1 for a duration in milliseconds, `grace` is big-endian.
+//! Access windows are persisted with the state of their user, encoded as follows:
+//!
+//! ```text
+//! access window = revoked at: u64 || kind: u8 || grace: u64
+//! ```
+
+use anyhow::{
+    anyhow,
+    ensure,
+    Result,
+};
+use iota_streams_core::prelude::Vec;
+use iota_streams_core_edsig::signature::ed25519;
+
+/// Kind byte of grace windows counted in packets.
+const GRACE_MESSAGES: u8 = 0;
+
+/// Kind byte of grace windows counted in milliseconds.
+const GRACE_TIME: u8 = 1;
+
+/// Size of an encoded grace window.
+const GRACE_SIZE: usize = 1 + 8;
+
+/// Size of an encoded revocation.
+const REVOCATION_SIZE: usize = ed25519::PUBLIC_KEY_LENGTH + GRACE_SIZE;
+
+/// Size of an encoded access window.
+pub(crate) const ACCESS_WINDOW_SIZE: usize = 8 + GRACE_SIZE;
+
+/// Grace window of a revoked subscriber.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraceWindow {
+    /// Access ends once the author sent this many packets after the revocation.
+    Messages(u32),
+    /// Access ends this many milliseconds after the revocation.
+    Time(u64),
+}
+
+impl GraceWindow {
+    fn put(&self, bytes: &mut Vec<u8>) {
+        let (kind, grace) = match *self {
+            GraceWindow::Messages(messages) => (GRACE_MESSAGES, messages as u64),
+            GraceWindow::Time(millis) => (GRACE_TIME, millis),
+        };
+        bytes.push(kind);
+        bytes.extend_from_slice(&grace.to_be_bytes());
+    }
+
+    fn take(bytes: &[u8]) -> Result<Self> {
+        let mut grace = [0_u8; 8];
+        grace.copy_from_slice(&bytes[1..GRACE_SIZE]);
+        let grace = u64::from_be_bytes(grace);
+        match bytes[0] {
+            GRACE_MESSAGES => {
+                ensure!(grace <= u32::MAX as u64, "Bad revocation grace: {}", grace);
+                Ok(GraceWindow::Messages(grace as u32))
+            }
+            GRACE_TIME => Ok(GraceWindow::Time(grace)),
+            kind => Err(anyhow!("Unknown revocation grace kind: {}", kind)),
+        }
+    }
+}
+
+/// Effective access window of a revoked subscriber.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccessWindow {
+    /// Timestamp of the revocation message in milliseconds.
+    pub revoked_at: u64,
+    /// Remaining grace window.
+    pub grace: GraceWindow,
+}
+
+impl AccessWindow {
+    /// Timestamp access ends at for time windows, `None` for windows counted in packets.
+    pub fn ends_at(&self) -> Option<u64> {
+        match self.grace {
+            GraceWindow::Messages(_) => None,
+            GraceWindow::Time(millis) => Some(self.revoked_at.saturating_add(millis)),
+        }
+    }
+
+    /// Whether access is still granted after `sent` packets since the revocation at timestamp `now`.
+    pub fn is_open(&self, sent: u32, now: u64) -> bool {
+        match self.grace {
+            GraceWindow::Messages(messages) => sent < messages,
+            GraceWindow::Time(millis) => now < self.revoked_at.saturating_add(millis),
+        }
+    }
+
+    /// Window remaining after `sent` packets since the revocation.
+    pub fn after(&self, sent: u32) -> Self {
+        let grace = match self.grace {
+            GraceWindow::Messages(messages) => GraceWindow::Messages(messages.saturating_sub(sent)),
+            time => time,
+        };
+        Self {
+            revoked_at: self.revoked_at,
+            grace,
+        }
+    }
+
+    /// Encode the access window, see the module docs.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(ACCESS_WINDOW_SIZE);
+        bytes.extend_from_slice(&self.revoked_at.to_be_bytes());
+        self.grace.put(&mut bytes);
+        bytes
+    }
+
+    /// Decode the access window encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(
+            bytes.len() == ACCESS_WINDOW_SIZE,
+            "Bad access window length: {}",
+            bytes.len()
+        );
+        let mut revoked_at = [0_u8; 8];
+        revoked_at.copy_from_slice(&bytes[..8]);
+        Ok(Self {
+            revoked_at: u64::from_be_bytes(revoked_at),
+            grace: GraceWindow::take(&bytes[8..])?,
+        })
+    }
+}
+
+/// Revocation of a subscriber announced by the author.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Revocation {
+    /// Public key of the revoked subscriber.
+    pub pk: ed25519::PublicKey,
+    /// Grace window of the subscriber.
+    pub grace: GraceWindow,
+}
+
+impl Revocation {
+    /// Encode the revocation, see the module docs.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(REVOCATION_SIZE);
+        bytes.extend_from_slice(self.pk.as_bytes());
+        self.grace.put(&mut bytes);
+        bytes
+    }
+
+    /// Decode the revocation encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(bytes.len() == REVOCATION_SIZE, "Bad revocation length: {}", bytes.len());
+        let (pk, rest) = bytes.split_at(ed25519::PUBLIC_KEY_LENGTH);
+        let pk = ed25519::PublicKey::from_bytes(pk).map_err(|e| anyhow!("Bad revoked public key: {}", e))?;
+        let grace = GraceWindow::take(rest)?;
+        Ok(Self { pk, grace })
+    }
+}
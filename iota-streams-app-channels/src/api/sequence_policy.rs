@@ -0,0 +1,117 @@
+use iota_streams_core::prelude::Vec;
+use iota_streams_core_edsig::signature::ed25519;
+
+/// Number of recent sequence numbers remembered per publisher to detect replays.
+pub const REPLAY_WINDOW: u64 = 64;
+
+/// Handling of out-of-order, missing and replayed messages of a publisher.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SequencePolicy {
+    /// Accept all messages, violations are only reported.
+    Permissive,
+    /// Reject replays and messages more than `window` sequence numbers behind or ahead
+    /// of the latest message of the publisher.
+    Windowed(u64),
+    /// Reject any violation.
+    StrictlyInOrder,
+}
+
+impl Default for SequencePolicy {
+    fn default() -> Self {
+        SequencePolicy::Permissive
+    }
+}
+
+/// Deviation of a message from the sequence of its publisher.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SequenceViolation {
+    /// Message with the sequence number was already received.
+    Replay { seq_num: u64 },
+    /// Message is older than the latest received message.
+    OutOfOrder { latest: u64, seq_num: u64 },
+    /// Messages between the latest received message and this one are missing.
+    Gap { latest: u64, seq_num: u64 },
+}
+
+/// Tracker of sequence numbers of received messages per publisher.
+#[derive(Default)]
+pub struct SequenceGuard {
+    pub policy: SequencePolicy,
+    /// Latest sequence number and recently received sequence numbers of each publisher.
+    publishers: Vec<(ed25519::PublicKey, u64, Vec<u64>)>,
+}
+
+impl SequenceGuard {
+    /// Account message `seq_num` of `pk`. Returns the violation, if any, and whether
+    /// the message is accepted by the policy. Gaps are only reported if `detect_gaps`
+    /// is set, ie. when publishers don't share sequence numbers.
+    pub fn check(
+        &mut self,
+        pk: &ed25519::PublicKey,
+        seq_num: u64,
+        detect_gaps: bool,
+    ) -> (Option<SequenceViolation>, bool) {
+        let i = match self.publishers.iter().position(|(p, _, _)| p == pk) {
+            Some(i) => i,
+            None => {
+                self.publishers.push((pk.clone(), seq_num, vec![seq_num]));
+                return (None, true);
+            }
+        };
+
+        let tracked = match self.policy {
+            SequencePolicy::Windowed(window) => core::cmp::max(window, 1),
+            _ => REPLAY_WINDOW,
+        };
+        let (_, latest, seen) = &mut self.publishers[i];
+        let violation = if seen.contains(&seq_num) {
+            Some(SequenceViolation::Replay { seq_num })
+        } else if seq_num < *latest {
+            Some(SequenceViolation::OutOfOrder {
+                latest: *latest,
+                seq_num,
+            })
+        } else if detect_gaps && seq_num > *latest + 1 {
+            Some(SequenceViolation::Gap {
+                latest: *latest,
+                seq_num,
+            })
+        } else {
+            None
+        };
+
+        let accepted = match (self.policy, &violation) {
+            (SequencePolicy::Permissive, _) | (_, None) => true,
+            (SequencePolicy::StrictlyInOrder, Some(_)) => false,
+            (SequencePolicy::Windowed(_), Some(SequenceViolation::Replay { .. })) => false,
+            (SequencePolicy::Windowed(window), Some(SequenceViolation::OutOfOrder { latest, seq_num })) => {
+                latest - seq_num <= window
+            }
+            (SequencePolicy::Windowed(window), Some(SequenceViolation::Gap { latest, seq_num })) => {
+                seq_num - latest <= window
+            }
+        };
+
+        if accepted {
+            if seq_num > *latest {
+                *latest = seq_num;
+            }
+            seen.push(seq_num);
+            let latest = *latest;
+            seen.retain(|s| s + tracked > latest);
+        }
+        (violation, accepted)
+    }
+
+    /// Size of tracked sequence numbers in bytes.
+    pub fn footprint(&self) -> usize {
+        let seen: usize = self.publishers.iter().map(|(_, _, seen)| seen.len()).sum();
+        self.publishers.len() * core::mem::size_of::<(ed25519::PublicKey, u64, Vec<u64>)>()
+            + seen * core::mem::size_of::<u64>()
+    }
+
+    /// Forget received sequence numbers.
+    pub fn reset(&mut self) {
+        self.publishers.clear();
+    }
+}
@@ -0,0 +1,82 @@
+use iota_streams_core::{
+    prelude::Vec,
+    sponge::spongos::Spongos,
+};
+
+/// Default number of keyloads kept in a session cache.
+pub const DEFAULT_SESSION_CACHE_CAPACITY: usize = 16;
+
+/// Spongos states the branches of keyloads the user is a recipient of are joined to, by keyload
+/// link. Keyloads the user isn't a recipient of are not cached, pre-shared keys stored later may
+/// unwrap them.
+///
+/// A cached keyload is looked up by its link only, its bytes aren't read again: the cache only
+/// spares key agreement when the same keyload is handled again, eg. when fetched twice.
+pub struct SessionCache<F, Rel> {
+    capacity: usize,
+    /// Entries from least to most recently used.
+    entries: Vec<(Rel, Spongos<F>)>,
+}
+
+impl<F, Rel> SessionCache<F, Rel> {
+    /// Cache keeping at most `capacity` keyloads, `0` disables caching.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Size of cached entries in bytes.
+    pub fn footprint(&self) -> usize {
+        self.entries.len() * core::mem::size_of::<(Rel, Spongos<F>)>()
+    }
+
+    fn evict(&mut self) {
+        if self.entries.len() > self.capacity {
+            let excess = self.entries.len() - self.capacity;
+            self.entries.drain(..excess);
+        }
+    }
+}
+
+impl<F: Clone, Rel: Eq> SessionCache<F, Rel> {
+    /// Cached spongos state of keyload `rel`, `None` if not cached.
+    pub fn get(&mut self, rel: &Rel) -> Option<Spongos<F>> {
+        let i = self.entries.iter().position(|(r, _)| r == rel)?;
+        let entry = self.entries.remove(i);
+        let spongos = entry.1.clone();
+        self.entries.push(entry);
+        Some(spongos)
+    }
+
+    /// Cache spongos state of keyload `rel`, evicting the least recently used keyloads if full.
+    pub fn insert(&mut self, rel: Rel, spongos: Spongos<F>) {
+        self.entries.retain(|(r, _)| *r != rel);
+        self.entries.push((rel, spongos));
+        self.evict();
+    }
+}
+
+impl<F, Rel> Default for SessionCache<F, Rel> {
+    fn default() -> Self {
+        Self::new(DEFAULT_SESSION_CACHE_CAPACITY)
+    }
+}
@@ -0,0 +1,143 @@
+//! Versioned header of exported user states.
+//!
+//! An exported state starts with a plaintext header followed by the state encrypted with the
+//! export password:
+//!
+//! ```text
+//! header = STATE_MAGIC || version: u8 || flag: u8
+//! ```
+//!
+//! States of versions 0 to 2 predate the header and start with the version and flag bytes only.
+//! Each version appends groups of fields to the state of the previous one, see `StateFields`;
+//! states of version 2 gained groups without a version bump and are imported with the layout
+//! whose MAC verifies. `User::migrate_state` upgrades states of any supported version to
+//! `STATE_VERSION`, states of unknown versions (eg. exported by a newer release) are rejected
+//! with `StateError`.
+
+use core::fmt;
+
+/// Magic bytes of exported states.
+pub const STATE_MAGIC: [u8; 4] = *b"IOSS";
+
+/// Version of states exported by this crate.
+pub const STATE_VERSION: u8 = 4;
+
+/// Latest version of states exported without `STATE_MAGIC`.
+pub const LEGACY_STATE_VERSION: u8 = 2;
+
+/// Oldest version of states imported by this crate.
+pub const MIN_STATE_VERSION: u8 = 0;
+
+/// Error of states that can't be imported or migrated by this crate; it's returned wrapped in
+/// `anyhow::Error` and can be recovered with `downcast_ref`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StateError {
+    /// State is shorter than its header.
+    Truncated,
+    /// State of a version this crate doesn't support.
+    UnknownVersion { version: u8 },
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::Truncated => write!(f, "User state is truncated."),
+            StateError::UnknownVersion { version } => write!(
+                f,
+                "Unknown user state version {}, supported versions are {} to {}.",
+                version, MIN_STATE_VERSION, STATE_VERSION
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StateError {}
+
+/// Plaintext header of an exported state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StateHeader {
+    pub version: u8,
+    pub flag: u8,
+}
+
+impl StateHeader {
+    /// Parse the header of `bytes`, either versioned or legacy.
+    pub fn parse(bytes: &[u8]) -> Result<Self, StateError> {
+        if bytes.starts_with(&STATE_MAGIC) {
+            let n = STATE_MAGIC.len();
+            if bytes.len() < n + 2 {
+                return Err(StateError::Truncated);
+            }
+            let version = bytes[n];
+            if version <= LEGACY_STATE_VERSION || STATE_VERSION < version {
+                return Err(StateError::UnknownVersion { version });
+            }
+            return Ok(Self {
+                version,
+                flag: bytes[n + 1],
+            });
+        }
+
+        if bytes.len() < 2 {
+            return Err(StateError::Truncated);
+        }
+        match bytes[0] {
+            version if version <= LEGACY_STATE_VERSION => Ok(Self {
+                version,
+                flag: bytes[1],
+            }),
+            version => Err(StateError::UnknownVersion { version }),
+        }
+    }
+
+    /// Whether the state has `STATE_MAGIC`.
+    pub fn has_magic(&self) -> bool {
+        self.version > LEGACY_STATE_VERSION
+    }
+}
+
+/// Groups of fields of the serialized state in the order they were added, a state holds the
+/// groups up to the latest one of its version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum StateFields {
+    /// Keys, link store, pre-shared keys and public keys of version 0.
+    Base,
+    /// Branch delegations added in version 1.
+    Delegations,
+    /// Read-only recipients added in version 2.
+    ReadOnly,
+    /// Linear branches added to version 2.
+    LinearBranches,
+    /// MSS key state added to version 2.
+    Mss,
+    /// ML-KEM public keys added to version 2.
+    Kem,
+    /// Msgid hash added to version 2, all groups of version 3.
+    MsgidHash,
+    /// Membership epoch, signed packets in branches with read-only recipients and opaque state
+    /// of the user wrapper added in version 4, eg. the channel state of the tangle `User`.
+    Ext,
+}
+
+impl StateFields {
+    /// Groups of states exported by this crate.
+    pub(crate) const LATEST: StateFields = StateFields::Ext;
+
+    /// Candidate latest groups of states of `version`, from the oldest layout.
+    pub(crate) fn of_version(version: u8) -> &'static [StateFields] {
+        match version {
+            0 => &[StateFields::Base],
+            1 => &[StateFields::Delegations],
+            2 => &[
+                StateFields::ReadOnly,
+                StateFields::LinearBranches,
+                StateFields::Mss,
+                StateFields::Kem,
+                StateFields::MsgidHash,
+            ],
+            3 => &[StateFields::MsgidHash],
+            _ => &[StateFields::LATEST],
+        }
+    }
+}
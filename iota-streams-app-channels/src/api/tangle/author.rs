@@ -1,7 +1,12 @@
 //! Customize Author with default implementation for use over the Tangle.
 
-use anyhow::Result;
+use anyhow::{
+    ensure,
+    Result,
+};
 use core::fmt;
+#[cfg(feature = "stream")]
+use futures_core::Stream;
 
 use super::*;
 use crate::api::tangle::{
@@ -19,27 +24,255 @@ pub struct Author<Trans> {
 
 impl<Trans: Transport> Author<Trans>
 {
+    /// Start building an Author instance from named options, see `AuthorBuilder`.
+    pub fn builder() -> AuthorBuilder<Trans> {
+        AuthorBuilder::default()
+    }
+
     /// Create a new Author instance, generate new MSS keypair and optionally NTRU keypair.
     ///
     /// # Arguments
-    /// * `seed` - A string slice representing the seed of the user [Characters: A-Z, 9]
+    /// * `seed` - Seed of the user, see `Seed`
+    /// * `encoding` - A string slice representing the encoding type for the message [supported: utf-8]
+    /// * `payload_length` - Maximum size in bytes of payload per message chunk [1-1024],
+    /// * `multi_branching` - Boolean representing use of multi-branch or single-branch sequencing
+    /// * `transport` - Transport object used for sending and receiving
+    ///
+    pub fn new(seed: &Seed, encoding: &str, payload_length: usize, multi_branching: bool, transport: Trans) -> Self {
+        let mut user = User::new(seed, encoding, payload_length, multi_branching, transport);
+        let channel_idx = 0_u64;
+        let _ = user.user.create_channel(channel_idx);
+        Self { user }
+    }
+
+    /// Create a new Author instance of a channel with message identifiers `msgid_size` bytes wide.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed of the user, see `Seed`
+    /// * `encoding` - A string slice representing the encoding type for the message [supported: utf-8]
+    /// * `payload_length` - Maximum size in bytes of payload per message chunk [1-1024],
+    /// * `multi_branching` - Boolean representing use of multi-branch or single-branch sequencing
+    /// * `msgid_size` - Width of message identifiers in bytes [supported: 12, 24, 32]
+    /// * `transport` - Transport object used for sending and receiving
+    ///
+    pub fn new_with_msgid_size(
+        seed: &Seed,
+        encoding: &str,
+        payload_length: usize,
+        multi_branching: bool,
+        msgid_size: usize,
+        transport: Trans,
+    ) -> Result<Self> {
+        let mut user = User::new(seed, encoding, payload_length, multi_branching, transport);
+        user.user = user.user.with_link_gen(LinkGen::default().with_msgid_size(msgid_size)?);
+        let channel_idx = 0_u64;
+        user.user.create_channel(channel_idx)?;
+        Ok(Self { user })
+    }
+
+    /// Create a new Author instance of a channel deriving message identifiers with `msgid_hash`,
+    /// eg. for indexers computing identifiers of expected messages with BLAKE2b or SHA-256.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed of the user, see `Seed`
+    /// * `encoding` - A string slice representing the encoding type for the message [supported: utf-8]
+    /// * `payload_length` - Maximum size in bytes of payload per message chunk [1-1024],
+    /// * `multi_branching` - Boolean representing use of multi-branch or single-branch sequencing
+    /// * `msgid_hash` - Hash function of message identifiers
+    /// * `transport` - Transport object used for sending and receiving
+    ///
+    pub fn new_with_msgid_hash(
+        seed: &Seed,
+        encoding: &str,
+        payload_length: usize,
+        multi_branching: bool,
+        msgid_hash: MsgIdHash,
+        transport: Trans,
+    ) -> Self {
+        let mut user = User::new(seed, encoding, payload_length, multi_branching, transport);
+        user.user = user.user.with_link_gen(LinkGen::default().with_msgid_hash(msgid_hash));
+        let channel_idx = 0_u64;
+        let _ = user.user.create_channel(channel_idx);
+        Self { user }
+    }
+
+    /// Create a new Author instance of a channel encapsulating session keys in keyloads with
+    /// both X25519 and ML-KEM, a post-quantum key encapsulation mechanism, so recorded keyloads
+    /// stay confidential even if X25519 gets broken. Keyloads grow by about 1 KB per subscriber.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed of the user, see `Seed`
+    /// * `encoding` - A string slice representing the encoding type for the message [supported: utf-8]
+    /// * `payload_length` - Maximum size in bytes of payload per message chunk [1-1024],
+    /// * `multi_branching` - Boolean representing use of multi-branch or single-branch sequencing
+    /// * `transport` - Transport object used for sending and receiving
+    ///
+    #[cfg(feature = "pq-kem")]
+    pub fn new_with_hybrid_kem(
+        seed: &Seed,
+        encoding: &str,
+        payload_length: usize,
+        multi_branching: bool,
+        transport: Trans,
+    ) -> Self {
+        let mut user = User::new(seed, encoding, payload_length, multi_branching, transport);
+        user.user = user.user.with_hybrid_kem(true);
+        let channel_idx = 0_u64;
+        let _ = user.user.create_channel(channel_idx);
+        Self { user }
+    }
+
+    /// Create a new Author instance of a channel masking payloads with synthetic IVs derived
+    /// from the session key and the payload, so payloads stay confidential even if the device
+    /// RNG is weak and spongos states get reused. Packets grow by 32 bytes.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed of the user, see `Seed`
+    /// * `encoding` - A string slice representing the encoding type for the message [supported: utf-8]
+    /// * `payload_length` - Maximum size in bytes of payload per message chunk [1-1024],
+    /// * `multi_branching` - Boolean representing use of multi-branch or single-branch sequencing
+    /// * `transport` - Transport object used for sending and receiving
+    ///
+    pub fn new_with_siv_masking(
+        seed: &Seed,
+        encoding: &str,
+        payload_length: usize,
+        multi_branching: bool,
+        transport: Trans,
+    ) -> Self {
+        let mut user = User::new(seed, encoding, payload_length, multi_branching, transport);
+        user.user = user.user.with_siv_masking(true);
+        let channel_idx = 0_u64;
+        let _ = user.user.create_channel(channel_idx);
+        Self { user }
+    }
+
+    /// Create a new Author instance of a hybrid channel: the announcement and signed packets are
+    /// signed with both Ed25519 and MSS, a hash-based post-quantum signature scheme, for
+    /// channels whose messages must stay authentic for decades.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed of the user, see `Seed`
     /// * `encoding` - A string slice representing the encoding type for the message [supported: utf-8]
     /// * `payload_length` - Maximum size in bytes of payload per message chunk [1-1024],
     /// * `multi_branching` - Boolean representing use of multi-branch or single-branch sequencing
+    /// * `mss_height` - Height of the MSS Merkle tree, the author can sign 2^mss_height messages [1-16]
     /// * `transport` - Transport object used for sending and receiving
     ///
-    pub fn new(seed: &str, encoding: &str, payload_length: usize, multi_branching: bool, transport: Trans) -> Self {
+    /// MSS is stateful: never sign with an older copy of the author state, see `import_hybrid`.
+    ///
+    #[cfg(feature = "pq-sig")]
+    pub fn new_hybrid(
+        seed: &Seed,
+        encoding: &str,
+        payload_length: usize,
+        multi_branching: bool,
+        mss_height: usize,
+        transport: Trans,
+    ) -> Result<Self> {
         let mut user = User::new(seed, encoding, payload_length, multi_branching, transport);
         let channel_idx = 0_u64;
+        user.user.create_hybrid_channel(channel_idx, mss_height)?;
+        Ok(Self { user })
+    }
+
+    /// Create a new Author instance coordinating a single-branch channel authored by a threshold
+    /// group: the announcement and signed packets of the author are signed by the group, see
+    /// `frost`. The instance's own key can't publish as the channel author.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed of the user, see `Seed`
+    /// * `encoding` - A string slice representing the encoding type for the message [supported: utf-8]
+    /// * `payload_length` - Maximum size in bytes of payload per message chunk [1-1024],
+    /// * `group_pk` - Public Key of the threshold group
+    /// * `transport` - Transport object used for sending and receiving
+    ///
+    pub fn new_threshold(
+        seed: &Seed,
+        encoding: &str,
+        payload_length: usize,
+        group_pk: &ed25519::PublicKey,
+        transport: Trans,
+    ) -> Result<Self> {
+        let mut user = User::new(seed, encoding, payload_length, false, transport);
+        let channel_idx = 0_u64;
+        user.user.create_threshold_channel(group_pk, channel_idx)?;
+        Ok(Self { user })
+    }
+
+    /// Create a new Author instance of a channel with counter sequencing: message identifiers
+    /// are derived from a counter of each publisher, so packets are published without
+    /// accompanying sequence messages.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed of the user, see `Seed`
+    /// * `encoding` - A string slice representing the encoding type for the message [supported: utf-8]
+    /// * `payload_length` - Maximum size in bytes of payload per message chunk [1-1024],
+    /// * `transport` - Transport object used for sending and receiving
+    ///
+    pub fn new_with_counter_sequencing(seed: &Seed, encoding: &str, payload_length: usize, transport: Trans) -> Self {
+        let mut user = User::new(seed, encoding, payload_length, false, transport);
+        user.user = user.user.with_counter_sequencing(true);
+        let channel_idx = 0_u64;
         let _ = user.user.create_channel(channel_idx);
         Self { user }
     }
 
+    /// Wrap a User that created a channel.
+    pub fn from_user(user: User<Trans>) -> Result<Self> {
+        ensure!(user.is_author(), "User is not the author of a channel.");
+        Ok(Self { user })
+    }
+
+    /// Unwrap the underlying role-agnostic User.
+    pub fn into_user(self) -> User<Trans> {
+        self.user
+    }
+
     /// Return boolean representing the sequencing nature of the channel
     pub fn is_multi_branching(&self) -> bool {
         self.user.is_multi_branching()
     }
 
+    /// Return boolean representing use of per-publisher message counters instead of sequence messages
+    pub fn is_counter_sequencing(&self) -> bool {
+        self.user.is_counter_sequencing()
+    }
+
+    /// Return boolean representing use of post-quantum signatures by the channel author
+    pub fn is_hybrid(&self) -> bool {
+        self.user.is_hybrid()
+    }
+
+    /// Return boolean representing use of post-quantum key encapsulation in keyloads of the channel
+    pub fn is_hybrid_kem(&self) -> bool {
+        self.user.is_hybrid_kem()
+    }
+
+    /// Return boolean representing use of synthetic IVs for masked payloads of the channel
+    pub fn is_siv_masking(&self) -> bool {
+        self.user.is_siv_masking()
+    }
+
+    /// Number of messages the author can still sign in a hybrid channel
+    #[cfg(feature = "pq-sig")]
+    pub fn remaining_pq_signatures(&self) -> Option<u32> {
+        self.user.remaining_pq_signatures()
+    }
+
+    /// Index of the next MSS leaf in a hybrid channel. Store it after each signed message,
+    /// outside of exported states, and pass it to `import_hybrid`.
+    #[cfg(feature = "pq-sig")]
+    pub fn next_pq_signature_index(&self) -> Option<u32> {
+        self.user.next_pq_signature_index()
+    }
+
+    /// Set callback populating the application-specific header extension of outgoing messages,
+    /// eg. with routing hints readable before the message content is unwrapped.
+    pub fn set_header_ext<E: Fn(&Header) -> Vec<u8> + 'static>(&mut self, header_ext: E) {
+        self.user.set_header_ext(header_ext)
+    }
+
     /// Fetch the Address (application instance) of the channel.
     pub fn channel_address(&self) -> Option<&ChannelAddress> {
         self.user.channel_address()
@@ -50,6 +283,11 @@ impl<Trans: Transport> Author<Trans>
         self.user.get_pk()
     }
 
+    /// Fetch the fingerprint of the user's secret key for support diagnostics
+    pub fn fingerprint(&self) -> Fingerprint {
+        self.user.fingerprint()
+    }
+
     /// Send an announcement message, generating a channel.
     pub fn send_announce(&mut self) -> Result<Address> {
         self.user.send_announce()
@@ -71,6 +309,124 @@ impl<Trans: Transport> Author<Trans>
         self.user.send_keyload(link_to, psk_ids, ke_pks)
     }
 
+    /// Create and send a new keyload for a list of subscribers, recipients in `read_only`
+    /// may not publish in its branch. The branch accepts signed packets only, tagged packets
+    /// can't be attributed to their sender.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    ///  * `read_only` - Vector of Public Keys of recipients allowed to read only
+    ///
+    pub fn send_keyload_with_read_only(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<ed25519::PublicKey>,
+        read_only: &Vec<ed25519::PublicKey>,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_keyload_with_read_only(link_to, psk_ids, ke_pks, read_only)
+    }
+
+    /// Create and send a new keyload for a list of subscribers without listing their public
+    /// keys, recipients find their key slot by trial decryption.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `ke_pks`  - Vector of Public Keys of the recipients
+    ///
+    pub fn send_anonymous_keyload(
+        &mut self,
+        link_to: &Address,
+        ke_pks: &Vec<ed25519::PublicKey>,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_anonymous_keyload(link_to, ke_pks)
+    }
+
+    /// Forget subscriber `pk`, subsequent keyloads for everyone are not shared with it.
+    /// Returns whether the subscriber was known.
+    ///
+    ///  # Arguments
+    ///  * `pk` - Public Key of the subscriber
+    ///
+    pub fn remove_subscriber(&mut self, pk: &ed25519::PublicKey) -> Result<bool> {
+        self.user.remove_subscriber(pk)
+    }
+
+    /// Revoke subscriber `pk` after a grace window and announce the revocation in a signed
+    /// packet, see `User::revoke_subscriber`.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the revocation will be attached to
+    ///  * `pk` - Public Key of the revoked subscriber
+    ///  * `grace` - Grace window of the subscriber
+    ///
+    pub fn revoke_subscriber(
+        &mut self,
+        link_to: &Address,
+        pk: &ed25519::PublicKey,
+        grace: GraceWindow,
+    ) -> Result<(Address, Option<Address>, AccessWindow)> {
+        self.user.revoke_subscriber(link_to, pk, grace)
+    }
+
+    /// Remaining access window of revoked subscriber `pk`, `None` if it isn't pending revocation.
+    pub fn revocation_window(&self, pk: &ed25519::PublicKey) -> Option<AccessWindow> {
+        self.user.revocation_window(pk)
+    }
+
+    /// Group subscribers in a key tree of `capacity` members, see `User::enable_key_tree`.
+    ///
+    ///  # Arguments
+    ///  * `capacity` - Largest number of members
+    ///
+    pub fn enable_key_tree(&mut self, capacity: usize) -> Result<()> {
+        self.user.enable_key_tree(capacity)
+    }
+
+    /// Id of the group key of the key tree, `None` unless enabled.
+    pub fn tree_pskid(&self) -> Option<PskId> {
+        self.user.tree_pskid()
+    }
+
+    /// Add subscriber `pk` to the key tree and send the update in a private packet.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the update will be attached to
+    ///  * `pk` - Public Key of the new member
+    ///
+    pub fn tree_add_member(
+        &mut self,
+        link_to: &Address,
+        pk: &ed25519::PublicKey,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.tree_add_member(link_to, pk)
+    }
+
+    /// Remove member `pk` from the key tree and send the update in a signed packet.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the update will be attached to
+    ///  * `pk` - Public Key of the removed member
+    ///
+    pub fn tree_remove_member(
+        &mut self,
+        link_to: &Address,
+        pk: &ed25519::PublicKey,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.tree_remove_member(link_to, pk)
+    }
+
+    /// Create and send a keyload for the current members of the key tree.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///
+    pub fn send_tree_keyload(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.send_tree_keyload(link_to)
+    }
+
     /// Create and send keyload for all subscribed subscribers.
     ///
     ///  # Arguments
@@ -80,6 +436,207 @@ impl<Trans: Transport> Author<Trans>
         self.user.send_keyload_for_everyone(link_to)
     }
 
+    /// Set rate policy declared to publishers in subsequent keyloads.
+    ///
+    ///  # Arguments
+    ///  * `policy` - Maximal number of messages per period of each publisher
+    ///
+    pub fn set_rate_policy(&mut self, policy: RatePolicy) {
+        self.user.set_rate_policy(policy)
+    }
+
+    /// Set branch policy declared in subsequent keyloads, linear branches of multi-branch
+    /// channels are sequenced without sequence messages.
+    ///
+    ///  # Arguments
+    ///  * `policy` - Sequencing of messages linked to the keyloads
+    ///
+    pub fn set_branch_policy(&mut self, policy: BranchPolicy) {
+        self.user.set_branch_policy(policy)
+    }
+
+    /// Select whether packets exceeding the rate policy are flagged or dropped.
+    pub fn set_rate_action(&mut self, action: RateAction) {
+        self.user.set_rate_action(action)
+    }
+
+    /// Publishers which exceeded the rate policy.
+    pub fn flagged_publishers(&self) -> &[ed25519::PublicKey] {
+        self.user.flagged_publishers()
+    }
+
+    /// Select handling of replayed, out-of-order and missing messages, see `SequencePolicy`.
+    pub fn set_sequence_policy(&mut self, policy: SequencePolicy) {
+        self.user.set_sequence_policy(policy)
+    }
+
+    /// Transparently send an updated keyload for everyone ahead of the next packet of a branch
+    /// once subscribers were added or removed, see `User::set_auto_rekey`.
+    pub fn set_auto_rekey(&mut self, auto_rekey: bool) {
+        self.user.set_auto_rekey(auto_rekey)
+    }
+
+    /// Return boolean representing transparent rekeying of branches on membership changes
+    pub fn is_auto_rekey(&self) -> bool {
+        self.user.is_auto_rekey()
+    }
+
+    /// Queue received subscriptions for an explicit decision instead of accepting them, see
+    /// `User::set_subscription_approval`.
+    pub fn set_subscription_approval(&mut self, approval: bool) {
+        self.user.set_subscription_approval(approval)
+    }
+
+    /// Return boolean representing explicit approval of subscriptions
+    pub fn is_subscription_approval(&self) -> bool {
+        self.user.is_subscription_approval()
+    }
+
+    /// Return packets from generic message handling with masked payloads decrypted on first access.
+    pub fn set_lazy_payloads(&mut self, lazy: bool) {
+        self.user.set_lazy_payloads(lazy)
+    }
+
+    /// Cap the number of keyloads whose unwrapped session state is cached, `0` disables caching.
+    pub fn set_session_cache_capacity(&mut self, capacity: usize) {
+        self.user.set_session_cache_capacity(capacity)
+    }
+
+    /// Forget cached keyload session states.
+    pub fn clear_session_cache(&mut self) {
+        self.user.clear_session_cache()
+    }
+
+    /// Number of keyloads with cached session state.
+    pub fn cached_sessions(&self) -> usize {
+        self.user.cached_sessions()
+    }
+
+    /// Drop state of messages processed before the checkpoint `link`, returns the number of
+    /// dropped messages.
+    pub fn prune_before(&mut self, link: &Address) -> Result<usize> {
+        self.user.prune_before(link)
+    }
+
+    /// Approximate memory used by link store, spongos states, cursors and other state.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        self.user.memory_footprint()
+    }
+
+    /// Set header predicate of packets to be unwrapped when fetching, rejected packets are
+    /// skipped, see `User::set_fetch_filter`.
+    pub fn set_fetch_filter<P: Fn(&Header) -> bool + 'static>(&mut self, filter: P) {
+        self.user.set_fetch_filter(filter)
+    }
+
+    /// Remove fetch filter, fetched packets are all unwrapped.
+    pub fn clear_fetch_filter(&mut self) {
+        self.user.clear_fetch_filter()
+    }
+
+    /// Store a pre-shared key identified by `pskid`, see `User::store_psk`.
+    pub fn store_psk(&mut self, pskid: PskId, psk: Psk) {
+        self.user.store_psk(pskid, psk)
+    }
+
+    /// Generate random pre-shared keys reserved for recipients joining later, to be listed
+    /// in keyloads with their ids and handed to the recipients out-of-band.
+    pub fn reserve_psk_slots(&mut self, count: usize) -> Result<Vec<(PskId, Psk)>> {
+        self.user.reserve_psk_slots(count)
+    }
+
+    /// Set delivery priority (0 to `hdf::MAX_PRIORITY`) of subsequently sent messages.
+    pub fn set_priority(&mut self, priority: u8) -> Result<()> {
+        self.user.set_priority(priority)
+    }
+
+    /// Register a hook notarizing every successfully sent message, see `Notarizer`.
+    pub fn set_notarizer<N: Notarizer + 'static>(&mut self, notarizer: N) {
+        self.user.set_notarizer(notarizer)
+    }
+
+    /// Remove the registered notarizer.
+    pub fn clear_notarizer(&mut self) {
+        self.user.clear_notarizer()
+    }
+
+    /// Register callbacks notified of processed messages, see `UserEvents`.
+    pub fn register_events<E: UserEvents + 'static>(&mut self, events: E) {
+        self.user.register_events(events)
+    }
+
+    /// Remove registered callbacks.
+    pub fn unregister_events(&mut self) {
+        self.user.unregister_events()
+    }
+
+    /// Create and send a branch delegation, the delegate can send keyloads attached to it.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the delegation will be attached to
+    ///  * `delegate_pk` - Public Key of the subscriber delegated the new branch
+    ///
+    pub fn send_branch_delegation(
+        &mut self,
+        link_to: &Address,
+        delegate_pk: &ed25519::PublicKey,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_branch_delegation(link_to, delegate_pk)
+    }
+
+    /// Create and send a checkpoint of the sequencing state of all known publishers.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the checkpoint will be attached to
+    ///
+    pub fn send_checkpoint(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.send_checkpoint(link_to)
+    }
+
+    /// List a branch in the directory of the channel under `name`, see `User::set_directory_entry`.
+    ///
+    ///  # Arguments
+    ///  * `name` - Name of the branch
+    ///  * `keyload` - Address of the latest keyload of the branch
+    ///  * `content_types` - Content types of the packets of the branch
+    ///
+    pub fn set_directory_entry(&mut self, name: &str, keyload: &Address, content_types: &[&str]) -> Result<()> {
+        self.user.set_directory_entry(name, keyload, content_types)
+    }
+
+    /// Remove the branch named `name` from the directory, returns whether it was listed.
+    pub fn remove_directory_entry(&mut self, name: &str) -> Result<bool> {
+        self.user.remove_directory_entry(name)
+    }
+
+    /// Directory of the channel.
+    pub fn directory(&self) -> &Directory {
+        self.user.directory()
+    }
+
+    /// Address of the latest published directory.
+    pub fn directory_link(&self) -> Option<&Address> {
+        self.user.directory_link()
+    }
+
+    /// Create and send the directory in a signed packet.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the directory will be attached to
+    ///
+    pub fn send_directory(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.send_directory(link_to)
+    }
+
+    /// Publish the directory again after every `packets` sent packets, 0 disables refreshes.
+    ///
+    ///  # Arguments
+    ///  * `packets` - Number of packets sent between directories
+    ///
+    pub fn set_directory_refresh(&mut self, packets: u32) -> Result<()> {
+        self.user.set_directory_refresh(packets)
+    }
+
     /// Create and send a signed packet.
     ///
     ///  # Arguments
@@ -96,6 +653,57 @@ impl<Trans: Transport> Author<Trans>
         self.user.send_signed_packet(link_to, public_payload, masked_payload)
     }
 
+    /// Hash of the announcement to be signed by the threshold group with
+    /// `threshold_signature_context(ANNOUNCE)`.
+    pub fn threshold_announcement_tag(&self) -> Result<[u8; 64]> {
+        self.user.threshold_announcement_tag()
+    }
+
+    /// Send the announcement signed by the threshold group.
+    ///
+    ///  # Arguments
+    ///  * `sig` - Group signature of the announcement tag
+    ///
+    pub fn send_threshold_announce(&mut self, sig: &ed25519::Signature) -> Result<Address> {
+        self.user.send_threshold_announce(sig)
+    }
+
+    /// Hash of the next signed packet to be signed by the threshold group with
+    /// `threshold_signature_context(SIGNED_PACKET)`.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///
+    pub fn threshold_signed_packet_tag(
+        &self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<[u8; 64]> {
+        self.user.threshold_signed_packet_tag(link_to, public_payload, masked_payload)
+    }
+
+    /// Send a signed packet signed by the threshold group.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `sig` - Group signature of the packet tag
+    ///
+    pub fn send_threshold_signed_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        sig: &ed25519::Signature,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user
+            .send_threshold_signed_packet(link_to, public_payload, masked_payload, sig)
+    }
+
     /// Create and send a tagged packet.
     ///
     ///  # Arguments
@@ -112,6 +720,164 @@ impl<Trans: Transport> Author<Trans>
         self.user.send_tagged_packet(link_to, public_payload, masked_payload)
     }
 
+    /// Create and send a deniable packet, authenticated with the branch session key MAC
+    /// instead of a signature, see `User::send_deniable_packet`.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of a keyload the user has the session key of or of another deniable packet
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///
+    pub fn send_deniable_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_deniable_packet(link_to, public_payload, masked_payload)
+    }
+
+    /// Create and send a private packet, with the private payload readable by the recipient only.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `recipient_pk` - ed25519 Public Key of the recipient of the private payload
+    ///  * `private_payload` - Wrapped vector of Bytes accessible by the recipient only
+    ///
+    pub fn send_private_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        recipient_pk: &ed25519::PublicKey,
+        private_payload: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_private_packet(link_to, public_payload, masked_payload, recipient_pk, private_payload)
+    }
+
+    /// Create and send a signed packet with the masked payload offloaded to a blob store.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access, stored in `blobs`
+    ///  * `blobs` - Blob store the masked payload is offloaded to
+    ///
+    pub fn send_signed_packet_offloaded<B: BlobStore>(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        blobs: &mut B,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_signed_packet_offloaded(link_to, public_payload, masked_payload, blobs)
+    }
+
+    /// Create and send a signed packet co-signed with a secp256k1 key, eg. one known to an
+    /// Ethereum contract.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `cosigner` - Secp256k1 key co-signing the packet digest
+    ///
+    #[cfg(feature = "secp256k1")]
+    pub fn send_signed_packet_cosigned(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        cosigner: &secp256k1::SigningKey,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_signed_packet_cosigned(link_to, public_payload, masked_payload, cosigner)
+    }
+
+    /// Create and send a tagged packet with the masked payload offloaded to a blob store.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access, stored in `blobs`
+    ///  * `blobs` - Blob store the masked payload is offloaded to
+    ///
+    pub fn send_tagged_packet_offloaded<B: BlobStore>(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        blobs: &mut B,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_tagged_packet_offloaded(link_to, public_payload, masked_payload, blobs)
+    }
+
+    /// Create and send a signed packet with the masked payload embargoed until a later release.
+    /// The payload key is kept in the pending embargoes until it's sent with `send_release` or
+    /// `send_due_releases`.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access once released
+    ///  * `release_at` - Milestone index the payload is due to be released at, `None` to release at will
+    ///
+    pub fn send_embargoed_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        release_at: Option<u32>,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_embargoed_packet(link_to, public_payload, masked_payload, release_at)
+    }
+
+    /// Pending embargoes of sent embargoed packets, part of the exported state.
+    pub fn embargoes(&self) -> &[Embargo] {
+        self.user.embargoes()
+    }
+
+    /// Restore a pending embargo, eg. one exported by an earlier release.
+    pub fn add_embargo(&mut self, embargo: Embargo) {
+        self.user.add_embargo(embargo)
+    }
+
+    /// Create and send the release of a pending embargo.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the release will be attached to
+    ///  * `embargoed_link` - Address of the embargoed packet
+    ///
+    pub fn send_release(&mut self, link_to: &Address, embargoed_link: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.send_release(link_to, embargoed_link)
+    }
+
+    /// Create and send the releases of pending embargoes due at milestone index `milestone`.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the releases will be attached to
+    ///  * `milestone` - Index of the latest milestone
+    ///
+    pub fn send_due_releases(&mut self, link_to: &Address, milestone: u32) -> Result<Vec<Address>> {
+        self.user.send_due_releases(link_to, milestone)
+    }
+
+    /// Create and send a signed packet committing to `fields` for selective disclosure, returns
+    /// the openings of the fields to keep for disclosing them later.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `fields` - Values of the fields, in order
+    ///
+    pub fn send_disclosable_packet(
+        &mut self,
+        link_to: &Address,
+        fields: &[Bytes],
+    ) -> Result<(Address, Option<Address>, Vec<FieldOpening>)> {
+        self.user.send_disclosable_packet(link_to, fields)
+    }
+
 
     /// Receive and process a subscribe message.
     ///
@@ -122,6 +888,35 @@ impl<Trans: Transport> Author<Trans>
         self.user.receive_subscribe(link)
     }
 
+    /// Subscriptions queued since the last call, see `set_subscription_approval`.
+    pub fn fetch_pending_subscriptions(&mut self) -> Vec<PendingSubscription> {
+        self.user.fetch_pending_subscriptions()
+    }
+
+    /// Subscriptions awaiting a decision.
+    pub fn pending_subscriptions(&self) -> Vec<&PendingSubscription> {
+        self.user.pending_subscriptions()
+    }
+
+    /// Accept a pending subscription, the subscriber is included in subsequent keyloads for
+    /// everyone. Returns the public key of the subscriber.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the subscribe message
+    ///
+    pub fn accept_subscription(&mut self, link: &Address) -> Result<ed25519::PublicKey> {
+        self.user.accept_subscription(link)
+    }
+
+    /// Reject a pending subscription, it is dropped. Returns the public key of the subscriber.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the subscribe message
+    ///
+    pub fn reject_subscription(&mut self, link: &Address) -> Result<ed25519::PublicKey> {
+        self.user.reject_subscription(link)
+    }
+
     /// Receive and process a signed packet message.
     ///
     ///  # Arguments
@@ -140,6 +935,66 @@ impl<Trans: Transport> Author<Trans>
         self.user.receive_tagged_packet(link)
     }
 
+    /// Receive and process a deniable packet, authenticated by the branch session key only.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_deniable_packet(&mut self, link: &Address) -> Result<(Bytes, Bytes)> {
+        self.user.receive_deniable_packet(link)
+    }
+
+    /// Receive and process a private packet message. The private payload is `None` if the packet
+    /// is addressed to another recipient.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_private_packet(
+        &mut self,
+        link: &Address,
+    ) -> Result<(ed25519::PublicKey, Bytes, Bytes, Option<Bytes>)> {
+        self.user.receive_private_packet(link)
+    }
+
+    /// Receive and process a signed packet message with the masked payload offloaded to a blob store.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///  * `blobs` - Blob store the masked payload is fetched from
+    ///
+    pub fn receive_signed_packet_offloaded<B: BlobStore>(
+        &mut self,
+        link: &Address,
+        blobs: &mut B,
+    ) -> Result<(ed25519::PublicKey, Bytes, Bytes)> {
+        self.user.receive_signed_packet_offloaded(link, blobs)
+    }
+
+    /// Receive and process a signed packet message together with its secp256k1 co-signature,
+    /// `None` if the packet is not co-signed.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    #[cfg(feature = "secp256k1")]
+    pub fn receive_signed_packet_cosigned(
+        &mut self,
+        link: &Address,
+    ) -> Result<(ed25519::PublicKey, Bytes, Bytes, Option<secp256k1::Cosignature>)> {
+        self.user.receive_signed_packet_cosigned(link)
+    }
+
+    /// Receive and process a tagged packet message with the masked payload offloaded to a blob store.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///  * `blobs` - Blob store the masked payload is fetched from
+    ///
+    pub fn receive_tagged_packet_offloaded<B: BlobStore>(&mut self, link: &Address, blobs: &mut B) -> Result<(Bytes, Bytes)> {
+        self.user.receive_tagged_packet_offloaded(link, blobs)
+    }
+
     /// Receive and process a sequence message.
     ///
     ///  # Arguments
@@ -181,11 +1036,76 @@ impl<Trans: Transport> Author<Trans>
         self.user.store_state_for_all(link, seq_num)
     }
 
+    /// Retrieves the sequencing state of each publishing participant: the link of the latest
+    /// processed message and its cursor position
+    pub fn fetch_state(&self) -> Result<Vec<(ed25519::PublicKey, Cursor<Address>)>> {
+        self.user.fetch_state()
+    }
+
+    /// Retrieves and processes messages until no new messages are found, bringing the
+    /// sequencing state fully up to date. Returns all retrieved messages
+    pub fn sync_state(&mut self) -> Vec<UnwrappedMessage> {
+        self.user.sync_state()
+    }
+
+    /// Like `sync_state`, with the next messages fetched with `fetcher` on a separate thread
+    /// while the current ones are unwrapped.
+    ///
+    ///   # Arguments
+    ///   * `fetcher` - Transport to fetch messages with, eg. a clone of the user's transport
+    ///
+    #[cfg(feature = "std")]
+    pub fn sync_state_pipelined<T>(&mut self, fetcher: T) -> Vec<UnwrappedMessage>
+    where
+        T: Transport + Send + 'static,
+    {
+        self.user.sync_state_pipelined(fetcher)
+    }
+
+    /// Message DAG of the channel for debugging of sequencing, see `MessageGraph::to_dot`
+    /// and `MessageGraph::to_json`.
+    pub fn export_graph(&mut self) -> Result<MessageGraph> {
+        self.user.export_graph()
+    }
+
+    /// Iterate over messages from `link` to the current head, fetching them on demand.
+    /// Only single-branch channels are supported.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the first message
+    ///
+    pub fn iter_from(&mut self, link: &Address) -> Result<History<'_, Trans>> {
+        self.user.iter_from(link)
+    }
+
+    /// Stream of messages fetched lazily until no new messages are found.
+    ///
+    /// ```ignore
+    /// let mut messages = author.messages();
+    /// while let Some(msg) = messages.next().await {
+    ///     let msg = msg?;
+    /// }
+    /// ```
+    #[cfg(feature = "stream")]
+    pub fn messages(&mut self) -> impl Stream<Item = Result<UnwrappedMessage>> + '_ {
+        self.user.messages()
+    }
+
     /// Retrieves the next message for each user (if present in transport layer) and returns them
     pub fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
         self.user.fetch_next_msgs()
     }
 
+    /// Unwrap the packet at `link` without updating the user state, eg. to preview it in a UI
+    /// before processing it. The packet can be received afterwards as if it wasn't peeked.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the packet to be peeked
+    ///
+    pub fn peek(&mut self, link: &Address) -> Result<UnwrappedMessage> {
+        self.user.peek(link)
+    }
+
     /// Receive and process a message of unknown type. Message will be handled appropriately and
     /// the unwrapped contents returned
     ///
@@ -221,6 +1141,40 @@ impl<Trans: Transport> Author<Trans>
     pub fn import(bytes: &[u8], pwd: &str, tsp: Trans) -> Result<Self> {
         User::<Trans>::import(bytes, 0, pwd, tsp).map(|user| Self { user })
     }
+
+    /// Deserialize the state of a hybrid channel author and decrypt it with password.
+    ///
+    /// **MSS leaves are one-time keys.** Restoring a state exported before later signatures
+    /// and signing with it reuses leaves, which lets anybody forge post-quantum signatures of
+    /// the author. The state is refused unless its next leaf is at least `min_pq_index`.
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Encrypted serialized user state
+    ///   * `pwd` - Encryption password
+    ///   * `tsp` - Transport object
+    ///   * `min_pq_index` - Latest `next_pq_signature_index` of the author
+    ///
+    #[cfg(feature = "pq-sig")]
+    pub fn import_hybrid(bytes: &[u8], pwd: &str, tsp: Trans, min_pq_index: u32) -> Result<Self> {
+        User::<Trans>::import_hybrid(bytes, 0, pwd, tsp, min_pq_index).map(|user| Self { user })
+    }
+
+    /// Upgrade user state exported by an earlier release to the current state version, fails
+    /// with `StateError` on unknown versions.
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Encrypted serialized user state
+    ///   * `pwd` - Encryption password
+    ///
+    pub fn migrate_state(bytes: &[u8], pwd: &str) -> Result<Vec<u8>> {
+        User::<Trans>::migrate_state(bytes, 0, pwd)
+    }
+}
+
+impl<Trans> fmt::Debug for Author<Trans> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Author").field("user", &self.user.user).finish()
+    }
 }
 
 impl<Trans> fmt::Display for Author<Trans> {
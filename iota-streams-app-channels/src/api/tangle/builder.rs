@@ -0,0 +1,304 @@
+//! Builders of Author and Subscriber instances.
+//!
+//! Options not set explicitly take their defaults: UTF-8 encoding, `PAYLOAD_BYTES` per message
+//! chunk, single-branch sequencing and channel index 0. Combinations of options are validated
+//! when the instance is built.
+
+use anyhow::{
+    anyhow,
+    ensure,
+    Result,
+};
+
+use super::*;
+use iota_streams_app::transport::tangle::PAYLOAD_BYTES;
+use iota_streams_core::prelude::{
+    String,
+    ToString,
+};
+
+/// Options common to Authors and Subscribers.
+struct UserOptions<Trans> {
+    seed: Option<Seed>,
+    encoding: String,
+    payload_length: usize,
+    transport: Option<Trans>,
+}
+
+impl<Trans> Default for UserOptions<Trans> {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            encoding: "utf-8".to_string(),
+            payload_length: PAYLOAD_BYTES,
+            transport: None,
+        }
+    }
+}
+
+impl<Trans: Transport> UserOptions<Trans> {
+    fn build(self, multi_branching: bool) -> Result<User<Trans>> {
+        let seed = self.seed.ok_or_else(|| anyhow!("Seed is not set."))?;
+        let transport = self.transport.ok_or_else(|| anyhow!("Transport is not set."))?;
+        ensure!(self.encoding == "utf-8", "Unsupported encoding: {}.", self.encoding);
+        ensure!(
+            0 < self.payload_length && self.payload_length <= PAYLOAD_BYTES,
+            "Payload length out of range: {}.",
+            self.payload_length
+        );
+        Ok(User::new(
+            &seed,
+            &self.encoding,
+            self.payload_length,
+            multi_branching,
+            transport,
+        ))
+    }
+}
+
+/// Builder of Author instances, see `Author::builder`.
+pub struct AuthorBuilder<Trans> {
+    options: UserOptions<Trans>,
+    multi_branching: bool,
+    channel_idx: u64,
+    msgid_size: Option<usize>,
+    msgid_hash: MsgIdHash,
+    counter_sequencing: bool,
+    siv_masking: bool,
+    #[cfg(feature = "pq-kem")]
+    hybrid_kem: bool,
+    #[cfg(feature = "pq-sig")]
+    mss_height: Option<usize>,
+    threshold_pk: Option<PublicKey>,
+    auto_rekey: bool,
+    subscription_approval: bool,
+}
+
+impl<Trans> Default for AuthorBuilder<Trans> {
+    fn default() -> Self {
+        Self {
+            options: UserOptions::default(),
+            multi_branching: false,
+            channel_idx: 0,
+            msgid_size: None,
+            msgid_hash: MsgIdHash::default(),
+            counter_sequencing: false,
+            siv_masking: false,
+            #[cfg(feature = "pq-kem")]
+            hybrid_kem: false,
+            #[cfg(feature = "pq-sig")]
+            mss_height: None,
+            threshold_pk: None,
+            auto_rekey: false,
+            subscription_approval: false,
+        }
+    }
+}
+
+impl<Trans: Transport> AuthorBuilder<Trans> {
+    /// Seed of the user
+    pub fn seed(mut self, seed: Seed) -> Self {
+        self.options.seed = Some(seed);
+        self
+    }
+
+    /// Encoding type for the message [supported: utf-8]
+    pub fn encoding(mut self, encoding: &str) -> Self {
+        self.options.encoding = encoding.to_string();
+        self
+    }
+
+    /// Maximum size in bytes of payload per message chunk [1-`PAYLOAD_BYTES`]
+    pub fn payload_length(mut self, payload_length: usize) -> Self {
+        self.options.payload_length = payload_length;
+        self
+    }
+
+    /// Transport object used for sending and receiving
+    pub fn transport(mut self, transport: Trans) -> Self {
+        self.options.transport = Some(transport);
+        self
+    }
+
+    /// Parameters shared with subscribers: encoding, payload length, branching and msgid size
+    pub fn config(mut self, config: &ChannelConfig) -> Result<Self> {
+        config.validate()?;
+        self.options.encoding = config.encoding.clone();
+        self.options.payload_length = config.payload_length;
+        self.multi_branching = config.multi_branching;
+        self.msgid_size = Some(config.msgid_size);
+        Ok(self)
+    }
+
+    /// Use of multi-branch or single-branch sequencing
+    pub fn branching(mut self, multi_branching: bool) -> Self {
+        self.multi_branching = multi_branching;
+        self
+    }
+
+    /// Index of the channel, different indices give different channel addresses
+    pub fn channel_idx(mut self, channel_idx: u64) -> Self {
+        self.channel_idx = channel_idx;
+        self
+    }
+
+    /// Width of message identifiers in bytes [supported: 12, 24, 32]
+    pub fn msgid_size(mut self, msgid_size: usize) -> Self {
+        self.msgid_size = Some(msgid_size);
+        self
+    }
+
+    /// Hash function of message identifiers
+    pub fn msgid_hash(mut self, msgid_hash: MsgIdHash) -> Self {
+        self.msgid_hash = msgid_hash;
+        self
+    }
+
+    /// Use of per-publisher message counters instead of sequence messages, single-branch only
+    pub fn counter_sequencing(mut self, counter_sequencing: bool) -> Self {
+        self.counter_sequencing = counter_sequencing;
+        self
+    }
+
+    /// Use of synthetic IVs for masked payloads, see `Author::new_with_siv_masking`
+    pub fn siv_masking(mut self, siv_masking: bool) -> Self {
+        self.siv_masking = siv_masking;
+        self
+    }
+
+    /// Use of post-quantum key encapsulation in keyloads, see `Author::new_with_hybrid_kem`
+    #[cfg(feature = "pq-kem")]
+    pub fn hybrid_kem(mut self, hybrid_kem: bool) -> Self {
+        self.hybrid_kem = hybrid_kem;
+        self
+    }
+
+    /// Sign with MSS besides Ed25519 with Merkle tree of `mss_height` [1-16], see `Author::new_hybrid`
+    #[cfg(feature = "pq-sig")]
+    pub fn hybrid(mut self, mss_height: usize) -> Self {
+        self.mss_height = Some(mss_height);
+        self
+    }
+
+    /// Coordinate a channel authored by threshold group `group_pk`, see `Author::new_threshold`
+    pub fn threshold(mut self, group_pk: &PublicKey) -> Self {
+        self.threshold_pk = Some(group_pk.clone());
+        self
+    }
+
+    /// Send updated keyloads ahead of packets on membership changes, see `Author::set_auto_rekey`
+    pub fn auto_rekey(mut self, auto_rekey: bool) -> Self {
+        self.auto_rekey = auto_rekey;
+        self
+    }
+
+    /// Queue subscriptions for an explicit decision, see `Author::set_subscription_approval`
+    pub fn subscription_approval(mut self, approval: bool) -> Self {
+        self.subscription_approval = approval;
+        self
+    }
+
+    /// Create the Author and its channel (without announcing it).
+    pub fn build(self) -> Result<Author<Trans>> {
+        ensure!(
+            !(self.multi_branching && self.counter_sequencing),
+            "Counter sequencing requires single-branch sequencing."
+        );
+        #[cfg(feature = "pq-sig")]
+        ensure!(
+            self.threshold_pk.is_none() || self.mss_height.is_none(),
+            "Threshold channels can't be hybrid."
+        );
+
+        let mut user = self.options.build(self.multi_branching)?;
+        let mut link_gen = LinkGen::default().with_msgid_hash(self.msgid_hash);
+        if let Some(msgid_size) = self.msgid_size {
+            link_gen = link_gen.with_msgid_size(msgid_size)?;
+        }
+        user.user = user
+            .user
+            .with_link_gen(link_gen)
+            .with_counter_sequencing(self.counter_sequencing)
+            .with_siv_masking(self.siv_masking);
+        #[cfg(feature = "pq-kem")]
+        if self.hybrid_kem {
+            user.user = user.user.with_hybrid_kem(true);
+        }
+        user.set_auto_rekey(self.auto_rekey);
+        user.set_subscription_approval(self.subscription_approval);
+
+        if let Some(group_pk) = self.threshold_pk {
+            user.user.create_threshold_channel(&group_pk, self.channel_idx)?;
+            return Author::from_user(user);
+        }
+        #[cfg(feature = "pq-sig")]
+        if let Some(mss_height) = self.mss_height {
+            user.user.create_hybrid_channel(self.channel_idx, mss_height)?;
+            return Author::from_user(user);
+        }
+        user.user.create_channel(self.channel_idx)?;
+        Author::from_user(user)
+    }
+}
+
+/// Builder of Subscriber instances, see `Subscriber::builder`. Channel options are set by the
+/// announcement the subscriber receives.
+pub struct SubscriberBuilder<Trans> {
+    options: UserOptions<Trans>,
+    config: Option<ChannelConfig>,
+}
+
+impl<Trans> Default for SubscriberBuilder<Trans> {
+    fn default() -> Self {
+        Self {
+            options: UserOptions::default(),
+            config: None,
+        }
+    }
+}
+
+impl<Trans: Transport> SubscriberBuilder<Trans> {
+    /// Seed of the user
+    pub fn seed(mut self, seed: Seed) -> Self {
+        self.options.seed = Some(seed);
+        self
+    }
+
+    /// Encoding type for the message [supported: utf-8]
+    pub fn encoding(mut self, encoding: &str) -> Self {
+        self.options.encoding = encoding.to_string();
+        self
+    }
+
+    /// Maximum size in bytes of payload per message chunk [1-`PAYLOAD_BYTES`]
+    pub fn payload_length(mut self, payload_length: usize) -> Self {
+        self.options.payload_length = payload_length;
+        self
+    }
+
+    /// Transport object used for sending and receiving
+    pub fn transport(mut self, transport: Trans) -> Self {
+        self.options.transport = Some(transport);
+        self
+    }
+
+    /// Parameters shared with the author, announcements of channels created with other
+    /// parameters are rejected
+    pub fn config(mut self, config: &ChannelConfig) -> Result<Self> {
+        config.validate()?;
+        self.options.encoding = config.encoding.clone();
+        self.options.payload_length = config.payload_length;
+        self.config = Some(config.clone());
+        Ok(self)
+    }
+
+    /// Create the Subscriber.
+    pub fn build(self) -> Result<Subscriber<Trans>> {
+        let user = self.options.build(false)?;
+        let mut subscriber = Subscriber::from_user(user)?;
+        if let Some(config) = self.config {
+            subscriber.set_channel_config(config)?;
+        }
+        Ok(subscriber)
+    }
+}
@@ -0,0 +1,337 @@
+//! Protocol state of a channel kept by the tangle `User` next to the api user state.
+//!
+//! The state is exported and imported together with the user state, so pending embargoes and
+//! revocations, the key tree and the directory survive a restart. It holds release keys and
+//! node keys and is only ever stored masked within the exported user state. All addresses are
+//! in the channel of the user, so only their msgids are encoded:
+//!
+//! ```text
+//! state = version: u8 || flags: u8 || sent at: u64 || directory refresh: u32 || directory sent: u32 ||
+//!     count: u32 || count * (link: msgid || release at: option u32 || release: bytes) ||
+//!     count: u32 || count * (msgid || epoch: u64 || root: msgid || keyload: msgid) ||
+//!     count: u32 || count * (pk: [u8; 32] || access window || sent: u32) ||
+//!     access window: option access window || key tree: option bytes || member keys: option bytes ||
+//!     directory: bytes || directory link: option msgid ||
+//!     count: u32 || count * (link: msgid || pk: [u8; 32] || fetched: u8 || timestamp: u64 || body: bytes)
+//! msgid = size: u8 || msgid bytes
+//! bytes = size: u32 || bytes
+//! option x = 0: u8 | 1: u8 || x
+//! ```
+//!
+//! `flags` has bit 0 set for auto rekey and bit 1 for subscription approval, integers are big-endian.
+
+use anyhow::{
+    anyhow,
+    ensure,
+    Result,
+};
+
+use super::*;
+use crate::api::revocation::ACCESS_WINDOW_SIZE;
+use iota_streams_app::{
+    message::BinaryMessage,
+    transport::tangle::MSGID_SIZES,
+};
+use iota_streams_core::prelude::{
+    HashMap,
+    Vec,
+};
+
+/// Version of the channel state encoding.
+const CHANNEL_STATE_VERSION: u8 = 0;
+
+/// Flag of auto rekey.
+const FLAG_AUTO_REKEY: u8 = 1;
+
+/// Flag of subscription approval.
+const FLAG_SUBSCRIPTION_APPROVAL: u8 = 2;
+
+/// Protocol state of a channel, see the module docs.
+#[derive(Default)]
+pub(super) struct ChannelState {
+    /// Pending embargoes of sent embargoed packets.
+    pub(super) embargoes: Vec<Embargo>,
+    /// Send keyloads for everyone ahead of packets when the subscribers changed.
+    pub(super) auto_rekey: bool,
+    /// Membership epoch of the keyload for everyone each message sent with auto rekey is
+    /// protected by, the message the keyload is linked to and the keyload.
+    pub(super) rekey_epochs: HashMap<MsgId, (u64, Address, MsgId)>,
+    /// Pending revocations: revoked subscriber, its access window and packets sent since.
+    pub(super) revocations: Vec<(PublicKey, AccessWindow, u32)>,
+    /// Access window of the user announced by the author, `None` unless revoked.
+    pub(super) access_window: Option<AccessWindow>,
+    /// Timestamp of the last sent message.
+    pub(super) sent_at: u64,
+    /// Key tree of the subscribers, `None` unless enabled by the author.
+    pub(super) key_tree: Option<KeyTree>,
+    /// Path keys of the user in the key tree of the author, `None` unless a member.
+    pub(super) tree_keys: Option<MemberKeys>,
+    /// Named branches of the channel published by the author.
+    pub(super) directory: Directory,
+    /// Address of the latest published directory, `None` until published.
+    pub(super) directory_link: Option<Address>,
+    /// Packets sent between refreshes of the directory and packets sent since the last one.
+    pub(super) directory_refresh: (u32, u32),
+    /// Queue subscriptions for an explicit decision instead of accepting them on receipt.
+    pub(super) subscription_approval: bool,
+    /// Subscriptions awaiting a decision, their messages and whether they were fetched.
+    pub(super) pending_subscriptions: Vec<(PendingSubscription, Message, bool)>,
+}
+
+fn put_msgid(bytes: &mut Vec<u8>, msgid: &MsgId) {
+    bytes.push(msgid.size() as u8);
+    bytes.extend_from_slice(msgid.as_ref());
+}
+
+fn put_bytes(bytes: &mut Vec<u8>, b: &[u8]) -> Result<()> {
+    ensure!(
+        b.len() <= u32::MAX as usize,
+        "Channel state field too long: {}.",
+        b.len()
+    );
+    bytes.extend_from_slice(&(b.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(b);
+    Ok(())
+}
+
+fn put_count(bytes: &mut Vec<u8>, count: usize) -> Result<()> {
+    ensure!(count <= u32::MAX as usize, "Too many channel state entries: {}.", count);
+    bytes.extend_from_slice(&(count as u32).to_be_bytes());
+    Ok(())
+}
+
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Result<&'a [u8]> {
+    ensure!(bytes.len() >= n, "Channel state is truncated.");
+    let (head, tail) = bytes.split_at(n);
+    *bytes = tail;
+    Ok(head)
+}
+
+fn take_u32(bytes: &mut &[u8]) -> Result<u32> {
+    let b = take(bytes, 4)?;
+    Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn take_u64(bytes: &mut &[u8]) -> Result<u64> {
+    let mut b = [0_u8; 8];
+    b.copy_from_slice(take(bytes, 8)?);
+    Ok(u64::from_be_bytes(b))
+}
+
+fn take_flag(bytes: &mut &[u8]) -> Result<bool> {
+    match take(bytes, 1)?[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        flag => Err(anyhow!("Bad channel state option: {}.", flag)),
+    }
+}
+
+fn take_msgid(bytes: &mut &[u8]) -> Result<MsgId> {
+    let size = take(bytes, 1)?[0] as usize;
+    ensure!(MSGID_SIZES.contains(&size), "Bad msgid size: {}.", size);
+    Ok(MsgId::from(take(bytes, size)?))
+}
+
+fn take_bytes<'a>(bytes: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let size = take_u32(bytes)? as usize;
+    take(bytes, size)
+}
+
+fn take_pk(bytes: &mut &[u8]) -> Result<PublicKey> {
+    PublicKey::from_bytes(take(bytes, ed25519::PUBLIC_KEY_LENGTH)?).map_err(|e| anyhow!("Bad public key: {}.", e))
+}
+
+fn take_access_window(bytes: &mut &[u8]) -> Result<AccessWindow> {
+    AccessWindow::from_bytes(take(bytes, ACCESS_WINDOW_SIZE)?)
+}
+
+impl ChannelState {
+    /// Encode the state, see the module docs.
+    pub(super) fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        bytes.push(CHANNEL_STATE_VERSION);
+        let mut flags = 0;
+        if self.auto_rekey {
+            flags |= FLAG_AUTO_REKEY;
+        }
+        if self.subscription_approval {
+            flags |= FLAG_SUBSCRIPTION_APPROVAL;
+        }
+        bytes.push(flags);
+        bytes.extend_from_slice(&self.sent_at.to_be_bytes());
+        bytes.extend_from_slice(&self.directory_refresh.0.to_be_bytes());
+        bytes.extend_from_slice(&self.directory_refresh.1.to_be_bytes());
+
+        put_count(&mut bytes, self.embargoes.len())?;
+        for embargo in &self.embargoes {
+            put_msgid(&mut bytes, &embargo.link.msgid);
+            match embargo.release_at {
+                Some(index) => {
+                    bytes.push(1);
+                    bytes.extend_from_slice(&index.to_be_bytes());
+                }
+                None => bytes.push(0),
+            }
+            put_bytes(&mut bytes, &embargo.release.to_bytes())?;
+        }
+
+        put_count(&mut bytes, self.rekey_epochs.len())?;
+        for (msgid, (epoch, root, keyload)) in &self.rekey_epochs {
+            put_msgid(&mut bytes, msgid);
+            bytes.extend_from_slice(&epoch.to_be_bytes());
+            put_msgid(&mut bytes, &root.msgid);
+            put_msgid(&mut bytes, keyload);
+        }
+
+        put_count(&mut bytes, self.revocations.len())?;
+        for (pk, window, sent) in &self.revocations {
+            bytes.extend_from_slice(pk.as_bytes());
+            bytes.extend_from_slice(&window.to_bytes());
+            bytes.extend_from_slice(&sent.to_be_bytes());
+        }
+
+        match &self.access_window {
+            Some(window) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&window.to_bytes());
+            }
+            None => bytes.push(0),
+        }
+        match &self.key_tree {
+            Some(tree) => {
+                bytes.push(1);
+                put_bytes(&mut bytes, &tree.to_bytes())?;
+            }
+            None => bytes.push(0),
+        }
+        match &self.tree_keys {
+            Some(keys) => {
+                bytes.push(1);
+                put_bytes(&mut bytes, &keys.to_bytes())?;
+            }
+            None => bytes.push(0),
+        }
+
+        put_bytes(&mut bytes, &self.directory.to_bytes()?)?;
+        match &self.directory_link {
+            Some(link) => {
+                bytes.push(1);
+                put_msgid(&mut bytes, &link.msgid);
+            }
+            None => bytes.push(0),
+        }
+
+        put_count(&mut bytes, self.pending_subscriptions.len())?;
+        for (pending, msg, fetched) in &self.pending_subscriptions {
+            put_msgid(&mut bytes, &pending.link.msgid);
+            bytes.extend_from_slice(pending.pk.as_bytes());
+            bytes.push(*fetched as u8);
+            bytes.extend_from_slice(&msg.timestamp.to_be_bytes());
+            put_bytes(&mut bytes, &msg.binary.body.bytes)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Decode the state encoded with `to_bytes`, addresses are in channel `appinst`.
+    pub(super) fn from_bytes(appinst: &AppInst, mut bytes: &[u8]) -> Result<Self> {
+        let bytes = &mut bytes;
+        let link = |msgid| Address::new(appinst.clone(), msgid);
+        let version = take(bytes, 1)?[0];
+        ensure!(
+            version == CHANNEL_STATE_VERSION,
+            "Unsupported channel state version: {}.",
+            version
+        );
+        let flags = take(bytes, 1)?[0];
+        let sent_at = take_u64(bytes)?;
+        let directory_refresh = (take_u32(bytes)?, take_u32(bytes)?);
+
+        let count = take_u32(bytes)?;
+        let mut embargoes = Vec::new();
+        for _ in 0..count {
+            let msgid = take_msgid(bytes)?;
+            let release_at = if take_flag(bytes)? {
+                Some(take_u32(bytes)?)
+            } else {
+                None
+            };
+            embargoes.push(Embargo {
+                link: link(msgid),
+                release_at,
+                release: EmbargoRelease::from_bytes(take_bytes(bytes)?)?,
+            });
+        }
+
+        let count = take_u32(bytes)?;
+        let mut rekey_epochs = HashMap::new();
+        for _ in 0..count {
+            let msgid = take_msgid(bytes)?;
+            let epoch = take_u64(bytes)?;
+            let root = link(take_msgid(bytes)?);
+            let keyload = take_msgid(bytes)?;
+            rekey_epochs.insert(msgid, (epoch, root, keyload));
+        }
+
+        let count = take_u32(bytes)?;
+        let mut revocations = Vec::new();
+        for _ in 0..count {
+            let pk = take_pk(bytes)?;
+            let window = take_access_window(bytes)?;
+            revocations.push((pk, window, take_u32(bytes)?));
+        }
+
+        let access_window = if take_flag(bytes)? {
+            Some(take_access_window(bytes)?)
+        } else {
+            None
+        };
+        let key_tree = if take_flag(bytes)? {
+            Some(KeyTree::from_bytes(take_bytes(bytes)?)?)
+        } else {
+            None
+        };
+        let tree_keys = if take_flag(bytes)? {
+            Some(MemberKeys::from_bytes(take_bytes(bytes)?)?)
+        } else {
+            None
+        };
+
+        let directory = Directory::from_bytes(appinst, take_bytes(bytes)?)?;
+        let directory_link = if take_flag(bytes)? {
+            Some(link(take_msgid(bytes)?))
+        } else {
+            None
+        };
+
+        let count = take_u32(bytes)?;
+        let mut pending_subscriptions = Vec::new();
+        for _ in 0..count {
+            let pending = PendingSubscription {
+                link: link(take_msgid(bytes)?),
+                pk: take_pk(bytes)?,
+            };
+            let fetched = take_flag(bytes)?;
+            let timestamp = take_u64(bytes)?;
+            let body = take_bytes(bytes)?.to_vec();
+            let msg = Message::with_timestamp(BinaryMessage::new(pending.link.clone(), body.into()), timestamp);
+            pending_subscriptions.push((pending, msg, fetched));
+        }
+        ensure!(bytes.is_empty(), "Trailing bytes after channel state: {}.", bytes.len());
+
+        Ok(Self {
+            embargoes,
+            auto_rekey: flags & FLAG_AUTO_REKEY != 0,
+            rekey_epochs,
+            revocations,
+            access_window,
+            sent_at,
+            key_tree,
+            tree_keys,
+            directory,
+            directory_link,
+            directory_refresh,
+            subscription_approval: flags & FLAG_SUBSCRIPTION_APPROVAL != 0,
+            pending_subscriptions,
+        })
+    }
+}
@@ -0,0 +1,103 @@
+//! Channel parameters shared by the author and subscribers of a channel.
+//!
+//! Parameters not carried in the announcement (encoding, payload length) must match on both
+//! sides out of band. A `ChannelConfig` persisted once (eg. with the `serde` feature) and passed
+//! to both `AuthorBuilder::config` and `SubscriberBuilder::config` keeps them consistent;
+//! subscribers additionally reject announcements of channels created with other parameters.
+
+use anyhow::{
+    ensure,
+    Result,
+};
+#[cfg(feature = "serde")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::*;
+use iota_streams_app::transport::tangle::{
+    MSGID_SIZE,
+    MSGID_SIZES,
+    PAYLOAD_BYTES,
+};
+use iota_streams_core::prelude::{
+    String,
+    ToString,
+};
+
+/// Spongos PRP of a channel.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PrpKind {
+    /// Keccak-f[1600], see `DefaultF`.
+    KeccakF1600,
+}
+
+impl Default for PrpKind {
+    fn default() -> Self {
+        PrpKind::KeccakF1600
+    }
+}
+
+/// Parameters of a channel.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChannelConfig {
+    /// Use of multi-branch or single-branch sequencing
+    pub multi_branching: bool,
+    /// Encoding type for the message [supported: utf-8]
+    pub encoding: String,
+    /// Maximum size in bytes of payload per message chunk [1-`PAYLOAD_BYTES`]
+    pub payload_length: usize,
+    /// Spongos PRP [supported: KeccakF1600]
+    pub prp: PrpKind,
+    /// Width in bytes of message identifiers, stored in transaction tags [supported: 12, 24, 32]
+    pub msgid_size: usize,
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        Self {
+            multi_branching: false,
+            encoding: "utf-8".to_string(),
+            payload_length: PAYLOAD_BYTES,
+            prp: PrpKind::default(),
+            msgid_size: MSGID_SIZE,
+        }
+    }
+}
+
+impl ChannelConfig {
+    /// Check that the parameters are supported.
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.encoding == "utf-8", "Unsupported encoding: {}.", self.encoding);
+        ensure!(
+            0 < self.payload_length && self.payload_length <= PAYLOAD_BYTES,
+            "Payload length out of range: {}.",
+            self.payload_length
+        );
+        ensure!(
+            MSGID_SIZES.contains(&self.msgid_size),
+            "Unsupported msgid size: {}.",
+            self.msgid_size
+        );
+        Ok(())
+    }
+
+    /// Check that the channel announced at `announcement_link` with `multi_branching` sequencing
+    /// was created with these parameters.
+    pub fn ensure_announced(&self, announcement_link: &Address, multi_branching: bool) -> Result<()> {
+        ensure!(
+            self.multi_branching == multi_branching,
+            "Channel branching differs from the config: multi_branching={}.",
+            multi_branching
+        );
+        ensure!(
+            self.msgid_size == announcement_link.msgid.size(),
+            "Channel msgid size differs from the config: {}.",
+            announcement_link.msgid.size()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+//! Directory of the named branches of a channel.
+//!
+//! The author of a channel with many branches publishes a directory in a signed packet: the
+//! name of every active branch, the link of its latest keyload and the content types of its
+//! packets, eg. MIME types. Subscribers and tooling bootstrap navigation of the channel from
+//! the link of a directory alone. Directories linked to the announcement are public; the author
+//! refreshes the directory periodically, see `User::set_directory_refresh`. Keyloads of the
+//! branches are all in the channel of the directory, so only their msgids are encoded:
+//!
+//! ```text
+//! directory = version: u8 || count: u16 || count * entry
+//! entry = name: string || keyload msgid size: u8 || keyload msgid || types: u8 || types * string
+//! string = size: u8 || utf-8 bytes
+//! ```
+//!
+//! Integers are big-endian.
+
+use anyhow::{
+    anyhow,
+    ensure,
+    Result,
+};
+
+use super::*;
+use iota_streams_app::transport::tangle::MSGID_SIZES;
+use iota_streams_core::prelude::{
+    String,
+    ToString,
+    Vec,
+};
+
+/// Version of the directory encoding.
+pub const DIRECTORY_VERSION: u8 = 0;
+
+/// Largest number of branches of a directory.
+pub const MAX_DIRECTORY_ENTRIES: usize = u16::MAX as usize;
+
+/// Named branch of a channel directory.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DirectoryEntry {
+    /// Name of the branch, at most 255 bytes
+    pub name: String,
+    /// Address of the latest keyload of the branch
+    pub keyload: Address,
+    /// Content types of the packets of the branch, at most 255 of at most 255 bytes each
+    pub content_types: Vec<String>,
+}
+
+/// Directory of the named branches of a channel.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Directory {
+    pub entries: Vec<DirectoryEntry>,
+}
+
+fn put_string(bytes: &mut Vec<u8>, s: &str) -> Result<()> {
+    ensure!(s.len() <= u8::MAX as usize, "Directory string too long: {}.", s);
+    bytes.push(s.len() as u8);
+    bytes.extend_from_slice(s.as_bytes());
+    Ok(())
+}
+
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Result<&'a [u8]> {
+    ensure!(bytes.len() >= n, "Directory is truncated.");
+    let (head, tail) = bytes.split_at(n);
+    *bytes = tail;
+    Ok(head)
+}
+
+fn take_string(bytes: &mut &[u8]) -> Result<String> {
+    let size = take(bytes, 1)?[0] as usize;
+    let s = take(bytes, size)?;
+    core::str::from_utf8(s)
+        .map(|s| s.to_string())
+        .map_err(|e| anyhow!("Bad directory string: {}.", e))
+}
+
+impl Directory {
+    /// Entry of the branch named `name`.
+    pub fn get(&self, name: &str) -> Option<&DirectoryEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Add the entry or replace the entry of the same name.
+    pub fn insert(&mut self, entry: DirectoryEntry) {
+        match self.entries.iter_mut().find(|e| e.name == entry.name) {
+            Some(e) => *e = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Remove the entry of the branch named `name`, returns whether it was listed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|entry| entry.name != name);
+        self.entries.len() != len
+    }
+
+    /// Encode the directory, see the module docs.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        ensure!(
+            self.entries.len() <= MAX_DIRECTORY_ENTRIES,
+            "Too many directory entries: {}.",
+            self.entries.len()
+        );
+        let mut bytes = Vec::new();
+        bytes.push(DIRECTORY_VERSION);
+        bytes.extend_from_slice(&(self.entries.len() as u16).to_be_bytes());
+        for entry in &self.entries {
+            put_string(&mut bytes, &entry.name)?;
+            let msgid = entry.keyload.msgid.as_ref();
+            bytes.push(msgid.len() as u8);
+            bytes.extend_from_slice(msgid);
+            ensure!(
+                entry.content_types.len() <= u8::MAX as usize,
+                "Too many content types of branch {}.",
+                entry.name
+            );
+            bytes.push(entry.content_types.len() as u8);
+            for content_type in &entry.content_types {
+                put_string(&mut bytes, content_type)?;
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Decode the directory encoded with `to_bytes`, keyloads are in channel `appinst`.
+    pub fn from_bytes(appinst: &AppInst, mut bytes: &[u8]) -> Result<Self> {
+        let bytes = &mut bytes;
+        let version = take(bytes, 1)?[0];
+        ensure!(version == DIRECTORY_VERSION, "Unsupported directory version: {}.", version);
+        let count = take(bytes, 2)?;
+        let count = u16::from_be_bytes([count[0], count[1]]) as usize;
+        let mut entries = Vec::with_capacity(count);
+        for _ in 0..count {
+            let name = take_string(bytes)?;
+            let size = take(bytes, 1)?[0] as usize;
+            ensure!(MSGID_SIZES.contains(&size), "Bad keyload msgid size: {}.", size);
+            let keyload = Address::new(appinst.clone(), MsgId::from(take(bytes, size)?));
+            let types = take(bytes, 1)?[0];
+            let content_types = (0..types).map(|_| take_string(bytes)).collect::<Result<Vec<_>>>()?;
+            entries.push(DirectoryEntry {
+                name,
+                keyload,
+                content_types,
+            });
+        }
+        ensure!(bytes.is_empty(), "Trailing bytes after directory: {}.", bytes.len());
+        Ok(Self { entries })
+    }
+}
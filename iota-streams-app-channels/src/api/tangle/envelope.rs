@@ -0,0 +1,164 @@
+//! Canonical JSON and CBOR representation of unwrapped messages.
+//!
+//! The envelope is a stable, documented schema for consumers outside of Rust, eg. services
+//! behind a gateway. Fields of version `ENVELOPE_VERSION`:
+//!
+//! | field             | type            | content                                              |
+//! |-------------------|-----------------|------------------------------------------------------|
+//! | `version`         | integer         | envelope schema version                              |
+//! | `link`            | string          | message address, hex with checksum                   |
+//! | `type`            | string          | `announce`, `keyload`, `signed_packet`, `tagged_packet`, `private_packet`, `branch_delegation`, `checkpoint`, `sequence`, `subscribe`, `unsubscribe` or `filtered` |
+//! | `pk`              | string or null  | hex Ed25519 public key of the publisher              |
+//! | `public_payload`  | string or null  | base64 public payload of packets                     |
+//! | `masked_payload`  | string or null  | base64 decrypted masked payload of packets           |
+//! | `private_payload` | string or null  | base64 private payload of private packets            |
+//! | `timestamp`       | integer or null | timestamp of the confirming milestone in ms          |
+//! | `milestone`       | integer or null | index of the confirming milestone                    |
+//!
+//! All fields are always present, absent values are `null`. New fields may be added in later
+//! versions, consumers should ignore unknown fields.
+
+use anyhow::{
+    anyhow,
+    Result,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::*;
+use iota_streams_app::transport::tangle::AddressFormat;
+use iota_streams_core::prelude::{
+    hex,
+    String,
+    ToString,
+    Vec,
+};
+
+/// Version of the envelope schema.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// Machine-readable representation of an unwrapped message, see module documentation.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Envelope {
+    pub version: u8,
+    pub link: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub pk: Option<String>,
+    pub public_payload: Option<String>,
+    pub masked_payload: Option<String>,
+    pub private_payload: Option<String>,
+    pub timestamp: Option<u64>,
+    pub milestone: Option<u32>,
+}
+
+impl Envelope {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| anyhow!("JSON error: {}", e))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| anyhow!("JSON error: {}", e))
+    }
+
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        serde_cbor::to_vec(self).map_err(|e| anyhow!("CBOR error: {}", e))
+    }
+
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self> {
+        serde_cbor::from_slice(cbor).map_err(|e| anyhow!("CBOR error: {}", e))
+    }
+}
+
+fn encode_payload(payload: &Bytes) -> Option<String> {
+    Some(base64::encode(&payload.0))
+}
+
+/// Conversion of unwrapped messages into envelopes.
+pub trait ToEnvelope {
+    /// Envelope of the message, masked payloads of lazy packets are decrypted.
+    fn to_envelope(&self) -> Result<Envelope>;
+
+    /// Envelope of the message as JSON.
+    fn to_json(&self) -> Result<String> {
+        self.to_envelope()?.to_json()
+    }
+
+    /// Envelope of the message as CBOR.
+    fn to_cbor(&self) -> Result<Vec<u8>> {
+        self.to_envelope()?.to_cbor()
+    }
+}
+
+impl ToEnvelope for UnwrappedMessage {
+    fn to_envelope(&self) -> Result<Envelope> {
+        let mut envelope = Envelope {
+            version: ENVELOPE_VERSION,
+            link: self.link.to_string_with(AddressFormat::Hex)?,
+            kind: String::new(),
+            pk: None,
+            public_payload: None,
+            masked_payload: None,
+            private_payload: None,
+            timestamp: self.milestone.map(|m| m.timestamp),
+            milestone: self.milestone.map(|m| m.index),
+        };
+        let kind = match &self.body {
+            MessageContent::Announce => "announce",
+            MessageContent::Keyload => "keyload",
+            MessageContent::SignedPacket {
+                pk,
+                public_payload,
+                masked_payload,
+            } => {
+                envelope.pk = Some(hex::encode(pk.as_bytes()));
+                envelope.public_payload = encode_payload(public_payload);
+                envelope.masked_payload = encode_payload(masked_payload);
+                "signed_packet"
+            }
+            MessageContent::TaggedPacket {
+                public_payload,
+                masked_payload,
+            } => {
+                envelope.public_payload = encode_payload(public_payload);
+                envelope.masked_payload = encode_payload(masked_payload);
+                "tagged_packet"
+            }
+            MessageContent::PrivatePacket {
+                pk,
+                public_payload,
+                masked_payload,
+                private_payload,
+            } => {
+                envelope.pk = Some(hex::encode(pk.as_bytes()));
+                envelope.public_payload = encode_payload(public_payload);
+                envelope.masked_payload = encode_payload(masked_payload);
+                envelope.private_payload = private_payload.as_ref().and_then(encode_payload);
+                "private_packet"
+            }
+            MessageContent::LazyPacket { pk, public_payload, .. } => {
+                envelope.pk = pk.as_ref().map(|pk| hex::encode(pk.as_bytes()));
+                envelope.public_payload = encode_payload(public_payload);
+                envelope.masked_payload = encode_payload(&self.masked_payload()?);
+                if pk.is_some() {
+                    "signed_packet"
+                } else {
+                    "tagged_packet"
+                }
+            }
+            MessageContent::BranchDelegation { delegate_pk } => {
+                envelope.pk = Some(hex::encode(delegate_pk.as_bytes()));
+                "branch_delegation"
+            }
+            MessageContent::Checkpoint { .. } => "checkpoint",
+            MessageContent::Sequence => "sequence",
+            MessageContent::Subscribe => "subscribe",
+            MessageContent::Unsubscribe => "unsubscribe",
+            MessageContent::Filtered { .. } => "filtered",
+        };
+        envelope.kind = kind.to_string();
+        Ok(envelope)
+    }
+}
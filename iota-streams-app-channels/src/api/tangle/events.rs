@@ -0,0 +1,41 @@
+use anyhow::Error;
+
+use super::*;
+
+/// Application callbacks notified of protocol events processed by a user.
+///
+/// Events are raised by `receive_subscribe` and by the generic message handling
+/// (`receive_message`, `fetch_next_msgs`, `sync_state`); typed `receive_*` calls return
+/// the content directly. Rekeys are raised by packet sends. All methods do nothing by default.
+pub trait UserEvents {
+    /// Subscription of `subscriber_pk` accepted [Author].
+    fn on_subscription(&mut self, _link: &Address, _subscriber_pk: &PublicKey) {}
+
+    /// Pending subscription of `subscriber_pk` rejected, see `User::set_subscription_approval` [Author].
+    fn on_subscription_rejected(&mut self, _link: &Address, _subscriber_pk: &PublicKey) {}
+
+    /// Keyload processed, `allowed` is true if the user is one of its recipients.
+    fn on_keyload(&mut self, _link: &Address, _allowed: bool) {}
+
+    /// Signed, tagged or private packet unwrapped.
+    fn on_packet(&mut self, _msg: &UnwrappedMessage) {}
+
+    /// Sequence message processed, `msg_link` is the link of the sequenced message.
+    fn on_sequence(&mut self, _link: &Address, _msg_link: &Address) {}
+
+    /// Packet of `publisher_pk` deviates from the publisher's sequence, see `SequencePolicy`.
+    fn on_sequence_violation(
+        &mut self,
+        _link: &Address,
+        _publisher_pk: &PublicKey,
+        _violation: &SequenceViolation,
+    ) {
+    }
+
+    /// Keyload for everyone sent ahead of a packet linked to `link_to` as the subscribers
+    /// changed, see `User::set_auto_rekey` [Author].
+    fn on_rekey(&mut self, _keyload_link: &Address, _link_to: &Address) {}
+
+    /// Message at `link` could not be handled.
+    fn on_error(&mut self, _link: &Address, _error: &Error) {}
+}
@@ -0,0 +1,418 @@
+//! REST and WebSocket gateway to channels for services outside of Rust.
+//!
+//! The gateway owns the authors and subscribers it creates, their keys never leave the process.
+//! Clients refer to them by the user id returned on creation. Bodies are JSON, links are hex
+//! addresses with checksum and payloads are base64. Received messages are `Envelope`s.
+//!
+//! | method | path                           | body                                                 | response                      |
+//! |--------|--------------------------------|------------------------------------------------------|-------------------------------|
+//! | POST   | `/channels`                    | `{"multi_branching": bool}`, optional               | `{"user", "pk", "announcement"}` |
+//! | POST   | `/subscriptions`               | `{"announcement"}`                                   | `{"user", "pk", "subscribe"}` |
+//! | POST   | `/users/{id}/subscribers`      | `{"subscribe"}`                                      | `{}`                          |
+//! | POST   | `/users/{id}/keyloads`         | `{"link_to"}`                                        | `{"link", "sequence"}`        |
+//! | POST   | `/users/{id}/packets`          | `{"link_to", "public_payload", "masked_payload", "signed"}` | `{"link", "sequence"}` |
+//! | GET    | `/users/{id}/messages`         |                                                      | array of envelopes            |
+//! | GET    | `/users/{id}/messages/stream`  | WebSocket upgrade                                    | envelopes as text frames      |
+//! | DELETE | `/users/{id}`                  |                                                      | `{}`                          |
+//!
+//! Keyloads are sent for all known subscribers. Packets are signed unless `"signed": false`.
+//! Errors are `{"error"}` with status 400, 404 for unknown users and routes, or 413 for bodies
+//! larger than `MAX_REQUEST_BODY_SIZE` bytes. Listing and
+//! streaming messages of a user share its cursors, each message is delivered once.
+
+use anyhow::{
+    anyhow,
+    Result,
+};
+use serde::Deserialize;
+use serde_json::{
+    json,
+    Value,
+};
+use std::{
+    io::Read,
+    time::Duration,
+};
+use tungstenite::{
+    handshake::derive_accept_key,
+    protocol::Role,
+    WebSocket,
+};
+
+use super::*;
+use iota_streams_app::transport::tangle::{
+    AddressFormat,
+    PAYLOAD_BYTES,
+};
+use iota_streams_core::{
+    prelude::{
+        hex,
+        HashMap,
+        String,
+        ToString,
+        Vec,
+    },
+    prng,
+};
+
+/// Maximum size in bytes of request bodies accepted by the gateway.
+pub const MAX_REQUEST_BODY_SIZE: usize = 1 << 20;
+
+/// Response of the gateway to a request.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GatewayResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// JSON body
+    pub body: String,
+}
+
+impl GatewayResponse {
+    fn json(status: u16, value: Value) -> Self {
+        Self {
+            status,
+            body: value.to_string(),
+        }
+    }
+
+    fn error(status: u16, msg: &str) -> Self {
+        Self::json(status, json!({ "error": msg }))
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct CreateChannel {
+    #[serde(default)]
+    multi_branching: bool,
+}
+
+#[derive(Deserialize)]
+struct CreateSubscription {
+    announcement: String,
+}
+
+#[derive(Deserialize)]
+struct AcceptSubscriber {
+    subscribe: String,
+}
+
+#[derive(Deserialize)]
+struct SendKeyload {
+    link_to: String,
+}
+
+fn default_signed() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct SendPacket {
+    link_to: String,
+    #[serde(default)]
+    public_payload: String,
+    #[serde(default)]
+    masked_payload: String,
+    #[serde(default = "default_signed")]
+    signed: bool,
+}
+
+fn parse_body<'a, T: Deserialize<'a>>(body: &'a [u8]) -> Result<T> {
+    serde_json::from_slice(body).map_err(|e| anyhow!("Bad request body: {}", e))
+}
+
+fn parse_link(link: &str) -> Result<Address> {
+    Address::from_str_with(link, AddressFormat::Hex)
+}
+
+fn format_link(link: &Address) -> Result<String> {
+    link.to_string_with(AddressFormat::Hex)
+}
+
+fn parse_payload(payload: &str) -> Result<Bytes> {
+    base64::decode(payload)
+        .map(Bytes)
+        .map_err(|e| anyhow!("Bad base64 payload: {}", e))
+}
+
+fn sent(link: &Address, sequence: &Option<Address>) -> Result<Value> {
+    let sequence = match sequence {
+        Some(sequence) => Some(format_link(sequence)?),
+        None => None,
+    };
+    Ok(json!({ "link": format_link(link)?, "sequence": sequence }))
+}
+
+/// Gateway exposing authors and subscribers of channels over REST and WebSocket, see module docs.
+pub struct Gateway<Trans> {
+    transport: Trans,
+    users: HashMap<String, Participant<Trans>>,
+    streams: Vec<(String, WebSocket<Box<dyn tiny_http::ReadWrite + Send>>)>,
+}
+
+impl<Trans: Transport + Clone> Gateway<Trans> {
+    pub fn new(transport: Trans) -> Self {
+        Self {
+            transport,
+            users: HashMap::new(),
+            streams: Vec::new(),
+        }
+    }
+
+    /// Ids of the users owned by the gateway.
+    pub fn users(&self) -> Vec<&String> {
+        self.users.keys().collect()
+    }
+
+    fn new_user_id(&self) -> String {
+        loop {
+            let id = hex::encode(&prng::random_nonce());
+            if !self.users.contains_key(&id) {
+                return id;
+            }
+        }
+    }
+
+    /// Handle a request without a WebSocket upgrade.
+    ///
+    /// # Arguments
+    /// * `method` - HTTP method of the request
+    /// * `path` - Path of the request, without query
+    /// * `body` - JSON body of the request, may be empty
+    ///
+    pub fn handle(&mut self, method: &str, path: &str, body: &[u8]) -> GatewayResponse {
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let result = match (method, segments.as_slice()) {
+            ("POST", ["channels"]) => self.create_channel(body),
+            ("POST", ["subscriptions"]) => self.create_subscription(body),
+            ("DELETE", ["users", id]) => match self.users.remove(*id) {
+                Some(_) => Ok(json!({})),
+                None => return GatewayResponse::error(404, "Unknown user."),
+            },
+            (_, ["users", id, ..]) if !self.users.contains_key(*id) => {
+                return GatewayResponse::error(404, "Unknown user.")
+            }
+            ("POST", ["users", id, "subscribers"]) => self.accept_subscriber(id, body),
+            ("POST", ["users", id, "keyloads"]) => self.send_keyload(id, body),
+            ("POST", ["users", id, "packets"]) => self.send_packet(id, body),
+            ("GET", ["users", id, "messages"]) => self.messages(id),
+            _ => return GatewayResponse::error(404, "Unknown route."),
+        };
+        match result {
+            Ok(value) => GatewayResponse::json(if method == "POST" { 201 } else { 200 }, value),
+            Err(e) => GatewayResponse::error(400, &e.to_string()),
+        }
+    }
+
+    fn create_channel(&mut self, body: &[u8]) -> Result<Value> {
+        let req: CreateChannel = if body.is_empty() {
+            CreateChannel::default()
+        } else {
+            parse_body(body)?
+        };
+        let seed = Seed::from_bytes(&prng::random_key())?;
+        let mut author = Author::new(
+            &seed,
+            "utf-8",
+            PAYLOAD_BYTES,
+            req.multi_branching,
+            self.transport.clone(),
+        );
+        let announcement = author.send_announce()?;
+        let pk = hex::encode(author.get_pk().as_bytes());
+        let id = self.new_user_id();
+        self.users.insert(id.clone(), Participant::Author(author));
+        Ok(json!({ "user": id, "pk": pk, "announcement": format_link(&announcement)? }))
+    }
+
+    fn create_subscription(&mut self, body: &[u8]) -> Result<Value> {
+        let req: CreateSubscription = parse_body(body)?;
+        let announcement = parse_link(&req.announcement)?;
+        let seed = Seed::from_bytes(&prng::random_key())?;
+        let mut subscriber = Subscriber::new(&seed, "utf-8", PAYLOAD_BYTES, self.transport.clone());
+        subscriber.receive_announcement(&announcement)?;
+        let subscribe = subscriber.send_subscribe(&announcement)?;
+        let pk = hex::encode(subscriber.get_pk().as_bytes());
+        let id = self.new_user_id();
+        self.users.insert(id.clone(), Participant::Subscriber(subscriber));
+        Ok(json!({ "user": id, "pk": pk, "subscribe": format_link(&subscribe)? }))
+    }
+
+    fn accept_subscriber(&mut self, id: &str, body: &[u8]) -> Result<Value> {
+        let req: AcceptSubscriber = parse_body(body)?;
+        let subscribe = parse_link(&req.subscribe)?;
+        match self.users.get_mut(id) {
+            Some(Participant::Author(author)) => author.receive_subscribe(&subscribe)?,
+            _ => return Err(anyhow!("User {} is not an author.", id)),
+        }
+        Ok(json!({}))
+    }
+
+    fn send_keyload(&mut self, id: &str, body: &[u8]) -> Result<Value> {
+        let req: SendKeyload = parse_body(body)?;
+        let link_to = parse_link(&req.link_to)?;
+        let (link, sequence) = match self.users.get_mut(id) {
+            Some(Participant::Author(author)) => author.send_keyload_for_everyone(&link_to)?,
+            Some(Participant::Subscriber(subscriber)) => subscriber.send_keyload_for_everyone(&link_to)?,
+            None => return Err(anyhow!("Unknown user: {}.", id)),
+        };
+        sent(&link, &sequence)
+    }
+
+    fn send_packet(&mut self, id: &str, body: &[u8]) -> Result<Value> {
+        let req: SendPacket = parse_body(body)?;
+        let link_to = parse_link(&req.link_to)?;
+        let public_payload = parse_payload(&req.public_payload)?;
+        let masked_payload = parse_payload(&req.masked_payload)?;
+        let (link, sequence) = match (self.users.get_mut(id), req.signed) {
+            (Some(Participant::Author(author)), true) => {
+                author.send_signed_packet(&link_to, &public_payload, &masked_payload)?
+            }
+            (Some(Participant::Author(author)), false) => {
+                author.send_tagged_packet(&link_to, &public_payload, &masked_payload)?
+            }
+            (Some(Participant::Subscriber(subscriber)), true) => {
+                subscriber.send_signed_packet(&link_to, &public_payload, &masked_payload)?
+            }
+            (Some(Participant::Subscriber(subscriber)), false) => {
+                subscriber.send_tagged_packet(&link_to, &public_payload, &masked_payload)?
+            }
+            (None, _) => return Err(anyhow!("Unknown user: {}.", id)),
+        };
+        sent(&link, &sequence)
+    }
+
+    fn messages(&mut self, id: &str) -> Result<Value> {
+        let user = self.users.get_mut(id).ok_or_else(|| anyhow!("Unknown user: {}.", id))?;
+        let envelopes = user
+            .sync_state()
+            .iter()
+            .map(|msg| msg.to_envelope())
+            .collect::<Result<Vec<_>>>()?;
+        serde_json::to_value(envelopes).map_err(|e| anyhow!("JSON error: {}", e))
+    }
+
+    /// Push new messages of the users with open streams to their WebSockets, closed sockets
+    /// are dropped.
+    pub fn poll_streams(&mut self) {
+        let mut ids: Vec<String> = self.streams.iter().map(|(id, _)| id.clone()).collect();
+        ids.sort();
+        ids.dedup();
+        for id in ids {
+            let frames: Vec<String> = match self.users.get_mut(&id) {
+                Some(user) => user.sync_state().iter().filter_map(|msg| msg.to_json().ok()).collect(),
+                None => {
+                    self.streams.retain(|(stream_id, _)| *stream_id != id);
+                    continue;
+                }
+            };
+            let mut i = 0;
+            while i < self.streams.len() {
+                let (stream_id, socket) = &mut self.streams[i];
+                let open = *stream_id != id
+                    || frames
+                        .iter()
+                        .all(|frame| socket.write_message(tungstenite::Message::Text(frame.clone())).is_ok());
+                if open {
+                    i += 1;
+                } else {
+                    self.streams.swap_remove(i);
+                }
+            }
+        }
+    }
+
+    /// Serve the gateway over HTTP. Failures to receive or respond to a request are logged
+    /// and the gateway keeps serving, only failing to listen on `addr` is returned.
+    ///
+    /// # Arguments
+    /// * `addr` - Socket address to listen on, eg. `127.0.0.1:8080`
+    /// * `poll_interval` - Interval of polling new messages of streamed users
+    ///
+    pub fn serve(&mut self, addr: &str, poll_interval: Duration) -> Result<()> {
+        let server = tiny_http::Server::http(addr).map_err(|e| anyhow!("Failed to listen on {}: {}", addr, e))?;
+        loop {
+            match server.recv_timeout(poll_interval) {
+                Ok(Some(request)) => {
+                    if let Err(e) = self.respond(request) {
+                        log::warn!("Failed to respond to request: {}", e);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("Failed to receive request: {}", e),
+            }
+            self.poll_streams();
+        }
+    }
+
+    fn respond(&mut self, mut request: tiny_http::Request) -> Result<()> {
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+        let path = url.split('?').next().unwrap_or("");
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        if let ["users", id, "messages", "stream"] = segments.as_slice() {
+            return self.upgrade(request, id);
+        }
+
+        let mut body = Vec::new();
+        let too_large = request.body_length().map_or(false, |len| len > MAX_REQUEST_BODY_SIZE) || {
+            request
+                .as_reader()
+                .take(MAX_REQUEST_BODY_SIZE as u64 + 1)
+                .read_to_end(&mut body)?;
+            body.len() > MAX_REQUEST_BODY_SIZE
+        };
+        let response = if too_large {
+            GatewayResponse::error(413, "Request body too large.")
+        } else {
+            self.handle(&method, path, &body)
+        };
+        let content_type = "Content-Type: application/json"
+            .parse::<tiny_http::Header>()
+            .map_err(|_| anyhow!("Bad header."))?;
+        request.respond(
+            tiny_http::Response::from_string(response.body)
+                .with_status_code(response.status)
+                .with_header(content_type),
+        )?;
+        Ok(())
+    }
+
+    fn upgrade(&mut self, request: tiny_http::Request, id: &str) -> Result<()> {
+        let key = request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv("Sec-WebSocket-Key"))
+            .map(|h| h.value.as_str().to_string());
+        let error = match key {
+            _ if request.method() != &tiny_http::Method::Get => Some(GatewayResponse::error(404, "Unknown route.")),
+            _ if !self.users.contains_key(id) => Some(GatewayResponse::error(404, "Unknown user.")),
+            None => Some(GatewayResponse::error(400, "WebSocket upgrade expected.")),
+            Some(_) => None,
+        };
+        if let Some(response) = error {
+            request.respond(tiny_http::Response::from_string(response.body).with_status_code(response.status))?;
+            return Ok(());
+        }
+
+        let headers = [
+            "Upgrade: websocket".to_string(),
+            "Connection: Upgrade".to_string(),
+            format!(
+                "Sec-WebSocket-Accept: {}",
+                derive_accept_key(key.unwrap_or_default().as_bytes())
+            ),
+        ];
+        let mut response = tiny_http::Response::new_empty(tiny_http::StatusCode(101));
+        for header in headers.iter() {
+            response = response.with_header(
+                header
+                    .parse::<tiny_http::Header>()
+                    .map_err(|_| anyhow!("Bad header: {}", header))?,
+            );
+        }
+        let socket = request.upgrade("websocket", response);
+        self.streams
+            .push((id.to_string(), WebSocket::from_raw_socket(socket, Role::Server, None)));
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+//! Message DAG of a channel, for visualizing branching structure when debugging sequencing.
+
+use anyhow::Result;
+use core::fmt::Write as _;
+
+use iota_streams_core::{
+    prelude::{
+        String,
+        ToString,
+        Vec,
+    },
+    sponge::prp::PRP,
+};
+use iota_streams_ddml::{
+    command::*,
+    io,
+    link_store::EmptyLinkStore,
+    types::*,
+};
+
+use super::*;
+
+/// Kind of reference between two messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// Message is linked to the referenced message.
+    Link,
+    /// Sequence message references the sequenced message.
+    Sequence,
+}
+
+/// Messages known to a user and references between them, see `User::export_graph`.
+#[derive(Clone, Default)]
+pub struct MessageGraph {
+    /// Message identifiers with message types.
+    pub nodes: Vec<(MsgId, MsgInfo)>,
+    /// References from a message to another one.
+    pub edges: Vec<(MsgId, MsgId, EdgeKind)>,
+}
+
+impl MessageGraph {
+    /// Graph in Graphviz DOT format, edges point from a message to the referenced one.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph channel {\n");
+        for (msgid, info) in self.nodes.iter() {
+            let _ = writeln!(dot, "    \"{}\" [label=\"{:?}\\n{}\"];", msgid, info, msgid);
+        }
+        for (from, to, kind) in self.edges.iter() {
+            let style = match kind {
+                EdgeKind::Link => "solid",
+                EdgeKind::Sequence => "dashed",
+            };
+            let _ = writeln!(dot, "    \"{}\" -> \"{}\" [style={}];", from, to, style);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Graph in JSON format:
+    /// `{"nodes":[{"id":..,"type":..}],"edges":[{"from":..,"to":..,"kind":..}]}`.
+    pub fn to_json(&self) -> String {
+        let nodes: Vec<String> = self
+            .nodes
+            .iter()
+            .map(|(msgid, info)| format!("{{\"id\":\"{}\",\"type\":\"{:?}\"}}", msgid, info))
+            .collect();
+        let edges: Vec<String> = self
+            .edges
+            .iter()
+            .map(|(from, to, kind)| format!("{{\"from\":\"{}\",\"to\":\"{}\",\"kind\":\"{:?}\"}}", from, to, kind))
+            .collect();
+        format!("{{\"nodes\":[{}],\"edges\":[{}]}}", nodes.join(","), edges.join(","))
+    }
+
+    pub(crate) fn sort(&mut self) {
+        self.nodes.sort_by_key(|(msgid, _)| msgid.to_string());
+        self.edges.sort_by_key(|(from, to, _)| (from.to_string(), to.to_string()));
+    }
+}
+
+/// Links found in plain text at the start of message content: the joined link of
+/// all messages but announcement, and the link referenced by sequence messages.
+struct References {
+    sequence: bool,
+    link: MsgId,
+    pk: PublicKey,
+    seq_num: Uint64,
+    ref_link: MsgId,
+}
+
+impl<F, Store> message::ContentUnwrap<F, Store> for References
+where
+    F: PRP,
+{
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        _store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        ctx.skip(<&mut Fallback<MsgId>>::from(&mut self.link))?;
+        if self.sequence {
+            ctx.absorb(&mut self.pk)?
+                .skip(&mut self.seq_num)?
+                .absorb(<&mut Fallback<MsgId>>::from(&mut self.ref_link))?;
+        }
+        Ok(ctx)
+    }
+}
+
+/// References of a message to other messages, the sequenced message link is generated with
+/// `link_gen`. Message contents are not authenticated.
+pub(crate) fn references(msg: &Message, link_gen: &LinkGen) -> Result<Vec<(MsgId, EdgeKind)>> {
+    let preparsed = msg.binary.parse_header()?;
+    let content_type = preparsed.content_type();
+    if content_type == crate::message::ANNOUNCE {
+        return Ok(Vec::new());
+    }
+
+    // Placeholders of the channel msgid width.
+    let msgid = msg.binary.link.msgid.clone();
+    let content = References {
+        sequence: content_type == crate::message::SEQUENCE,
+        link: msgid.clone(),
+        pk: PublicKey::default(),
+        seq_num: Uint64(0),
+        ref_link: msgid,
+    };
+    let store = EmptyLinkStore::<DefaultF, MsgId, ()>::default();
+    let refs = preparsed.unwrap(&store, content)?.pcf.content;
+
+    let mut edges = vec![(refs.link, EdgeKind::Link)];
+    if refs.sequence {
+        let sequenced = link_gen.link_from(&refs.pk, Cursor::new_at(&refs.ref_link, 0, refs.seq_num.0 as u32));
+        edges.push((sequenced.msgid, EdgeKind::Sequence));
+    }
+    Ok(edges)
+}
@@ -0,0 +1,36 @@
+//! Blocking iterator over channel history.
+
+use iota_streams_core::prelude::vec;
+
+use super::*;
+
+/// Iterator over messages of a channel from a given link to the current head, see
+/// `User::iter_from`.
+///
+/// Messages are fetched on demand one round (the next message of each publisher) at a time.
+/// Messages that could not be handled are skipped, the iteration ends when a round finds
+/// no new messages.
+pub struct History<'a, Trans> {
+    user: &'a mut User<Trans>,
+    pending: vec::IntoIter<UnwrappedMessage>,
+}
+
+impl<'a, Trans> History<'a, Trans> {
+    pub(crate) fn new(user: &'a mut User<Trans>, first: UnwrappedMessage) -> Self {
+        Self {
+            user,
+            pending: vec![first].into_iter(),
+        }
+    }
+}
+
+impl<'a, Trans: Transport> Iterator for History<'a, Trans> {
+    type Item = UnwrappedMessage;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.len() == 0 {
+            self.pending = self.user.fetch_next_msgs().into_iter();
+        }
+        self.pending.next()
+    }
+}
@@ -0,0 +1,20 @@
+//! Progress of the subscription handshake, see `Subscriber::join`.
+
+use super::Address;
+
+/// Stage reached by a subscriber joining a channel.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum JoinProgress {
+    /// Announcement processed and subscription sent at the address, no keyload including the
+    /// subscriber was received yet.
+    Subscribed(Address),
+    /// Keyload at the address includes the subscriber, the handshake is complete.
+    Joined(Address),
+}
+
+impl JoinProgress {
+    /// Whether the subscriber received a keyload including it.
+    pub fn is_joined(&self) -> bool {
+        matches!(self, JoinProgress::Joined(_))
+    }
+}
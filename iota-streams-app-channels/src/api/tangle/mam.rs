@@ -0,0 +1,131 @@
+//! Import of legacy MAM (Streams v0) channels.
+//!
+//! A MAM channel is a chain of messages, each found at the address derived from its root and
+//! pointing to the root of the next one. `migrate_mam_channel` follows the chain from a given
+//! root and republishes the payload of every message as a signed packet of a Streams channel,
+//! so the history of the MAM channel stays readable after MAM is retired:
+//!
+//! * the public payload is the MAM root of the message (81 trytes), recording its provenance;
+//! * the masked payload is the decoded MAM payload, readable by the subscribers of the branch
+//!   the packets are linked to (eg. a keyload for restricted MAM channels).
+//!
+//! Fetching and decoding of MAM messages (Curl-P masking with the side key and Merkle tree
+//! signatures) is done by a `MamReader`, eg. wrapping a MAM client library; `BucketMamReader`
+//! serves decoded messages from memory.
+
+use anyhow::{
+    ensure,
+    Result,
+};
+
+use super::*;
+use iota_streams_core::prelude::{
+    HashMap,
+    HashSet,
+    String,
+    ToString,
+    Vec,
+};
+
+/// Length of MAM roots in trytes.
+pub const MAM_ROOT_TRYTES: usize = 81;
+
+/// Decoded message of a MAM channel.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MamMessage {
+    /// Root of the message
+    pub root: String,
+    /// Decoded payload
+    pub payload: Vec<u8>,
+    /// Root of the next message of the channel
+    pub next_root: String,
+}
+
+/// Reader of MAM channels.
+pub trait MamReader {
+    /// Fetch, unmask with `side_key` (restricted mode) and verify the message at `root`, `None`
+    /// if there is no message at `root` yet, ie. the end of the channel.
+    fn read(&mut self, root: &str, side_key: Option<&str>) -> Result<Option<MamMessage>>;
+}
+
+/// In-memory MAM reader of already decoded messages, keyed by root.
+#[derive(Default)]
+pub struct BucketMamReader {
+    messages: HashMap<String, MamMessage>,
+}
+
+impl BucketMamReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a decoded message.
+    pub fn insert(&mut self, message: MamMessage) {
+        self.messages.insert(message.root.clone(), message);
+    }
+}
+
+impl MamReader for BucketMamReader {
+    fn read(&mut self, root: &str, _side_key: Option<&str>) -> Result<Option<MamMessage>> {
+        Ok(self.messages.get(root).cloned())
+    }
+}
+
+/// Outcome of a migration of a MAM channel.
+#[derive(Clone, Debug)]
+pub struct MamMigration {
+    /// Links of the republished packets, in the order of the MAM channel
+    pub links: Vec<Address>,
+    /// Root of the next (not yet published) MAM message, continue the migration from it
+    pub next_root: String,
+}
+
+fn is_trytes(s: &str) -> bool {
+    s.bytes().all(|c| c == b'9' || (b'A'..=b'Z').contains(&c))
+}
+
+/// Republish the messages of the MAM channel starting at `root` as signed packets of `author`'s
+/// channel. The first packet is linked to `link_to`, each next packet to the previous one.
+///
+///  # Arguments
+///  * `reader` - Reader of the MAM channel
+///  * `root` - Root of the first MAM message to migrate
+///  * `side_key` - Side key of a restricted MAM channel, `None` for public and private channels
+///  * `author` - Author of the Streams channel the messages are republished in
+///  * `link_to` - Address of the announcement or keyload the packets are attached to
+///
+pub fn migrate_mam_channel<R: MamReader, Trans: Transport>(
+    reader: &mut R,
+    root: &str,
+    side_key: Option<&str>,
+    author: &mut Author<Trans>,
+    link_to: &Address,
+) -> Result<MamMigration> {
+    ensure!(
+        root.len() == MAM_ROOT_TRYTES && is_trytes(root),
+        "Bad MAM root: {}.",
+        root
+    );
+    if let Some(side_key) = side_key {
+        ensure!(
+            !side_key.is_empty() && side_key.len() <= MAM_ROOT_TRYTES && is_trytes(side_key),
+            "Bad MAM side key."
+        );
+    }
+
+    let mut links = Vec::new();
+    let mut seen = HashSet::new();
+    let mut root = root.to_string();
+    let mut prev = link_to.clone();
+    while let Some(msg) = reader.read(&root, side_key)? {
+        ensure!(msg.root == root, "MAM message root differs: {}.", msg.root);
+        ensure!(seen.insert(root.clone()), "MAM channel loops at root {}.", root);
+        let public_payload = Bytes(msg.root.into_bytes());
+        let masked_payload = Bytes(msg.payload);
+        let (link, _) = author.send_signed_packet(&prev, &public_payload, &masked_payload)?;
+        links.push(link.clone());
+        prev = link;
+        root = msg.next_root;
+    }
+    Ok(MamMigration { links, next_root: root })
+}
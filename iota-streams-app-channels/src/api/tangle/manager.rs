@@ -0,0 +1,208 @@
+//! Manager of many channels sharing one transport.
+
+use anyhow::{
+    anyhow,
+    ensure,
+    Result,
+};
+
+use iota_streams_app::transport::tangle::PAYLOAD_BYTES;
+use iota_streams_core::prelude::{
+    HashMap,
+    Vec,
+};
+
+use super::*;
+
+const ROLE_AUTHOR: u8 = 0;
+const ROLE_SUBSCRIBER: u8 = 1;
+
+/// Role of the managed user in a channel.
+pub enum Participant<Trans> {
+    Author(Author<Trans>),
+    Subscriber(Subscriber<Trans>),
+}
+
+impl<Trans: Transport> Participant<Trans> {
+    pub(crate) fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
+        match self {
+            Participant::Author(a) => a.fetch_next_msgs(),
+            Participant::Subscriber(s) => s.fetch_next_msgs(),
+        }
+    }
+
+    pub(crate) fn sync_state(&mut self) -> Vec<UnwrappedMessage> {
+        match self {
+            Participant::Author(a) => a.sync_state(),
+            Participant::Subscriber(s) => s.sync_state(),
+        }
+    }
+
+    fn export(&self, pwd: &str) -> Result<(u8, Vec<u8>)> {
+        match self {
+            Participant::Author(a) => Ok((ROLE_AUTHOR, a.export(pwd)?)),
+            Participant::Subscriber(s) => Ok((ROLE_SUBSCRIBER, s.export(pwd)?)),
+        }
+    }
+}
+
+/// Owner of Author and Subscriber instances of many channels, keyed by channel address.
+/// All of them share the manager's transport.
+pub struct ChannelManager<Trans> {
+    transport: Trans,
+    channels: HashMap<ChannelAddress, Participant<Trans>>,
+}
+
+impl<Trans: Transport + Clone> ChannelManager<Trans> {
+    pub fn new(transport: Trans) -> Self {
+        Self {
+            transport,
+            channels: HashMap::new(),
+        }
+    }
+
+    /// Shared transport instance.
+    pub fn transport(&self) -> &Trans {
+        &self.transport
+    }
+
+    /// Create a new channel and send its announcement.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed of the author
+    /// * `multi_branching` - Boolean representing use of multi-branch or single-branch sequencing
+    ///
+    pub fn create_channel(&mut self, seed: &Seed, multi_branching: bool) -> Result<Address> {
+        let mut author = Author::new(seed, "utf-8", PAYLOAD_BYTES, multi_branching, self.transport.clone());
+        let announcement_link = author.send_announce()?;
+        self.insert_author(author)?;
+        Ok(announcement_link)
+    }
+
+    /// Join a channel as a subscriber by processing its announcement.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed of the subscriber
+    /// * `announcement_link` - Address of the channel announcement
+    ///
+    pub fn join_channel(&mut self, seed: &Seed, announcement_link: &Address) -> Result<()> {
+        let mut subscriber = Subscriber::new(seed, "utf-8", PAYLOAD_BYTES, self.transport.clone());
+        subscriber.receive_announcement(announcement_link)?;
+        self.insert_subscriber(subscriber)
+    }
+
+    /// Manage an existing author, the channel must be created and not managed yet.
+    pub fn insert_author(&mut self, author: Author<Trans>) -> Result<()> {
+        let appinst = author
+            .channel_address()
+            .ok_or_else(|| anyhow!("No channel created."))?
+            .clone();
+        ensure!(!self.channels.contains_key(&appinst), "Channel {} is already managed.", appinst);
+        self.channels.insert(appinst, Participant::Author(author));
+        Ok(())
+    }
+
+    /// Manage an existing subscriber, the announcement must be processed and the channel not managed yet.
+    pub fn insert_subscriber(&mut self, subscriber: Subscriber<Trans>) -> Result<()> {
+        let appinst = subscriber
+            .channel_address()
+            .ok_or_else(|| anyhow!("No channel registered."))?
+            .clone();
+        ensure!(!self.channels.contains_key(&appinst), "Channel {} is already managed.", appinst);
+        self.channels.insert(appinst, Participant::Subscriber(subscriber));
+        Ok(())
+    }
+
+    /// Stop managing the channel and return its participant.
+    pub fn remove(&mut self, appinst: &ChannelAddress) -> Option<Participant<Trans>> {
+        self.channels.remove(appinst)
+    }
+
+    pub fn channels(&self) -> Vec<&ChannelAddress> {
+        self.channels.keys().collect()
+    }
+
+    pub fn get(&self, appinst: &ChannelAddress) -> Option<&Participant<Trans>> {
+        self.channels.get(appinst)
+    }
+
+    pub fn author_mut(&mut self, appinst: &ChannelAddress) -> Option<&mut Author<Trans>> {
+        match self.channels.get_mut(appinst) {
+            Some(Participant::Author(a)) => Some(a),
+            _ => None,
+        }
+    }
+
+    pub fn subscriber_mut(&mut self, appinst: &ChannelAddress) -> Option<&mut Subscriber<Trans>> {
+        match self.channels.get_mut(appinst) {
+            Some(Participant::Subscriber(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the next message of each publisher in each managed channel.
+    pub fn fetch_next_msgs(&mut self) -> Vec<(ChannelAddress, UnwrappedMessage)> {
+        let mut msgs = Vec::new();
+        for (appinst, participant) in self.channels.iter_mut() {
+            msgs.extend(participant.fetch_next_msgs().into_iter().map(|m| (appinst.clone(), m)));
+        }
+        msgs
+    }
+
+    /// Retrieves all new messages in all managed channels.
+    pub fn sync_state(&mut self) -> Vec<(ChannelAddress, UnwrappedMessage)> {
+        let mut msgs = Vec::new();
+        for (appinst, participant) in self.channels.iter_mut() {
+            msgs.extend(participant.sync_state().into_iter().map(|m| (appinst.clone(), m)));
+        }
+        msgs
+    }
+
+    /// Serialize states of all managed users, each encrypted with password.
+    pub fn export(&self, pwd: &str) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(self.channels.len() as u32).to_be_bytes());
+        for participant in self.channels.values() {
+            let (role, state) = participant.export(pwd)?;
+            bytes.push(role);
+            bytes.extend_from_slice(&(state.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(&state);
+        }
+        Ok(bytes)
+    }
+
+    /// Deserialize states of managed users exported with `export`. States of hybrid channel
+    /// authors are refused, their MSS index can't be checked here: import them with
+    /// `Author::import_hybrid` and insert them with `insert_author`.
+    pub fn import(bytes: &[u8], pwd: &str, transport: Trans) -> Result<Self> {
+        fn read_u32(bytes: &[u8], pos: &mut usize) -> Result<usize> {
+            ensure!(bytes.len() >= *pos + 4, "Manager state is truncated.");
+            let mut n = [0_u8; 4];
+            n.copy_from_slice(&bytes[*pos..*pos + 4]);
+            *pos += 4;
+            Ok(u32::from_be_bytes(n) as usize)
+        }
+
+        let mut manager = Self::new(transport);
+        let mut pos = 0;
+        let count = read_u32(bytes, &mut pos)?;
+        for _ in 0..count {
+            ensure!(bytes.len() > pos, "Manager state is truncated.");
+            let role = bytes[pos];
+            pos += 1;
+            let len = read_u32(bytes, &mut pos)?;
+            ensure!(bytes.len() >= pos + len, "Manager state is truncated.");
+            let state = &bytes[pos..pos + len];
+            pos += len;
+            match role {
+                ROLE_AUTHOR => manager.insert_author(Author::import(state, pwd, manager.transport.clone())?)?,
+                ROLE_SUBSCRIBER => {
+                    manager.insert_subscriber(Subscriber::import(state, pwd, manager.transport.clone())?)?
+                }
+                _ => return Err(anyhow!("Bad role in manager state: {}.", role)),
+            }
+        }
+        ensure!(pos == bytes.len(), "Trailing bytes in manager state.");
+        Ok(manager)
+    }
+}
@@ -4,6 +4,28 @@ use super::{
     pk_store::PublicKeyMap,
     psk_store::PresharedKeyMap,
 };
+pub use super::branch_policy::BranchPolicy;
+pub use super::footprint::MemoryFootprint;
+pub use super::key_tree::{
+    KeyTree,
+    MemberKeys,
+    TreeUpdate,
+    TreeWelcome,
+};
+pub use super::lineage::Authentication;
+pub use super::rate_limit::{
+    RateAction,
+    RatePolicy,
+};
+pub use super::revocation::{
+    AccessWindow,
+    GraceWindow,
+    Revocation,
+};
+pub use super::sequence_policy::{
+    SequencePolicy,
+    SequenceViolation,
+};
 use iota_streams_app::{
     message,
     transport::{
@@ -18,13 +40,34 @@ use iota_streams_app::{
     },
 };
 
-pub use message::Cursor;
-// Bring trait methods into scope publicly.
-pub use message::LinkGenerator as _;
+pub use message::{
+    Cursor,
+    EmbargoId,
+    EmbargoRelease,
+    EmbargoedPayload,
+    FieldCommitment,
+    FieldCommitments,
+    FieldOpening,
+    FieldSalt,
+    HasLink,
+    LinkGenerator,
+    Milestone,
+    OffloadedPayload,
+};
 pub use transport::{
+    BlobStore,
+    BucketBlobStore,
     Transport as _,
     TransportOptions as _,
 };
+/// Hash functions of message identifiers a channel can be created with.
+pub use transport::tangle::MsgIdHash;
+
+/// Versions and errors of exported user states.
+pub use crate::api::state::{
+    StateError,
+    STATE_VERSION,
+};
 
 use iota_streams_core::psk;
 use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
@@ -32,6 +75,12 @@ use iota_streams_ddml::link_store::DefaultLinkStore;
 pub use iota_streams_ddml::types::Bytes;
 
 use iota_streams_core_edsig::signature::ed25519;
+/// Threshold signatures of channels authored by a group.
+pub use iota_streams_core_edsig::signature::frost;
+/// Secp256k1 co-signatures of signed packets.
+#[cfg(feature = "secp256k1")]
+pub use iota_streams_core_edsig::signature::secp256k1;
+pub use crate::message::signer::threshold_signature_context;
 
 /// Default spongos PRP.
 pub type DefaultF = KeccakF1600;
@@ -39,6 +88,30 @@ pub type DefaultF = KeccakF1600;
 /// Identifiers for Pre-Shared Keys
 pub type PskIds = psk::PskIds;
 
+/// Identifier of a Pre-Shared Key
+pub type PskId = psk::PskId;
+
+/// Pre-Shared Key
+pub type Psk = psk::Psk;
+
+/// Derive the identifier of a Pre-Shared Key, see `psk::pskid_from_psk`
+pub fn pskid_from_psk(psk: &Psk) -> PskId {
+    psk::pskid_from_psk::<DefaultF>(psk)
+}
+
+/// Validated user seed
+pub use iota_streams_core::seed::Seed;
+
+/// Fingerprint of a secret key, printed instead of the key by `Debug` implementations
+pub use iota_streams_core::fingerprint::Fingerprint;
+
+/// Argon2id parameters of passphrase-derived Pre-Shared Keys
+pub use psk::PassphraseParams;
+
+/// Derive a Pre-Shared Key from a passphrase
+#[cfg(feature = "passphrase")]
+pub use psk::from_passphrase as psk_from_passphrase;
+
 /// Tangle Address Link type.
 pub type Address = TangleAddress;
 /// Tangle Address representing Channel Application Instance.
@@ -57,10 +130,21 @@ pub type WrappedSequence = super::user::WrappedSequence<DefaultF, Address>;
 pub type WrapStateSequence = super::user::WrapStateSequence<DefaultF, Address>;
 /// Ed25519 Public Key
 pub type PublicKey = ed25519::PublicKey;
+/// Ed25519 Signature
+pub type Signature = ed25519::Signature;
+/// Masked payload of a lazily unwrapped packet
+pub type MaskedPayload = iota_streams_ddml::types::MaskedBytes<DefaultF>;
+/// Verified message of a lineage
+pub type LineageStep = super::lineage::LineageStep<Address>;
+/// Chain of verified messages back to the channel announcement
+pub type LineageProof = super::lineage::LineageProof<Address>;
 
 /// Message type with parsed header.
 pub type Preparsed<'a> = message::PreparsedMessage<'a, DefaultF, Address>;
 
+/// Message header type.
+pub type Header = message::hdf::HDF<Address>;
+
 /// Sequence State information
 pub type SeqState = Cursor<MsgId>;
 /// Public Key Mapping for sequence states
@@ -98,9 +182,33 @@ pub enum MessageContent {
         public_payload: Bytes,
         masked_payload: Bytes,
     },
+    PrivatePacket {
+        pk: PublicKey,
+        public_payload: Bytes,
+        masked_payload: Bytes,
+        private_payload: Option<Bytes>,
+    },
+    BranchDelegation {
+        delegate_pk: PublicKey,
+    },
+    /// Checkpoint of the sequencing state of `publishers` publishers.
+    Checkpoint {
+        publishers: usize,
+    },
     Sequence,
     Subscribe,
     Unsubscribe,
+    /// Signed (with `pk`) or tagged packet with the masked payload not decrypted yet,
+    /// see `User::set_lazy_payloads`.
+    LazyPacket {
+        pk: Option<PublicKey>,
+        public_payload: Bytes,
+        masked_payload: MaskedPayload,
+    },
+    /// Packet skipped without unwrapping, rejected by the fetch filter, see `User::set_fetch_filter`.
+    Filtered {
+        content_type: u8,
+    },
 }
 
 impl MessageContent {
@@ -126,16 +234,141 @@ impl MessageContent {
             masked_payload,
         }
     }
+
+    pub fn new_private_packet(
+        pk: PublicKey,
+        public_payload: Bytes,
+        masked_payload: Bytes,
+        private_payload: Option<Bytes>,
+    ) -> Self {
+        Self::PrivatePacket {
+            pk,
+            public_payload,
+            masked_payload,
+            private_payload,
+        }
+    }
+
+    pub fn new_branch_delegation(delegate_pk: PublicKey) -> Self {
+        Self::BranchDelegation { delegate_pk }
+    }
+
+    pub fn new_checkpoint(publishers: usize) -> Self {
+        Self::Checkpoint { publishers }
+    }
+
+    pub fn new_filtered(content_type: u8) -> Self {
+        Self::Filtered { content_type }
+    }
+
+    /// Signed (with `pk`) or tagged packet, lazy if the masked payload was kept encrypted.
+    pub(crate) fn new_packet(pk: Option<PublicKey>, public_payload: Bytes, masked_payload: MaskedPayload) -> Self {
+        if masked_payload.is_deferred() {
+            return Self::LazyPacket {
+                pk,
+                public_payload,
+                masked_payload,
+            };
+        }
+        match pk {
+            Some(pk) => Self::new_signed_packet(pk, public_payload, masked_payload.into_bytes()),
+            None => Self::new_tagged_packet(public_payload, masked_payload.into_bytes()),
+        }
+    }
 }
 
 /// Generic unwrapped message type containing possible message contents
 pub type UnwrappedMessage = message::GenericMessage<Address, MessageContent>;
 
+/// Payload access for unwrapped messages.
+pub trait PacketPayloads {
+    /// Masked payload of a packet, decrypted on first access for lazy packets.
+    fn masked_payload(&self) -> anyhow::Result<Bytes>;
+}
+
+impl PacketPayloads for UnwrappedMessage {
+    fn masked_payload(&self) -> anyhow::Result<Bytes> {
+        match &self.body {
+            MessageContent::SignedPacket { masked_payload, .. }
+            | MessageContent::TaggedPacket { masked_payload, .. }
+            | MessageContent::PrivatePacket { masked_payload, .. } => Ok(masked_payload.clone()),
+            MessageContent::LazyPacket { masked_payload, .. } => Ok(masked_payload.decrypt()),
+            _ => Err(anyhow::anyhow!("Message {} is not a packet", self.link)),
+        }
+    }
+}
+
+mod events;
+/// Callbacks notified of protocol events processed by a user.
+pub use events::UserEvents;
+
 #[cfg(not(feature = "async"))]
 mod user;
-/// User object storing the Auth/Sub implementation as well as the transport instance
+/// User object storing the Auth/Sub implementation as well as the transport instance,
+/// acting in the role of its registered channel
+#[cfg(not(feature = "async"))]
+pub use user::{
+    Embargo,
+    PendingSubscription,
+    Role,
+    User,
+};
+
+#[cfg(not(feature = "async"))]
+mod channel_state;
+
+mod graph;
+/// Message DAG of a channel.
+pub use graph::{
+    EdgeKind,
+    MessageGraph,
+};
+
+mod directory;
+/// Directory of the named branches of a channel.
+pub use directory::{
+    Directory,
+    DirectoryEntry,
+    DIRECTORY_VERSION,
+    MAX_DIRECTORY_ENTRIES,
+};
+
+#[cfg(not(feature = "async"))]
+mod notarize;
+/// Notarization of sent messages in external systems.
+#[cfg(not(feature = "async"))]
+pub use notarize::{
+    message_hash,
+    MessageHash,
+    NotarizationRecord,
+    Notarizer,
+    MESSAGE_HASH_SIZE,
+};
+#[cfg(all(not(feature = "async"), feature = "std"))]
+pub use notarize::FileNotarizer;
+
+#[cfg(all(not(feature = "async"), feature = "sync-client"))]
+mod proof;
+/// Inclusion proofs of messages verifiable offline.
+#[cfg(all(not(feature = "async"), feature = "sync-client"))]
+pub use proof::{
+    AttachProof,
+    InclusionProof,
+    InclusionProofs,
+    ProvenMessage,
+};
+
+#[cfg(not(feature = "async"))]
+mod history;
+/// Blocking iterator over channel history.
 #[cfg(not(feature = "async"))]
-pub use user::User;
+pub use history::History;
+
+#[cfg(all(not(feature = "async"), feature = "stream"))]
+mod stream;
+/// Asynchronous stream of messages retrieved by a user.
+#[cfg(all(not(feature = "async"), feature = "stream"))]
+pub use stream::Messages;
 
 #[cfg(not(feature = "async"))]
 mod author;
@@ -143,11 +376,84 @@ mod author;
 #[cfg(not(feature = "async"))]
 pub use author::Author;
 
+mod join;
+/// Progress of a subscriber joining a channel.
+pub use join::JoinProgress;
+
 #[cfg(not(feature = "async"))]
 mod subscriber;
 /// Tangle-specific Channel Subscriber type.
 #[cfg(not(feature = "async"))]
 pub use subscriber::Subscriber;
 
+mod config;
+/// Channel parameters shared by the author and subscribers.
+pub use config::{
+    ChannelConfig,
+    PrpKind,
+};
+
+#[cfg(feature = "envelope")]
+mod envelope;
+/// Canonical JSON and CBOR representation of unwrapped messages.
+#[cfg(feature = "envelope")]
+pub use envelope::{
+    Envelope,
+    ToEnvelope,
+    ENVELOPE_VERSION,
+};
+
+#[cfg(all(not(feature = "async"), feature = "gateway"))]
+mod gateway;
+/// REST and WebSocket gateway to channels.
+#[cfg(all(not(feature = "async"), feature = "gateway"))]
+pub use gateway::{
+    Gateway,
+    GatewayResponse,
+};
+
+/// Protobuf messages of gateways, see `proto/streams.proto`, and converters.
+#[cfg(feature = "proto")]
+pub mod proto;
+
+#[cfg(not(feature = "async"))]
+mod builder;
+/// Builders of Author and Subscriber instances from named options.
+#[cfg(not(feature = "async"))]
+pub use builder::{
+    AuthorBuilder,
+    SubscriberBuilder,
+};
+
+#[cfg(not(feature = "async"))]
+mod manager;
+/// Manager of Authors and Subscribers of many channels sharing a transport.
+#[cfg(not(feature = "async"))]
+pub use manager::{
+    ChannelManager,
+    Participant,
+};
+
+#[cfg(all(not(feature = "async"), feature = "mam"))]
+mod mam;
+/// Import of legacy MAM channels.
+#[cfg(all(not(feature = "async"), feature = "mam"))]
+pub use mam::{
+    migrate_mam_channel,
+    BucketMamReader,
+    MamMessage,
+    MamMigration,
+    MamReader,
+    MAM_ROOT_TRYTES,
+};
+
+/// Deterministic simulation of scripted scenarios.
+#[cfg(not(feature = "async"))]
+pub mod simulation;
+
+/// Deterministic test vectors of wrapped messages for compatibility checks of other implementations.
+#[cfg(all(not(feature = "async"), feature = "test-vectors"))]
+pub mod test_vectors;
+
 #[cfg(not(feature = "async"))]
 pub mod test;
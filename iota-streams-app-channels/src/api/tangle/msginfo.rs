@@ -9,7 +9,7 @@ use iota_streams_ddml::{
 };
 
 /// Message associated info stored internally in User context, just message type indicator.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum MsgInfo {
     Announce,
     Keyload,
@@ -18,6 +18,10 @@ pub enum MsgInfo {
     Subscribe,
     Unsubscribe,
     Sequence,
+    PrivatePacket,
+    BranchDelegation,
+    Checkpoint,
+    DeniablePacket,
 }
 
 // Default instance is required by ddml unwrap logic as unwrap modifies/updates an existing object rather producing a new one.
@@ -37,6 +41,10 @@ impl From<MsgInfo> for u8 {
             MsgInfo::Subscribe => 4,
             MsgInfo::Unsubscribe => 5,
             MsgInfo::Sequence => 6,
+            MsgInfo::PrivatePacket => 7,
+            MsgInfo::BranchDelegation => 8,
+            MsgInfo::Checkpoint => 9,
+            MsgInfo::DeniablePacket => 10,
         }
     }
 }
@@ -58,6 +66,10 @@ impl TryFrom<u8> for MsgInfo {
             4 => Ok(MsgInfo::Subscribe),
             5 => Ok(MsgInfo::Unsubscribe),
             6 => Ok(MsgInfo::Sequence),
+            7 => Ok(MsgInfo::PrivatePacket),
+            8 => Ok(MsgInfo::BranchDelegation),
+            9 => Ok(MsgInfo::Checkpoint),
+            10 => Ok(MsgInfo::DeniablePacket),
             _ => Err(()),
         }
     }
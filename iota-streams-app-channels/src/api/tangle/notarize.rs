@@ -0,0 +1,144 @@
+//! Notarization of sent messages in external systems.
+//!
+//! A `Notarizer` registered with a user is invoked after every successful send with the link of
+//! the message, the hash of its binary encoding and the milestone confirming it if the transport
+//! reports one. Deployments anchor channel heads with it, eg. into a database or another ledger,
+//! so that the channel can be cross-audited against the anchors. `FileNotarizer` appends the
+//! notarizations to a text file, one record per line:
+//!
+//! ```text
+//! <appinst hex>:<msgid hex> <message hash hex> <milestone index or -> <milestone timestamp or ->
+//! ```
+
+use anyhow::{
+    anyhow,
+    ensure,
+    Result,
+};
+
+use super::*;
+use iota_streams_core::{
+    format,
+    prelude::{
+        String,
+        ToString,
+        Vec,
+    },
+    sponge::spongos::hash_data,
+};
+
+/// Size of message hashes in bytes.
+pub const MESSAGE_HASH_SIZE: usize = 32;
+
+/// Hash of the binary encoding of a message.
+pub type MessageHash = [u8; MESSAGE_HASH_SIZE];
+
+/// Hash of the binary encoding of `msg` with spongos over `DefaultF`.
+pub fn message_hash(msg: &Message) -> MessageHash {
+    let mut hash = [0_u8; MESSAGE_HASH_SIZE];
+    hash_data::<DefaultF>(&msg.binary.body.bytes, &mut hash);
+    hash
+}
+
+/// Hook anchoring sent messages into an external system.
+pub trait Notarizer {
+    /// Notarize the message sent at `link`. Errors are logged, the message is already sent.
+    fn notarize(&mut self, link: &Address, hash: &MessageHash, milestone: Option<&Milestone>) -> Result<()>;
+}
+
+/// Notarization of a message as recorded by `FileNotarizer`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NotarizationRecord {
+    /// Address of the message
+    pub link: Address,
+    /// Hash of the binary encoding of the message
+    pub hash: MessageHash,
+    /// Milestone confirming the message, if known when sent
+    pub milestone: Option<Milestone>,
+}
+
+impl NotarizationRecord {
+    /// Text line of the record, see the module docs.
+    pub fn to_line(&self) -> String {
+        let (index, timestamp) = match self.milestone {
+            Some(ref milestone) => (milestone.index.to_string(), milestone.timestamp.to_string()),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        format!(
+            "{}:{} {} {} {}",
+            self.link.appinst,
+            self.link.msgid,
+            hex::encode(&self.hash),
+            index,
+            timestamp
+        )
+    }
+
+    /// Parse a text line of a record.
+    pub fn from_line(line: &str) -> Result<Self> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        ensure!(fields.len() == 4, "Bad notarization record: {}.", line);
+        let (appinst, msgid) = fields[0]
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Bad notarization link: {}.", fields[0]))?;
+        let link =
+            Address::from_str(appinst, msgid).map_err(|_| anyhow!("Bad notarization link: {}.", fields[0]))?;
+        let hash = hex::decode(fields[1]).map_err(|e| anyhow!("Bad notarization hash: {}.", e))?;
+        ensure!(hash.len() == MESSAGE_HASH_SIZE, "Bad notarization hash length: {}.", hash.len());
+        let milestone = match (fields[2], fields[3]) {
+            ("-", "-") => None,
+            (index, timestamp) => Some(Milestone {
+                index: index.parse().map_err(|_| anyhow!("Bad milestone index: {}.", index))?,
+                timestamp: timestamp
+                    .parse()
+                    .map_err(|_| anyhow!("Bad milestone timestamp: {}.", timestamp))?,
+            }),
+        };
+        let mut record = Self {
+            link,
+            hash: [0_u8; MESSAGE_HASH_SIZE],
+            milestone,
+        };
+        record.hash.copy_from_slice(&hash);
+        Ok(record)
+    }
+}
+
+/// Notarizer appending records to a text file.
+#[cfg(feature = "std")]
+pub struct FileNotarizer {
+    file: std::fs::File,
+}
+
+#[cfg(feature = "std")]
+impl FileNotarizer {
+    /// Open the file at `path` for appending records, create it if it doesn't exist.
+    pub fn new<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file })
+    }
+
+    /// Read the records of the file at `path`, eg. to audit a channel against them.
+    pub fn read_records<P: AsRef<std::path::Path>>(path: P) -> Result<Vec<NotarizationRecord>> {
+        std::fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(NotarizationRecord::from_line)
+            .collect()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Notarizer for FileNotarizer {
+    fn notarize(&mut self, link: &Address, hash: &MessageHash, milestone: Option<&Milestone>) -> Result<()> {
+        use std::io::Write;
+        let record = NotarizationRecord {
+            link: link.clone(),
+            hash: *hash,
+            milestone: milestone.cloned(),
+        };
+        writeln!(self.file, "{}", record.to_line())?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
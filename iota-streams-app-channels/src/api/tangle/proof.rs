@@ -0,0 +1,64 @@
+//! Messages handed to auditors together with their inclusion proofs.
+//!
+//! The tangle client fetches the inclusion proof of a message with `fetch_inclusion_proof`: the
+//! transactions carrying the message and the milestone referencing them with its bundle.
+//! A received message with its proof attached is a `ProvenMessage`; an auditor verifies offline
+//! that the transactions carry the very message at its link, see `InclusionProof::verify`.
+
+use anyhow::{
+    ensure,
+    Result,
+};
+
+use super::*;
+pub use iota_streams_app::transport::tangle::client::{
+    InclusionProof,
+    InclusionProofs,
+};
+
+/// Unwrapped message with the inclusion proof of its binary encoding.
+pub struct ProvenMessage {
+    /// Unwrapped message
+    pub message: UnwrappedMessage,
+    /// Inclusion proof of the message
+    pub proof: InclusionProof,
+}
+
+impl ProvenMessage {
+    /// Verify the proof and return the binary message it carries, the message is unwrapped from
+    /// it by the auditor's user.
+    ///
+    /// # Arguments
+    /// * `min_weight_magnitude` - Proof-of-work the transactions must carry
+    pub fn verify(&self, min_weight_magnitude: u8) -> Result<Message> {
+        ensure!(
+            self.proof.link == self.message.link,
+            "Inclusion proof is of another message: {}.",
+            self.proof.link
+        );
+        let msg = self.proof.verify(min_weight_magnitude)?;
+        ensure!(
+            msg.binary.link == self.message.link,
+            "Inclusion proof carries another message: {}.",
+            msg.binary.link
+        );
+        Ok(msg)
+    }
+}
+
+/// Attachment of inclusion proofs to unwrapped messages.
+pub trait AttachProof {
+    /// Attach the inclusion `proof` of the message, it must be of the message link.
+    fn attach_proof(self, proof: InclusionProof) -> Result<ProvenMessage>;
+}
+
+impl AttachProof for UnwrappedMessage {
+    fn attach_proof(self, proof: InclusionProof) -> Result<ProvenMessage> {
+        ensure!(
+            proof.link == self.link,
+            "Inclusion proof is of another message: {}.",
+            proof.link
+        );
+        Ok(ProvenMessage { message: self, proof })
+    }
+}
@@ -0,0 +1,347 @@
+//! Protobuf messages of `proto/streams.proto` and converters from and to the crate types.
+//!
+//! gRPC gateways encode the `pb` messages with `prost::Message`. Conversions into the crate
+//! types validate sizes of addresses and keys; conversions of unwrapped messages decrypt lazy
+//! packets and are fallible too. The `pb` messages must be kept in sync with the `.proto` file.
+
+use anyhow::{
+    anyhow,
+    ensure,
+    Error,
+    Result,
+};
+use core::convert::TryFrom;
+
+use super::*;
+use iota_streams_app::{
+    message::Milestone,
+    transport::tangle::{
+        APPINST_SIZE,
+        MSGID_SIZES,
+    },
+};
+use iota_streams_core::prelude::Vec;
+
+/// Messages of package `iota.streams.v1`.
+pub mod pb {
+    use iota_streams_core::prelude::Vec;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ChannelAddress {
+        #[prost(bytes = "vec", tag = "1")]
+        pub appinst: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MessageLink {
+        #[prost(bytes = "vec", tag = "1")]
+        pub appinst: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        pub msgid: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Milestone {
+        #[prost(uint32, tag = "1")]
+        pub index: u32,
+        #[prost(uint64, tag = "2")]
+        pub timestamp: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Announce {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Keyload {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SignedPacket {
+        #[prost(bytes = "vec", tag = "1")]
+        pub pk: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        pub public_payload: Vec<u8>,
+        #[prost(bytes = "vec", tag = "3")]
+        pub masked_payload: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TaggedPacket {
+        #[prost(bytes = "vec", tag = "1")]
+        pub public_payload: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        pub masked_payload: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct PrivatePacket {
+        #[prost(bytes = "vec", tag = "1")]
+        pub pk: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        pub public_payload: Vec<u8>,
+        #[prost(bytes = "vec", tag = "3")]
+        pub masked_payload: Vec<u8>,
+        #[prost(bytes = "vec", optional, tag = "4")]
+        pub private_payload: Option<Vec<u8>>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct BranchDelegation {
+        #[prost(bytes = "vec", tag = "1")]
+        pub delegate_pk: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Checkpoint {
+        #[prost(uint64, tag = "1")]
+        pub publishers: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Sequence {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Subscribe {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Unsubscribe {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Filtered {
+        #[prost(uint32, tag = "1")]
+        pub content_type: u32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct UnwrappedMessage {
+        #[prost(message, optional, tag = "1")]
+        pub link: Option<MessageLink>,
+        #[prost(message, optional, tag = "2")]
+        pub milestone: Option<Milestone>,
+        #[prost(oneof = "unwrapped_message::Content", tags = "3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13")]
+        pub content: Option<unwrapped_message::Content>,
+    }
+
+    pub mod unwrapped_message {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Content {
+            #[prost(message, tag = "3")]
+            Announce(super::Announce),
+            #[prost(message, tag = "4")]
+            Keyload(super::Keyload),
+            #[prost(message, tag = "5")]
+            SignedPacket(super::SignedPacket),
+            #[prost(message, tag = "6")]
+            TaggedPacket(super::TaggedPacket),
+            #[prost(message, tag = "7")]
+            PrivatePacket(super::PrivatePacket),
+            #[prost(message, tag = "8")]
+            BranchDelegation(super::BranchDelegation),
+            #[prost(message, tag = "9")]
+            Checkpoint(super::Checkpoint),
+            #[prost(message, tag = "10")]
+            Sequence(super::Sequence),
+            #[prost(message, tag = "11")]
+            Subscribe(super::Subscribe),
+            #[prost(message, tag = "12")]
+            Unsubscribe(super::Unsubscribe),
+            #[prost(message, tag = "13")]
+            Filtered(super::Filtered),
+        }
+    }
+}
+
+use pb::unwrapped_message::Content;
+
+fn appinst_from_bytes(appinst: &[u8]) -> Result<AppInst> {
+    ensure!(
+        appinst.len() == APPINST_SIZE,
+        "Bad channel address size: {}.",
+        appinst.len()
+    );
+    Ok(AppInst::from(appinst))
+}
+
+fn pk_from_bytes(pk: &[u8]) -> Result<PublicKey> {
+    PublicKey::from_bytes(pk).map_err(|e| anyhow!("Bad public key: {}", e))
+}
+
+impl From<&AppInst> for pb::ChannelAddress {
+    fn from(appinst: &AppInst) -> Self {
+        Self {
+            appinst: appinst.as_ref().to_vec(),
+        }
+    }
+}
+
+impl TryFrom<pb::ChannelAddress> for AppInst {
+    type Error = Error;
+    fn try_from(address: pb::ChannelAddress) -> Result<Self> {
+        appinst_from_bytes(&address.appinst)
+    }
+}
+
+impl From<&Address> for pb::MessageLink {
+    fn from(link: &Address) -> Self {
+        Self {
+            appinst: link.appinst.as_ref().to_vec(),
+            msgid: link.msgid.as_ref().to_vec(),
+        }
+    }
+}
+
+impl TryFrom<pb::MessageLink> for Address {
+    type Error = Error;
+    fn try_from(link: pb::MessageLink) -> Result<Self> {
+        ensure!(
+            MSGID_SIZES.contains(&link.msgid.len()),
+            "Bad msgid size: {}.",
+            link.msgid.len()
+        );
+        Ok(Address::new(
+            appinst_from_bytes(&link.appinst)?,
+            MsgId::from(&link.msgid[..]),
+        ))
+    }
+}
+
+impl From<Milestone> for pb::Milestone {
+    fn from(milestone: Milestone) -> Self {
+        Self {
+            index: milestone.index,
+            timestamp: milestone.timestamp,
+        }
+    }
+}
+
+impl From<pb::Milestone> for Milestone {
+    fn from(milestone: pb::Milestone) -> Self {
+        Self {
+            index: milestone.index,
+            timestamp: milestone.timestamp,
+        }
+    }
+}
+
+impl TryFrom<&UnwrappedMessage> for pb::UnwrappedMessage {
+    type Error = Error;
+    fn try_from(msg: &UnwrappedMessage) -> Result<Self> {
+        let content = match &msg.body {
+            MessageContent::Announce => Content::Announce(pb::Announce {}),
+            MessageContent::Keyload => Content::Keyload(pb::Keyload {}),
+            MessageContent::SignedPacket {
+                pk,
+                public_payload,
+                masked_payload,
+            } => Content::SignedPacket(pb::SignedPacket {
+                pk: pk.as_bytes().to_vec(),
+                public_payload: public_payload.0.clone(),
+                masked_payload: masked_payload.0.clone(),
+            }),
+            MessageContent::TaggedPacket {
+                public_payload,
+                masked_payload,
+            } => Content::TaggedPacket(pb::TaggedPacket {
+                public_payload: public_payload.0.clone(),
+                masked_payload: masked_payload.0.clone(),
+            }),
+            MessageContent::PrivatePacket {
+                pk,
+                public_payload,
+                masked_payload,
+                private_payload,
+            } => Content::PrivatePacket(pb::PrivatePacket {
+                pk: pk.as_bytes().to_vec(),
+                public_payload: public_payload.0.clone(),
+                masked_payload: masked_payload.0.clone(),
+                private_payload: private_payload.as_ref().map(|p| p.0.clone()),
+            }),
+            MessageContent::LazyPacket { pk, public_payload, .. } => {
+                let masked_payload = msg.masked_payload()?.0;
+                match pk {
+                    Some(pk) => Content::SignedPacket(pb::SignedPacket {
+                        pk: pk.as_bytes().to_vec(),
+                        public_payload: public_payload.0.clone(),
+                        masked_payload,
+                    }),
+                    None => Content::TaggedPacket(pb::TaggedPacket {
+                        public_payload: public_payload.0.clone(),
+                        masked_payload,
+                    }),
+                }
+            }
+            MessageContent::BranchDelegation { delegate_pk } => Content::BranchDelegation(pb::BranchDelegation {
+                delegate_pk: delegate_pk.as_bytes().to_vec(),
+            }),
+            MessageContent::Checkpoint { publishers } => Content::Checkpoint(pb::Checkpoint {
+                publishers: *publishers as u64,
+            }),
+            MessageContent::Sequence => Content::Sequence(pb::Sequence {}),
+            MessageContent::Subscribe => Content::Subscribe(pb::Subscribe {}),
+            MessageContent::Unsubscribe => Content::Unsubscribe(pb::Unsubscribe {}),
+            MessageContent::Filtered { content_type } => Content::Filtered(pb::Filtered {
+                content_type: *content_type as u32,
+            }),
+        };
+        Ok(Self {
+            link: Some(pb::MessageLink::from(&msg.link)),
+            milestone: msg.milestone.map(pb::Milestone::from),
+            content: Some(content),
+        })
+    }
+}
+
+impl TryFrom<pb::UnwrappedMessage> for UnwrappedMessage {
+    type Error = Error;
+    fn try_from(msg: pb::UnwrappedMessage) -> Result<Self> {
+        let link = Address::try_from(msg.link.ok_or_else(|| anyhow!("Message link is missing."))?)?;
+        let body = match msg.content.ok_or_else(|| anyhow!("Message content is missing."))? {
+            Content::Announce(_) => MessageContent::Announce,
+            Content::Keyload(_) => MessageContent::Keyload,
+            Content::SignedPacket(p) => MessageContent::SignedPacket {
+                pk: pk_from_bytes(&p.pk)?,
+                public_payload: Bytes(p.public_payload),
+                masked_payload: Bytes(p.masked_payload),
+            },
+            Content::TaggedPacket(p) => MessageContent::TaggedPacket {
+                public_payload: Bytes(p.public_payload),
+                masked_payload: Bytes(p.masked_payload),
+            },
+            Content::PrivatePacket(p) => MessageContent::PrivatePacket {
+                pk: pk_from_bytes(&p.pk)?,
+                public_payload: Bytes(p.public_payload),
+                masked_payload: Bytes(p.masked_payload),
+                private_payload: p.private_payload.map(Bytes),
+            },
+            Content::BranchDelegation(d) => MessageContent::BranchDelegation {
+                delegate_pk: pk_from_bytes(&d.delegate_pk)?,
+            },
+            Content::Checkpoint(c) => MessageContent::Checkpoint {
+                publishers: c.publishers as usize,
+            },
+            Content::Sequence(_) => MessageContent::Sequence,
+            Content::Subscribe(_) => MessageContent::Subscribe,
+            Content::Unsubscribe(_) => MessageContent::Unsubscribe,
+            Content::Filtered(f) => MessageContent::Filtered {
+                content_type: u8::try_from(f.content_type)
+                    .map_err(|_| anyhow!("Bad content type: {}.", f.content_type))?,
+            },
+        };
+        Ok(UnwrappedMessage::new(link, body).with_milestone(msg.milestone.map(Milestone::from)))
+    }
+}
+
+/// Encode `msg` as protobuf `iota.streams.v1.UnwrappedMessage`.
+pub fn encode_unwrapped(msg: &UnwrappedMessage) -> Result<Vec<u8>> {
+    use prost::Message as _;
+    let msg = pb::UnwrappedMessage::try_from(msg)?;
+    let mut buf = Vec::with_capacity(msg.encoded_len());
+    msg.encode(&mut buf).map_err(|e| anyhow!("Protobuf error: {}", e))?;
+    Ok(buf)
+}
+
+/// Decode protobuf `iota.streams.v1.UnwrappedMessage`.
+pub fn decode_unwrapped(buf: &[u8]) -> Result<UnwrappedMessage> {
+    use prost::Message as _;
+    let msg = pb::UnwrappedMessage::decode(buf).map_err(|e| anyhow!("Protobuf error: {}", e))?;
+    UnwrappedMessage::try_from(msg)
+}
@@ -0,0 +1,346 @@
+//! Deterministic simulation of channel participants over a shared transport.
+//!
+//! A scenario is a text file with one event per line, `#` starts a comment:
+//!
+//! ```text
+//! seed 42
+//! author A
+//! subscriber S1
+//! subscriber S2
+//! announce A
+//! receive_announcement S1 A
+//! receive_announcement S2 A
+//! subscribe S1 A
+//! keyload A
+//! signed A A 32
+//! tagged S1 A 16
+//! sync S2
+//! expect S2 2
+//! ```
+//!
+//! Events:
+//!
+//! * `seed <u64>` -- seed of the user keys and of payloads; must precede participants.
+//!
+//! * `author <name> [multi]` -- create an author of channel `<name>`, optionally multi-branching.
+//!
+//! * `subscriber <name>` -- create a subscriber.
+//!
+//! * `announce <author>` -- send announcement of the channel.
+//!
+//! * `receive_announcement <subscriber> <author>` -- register subscriber in the channel.
+//!
+//! * `subscribe <subscriber> <author>` -- send subscribe message, the author processes it immediately.
+//!
+//! * `keyload <author>` -- send keyload for all subscribers.
+//!
+//! * `signed <user> <author> <size>` -- send signed packet with a random masked payload of `<size>`
+//! bytes in the channel of `<author>`.
+//!
+//! * `tagged <user> <author> <size>` -- send tagged packet.
+//!
+//! * `sync <user>` -- fetch all new messages.
+//!
+//! * `expect <user> <count>` -- fail unless the last `sync` of the user returned `<count>` messages.
+//!
+//! Outcomes of events are deterministic for a given scenario: user keys and payloads are
+//! derived from the seed. Session keys and ephemeral keys are random, so keyloads
+//! and masked payloads differ between runs, but which participant can read what does not.
+
+use anyhow::{
+    anyhow,
+    bail,
+    ensure,
+    Result,
+};
+
+use iota_streams_app::transport::tangle::PAYLOAD_BYTES;
+use iota_streams_core::{
+    prelude::{
+        HashMap,
+        String,
+        ToString,
+        Vec,
+    },
+    prng,
+};
+
+use super::*;
+
+/// Event of a scenario.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    Author { name: String, multi_branching: bool },
+    Subscriber { name: String },
+    Announce { author: String },
+    ReceiveAnnouncement { user: String, author: String },
+    Subscribe { user: String, author: String },
+    Keyload { author: String },
+    SignedPacket { user: String, author: String, size: usize },
+    TaggedPacket { user: String, author: String, size: usize },
+    Sync { user: String },
+    Expect { user: String, count: usize },
+}
+
+/// Seeded schedule of events.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Scenario {
+    pub seed: u64,
+    pub events: Vec<Event>,
+}
+
+impl Scenario {
+    /// Parse scenario from its textual form, see module documentation.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut scenario = Scenario::default();
+        for (n, line) in s.lines().enumerate() {
+            let line = line.splitn(2, '#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let args: Vec<&str> = line.split_whitespace().collect();
+            let arg = |i: usize| -> Result<String> {
+                args.get(i)
+                    .map(|a| a.to_string())
+                    .ok_or_else(|| anyhow!("Line {}: missing argument {} of `{}`.", n + 1, i, args[0]))
+            };
+            let num = |i: usize| -> Result<usize> {
+                arg(i)?
+                    .parse()
+                    .map_err(|_| anyhow!("Line {}: bad number in `{}`.", n + 1, args[0]))
+            };
+            let event = match args[0] {
+                "seed" => {
+                    ensure!(scenario.events.is_empty(), "Line {}: seed must come first.", n + 1);
+                    scenario.seed = arg(1)?
+                        .parse()
+                        .map_err(|_| anyhow!("Line {}: bad seed.", n + 1))?;
+                    continue;
+                }
+                "author" => Event::Author {
+                    name: arg(1)?,
+                    multi_branching: args.get(2) == Some(&"multi"),
+                },
+                "subscriber" => Event::Subscriber { name: arg(1)? },
+                "announce" => Event::Announce { author: arg(1)? },
+                "receive_announcement" => Event::ReceiveAnnouncement {
+                    user: arg(1)?,
+                    author: arg(2)?,
+                },
+                "subscribe" => Event::Subscribe {
+                    user: arg(1)?,
+                    author: arg(2)?,
+                },
+                "keyload" => Event::Keyload { author: arg(1)? },
+                "signed" => Event::SignedPacket {
+                    user: arg(1)?,
+                    author: arg(2)?,
+                    size: num(3)?,
+                },
+                "tagged" => Event::TaggedPacket {
+                    user: arg(1)?,
+                    author: arg(2)?,
+                    size: num(3)?,
+                },
+                "sync" => Event::Sync { user: arg(1)? },
+                "expect" => Event::Expect {
+                    user: arg(1)?,
+                    count: num(2)?,
+                },
+                unknown => bail!("Line {}: unknown event `{}`.", n + 1, unknown),
+            };
+            scenario.events.push(event);
+        }
+        Ok(scenario)
+    }
+}
+
+/// Participants of a simulation sharing one transport.
+pub struct Simulation<Trans> {
+    transport: Trans,
+    prng: prng::Prng<DefaultF>,
+    counter: u64,
+    authors: HashMap<String, Author<Trans>>,
+    subscribers: HashMap<String, Subscriber<Trans>>,
+    /// Announcement and latest message link of each channel, keyed by author name.
+    links: HashMap<String, (Address, Address)>,
+    /// Number of messages returned by the last sync of each user.
+    synced: HashMap<String, usize>,
+    trace: Vec<String>,
+}
+
+impl<Trans: Transport + Clone> Simulation<Trans> {
+    pub fn new(seed: u64, transport: Trans) -> Self {
+        Self {
+            transport,
+            prng: prng::from_seed("IOTA Streams Channels simulation", &seed.to_string()),
+            counter: 0,
+            authors: HashMap::new(),
+            subscribers: HashMap::new(),
+            links: HashMap::new(),
+            synced: HashMap::new(),
+            trace: Vec::new(),
+        }
+    }
+
+    /// Run all events of `scenario` over `transport`. Failed events are recorded in the trace
+    /// and do not stop the simulation, failed expectations do.
+    pub fn run(scenario: &Scenario, transport: Trans) -> Result<Self> {
+        let mut sim = Self::new(scenario.seed, transport);
+        for event in scenario.events.iter() {
+            sim.step(event)?;
+        }
+        Ok(sim)
+    }
+
+    /// Log of event outcomes, one line per event.
+    pub fn trace(&self) -> &[String] {
+        &self.trace
+    }
+
+    pub fn author(&self, name: &str) -> Option<&Author<Trans>> {
+        self.authors.get(name)
+    }
+
+    pub fn subscriber(&self, name: &str) -> Option<&Subscriber<Trans>> {
+        self.subscribers.get(name)
+    }
+
+    /// Process one event.
+    pub fn step(&mut self, event: &Event) -> Result<()> {
+        if let Event::Expect { user, count } = event {
+            let synced = self.synced.get(user).copied().unwrap_or(0);
+            ensure!(
+                synced == *count,
+                "Expected {} messages synced by {}, found {}.",
+                count,
+                user,
+                synced
+            );
+            self.trace.push(format!("{:?}: ok", event));
+            return Ok(());
+        }
+        let outcome = match self.apply(event) {
+            Ok(outcome) => outcome,
+            Err(e) => format!("error: {}", e),
+        };
+        self.trace.push(format!("{:?}: {}", event, outcome));
+        Ok(())
+    }
+
+    fn user_seed(&self, name: &str) -> Result<Seed> {
+        Seed::from_bytes(&self.prng.gen_n(format!("user {}", name), 32))
+    }
+
+    fn payload(&mut self, size: usize) -> Bytes {
+        self.counter += 1;
+        Bytes(self.prng.gen_n(&self.counter.to_be_bytes(), size))
+    }
+
+    fn channel_links(&self, author: &str) -> Result<&(Address, Address)> {
+        self.links
+            .get(author)
+            .ok_or_else(|| anyhow!("Channel {} not announced.", author))
+    }
+
+    fn author_mut(&mut self, name: &str) -> Result<&mut Author<Trans>> {
+        self.authors.get_mut(name).ok_or_else(|| anyhow!("Unknown author {}.", name))
+    }
+
+    fn subscriber_mut(&mut self, name: &str) -> Result<&mut Subscriber<Trans>> {
+        self.subscribers
+            .get_mut(name)
+            .ok_or_else(|| anyhow!("Unknown subscriber {}.", name))
+    }
+
+    fn update_link(&mut self, author: &str, link: Address) {
+        if let Some(links) = self.links.get_mut(author) {
+            links.1 = link;
+        }
+    }
+
+    fn apply(&mut self, event: &Event) -> Result<String> {
+        match event {
+            Event::Author { name, multi_branching } => {
+                ensure!(
+                    !self.authors.contains_key(name) && !self.subscribers.contains_key(name),
+                    "User {} already exists.",
+                    name
+                );
+                let seed = self.user_seed(name)?;
+                let author = Author::new(&seed, "utf-8", PAYLOAD_BYTES, *multi_branching, self.transport.clone());
+                self.authors.insert(name.clone(), author);
+                Ok("created".to_string())
+            }
+            Event::Subscriber { name } => {
+                ensure!(
+                    !self.authors.contains_key(name) && !self.subscribers.contains_key(name),
+                    "User {} already exists.",
+                    name
+                );
+                let seed = self.user_seed(name)?;
+                let subscriber = Subscriber::new(&seed, "utf-8", PAYLOAD_BYTES, self.transport.clone());
+                self.subscribers.insert(name.clone(), subscriber);
+                Ok("created".to_string())
+            }
+            Event::Announce { author } => {
+                let link = self.author_mut(author)?.send_announce()?;
+                self.links.insert(author.clone(), (link.clone(), link.clone()));
+                Ok(format!("{}", link))
+            }
+            Event::ReceiveAnnouncement { user, author } => {
+                let link = self.channel_links(author)?.0.clone();
+                self.subscriber_mut(user)?.receive_announcement(&link)?;
+                Ok("registered".to_string())
+            }
+            Event::Subscribe { user, author } => {
+                let link = self.channel_links(author)?.0.clone();
+                let subscribe_link = self.subscriber_mut(user)?.send_subscribe(&link)?;
+                self.author_mut(author)?.receive_subscribe(&subscribe_link)?;
+                Ok(format!("{}", subscribe_link))
+            }
+            Event::Keyload { author } => {
+                let link = self.channel_links(author)?.0.clone();
+                let (keyload_link, _) = self.author_mut(author)?.send_keyload_for_everyone(&link)?;
+                self.update_link(author, keyload_link.clone());
+                Ok(format!("{}", keyload_link))
+            }
+            Event::SignedPacket { user, author, size } => {
+                let link_to = self.channel_links(author)?.1.clone();
+                let masked_payload = self.payload(*size);
+                let public_payload = Bytes::default();
+                let (link, _) = if let Some(a) = self.authors.get_mut(user) {
+                    a.send_signed_packet(&link_to, &public_payload, &masked_payload)?
+                } else {
+                    self.subscriber_mut(user)?
+                        .send_signed_packet(&link_to, &public_payload, &masked_payload)?
+                };
+                self.update_link(author, link.clone());
+                Ok(format!("{}", link))
+            }
+            Event::TaggedPacket { user, author, size } => {
+                let link_to = self.channel_links(author)?.1.clone();
+                let masked_payload = self.payload(*size);
+                let public_payload = Bytes::default();
+                let (link, _) = if let Some(a) = self.authors.get_mut(user) {
+                    a.send_tagged_packet(&link_to, &public_payload, &masked_payload)?
+                } else {
+                    self.subscriber_mut(user)?
+                        .send_tagged_packet(&link_to, &public_payload, &masked_payload)?
+                };
+                self.update_link(author, link.clone());
+                Ok(format!("{}", link))
+            }
+            Event::Sync { user } => {
+                let msgs = if let Some(a) = self.authors.get_mut(user) {
+                    a.sync_state()
+                } else {
+                    self.subscriber_mut(user)?.sync_state()
+                };
+                self.synced.insert(user.clone(), msgs.len());
+                Ok(format!("{} messages", msgs.len()))
+            }
+            Event::Expect { .. } => Err(anyhow!("Expectations are not applied.")),
+        }
+    }
+}
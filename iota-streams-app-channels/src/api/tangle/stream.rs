@@ -0,0 +1,55 @@
+//! Asynchronous stream of messages retrieved by a user.
+
+use anyhow::Result;
+use core::{
+    pin::Pin,
+    task::{
+        Context,
+        Poll,
+    },
+};
+use futures_core::Stream;
+
+use iota_streams_core::prelude::{
+    vec,
+    Vec,
+};
+
+use super::*;
+
+/// Stream of messages published in the channel, see `User::messages`.
+///
+/// Messages are fetched lazily one round (the next message of each publisher) at a time,
+/// in the order `sync_state` would return them. Messages that could not be handled are
+/// yielded as errors. The stream ends when a round finds no new messages.
+pub struct Messages<'a, Trans> {
+    user: &'a mut User<Trans>,
+    pending: vec::IntoIter<Result<UnwrappedMessage>>,
+    done: bool,
+}
+
+impl<'a, Trans> Messages<'a, Trans> {
+    pub(crate) fn new(user: &'a mut User<Trans>) -> Self {
+        Self {
+            user,
+            pending: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+}
+
+impl<'a, Trans: Transport> Stream for Messages<'a, Trans> {
+    type Item = Result<UnwrappedMessage>;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.pending.len() == 0 && !this.done {
+            let msgs = this.user.try_fetch_next_msgs();
+            // Sequencing state does not advance past failed messages, fetching them again
+            // would yield the same errors.
+            this.done = msgs.iter().all(|msg| msg.is_err());
+            this.pending = msgs.into_iter();
+        }
+        Poll::Ready(this.pending.next())
+    }
+}
@@ -1,7 +1,13 @@
 //! Customize Subscriber with default parameters for use over the Tangle.
 
-use anyhow::Result;
+use anyhow::{
+    anyhow,
+    ensure,
+    Result,
+};
 use core::fmt;
+#[cfg(feature = "stream")]
+use futures_core::Stream;
 
 use super::*;
 use crate::api::tangle::{
@@ -15,21 +21,54 @@ use iota_streams_core_edsig::signature::ed25519;
 /// Subscriber Object. Contains User API.
 pub struct Subscriber<T> {
     user: User<T>,
+    config: Option<ChannelConfig>,
+    join: Option<JoinProgress>,
 }
 
 impl<Trans: Transport> Subscriber<Trans>
 {
+    /// Start building a Subscriber instance from named options, see `SubscriberBuilder`.
+    pub fn builder() -> SubscriberBuilder<Trans> {
+        SubscriberBuilder::default()
+    }
+
     /// Create a new Subscriber instance, generate new MSS keypair and optionally NTRU keypair.
     ///
     /// # Arguments
-    /// * `seed` - A string slice representing the seed of the user [Characters: A-Z, 9]
+    /// * `seed` - Seed of the user, see `Seed`
     /// * `encoding` - A string slice representing the encoding type for the message [supported: utf-8]
     /// * `payload_length` - Maximum size in bytes of payload per message chunk [1-1024],
     /// * `transport` - Transport object used for sending and receiving
     ///
-    pub fn new(seed: &str, encoding: &str, payload_length: usize, transport: Trans) -> Self {
+    pub fn new(seed: &Seed, encoding: &str, payload_length: usize, transport: Trans) -> Self {
         let user = User::new(seed, encoding, payload_length, false, transport);
-        Self { user }
+        Self {
+            user,
+            config: None,
+            join: None,
+        }
+    }
+
+    /// Expect channels created with `config`, announcements of other channels are rejected.
+    pub fn set_channel_config(&mut self, config: ChannelConfig) -> Result<()> {
+        config.validate()?;
+        self.config = Some(config);
+        Ok(())
+    }
+
+    /// Wrap a User that is not the author of a channel.
+    pub fn from_user(user: User<Trans>) -> Result<Self> {
+        ensure!(!user.is_author(), "User is the author of a channel.");
+        Ok(Self {
+            user,
+            config: None,
+            join: None,
+        })
+    }
+
+    /// Unwrap the underlying role-agnostic User.
+    pub fn into_user(self) -> User<Trans> {
+        self.user
     }
 
     /// Returns a boolean representing whether an Announcement message has been processed
@@ -47,6 +86,11 @@ impl<Trans: Transport> Subscriber<Trans>
         self.user.get_pk()
     }
 
+    /// Fetch the fingerprint of the user's secret key for support diagnostics
+    pub fn fingerprint(&self) -> Fingerprint {
+        self.user.fingerprint()
+    }
+
     /// Fetch the Address (application instance) of the channel.
     pub fn channel_address(&self) -> Option<&ChannelAddress> {
         self.user.channel_address()
@@ -57,6 +101,32 @@ impl<Trans: Transport> Subscriber<Trans>
         self.user.is_multi_branching()
     }
 
+    /// Return boolean representing use of per-publisher message counters instead of sequence messages
+    pub fn is_counter_sequencing(&self) -> bool {
+        self.user.is_counter_sequencing()
+    }
+
+    /// Return boolean representing use of post-quantum signatures by the channel author
+    pub fn is_hybrid(&self) -> bool {
+        self.user.is_hybrid()
+    }
+
+    /// Return boolean representing use of post-quantum key encapsulation in keyloads of the channel
+    pub fn is_hybrid_kem(&self) -> bool {
+        self.user.is_hybrid_kem()
+    }
+
+    /// Return boolean representing use of synthetic IVs for masked payloads of the channel
+    pub fn is_siv_masking(&self) -> bool {
+        self.user.is_siv_masking()
+    }
+
+    /// Set callback populating the application-specific header extension of outgoing messages,
+    /// eg. with routing hints readable before the message content is unwrapped.
+    pub fn set_header_ext<E: Fn(&Header) -> Vec<u8> + 'static>(&mut self, header_ext: E) {
+        self.user.set_header_ext(header_ext)
+    }
+
     /// Create and Send a Subscribe message to a Channel app instance.
     ///
     /// # Arguments
@@ -98,6 +168,99 @@ impl<Trans: Transport> Subscriber<Trans>
         self.user.send_signed_packet(link_to, public_payload, masked_payload)
     }
 
+    /// Create and send a deniable packet, authenticated with the branch session key MAC
+    /// instead of a signature, see `User::send_deniable_packet`.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of a keyload the user has the session key of or of another deniable packet
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///
+    pub fn send_deniable_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_deniable_packet(link_to, public_payload, masked_payload)
+    }
+
+    /// Create and send a private packet, with the private payload readable by the recipient only.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `recipient_pk` - ed25519 Public Key of the recipient of the private payload
+    ///  * `private_payload` - Wrapped vector of Bytes accessible by the recipient only
+    ///
+    pub fn send_private_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        recipient_pk: &ed25519::PublicKey,
+        private_payload: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_private_packet(link_to, public_payload, masked_payload, recipient_pk, private_payload)
+    }
+
+    /// Create and send a signed packet with the masked payload offloaded to a blob store.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access, stored in `blobs`
+    ///  * `blobs` - Blob store the masked payload is offloaded to
+    ///
+    pub fn send_signed_packet_offloaded<B: BlobStore>(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        blobs: &mut B,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_signed_packet_offloaded(link_to, public_payload, masked_payload, blobs)
+    }
+
+    /// Create and send a signed packet co-signed with a secp256k1 key, eg. one known to an
+    /// Ethereum contract.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `cosigner` - Secp256k1 key co-signing the packet digest
+    ///
+    #[cfg(feature = "secp256k1")]
+    pub fn send_signed_packet_cosigned(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        cosigner: &secp256k1::SigningKey,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_signed_packet_cosigned(link_to, public_payload, masked_payload, cosigner)
+    }
+
+    /// Create and send a tagged packet with the masked payload offloaded to a blob store.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access, stored in `blobs`
+    ///  * `blobs` - Blob store the masked payload is offloaded to
+    ///
+    pub fn send_tagged_packet_offloaded<B: BlobStore>(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        blobs: &mut B,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_tagged_packet_offloaded(link_to, public_payload, masked_payload, blobs)
+    }
+
     // Unsubscribe from the Channel app instance.
     // pub pub fn unsubscribe(&mut self, link_to: &Address) -> Result<Message> {
     // TODO: lookup link_to Subscribe message.
@@ -110,7 +273,14 @@ impl<Trans: Transport> Subscriber<Trans>
     /// * `link_to` - Address of the Channel Announcement message
     ///
     pub fn receive_announcement(&mut self, link: &Address) -> Result<()> {
-        self.user.receive_announcement(link)
+        self.user.receive_announcement(link)?;
+        if let Some(config) = &self.config {
+            if let Err(e) = config.ensure_announced(link, self.user.is_multi_branching()) {
+                self.user.unregister();
+                return Err(e);
+            }
+        }
+        Ok(())
     }
 
     /// Receive and process a keyload message.
@@ -122,6 +292,249 @@ impl<Trans: Transport> Subscriber<Trans>
         self.user.receive_keyload(link)
     }
 
+    /// Join the channel announced at `announcement_link`: process the announcement, send a
+    /// subscription and wait for a keyload including the subscriber. Each call advances the
+    /// handshake as far as possible without blocking, call again while it is `Subscribed`,
+    /// eg. once the author processed the subscription. Messages fetched while waiting are
+    /// processed as by `fetch_next_msgs`.
+    ///
+    ///  # Arguments
+    ///  * `announcement_link` - Address of the Channel Announcement message
+    ///
+    pub fn join(&mut self, announcement_link: &Address) -> Result<JoinProgress> {
+        if !self.is_registered() {
+            self.receive_announcement(announcement_link)?;
+        }
+        ensure!(
+            self.channel_address() == Some(&announcement_link.appinst),
+            "Subscriber is registered to another channel."
+        );
+        if self.join.is_none() {
+            let subscribe_link = self.send_subscribe(announcement_link)?;
+            self.join = Some(JoinProgress::Subscribed(subscribe_link));
+        }
+
+        while let Some(JoinProgress::Subscribed(_)) = &self.join {
+            let msgs = self.fetch_next_msgs();
+            if msgs.is_empty() {
+                break;
+            }
+            if let Some(msg) = msgs
+                .iter()
+                .find(|msg| matches!(msg.body, MessageContent::Keyload) && self.user.has_keyload_access(&msg.link))
+            {
+                self.join = Some(JoinProgress::Joined(msg.link.clone()));
+            }
+        }
+        self.join.clone().ok_or_else(|| anyhow!("Subscription not sent."))
+    }
+
+    /// Rate policy declared in the keyload at `keyload`, `None` if messages of its branch are
+    /// not limited.
+    pub fn branch_rate_policy(&self, keyload: &Address) -> Option<&RatePolicy> {
+        self.user.branch_rate_policy(keyload)
+    }
+
+    /// Select whether packets exceeding the rate policy are flagged or dropped.
+    pub fn set_rate_action(&mut self, action: RateAction) {
+        self.user.set_rate_action(action)
+    }
+
+    /// Publishers which exceeded the rate policy.
+    pub fn flagged_publishers(&self) -> &[ed25519::PublicKey] {
+        self.user.flagged_publishers()
+    }
+
+    /// Select handling of replayed, out-of-order and missing messages, see `SequencePolicy`.
+    pub fn set_sequence_policy(&mut self, policy: SequencePolicy) {
+        self.user.set_sequence_policy(policy)
+    }
+
+    /// Return packets from generic message handling with masked payloads decrypted on first access.
+    pub fn set_lazy_payloads(&mut self, lazy: bool) {
+        self.user.set_lazy_payloads(lazy)
+    }
+
+    /// Cap the number of keyloads whose unwrapped session state is cached, `0` disables caching.
+    pub fn set_session_cache_capacity(&mut self, capacity: usize) {
+        self.user.set_session_cache_capacity(capacity)
+    }
+
+    /// Forget cached keyload session states.
+    pub fn clear_session_cache(&mut self) {
+        self.user.clear_session_cache()
+    }
+
+    /// Number of keyloads with cached session state.
+    pub fn cached_sessions(&self) -> usize {
+        self.user.cached_sessions()
+    }
+
+    /// Drop state of messages processed before the checkpoint `link`, returns the number of
+    /// dropped messages.
+    pub fn prune_before(&mut self, link: &Address) -> Result<usize> {
+        self.user.prune_before(link)
+    }
+
+    /// Approximate memory used by link store, spongos states, cursors and other state.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        self.user.memory_footprint()
+    }
+
+    /// Set header predicate of packets to be unwrapped when fetching, rejected packets are
+    /// skipped, see `User::set_fetch_filter`.
+    pub fn set_fetch_filter<P: Fn(&Header) -> bool + 'static>(&mut self, filter: P) {
+        self.user.set_fetch_filter(filter)
+    }
+
+    /// Remove fetch filter, fetched packets are all unwrapped.
+    pub fn clear_fetch_filter(&mut self) {
+        self.user.clear_fetch_filter()
+    }
+
+    /// Store a pre-shared key identified by `pskid`, see `User::store_psk`.
+    pub fn store_psk(&mut self, pskid: PskId, psk: Psk) {
+        self.user.store_psk(pskid, psk)
+    }
+
+    /// Set delivery priority (0 to `hdf::MAX_PRIORITY`) of subsequently sent messages.
+    pub fn set_priority(&mut self, priority: u8) -> Result<()> {
+        self.user.set_priority(priority)
+    }
+
+    /// Retain binary messages received from transport for archiving, see `raw`.
+    pub fn set_retain_raw(&mut self, retain: bool) {
+        self.user.set_retain_raw(retain)
+    }
+
+    /// Retained binary message at `link`, eg. of an unwrapped message.
+    pub fn raw(&self, link: &Address) -> Option<&Message> {
+        self.user.raw(link)
+    }
+
+    /// Remove and return all retained binary messages.
+    pub fn take_raw(&mut self) -> Vec<Message> {
+        self.user.take_raw()
+    }
+
+    /// Register a hook notarizing every successfully sent message, see `Notarizer`.
+    pub fn set_notarizer<N: Notarizer + 'static>(&mut self, notarizer: N) {
+        self.user.set_notarizer(notarizer)
+    }
+
+    /// Remove the registered notarizer.
+    pub fn clear_notarizer(&mut self) {
+        self.user.clear_notarizer()
+    }
+
+    /// Register callbacks notified of processed messages, see `UserEvents`.
+    pub fn register_events<E: UserEvents + 'static>(&mut self, events: E) {
+        self.user.register_events(events)
+    }
+
+    /// Remove registered callbacks.
+    pub fn unregister_events(&mut self) {
+        self.user.unregister_events()
+    }
+
+    /// Receive and process a branch delegation message, returns the delegate.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_branch_delegation(&mut self, link: &Address) -> Result<ed25519::PublicKey> {
+        self.user.receive_branch_delegation(link)
+    }
+
+    /// Receive and process a checkpoint message, fetching continues from the checkpointed
+    /// sequencing state. Returns the number of publishers in the checkpoint.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_checkpoint(&mut self, link: &Address) -> Result<usize> {
+        self.user.receive_checkpoint(link)
+    }
+
+    /// Create and send a new keyload for a list of subscribers in a branch delegated to the subscriber.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the branch delegation message
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    ///
+    pub fn send_keyload(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<ed25519::PublicKey>,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_keyload(link_to, psk_ids, ke_pks)
+    }
+
+    /// Create and send a new keyload in a branch delegated to the subscriber, recipients in
+    /// `read_only` may not publish in its branch. The branch accepts signed packets only,
+    /// tagged packets can't be attributed to their sender.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    ///  * `read_only` - Vector of Public Keys of recipients allowed to read only
+    ///
+    pub fn send_keyload_with_read_only(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<ed25519::PublicKey>,
+        read_only: &Vec<ed25519::PublicKey>,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_keyload_with_read_only(link_to, psk_ids, ke_pks, read_only)
+    }
+
+    /// Create and send a new keyload for a list of subscribers without listing their public
+    /// keys, recipients find their key slot by trial decryption.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `ke_pks`  - Vector of Public Keys of the recipients
+    ///
+    pub fn send_anonymous_keyload(
+        &mut self,
+        link_to: &Address,
+        ke_pks: &Vec<ed25519::PublicKey>,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_anonymous_keyload(link_to, ke_pks)
+    }
+
+    /// Create and send keyload for all known recipients in a branch delegated to the subscriber.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the branch delegation message
+    ///
+    pub fn send_keyload_for_everyone(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.user.send_keyload_for_everyone(link_to)
+    }
+
+    /// Set branch policy declared in subsequent keyloads, linear branches of multi-branch
+    /// channels are sequenced without sequence messages.
+    ///
+    ///  # Arguments
+    ///  * `policy` - Sequencing of messages linked to the keyloads
+    ///
+    pub fn set_branch_policy(&mut self, policy: BranchPolicy) {
+        self.user.set_branch_policy(policy)
+    }
+
+    /// Add a recipient of keyloads in delegated branches.
+    ///
+    ///  # Arguments
+    ///  * `pk` - Public Key of the recipient
+    ///
+    pub fn add_recipient(&mut self, pk: ed25519::PublicKey) -> Result<()> {
+        self.user.add_recipient(pk)
+    }
+
     /// Receive and process a signed packet message.
     ///
     ///  # Arguments
@@ -140,6 +553,147 @@ impl<Trans: Transport> Subscriber<Trans>
         self.user.receive_tagged_packet(link)
     }
 
+    /// Receive and process a deniable packet, authenticated by the branch session key only.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_deniable_packet(&mut self, link: &Address) -> Result<(Bytes, Bytes)> {
+        self.user.receive_deniable_packet(link)
+    }
+
+    /// Receive and process a private packet message. The private payload is `None` if the packet
+    /// is addressed to another recipient.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_private_packet(
+        &mut self,
+        link: &Address,
+    ) -> Result<(ed25519::PublicKey, Bytes, Bytes, Option<Bytes>)> {
+        self.user.receive_private_packet(link)
+    }
+
+    /// Receive and process a signed packet message with the masked payload offloaded to a blob store.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///  * `blobs` - Blob store the masked payload is fetched from
+    ///
+    pub fn receive_signed_packet_offloaded<B: BlobStore>(
+        &mut self,
+        link: &Address,
+        blobs: &mut B,
+    ) -> Result<(ed25519::PublicKey, Bytes, Bytes)> {
+        self.user.receive_signed_packet_offloaded(link, blobs)
+    }
+
+    /// Receive and process a signed packet message together with its secp256k1 co-signature,
+    /// `None` if the packet is not co-signed.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    #[cfg(feature = "secp256k1")]
+    pub fn receive_signed_packet_cosigned(
+        &mut self,
+        link: &Address,
+    ) -> Result<(ed25519::PublicKey, Bytes, Bytes, Option<secp256k1::Cosignature>)> {
+        self.user.receive_signed_packet_cosigned(link)
+    }
+
+    /// Receive and process a tagged packet message with the masked payload offloaded to a blob store.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///  * `blobs` - Blob store the masked payload is fetched from
+    ///
+    pub fn receive_tagged_packet_offloaded<B: BlobStore>(&mut self, link: &Address, blobs: &mut B) -> Result<(Bytes, Bytes)> {
+        self.user.receive_tagged_packet_offloaded(link, blobs)
+    }
+
+    /// Receive and process a signed packet message with an embargoed masked payload, open it with
+    /// the matching release.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_embargoed_packet(&mut self, link: &Address) -> Result<(ed25519::PublicKey, Bytes, EmbargoedPayload)> {
+        self.user.receive_embargoed_packet(link)
+    }
+
+    /// Receive and process the release of an embargoed payload.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_release(&mut self, link: &Address) -> Result<(ed25519::PublicKey, EmbargoRelease)> {
+        self.user.receive_release(link)
+    }
+
+    /// Receive and process the revocation of a subscriber, returns its access window.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_revocation(&mut self, link: &Address) -> Result<(ed25519::PublicKey, Revocation, AccessWindow)> {
+        self.user.receive_revocation(link)
+    }
+
+    /// Access window of the subscriber announced by the author, `None` unless revoked.
+    pub fn access_window(&self) -> Option<AccessWindow> {
+        self.user.access_window()
+    }
+
+    /// Receive and process a directory of the author.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_directory(&mut self, link: &Address) -> Result<Directory> {
+        self.user.receive_directory(link)
+    }
+
+    /// Latest received directory of the channel.
+    pub fn directory(&self) -> &Directory {
+        self.user.directory()
+    }
+
+    /// Receive and process a key tree update of the author, returns whether the subscriber is a
+    /// member of the tree after it.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_tree_update(&mut self, link: &Address) -> Result<bool> {
+        self.user.receive_tree_update(link)
+    }
+
+    /// Process a key tree update already unwrapped, updates must be processed in order.
+    ///
+    ///  # Arguments
+    ///  * `msg` - Unwrapped update
+    ///
+    pub fn handle_tree_update(&mut self, msg: &UnwrappedMessage) -> Result<bool> {
+        self.user.handle_tree_update(msg)
+    }
+
+    /// Whether the subscriber is a member of the key tree of the author.
+    pub fn is_tree_member(&self) -> bool {
+        self.user.is_tree_member()
+    }
+
+    /// Receive and process a disclosable packet, returns the commitments its disclosed fields
+    /// are checked against.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_disclosable_packet(&mut self, link: &Address) -> Result<(ed25519::PublicKey, FieldCommitments)> {
+        self.user.receive_disclosable_packet(link)
+    }
+
     /// Receive and process a sequence message.
     ///
     ///  # Arguments
@@ -183,11 +737,112 @@ impl<Trans: Transport> Subscriber<Trans>
         self.user.gen_next_msg_ids(branching)
     }
 
+    /// Retrieves the sequencing state of each publishing participant: the link of the latest
+    /// processed message and its cursor position
+    pub fn fetch_state(&self) -> Result<Vec<(ed25519::PublicKey, Cursor<Address>)>> {
+        self.user.fetch_state()
+    }
+
+    /// Retrieves and processes messages until no new messages are found, bringing the
+    /// sequencing state fully up to date. Returns all retrieved messages
+    pub fn sync_state(&mut self) -> Vec<UnwrappedMessage> {
+        self.user.sync_state()
+    }
+
+    /// Like `sync_state`, with the next messages fetched with `fetcher` on a separate thread
+    /// while the current ones are unwrapped.
+    ///
+    ///   # Arguments
+    ///   * `fetcher` - Transport to fetch messages with, eg. a clone of the user's transport
+    ///
+    #[cfg(feature = "std")]
+    pub fn sync_state_pipelined<T>(&mut self, fetcher: T) -> Vec<UnwrappedMessage>
+    where
+        T: Transport + Send + 'static,
+    {
+        self.user.sync_state_pipelined(fetcher)
+    }
+
+    /// Unwrap the packet at `link` without updating the user state, eg. to preview it in a UI
+    /// before processing it. The packet can be received afterwards as if it wasn't peeked.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the packet to be peeked
+    ///
+    pub fn peek(&mut self, link: &Address) -> Result<UnwrappedMessage> {
+        self.user.peek(link)
+    }
+
+    /// Check header integrity and signature of the message at `link` without unwrapping it,
+    /// eg. to filter forged traffic on a relay. Returns `None` if only the header could be
+    /// checked as the message is in a branch the subscriber has no session key for. Relays
+    /// can't filter forged messages in such branches, signatures are over the plaintext state
+    /// and can't be checked without the session key.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be verified
+    ///
+    pub fn verify_only(&mut self, link: &Address) -> Result<Option<Authentication>> {
+        self.user.verify_only(link)
+    }
+
+    /// Check header integrity and signature of a wrapped message, see `verify_only`.
+    ///
+    ///   # Arguments
+    ///   * `msg` - Wrapped message to be verified
+    ///
+    pub fn verify_binary(&self, msg: Message) -> Result<Option<Authentication>> {
+        self.user.verify_binary(msg)
+    }
+
+    /// Verify the chain of messages from `link` back to the channel announcement, returns
+    /// the verified messages as a proof for auditors.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be verified
+    ///
+    pub fn verify_lineage(&mut self, link: &Address) -> Result<LineageProof> {
+        self.user.verify_lineage(link)
+    }
+
+    /// Iterate over messages from `link` to the current head, fetching them on demand.
+    /// Only single-branch channels are supported.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the first message
+    ///
+    pub fn iter_from(&mut self, link: &Address) -> Result<History<'_, Trans>> {
+        self.user.iter_from(link)
+    }
+
+    /// Stream of messages fetched lazily until no new messages are found.
+    ///
+    /// ```ignore
+    /// let mut messages = subscriber.messages();
+    /// while let Some(msg) = messages.next().await {
+    ///     let msg = msg?;
+    /// }
+    /// ```
+    #[cfg(feature = "stream")]
+    pub fn messages(&mut self) -> impl Stream<Item = Result<UnwrappedMessage>> + '_ {
+        self.user.messages()
+    }
+
     /// Retrieves the next message for each user (if present in transport layer) and returns them
     pub fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
         self.user.fetch_next_msgs()
     }
 
+    /// Retrieves the next message of publisher `pk` only (if present in transport layer),
+    /// see `User::fetch_next_msgs_from`
+    ///
+    ///   # Arguments
+    ///   * `pk` - Public key of the publisher
+    ///
+    pub fn fetch_next_msgs_from(&mut self, pk: &ed25519::PublicKey) -> Vec<UnwrappedMessage> {
+        self.user.fetch_next_msgs_from(pk)
+    }
+
     /// Receive and process a message of unknown type. Message will be handled appropriately and
     /// the unwrapped contents returned
     ///
@@ -216,7 +871,32 @@ impl<Trans: Transport> Subscriber<Trans>
     ///   * `tsp` - Transport object
     ///
     pub fn import(bytes: &[u8], pwd: &str, tsp: Trans) -> Result<Self> {
-        User::<Trans>::import(bytes, 1, pwd, tsp).map(|user| Self { user })
+        User::<Trans>::import(bytes, 1, pwd, tsp).map(|user| Self {
+            user,
+            config: None,
+            join: None,
+        })
+    }
+
+    /// Upgrade user state exported by an earlier release to the current state version, fails
+    /// with `StateError` on unknown versions.
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Encrypted serialized user state
+    ///   * `pwd` - Encryption password
+    ///
+    pub fn migrate_state(bytes: &[u8], pwd: &str) -> Result<Vec<u8>> {
+        User::<Trans>::migrate_state(bytes, 1, pwd)
+    }
+}
+
+impl<T: Transport> fmt::Debug for Subscriber<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscriber")
+            .field("user", &self.user.user)
+            .field("config", &self.config)
+            .field("join", &self.join)
+            .finish()
     }
 }
 
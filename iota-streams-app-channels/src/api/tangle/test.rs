@@ -1,21 +1,51 @@
 #![allow(non_snake_case)]
-use crate::api::tangle::{
-    Address,
-    Author,
-    Subscriber,
+use crate::{
+    api::tangle::{
+        Address,
+        Author,
+        BranchPolicy,
+        DefaultF,
+        MessageContent,
+        PacketPayloads,
+        Role,
+        SequencePolicy,
+        SequenceViolation,
+        Signature,
+        StateError,
+        Subscriber,
+        User,
+        UserEvents,
+        frost,
+        threshold_signature_context,
+        STATE_VERSION,
+    },
+    message::{
+        ANNOUNCE,
+        SIGNED_PACKET,
+    },
 };
 use anyhow::{
     ensure,
     Result,
 };
 use iota_streams_app::{
-    message::HasLink,
-    transport::tangle::PAYLOAD_BYTES,
+    message::{
+        HasLink,
+        Milestone,
+    },
+    transport::{
+        tangle::PAYLOAD_BYTES,
+        ChaosOptions,
+        ChaosTransport,
+        PriorityTransport,
+    },
 };
 use iota_streams_core::{
     prelude::{
         string::ToString,
+        vec,
         Rc,
+        Vec,
     },
     println,
 };
@@ -31,16 +61,16 @@ pub fn example<T: Transport>(transport: T) -> Result<()>
     let transport = Rc::new(RefCell::new(transport));
 
     let mut author = Author::new(
-        "AUTHOR9SEED",
+        &"AUTHOR9SEED".parse()?,
         encoding,
         PAYLOAD_BYTES,
         multi_branching,
         transport.clone(),
     );
 
-    let mut subscriberA = Subscriber::new("SUBSCRIBERA9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberA = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
 
-    let mut subscriberB = Subscriber::new("SUBSCRIBERB9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberB = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
 
     let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
     let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
@@ -147,3 +177,3607 @@ fn run_basic_scenario() {
     let transport = crate::api::tangle::BucketTransport::new();
     assert!(dbg!(example(transport)).is_ok());
 }
+
+pub fn example_msgid_size<T: Transport>(transport: T, msgid_size: usize) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new_with_msgid_size(
+        &"AUTHOR9SEED".parse()?,
+        encoding,
+        PAYLOAD_BYTES,
+        false,
+        msgid_size,
+        transport.clone(),
+    )?;
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    ensure!(announcement_link.msgid.size() == msgid_size, "bad announcement msgid size");
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    ensure!(subscribe_link.msgid.size() == msgid_size, "bad subscribe msgid size");
+    author.receive_subscribe(&subscribe_link)?;
+
+    let (signed_packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let (_, unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&signed_packet_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+
+    let subdump = subscriber.export("pwdSub")?;
+    let subscriber2 = Subscriber::import(subdump.as_ref(), "pwdSub", transport.clone())?;
+    ensure!(
+        subscriber2.channel_address() == subscriber.channel_address(),
+        "bad imported channel address"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_msgid_size_scenario() {
+    for msgid_size in &[12, 24, 32] {
+        let transport = crate::api::tangle::BucketTransport::new();
+        assert!(dbg!(example_msgid_size(transport, *msgid_size)).is_ok());
+    }
+}
+
+pub fn example_msgid_hash<T: Transport>(transport: T, msgid_hash: MsgIdHash) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new_with_msgid_hash(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, msgid_hash, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    let default_transport = Rc::new(RefCell::new(crate::api::tangle::BucketTransport::new()));
+    let mut default_author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, default_transport);
+    let default_link = default_author.send_announce()?;
+    ensure!(
+        (announcement_link.msgid == default_link.msgid) == (msgid_hash == MsgIdHash::Spongos),
+        "msgid hash not applied"
+    );
+    subscriber.receive_announcement(&announcement_link)?;
+
+    println!("subscriber derives links with the announced hash");
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+
+    let (signed_packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let (_, unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&signed_packet_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+
+    let (tagged_packet_link, _) = author.send_tagged_packet(&signed_packet_link, &public_payload, &masked_payload)?;
+    ensure!(subscriber.fetch_next_msgs().len() == 1, "subscriber failed to find next message");
+
+    println!("restore msgid hash");
+    let subdump = subscriber.export("pwdSub")?;
+    let mut subscriber2 = Subscriber::import(subdump.as_ref(), "pwdSub", transport.clone())?;
+    author.send_signed_packet(&tagged_packet_link, &public_payload, &masked_payload)?;
+    ensure!(subscriber2.fetch_next_msgs().len() == 1, "imported subscriber failed to find next message");
+    Ok(())
+}
+
+#[test]
+fn run_msgid_hash_scenario() {
+    for msgid_hash in &[MsgIdHash::Spongos, MsgIdHash::Blake2b, MsgIdHash::Sha256] {
+        let transport = crate::api::tangle::BucketTransport::new();
+        assert!(dbg!(example_msgid_hash(transport, *msgid_hash)).is_ok());
+    }
+}
+
+pub fn example_header_ext<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    author.set_header_ext(|header| vec![header.get_content_type(), 7]);
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let (signed_packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    {
+        let msg = transport.borrow_mut().recv_message(&signed_packet_link)?;
+        let preparsed = msg.binary.parse_header()?;
+        ensure!(
+            preparsed.header_ext() == &[SIGNED_PACKET, 7][..],
+            "bad header extension"
+        );
+    }
+    let (_, unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&signed_packet_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+
+    Ok(())
+}
+
+#[test]
+fn run_header_ext_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_header_ext(transport)).is_ok());
+}
+
+pub fn example_offloaded<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+    let mut blobs = BucketBlobStore::new();
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes(vec![7; 4 * PAYLOAD_BYTES]);
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let (signed_packet_link, _) =
+        author.send_signed_packet_offloaded(&announcement_link, &public_payload, &masked_payload, &mut blobs)?;
+    let (_, unwrapped_public, unwrapped_reference) = subscriber.receive_signed_packet(&signed_packet_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(unwrapped_reference.0.len() < PAYLOAD_BYTES, "masked payload not offloaded");
+
+    let (_, _, unwrapped_masked) = subscriber.receive_signed_packet_offloaded(&signed_packet_link, &mut blobs)?;
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+
+    Ok(())
+}
+
+#[test]
+fn run_offloaded_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_offloaded(transport)).is_ok());
+}
+
+pub fn example_private_packet<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber_a = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriber_b = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let private_payload = Bytes("PRIVATEPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    subscriber_a.receive_announcement(&announcement_link)?;
+    subscriber_b.receive_announcement(&announcement_link)?;
+
+    let recipient_pk = subscriber_a.get_pk().clone();
+    let (private_packet_link, _) = author.send_private_packet(
+        &announcement_link,
+        &public_payload,
+        &masked_payload,
+        &recipient_pk,
+        &private_payload,
+    )?;
+
+    let (_, unwrapped_public, unwrapped_masked, unwrapped_private) =
+        subscriber_a.receive_private_packet(&private_packet_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+    ensure!(Some(private_payload) == unwrapped_private, "bad unwrapped private payload");
+
+    let (_, unwrapped_public, _, unwrapped_private) = subscriber_b.receive_private_packet(&private_packet_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(unwrapped_private.is_none(), "private payload must not be accessible");
+
+    Ok(())
+}
+
+#[test]
+fn run_private_packet_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_private_packet(transport)).is_ok());
+}
+
+pub fn example_chaos(seed: u64) -> Result<()>
+{
+    let encoding = "utf-8";
+    let opts = ChaosOptions {
+        latency: 1,
+        reorder_window: 4,
+        ..ChaosOptions::default()
+    };
+    let transport = Rc::new(RefCell::new(ChaosTransport::new(BucketTransport::new(), opts, seed)));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    transport.borrow_mut().flush::<Address>()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let payloads: Vec<Bytes> = (0..5).map(|i| Bytes(vec![i; 8])).collect();
+    for public_payload in payloads.iter() {
+        author.send_signed_packet(&announcement_link, public_payload, &Bytes::default())?;
+    }
+
+    // Delayed and reordered messages are not found until delivered, the sequence state
+    // must not advance past a missing message.
+    let mut msgs = subscriber.sync_state();
+    ensure!(msgs.len() <= payloads.len(), "too many messages");
+    transport.borrow_mut().flush::<Address>()?;
+    msgs.extend(subscriber.sync_state());
+    ensure!(msgs.len() == payloads.len(), "lost messages: {}", msgs.len());
+
+    for (msg, public_payload) in msgs.iter().zip(payloads.iter()) {
+        match &msg.body {
+            MessageContent::SignedPacket { public_payload: p, .. } => {
+                ensure!(p == public_payload, "messages out of order")
+            }
+            _ => ensure!(false, "bad message type"),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn run_chaos_scenario() {
+    for seed in 1..8 {
+        assert!(dbg!(example_chaos(seed)).is_ok());
+    }
+}
+
+pub const SIMULATION_SCENARIO: &str = "
+seed 7
+author A
+subscriber S1
+subscriber S2
+announce A
+receive_announcement S1 A
+receive_announcement S2 A
+subscribe S1 A
+keyload A
+signed A A 32
+signed A A 16
+sync S1
+expect S1 3
+# S2 is not a recipient of the keyload and can't read past it
+sync S2
+expect S2 1
+";
+
+pub fn example_simulation() -> Result<()>
+{
+    let scenario = simulation::Scenario::parse(SIMULATION_SCENARIO)?;
+    let sim1 = simulation::Simulation::run(&scenario, Rc::new(RefCell::new(BucketTransport::new())))?;
+    let sim2 = simulation::Simulation::run(&scenario, Rc::new(RefCell::new(BucketTransport::new())))?;
+    ensure!(sim1.trace() == sim2.trace(), "simulation is not deterministic");
+    Ok(())
+}
+
+#[test]
+fn run_simulation_scenario() {
+    assert!(dbg!(example_simulation()).is_ok());
+}
+
+pub fn example_channel_manager<T: Transport>(transport: T) -> Result<()>
+{
+    let transport = Rc::new(RefCell::new(transport));
+    let mut gateway = ChannelManager::new(transport.clone());
+    let mut publisher = ChannelManager::new(transport.clone());
+
+    let announcement_a = publisher.create_channel(&"AUTHORA9SEED".parse()?, false)?;
+    let announcement_b = publisher.create_channel(&"AUTHORB9SEED".parse()?, false)?;
+    gateway.join_channel(&"GATEWAY9SEED".parse()?, &announcement_a)?;
+    gateway.join_channel(&"GATEWAY9SEED".parse()?, &announcement_b)?;
+    ensure!(gateway.channels().len() == 2, "bad number of channels");
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    for announcement_link in [&announcement_a, &announcement_b].iter() {
+        let author = publisher.author_mut(&announcement_link.appinst).unwrap();
+        author.send_signed_packet(announcement_link, &public_payload, &masked_payload)?;
+    }
+
+    let state = gateway.export("pwdGateway")?;
+    let mut gateway = ChannelManager::import(&state, "pwdGateway", transport.clone())?;
+    let msgs = gateway.sync_state();
+    ensure!(msgs.len() == 2, "bad number of messages: {}", msgs.len());
+    ensure!(
+        msgs.iter().any(|(appinst, _)| *appinst == announcement_a.appinst)
+            && msgs.iter().any(|(appinst, _)| *appinst == announcement_b.appinst),
+        "messages of a channel not fetched"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_channel_manager_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_channel_manager(transport)).is_ok());
+}
+
+pub fn example_unified_user<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    // Alice authors channel A and subscribes to channel B of Bob, all users are of the same type.
+    let mut alice_a = User::new(&"ALICE9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut alice_b = User::new(&"ALICE9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut bob_b = User::new(&"BOB9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    ensure!(alice_a.role().is_none(), "unregistered user has a role");
+
+    alice_a.create_channel(0)?;
+    bob_b.create_channel(0)?;
+    let announcement_a = alice_a.send_announce()?;
+    let announcement_b = bob_b.send_announce()?;
+    ensure!(alice_a.role() == Some(Role::Author), "channel creator is not the author");
+
+    alice_b.receive_announcement(&announcement_b)?;
+    ensure!(alice_b.role() == Some(Role::Subscriber), "announcement receiver is not a subscriber");
+    ensure!(alice_b.send_announce().is_err(), "subscriber announced a channel");
+    ensure!(
+        alice_b.send_keyload_for_everyone(&announcement_b).is_err(),
+        "subscriber sent a keyload"
+    );
+    ensure!(alice_a.send_subscribe(&announcement_a).is_err(), "author subscribed to own channel");
+
+    let subscribe_link = alice_b.send_subscribe(&announcement_b)?;
+    bob_b.receive_subscribe(&subscribe_link)?;
+    let (keyload_link, _) = bob_b.send_keyload_for_everyone(&announcement_b)?;
+    ensure!(alice_b.receive_keyload(&keyload_link)?, "subscriber has no access to keyload");
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = alice_a.send_signed_packet(&announcement_a, &public_payload, &masked_payload)?;
+    let mut bob_a = User::new(&"BOB9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    bob_a.receive_announcement(&announcement_a)?;
+    let (_, _, unwrapped_masked) = bob_a.receive_signed_packet(&packet_link)?;
+    ensure!(unwrapped_masked == masked_payload, "bad masked payload");
+
+    ensure!(Author::from_user(bob_a).is_err(), "subscriber accepted as Author");
+    ensure!(Subscriber::from_user(alice_b).is_ok(), "subscriber not accepted as Subscriber");
+    ensure!(Author::from_user(alice_a).is_ok(), "channel creator not accepted as Author");
+    Ok(())
+}
+
+#[test]
+fn run_unified_user_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_unified_user(transport)).is_ok());
+}
+
+pub fn example_branch_delegation<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let multi_branching = false;
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, multi_branching, transport.clone());
+    let mut operator = Subscriber::new(&"OPERATOR9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut reader = Subscriber::new(&"READER9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    operator.receive_announcement(&announcement_link)?;
+    reader.receive_announcement(&announcement_link)?;
+
+    println!("delegate branch");
+    let (delegation_link, _) = author.send_branch_delegation(&announcement_link, operator.get_pk())?;
+    ensure!(
+        operator.receive_branch_delegation(&delegation_link)? == *operator.get_pk(),
+        "bad delegate"
+    );
+    ensure!(
+        reader.receive_branch_delegation(&delegation_link)? == *operator.get_pk(),
+        "bad delegate"
+    );
+
+    println!("delegated keyload");
+    ensure!(
+        operator.send_keyload_for_everyone(&announcement_link).is_err(),
+        "delegate sent keyload outside of its branch"
+    );
+    ensure!(
+        reader.send_keyload_for_everyone(&delegation_link).is_err(),
+        "non-delegate sent keyload in delegated branch"
+    );
+    operator.add_recipient(reader.get_pk().clone())?;
+    let (keyload_link, _) = operator.send_keyload(&delegation_link, &Vec::new(), &vec![reader.get_pk().clone()])?;
+    ensure!(reader.receive_keyload(&keyload_link)?, "reader has no access to delegated keyload");
+
+    println!("packet in delegated branch");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = operator.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (_, _, unwrapped_masked) = reader.receive_signed_packet(&packet_link)?;
+    ensure!(unwrapped_masked == masked_payload, "bad masked payload");
+
+    Ok(())
+}
+
+#[test]
+fn run_branch_delegation_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_branch_delegation(transport)).is_ok());
+}
+
+pub fn example_read_only_keyload<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let multi_branching = false;
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, multi_branching, transport.clone());
+    let mut writer = Subscriber::new(&"WRITER9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut reader = Subscriber::new(&"READER9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    for subscriber in [&mut writer, &mut reader].iter_mut() {
+        subscriber.receive_announcement(&announcement_link)?;
+        let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+        author.receive_subscribe(&subscribe_link)?;
+    }
+
+    println!("keyload");
+    let recipients = vec![writer.get_pk().clone(), reader.get_pk().clone()];
+    let read_only = vec![reader.get_pk().clone()];
+    let (keyload_link, _) =
+        author.send_keyload_with_read_only(&announcement_link, &Vec::new(), &recipients, &read_only)?;
+    ensure!(writer.receive_keyload(&keyload_link)?, "writer has no access to keyload");
+    ensure!(reader.receive_keyload(&keyload_link)?, "reader has no access to keyload");
+
+    println!("signed packets");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (writer_packet_link, _) = writer.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (_, _, unwrapped_masked) = reader.receive_signed_packet(&writer_packet_link)?;
+    ensure!(unwrapped_masked == masked_payload, "bad masked payload");
+    author.receive_signed_packet(&writer_packet_link)?;
+    let (reader_packet_link, _) = reader.send_signed_packet(&writer_packet_link, &public_payload, &masked_payload)?;
+    ensure!(
+        writer.receive_signed_packet(&reader_packet_link).is_err(),
+        "signed packet of read-only recipient accepted"
+    );
+
+    println!("tagged packets");
+    let (reader_packet_link, _) = reader.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    ensure!(
+        writer.receive_tagged_packet(&reader_packet_link).is_err(),
+        "tagged packet linked to the keyload accepted"
+    );
+    let (reader_packet_link, _) = reader.send_tagged_packet(&writer_packet_link, &public_payload, &masked_payload)?;
+    ensure!(
+        author.receive_tagged_packet(&reader_packet_link).is_err(),
+        "tagged packet in the branch accepted"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_read_only_keyload_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_read_only_keyload(transport)).is_ok());
+}
+
+pub fn example_rate_limit<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let multi_branching = false;
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, multi_branching, transport.clone());
+    let mut publisher = Subscriber::new(&"PUBLISHER9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut reader = Subscriber::new(&"READER9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    for subscriber in [&mut publisher, &mut reader].iter_mut() {
+        subscriber.receive_announcement(&announcement_link)?;
+        let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+        author.receive_subscribe(&subscribe_link)?;
+    }
+
+    println!("keyload with rate policy");
+    let policy = RatePolicy::new(2, 3_600_000);
+    author.set_rate_policy(policy);
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(
+        publisher.receive_keyload(&keyload_link)?,
+        "publisher has no access to keyload"
+    );
+    ensure!(reader.receive_keyload(&keyload_link)?, "reader has no access to keyload");
+    ensure!(
+        reader.branch_rate_policy(&keyload_link) == Some(&policy),
+        "rate policy not adopted"
+    );
+    reader.set_rate_action(RateAction::Drop);
+
+    println!("keyload of another branch without rate policy");
+    author.set_rate_policy(RatePolicy::default());
+    let (other_keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(
+        publisher.receive_keyload(&other_keyload_link)?,
+        "publisher has no access to other keyload"
+    );
+    ensure!(
+        reader.receive_keyload(&other_keyload_link)?,
+        "reader has no access to other keyload"
+    );
+    ensure!(
+        reader.branch_rate_policy(&other_keyload_link).is_none(),
+        "other branch limited"
+    );
+    ensure!(
+        reader.branch_rate_policy(&keyload_link) == Some(&policy),
+        "rate policy replaced by other keyload"
+    );
+
+    println!("signed packets");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let mut results = Vec::new();
+    let mut link_to = keyload_link.clone();
+    for _ in 0..3 {
+        let (packet_link, _) = publisher.send_signed_packet(&link_to, &public_payload, &masked_payload)?;
+        results.push(reader.receive_signed_packet(&packet_link).is_ok());
+        // Packets linked to packets of the branch are accounted in the branch.
+        link_to = packet_link;
+    }
+    ensure!(results == vec![true, true, false], "rate policy not enforced: {:?}", results);
+    ensure!(
+        reader.flagged_publishers() == &[publisher.get_pk().clone()][..],
+        "publisher not flagged"
+    );
+
+    println!("signed packets of the other branch");
+    for _ in 0..3 {
+        let (packet_link, _) = publisher.send_signed_packet(&other_keyload_link, &public_payload, &masked_payload)?;
+        ensure!(
+            reader.receive_signed_packet(&packet_link).is_ok(),
+            "packet of unlimited branch dropped"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn run_rate_limit_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_rate_limit(transport)).is_ok());
+}
+
+pub fn example_priority() -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(PriorityTransport::new(BucketTransport::new(), None)));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    println!("rate-limited transport");
+    transport.borrow_mut().set_budget(Some(0));
+    let telemetry = Bytes("TELEMETRY".as_bytes().to_vec());
+    let alert = Bytes("ALERT".as_bytes().to_vec());
+    let (telemetry_link, _) = author.send_signed_packet(&announcement_link, &telemetry, &Bytes::default())?;
+    author.set_priority(3)?;
+    let (alert_link, _) = author.send_signed_packet(&announcement_link, &alert, &Bytes::default())?;
+    ensure!(transport.borrow().queued() == vec![1, 0, 0, 1], "messages not queued by priority");
+
+    println!("alert sent first");
+    transport.borrow_mut().refill::<Address>(1)?;
+    let (_, public_payload, _) = subscriber.receive_signed_packet(&alert_link)?;
+    ensure!(public_payload == alert, "bad alert payload");
+    ensure!(subscriber.receive_signed_packet(&telemetry_link).is_err(), "telemetry not delayed");
+
+    transport.borrow_mut().refill::<Address>(1)?;
+    let (_, public_payload, _) = subscriber.receive_signed_packet(&telemetry_link)?;
+    ensure!(public_payload == telemetry, "bad telemetry payload");
+
+    Ok(())
+}
+
+#[test]
+fn run_priority_scenario() {
+    assert!(dbg!(example_priority()).is_ok());
+}
+
+struct EventLog(Rc<RefCell<Vec<&'static str>>>);
+
+impl UserEvents for EventLog {
+    fn on_subscription(&mut self, _link: &Address, _subscriber_pk: &PublicKey) {
+        self.0.borrow_mut().push("subscription");
+    }
+
+    fn on_subscription_rejected(&mut self, _link: &Address, _subscriber_pk: &PublicKey) {
+        self.0.borrow_mut().push("rejected subscription");
+    }
+
+    fn on_keyload(&mut self, _link: &Address, allowed: bool) {
+        self.0.borrow_mut().push(if allowed { "keyload" } else { "foreign keyload" });
+    }
+
+    fn on_packet(&mut self, _msg: &UnwrappedMessage) {
+        self.0.borrow_mut().push("packet");
+    }
+
+    fn on_sequence_violation(
+        &mut self,
+        _link: &Address,
+        _publisher_pk: &PublicKey,
+        violation: &SequenceViolation,
+    ) {
+        self.0.borrow_mut().push(match violation {
+            SequenceViolation::Replay { .. } => "replay",
+            SequenceViolation::OutOfOrder { .. } => "out of order",
+            SequenceViolation::Gap { .. } => "gap",
+        });
+    }
+}
+
+pub fn example_user_events<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let author_log = Rc::new(RefCell::new(Vec::new()));
+    let subscriber_log = Rc::new(RefCell::new(Vec::new()));
+    author.register_events(EventLog(author_log.clone()));
+    subscriber.register_events(EventLog(subscriber_log.clone()));
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+    ensure!(*author_log.borrow() == vec!["subscription"], "subscription not notified");
+
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    author.send_signed_packet(&keyload_link, &public_payload, &Bytes::default())?;
+
+    let msgs = subscriber.sync_state();
+    ensure!(msgs.len() == 2, "messages not found: {}", msgs.len());
+    ensure!(*subscriber_log.borrow() == vec!["keyload", "packet"], "events: {:?}", subscriber_log.borrow());
+
+    Ok(())
+}
+
+#[test]
+fn run_user_events_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_user_events(transport)).is_ok());
+}
+
+
+pub fn example_iter_from<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    let payloads: Vec<Bytes> = (0..3).map(|i| Bytes(vec![i; 8])).collect();
+    let mut packet_links = Vec::new();
+    for public_payload in payloads.iter() {
+        let (packet_link, _) = author.send_signed_packet(&announcement_link, public_payload, &Bytes::default())?;
+        packet_links.push(packet_link);
+    }
+
+    println!("whole history");
+    let msgs: Vec<UnwrappedMessage> = subscriber.iter_from(&announcement_link)?.collect();
+    ensure!(msgs.len() == 1 + payloads.len(), "lost messages: {}", msgs.len());
+    ensure!(matches!(msgs[0].body, MessageContent::Announce), "history does not start with announcement");
+
+    println!("history from the second packet");
+    let msgs: Vec<UnwrappedMessage> = subscriber.iter_from(&packet_links[1])?.collect();
+    ensure!(msgs.len() == 2, "bad number of messages: {}", msgs.len());
+    for (msg, public_payload) in msgs.iter().zip(payloads[1..].iter()) {
+        match &msg.body {
+            MessageContent::SignedPacket { public_payload: p, .. } => {
+                ensure!(p == public_payload, "messages out of order")
+            }
+            _ => ensure!(false, "bad message type"),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn run_iter_from_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_iter_from(transport)).is_ok());
+}
+
+
+pub fn example_export_graph<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let multi_branching = true;
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, multi_branching, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+    let (keyload_link, keyload_seq) = author.send_keyload_for_everyone(&announcement_link)?;
+    let keyload_seq = keyload_seq.ok_or_else(|| anyhow::anyhow!("keyload not sequenced"))?;
+
+    let graph = author.export_graph()?;
+    ensure!(graph.nodes.len() == 4, "bad number of nodes: {}", graph.nodes.len());
+    ensure!(
+        graph
+            .edges
+            .contains(&(keyload_link.msgid.clone(), announcement_link.msgid.clone(), EdgeKind::Link)),
+        "keyload not linked to announcement"
+    );
+    ensure!(
+        graph
+            .edges
+            .contains(&(keyload_seq.msgid.clone(), keyload_link.msgid.clone(), EdgeKind::Sequence)),
+        "keyload not sequenced"
+    );
+
+    let dot = graph.to_dot();
+    ensure!(dot.starts_with("digraph channel {"), "bad DOT: {}", dot);
+    let json = graph.to_json();
+    ensure!(json.contains("\"type\":\"Keyload\""), "bad JSON: {}", json);
+
+    Ok(())
+}
+
+#[test]
+fn run_export_graph_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_export_graph(transport)).is_ok());
+}
+
+
+pub fn example_verify_lineage<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    let (signed_packet_link, _) = author.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (tagged_packet_link, _) = author.send_tagged_packet(&signed_packet_link, &public_payload, &masked_payload)?;
+    ensure!(subscriber.receive_keyload(&keyload_link)?, "subscriber has no access to keyload");
+    subscriber.receive_signed_packet(&signed_packet_link)?;
+    subscriber.receive_tagged_packet(&tagged_packet_link)?;
+
+    let proof = subscriber.verify_lineage(&tagged_packet_link)?;
+    let links: Vec<&Address> = proof.steps.iter().map(|step| &step.link).collect();
+    ensure!(
+        links == vec![&tagged_packet_link, &signed_packet_link, &keyload_link, &announcement_link],
+        "bad lineage"
+    );
+    ensure!(proof.steps[0].authentication == Authentication::Mac, "tagged packet not authenticated with MAC");
+    ensure!(
+        proof.signers().iter().all(|pk| *pk == author.get_pk()),
+        "lineage not signed by author"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_verify_lineage_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_verify_lineage(transport)).is_ok());
+}
+
+
+pub fn example_verify_only<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut relay = Subscriber::new(&"RELAY9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut reader = Subscriber::new(&"READER9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    relay.receive_announcement(&announcement_link)?;
+    reader.receive_announcement(&announcement_link)?;
+    let subscribe_link = reader.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    println!("public packet");
+    let (public_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    ensure!(
+        relay.verify_only(&public_link)? == Some(Authentication::Signature(author.get_pk().clone())),
+        "public packet not verified"
+    );
+
+    println!("packet in a branch the relay can't read");
+    let (keyload_link, _) = author.send_keyload_for_everyone(&public_link)?;
+    ensure!(reader.receive_keyload(&keyload_link)?, "reader has no access to keyload");
+    let (private_link, _) = author.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    ensure!(relay.verify_only(&private_link)?.is_none(), "private packet verified");
+
+    println!("forged packet");
+    let mut forged = transport.borrow_mut().recv_message(&public_link)?;
+    if let Some(byte) = forged.binary.body.bytes.last_mut() {
+        *byte ^= 1;
+    }
+    ensure!(relay.verify_binary(forged).is_err(), "forged packet not detected");
+
+    println!("forged packet in a branch the relay can't read is unverified");
+    let mut forged = transport.borrow_mut().recv_message(&private_link)?;
+    if let Some(byte) = forged.binary.body.bytes.last_mut() {
+        *byte ^= 1;
+    }
+    ensure!(relay.verify_binary(forged.clone())?.is_none(), "forged private packet verified");
+    ensure!(reader.verify_binary(forged).is_err(), "forged private packet not detected by recipient");
+
+    Ok(())
+}
+
+#[test]
+fn run_verify_only_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_verify_only(transport)).is_ok());
+}
+
+
+pub fn example_mixed_versions<T: Transport>(transport: T) -> Result<()>
+{
+    use crate::api::tangle::{
+        Message,
+        MsgInfo,
+    };
+    use iota_streams_app::message::{
+        has_keyload_policies,
+        STREAMS_1_VER,
+        STREAMS_VER,
+    };
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let mut author = author.into_user();
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    println!("packet wrapped with the first version");
+    let wrapped = {
+        let mut prepared = author
+            .user
+            .prepare_signed_packet(&announcement_link.msgid, &public_payload, &masked_payload)?;
+        prepared.header = prepared.header.with_version(STREAMS_1_VER)?;
+        prepared.wrap()?
+    };
+    let old_link = wrapped.message.link.clone();
+    transport.borrow_mut().send_message(&Message::new(wrapped.message))?;
+    author.user.commit_wrapped(wrapped.wrapped, MsgInfo::SignedPacket)?;
+
+    let old_msg = transport.borrow_mut().recv_message(&old_link)?;
+    ensure!(
+        old_msg.binary.parse_header()?.version() == STREAMS_1_VER.0,
+        "packet not wrapped with the first version"
+    );
+    let (pk, public, masked) = subscriber.receive_signed_packet(&old_link)?;
+    ensure!(pk == *author.get_pk(), "bad signer of first version packet");
+    ensure!(public == public_payload && masked == masked_payload, "bad payloads of first version packet");
+
+    println!("packet wrapped with the current version linked to it");
+    let (new_link, _) = author.send_signed_packet(&old_link, &public_payload, &masked_payload)?;
+    let new_msg = transport.borrow_mut().recv_message(&new_link)?;
+    ensure!(
+        new_msg.binary.parse_header()?.version() == STREAMS_VER.0,
+        "packet not wrapped with the current version"
+    );
+    let (_, public, masked) = subscriber.receive_signed_packet(&new_link)?;
+    ensure!(public == public_payload && masked == masked_payload, "bad payloads of current version packet");
+
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+
+    println!("keyload and tagged packet wrapped with the first version");
+    let wrapped = {
+        let mut prepared = author.user.prepare_keyload_for_everyone(&announcement_link.msgid)?;
+        prepared.header = prepared.header.with_version(STREAMS_1_VER)?;
+        prepared.content.content.policies = has_keyload_policies(STREAMS_1_VER);
+        prepared.wrap()?
+    };
+    let old_keyload_link = wrapped.message.link.clone();
+    transport.borrow_mut().send_message(&Message::new(wrapped.message))?;
+    author.user.commit_wrapped(wrapped.wrapped, MsgInfo::Keyload)?;
+    ensure!(
+        subscriber.receive_keyload(&old_keyload_link)?,
+        "subscriber has no access to first version keyload"
+    );
+
+    let wrapped = {
+        let mut prepared = author
+            .user
+            .prepare_tagged_packet(&old_keyload_link.msgid, &public_payload, &masked_payload)?;
+        prepared.header = prepared.header.with_version(STREAMS_1_VER)?;
+        prepared.wrap()?
+    };
+    let old_tagged_link = wrapped.message.link.clone();
+    transport.borrow_mut().send_message(&Message::new(wrapped.message))?;
+    author.user.commit_wrapped(wrapped.wrapped, MsgInfo::TaggedPacket)?;
+    let old_msg = transport.borrow_mut().recv_message(&old_tagged_link)?;
+    ensure!(
+        old_msg.binary.parse_header()?.version() == STREAMS_1_VER.0,
+        "tagged packet not wrapped with the first version"
+    );
+    let (public, masked) = subscriber.receive_tagged_packet(&old_tagged_link)?;
+    ensure!(
+        public == public_payload && masked == masked_payload,
+        "bad payloads of first version tagged packet"
+    );
+
+    println!("keyload and tagged packet wrapped with the current version");
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    let keyload_msg = transport.borrow_mut().recv_message(&keyload_link)?;
+    ensure!(
+        keyload_msg.binary.parse_header()?.version() == STREAMS_VER.0,
+        "keyload not wrapped with the current version"
+    );
+    ensure!(
+        subscriber.receive_keyload(&keyload_link)?,
+        "subscriber has no access to current version keyload"
+    );
+    let (tagged_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (public, masked) = subscriber.receive_tagged_packet(&tagged_link)?;
+    ensure!(
+        public == public_payload && masked == masked_payload,
+        "bad payloads of current version tagged packet"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_mixed_versions_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_mixed_versions(transport)).is_ok());
+}
+
+
+pub fn example_retain_raw<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    subscriber.set_retain_raw(true);
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    author.send_signed_packet(&announcement_link, &public_payload, &Bytes::default())?;
+
+    let msgs = subscriber.sync_state();
+    ensure!(msgs.len() == 1, "bad number of messages: {}", msgs.len());
+    let published = transport.borrow_mut().recv_message(&msgs[0].link)?;
+    match subscriber.raw(&msgs[0].link) {
+        Some(raw) => ensure!(raw.binary == published.binary, "retained message differs from published one"),
+        None => ensure!(false, "message not retained"),
+    }
+
+    let archived = subscriber.take_raw();
+    ensure!(archived.len() == 2, "bad number of retained messages: {}", archived.len());
+    ensure!(subscriber.raw(&msgs[0].link).is_none(), "retained messages not taken");
+
+    Ok(())
+}
+
+#[test]
+fn run_retain_raw_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_retain_raw(transport)).is_ok());
+}
+
+
+pub fn example_sequence_policy<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let log = Rc::new(RefCell::new(Vec::new()));
+    subscriber.register_events(EventLog(log.clone()));
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let (first_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &Bytes::default())?;
+    let (second_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &Bytes::default())?;
+
+    println!("permissive");
+    subscriber.receive_signed_packet(&second_link)?;
+    subscriber.receive_signed_packet(&second_link)?;
+    ensure!(*log.borrow() == vec!["replay"], "replay not reported: {:?}", log.borrow());
+
+    println!("strictly in order");
+    subscriber.set_sequence_policy(SequencePolicy::StrictlyInOrder);
+    ensure!(subscriber.receive_signed_packet(&first_link).is_err(), "out of order packet accepted");
+    ensure!(
+        *log.borrow() == vec!["replay", "out of order"],
+        "out of order packet not reported: {:?}",
+        log.borrow()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_sequence_policy_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_sequence_policy(transport)).is_ok());
+}
+
+
+pub fn example_milestone_timestamp() -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let confirmed = Rc::new(RefCell::new(BucketTransport::new()));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, confirmed.clone());
+
+    let announcement_link = author.send_announce()?;
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &Bytes::default())?;
+
+    // Transport confirming the packet only, as a client would after fetching it from a node.
+    let milestone = Milestone {
+        index: 7,
+        timestamp: 1_600_000_000,
+    };
+    let announcement = transport.borrow_mut().recv_message(&announcement_link)?;
+    let mut packet = transport.borrow_mut().recv_message(&packet_link)?;
+    packet.milestone = Some(milestone);
+    confirmed.borrow_mut().send_message(&announcement)?;
+    confirmed.borrow_mut().send_message(&packet)?;
+
+    subscriber.receive_announcement(&announcement_link)?;
+    let unwrapped = subscriber.receive_msg(&packet_link, None)?;
+    ensure!(
+        unwrapped.timestamp() == Some(milestone),
+        "milestone not propagated: {:?}",
+        unwrapped.timestamp()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_milestone_timestamp_scenario() {
+    assert!(dbg!(example_milestone_timestamp()).is_ok());
+}
+
+
+pub fn example_buffer_reuse<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    // Buffers of longer messages are reused for shorter ones and vice versa.
+    for len in [2000_usize, 10, 3000, 0].iter() {
+        let public_payload = Bytes(vec![*len as u8; *len]);
+        let (link, _) = author.send_signed_packet(&announcement_link, &public_payload, &Bytes::default())?;
+        let (_pk, unwrapped_public, _) = subscriber.receive_signed_packet(&link)?;
+        ensure!(
+            unwrapped_public == public_payload,
+            "payload of {} bytes mismatch",
+            len
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn run_buffer_reuse_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_buffer_reuse(transport)).is_ok());
+}
+
+
+pub fn example_lazy_payloads<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    subscriber.set_lazy_payloads(true);
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let (second_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+
+    let msgs = subscriber.fetch_next_msgs();
+    ensure!(msgs.len() == 1, "expected one message, found {}", msgs.len());
+    match &msgs[0].body {
+        MessageContent::LazyPacket { pk, .. } => ensure!(pk.as_ref() == Some(author.get_pk()), "wrong publisher"),
+        _ => return Err(anyhow::anyhow!("packet not lazy")),
+    }
+    ensure!(msgs[0].masked_payload()? == masked_payload, "lazy masked payload mismatch");
+    ensure!(msgs[0].masked_payload()? == masked_payload, "cached masked payload mismatch");
+
+    // Typed receive decrypts regardless.
+    let (_pk, _public, unwrapped_masked) = subscriber.receive_signed_packet(&second_link)?;
+    ensure!(unwrapped_masked == masked_payload, "masked payload mismatch");
+
+    Ok(())
+}
+
+#[test]
+fn run_lazy_payloads_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_lazy_payloads(transport)).is_ok());
+}
+
+pub fn example_session_cache<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::{
+        Psk,
+        PskId,
+    };
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber_a = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriber_b = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber_a.receive_announcement(&announcement_link)?;
+    subscriber_b.receive_announcement(&announcement_link)?;
+    let subscribe_link = subscriber_a.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+
+    let pskid = PskId::clone_from_slice(&[3; 16]);
+    let psk = Psk::clone_from_slice(&[4; 32]);
+    author.store_psk(pskid.clone(), psk.clone());
+
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(subscriber_a.receive_keyload(&keyload_link)?, "subscriberA failed to unwrap keyload");
+    ensure!(!subscriber_b.receive_keyload(&keyload_link)?, "subscriberB unwrapped keyload");
+    ensure!(subscriber_a.cached_sessions() == 1, "keyload session not cached");
+    ensure!(subscriber_b.cached_sessions() == 0, "keyload miss cached");
+
+    // Cached session is joined again.
+    ensure!(subscriber_a.receive_keyload(&keyload_link)?, "cached keyload not allowed");
+
+    // Misses are not cached, the keyload is unwrapped with a pre-shared key stored later.
+    subscriber_b.store_psk(pskid, psk);
+    ensure!(
+        subscriber_b.receive_keyload(&keyload_link)?,
+        "subscriberB failed to unwrap keyload with psk"
+    );
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (tagged_packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (_, unwrapped_masked) = subscriber_a.receive_tagged_packet(&tagged_packet_link)?;
+    ensure!(unwrapped_masked == masked_payload, "masked payload mismatch");
+
+    subscriber_a.clear_session_cache();
+    ensure!(subscriber_a.cached_sessions() == 0, "session cache not cleared");
+    ensure!(subscriber_a.receive_keyload(&keyload_link)?, "subscriberA failed to unwrap keyload again");
+
+    subscriber_a.set_session_cache_capacity(0);
+    ensure!(subscriber_a.cached_sessions() == 0, "session cache not capped");
+
+    Ok(())
+}
+
+#[test]
+fn run_session_cache_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_session_cache(transport)).is_ok());
+}
+
+pub fn example_memory_footprint<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let initial = subscriber.memory_footprint();
+    ensure!(initial.links == 1, "expected announcement link, found {}", initial.links);
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (signed_packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    subscriber.receive_signed_packet(&signed_packet_link)?;
+
+    let footprint = subscriber.memory_footprint();
+    ensure!(footprint.links == 2, "expected 2 links, found {}", footprint.links);
+    ensure!(footprint.link_store == 2 * initial.link_store, "link store size mismatch");
+    ensure!(footprint.total() > initial.total(), "footprint did not grow");
+    ensure!(author.memory_footprint().links == 2, "author link store size mismatch");
+
+    Ok(())
+}
+
+#[test]
+fn run_memory_footprint_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_memory_footprint(transport)).is_ok());
+}
+
+pub fn example_prune_before<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (first_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let (checkpoint_link, _) = author.send_signed_packet(&first_link, &public_payload, &masked_payload)?;
+    subscriber.receive_signed_packet(&first_link)?;
+    subscriber.receive_signed_packet(&checkpoint_link)?;
+
+    let pruned = subscriber.prune_before(&checkpoint_link)?;
+    ensure!(pruned == 1, "expected 1 pruned message, found {}", pruned);
+    ensure!(subscriber.memory_footprint().links == 2, "pruned message still stored");
+
+    // Messages linked to the announcement or the checkpoint are still processed.
+    let (link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    subscriber.receive_signed_packet(&link)?;
+    let (link, _) = author.send_signed_packet(&checkpoint_link, &public_payload, &masked_payload)?;
+    subscriber.receive_signed_packet(&link)?;
+
+    let (link, _) = author.send_signed_packet(&first_link, &public_payload, &masked_payload)?;
+    match subscriber.receive_signed_packet(&link) {
+        Err(e) => ensure!(format!("{:#}", e).contains("pruned"), "unclear error: {:#}", e),
+        Ok(_) => return Err(anyhow::anyhow!("message linked to pruned message unwrapped")),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn run_prune_before_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_prune_before(transport)).is_ok());
+}
+
+pub fn example_checkpoint<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    for _ in 0..3 {
+        author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    }
+    let (checkpoint_link, _) = author.send_checkpoint(&announcement_link)?;
+    let latest_payload = Bytes("LATESTPAYLOAD".as_bytes().to_vec());
+    author.send_signed_packet(&announcement_link, &public_payload, &latest_payload)?;
+
+    subscriber.receive_announcement(&announcement_link)?;
+    let publishers = subscriber.receive_checkpoint(&checkpoint_link)?;
+    ensure!(publishers == 1, "expected 1 publisher in checkpoint, found {}", publishers);
+
+    // Only the packet after the checkpoint is fetched.
+    let msgs = subscriber.sync_state();
+    ensure!(msgs.len() == 1, "expected 1 message after checkpoint, found {}", msgs.len());
+    match &msgs[0].body {
+        MessageContent::SignedPacket { masked_payload, .. } => {
+            ensure!(*masked_payload == latest_payload, "wrong message after checkpoint")
+        }
+        _ => return Err(anyhow::anyhow!("expected signed packet after checkpoint")),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn run_checkpoint_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_checkpoint(transport)).is_ok());
+}
+
+pub fn example_sync_pipelined<T: Transport + Clone + Send + 'static>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let payloads: Vec<Bytes> = (0..5).map(|i| Bytes(vec![i; 8])).collect();
+    for masked_payload in payloads.iter() {
+        author.send_signed_packet(&announcement_link, &Bytes::default(), masked_payload)?;
+    }
+
+    let fetcher = transport.borrow().clone();
+    let msgs = subscriber.sync_state_pipelined(fetcher);
+    ensure!(msgs.len() == payloads.len(), "expected {} messages, found {}", payloads.len(), msgs.len());
+    for (msg, payload) in msgs.iter().zip(payloads.iter()) {
+        match &msg.body {
+            MessageContent::SignedPacket { masked_payload, .. } => {
+                ensure!(masked_payload == payload, "messages synced out of order")
+            }
+            _ => return Err(anyhow::anyhow!("expected signed packet")),
+        }
+    }
+    ensure!(subscriber.sync_state().is_empty(), "state not fully synced");
+
+    Ok(())
+}
+
+#[test]
+fn run_sync_pipelined_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_sync_pipelined(transport)).is_ok());
+}
+
+pub fn example_fetch_from<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let multi_branching = true;
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, multi_branching, transport.clone());
+    let mut device_a = Subscriber::new(&"DEVICEA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut device_b = Subscriber::new(&"DEVICEB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut reader = Subscriber::new(&"READER9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    for subscriber in [&mut device_a, &mut device_b, &mut reader].iter_mut() {
+        subscriber.receive_announcement(&announcement_link)?;
+        let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+        author.receive_subscribe(&subscribe_link)?;
+    }
+
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    for subscriber in [&mut device_a, &mut device_b, &mut reader].iter_mut() {
+        ensure!(subscriber.receive_keyload(&keyload_link)?, "subscriber has no access to keyload");
+    }
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    for _ in 0..2 {
+        device_a.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+        device_b.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    }
+
+    println!("fetch packets of device A");
+    let device_a_pk = device_a.get_pk().clone();
+    let mut msgs = Vec::new();
+    loop {
+        let next_msgs = reader.fetch_next_msgs_from(&device_a_pk);
+        if next_msgs.is_empty() {
+            break;
+        }
+        msgs.extend(next_msgs);
+    }
+    let packets = msgs.iter().filter(|msg| matches!(msg.body, MessageContent::TaggedPacket { .. })).count();
+    ensure!(packets == 2, "expected 2 packets of device A, found {}", packets);
+
+    println!("packets of device B are still pending");
+    let msgs = reader.sync_state();
+    let packets = msgs.iter().filter(|msg| matches!(msg.body, MessageContent::TaggedPacket { .. })).count();
+    ensure!(packets == 2, "expected 2 packets of device B, found {}", packets);
+
+    Ok(())
+}
+
+#[test]
+fn run_fetch_from_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_fetch_from(transport)).is_ok());
+}
+
+pub fn example_fetch_filter<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    for i in 0..4u8 {
+        author.set_priority(i % 2)?;
+        author.send_signed_packet(&announcement_link, &public_payload, &Bytes(vec![i]))?;
+    }
+
+    println!("fetch urgent packets only");
+    subscriber.set_fetch_filter(|header| header.get_priority() > 0);
+    let msgs = subscriber.sync_state();
+    ensure!(msgs.len() == 2, "expected 2 urgent packets, found {}", msgs.len());
+    for msg in msgs.iter() {
+        match &msg.body {
+            MessageContent::SignedPacket { masked_payload, .. } => {
+                ensure!(masked_payload.0[0] % 2 == 1, "packet not matching filter fetched")
+            }
+            _ => return Err(anyhow::anyhow!("expected signed packet")),
+        }
+    }
+
+    println!("cursors advanced past skipped packets");
+    subscriber.clear_fetch_filter();
+    author.send_signed_packet(&announcement_link, &public_payload, &Bytes(vec![4]))?;
+    let msgs = subscriber.sync_state();
+    ensure!(msgs.len() == 1, "expected 1 new packet, found {}", msgs.len());
+
+    Ok(())
+}
+
+#[test]
+fn run_fetch_filter_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_fetch_filter(transport)).is_ok());
+}
+
+pub fn example_psk_groups<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::{
+        pskid_from_psk,
+        Psk,
+        PskId,
+    };
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut group_a = Subscriber::new(&"GROUPA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut group_b = Subscriber::new(&"GROUPB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut outsider = Subscriber::new(&"OUTSIDER9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let psk_a = Psk::clone_from_slice(&[2; 32]);
+    let psk_b = Psk::clone_from_slice(&[4; 32]);
+    let (pskid_a, pskid_b) = (pskid_from_psk(&psk_a), pskid_from_psk(&psk_b));
+    ensure!(pskid_a != pskid_b, "pre-shared key identifiers collide");
+    author.store_psk(pskid_a.clone(), psk_a.clone());
+    author.store_psk(pskid_b.clone(), psk_b.clone());
+    group_a.store_psk(pskid_a.clone(), psk_a);
+    group_b.store_psk(pskid_b.clone(), psk_b);
+    outsider.store_psk(PskId::clone_from_slice(&[5; 16]), Psk::clone_from_slice(&[6; 32]));
+
+    let announcement_link = author.send_announce()?;
+    for subscriber in [&mut group_a, &mut group_b, &mut outsider].iter_mut() {
+        subscriber.receive_announcement(&announcement_link)?;
+    }
+
+    ensure!(
+        author
+            .send_keyload(&announcement_link, &vec![PskId::clone_from_slice(&[7; 16])], &Vec::new())
+            .is_err(),
+        "keyload for unknown pre-shared key sent"
+    );
+
+    println!("keyload for both groups");
+    let (keyload_link, _) = author.send_keyload(&announcement_link, &vec![pskid_a, pskid_b], &Vec::new())?;
+    ensure!(group_a.receive_keyload(&keyload_link)?, "group A has no access to keyload");
+    ensure!(group_b.receive_keyload(&keyload_link)?, "group B has no access to keyload");
+    ensure!(!outsider.receive_keyload(&keyload_link)?, "outsider has access to keyload");
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    for subscriber in [&mut group_a, &mut group_b].iter_mut() {
+        let (_, unwrapped_masked) = subscriber.receive_tagged_packet(&packet_link)?;
+        ensure!(unwrapped_masked == masked_payload, "bad masked payload");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn run_psk_groups_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_psk_groups(transport)).is_ok());
+}
+
+pub fn example_reserved_slots<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+
+    println!("keyload with reserved slots");
+    let slots = author.reserve_psk_slots(2)?;
+    let slot_ids = slots.iter().map(|(pskid, _)| pskid.clone()).collect();
+    let (keyload_link, _) = author.send_keyload(&announcement_link, &slot_ids, &vec![subscriber.get_pk().clone()])?;
+    ensure!(subscriber.receive_keyload(&keyload_link)?, "subscriber has no access to keyload");
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+
+    println!("device joining with a slot key");
+    let mut device = Subscriber::new(&"DEVICE9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    device.receive_announcement(&announcement_link)?;
+    let (pskid, psk) = slots[1].clone();
+    device.store_psk(pskid, psk);
+    ensure!(device.receive_keyload(&keyload_link)?, "device has no access to keyload");
+    let (_, unwrapped_masked) = device.receive_tagged_packet(&packet_link)?;
+    ensure!(unwrapped_masked == masked_payload, "bad masked payload");
+
+    Ok(())
+}
+
+#[test]
+fn run_reserved_slots_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_reserved_slots(transport)).is_ok());
+}
+
+pub fn example_anonymous_keyload<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriberA = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberB = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut outsider = Subscriber::new(&"OUTSIDER9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    for subscriber in [&mut subscriberA, &mut subscriberB, &mut outsider].iter_mut() {
+        subscriber.receive_announcement(&announcement_link)?;
+        let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+        author.receive_subscribe(&subscribe_link)?;
+    }
+
+    println!("anonymous keyload");
+    let recipients = vec![subscriberA.get_pk().clone(), subscriberB.get_pk().clone()];
+    let (keyload_link, _) = author.send_anonymous_keyload(&announcement_link, &recipients)?;
+    {
+        let msg = transport.borrow_mut().recv_message(&keyload_link)?;
+        let bytes = &msg.binary.body.bytes;
+        ensure!(
+            !recipients.iter().any(|pk| bytes.windows(32).any(|w| w == pk.as_bytes())),
+            "recipient public key listed in anonymous keyload"
+        );
+    }
+    ensure!(subscriberA.receive_keyload(&keyload_link)?, "subscriber A has no access to keyload");
+    ensure!(subscriberB.receive_keyload(&keyload_link)?, "subscriber B has no access to keyload");
+    ensure!(!outsider.receive_keyload(&keyload_link)?, "outsider has access to keyload");
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = subscriberB.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (_, unwrapped_masked) = subscriberA.receive_tagged_packet(&packet_link)?;
+    ensure!(unwrapped_masked == masked_payload, "bad masked payload");
+    ensure!(outsider.receive_tagged_packet(&packet_link).is_err(), "outsider unwrapped tagged packet");
+
+    Ok(())
+}
+
+#[test]
+fn run_anonymous_keyload_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_anonymous_keyload(transport)).is_ok());
+}
+
+pub fn example_deniable_packet<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    ensure!(
+        subscriber
+            .send_deniable_packet(&announcement_link, &public_payload, &masked_payload)
+            .is_err(),
+        "deniable packet linked to announcement sent"
+    );
+
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(subscriber.receive_keyload(&keyload_link)?, "subscriber has no access to keyload");
+
+    let (packet_link, _) = subscriber.send_deniable_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (unwrapped_public, unwrapped_masked) = author.receive_deniable_packet(&packet_link)?;
+    ensure!(unwrapped_public == public_payload, "bad public payload");
+    ensure!(unwrapped_masked == masked_payload, "bad masked payload");
+
+    let (reply_link, _) = author.send_deniable_packet(&packet_link, &public_payload, &masked_payload)?;
+    let (_, unwrapped_masked) = subscriber.receive_deniable_packet(&reply_link)?;
+    ensure!(unwrapped_masked == masked_payload, "bad masked payload of reply");
+
+    let (tagged_link, _) = subscriber.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    ensure!(
+        author.receive_deniable_packet(&tagged_link).is_err(),
+        "tagged packet accepted as deniable packet"
+    );
+    author.receive_tagged_packet(&tagged_link)?;
+    ensure!(
+        author
+            .send_deniable_packet(&tagged_link, &public_payload, &masked_payload)
+            .is_err(),
+        "deniable packet linked to tagged packet sent"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_deniable_packet_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_deniable_packet(transport)).is_ok());
+}
+
+pub fn example_counter_sequencing<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new_with_counter_sequencing(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    ensure!(subscriber.is_counter_sequencing(), "counter sequencing not announced");
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+
+    println!("packets without sequence messages");
+    let (keyload_link, keyload_seq) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(keyload_seq.is_none(), "sequence message sent with counter sequencing");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    for i in 0..3u8 {
+        let (_, seq_link) = author.send_signed_packet(&keyload_link, &public_payload, &Bytes(vec![i]))?;
+        ensure!(seq_link.is_none(), "sequence message sent with counter sequencing");
+    }
+
+    let msgs = subscriber.sync_state();
+    ensure!(msgs.len() == 4, "expected keyload and 3 packets, found {}", msgs.len());
+
+    println!("publishers count independently");
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    subscriber.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    author.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let msgs = author.sync_state();
+    ensure!(msgs.len() == 1, "expected 1 packet of the subscriber, found {}", msgs.len());
+    let msgs = subscriber.sync_state();
+    ensure!(msgs.len() == 1, "expected 1 packet of the author, found {}", msgs.len());
+
+    Ok(())
+}
+
+#[test]
+fn run_counter_sequencing_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_counter_sequencing(transport)).is_ok());
+}
+
+pub fn example_branch_policy<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let multi_branching = true;
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, multi_branching, transport.clone());
+    let mut device_a = Subscriber::new(&"DEVICEA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut device_b = Subscriber::new(&"DEVICEB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    for subscriber in [&mut device_a, &mut device_b].iter_mut() {
+        subscriber.receive_announcement(&announcement_link)?;
+        let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+        author.receive_subscribe(&subscribe_link)?;
+    }
+
+    author.set_branch_policy(BranchPolicy::Linear);
+    let (log_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    author.set_branch_policy(BranchPolicy::Channel);
+    let (branch_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    device_a.sync_state();
+    device_b.sync_state();
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    println!("linear event log");
+    let (_, seq_link) = device_a.send_tagged_packet(&log_link, &public_payload, &masked_payload)?;
+    ensure!(seq_link.is_none(), "sequence message sent in linear branch");
+    device_b.sync_state();
+    let (_, seq_link) = device_b.send_tagged_packet(&log_link, &public_payload, &masked_payload)?;
+    ensure!(seq_link.is_none(), "sequence message sent in linear branch");
+
+    println!("per-device branch");
+    let (_, seq_link) = device_a.send_tagged_packet(&branch_link, &public_payload, &masked_payload)?;
+    ensure!(seq_link.is_some(), "no sequence message sent in multi-branch branch");
+
+    let msgs = author.sync_state();
+    let packets = msgs.iter().filter(|msg| matches!(msg.body, MessageContent::TaggedPacket { .. })).count();
+    ensure!(packets == 3, "expected 3 packets, found {}", packets);
+
+    Ok(())
+}
+
+#[test]
+fn run_branch_policy_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_branch_policy(transport)).is_ok());
+}
+
+pub fn example_threshold_author<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let (group_pk, shares) = frost::deal(&[7_u8; 64], 2, 3).ok_or_else(|| anyhow::anyhow!("dealing failed"))?;
+    let group_sign = |content_type: u8, tag: &[u8; 64], signers: &[usize], round: u8| -> Result<Signature> {
+        let context = threshold_signature_context(content_type);
+        let (nonces, commitments): (Vec<_>, Vec<_>) = signers
+            .iter()
+            .map(|i| frost::commit(&shares[*i], &[round + *i as u8; 64]))
+            .unzip();
+        let sig_shares = nonces
+            .into_iter()
+            .zip(signers)
+            .map(|(nonces, i)| frost::sign(&shares[*i], nonces, &commitments, &context, tag))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| anyhow::anyhow!("signing failed"))?;
+        frost::aggregate(&group_pk, &commitments, &sig_shares, &context, tag)
+            .ok_or_else(|| anyhow::anyhow!("aggregation failed"))
+    };
+
+    let mut coordinator = Author::new_threshold(&"COORDINATOR9SEED".parse()?, encoding, PAYLOAD_BYTES, &group_pk, transport.clone())?;
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("coordinator can't announce alone");
+    ensure!(coordinator.send_announce().is_err(), "coordinator announced without the group");
+    let tag = coordinator.threshold_announcement_tag()?;
+    ensure!(group_sign(ANNOUNCE, &tag, &[1], 1).is_err(), "single member signed for the group");
+
+    let sig = group_sign(ANNOUNCE, &tag, &[0, 2], 1)?;
+    let announcement_link = coordinator.send_threshold_announce(&sig)?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let tag = coordinator.threshold_signed_packet_tag(&announcement_link, &public_payload, &masked_payload)?;
+    let sig = group_sign(SIGNED_PACKET, &tag, &[1, 2], 2)?;
+    let (packet_link, _) =
+        coordinator.send_threshold_signed_packet(&announcement_link, &public_payload, &masked_payload, &sig)?;
+
+    let (pk, unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&packet_link)?;
+    ensure!(pk == group_pk, "packet not signed by the group");
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+
+    Ok(())
+}
+
+#[test]
+fn run_threshold_author_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_threshold_author(transport)).is_ok());
+}
+
+#[cfg(feature = "pq-sig")]
+pub fn example_hybrid_signatures<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new_hybrid(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, 3, transport.clone())?;
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("announce hybrid channel");
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    ensure!(subscriber.is_hybrid(), "subscriber didn't detect hybrid channel");
+
+    println!("sign packet with Ed25519 and MSS");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let (_pk, unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&packet_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+    ensure!(author.remaining_pq_signatures() == Some(6), "MSS leaves not used up");
+
+    println!("restore MSS key pair");
+    let dump = author.export("pwdAuthor")?;
+    let next_index = author.next_pq_signature_index().unwrap();
+    ensure!(
+        Author::import(dump.as_ref(), "pwdAuthor", transport.clone()).is_err(),
+        "hybrid author imported without MSS index"
+    );
+    let author2 = Author::import_hybrid(dump.as_ref(), "pwdAuthor", transport.clone(), next_index)?;
+    ensure!(
+        author2.remaining_pq_signatures() == Some(6),
+        "MSS leaves reused after import"
+    );
+
+    println!("refuse stale MSS key pair");
+    author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let next_index = author.next_pq_signature_index().unwrap();
+    ensure!(
+        Author::import_hybrid(dump.as_ref(), "pwdAuthor", transport.clone(), next_index).is_err(),
+        "stale hybrid author state imported"
+    );
+    Ok(())
+}
+
+#[cfg(feature = "pq-sig")]
+#[test]
+fn run_hybrid_signatures_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_hybrid_signatures(transport)).is_ok());
+}
+
+#[cfg(feature = "pq-kem")]
+pub fn example_hybrid_kem<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new_with_hybrid_kem(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber_a = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriber_b = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber_a.receive_announcement(&announcement_link)?;
+    subscriber_b.receive_announcement(&announcement_link)?;
+    ensure!(subscriber_a.is_hybrid_kem(), "hybrid key encapsulation flag not announced");
+
+    println!("subscribe with ML-KEM public key");
+    let subscribe_link = subscriber_a.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+
+    println!("keyload with hybrid key encapsulation");
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(subscriber_a.receive_keyload(&keyload_link)?, "subscriberA failed to unwrap keyload");
+    ensure!(!subscriber_b.receive_keyload(&keyload_link)?, "subscriberB unwrapped keyload");
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (tagged_packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (unwrapped_public, unwrapped_masked) = subscriber_a.receive_tagged_packet(&tagged_packet_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+
+    println!("restore ML-KEM keys");
+    let dump = author.export("pwdAuthor")?;
+    let mut author2 = Author::import(dump.as_ref(), "pwdAuthor", transport.clone())?;
+    let (keyload_link, _) = author2.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(subscriber_a.receive_keyload(&keyload_link)?, "subscriberA failed to unwrap keyload of imported author");
+    Ok(())
+}
+
+#[cfg(feature = "pq-kem")]
+#[test]
+fn run_hybrid_kem_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_hybrid_kem(transport)).is_ok());
+}
+
+pub fn example_siv_masking<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new_with_siv_masking(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    ensure!(subscriber.is_siv_masking(), "SIV masking flag not announced");
+
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(subscriber.receive_keyload(&keyload_link)?, "subscriber failed to unwrap keyload");
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    println!("signed packet with synthetic IV");
+    let (signed_packet_link, _) = author.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (_, unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&signed_packet_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+
+    println!("tagged packet with synthetic IV");
+    let (tagged_packet_link, _) = subscriber.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (unwrapped_public, unwrapped_masked) = author.receive_tagged_packet(&tagged_packet_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+    Ok(())
+}
+
+#[test]
+fn run_siv_masking_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_siv_masking(transport)).is_ok());
+}
+
+#[cfg(feature = "passphrase")]
+pub fn example_passphrase_psk<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::{
+        psk_from_passphrase,
+        pskid_from_psk,
+        PassphraseParams,
+    };
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut device = Subscriber::new(&"DEVICE9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut outsider = Subscriber::new(&"OUTSIDER9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    device.receive_announcement(&announcement_link)?;
+    outsider.receive_announcement(&announcement_link)?;
+
+    println!("derive pre-shared keys from passphrases");
+    let salt = announcement_link.appinst.as_ref();
+    let params = PassphraseParams::default();
+    let psk = psk_from_passphrase("correct horse battery staple", salt, params)?;
+    let pskid = pskid_from_psk(&psk);
+    author.store_psk(pskid.clone(), psk);
+    device.store_psk(pskid.clone(), psk_from_passphrase("correct horse battery staple", salt, params)?);
+    outsider.store_psk(pskid.clone(), psk_from_passphrase("correct horse battery stapler", salt, params)?);
+    ensure!(psk_from_passphrase("short salt", &salt[..4], params).is_err(), "short salt accepted");
+
+    let (keyload_link, _) = author.send_keyload(&announcement_link, &vec![pskid], &Vec::new())?;
+    ensure!(device.receive_keyload(&keyload_link)?, "device has no access to keyload");
+    ensure!(!outsider.receive_keyload(&keyload_link)?, "outsider has access to keyload");
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (_, unwrapped_masked) = device.receive_tagged_packet(&packet_link)?;
+    ensure!(unwrapped_masked == masked_payload, "bad masked payload");
+    Ok(())
+}
+
+#[cfg(feature = "passphrase")]
+#[test]
+fn run_passphrase_psk_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_passphrase_psk(transport)).is_ok());
+}
+
+pub fn example_builders<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    println!("invalid options are rejected");
+    ensure!(
+        Author::<Rc<RefCell<T>>>::builder().seed("AUTHOR9SEED".parse()?).build().is_err(),
+        "author built without transport"
+    );
+    ensure!(
+        Author::builder()
+            .seed("AUTHOR9SEED".parse()?)
+            .branching(true)
+            .counter_sequencing(true)
+            .transport(transport.clone())
+            .build()
+            .is_err(),
+        "multi-branch author built with counter sequencing"
+    );
+    ensure!(
+        Subscriber::builder()
+            .seed("SUBSCRIBERA9SEED".parse()?)
+            .payload_length(0)
+            .transport(transport.clone())
+            .build()
+            .is_err(),
+        "subscriber built with empty payload length"
+    );
+
+    let mut author = Author::builder()
+        .seed("AUTHOR9SEED".parse()?)
+        .encoding(encoding)
+        .counter_sequencing(true)
+        .siv_masking(true)
+        .transport(transport.clone())
+        .build()?;
+    let mut subscriber = Subscriber::builder()
+        .seed("SUBSCRIBERA9SEED".parse()?)
+        .transport(transport.clone())
+        .build()?;
+    let default_author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    ensure!(author.get_pk() == default_author.get_pk(), "builder derived different keys");
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    ensure!(
+        subscriber.is_counter_sequencing() && subscriber.is_siv_masking() && !subscriber.is_multi_branching(),
+        "options not announced"
+    );
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (signed_packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let (_, unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&signed_packet_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+    Ok(())
+}
+
+#[test]
+fn run_builders_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_builders(transport)).is_ok());
+}
+
+pub fn example_channel_config<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::ChannelConfig;
+
+    let transport = Rc::new(RefCell::new(transport));
+    let config = ChannelConfig {
+        multi_branching: true,
+        msgid_size: 24,
+        ..ChannelConfig::default()
+    };
+    ensure!(
+        ChannelConfig {
+            payload_length: 0,
+            ..config.clone()
+        }
+        .validate()
+        .is_err(),
+        "empty payload length validated"
+    );
+
+    let mut author = Author::builder()
+        .seed("AUTHOR9SEED".parse()?)
+        .config(&config)?
+        .transport(transport.clone())
+        .build()?;
+    let mut subscriber = Subscriber::builder()
+        .seed("SUBSCRIBERA9SEED".parse()?)
+        .config(&config)?
+        .transport(transport.clone())
+        .build()?;
+    let mut mismatched = Subscriber::builder()
+        .seed("SUBSCRIBERB9SEED".parse()?)
+        .config(&ChannelConfig::default())?
+        .transport(transport.clone())
+        .build()?;
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    ensure!(subscriber.is_multi_branching(), "channel not multi-branching");
+
+    println!("subscriber rejects channel with other parameters");
+    ensure!(
+        mismatched.receive_announcement(&announcement_link).is_err(),
+        "announcement with mismatched config accepted"
+    );
+    ensure!(!mismatched.is_registered(), "subscriber registered to mismatched channel");
+    Ok(())
+}
+
+#[test]
+fn run_channel_config_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_channel_config(transport)).is_ok());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn channel_config_serde() {
+    use crate::api::tangle::ChannelConfig;
+
+    let config = ChannelConfig {
+        multi_branching: true,
+        payload_length: 512,
+        ..ChannelConfig::default()
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(serde_json::from_str::<ChannelConfig>(&json).unwrap(), config);
+}
+
+pub fn example_redacted_debug<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::pskid_from_psk;
+    use iota_streams_core::format;
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let psk = Psk::from([3_u8; 32]);
+    author.store_psk(pskid_from_psk(&psk), psk.clone());
+
+    let dbg = format!("{:?}", author);
+    let secret = hex::encode(author.into_user().user.sig_kp.secret.as_bytes());
+    ensure!(!dbg.contains(&secret), "secret key printed");
+    ensure!(!dbg.contains(&hex::encode(psk)), "pre-shared key printed");
+    ensure!(dbg.contains(&hex::encode(pskid_from_psk(&psk))), "pskid not printed");
+
+    let author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    ensure!(dbg.contains(&author.fingerprint().to_string()), "fingerprint not printed");
+    let other = Author::new(&"AUTHOR9SEEDB".parse()?, encoding, PAYLOAD_BYTES, false, transport);
+    ensure!(author.fingerprint() != other.fingerprint(), "different keys with the same fingerprint");
+    Ok(())
+}
+
+#[test]
+fn run_redacted_debug_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_redacted_debug(transport)).is_ok());
+}
+
+pub fn example_seed<T: Transport>(transport: T) -> Result<()> {
+    use iota_streams_core::format;
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    println!("invalid seeds are rejected");
+    ensure!("".parse::<Seed>().is_err(), "empty seed accepted");
+    ensure!("https://chrysalis-nodes.iota.org".parse::<Seed>().is_err(), "URL accepted as seed");
+    ensure!(Seed::from_bytes(&[1_u8; 8]).is_err(), "short binary seed accepted");
+
+    let seed: Seed = "AUTHOR9SEED".parse()?;
+    ensure!(seed == "AUTHOR9SEED".parse()?, "equal seeds differ");
+    ensure!(seed != "AUTHOR9SEED9".parse()?, "different seeds equal");
+    ensure!(!format!("{:?}", seed).contains("AUTHOR"), "seed printed");
+
+    println!("string seed gives the same keys as its bytes");
+    let subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let binary_subscriber = Subscriber::new(&Seed::from_bytes(b"SUBSCRIBERA9SEED")?, encoding, PAYLOAD_BYTES, transport);
+    ensure!(subscriber.get_pk() == binary_subscriber.get_pk(), "binary seed derived different keys");
+    Ok(())
+}
+
+#[test]
+fn run_seed_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_seed(transport)).is_ok());
+}
+
+pub fn example_address_checksum<T: Transport>(transport: T) -> Result<()> {
+    use iota_streams_app::transport::tangle::AddressFormat;
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+    let mut author = Author::new_with_msgid_size(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, 24, transport)?;
+    let announcement_link = author.send_announce()?;
+
+    for format in &[AddressFormat::Trytes, AddressFormat::Hex, AddressFormat::Bech32("iota")] {
+        let s = announcement_link.to_string_with(*format)?;
+        ensure!(Address::from_str_with(&s, *format)? == announcement_link, "bad decoded address");
+
+        println!("truncated address is rejected");
+        let (link, _) = s.split_at(s.rfind(':').unwrap());
+        ensure!(Address::from_str_with(link, *format).is_err(), "address without checksum decoded");
+    }
+
+    println!("address with truncated msgid is rejected");
+    let s = announcement_link.to_string_with(AddressFormat::Hex)?;
+    let mut parts = s.split(':');
+    let (appinst, msgid, checksum) = (parts.next().unwrap(), parts.next().unwrap(), parts.next().unwrap());
+    let truncated = [appinst, &msgid[..24], checksum].join(":");
+    ensure!(truncated.parse::<Address>().is_err(), "address with truncated msgid decoded");
+    ensure!(s.parse::<Address>()? == announcement_link, "bad parsed address");
+    Ok(())
+}
+
+#[test]
+fn run_address_checksum_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_address_checksum(transport)).is_ok());
+}
+
+pub fn example_peek<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    ensure!(subscriber.peek(&announcement_link).is_err(), "announcement peeked");
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(subscriber.receive_keyload(&keyload_link)?, "subscriber failed to unwrap keyload");
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (tagged_packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+
+    println!("peeked packet is not processed");
+    let peeked = subscriber.peek(&tagged_packet_link)?;
+    ensure!(peeked.masked_payload()? == masked_payload, "bad peeked masked payload");
+    let peeked_again = subscriber.peek(&tagged_packet_link)?;
+    ensure!(peeked_again.masked_payload()? == masked_payload, "bad peeked masked payload");
+    let msgs = subscriber.fetch_next_msgs();
+    ensure!(msgs.len() == 1, "peeked packet not fetched");
+    ensure!(msgs[0].link == tagged_packet_link, "bad fetched packet");
+
+    println!("signed packet linked to a peeked packet can't be peeked before it's processed");
+    let (signed_packet_link, _) = author.send_signed_packet(&tagged_packet_link, &public_payload, &masked_payload)?;
+    let mut late_subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    late_subscriber.receive_announcement(&announcement_link)?;
+    late_subscriber.receive_keyload(&keyload_link)?;
+    late_subscriber.peek(&tagged_packet_link)?;
+    ensure!(late_subscriber.peek(&signed_packet_link).is_err(), "packet linked to peeked packet unwrapped");
+    late_subscriber.receive_tagged_packet(&tagged_packet_link)?;
+    let peeked = late_subscriber.peek(&signed_packet_link)?;
+    ensure!(peeked.masked_payload()? == masked_payload, "bad peeked masked payload");
+    Ok(())
+}
+
+#[test]
+fn run_peek_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_peek(transport)).is_ok());
+}
+
+#[cfg(feature = "envelope")]
+pub fn example_envelope<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::{
+        Envelope,
+        ToEnvelope,
+    };
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport);
+
+    let announcement_link = author.send_announce()?;
+    let announcement = subscriber.receive_msg(&announcement_link, None)?;
+    let envelope = announcement.to_envelope()?;
+    ensure!(envelope.kind == "announce", "bad envelope type: {}", envelope.kind);
+    ensure!(envelope.public_payload.is_none(), "announcement with payload");
+
+    let public_payload = Bytes(b"public".to_vec());
+    let masked_payload = Bytes(b"masked".to_vec());
+    let (signed_packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let packet = subscriber.receive_msg(&signed_packet_link, None)?;
+    let envelope = packet.to_envelope()?;
+    ensure!(envelope.kind == "signed_packet", "bad envelope type: {}", envelope.kind);
+    ensure!(envelope.link.parse::<Address>()? == signed_packet_link, "bad envelope link");
+    ensure!(
+        envelope.pk.as_deref() == Some(hex::encode(author.get_pk().as_bytes()).as_str()),
+        "bad envelope pk"
+    );
+    ensure!(envelope.public_payload.as_deref() == Some("cHVibGlj"), "bad envelope public payload");
+    ensure!(envelope.masked_payload.as_deref() == Some("bWFza2Vk"), "bad envelope masked payload");
+
+    let json = packet.to_json()?;
+    ensure!(json.contains("\"type\":\"signed_packet\""), "bad JSON: {}", json);
+    ensure!(json.contains("\"timestamp\":null"), "absent field omitted: {}", json);
+    ensure!(Envelope::from_json(&json)? == envelope, "bad JSON roundtrip");
+    ensure!(Envelope::from_cbor(&packet.to_cbor()?)? == envelope, "bad CBOR roundtrip");
+    Ok(())
+}
+
+#[cfg(feature = "envelope")]
+#[test]
+fn run_envelope_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_envelope(transport)).is_ok());
+}
+
+#[cfg(feature = "proto")]
+pub fn example_proto<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::proto::{
+        self,
+        pb,
+    };
+    use core::convert::TryFrom;
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport);
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let link = pb::MessageLink::from(&announcement_link);
+    ensure!(Address::try_from(link.clone())? == announcement_link, "bad converted link");
+    let mut truncated = link;
+    truncated.msgid.pop();
+    ensure!(Address::try_from(truncated).is_err(), "link with truncated msgid converted");
+
+    let public_payload = Bytes(b"public".to_vec());
+    let masked_payload = Bytes(b"masked".to_vec());
+    let (signed_packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let packet = subscriber.receive_msg(&signed_packet_link, None)?;
+    let decoded = proto::decode_unwrapped(&proto::encode_unwrapped(&packet)?)?;
+    ensure!(decoded.link == packet.link, "bad decoded link");
+    match decoded.body {
+        MessageContent::SignedPacket {
+            pk,
+            public_payload: p,
+            masked_payload: m,
+        } => {
+            ensure!(&pk == author.get_pk(), "bad decoded pk");
+            ensure!(p == public_payload && m == masked_payload, "bad decoded payloads");
+        }
+        _ => ensure!(false, "bad decoded message type"),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "proto")]
+#[test]
+fn run_proto_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_proto(transport)).is_ok());
+}
+
+#[cfg(feature = "test-vectors")]
+pub fn example_test_vectors() -> Result<()> {
+    use super::test_vectors;
+
+    let vectors = test_vectors::generate()?;
+    ensure!(vectors == test_vectors::generate()?, "test vectors are not deterministic");
+    ensure!(vectors.len() == 7, "bad number of test vectors: {}", vectors.len());
+    test_vectors::verify(&vectors)?;
+
+    let parsed = test_vectors::parse(&test_vectors::to_text(&vectors))?;
+    ensure!(parsed == vectors, "bad parsed test vectors");
+
+    let mut tampered = vectors;
+    tampered[2].message[10] ^= 1;
+    ensure!(test_vectors::verify(&tampered).is_err(), "tampered test vector verified");
+    Ok(())
+}
+
+#[cfg(feature = "test-vectors")]
+#[test]
+fn run_test_vectors_scenario() {
+    assert!(dbg!(example_test_vectors()).is_ok());
+}
+
+pub fn example_state_migration<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    println!("migrate current state");
+    let dump = author.export("pwdAuthor")?;
+    ensure!(dump[4] == STATE_VERSION, "bad state version in header");
+    let migrated = Author::<Rc<RefCell<T>>>::migrate_state(&dump, "pwdAuthor")?;
+    ensure!(migrated == dump, "current state changed by migration");
+    let author2 = Author::import(&migrated, "pwdAuthor", transport.clone())?;
+    ensure!(author2.channel_address() == author.channel_address(), "bad imported channel address");
+
+    println!("reject unknown state versions");
+    let mut newer = dump.clone();
+    newer[4] = STATE_VERSION + 1;
+    let err = Author::<Rc<RefCell<T>>>::migrate_state(&newer, "pwdAuthor").unwrap_err();
+    ensure!(
+        err.downcast_ref::<StateError>() == Some(&StateError::UnknownVersion { version: STATE_VERSION + 1 }),
+        "bad error of unknown state version: {}",
+        err
+    );
+    let err = Author::import(&newer, "pwdAuthor", transport.clone()).unwrap_err();
+    ensure!(err.downcast_ref::<StateError>().is_some(), "unknown state version imported");
+    let err = Subscriber::<Rc<RefCell<T>>>::migrate_state(&[1_u8], "pwdSub").unwrap_err();
+    ensure!(err.downcast_ref::<StateError>() == Some(&StateError::Truncated), "bad error of truncated state");
+    Ok(())
+}
+
+#[test]
+fn run_state_migration_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_state_migration(transport)).is_ok());
+}
+
+/// State of `user` as exported by release 1.0.0 of the crate: version 0 without `STATE_MAGIC`
+/// and fields of version 0 only.
+fn baseline_export<T>(user: &User<T>, flag: u8, pwd: &str) -> Result<Vec<u8>> {
+    use iota_streams_core::{
+        prelude::typenum::U32,
+        prng,
+        psk,
+        sponge::prp::PRP,
+    };
+    use iota_streams_ddml::{
+        command::*,
+        link_store::LinkStore as _,
+        types::*,
+    };
+    use crate::api::{
+        pk_store::PublicKeyStore as _,
+        psk_store::PresharedKeyStore as _,
+    };
+
+    const VERSION: u8 = 0;
+    let user = &user.user;
+    let link_store = user.link_store.borrow();
+    let buf_size = {
+        let mut ctx = sizeof::Context::<DefaultF>::new();
+        ctx
+            .absorb(Uint8(VERSION))?
+            .absorb(Uint8(flag))?
+            .mask(<&NBytes::<U32>>::from(&user.sig_kp.secret.as_bytes()[..]))?
+            .absorb(Uint8(user.flags))?
+            .absorb(<&Bytes>::from(&user.message_encoding))?
+            .absorb(Uint64(user.uniform_payload_length as u64))?
+            .absorb(Uint8(1))?
+            .absorb(<&Fallback::<Address>>::from(user.appinst.as_ref().unwrap()))?
+            .absorb(Uint8(if user.author_sig_pk.is_some() { 1 } else { 0 }))?
+        ;
+        if let Some(ref author_sig_pk) = user.author_sig_pk {
+            ctx.absorb(author_sig_pk)?;
+        }
+        let links = link_store.iter();
+        let psks = user.psk_store.iter();
+        let pks = user.pk_store.iter();
+        ctx
+            .absorb(Size(links.len()))?
+            .repeated(links.into_iter(), |ctx, (link, (s, info))| {
+                ctx
+                    .absorb(<&Fallback::<MsgId>>::from(link))?
+                    .mask(<&NBytes::<<DefaultF as PRP>::CapacitySize>>::from(s.arr()))?
+                    .absorb(<&Fallback::<MsgInfo>>::from(info))
+            })?
+            .absorb(Size(psks.len()))?
+            .repeated(psks.into_iter(), |ctx, (pskid, psk)| {
+                ctx
+                    .mask(<&NBytes<psk::PskIdSize>>::from(pskid))?
+                    .mask(<&NBytes<psk::PskSize>>::from(psk))
+            })?
+            .absorb(Size(pks.len()))?
+            .repeated(pks.into_iter(), |ctx, (pk, cursor)| {
+                ctx
+                    .absorb(pk)?
+                    .absorb(<&Fallback::<MsgId>>::from(&cursor.link))?
+                    .absorb(Uint32(cursor.branch_no))?
+                    .absorb(Uint32(cursor.seq_no))
+            })?
+            .commit()?
+            .squeeze(Mac(32))?
+        ;
+        ctx.get_size()
+    };
+
+    let mut buf = vec![0; buf_size];
+    {
+        let mut ctx = wrap::Context::<DefaultF, &mut [u8]>::new(&mut buf[..]);
+        let prng = prng::from_seed::<DefaultF>("IOTA Streams Channels app", pwd);
+        let key = NBytes::<U32>(prng.gen_arr("user export key"));
+        ctx
+            .absorb(Uint8(VERSION))?
+            .absorb(Uint8(flag))?
+            .absorb(External(&key))?
+            .mask(<&NBytes::<U32>>::from(&user.sig_kp.secret.as_bytes()[..]))?
+            .absorb(Uint8(user.flags))?
+            .absorb(<&Bytes>::from(&user.message_encoding))?
+            .absorb(Uint64(user.uniform_payload_length as u64))?
+            .absorb(Uint8(1))?
+            .absorb(<&Fallback::<Address>>::from(user.appinst.as_ref().unwrap()))?
+            .absorb(Uint8(if user.author_sig_pk.is_some() { 1 } else { 0 }))?
+        ;
+        if let Some(ref author_sig_pk) = user.author_sig_pk {
+            ctx.absorb(author_sig_pk)?;
+        }
+        let links = link_store.iter();
+        let psks = user.psk_store.iter();
+        let pks = user.pk_store.iter();
+        ctx
+            .absorb(Size(links.len()))?
+            .repeated(links.into_iter(), |ctx, (link, (s, info))| {
+                ctx
+                    .absorb(<&Fallback::<MsgId>>::from(link))?
+                    .mask(<&NBytes::<<DefaultF as PRP>::CapacitySize>>::from(s.arr()))?
+                    .absorb(<&Fallback::<MsgInfo>>::from(info))
+            })?
+            .absorb(Size(psks.len()))?
+            .repeated(psks.into_iter(), |ctx, (pskid, psk)| {
+                ctx
+                    .mask(<&NBytes<psk::PskIdSize>>::from(pskid))?
+                    .mask(<&NBytes<psk::PskSize>>::from(psk))
+            })?
+            .absorb(Size(pks.len()))?
+            .repeated(pks.into_iter(), |ctx, (pk, cursor)| {
+                ctx
+                    .absorb(pk)?
+                    .absorb(<&Fallback::<MsgId>>::from(&cursor.link))?
+                    .absorb(Uint32(cursor.branch_no))?
+                    .absorb(Uint32(cursor.seq_no))
+            })?
+            .commit()?
+            .squeeze(Mac(32))?
+        ;
+        ensure!(ctx.stream.is_empty(), "OStream has not been exhausted.");
+    }
+    Ok(buf)
+}
+
+pub fn example_baseline_state_migration<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = User::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = User::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    author.create_channel(0)?;
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+
+    println!("import baseline state");
+    let dump = baseline_export(&author, 0, "pwdAuthor")?;
+    ensure!(dump[0] == 0, "bad baseline state version");
+    let mut author2 = User::import(&dump, 0, "pwdAuthor", transport.clone())?;
+    ensure!(author2.channel_address() == author.channel_address(), "bad imported channel address");
+
+    println!("migrate baseline state");
+    let migrated = User::<Rc<RefCell<T>>>::migrate_state(&dump, 0, "pwdAuthor")?;
+    ensure!(migrated[4] == STATE_VERSION, "bad migrated state version");
+    User::import(&migrated, 0, "pwdAuthor", transport.clone())?;
+
+    println!("imported author keeps the subscriber");
+    let (keyload_link, _) = author2.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(subscriber.receive_keyload(&keyload_link)?, "subscriber is not a recipient of the keyload");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author2.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (_, unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&packet_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+    Ok(())
+}
+
+#[test]
+fn run_baseline_state_migration_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_baseline_state_migration(transport)).is_ok());
+}
+
+pub fn example_channel_state_export<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::GraceWindow;
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::builder()
+        .seed("AUTHOR9SEED".parse()?)
+        .transport(transport.clone())
+        .auto_rekey(true)
+        .build()?;
+    author.enable_key_tree(2)?;
+    let mut subscriberA = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberB = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    for subscriber in [&mut subscriberA, &mut subscriberB].iter_mut() {
+        subscriber.receive_announcement(&announcement_link)?;
+        let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+        author.receive_subscribe(&subscribe_link)?;
+    }
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(subscriberA.receive_keyload(&keyload_link)?, "subscriberA failed to unwrap keyload");
+    ensure!(subscriberB.receive_keyload(&keyload_link)?, "subscriberB failed to unwrap keyload");
+
+    let pkA = subscriberA.get_pk().clone();
+    let (revocation_link, _, window) = author.revoke_subscriber(&keyload_link, &pkA, GraceWindow::Messages(3))?;
+    subscriberA.receive_revocation(&revocation_link)?;
+    let pkB = subscriberB.get_pk().clone();
+    let (update_link, _) = author.tree_add_member(&revocation_link, &pkB)?;
+    ensure!(subscriberB.receive_tree_update(&update_link)?, "subscriberB is not a tree member");
+    let (embargoed_link, _) =
+        author.send_embargoed_packet(&update_link, &public_payload, &masked_payload, Some(100))?;
+    author.set_directory_entry("sensors", &keyload_link, &["application/json"])?;
+
+    println!("export and import channel state");
+    let author_dump = author.export("pwdAuthor")?;
+    let mut author = Author::import(&author_dump, "pwdAuthor", transport.clone())?;
+    ensure!(
+        author.revocation_window(&pkA) == Some(window.after(2)),
+        "revocation lost on import"
+    );
+    ensure!(author.tree_pskid().is_some(), "key tree lost on import");
+    ensure!(
+        author.embargoes().len() == 1 && author.embargoes()[0].link == embargoed_link,
+        "embargo lost on import"
+    );
+    ensure!(author.directory().get("sensors").is_some(), "directory lost on import");
+
+    let subscriberA_dump = subscriberA.export("pwdSubscriberA")?;
+    let mut subscriberA = Subscriber::import(&subscriberA_dump, "pwdSubscriberA", transport.clone())?;
+    ensure!(subscriberA.access_window() == Some(window), "access window lost on import");
+    let subscriberB_dump = subscriberB.export("pwdSubscriberB")?;
+    let mut subscriberB = Subscriber::import(&subscriberB_dump, "pwdSubscriberB", transport.clone())?;
+    ensure!(subscriberB.is_tree_member(), "tree keys lost on import");
+
+    println!("revocation cuts over after import");
+    let (packet_link, _) = author.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    ensure!(author.revocation_window(&pkA).is_none(), "revocation still pending");
+    let (cutover_link, _) = author.send_signed_packet(&packet_link, &public_payload, &masked_payload)?;
+    ensure!(
+        subscriberA.receive_signed_packet(&cutover_link).is_err(),
+        "revoked subscriberA unwrapped packet"
+    );
+    ensure!(!author.remove_subscriber(&pkA)?, "revoked subscriberA not removed");
+
+    println!("imported member unwraps tree keyloads");
+    let (tree_keyload_link, _) = author.send_tree_keyload(&update_link)?;
+    ensure!(
+        subscriberB.receive_keyload(&tree_keyload_link)?,
+        "subscriberB failed to unwrap tree keyload"
+    );
+    let releases = author.send_due_releases(&tree_keyload_link, 100)?;
+    ensure!(releases.len() == 1, "embargo is not released after import");
+    Ok(())
+}
+
+#[test]
+fn run_channel_state_export_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_channel_state_export(transport)).is_ok());
+}
+
+#[cfg(feature = "mam")]
+pub fn example_mam_migration<T: Transport>(transport: T) -> Result<()> {
+    use super::{
+        migrate_mam_channel,
+        BucketMamReader,
+        MamMessage,
+    };
+    use iota_streams_core::prelude::String;
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let roots: Vec<String> = ["A", "B", "C", "D"].iter().map(|c| c.repeat(81)).collect();
+    let mut reader = BucketMamReader::new();
+    for i in 0..3 {
+        reader.insert(MamMessage {
+            root: roots[i].clone(),
+            payload: format!("MAM MESSAGE {}", i).into_bytes(),
+            next_root: roots[i + 1].clone(),
+        });
+    }
+
+    println!("migrate MAM channel");
+    let migration = migrate_mam_channel(&mut reader, &roots[0], None, &mut author, &announcement_link)?;
+    ensure!(migration.links.len() == 3, "bad number of migrated messages");
+    ensure!(migration.next_root == roots[3], "bad next MAM root");
+    for (i, link) in migration.links.iter().enumerate() {
+        let (_pk, public, masked) = subscriber.receive_signed_packet(link)?;
+        ensure!(public.0 == roots[i].as_bytes(), "bad MAM root of migrated message");
+        ensure!(masked.0 == format!("MAM MESSAGE {}", i).into_bytes(), "bad payload of migrated message");
+    }
+
+    println!("reject bad MAM roots and loops");
+    ensure!(
+        migrate_mam_channel(&mut reader, "ROOT", None, &mut author, &announcement_link).is_err(),
+        "bad MAM root accepted"
+    );
+    reader.insert(MamMessage {
+        root: roots[3].clone(),
+        payload: Vec::new(),
+        next_root: roots[3].clone(),
+    });
+    ensure!(
+        migrate_mam_channel(&mut reader, &roots[3], None, &mut author, &announcement_link).is_err(),
+        "looping MAM channel accepted"
+    );
+    Ok(())
+}
+
+#[cfg(feature = "mam")]
+#[test]
+fn run_mam_migration_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_mam_migration(transport)).is_ok());
+}
+
+pub fn example_embargo<T: Transport>(transport: T) -> Result<()>
+{
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, true, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let (embargoed_link, _) =
+        author.send_embargoed_packet(&announcement_link, &public_payload, &masked_payload, Some(100))?;
+    let (_, unwrapped_public, embargoed) = subscriber.receive_embargoed_packet(&embargoed_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(embargoed.release_at == Some(100), "bad release milestone");
+    ensure!(author.embargoes().len() == 1, "embargo is not pending");
+
+    ensure!(author.send_due_releases(&announcement_link, 99)?.is_empty(), "released before milestone");
+    let releases = author.send_due_releases(&announcement_link, 100)?;
+    ensure!(releases.len() == 1, "embargo is not released at milestone");
+    ensure!(author.embargoes().is_empty(), "released embargo is still pending");
+    ensure!(
+        author.send_release(&announcement_link, &embargoed_link).is_err(),
+        "embargo released twice"
+    );
+
+    let (_, release) = subscriber.receive_release(&releases[0])?;
+    let unwrapped_masked = embargoed.open::<DefaultF>(&release)?;
+    ensure!(masked_payload.0 == unwrapped_masked, "bad unwrapped masked payload");
+
+    Ok(())
+}
+
+#[test]
+fn run_embargo_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_embargo(transport)).is_ok());
+}
+
+#[cfg(feature = "secp256k1")]
+pub fn example_cosigned_packet<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::secp256k1;
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let cosigner = secp256k1::SigningKey::from_bytes(&[7; 32])?;
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let (cosigned_link, _) =
+        author.send_signed_packet_cosigned(&announcement_link, &public_payload, &masked_payload, &cosigner)?;
+    let (_, unwrapped_public, unwrapped_masked, cosignature) =
+        subscriber.receive_signed_packet_cosigned(&cosigned_link)?;
+    ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+    let cosignature = cosignature.ok_or_else(|| anyhow::anyhow!("packet is not co-signed"))?;
+    ensure!(cosignature.verify(&cosigner.verifying_key()), "bad co-signature");
+    let address = secp256k1::eth_address(&cosigner.verifying_key());
+    ensure!(
+        cosignature.signer().map(|pk| secp256k1::eth_address(&pk)) == Some(address),
+        "bad co-signer address"
+    );
+
+    let (signed_link, _) = author.send_signed_packet(&cosigned_link, &public_payload, &masked_payload)?;
+    let (_, _, _, cosignature) = subscriber.receive_signed_packet_cosigned(&signed_link)?;
+    ensure!(cosignature.is_none(), "plain signed packet is co-signed");
+
+    Ok(())
+}
+
+#[cfg(feature = "secp256k1")]
+#[test]
+fn run_cosigned_packet_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_cosigned_packet(transport)).is_ok());
+}
+
+#[cfg(feature = "std")]
+pub fn example_notarization<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::{
+        message_hash,
+        FileNotarizer,
+    };
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+    let path = std::env::temp_dir().join(format!("streams-notarization-{}.txt", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    author.set_notarizer(FileNotarizer::new(&path)?);
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    let (signed_packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    author.clear_notarizer();
+    author.send_signed_packet(&signed_packet_link, &public_payload, &masked_payload)?;
+
+    let records = FileNotarizer::read_records(&path)?;
+    std::fs::remove_file(&path)?;
+    ensure!(records.len() == 2, "expected 2 notarized messages, found {}", records.len());
+    for (record, link) in records.iter().zip([announcement_link, signed_packet_link].iter()) {
+        ensure!(record.link == *link, "bad notarized link");
+        let msg = transport.borrow_mut().recv_message(link)?;
+        ensure!(record.hash == message_hash(&msg), "bad notarized hash");
+        ensure!(record.milestone.is_none(), "unexpected milestone");
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn run_notarization_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_notarization(transport)).is_ok());
+}
+
+#[cfg(feature = "sync-client")]
+pub fn example_inclusion_proof<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::{
+        AttachProof,
+        InclusionProof,
+    };
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let (signed_packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+
+    // Proof as handed to an auditor, exchanged as text.
+    let msg = transport.borrow_mut().recv_message(&signed_packet_link)?;
+    let proof = InclusionProof::from_message(&msg, 1)?;
+    let proof = InclusionProof::from_text(&proof.to_text())?;
+    ensure!(proof.milestone.is_none(), "unexpected milestone");
+
+    let peeked = subscriber.peek(&signed_packet_link)?;
+    let mut other = proof.clone();
+    other.link = announcement_link;
+    ensure!(peeked.attach_proof(other).is_err(), "proof of another message attached");
+
+    let mut truncated = proof.clone();
+    truncated.transactions.pop();
+    ensure!(truncated.verify::<DefaultF>(1).is_err(), "proof without transactions verified");
+
+    let unwrapped = subscriber.receive_msg(&signed_packet_link, None)?;
+    let verified = unwrapped.attach_proof(proof)?.verify(1)?;
+    ensure!(
+        verified.binary.body.bytes == msg.binary.body.bytes,
+        "proof carries another message body"
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "sync-client")]
+#[test]
+fn run_inclusion_proof_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_inclusion_proof(transport)).is_ok());
+}
+
+pub fn example_disclosure<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::FieldOpening;
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let fields = vec![
+        Bytes("NAME".as_bytes().to_vec()),
+        Bytes("BIRTHDATE".as_bytes().to_vec()),
+        Bytes("BALANCE".as_bytes().to_vec()),
+    ];
+    let (packet_link, _, openings) = author.send_disclosable_packet(&announcement_link, &fields)?;
+    ensure!(openings.len() == fields.len(), "expected an opening per field");
+
+    let (pk, commitments) = subscriber.receive_disclosable_packet(&packet_link)?;
+    ensure!(pk == *author.get_pk(), "bad disclosable packet signer");
+    ensure!(commitments.commitments.len() == fields.len(), "expected a commitment per field");
+
+    // Only the second field is disclosed, exchanged as bytes.
+    let opening = FieldOpening::from_bytes(&openings[1].to_bytes())?;
+    commitments.verify::<DefaultF>(&opening)?;
+    ensure!(opening.value == fields[1].0, "bad disclosed value");
+
+    let mut forged = opening.clone();
+    forged.value = "ANOTHERDATE".as_bytes().to_vec();
+    ensure!(commitments.verify::<DefaultF>(&forged).is_err(), "forged value verified");
+    let mut moved = opening;
+    moved.index = 2;
+    ensure!(commitments.verify::<DefaultF>(&moved).is_err(), "field verified at another index");
+
+    Ok(())
+}
+
+#[test]
+fn run_disclosure_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_disclosure(transport)).is_ok());
+}
+
+struct RekeyLog(Rc<RefCell<Vec<Address>>>);
+
+impl UserEvents for RekeyLog {
+    fn on_rekey(&mut self, keyload_link: &Address, _link_to: &Address) {
+        self.0.borrow_mut().push(keyload_link.clone());
+    }
+}
+
+pub fn example_auto_rekey<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::builder()
+        .seed("AUTHOR9SEED".parse()?)
+        .transport(transport.clone())
+        .auto_rekey(true)
+        .build()?;
+    let rekeys = Rc::new(RefCell::new(Vec::new()));
+    author.register_events(RekeyLog(rekeys.clone()));
+    let mut subscriberA = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberB = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    subscriberA.receive_announcement(&announcement_link)?;
+    subscriberB.receive_announcement(&announcement_link)?;
+    let subscribeA_link = subscriberA.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribeA_link)?;
+
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(subscriberA.receive_keyload(&keyload_link)?, "subscriberA failed to unwrap keyload");
+    let (packet1_link, _) = author.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    ensure!(rekeys.borrow().is_empty(), "unexpected rekey");
+    subscriberA.receive_signed_packet(&packet1_link)?;
+
+    // Subscriber B joins: the next packet is preceded by a keyload shared with it.
+    let subscribeB_link = subscriberB.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribeB_link)?;
+    let (packet2_link, _) = author.send_signed_packet(&packet1_link, &public_payload, &masked_payload)?;
+    ensure!(rekeys.borrow().len() == 1, "expected a rekey after subscription");
+    let rekey_link = rekeys.borrow()[0].clone();
+    ensure!(subscriberA.receive_keyload(&rekey_link)?, "subscriberA failed to unwrap rekey");
+    ensure!(subscriberB.receive_keyload(&rekey_link)?, "subscriberB failed to unwrap rekey");
+    let (_, _, unwrapped_masked) = subscriberB.receive_signed_packet(&packet2_link)?;
+    ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
+    subscriberA.receive_signed_packet(&packet2_link)?;
+
+    // Subscriber A is removed: the next packet is preceded by a keyload without it.
+    ensure!(author.remove_subscriber(subscriberA.get_pk())?, "subscriberA not removed");
+    let (packet3_link, _) = author.send_signed_packet(&packet2_link, &public_payload, &masked_payload)?;
+    ensure!(rekeys.borrow().len() == 2, "expected a rekey after removal");
+    let rekey_link = rekeys.borrow()[1].clone();
+    ensure!(!subscriberA.receive_keyload(&rekey_link)?, "removed subscriberA unwrapped rekey");
+    ensure!(subscriberA.receive_signed_packet(&packet3_link).is_err(), "removed subscriberA unwrapped packet");
+    ensure!(subscriberB.receive_keyload(&rekey_link)?, "subscriberB failed to unwrap rekey");
+    subscriberB.receive_signed_packet(&packet3_link)?;
+
+    author.send_signed_packet(&packet3_link, &public_payload, &masked_payload)?;
+    ensure!(rekeys.borrow().len() == 2, "unexpected rekey without membership change");
+    ensure!(!author.remove_subscriber(subscriberA.get_pk())?, "unknown subscriber removed");
+
+    Ok(())
+}
+
+#[test]
+fn run_auto_rekey_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_auto_rekey(transport)).is_ok());
+}
+
+pub fn example_grace_revocation<T: Transport>(transport: T) -> Result<()> {
+    use crate::api::tangle::{
+        GraceWindow,
+        Revocation,
+    };
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::builder()
+        .seed("AUTHOR9SEED".parse()?)
+        .transport(transport.clone())
+        .auto_rekey(true)
+        .build()?;
+    let rekeys = Rc::new(RefCell::new(Vec::new()));
+    author.register_events(RekeyLog(rekeys.clone()));
+    let mut subscriberA = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberB = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    for subscriber in [&mut subscriberA, &mut subscriberB].iter_mut() {
+        subscriber.receive_announcement(&announcement_link)?;
+        let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+        author.receive_subscribe(&subscribe_link)?;
+    }
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(subscriberA.receive_keyload(&keyload_link)?, "subscriberA failed to unwrap keyload");
+    ensure!(
+        subscriberB.receive_keyload(&keyload_link)?,
+        "subscriberB failed to unwrap keyload"
+    );
+    let pkA = subscriberA.get_pk().clone();
+
+    // Revocations are accepted from the author only.
+    let forged = Revocation {
+        pk: pkA.clone(),
+        grace: GraceWindow::Messages(0),
+    };
+    let (forged_link, _) =
+        subscriberB.send_signed_packet(&keyload_link, &Bytes(Vec::new()), &Bytes(forged.to_bytes()))?;
+    ensure!(
+        subscriberA.receive_revocation(&forged_link).is_err(),
+        "forged revocation accepted"
+    );
+    ensure!(subscriberA.access_window().is_none(), "forged access window kept");
+
+    // Subscriber A keeps access for 2 more packets.
+    let (revocation_link, _, window) = author.revoke_subscriber(&keyload_link, &pkA, GraceWindow::Messages(2))?;
+    ensure!(window.grace == GraceWindow::Messages(2), "bad access window");
+    let (_, revocation, received_window) = subscriberA.receive_revocation(&revocation_link)?;
+    ensure!(revocation.pk == pkA, "bad revoked subscriber");
+    ensure!(received_window == window, "bad received access window");
+    ensure!(subscriberA.access_window() == Some(window), "access window not kept");
+    ensure!(subscriberB.access_window().is_none(), "unexpected access window");
+
+    let (packet1_link, _) = author.send_signed_packet(&revocation_link, &public_payload, &masked_payload)?;
+    ensure!(
+        author.revocation_window(&pkA).map(|window| window.grace) == Some(GraceWindow::Messages(1)),
+        "bad remaining access window"
+    );
+    subscriberA.receive_signed_packet(&packet1_link)?;
+    let (packet2_link, _) = author.send_signed_packet(&packet1_link, &public_payload, &masked_payload)?;
+    ensure!(author.revocation_window(&pkA).is_none(), "revocation still pending");
+    ensure!(rekeys.borrow().is_empty(), "unexpected rekey within the grace window");
+    subscriberA.receive_signed_packet(&packet2_link)?;
+
+    // Hard cutover: the next packet is preceded by a keyload without subscriber A.
+    let (packet3_link, _) = author.send_signed_packet(&packet2_link, &public_payload, &masked_payload)?;
+    ensure!(rekeys.borrow().len() == 1, "expected a rekey at cutover");
+    let rekey_link = rekeys.borrow()[0].clone();
+    ensure!(!subscriberA.receive_keyload(&rekey_link)?, "revoked subscriberA unwrapped rekey");
+    ensure!(subscriberA.receive_signed_packet(&packet3_link).is_err(), "revoked subscriberA unwrapped packet");
+
+    // Empty windows cut over at once.
+    let pkB = subscriberB.get_pk().clone();
+    author.revoke_subscriber(&packet3_link, &pkB, GraceWindow::Time(0))?;
+    ensure!(author.revocation_window(&pkB).is_none(), "empty window pending");
+    ensure!(!author.remove_subscriber(&pkB)?, "subscriberB not removed");
+
+    Ok(())
+}
+
+#[test]
+fn run_grace_revocation_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_grace_revocation(transport)).is_ok());
+}
+
+pub fn example_group_keying<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    author.enable_key_tree(3)?;
+    let mut subscriberA = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberB = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberC = Subscriber::new(&"SUBSCRIBERC9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    let mut subscribers = [&mut subscriberA, &mut subscriberB, &mut subscriberC];
+    for subscriber in subscribers.iter_mut() {
+        subscriber.receive_announcement(&announcement_link)?;
+    }
+
+    // Members are added one by one, every subscriber processes every update.
+    let mut link = announcement_link;
+    for i in 0..subscribers.len() {
+        let pk = subscribers[i].get_pk().clone();
+        link = author.tree_add_member(&link, &pk)?.0;
+        for (j, subscriber) in subscribers.iter_mut().enumerate() {
+            ensure!(
+                subscriber.receive_tree_update(&link)? == (j <= i),
+                "bad membership of subscriber {} after update {}",
+                j,
+                i
+            );
+        }
+    }
+    ensure!(author.tree_add_member(&link, subscribers[0].get_pk()).is_err(), "member added twice");
+
+    let (keyload_link, _) = author.send_tree_keyload(&link)?;
+    let (packet_link, _) = author.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    for subscriber in subscribers.iter_mut() {
+        ensure!(subscriber.receive_keyload(&keyload_link)?, "member failed to unwrap tree keyload");
+        let (_, _, unwrapped_masked) = subscriber.receive_signed_packet(&packet_link)?;
+        ensure!(unwrapped_masked == masked_payload, "bad masked payload");
+    }
+
+    // Removed member B keeps no key of the new tree.
+    let pkB = subscribers[1].get_pk().clone();
+    let (update_link, _) = author.tree_remove_member(&link, &pkB)?;
+    ensure!(subscribers[0].receive_tree_update(&update_link)?, "subscriberA removed");
+    ensure!(!subscribers[1].receive_tree_update(&update_link)?, "subscriberB not removed");
+    ensure!(subscribers[2].receive_tree_update(&update_link)?, "subscriberC removed");
+    ensure!(!subscribers[1].is_tree_member(), "subscriberB still a member");
+
+    let (keyload_link, _) = author.send_tree_keyload(&update_link)?;
+    ensure!(subscribers[0].receive_keyload(&keyload_link)?, "subscriberA failed to unwrap tree keyload");
+    ensure!(!subscribers[1].receive_keyload(&keyload_link)?, "removed subscriberB unwrapped tree keyload");
+    ensure!(subscribers[2].receive_keyload(&keyload_link)?, "subscriberC failed to unwrap tree keyload");
+
+    Ok(())
+}
+
+#[test]
+fn run_group_keying_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_group_keying(transport)).is_ok());
+}
+
+pub fn example_directory<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::builder()
+        .seed("AUTHOR9SEED".parse()?)
+        .transport(transport.clone())
+        .auto_rekey(true)
+        .build()?;
+    let mut subscriberA = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberB = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce()?;
+    subscriberA.receive_announcement(&announcement_link)?;
+    let subscribe_link = subscriberA.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+
+    let (telemetry_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    let (alerts_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    author.set_directory_entry("telemetry", &telemetry_link, &["application/cbor"])?;
+    author.set_directory_entry("alerts", &alerts_link, &["text/plain", "application/json"])?;
+    author.set_directory_refresh(2)?;
+    let (directory_link, _) = author.send_directory(&announcement_link)?;
+    ensure!(author.directory_link() == Some(&directory_link), "bad directory link");
+
+    // Subscriber A navigates the channel from the directory link.
+    let directory = subscriberA.receive_directory(&directory_link)?;
+    ensure!(directory == *author.directory(), "bad received directory");
+    ensure!(directory.entries.len() == 2, "bad number of branches");
+    let alerts = directory.get("alerts").ok_or_else(|| anyhow::anyhow!("branch alerts not listed"))?;
+    ensure!(alerts.content_types == ["text/plain", "application/json"], "bad content types");
+    ensure!(subscriberA.receive_keyload(&alerts.keyload)?, "subscriberA failed to unwrap alerts keyload");
+    ensure!(directory.get("logs").is_none(), "unexpected branch");
+
+    // A new subscriber rekeys the telemetry branch, its entry follows.
+    subscriberB.receive_announcement(&announcement_link)?;
+    let subscribe_link = subscriberB.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+    let (packet_link, _) = author.send_signed_packet(&telemetry_link, &public_payload, &masked_payload)?;
+    let telemetry = author.directory().get("telemetry").map(|entry| entry.keyload.clone());
+    ensure!(
+        telemetry.is_some() && telemetry != Some(telemetry_link.clone()),
+        "telemetry entry not rekeyed"
+    );
+    ensure!(
+        author.directory().get("alerts").map(|entry| &entry.keyload) == Some(&alerts_link),
+        "alerts entry rekeyed"
+    );
+    ensure!(author.directory_link() == Some(&directory_link), "early directory refresh");
+
+    // The directory is refreshed after 2 packets, linked to the previous one.
+    author.send_signed_packet(&packet_link, &public_payload, &masked_payload)?;
+    let refreshed_link = author
+        .directory_link()
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("directory link lost"))?;
+    ensure!(refreshed_link != directory_link, "directory not refreshed");
+    let directory = subscriberA.receive_directory(&refreshed_link)?;
+    ensure!(
+        directory.get("telemetry").map(|entry| entry.keyload.clone()) == telemetry,
+        "bad refreshed telemetry entry"
+    );
+    ensure!(subscriberB.receive_directory(&directory_link).is_ok(), "public directory not readable");
+
+    ensure!(author.remove_directory_entry("alerts")?, "alerts entry not removed");
+    ensure!(!author.remove_directory_entry("alerts")?, "alerts entry removed twice");
+
+    Ok(())
+}
+
+#[test]
+fn run_directory_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_directory(transport)).is_ok());
+}
+
+#[cfg(feature = "gateway")]
+pub fn example_gateway<T: Transport>(transport: T) -> Result<()> {
+    let transport = Rc::new(RefCell::new(transport));
+    let mut gateway = Gateway::new(transport);
+
+    let request = |gateway: &mut Gateway<_>, method: &str, path: &str, body: serde_json::Value| {
+        let body = if body.is_null() {
+            Vec::new()
+        } else {
+            body.to_string().into_bytes()
+        };
+        let response = gateway.handle(method, path, &body);
+        serde_json::from_str::<serde_json::Value>(&response.body).map(|json| (response.status, json))
+    };
+
+    let (status, channel) = request(&mut gateway, "POST", "/channels", serde_json::Value::Null)?;
+    ensure!(status == 201, "channel not created: {}", channel);
+    let author = channel["user"].as_str().unwrap_or_default().to_string();
+    let announcement = channel["announcement"].clone();
+
+    let (status, subscription) = request(
+        &mut gateway,
+        "POST",
+        "/subscriptions",
+        serde_json::json!({ "announcement": announcement }),
+    )?;
+    ensure!(status == 201, "subscription not created: {}", subscription);
+    let subscriber = subscription["user"].as_str().unwrap_or_default().to_string();
+    ensure!(gateway.users().len() == 2, "bad number of users");
+
+    let (status, _) = request(
+        &mut gateway,
+        "POST",
+        &format!("/users/{}/subscribers", author),
+        serde_json::json!({ "subscribe": subscription["subscribe"] }),
+    )?;
+    ensure!(status == 201, "subscriber not accepted");
+    let (status, keyload) = request(
+        &mut gateway,
+        "POST",
+        &format!("/users/{}/keyloads", author),
+        serde_json::json!({ "link_to": announcement }),
+    )?;
+    ensure!(status == 201, "keyload not sent: {}", keyload);
+    let (status, packet) = request(
+        &mut gateway,
+        "POST",
+        &format!("/users/{}/packets", author),
+        serde_json::json!({
+            "link_to": keyload["link"],
+            "public_payload": base64::encode("PUBLICPAYLOAD"),
+            "masked_payload": base64::encode("MASKEDPAYLOAD"),
+        }),
+    )?;
+    ensure!(status == 201, "packet not sent: {}", packet);
+
+    let (status, messages) = request(
+        &mut gateway,
+        "GET",
+        &format!("/users/{}/messages", subscriber),
+        serde_json::Value::Null,
+    )?;
+    ensure!(status == 200, "messages not listed: {}", messages);
+    let envelopes: Vec<Envelope> = serde_json::from_value(messages)?;
+    let envelope = envelopes
+        .iter()
+        .find(|envelope| envelope.kind == "signed_packet")
+        .ok_or_else(|| anyhow::anyhow!("packet not received"))?;
+    ensure!(envelope.link == packet["link"], "bad packet link");
+    ensure!(
+        envelope.masked_payload == Some(base64::encode("MASKEDPAYLOAD")),
+        "bad masked payload"
+    );
+
+    let (status, _) = request(
+        &mut gateway,
+        "POST",
+        "/users/unknown/packets",
+        serde_json::json!({ "link_to": keyload["link"] }),
+    )?;
+    ensure!(status == 404, "unknown user accepted");
+    let (status, error) = request(
+        &mut gateway,
+        "POST",
+        &format!("/users/{}/keyloads", subscriber),
+        serde_json::json!({ "link_to": "not a link" }),
+    )?;
+    ensure!(status == 400 && error["error"].is_string(), "bad link accepted");
+    let (status, _) = request(
+        &mut gateway,
+        "DELETE",
+        &format!("/users/{}", subscriber),
+        serde_json::Value::Null,
+    )?;
+    ensure!(status == 200 && gateway.users().len() == 1, "subscriber not removed");
+    Ok(())
+}
+
+#[cfg(feature = "gateway")]
+#[test]
+fn run_gateway_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_gateway(transport)).is_ok());
+}
+
+pub fn example_join<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriberA = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberB = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    let subscribe_link = match subscriberA.join(&announcement_link)? {
+        JoinProgress::Subscribed(link) => link,
+        progress => return Err(anyhow::anyhow!("joined without a keyload: {:?}", progress)),
+    };
+    ensure!(
+        subscriberA.join(&announcement_link)? == JoinProgress::Subscribed(subscribe_link.clone()),
+        "subscription sent twice"
+    );
+    author.receive_subscribe(&subscribe_link)?;
+
+    // A keyload for another subscriber doesn't complete the handshake.
+    let pkB = *subscriberB.get_pk();
+    author.send_keyload(&announcement_link, &Vec::new(), &vec![pkB])?;
+    ensure!(!subscriberA.join(&announcement_link)?.is_joined(), "joined without access");
+
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(
+        subscriberA.join(&announcement_link)? == JoinProgress::Joined(keyload_link.clone()),
+        "subscriberA failed to join"
+    );
+    ensure!(
+        subscriberA.join(&announcement_link)? == JoinProgress::Joined(keyload_link),
+        "handshake not resumed"
+    );
+
+    let mut other = Author::new(&"OTHER9AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let other_announcement_link = other.send_announce()?;
+    ensure!(
+        subscriberA.join(&other_announcement_link).is_err(),
+        "joined a second channel"
+    );
+    Ok(())
+}
+
+#[test]
+fn run_join_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_join(transport)).is_ok());
+}
+
+pub fn example_subscription_approval<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::builder()
+        .seed("AUTHOR9SEED".parse()?)
+        .transport(transport.clone())
+        .subscription_approval(true)
+        .build()?;
+    let mut subscriberA = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberB = Subscriber::new(&"SUBSCRIBERB9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+    let log = Rc::new(RefCell::new(Vec::new()));
+    author.register_events(EventLog(log.clone()));
+
+    let announcement_link = author.send_announce()?;
+    subscriberA.receive_announcement(&announcement_link)?;
+    subscriberB.receive_announcement(&announcement_link)?;
+    let subscribeA_link = subscriberA.send_subscribe(&announcement_link)?;
+    let subscribeB_link = subscriberB.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribeA_link)?;
+    author.receive_subscribe(&subscribeA_link)?;
+    author.receive_subscribe(&subscribeB_link)?;
+    ensure!(log.borrow().is_empty(), "subscription accepted on receipt");
+
+    let pending = author.fetch_pending_subscriptions();
+    ensure!(pending.len() == 2, "bad number of pending subscriptions: {}", pending.len());
+    ensure!(
+        pending[0].link == subscribeA_link && pending[0].pk == *subscriberA.get_pk(),
+        "bad pending subscription of subscriberA"
+    );
+    ensure!(pending[1].pk == *subscriberB.get_pk(), "bad pending subscription of subscriberB");
+    ensure!(author.fetch_pending_subscriptions().is_empty(), "pending subscriptions fetched twice");
+    ensure!(author.pending_subscriptions().len() == 2, "undecided subscriptions dropped");
+
+    ensure!(
+        author.accept_subscription(&subscribeA_link)? == *subscriberA.get_pk(),
+        "bad accepted subscriber"
+    );
+    ensure!(
+        author.reject_subscription(&subscribeB_link)? == *subscriberB.get_pk(),
+        "bad rejected subscriber"
+    );
+    ensure!(author.pending_subscriptions().is_empty(), "decided subscriptions pending");
+    ensure!(
+        author.accept_subscription(&subscribeB_link).is_err(),
+        "rejected subscription accepted"
+    );
+    ensure!(
+        *log.borrow() == ["subscription", "rejected subscription"],
+        "bad events: {:?}",
+        log.borrow()
+    );
+
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    ensure!(subscriberA.receive_keyload(&keyload_link)?, "subscriberA failed to unwrap keyload");
+    ensure!(!subscriberB.receive_keyload(&keyload_link)?, "rejected subscriberB unwrapped keyload");
+    Ok(())
+}
+
+#[test]
+fn run_subscription_approval_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_subscription_approval(transport)).is_ok());
+}
+
+pub fn example_did_url<T: Transport>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+    let mut author = Author::new_with_msgid_size(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, 24, transport)?;
+    let announcement_link = author.send_announce()?;
+    let channel_address = author.channel_address().unwrap().clone();
+
+    let did = channel_address.to_did();
+    ensure!(did.starts_with("did:iota:streams:"), "bad channel DID: {}", did);
+    ensure!(ChannelAddress::from_did(&did)? == channel_address, "bad decoded channel DID");
+    ensure!(ChannelAddress::from_did("did:example:123").is_err(), "foreign DID decoded");
+
+    let url = announcement_link.to_did_url();
+    ensure!(url.starts_with(&format!("{}#", did)), "bad DID URL: {}", url);
+    ensure!(Address::from_did_url(&url)? == announcement_link, "bad decoded DID URL");
+    ensure!(Address::from_did_url(&did).is_err(), "channel DID decoded as message");
+
+    println!("DID URL with truncated msgid is rejected");
+    let (channel, fragment) = url.split_at(url.find('#').unwrap());
+    let checksum = &fragment[fragment.find(':').unwrap()..];
+    let truncated = format!("{}{}{}", channel, &fragment[..25], checksum);
+    ensure!(Address::from_did_url(&truncated).is_err(), "DID URL with truncated msgid decoded");
+    Ok(())
+}
+
+#[test]
+fn run_did_url_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_did_url(transport)).is_ok());
+}
+
+#[cfg(feature = "stream")]
+pub fn example_messages_stream<T: Transport>(transport: T) -> Result<()>
+{
+    use futures::{
+        executor::block_on,
+        StreamExt,
+    };
+
+    let encoding = "utf-8";
+    let transport = Rc::new(RefCell::new(transport));
+
+    let mut author = Author::new(&"AUTHOR9SEED".parse()?, encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new(&"SUBSCRIBERA9SEED".parse()?, encoding, PAYLOAD_BYTES, transport.clone());
+
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let payloads: Vec<Bytes> = (0..3).map(|i| Bytes(vec![i; 8])).collect();
+    for public_payload in payloads.iter() {
+        author.send_signed_packet(&announcement_link, public_payload, &Bytes::default())?;
+    }
+
+    block_on(async {
+        let mut messages = subscriber.messages();
+        let mut n = 0;
+        while let Some(msg) = messages.next().await {
+            match msg?.body {
+                MessageContent::SignedPacket { public_payload, .. } => {
+                    ensure!(public_payload == payloads[n], "messages out of order")
+                }
+                _ => ensure!(false, "bad message type"),
+            }
+            n += 1;
+        }
+        ensure!(n == payloads.len(), "lost messages: {}", n);
+        Ok(())
+    })
+}
+
+#[cfg(feature = "stream")]
+#[test]
+fn run_messages_stream_scenario() {
+    let transport = crate::api::tangle::BucketTransport::new();
+    assert!(dbg!(example_messages_stream(transport)).is_ok());
+}
@@ -0,0 +1,199 @@
+//! Deterministic test vectors of wrapped messages.
+//!
+//! `generate` runs a fixed scenario with fixed seeds and a seeded `SystemRng` (see
+//! `prng::with_rng`), so nonces, session and ephemeral keys and thus the wrapped bytes of every
+//! message type are reproducible. Alternative implementations check byte-level compatibility by
+//! wrapping the same scenario and comparing against the vectors, and `verify` checks vectors
+//! produced elsewhere against this crate.
+//!
+//! The module is only built with the `test-vectors` feature, which lets the system RNG be
+//! replaced and must not be enabled in production builds.
+//!
+//! Vectors are exchanged as text, one vector per line:
+//!
+//! ```text
+//! <name> <appinst hex>:<msgid hex> <message hex>
+//! ```
+
+use anyhow::{
+    anyhow,
+    ensure,
+    Result,
+};
+use core::cell::RefCell;
+
+use super::*;
+use iota_streams_app::transport::tangle::PAYLOAD_BYTES;
+use iota_streams_core::{
+    prelude::{
+        typenum::Unsigned,
+        vec,
+        Rc,
+        String,
+        ToString,
+        Vec,
+    },
+    prng,
+};
+
+/// Seed of the author of the test vector scenario.
+pub const AUTHOR_SEED: &str = "IOTA Streams test vectors author";
+/// Seed of the subscriber of the test vector scenario.
+pub const SUBSCRIBER_SEED: &str = "IOTA Streams test vectors subscriber";
+/// Seed of `SystemRng` while the scenario is run.
+pub const RNG_SEED: &str = "IOTA Streams test vectors rng";
+
+/// Wrapped message of the scenario.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestVector {
+    /// Name of the message in the scenario, eg. `keyload`
+    pub name: String,
+    /// Address of the message
+    pub link: Address,
+    /// Binary encoded message
+    pub message: Vec<u8>,
+}
+
+impl TestVector {
+    /// Text line of the vector, see the module docs.
+    pub fn to_line(&self) -> String {
+        format!(
+            "{} {}:{} {}",
+            self.name,
+            self.link.appinst,
+            self.link.msgid,
+            hex::encode(&self.message)
+        )
+    }
+
+    /// Parse a text line of a vector.
+    pub fn from_line(line: &str) -> Result<Self> {
+        let mut fields = line.split_whitespace();
+        let mut field = |what: &str| fields.next().ok_or_else(|| anyhow!("Test vector {} is missing.", what));
+        let name = field("name")?.to_string();
+        let link = field("link")?;
+        let message = field("message")?;
+        let (appinst, msgid) = link
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Bad test vector link: {}.", link))?;
+        let link = Address::from_str(appinst, msgid).map_err(|_| anyhow!("Bad test vector link: {}.", link))?;
+        let message = hex::decode(message).map_err(|e| anyhow!("Bad test vector message: {}.", e))?;
+        ensure!(fields.next().is_none(), "Trailing fields in test vector {}.", name);
+        Ok(Self { name, link, message })
+    }
+}
+
+/// Test vectors as text, one line per vector.
+pub fn to_text(vectors: &[TestVector]) -> String {
+    let mut text = String::new();
+    for v in vectors {
+        text.push_str(&v.to_line());
+        text.push('\n');
+    }
+    text
+}
+
+/// Parse test vectors, empty lines and lines starting with `#` are skipped.
+pub fn parse(text: &str) -> Result<Vec<TestVector>> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(TestVector::from_line)
+        .collect()
+}
+
+/// Generate the test vectors: announce, subscribe, keyload with its sequence message, signed,
+/// tagged and private packets of a multi-branch channel.
+pub fn generate() -> Result<Vec<TestVector>> {
+    let rng = prng::Rng::new(
+        prng::from_seed::<DefaultF>("IOTA Streams test vectors", RNG_SEED),
+        vec![0; prng::NonceSize::<DefaultF>::USIZE],
+    );
+    prng::with_rng(rng, run_scenario)
+}
+
+fn run_scenario() -> Result<Vec<TestVector>> {
+    let transport = Rc::new(RefCell::new(BucketTransport::new()));
+    let author_seed = Seed::from_bytes(AUTHOR_SEED.as_bytes())?;
+    let subscriber_seed = Seed::from_bytes(SUBSCRIBER_SEED.as_bytes())?;
+    let mut author = Author::new(&author_seed, "utf-8", PAYLOAD_BYTES, true, transport.clone());
+    let mut subscriber = Subscriber::new(&subscriber_seed, "utf-8", PAYLOAD_BYTES, transport.clone());
+    let public_payload = Bytes(b"PUBLIC PAYLOAD".to_vec());
+    let masked_payload = Bytes(b"MASKED PAYLOAD".to_vec());
+    let private_payload = Bytes(b"PRIVATE PAYLOAD".to_vec());
+
+    let mut vectors = Vec::new();
+    let mut push = |name: &str, link: &Address| -> Result<()> {
+        let msgs = transport.borrow_mut().recv_messages(link)?;
+        let msg = msgs
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("Test vector {} is not sent.", name))?;
+        vectors.push(TestVector {
+            name: name.to_string(),
+            link: link.clone(),
+            message: msg.binary.body.bytes,
+        });
+        Ok(())
+    };
+
+    let announcement_link = author.send_announce()?;
+    push("announce", &announcement_link)?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    push("subscribe", &subscribe_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+
+    let (keyload_link, keyload_seq) = author.send_keyload_for_everyone(&announcement_link)?;
+    push("keyload", &keyload_link)?;
+    if let Some(seq_link) = keyload_seq {
+        push("sequence", &seq_link)?;
+    }
+    ensure!(subscriber.receive_keyload(&keyload_link)?, "Keyload is not readable.");
+
+    let (signed_link, _) = author.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    push("signed_packet", &signed_link)?;
+
+    let (tagged_link, _) = subscriber.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    push("tagged_packet", &tagged_link)?;
+
+    let subscriber_pk = *subscriber.get_pk();
+    let (private_link, _) = author.send_private_packet(
+        &keyload_link,
+        &public_payload,
+        &masked_payload,
+        &subscriber_pk,
+        &private_payload,
+    )?;
+    push("private_packet", &private_link)?;
+
+    Ok(vectors)
+}
+
+/// Check `vectors`, eg. produced by another implementation, against the vectors generated by
+/// this crate, fails on the first missing or differing vector.
+pub fn verify(vectors: &[TestVector]) -> Result<()> {
+    let expected = generate()?;
+    ensure!(
+        vectors.len() == expected.len(),
+        "Expected {} test vectors, got {}.",
+        expected.len(),
+        vectors.len()
+    );
+    for (v, e) in vectors.iter().zip(expected.iter()) {
+        ensure!(v.name == e.name, "Expected test vector {}, got {}.", e.name, v.name);
+        ensure!(v.link == e.link, "Test vector {} link differs: {}.", v.name, v.link);
+        if let Some(pos) = v.message.iter().zip(e.message.iter()).position(|(a, b)| a != b) {
+            return Err(anyhow!("Test vector {} differs at byte {}.", v.name, pos));
+        }
+        ensure!(
+            v.message.len() == e.message.len(),
+            "Test vector {} length differs: {} bytes, expected {}.",
+            v.name,
+            v.message.len(),
+            e.message.len()
+        );
+    }
+    Ok(())
+}
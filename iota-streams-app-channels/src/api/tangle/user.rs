@@ -1,29 +1,93 @@
 use anyhow::{
     anyhow,
+    ensure,
     Result,
 };
 
 use iota_streams_app::message::{
+    BinaryMessage,
     HasLink as _,
     LinkGenerator,
 };
 use iota_streams_core::{
-    prelude::Vec,
+    prelude::{
+        Box,
+        HashMap,
+        ToString,
+        Vec,
+    },
     prng,
 };
+use iota_streams_ddml::{
+    link_store::LinkStore as _,
+    types::NBytes,
+};
 
-use super::*;
+use super::{
+    channel_state::ChannelState,
+    *,
+};
 use crate::{
-    api,
+    api::{
+        self,
+        pk_store::PublicKeyStore as _,
+    },
     message,
 };
 
 type UserImp = api::user::User<DefaultF, Address, LinkGen, LinkStore, PkStore, PskStore>;
 
-/// Baseline User api object. Contains the api user implementation as well as the transport object
+/// Messages deniable packets may be linked to: keyloads are stored only if the user has their
+/// session key, deniable packets only if linked to such a keyload in turn.
+const DENIABLE_LINKS: &[MsgInfo] = &[MsgInfo::Keyload, MsgInfo::DeniablePacket];
+
+/// Role of a user in the channel it is registered to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// User created the channel: announces it, accepts subscriptions and shares keyloads.
+    Author,
+    /// User processed the channel announcement: subscribes and reads keyloads.
+    Subscriber,
+}
+
+/// Embargo of a sent embargoed packet, pending until its release is sent.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Embargo {
+    /// Address of the embargoed packet
+    pub link: Address,
+    /// Milestone index the payload is due to be released at, `None` if released at will
+    pub release_at: Option<u32>,
+    /// Release carrying the payload key
+    pub release: EmbargoRelease,
+}
+
+/// Subscription received by an author and awaiting a decision, see `User::set_subscription_approval`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PendingSubscription {
+    /// Address of the subscribe message
+    pub link: Address,
+    /// Public key of the subscriber
+    pub pk: PublicKey,
+}
+
+/// Baseline User api object. Contains the api user implementation as well as the transport object.
+///
+/// User is not tied to a role: it becomes the author of a channel with `create_channel` or
+/// a subscriber with `receive_announcement`, and operations of the other role are rejected.
+/// An application acting as author in one channel and subscriber in another keeps one User
+/// per channel instead of separate Author and Subscriber objects.
 pub struct User<Trans> {
     pub user: UserImp,
     pub transport: Trans,
+    events: Option<Box<dyn UserEvents>>,
+    /// Retained binary messages, `None` unless retention is enabled.
+    retained: Option<HashMap<Address, Message>>,
+    /// Header predicate of packets to be unwrapped when fetching, `None` accepts all.
+    fetch_filter: Option<Box<dyn Fn(&Header) -> bool>>,
+    /// Hook notarizing sent messages, `None` unless registered.
+    notarizer: Option<Box<dyn Notarizer>>,
+    /// Protocol state of the channel, exported and imported together with `user`.
+    state: ChannelState,
 }
 
 #[cfg(not(feature = "async"))]
@@ -34,22 +98,30 @@ where
     /// Create a new User instance.
     ///
     /// # Arguments
-    /// * `seed` - A string slice representing the seed of the user [Characters: A-Z, 9]
+    /// * `seed` - Seed of the user, see `Seed`
     /// * `encoding` - A string slice representing the encoding type for the message [supported: utf-8]
     /// * `payload_length` - Maximum size in bytes of payload per message chunk [1-1024],
     /// * `multi_branching` - Boolean representing use of multi-branch or single-branch sequencing
     /// * `transport` - Transport object used for sending and receiving
     ///
-    pub fn new(seed: &str, encoding: &str, payload_length: usize, multi_branching: bool, transport: Trans) -> Self {
+    pub fn new(seed: &Seed, encoding: &str, payload_length: usize, multi_branching: bool, transport: Trans) -> Self {
         let nonce = "TANGLEUSERNONCE".as_bytes().to_vec();
         let user = UserImp::gen(
-            prng::from_seed("IOTA Streams Channels user sig keypair", seed),
+            prng::from_seed_bytes("IOTA Streams Channels user sig keypair", seed.as_bytes()),
             nonce,
             if multi_branching { 1 } else { 0 },
             encoding.as_bytes().to_vec(),
             payload_length,
         );
-        Self { user, transport }
+        Self {
+            user,
+            transport,
+            events: None,
+            retained: None,
+            fetch_filter: None,
+            notarizer: None,
+            state: ChannelState::default(),
+        }
     }
 
     // Attributes
@@ -64,11 +136,41 @@ where
         self.user.is_multi_branching()
     }
 
+    /// Return boolean representing use of per-publisher message counters instead of sequence messages
+    pub fn is_counter_sequencing(&self) -> bool {
+        self.user.is_counter_sequencing()
+    }
+
+    /// Return boolean representing use of post-quantum signatures by the channel author
+    pub fn is_hybrid(&self) -> bool {
+        self.user.is_hybrid()
+    }
+
+    /// Return boolean representing use of post-quantum key encapsulation in keyloads of the channel
+    pub fn is_hybrid_kem(&self) -> bool {
+        self.user.is_hybrid_kem()
+    }
+
+    /// Return boolean representing use of synthetic IVs for masked payloads of the channel
+    pub fn is_siv_masking(&self) -> bool {
+        self.user.is_siv_masking()
+    }
+
     /// Fetch the user ed25519 public key
     pub fn get_pk(&self) -> &PublicKey {
         &self.user.sig_kp.public
     }
 
+    /// Fingerprint of the user's secret key for support diagnostics
+    pub fn fingerprint(&self) -> Fingerprint {
+        self.user.fingerprint()
+    }
+
+    /// Set callback populating the application-specific header extension of outgoing messages.
+    pub fn set_header_ext<E: Fn(&Header) -> Vec<u8> + 'static>(&mut self, header_ext: E) {
+        self.user.set_header_ext(header_ext)
+    }
+
     pub fn is_registered(&self) -> bool {
         self.user.appinst.is_some()
     }
@@ -78,6 +180,331 @@ where
         self.user.author_sig_pk = None;
     }
 
+    /// Role of the user in the registered channel, None if not registered.
+    pub fn role(&self) -> Option<Role> {
+        if !self.is_registered() {
+            None
+        } else if self.user.is_author() {
+            Some(Role::Author)
+        } else {
+            Some(Role::Subscriber)
+        }
+    }
+
+    /// Return boolean representing ownership of the registered channel
+    pub fn is_author(&self) -> bool {
+        self.user.is_author()
+    }
+
+    /// Create a new channel (without announcing it), the user becomes its Author.
+    ///
+    /// # Arguments
+    /// * `channel_idx` - Index of the channel, different indices give different channel addresses
+    ///
+    pub fn create_channel(&mut self, channel_idx: u64) -> Result<()> {
+        self.user.create_channel(channel_idx)
+    }
+
+    fn ensure_author(&self) -> Result<()> {
+        ensure!(self.is_author(), "Operation requires the Author role.");
+        Ok(())
+    }
+
+    /// Set rate policy declared to publishers in subsequent keyloads [Author].
+    pub fn set_rate_policy(&mut self, policy: RatePolicy) {
+        self.user.set_rate_policy(policy)
+    }
+
+    /// Rate policy declared in own keyloads [Author].
+    pub fn rate_policy(&self) -> &RatePolicy {
+        self.user.rate_policy()
+    }
+
+    /// Rate policy declared in the keyload at `keyload`, `None` if messages of its branch are
+    /// not limited [Author, Subscriber].
+    pub fn branch_rate_policy(&self, keyload: &Address) -> Option<&RatePolicy> {
+        self.user.branch_rate_policy(&keyload.msgid)
+    }
+
+    /// Set branch policy declared in subsequent keyloads [Author, Subscriber].
+    pub fn set_branch_policy(&mut self, policy: BranchPolicy) {
+        self.user.set_branch_policy(policy)
+    }
+
+    /// Select whether packets exceeding the rate policy are flagged or dropped [Author, Subscriber].
+    pub fn set_rate_action(&mut self, action: RateAction) {
+        self.user.set_rate_action(action)
+    }
+
+    /// Publishers which exceeded the rate policy [Author, Subscriber].
+    pub fn flagged_publishers(&self) -> &[PublicKey] {
+        self.user.flagged_publishers()
+    }
+
+    /// Select handling of out-of-order, missing and replayed packets [Author, Subscriber].
+    ///
+    /// # Arguments
+    /// * `policy` - Sequence policy, violations are reported with `UserEvents::on_sequence_violation`
+    ///
+    pub fn set_sequence_policy(&mut self, policy: SequencePolicy) {
+        self.user.set_sequence_policy(policy)
+    }
+
+    /// Current sequence policy [Author, Subscriber].
+    pub fn sequence_policy(&self) -> SequencePolicy {
+        self.user.sequence_policy()
+    }
+
+    /// Return signed and tagged packets from generic message handling (`receive_message`,
+    /// `fetch_next_msgs`, `sync_state`) as `MessageContent::LazyPacket` with the masked payload
+    /// decrypted on first access. Signatures and MACs are still verified on receive [Author, Subscriber].
+    ///
+    /// # Arguments
+    /// * `lazy` - Defer decryption of masked payloads
+    ///
+    pub fn set_lazy_payloads(&mut self, lazy: bool) {
+        self.user.set_defer_masked_payloads(lazy)
+    }
+
+    /// Transparently send an updated keyload for everyone ahead of the next packet of a branch
+    /// once subscribers were added or removed since its keyload [Author].
+    ///
+    /// Branches rooted at keyloads sent with `send_keyload_for_everyone` after auto rekey is
+    /// enabled are tracked, keyloads for explicit recipient lists are not. A packet linked to a
+    /// message of a stale branch is linked to a new keyload instead, linked to the same message
+    /// as the stale keyload, eg. the announcement, so new subscribers can process it. Sent
+    /// rekey keyloads are reported with `UserEvents::on_rekey`. Tracked branches are part of
+    /// the exported state.
+    ///
+    /// # Arguments
+    /// * `auto_rekey` - Send updated keyloads on membership changes
+    ///
+    pub fn set_auto_rekey(&mut self, auto_rekey: bool) {
+        self.state.auto_rekey = auto_rekey;
+    }
+
+    /// Return boolean representing transparent rekeying of branches on membership changes
+    pub fn is_auto_rekey(&self) -> bool {
+        self.state.auto_rekey
+    }
+
+    /// Queue received subscriptions for an explicit decision instead of accepting them [Author].
+    ///
+    /// With approval, `receive_subscribe` verifies a subscribe message and queues it, the
+    /// subscriber becomes a keyload recipient only once accepted with `accept_subscription`.
+    /// Decisions are reported with `UserEvents::on_subscription` and
+    /// `UserEvents::on_subscription_rejected`. Pending subscriptions are part of the exported
+    /// state.
+    ///
+    /// # Arguments
+    /// * `approval` - Queue subscriptions until accepted or rejected
+    ///
+    pub fn set_subscription_approval(&mut self, approval: bool) {
+        self.state.subscription_approval = approval;
+    }
+
+    /// Return boolean representing explicit approval of subscriptions
+    pub fn is_subscription_approval(&self) -> bool {
+        self.state.subscription_approval
+    }
+
+    /// Cap the number of keyloads whose unwrapped session state is cached [Author, Subscriber].
+    ///
+    /// # Arguments
+    /// * `capacity` - Maximum number of cached keyloads, `0` disables caching
+    ///
+    pub fn set_session_cache_capacity(&mut self, capacity: usize) {
+        self.user.set_session_cache_capacity(capacity)
+    }
+
+    /// Forget cached keyload session states [Author, Subscriber].
+    pub fn clear_session_cache(&mut self) {
+        self.user.clear_session_cache()
+    }
+
+    /// Number of keyloads with cached session state [Author, Subscriber].
+    pub fn cached_sessions(&self) -> usize {
+        self.user.cached_sessions()
+    }
+
+    /// Drop state of messages processed before a checkpoint. The announcement and delegated
+    /// branches are kept, so new messages can still be processed; unwrapping a message linked
+    /// to a dropped one fails with a link not found error [Author, Subscriber].
+    ///
+    /// # Arguments
+    /// * `link` - Address of the checkpoint message, kept with all later messages
+    ///
+    pub fn prune_before(&mut self, link: &Address) -> Result<usize> {
+        self.user.prune_before(&link.msgid)
+    }
+
+    /// Approximate memory used by link store, spongos states, cursors and other user
+    /// state, to validate RAM budgets and detect state growth [Author, Subscriber]
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        self.user.memory_footprint()
+    }
+
+    /// Store a pre-shared key. Keyloads shared with several PSK ids are unwrapped with the
+    /// PSK of a matching id, so recipient groups may be addressed by one PSK each [Author, Subscriber].
+    ///
+    /// # Arguments
+    /// * `pskid` - Identifier of the pre-shared key, as listed in keyloads
+    /// * `psk` - Pre-shared key
+    ///
+    pub fn store_psk(&mut self, pskid: PskId, psk: Psk) {
+        self.user.store_psk(pskid, psk)
+    }
+
+    /// Generate random pre-shared keys reserved for recipients joining later. Listing the slot
+    /// ids in a keyload lets a device later given a slot key read the branch without a new
+    /// keyload [Author].
+    ///
+    /// # Arguments
+    /// * `count` - Number of slots to reserve
+    ///
+    pub fn reserve_psk_slots(&mut self, count: usize) -> Result<Vec<(PskId, Psk)>> {
+        self.ensure_author()?;
+        Ok(self.user.reserve_psk_slots(count))
+    }
+
+    /// Set delivery priority (0 to `hdf::MAX_PRIORITY`) of subsequently sent messages [Author, Subscriber].
+    ///
+    /// # Arguments
+    /// * `priority` - Priority in the message header, transports queueing messages send
+    ///   higher priority ones first
+    pub fn set_priority(&mut self, priority: u8) -> Result<()> {
+        self.user.set_priority(priority)
+    }
+
+    /// Account a packet of publisher `pk` sent at `timestamp`; packets of unknown publishers are not limited.
+    /// Register callbacks notified of processed messages, replacing previously registered ones [Author, Subscriber].
+    ///
+    /// # Arguments
+    /// * `events` - Application hooks, see `UserEvents`
+    ///
+    pub fn register_events<E: UserEvents + 'static>(&mut self, events: E) {
+        self.events = Some(Box::new(events));
+    }
+
+    /// Remove registered callbacks [Author, Subscriber].
+    pub fn unregister_events(&mut self) {
+        self.events = None;
+    }
+
+    /// Register a hook notarizing every successfully sent message, replacing a previously
+    /// registered one [Author, Subscriber].
+    ///
+    /// # Arguments
+    /// * `notarizer` - Hook anchoring sent messages into an external system, see `Notarizer`
+    ///
+    pub fn set_notarizer<N: Notarizer + 'static>(&mut self, notarizer: N) {
+        self.notarizer = Some(Box::new(notarizer));
+    }
+
+    /// Remove the registered notarizer [Author, Subscriber].
+    pub fn clear_notarizer(&mut self) {
+        self.notarizer = None;
+    }
+
+    /// Retain binary messages received from transport, so that exactly what was published can be
+    /// archived alongside the unwrapped contents. Disabling retention drops retained messages
+    /// [Author, Subscriber].
+    ///
+    /// # Arguments
+    /// * `retain` - Whether to keep received binary messages
+    ///
+    pub fn set_retain_raw(&mut self, retain: bool) {
+        match (retain, self.retained.is_some()) {
+            (true, false) => self.retained = Some(HashMap::new()),
+            (false, true) => self.retained = None,
+            _ => {}
+        }
+    }
+
+    /// Retained binary message at `link`, if retention is enabled [Author, Subscriber].
+    ///
+    /// # Arguments
+    /// * `link` - Address of the message, eg. link of an unwrapped message
+    ///
+    pub fn raw(&self, link: &Address) -> Option<&Message> {
+        self.retained.as_ref().and_then(|retained| retained.get(link))
+    }
+
+    /// Remove and return all retained binary messages, eg. to archive them [Author, Subscriber].
+    pub fn take_raw(&mut self) -> Vec<Message> {
+        match self.retained.as_mut() {
+            Some(retained) => retained.drain().map(|(_link, msg)| msg).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Set header predicate of packets to be unwrapped by `fetch_next_msgs` and `sync_state`,
+    /// eg. matching content type, priority or header extension. Rejected packets are skipped
+    /// without unwrapping, sequencing state still advances past them; other messages are always
+    /// processed [Author, Subscriber].
+    ///
+    /// # Arguments
+    /// * `filter` - Predicate on the header of a fetched packet, `true` to unwrap the packet
+    ///
+    pub fn set_fetch_filter<P: Fn(&Header) -> bool + 'static>(&mut self, filter: P) {
+        self.fetch_filter = Some(Box::new(filter));
+    }
+
+    /// Remove fetch filter, fetched packets are all unwrapped [Author, Subscriber].
+    pub fn clear_fetch_filter(&mut self) {
+        self.fetch_filter = None;
+    }
+
+    fn recv_message(&mut self, link: &Address) -> Result<Message> {
+        let msg = self.transport.recv_message(link)?;
+        if let Some(retained) = self.retained.as_mut() {
+            retained.insert(link.clone(), msg.clone());
+        }
+        Ok(msg)
+    }
+
+    fn emit<Ev: FnOnce(&mut dyn UserEvents)>(&mut self, event: Ev) {
+        if let Some(events) = self.events.as_mut() {
+            event(events.as_mut());
+        }
+    }
+
+    fn check_rate(&mut self, pk: Option<&PublicKey>, timestamp: u64, link: &Address) -> Result<()> {
+        if let Some(pk) = pk {
+            if !self.user.check_rate(pk, &link.msgid, timestamp)? {
+                log::warn!("Publisher of {} exceeded rate policy", link);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check packet of `pk` against the sequence policy, violations are reported to the
+    /// registered events.
+    fn check_sequence(&mut self, pk: Option<&PublicKey>, seq_num: u64, link: &Address) -> Result<()> {
+        if let Some(pk) = pk {
+            let (violation, accepted) = self.user.check_sequence(pk, seq_num);
+            if let Some(violation) = violation {
+                log::warn!("Message {} violates sequence: {:?}", link, violation);
+                self.emit(|events| events.on_sequence_violation(link, pk, &violation));
+                ensure!(accepted, "Message {} rejected by sequence policy: {:?}", link, violation);
+            }
+        }
+        Ok(())
+    }
+
+    fn ensure_keyload_allowed(&self, link_to: &Address) -> Result<()> {
+        ensure!(
+            self.user.can_share_keyload(&link_to.msgid),
+            "Operation requires the Author role or delegation of the branch."
+        );
+        Ok(())
+    }
+
+    fn ensure_subscriber(&self) -> Result<()> {
+        ensure!(!self.is_author(), "Operation requires the Subscriber role.");
+        Ok(())
+    }
+
 
     // Send
 
@@ -89,7 +516,7 @@ where
     ///
     fn send_sequence(&mut self, wrapped: WrappedSequence) -> Result<Option<Address>> {
         if let Some(seq_msg) = wrapped.0 {
-            self.transport.send_message(&Message::new(seq_msg))?;
+            self.send_binary(seq_msg)?;
         }
 
         if let Some(wrap_state) = wrapped.1 {
@@ -99,9 +526,24 @@ where
         }
     }
 
+    /// Send a wrapped message and give its buffer back to the user for subsequent wraps.
+    fn send_binary(&mut self, binary: BinaryMessage<DefaultF, Address>) -> Result<()> {
+        let msg = Message::new(binary);
+        self.transport.send_message(&msg)?;
+        self.state.sent_at = msg.timestamp;
+        if let Some(notarizer) = self.notarizer.as_mut() {
+            let link = msg.binary.link.clone();
+            if let Err(e) = notarizer.notarize(&link, &message_hash(&msg), msg.milestone.as_ref()) {
+                log::warn!("Failed to notarize {}: {}", link, e);
+            }
+        }
+        self.user.recycle(msg.binary);
+        Ok(())
+    }
+
     /// Send a message without using sequencing logic. Reserved for Announce and Subscribe messages
     fn send_message(&mut self, msg: WrappedMessage, info: MsgInfo) -> Result<Address> {
-        self.transport.send_message(&Message::new(msg.message))?;
+        self.send_binary(msg.message)?;
         self.user.commit_wrapped(msg.wrapped, info)
     }
 
@@ -118,19 +560,186 @@ where
         ref_link: &MsgId,
         info: MsgInfo,
     ) -> Result<(Address, Option<Address>)> {
+        let keyload = matches!(info, MsgInfo::Keyload);
         let seq = self.user.wrap_sequence(ref_link)?;
-        self.transport.send_message(&Message::new(msg.message))?;
+        self.send_binary(msg.message)?;
         let seq_link = self.send_sequence(seq)?;
         let msg_link = self.user.commit_wrapped(msg.wrapped, info)?;
+        self.user.advance_linear_branch(ref_link, msg_link.msgid.clone());
+        if keyload {
+            let policy = self.user.branch_policy();
+            self.user.register_branch(&msg_link.msgid, policy);
+            let rate_policy = *self.user.rate_policy();
+            self.user.register_rate_policy(&msg_link.msgid, rate_policy);
+        } else if let Some(branch) = self.state.rekey_epochs.get(ref_link).cloned() {
+            self.state.rekey_epochs.insert(msg_link.msgid.clone(), branch);
+        }
+        if !keyload {
+            self.expire_revocations()?;
+            self.refresh_directory()?;
+        }
         Ok((msg_link, seq_link))
     }
 
+    /// Count a sent packet and publish the directory once the refresh interval is reached,
+    /// linked to the previous directory.
+    fn refresh_directory(&mut self) -> Result<()> {
+        let (interval, sent) = self.state.directory_refresh;
+        if interval == 0 {
+            return Ok(());
+        }
+        self.state.directory_refresh.1 = sent + 1;
+        if sent + 1 >= interval {
+            if let Some(link_to) = self.state.directory_link.clone() {
+                self.send_directory(&link_to)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Count a sent packet in the grace windows of pending revocations and remove the
+    /// subscribers whose window has ended.
+    fn expire_revocations(&mut self) -> Result<()> {
+        let now = self.state.sent_at;
+        for (_, _, sent) in self.state.revocations.iter_mut() {
+            *sent += 1;
+        }
+        let expired: Vec<PublicKey> = self
+            .state
+            .revocations
+            .iter()
+            .filter(|(_, window, sent)| !window.is_open(*sent, now))
+            .map(|(pk, _, _)| pk.clone())
+            .collect();
+        self.state
+            .revocations
+            .retain(|(_, window, sent)| window.is_open(*sent, now));
+        for pk in expired {
+            self.user.remove_subscriber(&pk)?;
+        }
+        Ok(())
+    }
+
+    /// Link a packet linked to `link_to` is sent linked to: with auto rekey, a new keyload for
+    /// everyone if the subscribers changed since the keyload of the branch of `link_to`. The
+    /// keyload is linked to the same message as the keyload it replaces.
+    fn rekey_link_to(&mut self, link_to: &Address) -> Result<Address> {
+        let epoch = self.user.membership_epoch();
+        match self.state.rekey_epochs.get(&link_to.msgid).cloned() {
+            Some((branch_epoch, root, stale)) if self.state.auto_rekey && branch_epoch != epoch => {
+                let (keyload_link, _) = self.send_keyload_for_everyone(&root)?;
+                for entry in self.state.directory.entries.iter_mut() {
+                    if entry.keyload.msgid == stale {
+                        entry.keyload = keyload_link.clone();
+                    }
+                }
+                self.emit(|events| events.on_rekey(&keyload_link, link_to));
+                Ok(keyload_link)
+            }
+            _ => Ok(link_to.clone()),
+        }
+    }
+
     /// Send an announcement message, generating a channel [Author].
     pub fn send_announce(&mut self) -> Result<Address> {
+        self.ensure_author()?;
         let msg = self.user.announce()?;
         self.send_message(msg, MsgInfo::Announce)
     }
 
+    /// Create a hybrid channel without announcing it: the announcement and signed packets of
+    /// the author are signed with both Ed25519 and MSS, a hash-based post-quantum signature
+    /// scheme [Author].
+    ///
+    /// # Arguments
+    /// * `channel_idx` - Index of the channel, different indices give different channel addresses
+    /// * `mss_height` - Height of the MSS Merkle tree, the author can sign 2^mss_height messages [1-16]
+    ///
+    #[cfg(feature = "pq-sig")]
+    pub fn create_hybrid_channel(&mut self, channel_idx: u64, mss_height: usize) -> Result<()> {
+        self.user.create_hybrid_channel(channel_idx, mss_height)
+    }
+
+    /// Number of messages the author of a hybrid channel can still sign [Author].
+    #[cfg(feature = "pq-sig")]
+    pub fn remaining_pq_signatures(&self) -> Option<u32> {
+        self.user.remaining_pq_signatures()
+    }
+
+    /// Index of the next MSS leaf the author of a hybrid channel signs with. Keep the latest
+    /// index outside of exported states and pass it to `import_hybrid`: importing a state
+    /// exported before later signatures would reuse MSS one-time keys [Author].
+    #[cfg(feature = "pq-sig")]
+    pub fn next_pq_signature_index(&self) -> Option<u32> {
+        self.user.next_pq_signature_index()
+    }
+
+    /// Create a channel authored by the threshold group `group_pk` without announcing it. The
+    /// user coordinates the group's messages, see `frost` [Author].
+    ///
+    /// # Arguments
+    /// * `group_pk` - Public Key of the threshold group
+    /// * `channel_idx` - Index of the channel, different indices give different channel addresses
+    ///
+    pub fn create_threshold_channel(&mut self, group_pk: &PublicKey, channel_idx: u64) -> Result<()> {
+        self.user.create_threshold_channel(group_pk, channel_idx)
+    }
+
+    /// Hash of the announcement of a threshold channel to be signed by the group with
+    /// `threshold_signature_context(ANNOUNCE)` [Author].
+    pub fn threshold_announcement_tag(&self) -> Result<[u8; 64]> {
+        self.user.threshold_announcement_tag()
+    }
+
+    /// Send the announcement of a threshold channel signed by the group [Author].
+    ///
+    /// # Arguments
+    /// * `sig` - Group signature of the announcement tag
+    ///
+    pub fn send_threshold_announce(&mut self, sig: &Signature) -> Result<Address> {
+        let msg = self.user.threshold_announce(sig)?;
+        self.send_message(msg, MsgInfo::Announce)
+    }
+
+    /// Hash of the next signed packet of a threshold channel to be signed by the group with
+    /// `threshold_signature_context(SIGNED_PACKET)`. No message may be sent or received before the packet is sent [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///
+    pub fn threshold_signed_packet_tag(
+        &self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<[u8; 64]> {
+        self.user
+            .threshold_signed_packet_tag(&link_to.msgid, public_payload, masked_payload)
+    }
+
+    /// Send a signed packet of a threshold channel signed by the group [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `sig` - Group signature of the packet tag
+    ///
+    pub fn send_threshold_signed_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        sig: &Signature,
+    ) -> Result<(Address, Option<Address>)> {
+        let msg = self
+            .user
+            .threshold_sign_packet(&link_to.msgid, public_payload, masked_payload, sig)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::SignedPacket)
+    }
+
     /// Create and send a signed packet [Author, Subscriber].
     ///
     ///  # Arguments
@@ -144,10 +753,35 @@ where
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<(Address, Option<Address>)> {
+        let link_to = &self.rekey_link_to(link_to)?;
         let msg = self.user.sign_packet(&link_to.msgid, public_payload, masked_payload)?;
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::SignedPacket)
     }
 
+    /// Create and send a signed packet co-signed with a secp256k1 key, eg. one known to an
+    /// Ethereum contract [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `cosigner` - Secp256k1 key co-signing the packet digest
+    ///
+    #[cfg(feature = "secp256k1")]
+    pub fn send_signed_packet_cosigned(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        cosigner: &secp256k1::SigningKey,
+    ) -> Result<(Address, Option<Address>)> {
+        let link_to = &self.rekey_link_to(link_to)?;
+        let msg = self
+            .user
+            .cosign_packet(&link_to.msgid, public_payload, masked_payload, cosigner)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::SignedPacket)
+    }
+
     /// Create and send a tagged packet [Author, Subscriber].
     ///
     ///  # Arguments
@@ -161,10 +795,355 @@ where
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<(Address, Option<Address>)> {
+        let link_to = &self.rekey_link_to(link_to)?;
         let msg = self.user.tag_packet(&link_to.msgid, public_payload, masked_payload)?;
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::TaggedPacket)
     }
 
+    /// Create and send a deniable packet, authenticated with a MAC keyed by the branch session
+    /// key instead of a signature. Any recipient of the branch keyload could have produced it,
+    /// so the packet doesn't prove its sender to anybody [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of a keyload the user has the session key of or of another deniable packet
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///
+    pub fn send_deniable_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        let (_spongos, info) = self.user.link_store.borrow().lookup(&link_to.msgid)?;
+        ensure!(
+            DENIABLE_LINKS.contains(&info),
+            "Deniable packet must be linked to a keyload or to a deniable packet, {} is not",
+            link_to
+        );
+        let msg = self
+            .user
+            .deniable_packet(&link_to.msgid, public_payload, masked_payload)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::DeniablePacket)
+    }
+
+    /// Create and send a private packet [Author, Subscriber]. The private payload can only be
+    /// decrypted by the recipient identified by `recipient_pk`.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access
+    ///  * `recipient_pk` - ed25519 Public Key of the recipient of the private payload
+    ///  * `private_payload` - Wrapped vector of Bytes accessible by the recipient only
+    ///
+    pub fn send_private_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        recipient_pk: &PublicKey,
+        private_payload: &Bytes,
+    ) -> Result<(Address, Option<Address>)> {
+        let link_to = &self.rekey_link_to(link_to)?;
+        let msg = self.user.private_packet(&link_to.msgid, public_payload, masked_payload, recipient_pk, private_payload)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::PrivatePacket)
+    }
+
+    /// Create and send a signed packet with the masked payload offloaded to a blob store [Author, Subscriber].
+    /// The masked payload is encrypted with a fresh key and put into `blobs`, the packet carries
+    /// only the masked reference to it.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access, stored in `blobs`
+    ///  * `blobs` - Blob store the masked payload is offloaded to
+    ///
+    pub fn send_signed_packet_offloaded<B: BlobStore>(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        blobs: &mut B,
+    ) -> Result<(Address, Option<Address>)> {
+        let offloaded = OffloadedPayload::offload::<DefaultF, B>(&masked_payload.0, NBytes::from(prng::random_key()), blobs)?;
+        self.send_signed_packet(link_to, public_payload, &Bytes(offloaded.to_bytes()))
+    }
+
+    /// Create and send a tagged packet with the masked payload offloaded to a blob store [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access, stored in `blobs`
+    ///  * `blobs` - Blob store the masked payload is offloaded to
+    ///
+    pub fn send_tagged_packet_offloaded<B: BlobStore>(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        blobs: &mut B,
+    ) -> Result<(Address, Option<Address>)> {
+        let offloaded = OffloadedPayload::offload::<DefaultF, B>(&masked_payload.0, NBytes::from(prng::random_key()), blobs)?;
+        self.send_tagged_packet(link_to, public_payload, &Bytes(offloaded.to_bytes()))
+    }
+
+    /// Create and send a signed packet with the masked payload embargoed until a later release [Author].
+    /// The masked payload is encrypted with a fresh key, kept in the pending embargoes until it's
+    /// sent with `send_release` or `send_due_releases`.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `public_payload` - Wrapped vector of Bytes to have public access
+    ///  * `masked_payload` - Wrapped vector of Bytes to have masked access once released
+    ///  * `release_at` - Milestone index the payload is due to be released at, `None` to release at will
+    ///
+    pub fn send_embargoed_packet(
+        &mut self,
+        link_to: &Address,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        release_at: Option<u32>,
+    ) -> Result<(Address, Option<Address>)> {
+        ensure!(release_at != Some(0), "Release milestone index must be positive.");
+        let release = EmbargoRelease {
+            id: NBytes::from(prng::random_nonce()),
+            key: NBytes::from(prng::random_key()),
+        };
+        let embargoed = EmbargoedPayload::seal::<DefaultF>(&masked_payload.0, &release, release_at);
+        let (link, seq_link) = self.send_signed_packet(link_to, public_payload, &Bytes(embargoed.to_bytes()))?;
+        self.state.embargoes.push(Embargo {
+            link: link.clone(),
+            release_at,
+            release,
+        });
+        Ok((link, seq_link))
+    }
+
+    /// Pending embargoes of sent embargoed packets [Author]. They are part of the exported
+    /// state, so payloads can be released after a restart.
+    pub fn embargoes(&self) -> &[Embargo] {
+        &self.state.embargoes
+    }
+
+    /// Restore a pending embargo, eg. one exported by an earlier release [Author].
+    pub fn add_embargo(&mut self, embargo: Embargo) {
+        self.state.embargoes.push(embargo);
+    }
+
+    /// Create and send the release of a pending embargo, a signed packet with the key of the
+    /// embargoed payload as masked payload [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the release will be attached to
+    ///  * `embargoed_link` - Address of the embargoed packet
+    ///
+    pub fn send_release(&mut self, link_to: &Address, embargoed_link: &Address) -> Result<(Address, Option<Address>)> {
+        let pos = self
+            .state
+            .embargoes
+            .iter()
+            .position(|e| &e.link == embargoed_link)
+            .ok_or_else(|| anyhow!("No pending embargo for {}.", embargoed_link))?;
+        let release = Bytes(self.state.embargoes[pos].release.to_bytes());
+        let sent = self.send_signed_packet(link_to, &Bytes::default(), &release)?;
+        self.state.embargoes.remove(pos);
+        Ok(sent)
+    }
+
+    /// Create and send the releases of pending embargoes due at milestone index `milestone`,
+    /// returns the addresses of the releases [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the releases will be attached to
+    ///  * `milestone` - Index of the latest milestone
+    ///
+    pub fn send_due_releases(&mut self, link_to: &Address, milestone: u32) -> Result<Vec<Address>> {
+        let due: Vec<Address> = self
+            .state
+            .embargoes
+            .iter()
+            .filter(|e| e.release_at.map_or(false, |release_at| release_at <= milestone))
+            .map(|e| e.link.clone())
+            .collect();
+        let mut links = Vec::with_capacity(due.len());
+        for embargoed_link in due {
+            let (link, _) = self.send_release(link_to, &embargoed_link)?;
+            links.push(link);
+        }
+        Ok(links)
+    }
+
+    /// Create and send a signed packet committing to `fields` for selective disclosure [Author].
+    /// The public payload is the salted commitments of the fields, returns the openings of the
+    /// fields as well: they are not stored by the user, keep them to disclose the fields later.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the packet will be attached to
+    ///  * `fields` - Values of the fields, in order
+    ///
+    pub fn send_disclosable_packet(
+        &mut self,
+        link_to: &Address,
+        fields: &[Bytes],
+    ) -> Result<(Address, Option<Address>, Vec<FieldOpening>)> {
+        let openings: Vec<FieldOpening> = fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| FieldOpening {
+                index: i as u32,
+                salt: NBytes::from(prng::random_nonce()),
+                value: field.0.clone(),
+            })
+            .collect();
+        let commitments = FieldCommitments::commit::<DefaultF>(&openings)?;
+        let (link, seq_link) = self.send_signed_packet(link_to, &Bytes(commitments.to_bytes()), &Bytes::default())?;
+        Ok((link, seq_link, openings))
+    }
+
+    /// Forget subscriber `pk`, subsequent keyloads for everyone are not shared with it. Returns
+    /// whether the subscriber was known [Author].
+    ///
+    ///  # Arguments
+    ///  * `pk` - Public Key of the subscriber
+    ///
+    pub fn remove_subscriber(&mut self, pk: &PublicKey) -> Result<bool> {
+        self.ensure_author()?;
+        self.state.revocations.retain(|(revoked, _, _)| revoked != pk);
+        self.user.remove_subscriber(pk)
+    }
+
+    /// Revoke subscriber `pk` after a grace window and announce the revocation in a signed
+    /// packet, masked for the branch of `link_to` [Author].
+    ///
+    /// Keyloads shared with the subscriber remain in use during the window, the subscriber is
+    /// removed at the first packet sent after it ends. With auto rekey the next packet is then
+    /// preceded by a keyload excluding it, see `set_auto_rekey`. Time windows are checked
+    /// against timestamps of sent messages, which are 0 without the `std` feature.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the revocation will be attached to
+    ///  * `pk` - Public Key of the revoked subscriber
+    ///  * `grace` - Grace window of the subscriber
+    ///
+    pub fn revoke_subscriber(
+        &mut self,
+        link_to: &Address,
+        pk: &PublicKey,
+        grace: GraceWindow,
+    ) -> Result<(Address, Option<Address>, AccessWindow)> {
+        self.ensure_author()?;
+        ensure!(self.user.pk_store.get(pk).is_some(), "Unknown subscriber.");
+        let revocation = Revocation {
+            pk: pk.clone(),
+            grace,
+        };
+        let (link, seq_link) = self.send_signed_packet(link_to, &Bytes::default(), &Bytes(revocation.to_bytes()))?;
+        let window = AccessWindow {
+            revoked_at: self.state.sent_at,
+            grace,
+        };
+        self.state.revocations.retain(|(revoked, _, _)| revoked != pk);
+        if window.is_open(0, self.state.sent_at) {
+            self.state.revocations.push((pk.clone(), window, 0));
+        } else {
+            self.user.remove_subscriber(pk)?;
+        }
+        Ok((link, seq_link, window))
+    }
+
+    /// Remaining access window of revoked subscriber `pk`, `None` if it isn't pending
+    /// revocation [Author].
+    pub fn revocation_window(&self, pk: &PublicKey) -> Option<AccessWindow> {
+        self.state
+            .revocations
+            .iter()
+            .find(|(revoked, _, _)| revoked == pk)
+            .map(|(_, window, sent)| window.after(*sent))
+    }
+
+    /// Group subscribers in a key tree of `capacity` members: membership changes are sent as
+    /// updates logarithmic in the number of members, and keyloads for the whole group list
+    /// only its group key, see `send_tree_keyload` [Author].
+    ///
+    ///  # Arguments
+    ///  * `capacity` - Largest number of members, up to `key_tree::MAX_TREE_CAPACITY`
+    ///
+    pub fn enable_key_tree(&mut self, capacity: usize) -> Result<()> {
+        self.ensure_author()?;
+        ensure!(self.state.key_tree.is_none(), "Key tree is already enabled.");
+        self.state.key_tree = Some(KeyTree::new(capacity)?);
+        Ok(())
+    }
+
+    /// Id of the group key of the key tree, `None` unless enabled [Author].
+    pub fn tree_pskid(&self) -> Option<PskId> {
+        self.state
+            .key_tree
+            .as_ref()
+            .map(|tree| pskid_from_psk(tree.group_key()))
+    }
+
+    fn key_tree_mut(&mut self) -> Result<&mut KeyTree> {
+        self.state
+            .key_tree
+            .as_mut()
+            .ok_or_else(|| anyhow!("Key tree is not enabled."))
+    }
+
+    fn store_tree_psk(&mut self) {
+        if let Some(psk) = self.state.key_tree.as_ref().map(|tree| *tree.group_key()) {
+            self.store_psk(pskid_from_psk(&psk), psk);
+        }
+    }
+
+    /// Add subscriber `pk` to the key tree and send the update in a private packet, the
+    /// subscriber's welcome is its private payload [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the update will be attached to
+    ///  * `pk` - Public Key of the new member
+    ///
+    pub fn tree_add_member(&mut self, link_to: &Address, pk: &PublicKey) -> Result<(Address, Option<Address>)> {
+        self.ensure_author()?;
+        let (welcome, update) = self.key_tree_mut()?.add::<DefaultF>(pk)?;
+        self.store_tree_psk();
+        self.send_private_packet(
+            link_to,
+            &Bytes(update.to_bytes()),
+            &Bytes::default(),
+            pk,
+            &Bytes(welcome.to_bytes()),
+        )
+    }
+
+    /// Remove member `pk` from the key tree and send the update in a signed packet, keyloads
+    /// sent with `send_tree_keyload` afterwards can't be read by it [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the update will be attached to
+    ///  * `pk` - Public Key of the removed member
+    ///
+    pub fn tree_remove_member(&mut self, link_to: &Address, pk: &PublicKey) -> Result<(Address, Option<Address>)> {
+        self.ensure_author()?;
+        let update = self.key_tree_mut()?.remove::<DefaultF>(pk)?;
+        self.store_tree_psk();
+        self.send_signed_packet(link_to, &Bytes(update.to_bytes()), &Bytes::default())
+    }
+
+    /// Create and send a keyload for the current members of the key tree, it lists the group
+    /// key only whatever the number of members [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///
+    pub fn send_tree_keyload(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        let pskid = self.tree_pskid().ok_or_else(|| anyhow!("Key tree is not enabled."))?;
+        self.send_keyload(link_to, &vec![pskid], &Vec::new())
+    }
+
     /// Create and send a new keyload for a list of subscribers [Author].
     ///
     ///  # Arguments
@@ -178,18 +1157,181 @@ where
         psk_ids: &PskIds,
         ke_pks: &Vec<PublicKey>,
     ) -> Result<(Address, Option<Address>)> {
+        self.ensure_keyload_allowed(link_to)?;
         let msg = self.user.share_keyload(&link_to.msgid, psk_ids, ke_pks)?;
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)
     }
 
+    /// Create and send a new keyload for a list of subscribers, recipients in `read_only`
+    /// may not publish in its branch. The branch accepts signed packets only, tagged packets
+    /// can't be attributed to their sender [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    ///  * `read_only` - Vector of Public Keys of recipients allowed to read only
+    ///
+    pub fn send_keyload_with_read_only(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<PublicKey>,
+        read_only: &Vec<PublicKey>,
+    ) -> Result<(Address, Option<Address>)> {
+        self.ensure_keyload_allowed(link_to)?;
+        let msg = self.user.share_keyload_with_read_only(&link_to.msgid, psk_ids, ke_pks, read_only)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)
+    }
+
+    /// Create and send a new keyload for a list of subscribers without listing their public
+    /// keys, so observers can't tell who has access to the branch. Recipients find their
+    /// key slot by trial decryption; all of them may publish [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `ke_pks`  - Vector of Public Keys of the recipients
+    ///
+    pub fn send_anonymous_keyload(
+        &mut self,
+        link_to: &Address,
+        ke_pks: &Vec<PublicKey>,
+    ) -> Result<(Address, Option<Address>)> {
+        self.ensure_keyload_allowed(link_to)?;
+        let msg = self.user.share_anonymous_keyload(&link_to.msgid, ke_pks)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)
+    }
+
     /// Create and send keyload for all subscribed subscribers [Author].
     ///
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
     ///
     pub fn send_keyload_for_everyone(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.ensure_keyload_allowed(link_to)?;
         let msg = self.user.share_keyload_for_everyone(&link_to.msgid)?;
-        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)
+        let sent = self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)?;
+        if self.state.auto_rekey {
+            let branch = (self.user.membership_epoch(), link_to.clone(), sent.0.msgid.clone());
+            self.state.rekey_epochs.insert(sent.0.msgid.clone(), branch);
+        }
+        Ok(sent)
+    }
+
+    /// Create and send a branch delegation, allowing the delegate to send keyloads linked to it [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the delegation will be attached to
+    ///  * `delegate_pk` - Public Key of the delegate of the new branch
+    ///
+    pub fn send_branch_delegation(
+        &mut self,
+        link_to: &Address,
+        delegate_pk: &PublicKey,
+    ) -> Result<(Address, Option<Address>)> {
+        self.ensure_author()?;
+        let msg = self.user.delegate_branch(&link_to.msgid, delegate_pk)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::BranchDelegation)
+    }
+
+    /// Create and send a checkpoint of the sequencing state of all known publishers, new
+    /// subscribers can continue from it instead of from the announcement [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the checkpoint will be attached to
+    ///
+    pub fn send_checkpoint(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.ensure_author()?;
+        let msg = self.user.checkpoint(&link_to.msgid)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Checkpoint)
+    }
+
+    /// List a branch in the directory of the channel under `name`, replacing the entry of the
+    /// same name. Entries of branches rekeyed automatically follow the new keyloads, see
+    /// `set_auto_rekey` [Author].
+    ///
+    ///  # Arguments
+    ///  * `name` - Name of the branch
+    ///  * `keyload` - Address of the latest keyload of the branch
+    ///  * `content_types` - Content types of the packets of the branch, eg. MIME types
+    ///
+    pub fn set_directory_entry(&mut self, name: &str, keyload: &Address, content_types: &[&str]) -> Result<()> {
+        self.ensure_author()?;
+        ensure!(name.len() <= u8::MAX as usize, "Branch name too long: {}.", name);
+        ensure!(
+            content_types.len() <= u8::MAX as usize && content_types.iter().all(|t| t.len() <= u8::MAX as usize),
+            "Too many or too long content types of branch {}.",
+            name
+        );
+        self.state.directory.insert(DirectoryEntry {
+            name: name.to_string(),
+            keyload: keyload.clone(),
+            content_types: content_types.iter().map(|t| t.to_string()).collect(),
+        });
+        Ok(())
+    }
+
+    /// Remove the branch named `name` from the directory, returns whether it was listed [Author].
+    pub fn remove_directory_entry(&mut self, name: &str) -> Result<bool> {
+        self.ensure_author()?;
+        Ok(self.state.directory.remove(name))
+    }
+
+    /// Directory of the channel: maintained by the author, the latest received one otherwise
+    /// [Author, Subscriber].
+    pub fn directory(&self) -> &Directory {
+        &self.state.directory
+    }
+
+    /// Address of the latest published or received directory [Author, Subscriber].
+    pub fn directory_link(&self) -> Option<&Address> {
+        self.state.directory_link.as_ref()
+    }
+
+    /// Create and send the directory in a signed packet, link it to the announcement to make it
+    /// public [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the directory will be attached to
+    ///
+    pub fn send_directory(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
+        self.ensure_author()?;
+        let directory = self.state.directory.to_bytes()?;
+        // No refresh is due while the directory itself is sent.
+        let previous = self.state.directory_link.take();
+        match self.send_signed_packet(link_to, &Bytes(directory), &Bytes::default()) {
+            Ok((link, seq_link)) => {
+                self.state.directory_link = Some(link.clone());
+                self.state.directory_refresh.1 = 0;
+                Ok((link, seq_link))
+            }
+            Err(e) => {
+                self.state.directory_link = previous;
+                Err(e)
+            }
+        }
+    }
+
+    /// Publish the directory again after every `packets` sent packets, linked to the previous
+    /// directory; 0 disables refreshes. Refreshes start once a directory was sent with
+    /// `send_directory` [Author].
+    ///
+    ///  # Arguments
+    ///  * `packets` - Number of packets sent between directories
+    ///
+    pub fn set_directory_refresh(&mut self, packets: u32) -> Result<()> {
+        self.ensure_author()?;
+        self.state.directory_refresh = (packets, 0);
+        Ok(())
+    }
+
+    /// Add a keyload recipient, used by branch delegates who do not process subscriptions [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `pk` - Public Key of the recipient
+    ///
+    pub fn add_recipient(&mut self, pk: PublicKey) -> Result<()> {
+        self.user.insert_recipient(pk)
     }
 
     /// Create and Send a Subscribe message to a Channel app instance [Subscriber].
@@ -198,6 +1340,7 @@ where
     /// * `link_to` - Address of the Channel Announcement message
     ///
     pub fn send_subscribe(&mut self, link_to: &Address) -> Result<Address> {
+        self.ensure_subscriber()?;
         let msg = self.user.subscribe(&link_to.msgid)?;
         self.send_message(msg, MsgInfo::Subscribe)
     }
@@ -210,7 +1353,7 @@ where
     ///  * `link` - Address of the message to be processed
     ///
     pub fn receive_sequence(&mut self, link: &Address) -> Result<Address> {
-        let msg = self.transport.recv_message(link)?;
+        let msg = self.recv_message(link)?;
         if let Some(_addr) = &self.user.appinst {
             let seq_link = msg.binary.link.clone();
             let seq_msg = self.user.handle_sequence(msg.binary, MsgInfo::Sequence)?.body;
@@ -237,9 +1380,56 @@ where
     ///  * `link` - Address of the message to be processed
     ///
     pub fn receive_signed_packet(&mut self, link: &Address) -> Result<(PublicKey, Bytes, Bytes)> {
-        let msg = self.transport.recv_message(link)?;
-        // TODO: msg.timestamp is lost
+        let (pk, public_payload, masked_payload, _) = self.receive_signed_packet_at(link)?;
+        Ok((pk, public_payload, masked_payload))
+    }
+
+    /// Receive and process a signed packet message, returns its timestamp as well.
+    fn receive_signed_packet_at(&mut self, link: &Address) -> Result<(PublicKey, Bytes, Bytes, u64)> {
+        let msg = self.recv_message(link)?;
+        let timestamp = msg.timestamp;
+        let seq_num = msg.binary.parse_header()?.header.get_seq_num();
         let m = self.user.handle_signed_packet(msg.binary, MsgInfo::SignedPacket)?;
+        self.check_rate(Some(&m.body.0), timestamp, link)?;
+        self.check_sequence(Some(&m.body.0), seq_num, link)?;
+        let (pk, public_payload, masked_payload) = m.body;
+        Ok((pk, public_payload, masked_payload.into_bytes(), timestamp))
+    }
+
+    /// Receive and process a signed packet message together with its secp256k1 co-signature,
+    /// `None` if the packet is not co-signed [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    #[cfg(feature = "secp256k1")]
+    pub fn receive_signed_packet_cosigned(
+        &mut self,
+        link: &Address,
+    ) -> Result<(PublicKey, Bytes, Bytes, Option<secp256k1::Cosignature>)> {
+        let msg = self.recv_message(link)?;
+        let timestamp = msg.timestamp;
+        let seq_num = msg.binary.parse_header()?.header.get_seq_num();
+        let m = self.user.handle_cosigned_packet(msg.binary, MsgInfo::SignedPacket)?;
+        self.check_rate(Some(&m.body.0), timestamp, link)?;
+        self.check_sequence(Some(&m.body.0), seq_num, link)?;
+        let (pk, public_payload, masked_payload, cosignature) = m.body;
+        Ok((pk, public_payload, masked_payload.into_bytes(), cosignature))
+    }
+
+    /// Receive and process a private packet message [Author, Subscriber]. The private payload
+    /// is `None` unless the packet was addressed to this user.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_private_packet(&mut self, link: &Address) -> Result<(PublicKey, Bytes, Bytes, Option<Bytes>)> {
+        let msg = self.recv_message(link)?;
+        let timestamp = msg.timestamp;
+        let seq_num = msg.binary.parse_header()?.header.get_seq_num();
+        let m = self.user.handle_private_packet(msg.binary, MsgInfo::PrivatePacket)?;
+        self.check_rate(Some(&m.body.0), timestamp, link)?;
+        self.check_sequence(Some(&m.body.0), seq_num, link)?;
         Ok(m.body)
     }
 
@@ -249,9 +1439,193 @@ where
     ///  * `link` - Address of the message to be processed
     ///
     pub fn receive_tagged_packet(&mut self, link: &Address) -> Result<(Bytes, Bytes)> {
-        let msg = self.transport.recv_message(link)?;
+        let msg = self.recv_message(link)?;
         let m = self.user.handle_tagged_packet(msg.binary, MsgInfo::TaggedPacket)?;
-        Ok(m.body)
+        let (public_payload, masked_payload) = m.body;
+        Ok((public_payload, masked_payload.into_bytes()))
+    }
+
+    /// Receive and process a deniable packet, authenticated by the branch session key only
+    /// [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_deniable_packet(&mut self, link: &Address) -> Result<(Bytes, Bytes)> {
+        let msg = self.recv_message(link)?;
+        let m = self
+            .user
+            .handle_deniable_packet(msg.binary, MsgInfo::DeniablePacket, DENIABLE_LINKS)?;
+        let (public_payload, masked_payload) = m.body;
+        Ok((public_payload, masked_payload.into_bytes()))
+    }
+
+    /// Receive and process a signed packet message with the masked payload offloaded to a blob store [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///  * `blobs` - Blob store the masked payload is fetched from
+    ///
+    pub fn receive_signed_packet_offloaded<B: BlobStore>(
+        &mut self,
+        link: &Address,
+        blobs: &mut B,
+    ) -> Result<(PublicKey, Bytes, Bytes)> {
+        let (pk, public_payload, masked_payload) = self.receive_signed_packet(link)?;
+        let offloaded = OffloadedPayload::from_bytes(&masked_payload.0)?;
+        Ok((pk, public_payload, Bytes(offloaded.fetch::<DefaultF, B>(blobs)?)))
+    }
+
+    /// Receive and process a tagged packet message with the masked payload offloaded to a blob store [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///  * `blobs` - Blob store the masked payload is fetched from
+    ///
+    pub fn receive_tagged_packet_offloaded<B: BlobStore>(&mut self, link: &Address, blobs: &mut B) -> Result<(Bytes, Bytes)> {
+        let (public_payload, masked_payload) = self.receive_tagged_packet(link)?;
+        let offloaded = OffloadedPayload::from_bytes(&masked_payload.0)?;
+        Ok((public_payload, Bytes(offloaded.fetch::<DefaultF, B>(blobs)?)))
+    }
+
+    /// Receive and process a signed packet message with an embargoed masked payload, open it with
+    /// the matching release [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_embargoed_packet(&mut self, link: &Address) -> Result<(PublicKey, Bytes, EmbargoedPayload)> {
+        let (pk, public_payload, masked_payload) = self.receive_signed_packet(link)?;
+        Ok((pk, public_payload, EmbargoedPayload::from_bytes(&masked_payload.0)?))
+    }
+
+    /// Receive and process the release of an embargoed payload [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_release(&mut self, link: &Address) -> Result<(PublicKey, EmbargoRelease)> {
+        let (pk, _, masked_payload) = self.receive_signed_packet(link)?;
+        Ok((pk, EmbargoRelease::from_bytes(&masked_payload.0)?))
+    }
+
+    /// Receive and process the revocation of a subscriber, returns its access window. The
+    /// window of the user itself is kept, see `access_window` [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_revocation(&mut self, link: &Address) -> Result<(PublicKey, Revocation, AccessWindow)> {
+        let (pk, _, masked_payload, timestamp) = self.receive_signed_packet_at(link)?;
+        ensure!(
+            self.user.author_sig_pk.as_ref() == Some(&pk),
+            "Revocation isn't sent by the author."
+        );
+        let revocation = Revocation::from_bytes(&masked_payload.0)?;
+        let window = AccessWindow {
+            revoked_at: timestamp,
+            grace: revocation.grace,
+        };
+        if revocation.pk == *self.get_pk() {
+            self.state.access_window = Some(window);
+        }
+        Ok((pk, revocation, window))
+    }
+
+    /// Access window of the user announced by the author, `None` unless the user is revoked
+    /// [Subscriber].
+    pub fn access_window(&self) -> Option<AccessWindow> {
+        self.state.access_window
+    }
+
+    /// Receive and process a directory of the author, it replaces the directory of the user
+    /// [Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_directory(&mut self, link: &Address) -> Result<Directory> {
+        let (pk, public_payload, _) = self.receive_signed_packet(link)?;
+        ensure!(
+            self.user.author_sig_pk.as_ref() == Some(&pk),
+            "Directory isn't sent by the author."
+        );
+        let directory = Directory::from_bytes(&link.appinst, &public_payload.0)?;
+        self.state.directory = directory.clone();
+        self.state.directory_link = Some(link.clone());
+        Ok(directory)
+    }
+
+    /// Receive and process a key tree update of the author, returns whether the user is a member
+    /// of the tree after it. The group key of members is stored as pre-shared key, so keyloads
+    /// sent with `send_tree_keyload` are read like any other [Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_tree_update(&mut self, link: &Address) -> Result<bool> {
+        let msg = self.receive_message(link, None)?;
+        self.handle_tree_update(&msg)
+    }
+
+    /// Process a key tree update already unwrapped, eg. by `fetch_next_msgs`. Updates must be
+    /// processed in order [Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `msg` - Unwrapped update
+    ///
+    pub fn handle_tree_update(&mut self, msg: &UnwrappedMessage) -> Result<bool> {
+        let (pk, public_payload, welcome) = match &msg.body {
+            MessageContent::SignedPacket { pk, public_payload, .. } => (pk, public_payload, None),
+            MessageContent::PrivatePacket {
+                pk,
+                public_payload,
+                private_payload,
+                ..
+            } => (pk, public_payload, private_payload.as_ref()),
+            _ => return Err(anyhow!("Not a key tree update: {}.", msg.link)),
+        };
+        ensure!(
+            self.user.author_sig_pk.as_ref() == Some(pk),
+            "Key tree update isn't sent by the author."
+        );
+        let update = TreeUpdate::from_bytes(&public_payload.0)?;
+        if let Some(welcome) = welcome {
+            self.state.tree_keys = Some(MemberKeys::new(&TreeWelcome::from_bytes(&welcome.0)?));
+        }
+        let psk = match self.state.tree_keys.as_mut() {
+            Some(keys) => {
+                keys.apply::<DefaultF>(&update)?;
+                keys.group_key().copied()
+            }
+            None => None,
+        };
+        match psk {
+            Some(psk) => {
+                self.store_psk(pskid_from_psk(&psk), psk);
+                Ok(true)
+            }
+            None => {
+                self.state.tree_keys = None;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Whether the user is a member of the key tree of the author [Subscriber].
+    pub fn is_tree_member(&self) -> bool {
+        self.state.tree_keys.is_some()
+    }
+
+    /// Receive and process a disclosable packet, returns the commitments its disclosed fields
+    /// are checked against with `FieldCommitments::verify` [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_disclosable_packet(&mut self, link: &Address) -> Result<(PublicKey, FieldCommitments)> {
+        let (pk, public_payload, _) = self.receive_signed_packet(link)?;
+        Ok((pk, FieldCommitments::from_bytes(&public_payload.0)?))
     }
 
 
@@ -261,9 +1635,89 @@ where
     ///  * `link` - Address of the message to be processed
     ///
     pub fn receive_subscribe(&mut self, link: &Address) -> Result<()> {
-        let msg = self.transport.recv_message(link)?;
+        self.ensure_author()?;
+        let msg = self.recv_message(link)?;
+        if self.state.subscription_approval {
+            let pk = self
+                .user
+                .unwrap_subscribe(msg.binary.parse_header()?)?
+                .pcf
+                .content
+                .subscriber_sig_pk;
+            if !self
+                .state
+                .pending_subscriptions
+                .iter()
+                .any(|(pending, _, _)| pending.link == *link)
+            {
+                let pending = PendingSubscription { link: link.clone(), pk };
+                self.state.pending_subscriptions.push((pending, msg, false));
+            }
+            return Ok(());
+        }
         // TODO: Timestamp is lost.
-        self.user.handle_subscribe(msg.binary, MsgInfo::Subscribe)
+        let subscriber_pk = self.user.handle_subscribe(msg.binary, MsgInfo::Subscribe)?;
+        self.emit(|events| events.on_subscription(link, &subscriber_pk));
+        Ok(())
+    }
+
+    /// Subscriptions queued since the last call, see `set_subscription_approval` [Author].
+    pub fn fetch_pending_subscriptions(&mut self) -> Vec<PendingSubscription> {
+        self.state
+            .pending_subscriptions
+            .iter_mut()
+            .filter(|(_, _, fetched)| !*fetched)
+            .map(|(pending, _, fetched)| {
+                *fetched = true;
+                pending.clone()
+            })
+            .collect()
+    }
+
+    /// Subscriptions awaiting a decision [Author].
+    pub fn pending_subscriptions(&self) -> Vec<&PendingSubscription> {
+        self.state
+            .pending_subscriptions
+            .iter()
+            .map(|(pending, _, _)| pending)
+            .collect()
+    }
+
+    fn take_pending_subscription(&mut self, link: &Address) -> Result<(PendingSubscription, Message)> {
+        self.ensure_author()?;
+        let i = self
+            .state
+            .pending_subscriptions
+            .iter()
+            .position(|(pending, _, _)| pending.link == *link)
+            .ok_or_else(|| anyhow!("No pending subscription at {}.", link))?;
+        let (pending, msg, _) = self.state.pending_subscriptions.remove(i);
+        Ok((pending, msg))
+    }
+
+    /// Accept a pending subscription, the subscriber is included in subsequent keyloads for
+    /// everyone. Returns the public key of the subscriber [Author].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the subscribe message
+    ///
+    pub fn accept_subscription(&mut self, link: &Address) -> Result<PublicKey> {
+        let (_pending, msg) = self.take_pending_subscription(link)?;
+        let subscriber_pk = self.user.handle_subscribe(msg.binary, MsgInfo::Subscribe)?;
+        self.emit(|events| events.on_subscription(link, &subscriber_pk));
+        Ok(subscriber_pk)
+    }
+
+    /// Reject a pending subscription, it is dropped. Returns the public key of the subscriber
+    /// [Author].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the subscribe message
+    ///
+    pub fn reject_subscription(&mut self, link: &Address) -> Result<PublicKey> {
+        let (pending, _msg) = self.take_pending_subscription(link)?;
+        self.emit(|events| events.on_subscription_rejected(link, &pending.pk));
+        Ok(pending.pk)
     }
 
     /// Receive and Process an announcement message [Subscriber].
@@ -272,7 +1726,7 @@ where
     /// * `link_to` - Address of the Channel Announcement message
     ///
     pub fn receive_announcement(&mut self, link: &Address) -> Result<()> {
-        let msg = self.transport.recv_message(link)?;
+        let msg = self.recv_message(link)?;
         self.user.handle_announcement(msg.binary, MsgInfo::Announce)
     }
 
@@ -282,11 +1736,49 @@ where
     ///  * `link` - Address of the message to be processed
     ///
     pub fn receive_keyload(&mut self, link: &Address) -> Result<bool> {
-        let msg = self.transport.recv_message(link)?;
+        let msg = self.recv_message(link)?;
         let m = self.user.handle_keyload(msg.binary, MsgInfo::Keyload)?;
         Ok(m.body)
     }
 
+    /// Whether the keyload at `link` was processed and shared its session key with the user
+    /// [Author, Subscriber].
+    pub fn has_keyload_access(&self, link: &Address) -> bool {
+        matches!(self.user.link_store.borrow().lookup(&link.msgid), Ok((_, MsgInfo::Keyload)))
+    }
+
+    /// Receive and process a branch delegation message, returns the delegate [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_branch_delegation(&mut self, link: &Address) -> Result<PublicKey> {
+        let msg = self.recv_message(link)?;
+        let m = self.user.handle_branch_delegation(msg.binary, MsgInfo::BranchDelegation)?;
+        Ok(m.body)
+    }
+
+    /// Receive and process a checkpoint message, the sequencing state of publishers advances
+    /// to it. Returns the number of publishers in the checkpoint [Author, Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_checkpoint(&mut self, link: &Address) -> Result<usize> {
+        let msg = self.recv_message(link)?;
+        let seq_num = msg.binary.parse_header()?.header.get_seq_num();
+        let m = self.user.handle_checkpoint(msg.binary, MsgInfo::Checkpoint)?;
+        if self.user.is_counter_sequencing() {
+            if let Some(author_pk) = self.user.author_sig_pk.clone() {
+                self.user.advance_counter(&author_pk, seq_num as u32);
+            }
+        } else if !self.user.is_multi_branching() {
+            // Continue after the checkpoint itself.
+            self.user.store_state_for_all(link.msgid.clone(), seq_num as u32);
+        }
+        Ok(m.body)
+    }
+
     /// Receive and process a message of unknown type. Message will be handled appropriately and
     /// the unwrapped contents returned [Author, Subscriber].
     ///
@@ -295,7 +1787,7 @@ where
     ///   * `pk` - Optional ed25519 Public Key of the sending participant. None if unknown
     ///
     pub fn receive_message(&mut self, link: &Address, pk: Option<PublicKey>) -> Result<UnwrappedMessage> {
-        let msg = self.transport.recv_message(link)?;
+        let msg = self.recv_message(link)?;
         self.handle_message(msg, pk)
     }
 
@@ -339,11 +1831,185 @@ where
         self.user.gen_next_msg_ids(branching)
     }
 
+    /// Retrieves the sequencing state of each publishing participant: the link of the latest
+    /// processed message and its cursor position [Author, Subscriber]
+    pub fn fetch_state(&self) -> Result<Vec<(PublicKey, Cursor<Address>)>> {
+        self.user.fetch_state()
+    }
+
+    /// Retrieves and processes messages until no new messages are found in transport layer,
+    /// bringing the sequencing state fully up to date. Returns all retrieved messages [Author, Subscriber]
+    pub fn sync_state(&mut self) -> Vec<UnwrappedMessage> {
+        let mut msgs = Vec::new();
+        loop {
+            let next_msgs = self.fetch_next_msgs();
+            if next_msgs.is_empty() {
+                break;
+            }
+            msgs.extend(next_msgs);
+        }
+        msgs
+    }
+
+    /// Like `sync_state`, with messages fetched ahead on a separate thread: while a round of
+    /// messages (the next message of each publisher) is unwrapped, the following round is
+    /// already fetched with `fetcher`. Rounds are predicted from the sequencing state the
+    /// fetched messages lead to; a mispredicted round, eg. after a keyload introduced new
+    /// publishers, is fetched again [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `fetcher` - Transport to fetch messages with, eg. a clone of the user's transport
+    ///
+    #[cfg(feature = "std")]
+    pub fn sync_state_pipelined<T>(&mut self, fetcher: T) -> Vec<UnwrappedMessage>
+    where
+        T: Transport + Send + 'static,
+    {
+        use std::{
+            sync::mpsc,
+            thread,
+        };
+
+        type Round = Vec<(PublicKey, Cursor<Address>)>;
+        let (request_sender, requests) = mpsc::channel::<Round>();
+        let (result_sender, results) = mpsc::channel();
+        let worker = thread::spawn(move || {
+            let mut fetcher = fetcher;
+            for round in requests {
+                let found: Vec<(PublicKey, Cursor<Address>, Message)> = round
+                    .into_iter()
+                    .filter_map(|(pk, cursor)| match fetcher.recv_message(&cursor.link) {
+                        Ok(msg) => Some((pk, cursor, msg)),
+                        Err(e) => {
+                            log::debug!("No message at {}: {}", cursor.link, e);
+                            None
+                        }
+                    })
+                    .collect();
+                if result_sender.send(found).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let same_round = |a: &Round, b: &Round| {
+            a.len() == b.len() && a.iter().all(|(_, x)| b.iter().any(|(_, y)| x.link == y.link))
+        };
+
+        let mut msgs = Vec::new();
+        let _ = request_sender.send(self.user.gen_next_msg_ids(self.user.is_multi_branching()));
+        while let Ok(found) = results.recv() {
+            if found.is_empty() {
+                break;
+            }
+
+            let found_ids: Round = found.iter().map(|(pk, cursor, _)| (pk.clone(), cursor.clone())).collect();
+            let predicted = self.user.predict_next_msg_ids(&found_ids);
+            let _ = request_sender.send(predicted.clone());
+
+            self.user
+                .verify_signed_packets(found.iter().map(|(_, _, msg)| &msg.binary));
+            for (pk, cursor, msg) in found {
+                if let Some(retained) = self.retained.as_mut() {
+                    retained.insert(cursor.link.clone(), msg.clone());
+                }
+                match self.handle_fetched(pk, cursor.link, cursor.seq_no, msg) {
+                    Ok(UnwrappedMessage {
+                        body: MessageContent::Filtered { .. },
+                        ..
+                    }) => {}
+                    Ok(msg) => msgs.push(msg),
+                    Err(e) => log::warn!("{}", e),
+                }
+            }
+
+            let next = self.user.gen_next_msg_ids(self.user.is_multi_branching());
+            if !same_round(&next, &predicted) {
+                // Discard the mispredicted round.
+                if results.recv().is_err() {
+                    break;
+                }
+                let _ = request_sender.send(next);
+            }
+        }
+
+        drop(request_sender);
+        let _ = worker.join();
+        msgs
+    }
+
+    /// Process the message at `link` and iterate over the messages following it up to the
+    /// current head, fetching them on demand. Sequencing state is reset to `link` and
+    /// advances with the iteration [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the first message, eg. the announcement
+    ///
+    pub fn iter_from(&mut self, link: &Address) -> Result<History<'_, Trans>> {
+        ensure!(
+            !self.is_multi_branching() && !self.is_counter_sequencing(),
+            "Iteration from a link is only supported in single-branch channels"
+        );
+        let msg = self.recv_message(link)?;
+        let header = msg.binary.parse_header()?.header;
+        let first = if header.content_type == message::ANNOUNCE {
+            // Handling announcement resets sequencing state to the channel start.
+            let milestone = msg.milestone;
+            self.user.handle_announcement(msg.binary, MsgInfo::Announce)?;
+            UnwrappedMessage::new(link.clone(), MessageContent::new_announce()).with_milestone(milestone)
+        } else {
+            let first = self.handle_message(msg, None)?;
+            self.store_state_for_all(link, header.get_seq_num() as u32);
+            first
+        };
+        Ok(History::new(self, first))
+    }
+
+    /// Stream of messages fetched lazily until no new messages are found, an asynchronous
+    /// alternative to `sync_state` [Author, Subscriber]
+    #[cfg(feature = "stream")]
+    pub fn messages(&mut self) -> Messages<'_, Trans> {
+        Messages::new(self)
+    }
+
     /// Retrieves the next message for each user (if present in transport layer) and returns them [Author, Subscriber]
     pub fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
+        self.try_fetch_next_msgs()
+            .into_iter()
+            .filter_map(|msg| msg.map_err(|e| log::warn!("{}", e)).ok())
+            .collect()
+    }
+
+    /// Retrieves the next message for each user (if present in transport layer), messages
+    /// which could not be handled are returned as errors.
+    pub(crate) fn try_fetch_next_msgs(&mut self) -> Vec<Result<UnwrappedMessage>> {
         let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
-        let mut msgs = Vec::new();
+        self.try_fetch_msgs(ids)
+    }
+
+    /// Retrieves the next message of publisher `pk` (if present in transport layer), other
+    /// publishers' branches are neither fetched nor advanced. In single-branch channels
+    /// publishers share sequence numbers, so the message is only found if no other publisher
+    /// sent a message since the last processed one [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `pk` - Public key of the publisher
+    ///
+    pub fn fetch_next_msgs_from(&mut self, pk: &PublicKey) -> Vec<UnwrappedMessage> {
+        let ids = self
+            .user
+            .gen_next_msg_ids(self.user.is_multi_branching())
+            .into_iter()
+            .filter(|(id_pk, _)| id_pk == pk)
+            .collect();
+        self.try_fetch_msgs(ids)
+            .into_iter()
+            .filter_map(|msg| msg.map_err(|e| log::warn!("{}", e)).ok())
+            .collect()
+    }
 
+    fn try_fetch_msgs(&mut self, ids: Vec<(PublicKey, Cursor<Address>)>) -> Vec<Result<UnwrappedMessage>> {
+        let mut fetched = Vec::new();
         for (
             pk,
             Cursor {
@@ -353,20 +2019,37 @@ where
             },
         ) in ids
         {
-            let msg = self.transport.recv_message(&link);
+            match self.recv_message(&link) {
+                Ok(msg) => fetched.push((pk, link, seq_no, msg)),
+                Err(e) => log::debug!("No message at {}: {}", link, e),
+            }
+        }
 
-            if msg.is_ok() {
-                let msg = self.handle_message(msg.unwrap(), Some(pk));
-                if let Ok(msg) = msg {
-                    if !self.user.is_multi_branching() {
-                        self.user.store_state_for_all(link.msgid, seq_no);
-                    }
+        // Signatures of fetched signed packets are verified at once.
+        self.user.verify_signed_packets(fetched.iter().map(|(_, _, _, msg)| &msg.binary));
+
+        fetched
+            .into_iter()
+            .map(|(pk, link, seq_no, msg)| self.handle_fetched(pk, link, seq_no, msg))
+            .filter(|msg| !matches!(msg, Ok(UnwrappedMessage { body: MessageContent::Filtered { .. }, .. })))
+            .collect()
+    }
 
-                    msgs.push(msg);
+    /// Handle message fetched for the sequencing state of `pk` at `seq_no`.
+    fn handle_fetched(&mut self, pk: PublicKey, link: Address, seq_no: u32, msg: Message) -> Result<UnwrappedMessage> {
+        match self.handle_message_with(msg, Some(pk.clone()), true) {
+            Ok(msg) => {
+                if let Some(keyload) = self.user.linear_branch_of(&pk, &link) {
+                    self.user.advance_linear_branch(&keyload, link.msgid);
+                } else if self.user.is_counter_sequencing() {
+                    self.user.advance_counter(&pk, seq_no);
+                } else if !self.user.is_multi_branching() {
+                    self.user.store_state_for_all(link.msgid, seq_no);
                 }
+                Ok(msg)
             }
+            Err(e) => Err(anyhow!("Failed to handle message {}: {}", link, e)),
         }
-        msgs
     }
 
     /// Handle message of unknown type. Ingests a message and unwraps it according to it's determined
@@ -377,48 +2060,312 @@ where
     /// * `pk` - Optional ed25519 Public Key of the sending participant. None if unknown
     ///
     pub fn handle_message(&mut self, msg: Message, pk: Option<PublicKey>) -> Result<UnwrappedMessage> {
-        // Forget TangleMessage and timestamp
+        self.handle_message_with(msg, pk, false)
+    }
+
+    /// Handle message of unknown type, packets rejected by the fetch filter are skipped if `filter` is set.
+    fn handle_message_with(&mut self, msg: Message, pk: Option<PublicKey>, filter: bool) -> Result<UnwrappedMessage> {
+        let link = msg.binary.link.clone();
+        let result = self.do_handle_message(msg, pk, filter);
+        match &result {
+            Ok(m) => match m.body {
+                MessageContent::SignedPacket { .. }
+                | MessageContent::TaggedPacket { .. }
+                | MessageContent::PrivatePacket { .. }
+                | MessageContent::LazyPacket { .. } => self.emit(|events| events.on_packet(m)),
+                _ => {}
+            },
+            Err(e) => {
+                #[cfg(feature = "metrics")]
+                iota_streams_app::metrics::record_unwrap_failure();
+                self.emit(|events| events.on_error(&link, e))
+            }
+        }
+        result
+    }
+
+    fn do_handle_message(&mut self, msg: Message, pk: Option<PublicKey>, filter: bool) -> Result<UnwrappedMessage> {
+        let timestamp = msg.timestamp;
+        let milestone = msg.milestone;
         let msg = msg.binary;
         let preparsed = msg.parse_header()?;
-        match preparsed.header.content_type {
+        let seq_num = preparsed.header.get_seq_num();
+        let content_type = preparsed.header.content_type;
+        let packet = content_type == message::SIGNED_PACKET
+            || content_type == message::TAGGED_PACKET
+            || content_type == message::PRIVATE_PACKET
+            || content_type == message::DENIABLE_PACKET;
+        if filter && packet {
+            if let Some(fetch_filter) = self.fetch_filter.as_ref() {
+                if !fetch_filter(&preparsed.header) {
+                    return Ok(UnwrappedMessage::new(msg.link.clone(), MessageContent::new_filtered(content_type))
+                        .with_milestone(milestone));
+                }
+            }
+        }
+        let unwrapped = match preparsed.header.content_type {
             message::SIGNED_PACKET => {
                 let m = self.user.handle_signed_packet(msg, MsgInfo::SignedPacket)?;
-                let u = m.map(|(pk, public, masked)| MessageContent::new_signed_packet(pk, public, masked));
-                Ok(u)
+                self.check_rate(Some(&m.body.0), timestamp, &m.link)?;
+                self.check_sequence(Some(&m.body.0), seq_num, &m.link)?;
+                m.map(|(pk, public, masked)| MessageContent::new_packet(Some(pk), public, masked))
             }
             message::TAGGED_PACKET => {
                 let m = self.user.handle_tagged_packet(msg, MsgInfo::TaggedPacket)?;
-                let u = m.map(|(public, masked)| MessageContent::new_tagged_packet(public, masked));
-                Ok(u)
+                self.check_rate(pk.as_ref(), timestamp, &m.link)?;
+                self.check_sequence(pk.as_ref(), seq_num, &m.link)?;
+                m.map(|(public, masked)| MessageContent::new_packet(None, public, masked))
+            }
+            message::DENIABLE_PACKET => {
+                let m = self
+                    .user
+                    .handle_deniable_packet(msg, MsgInfo::DeniablePacket, DENIABLE_LINKS)?;
+                self.check_rate(pk.as_ref(), timestamp, &m.link)?;
+                self.check_sequence(pk.as_ref(), seq_num, &m.link)?;
+                m.map(|(public, masked)| MessageContent::new_packet(None, public, masked))
+            }
+            message::PRIVATE_PACKET => {
+                let m = self.user.handle_private_packet(msg, MsgInfo::PrivatePacket)?;
+                self.check_rate(Some(&m.body.0), timestamp, &m.link)?;
+                self.check_sequence(Some(&m.body.0), seq_num, &m.link)?;
+                m.map(|(pk, public, masked, private)| {
+                    MessageContent::new_private_packet(pk, public, masked, private)
+                })
             }
-            message::KEYLOAD => {
+            message::BRANCH_DELEGATION => {
+                let m = self.user.handle_branch_delegation(msg, MsgInfo::BranchDelegation)?;
+                m.map(MessageContent::new_branch_delegation)
+            }
+            message::CHECKPOINT => {
+                let m = self.user.handle_checkpoint(msg, MsgInfo::Checkpoint)?;
+                m.map(MessageContent::new_checkpoint)
+            }
+            message::KEYLOAD | message::ANONYMOUS_KEYLOAD => {
                 // So long as the unwrap has not failed, we will return a blank object to
                 // inform the user that a message was present, even if the use wasn't part of
                 // the keyload itself. This is to prevent sequencing failures
                 let m = self.user.handle_keyload(msg, MsgInfo::Keyload)?;
                 // TODO: Verify content, whether user is allowed or not!
-                let u = m.map(|_allowed| MessageContent::new_keyload());
-                Ok(u)
+                self.emit(|events| events.on_keyload(&m.link, m.body));
+                m.map(|_allowed| MessageContent::new_keyload())
             }
             message::SEQUENCE => {
+                let seq_link = msg.link.clone();
                 let store_link = msg.link.rel().clone();
                 let unwrapped = self.user.handle_sequence(msg, MsgInfo::Sequence)?;
                 let msg_link = self.user.link_gen.link_from(
                     &unwrapped.body.pk,
                     Cursor::new_at(&unwrapped.body.ref_link, 0, unwrapped.body.seq_num.0 as u32),
                 );
-                let msg = self.transport.recv_message(&msg_link)?;
+                let msg = self.recv_message(&msg_link)?;
                 self.user.store_state(pk.unwrap().clone(), store_link);
-                self.handle_message(msg, pk)
+                self.emit(|events| events.on_sequence(&seq_link, &msg_link));
+                return self.do_handle_message(msg, pk, filter);
+            }
+            unknown_content => return Err(anyhow!("Not a recognised message type: {}", unknown_content)),
+        };
+        Ok(unwrapped.with_milestone(milestone))
+    }
+
+    /// Unwrap a packet without committing its spongos state or updating sequence states, eg. to
+    /// preview it before processing it with `receive_message`. Signatures and MACs are verified,
+    /// other message types are rejected [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the packet to be peeked
+    ///
+    pub fn peek(&mut self, link: &Address) -> Result<UnwrappedMessage> {
+        let msg = self.transport.recv_message(link)?;
+        self.peek_message(msg)
+    }
+
+    /// Unwrap a binary packet without updating the user state, see `peek` [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `msg` - Wrapped packet to be peeked
+    ///
+    pub fn peek_message(&self, msg: Message) -> Result<UnwrappedMessage> {
+        let milestone = msg.milestone;
+        let msg = msg.binary;
+        let preparsed = msg.parse_header()?;
+        let body = match preparsed.header.content_type {
+            message::SIGNED_PACKET => {
+                let content = self.user.unwrap_signed_packet(preparsed)?.pcf.content;
+                self.user
+                    .ensure_write_permission(&content.link, Some(&content.sig_pk))?;
+                MessageContent::new_packet(Some(content.sig_pk), content.public_payload, content.masked_payload)
+            }
+            message::TAGGED_PACKET => {
+                let content = self.user.unwrap_tagged_packet(preparsed)?.pcf.content;
+                self.user.ensure_write_permission(&content.link, None)?;
+                MessageContent::new_packet(None, content.public_payload, content.masked_payload)
+            }
+            message::DENIABLE_PACKET => {
+                let content = self.user.unwrap_deniable_packet(preparsed)?.pcf.content;
+                let (_spongos, info) = self.user.link_store.borrow().lookup(&content.link)?;
+                ensure!(
+                    DENIABLE_LINKS.contains(&info),
+                    "Deniable packet is not linked to a keyload branch the user has the session key of."
+                );
+                self.user.ensure_write_permission(&content.link, None)?;
+                MessageContent::new_packet(None, content.public_payload, content.masked_payload)
+            }
+            message::PRIVATE_PACKET => {
+                let content = self.user.unwrap_private_packet(preparsed)?.pcf.content;
+                self.user
+                    .ensure_write_permission(&content.link, Some(&content.sig_pk))?;
+                MessageContent::new_private_packet(
+                    content.sig_pk,
+                    content.public_payload,
+                    content.masked_payload,
+                    content.private_payload,
+                )
+            }
+            content_type => return Err(anyhow!("Only packets can be peeked, message type: {}", content_type)),
+        };
+        Ok(UnwrappedMessage::new(msg.link.clone(), body).with_milestone(milestone))
+    }
+
+    /// Check header integrity and, where possible without the branch session key, signature
+    /// of a message, see `verify_binary` [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be verified
+    ///
+    pub fn verify_only(&mut self, link: &Address) -> Result<Option<Authentication>> {
+        let msg = self.transport.recv_message(link)?;
+        self.verify_binary(msg)
+    }
+
+    /// Check header integrity of a wrapped message and its signature or MAC if the linked
+    /// message was processed, without updating the user state. `None` means only the
+    /// header could be checked, eg. in a branch the user has no session key for; such
+    /// messages are unverified, forgeries in them are not detected as signatures don't cover
+    /// the ciphertext. Forged messages in readable branches fail [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `msg` - Wrapped message to be verified
+    ///
+    pub fn verify_binary(&self, msg: Message) -> Result<Option<Authentication>> {
+        self.user.verify_binary(msg.binary)
+    }
+
+    /// Walk the link chain of a message back to the channel announcement, unwrapping each
+    /// message again and verifying its signature or MAC [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to be verified
+    ///
+    pub fn verify_lineage(&mut self, link: &Address) -> Result<LineageProof> {
+        let appinst = match &self.user.appinst {
+            Some(appinst) => appinst.appinst.clone(),
+            None => return Err(anyhow!("No channel registered")),
+        };
+        let mut steps = Vec::new();
+        let mut next = Some(link.clone());
+        while let Some(link) = next {
+            let msg = self.transport.recv_message(&link)?;
+            let step = self.user.verify_message(msg.binary)?;
+            next = step.link_to.as_ref().map(|msgid| Address::new(appinst.clone(), msgid.clone()));
+            steps.push(step);
+        }
+        ensure!(
+            steps.last().map_or(false, |step| step.content_type == message::ANNOUNCE),
+            "Lineage of {} does not end with the announcement",
+            link
+        );
+        Ok(LineageProof { steps })
+    }
+
+    /// Message DAG of the channel as known to the user: processed and sent messages with
+    /// their types and the references between them. Messages are fetched from transport, those
+    /// not found are included without references [Author, Subscriber]
+    pub fn export_graph(&mut self) -> Result<MessageGraph> {
+        let appinst = match &self.user.appinst {
+            Some(appinst) => appinst.appinst.clone(),
+            None => return Err(anyhow!("No channel registered")),
+        };
+        let known: Vec<(MsgId, MsgInfo)> = self
+            .user
+            .link_store
+            .borrow()
+            .iter()
+            .into_iter()
+            .map(|(msgid, (_spongos, info))| (msgid.clone(), *info))
+            .collect();
+
+        let mut graph = MessageGraph::default();
+        for (msgid, info) in known {
+            let link = Address::new(appinst.clone(), msgid.clone());
+            match self.transport.recv_message(&link) {
+                Ok(msg) => {
+                    for (to, kind) in super::graph::references(&msg, &self.user.link_gen)? {
+                        graph.edges.push((msgid.clone(), to, kind));
+                    }
+                }
+                Err(e) => log::debug!("No message at {}: {}", link, e),
             }
-            unknown_content => Err(anyhow!("Not a recognised message type: {}", unknown_content)),
+            graph.nodes.push((msgid, info));
         }
+        graph.sort();
+        Ok(graph)
     }
 
+    /// Export the user state together with the protocol state of the channel: pending
+    /// embargoes, revocations, key tree, directory and subscriptions awaiting a decision.
+    /// Transport, events, notarizer, fetch filter and retained messages are not exported.
     pub fn export(&self, flag: u8, pwd: &str) -> Result<Vec<u8>> {
-        self.user.export(flag, pwd)
+        self.user.export_ext(flag, pwd, &self.state.to_bytes()?)
     }
+    pub fn migrate_state(bytes: &[u8], flag: u8, pwd: &str) -> Result<Vec<u8>> {
+        UserImp::migrate_state(bytes, flag, pwd)
+    }
+    /// Import a state exported with `export`, states exported by earlier releases have an
+    /// empty channel state. States of hybrid channel authors are refused, they must be
+    /// imported with `import_hybrid`.
     pub fn import(bytes: &[u8], flag: u8, pwd: &str, tsp: Trans) -> Result<Self> {
-        UserImp::import(bytes, flag, pwd).map(|u| Self { user: u, transport: tsp, })
+        let user = Self::do_import(bytes, flag, pwd, tsp)?;
+        #[cfg(feature = "pq-sig")]
+        ensure!(
+            user.next_pq_signature_index().is_none(),
+            "State of a hybrid channel author, import it with `import_hybrid` to prevent MSS one-time key reuse."
+        );
+        Ok(user)
+    }
+
+    /// Import a state of a hybrid channel author exported with `export`. `min_pq_index` is the
+    /// index of the next MSS leaf when the author last signed, see `next_pq_signature_index`;
+    /// states exported before later signatures are refused, as signing with them would reuse
+    /// MSS one-time keys and let anybody forge post-quantum signatures of the author.
+    #[cfg(feature = "pq-sig")]
+    pub fn import_hybrid(bytes: &[u8], flag: u8, pwd: &str, tsp: Trans, min_pq_index: u32) -> Result<Self> {
+        let user = Self::do_import(bytes, flag, pwd, tsp)?;
+        user.user.ensure_pq_signature_index(min_pq_index)?;
+        Ok(user)
+    }
+
+    fn do_import(bytes: &[u8], flag: u8, pwd: &str, tsp: Trans) -> Result<Self> {
+        let (user, ext) = UserImp::import_ext(bytes, flag, pwd)?;
+        let state = if ext.is_empty() {
+            ChannelState::default()
+        } else {
+            let appinst = user.appinst.as_ref().map(|link| link.appinst.clone());
+            ChannelState::from_bytes(&appinst.unwrap_or_default(), &ext)?
+        };
+        Ok(Self {
+            user,
+            transport: tsp,
+            events: None,
+            retained: None,
+            fetch_filter: None,
+            notarizer: None,
+            state,
+        })
+    }
+}
+
+impl<Trans> core::fmt::Debug for User<Trans> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("User").field("user", &self.user).finish()
     }
 }
@@ -11,13 +11,24 @@ use core::{
 use iota_streams_core::{
     prelude::{
         vec,
+        Box,
         Vec,
-        typenum::U32,
+        typenum::{
+            U32,
+            U4,
+        },
     },
+    fingerprint::Fingerprint,
     prng,
     psk,
     sponge::prp::{Inner, PRP,},
 };
+#[cfg(feature = "pq-kem")]
+use iota_streams_core_edsig::key_exchange::kyber;
+#[cfg(feature = "pq-sig")]
+use iota_streams_core_edsig::signature::mss;
+#[cfg(feature = "secp256k1")]
+use iota_streams_core_edsig::signature::secp256k1;
 use iota_streams_core_edsig::{
     key_exchange::x25519,
     signature::ed25519,
@@ -25,8 +36,15 @@ use iota_streams_core_edsig::{
 
 use iota_streams_app::message::{
     hdf::{
+        flags_with_link_width,
+        link_width_from_flags,
         FLAG_BRANCHING_MASK,
+        FLAG_COUNTER_SEQUENCING_MASK,
+        FLAG_HYBRID_KEM_MASK,
+        FLAG_SIV_MASK,
+        FLAG_MSGID_HASH_MASK,
         HDF,
+        MAX_PRIORITY,
     },
     *,
 };
@@ -43,10 +61,20 @@ use iota_streams_ddml::{
 
 use crate::{
     api::{
+        branch_policy::*,
+        footprint::*,
         pk_store::*,
+        lineage::*,
         psk_store::*,
+        rate_limit::*,
+        sequence_policy::*,
+        session_cache::*,
+        state::*,
+    },
+    message::{
+        signer::Signer,
+        *,
     },
-    message::*,
 };
 
 const ANN_MESSAGE_NUM: u32 = 0;
@@ -107,6 +135,28 @@ impl<F, Link: HasLink> WrappedSequence<F, Link> {
     }
 }
 
+/// Link joined by a message content, read without unwrapping the rest of the content.
+struct LinkedTo<Link: HasLink>(<Link as HasLink>::Rel);
+
+impl<F, Link, Store> ContentUnwrap<F, Store> for LinkedTo<Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: SkipFallback<F>,
+{
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        _store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        ctx.skip(<&mut Fallback<<Link as HasLink>::Rel>>::from(&mut self.0))?;
+        Ok(ctx)
+    }
+}
+
+/// Callback producing application-specific header extension for an outgoing message header.
+pub type HeaderExtFn<Link> = Box<dyn Fn(&HDF<Link>) -> Vec<u8>>;
+
 pub struct User<F, Link, LG, LS, PKS, PSKS>
 where
     F: PRP,
@@ -131,6 +181,44 @@ where
     /// Author's Ed25519 public key.
     pub(crate) author_sig_pk: Option<ed25519::PublicKey>,
 
+    /// Public key of the threshold group authoring the channel if the user coordinates the
+    /// group's messages. Not exported with user state.
+    pub(crate) threshold_pk: Option<ed25519::PublicKey>,
+
+    /// Own MSS key pair if the user is the author of a hybrid channel, see `create_hybrid_channel`.
+    #[cfg(feature = "pq-sig")]
+    pub(crate) mss_kp: Option<RefCell<mss::Keypair>>,
+
+    /// Author's MSS public key if the channel is hybrid.
+    #[cfg(feature = "pq-sig")]
+    pub(crate) author_mss_pk: Option<mss::PublicKey>,
+
+    /// Own ML-KEM key pair if the channel uses hybrid key encapsulation, derived from the
+    /// Ed25519 secret key. Not exported with user state.
+    #[cfg(feature = "pq-kem")]
+    pub(crate) kem_kp: Option<kyber::Keypair>,
+
+    /// ML-KEM public keys of keyload recipients including own one, see `with_hybrid_kem`.
+    #[cfg(feature = "pq-kem")]
+    pub(crate) kem_pks: Vec<(ed25519::PublicKey, kyber::PublicKey)>,
+
+    /// Delegated branches: root link of a branch and public key of its delegate.
+    pub(crate) delegations: Vec<(<Link as HasLink>::Rel, ed25519::PublicKey)>,
+
+    /// Read-only recipients of processed keyloads: keyload link and recipient public keys.
+    pub(crate) read_only: Vec<(<Link as HasLink>::Rel, Vec<ed25519::PublicKey>)>,
+
+    /// Signed packets in branches of keyloads with read-only recipients: packet link and
+    /// keyload link.
+    pub(crate) write_gated: Vec<(<Link as HasLink>::Rel, <Link as HasLink>::Rel)>,
+
+    /// Branches of keyloads with `BranchPolicy::Linear` in a multi-branch channel: keyload link
+    /// and cursor to the latest message of the branch with the next sequence number.
+    pub(crate) linear_branches: Vec<(<Link as HasLink>::Rel, Cursor<<Link as HasLink>::Rel>)>,
+
+    /// Number of changes of the known subscribers.
+    pub(crate) membership_epoch: u64,
+
     /// Link generator.
     pub(crate) link_gen: LG,
 
@@ -147,6 +235,38 @@ where
     pub message_encoding: Vec<u8>,
 
     pub uniform_payload_length: usize,
+
+    /// Header extension callback, called for every message header at wrap time.
+    /// Not exported with user state.
+    pub(crate) header_ext: Option<HeaderExtFn<Link>>,
+
+    /// Rate policy shared in own keyloads, rate policies of branches and message counters of
+    /// publishers. Not exported with user state.
+    pub(crate) rate_limiter: RateLimiter<<Link as HasLink>::Rel>,
+
+    /// Branch policy declared in own keyloads. Not exported with user state.
+    pub(crate) branch_policy: BranchPolicy,
+
+    /// Sequence numbers of received messages checked against the sequence policy.
+    /// Not exported with user state.
+    pub(crate) sequence_guard: SequenceGuard,
+
+    /// Delivery priority put into headers of outgoing messages. Not exported with user state.
+    pub(crate) priority: u8,
+
+    /// Buffers of sent messages reused for wrapping. Not exported with user state.
+    pub(crate) buffer_pool: BufferPool,
+
+    /// Keep masked payloads of signed and tagged packets encrypted until accessed.
+    /// Not exported with user state.
+    pub(crate) defer_masked_payloads: bool,
+
+    /// Spongos states of processed keyloads. Not exported with user state.
+    pub(crate) session_cache: SessionCache<F, <Link as HasLink>::Rel>,
+
+    /// Signed packets unwrapped and verified in the last batch by link, see
+    /// `verify_signed_packets`.
+    pub(crate) verified_packets: Vec<(Link, UnwrappedMessage<F, Link, signed_packet::ContentUnwrap<F, Link>>)>,
 }
 
 impl<F, Link, LG, LS, PKS, PSKS> Default for User<F, Link, LG, LS, PKS, PSKS>
@@ -173,16 +293,69 @@ where
             psk_store: PSKS::default(),
             pk_store: PKS::default(),
             author_sig_pk: None,
+            threshold_pk: None,
+            #[cfg(feature = "pq-sig")]
+            mss_kp: None,
+            #[cfg(feature = "pq-sig")]
+            author_mss_pk: None,
+            #[cfg(feature = "pq-kem")]
+            kem_kp: None,
+            #[cfg(feature = "pq-kem")]
+            kem_pks: Vec::new(),
+            delegations: Vec::new(),
+            read_only: Vec::new(),
+            write_gated: Vec::new(),
+            linear_branches: Vec::new(),
+            membership_epoch: 0,
             link_gen: LG::default(),
             link_store: RefCell::new(LS::default()),
             appinst: None,
             flags: 0,
             message_encoding: Vec::new(),
             uniform_payload_length: 0,
+            header_ext: None,
+            rate_limiter: RateLimiter::default(),
+            branch_policy: BranchPolicy::default(),
+            sequence_guard: SequenceGuard::default(),
+            buffer_pool: BufferPool::default(),
+            defer_masked_payloads: false,
+            session_cache: SessionCache::default(),
+            verified_packets: Vec::new(),
+            priority: 0,
         }
     }
 }
 
+impl<F, Link, LG, LS, PKS, PSKS> User<F, Link, LG, LS, PKS, PSKS>
+where
+    F: PRP,
+    Link: HasLink,
+{
+    /// Fingerprint of own Ed25519 secret key for support diagnostics: identifies the key
+    /// without revealing it.
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::of::<F>(self.sig_kp.secret.as_bytes())
+    }
+}
+
+/// Secrets are printed as fingerprints.
+impl<F, Link, LG, LS, PKS, PSKS> fmt::Debug for User<F, Link, LG, LS, PKS, PSKS>
+where
+    F: PRP,
+    Link: HasLink + fmt::Debug,
+    PSKS: PresharedKeyStore + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("User")
+            .field("pk", &format_args!("{}", hex::encode(self.sig_kp.public.as_bytes())))
+            .field("fingerprint", &self.fingerprint())
+            .field("appinst", &self.appinst)
+            .field("flags", &self.flags)
+            .field("psk_store", &self.psk_store)
+            .finish()
+    }
+}
+
 impl<F, Link, LG, LS, PKS, PSKS> User<F, Link, LG, LS, PKS, PSKS>
 where
     F: PRP,
@@ -226,12 +399,76 @@ where
             psk_store: PSKS::default(),
             pk_store: PKS::default(),
             author_sig_pk: None,
+            threshold_pk: None,
+            #[cfg(feature = "pq-sig")]
+            mss_kp: None,
+            #[cfg(feature = "pq-sig")]
+            author_mss_pk: None,
+            #[cfg(feature = "pq-kem")]
+            kem_kp: None,
+            #[cfg(feature = "pq-kem")]
+            kem_pks: Vec::new(),
+            delegations: Vec::new(),
+            read_only: Vec::new(),
+            write_gated: Vec::new(),
+            linear_branches: Vec::new(),
+            membership_epoch: 0,
             link_gen: LG::default(),
             link_store: RefCell::new(LS::default()),
             appinst: None,
             flags,
             message_encoding,
             uniform_payload_length,
+            header_ext: None,
+            rate_limiter: RateLimiter::default(),
+            branch_policy: BranchPolicy::default(),
+            sequence_guard: SequenceGuard::default(),
+            buffer_pool: BufferPool::default(),
+            defer_masked_payloads: false,
+            session_cache: SessionCache::default(),
+            verified_packets: Vec::new(),
+            priority: 0,
+        }
+    }
+
+    /// Replace the link generator. Allows to use user-provided link schemes (eg. URL-based
+    /// or UUID-based links) carrying their own configuration; must be set before a channel
+    /// is created or an announcement is handled.
+    pub fn with_link_gen(mut self, link_gen: LG) -> Self {
+        self.link_gen = link_gen;
+        self
+    }
+
+    /// Set callback populating the application-specific extension of headers of outgoing
+    /// messages, eg. with routing hints. Receivers can inspect it in the preparsed header
+    /// before unwrapping the content.
+    pub fn set_header_ext<E>(&mut self, header_ext: E)
+    where
+        E: Fn(&HDF<Link>) -> Vec<u8> + 'static,
+    {
+        self.header_ext = Some(Box::new(header_ext));
+    }
+
+    /// Set delivery priority of subsequent outgoing messages, see `hdf::MAX_PRIORITY`.
+    /// Transports queueing messages send more urgent ones first.
+    pub fn set_priority(&mut self, priority: u8) -> Result<()> {
+        ensure!(priority <= MAX_PRIORITY, "Priority out of range: {}", priority);
+        self.priority = priority;
+        Ok(())
+    }
+
+    pub fn get_priority(&self) -> u8 {
+        self.priority
+    }
+
+    fn with_header_ext(&self, header: HDF<Link>) -> Result<HDF<Link>> {
+        let header = header.with_priority(self.priority)?;
+        match self.header_ext {
+            Some(ref header_ext) => {
+                let ext = header_ext(&header);
+                header.with_ext(ext)
+            }
+            None => Ok(header),
         }
     }
 
@@ -243,6 +480,8 @@ where
         );
         self.link_gen.gen(&self.sig_kp.public, channel_idx);
         let appinst = self.link_gen.get();
+        self.flags = flags_with_link_width(self.flags, Link::rel_width(appinst.rel()))?;
+        self.flags = self.flags_with_msgid_hash();
         self.pk_store.insert(
             self.sig_kp.public.clone(),
             Cursor::new_at(appinst.rel().clone(), 0, 2_u32),
@@ -251,6 +490,186 @@ where
         Ok(())
     }
 
+    /// Create a channel authored by threshold group `group_pk` (see `ed25519::frost`) without
+    /// announcing it. The user coordinates the group's messages and publishes them once signed
+    /// by the group. Only single-branch channels are supported since the group doesn't sign
+    /// sequence messages.
+    pub fn create_threshold_channel(&mut self, group_pk: &ed25519::PublicKey, channel_idx: u64) -> Result<()> {
+        ensure!(
+            self.appinst.is_none(),
+            "Can't create channel: a channel already created/registered."
+        );
+        ensure!(
+            !self.is_multi_branching() && !self.is_counter_sequencing(),
+            "Threshold channels must use single-branch sequencing."
+        );
+        self.link_gen.gen(group_pk, channel_idx);
+        let appinst = self.link_gen.get();
+        self.flags = flags_with_link_width(self.flags, Link::rel_width(appinst.rel()))?;
+        self.flags = self.flags_with_msgid_hash();
+        let cursor = Cursor::new_at(appinst.rel().clone(), 0, 2_u32);
+        self.pk_store.insert(group_pk.clone(), cursor.clone());
+        self.pk_store.insert(self.sig_kp.public.clone(), cursor);
+        self.author_sig_pk = Some(group_pk.clone());
+        self.threshold_pk = Some(group_pk.clone());
+        self.appinst = Some(appinst);
+        Ok(())
+    }
+
+    /// Channel flags announcing the hash function of message links if it's not the default one.
+    fn flags_with_msgid_hash(&self) -> u8 {
+        if self.link_gen.hash_id() != 0 {
+            self.flags | FLAG_MSGID_HASH_MASK
+        } else {
+            self.flags & !FLAG_MSGID_HASH_MASK
+        }
+    }
+
+    fn threshold_pk(&self) -> Result<&ed25519::PublicKey> {
+        self.threshold_pk
+            .as_ref()
+            .ok_or_else(|| anyhow!("Channel is not authored by a threshold group."))
+    }
+
+    fn ensure_threshold_sig(&self, header: &HDF<Link>, tag: Option<[u8; 64]>, sig: &ed25519::Signature) -> Result<()> {
+        let sig = ed25519::PrehashedSignature {
+            pk: self.threshold_pk()?.clone(),
+            context: signature_context(header.version, header.content_type),
+            hash: tag.ok_or_else(|| anyhow!("Internal error: no tag"))?,
+            signature: sig.clone(),
+        };
+        ensure!(
+            ed25519::verify_prehashed_cofactored(&sig),
+            "Bad signature of the threshold group."
+        );
+        Ok(())
+    }
+
+    /// Create a hybrid channel (without announcing it): announcement and signed packets of the
+    /// author are wrapped with a post-quantum version (see `pq_version`) and signed with both Ed25519 and an MSS key
+    /// pair with Merkle tree of `mss_height`. The MSS key pair is derived from the Ed25519
+    /// secret key, the author can sign `2^mss_height` messages including the announcement.
+    #[cfg(feature = "pq-sig")]
+    pub fn create_hybrid_channel(&mut self, channel_idx: u64, mss_height: usize) -> Result<()> {
+        let mss_kp = mss::Keypair::from_seed(self.sig_kp.secret.as_bytes(), mss_height)
+            .ok_or_else(|| anyhow!("MSS tree height out of range: {}.", mss_height))?;
+        self.create_channel(channel_idx)?;
+        self.author_mss_pk = Some(mss_kp.public);
+        self.mss_kp = Some(RefCell::new(mss_kp));
+        Ok(())
+    }
+
+    /// Number of messages the author of a hybrid channel can still sign.
+    #[cfg(feature = "pq-sig")]
+    pub fn remaining_pq_signatures(&self) -> Option<u32> {
+        self.mss_kp.as_ref().map(|mss_kp| mss_kp.borrow().remaining())
+    }
+
+    /// Index of the next MSS leaf the author of a hybrid channel signs with. MSS leaves are
+    /// one-time keys, signing twice with a leaf lets anybody forge post-quantum signatures.
+    /// Exported states only hold the index at the time of the export; keep the latest index
+    /// outside of them and check imported states with `ensure_pq_signature_index`.
+    #[cfg(feature = "pq-sig")]
+    pub fn next_pq_signature_index(&self) -> Option<u32> {
+        self.mss_kp.as_ref().map(|mss_kp| mss_kp.borrow().next_index())
+    }
+
+    /// Fail if the next MSS leaf is below `min_index`, the index of the next leaf when the
+    /// author last signed: the state was exported before later signatures and signing with it
+    /// would reuse one-time keys.
+    #[cfg(feature = "pq-sig")]
+    pub fn ensure_pq_signature_index(&self, min_index: u32) -> Result<()> {
+        if let Some(next_index) = self.next_pq_signature_index() {
+            ensure!(
+                next_index >= min_index,
+                "State is older than the last MSS signature: next leaf {} < {}, signing would reuse one-time keys.",
+                next_index,
+                min_index
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether the author's announcement and signed packets carry post-quantum signatures.
+    pub fn is_hybrid(&self) -> bool {
+        #[cfg(feature = "pq-sig")]
+        {
+            self.author_mss_pk.is_some()
+        }
+        #[cfg(not(feature = "pq-sig"))]
+        {
+            false
+        }
+    }
+
+    /// Own signer of announcement and signed packets and the header version it requires.
+    fn own_signer<'a>(&'a self, header: HDF<Link>) -> Result<(HDF<Link>, Signer<'a>)> {
+        #[cfg(feature = "pq-sig")]
+        {
+            if let Some(ref mss_kp) = self.mss_kp {
+                let version = pq_version(header.version);
+                return Ok((header.with_version(version)?, Signer::Hybrid(&self.sig_kp, mss_kp)));
+            }
+        }
+        Ok((header, Signer::Keypair(&self.sig_kp)))
+    }
+
+    /// Check that signatures of a message wrapped with `version` can be verified.
+    fn ensure_signature_version(&self, version: Uint8) -> Result<()> {
+        ensure!(
+            cfg!(feature = "pq-sig") || !has_pq_signatures(version),
+            "Post-quantum signatures are not supported, enable the `pq-sig` feature."
+        );
+        Ok(())
+    }
+
+    /// Height and index of the next leaf of own MSS key pair exported with user state, zero
+    /// height if there's none.
+    fn mss_state(&self) -> (Uint8, Uint32) {
+        #[cfg(feature = "pq-sig")]
+        {
+            if let Some(ref mss_kp) = self.mss_kp {
+                let mss_kp = mss_kp.borrow();
+                return (Uint8(mss_kp.height() as u8), Uint32(mss_kp.next_index()));
+            }
+        }
+        (Uint8(0), Uint32(0))
+    }
+
+    fn author_mss_pk_bytes(&self) -> Option<NBytes<U64>> {
+        #[cfg(feature = "pq-sig")]
+        {
+            if let Some(ref mss_pk) = self.author_mss_pk {
+                let mut bytes = NBytes::<U64>::default();
+                bytes.as_mut_slice().copy_from_slice(&mss_pk.to_bytes());
+                return Some(bytes);
+            }
+        }
+        None
+    }
+
+    /// Rederive own MSS key pair from the Ed25519 secret key and skip used leaves.
+    fn restore_mss(&mut self, mss_height: Uint8, mss_next: Uint32, author_mss_pk: Option<NBytes<U64>>) -> Result<()> {
+        ensure!(
+            cfg!(feature = "pq-sig") || (mss_height.0 == 0 && mss_next.0 == 0 && author_mss_pk.is_none()),
+            "Post-quantum signatures are not supported, enable the `pq-sig` feature."
+        );
+        #[cfg(feature = "pq-sig")]
+        {
+            if mss_height.0 != 0 {
+                let mut mss_kp = mss::Keypair::from_seed(self.sig_kp.secret.as_bytes(), mss_height.0 as usize)
+                    .ok_or_else(|| anyhow!("MSS tree height out of range: {}.", mss_height))?;
+                mss_kp.set_next_index(mss_next.0);
+                self.mss_kp = Some(RefCell::new(mss_kp));
+            }
+            if let Some(author_mss_pk) = author_mss_pk {
+                self.author_mss_pk =
+                    Some(mss::PublicKey::from_bytes(author_mss_pk.as_slice()).ok_or_else(|| anyhow!("Bad MSS public key."))?);
+            }
+        }
+        Ok(())
+    }
+
     /// Save spongos and info associated to the message link
     pub fn commit_wrapped(
         &mut self,
@@ -268,13 +687,52 @@ where
             .with_content_type(ANNOUNCE)?
             .with_payload_length(1)?
             .with_seq_num(ANN_MESSAGE_NUM);
-        let content = announce::ContentWrap::new(&self.sig_kp, self.flags);
+        let header = self.with_header_ext(header)?;
+        let (header, signer) = self.own_signer(header)?;
+        let content = announce::ContentWrap::with_signer(signer, self.flags).with_msgid_hash(self.link_gen.hash_id());
         Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
     }
 
     /// Create Announcement message.
     pub fn announce(&self) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_announcement()?.wrap()
+        self.prepare_announcement()?.wrap_pooled(&self.buffer_pool)
+    }
+
+    /// Prepare Announcement message of a threshold channel, `sig` is the group signature or
+    /// `None` to get the hash to be signed.
+    pub fn prepare_threshold_announcement<'a>(
+        &'a self,
+        sig: Option<&'a ed25519::Signature>,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, announce::ContentWrap<'a, F>>> {
+        let group_pk = self.threshold_pk()?;
+        let msg_link = self.link_gen.get();
+        let header = HDF::new(msg_link)
+            .with_content_type(ANNOUNCE)?
+            .with_payload_length(1)?
+            .with_seq_num(ANN_MESSAGE_NUM);
+        let header = self.with_header_ext(header)?;
+        let content =
+            announce::ContentWrap::new_threshold(group_pk, self.flags, sig).with_msgid_hash(self.link_gen.hash_id());
+        Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+    }
+
+    /// Hash of the Announcement message of a threshold channel to be signed by the group.
+    pub fn threshold_announcement_tag(&self) -> Result<[u8; 64]> {
+        let prepared = self.prepare_threshold_announcement(None)?;
+        prepared.wrap()?;
+        prepared
+            .content
+            .content
+            .tag()
+            .ok_or_else(|| anyhow!("Internal error: no tag"))
+    }
+
+    /// Create Announcement message of a threshold channel with group signature `sig`.
+    pub fn threshold_announce(&self, sig: &ed25519::Signature) -> Result<WrappedMessage<F, Link>> {
+        let prepared = self.prepare_threshold_announcement(Some(sig))?;
+        let wrapped = prepared.wrap_pooled(&self.buffer_pool)?;
+        self.ensure_threshold_sig(&prepared.header, prepared.content.content.tag(), sig)?;
+        Ok(wrapped)
     }
 
     pub fn unwrap_announcement<'a>(
@@ -290,6 +748,14 @@ where
             );
         }
 
+        self.ensure_signature_version(preparsed.header.version)?;
+        #[cfg(feature = "pq-sig")]
+        let content = if has_pq_signatures(preparsed.header.version) {
+            announce::ContentUnwrap::<F>::hybrid()
+        } else {
+            announce::ContentUnwrap::<F>::default()
+        };
+        #[cfg(not(feature = "pq-sig"))]
         let content = announce::ContentUnwrap::<F>::default();
         let r = preparsed.unwrap(&*self.link_store.borrow(), content);
         r
@@ -308,6 +774,16 @@ where
         let unwrapped = self.unwrap_announcement(preparsed)?;
         let link = unwrapped.link.clone();
         let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        let width = Link::rel_width(link.rel());
+        ensure!(
+            width == 0 || width == link_width_from_flags(content.flags.0)?,
+            "Announcement link width {} does not match announced width",
+            width
+        );
+        ensure!(
+            cfg!(feature = "pq-kem") || content.flags.0 & FLAG_HYBRID_KEM_MASK == 0,
+            "Hybrid key encapsulation is not supported, enable the `pq-kem` feature."
+        );
         // TODO: check commit after message is done / before joined
 
         // TODO: Verify trust to Author's public key?
@@ -321,9 +797,18 @@ where
         self.pk_store.insert(self.sig_kp.public.clone(), cursor);
         // Reset link_gen
         self.link_gen.reset(link.clone());
+        self.link_gen.set_hash_id(content.msgid_hash.0)?;
         self.appinst = Some(link);
         self.author_sig_pk = Some(content.sig_pk);
         self.flags = content.flags.0;
+        #[cfg(feature = "pq-sig")]
+        {
+            self.author_mss_pk = content.mss_pk;
+        }
+        #[cfg(feature = "pq-kem")]
+        {
+            self.init_kem();
+        }
         Ok(())
     }
 
@@ -341,12 +826,15 @@ where
                     .with_content_type(SUBSCRIBE)?
                     .with_payload_length(1)?
                     .with_seq_num(SUB_MESSAGE_NUM);
+                let header = self.with_header_ext(header)?;
                 let unsubscribe_key = NBytes::from(prng::random_key());
                 let content = subscribe::ContentWrap {
                     link: link_to,
                     unsubscribe_key,
                     subscriber_sig_kp: &self.sig_kp,
                     author_ke_pk: author_ke_pk,
+                    #[cfg(feature = "pq-kem")]
+                    kem_pk: self.kem_kp.as_ref().map(|kem_kp| &kem_kp.public),
                     _phantom: core::marker::PhantomData,
                 };
                 Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
@@ -360,7 +848,7 @@ where
 
     /// Subscribe to the channel.
     pub fn subscribe(&mut self, link_to: &<Link as HasLink>::Rel) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_subscribe(link_to)?.wrap()
+        self.prepare_subscribe(link_to)?.wrap_pooled(&self.buffer_pool)
     }
 
     pub fn unwrap_subscribe<'a>(
@@ -368,7 +856,14 @@ where
         preparsed: PreparsedMessage<'a, F, Link>,
     ) -> Result<UnwrappedMessage<F, Link, subscribe::ContentUnwrap<F, Link>>> {
         self.ensure_appinst(&preparsed)?;
-        let content = subscribe::ContentUnwrap::new(&self.ke_kp.0);
+        let mut content = subscribe::ContentUnwrap::new(&self.ke_kp.0);
+        #[cfg(feature = "pq-kem")]
+        {
+            if self.is_hybrid_kem() {
+                content = content.with_hybrid_kem();
+            }
+        }
+        content.link = self.default_rel();
         preparsed.unwrap(&*self.link_store.borrow(), content)
     }
 
@@ -377,7 +872,7 @@ where
         &mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
-    ) -> Result<()> {
+    ) -> Result<ed25519::PublicKey> {
         let preparsed = msg.parse_header()?;
         // TODO: check content type
 
@@ -386,11 +881,21 @@ where
             .commit(self.link_store.borrow_mut(), info)?;
         // TODO: trust content.subscriber_sig_pk
         let subscriber_sig_pk = content.subscriber_sig_pk;
+        #[cfg(feature = "pq-kem")]
+        {
+            if let Some(kem_pk) = content.kem_pk {
+                self.kem_pks.retain(|(pk, _)| *pk != subscriber_sig_pk);
+                self.kem_pks.push((subscriber_sig_pk.clone(), kem_pk));
+            }
+        }
         let ref_link = self.appinst.as_ref().unwrap().rel().clone();
+        // Counters of publishers start at state 2 since 0 and 1 are reserved states.
+        let seq_no = if self.is_counter_sequencing() { 2 } else { SEQ_MESSAGE_NUM };
         self.pk_store
-            .insert(subscriber_sig_pk, Cursor::new_at(ref_link, 0, SEQ_MESSAGE_NUM));
+            .insert(subscriber_sig_pk.clone(), Cursor::new_at(ref_link, 0, seq_no));
+        self.membership_epoch += 1;
         // Unwrapped unsubscribe_key is not used explicitly.
-        Ok(())
+        Ok(subscriber_sig_pk)
     }
 
     fn do_prepare_keyload<'a, Psks, KePks>(
@@ -399,6 +904,8 @@ where
         link_to: &'a <Link as HasLink>::Rel,
         psks: Psks,
         ke_pks: KePks,
+        read_only: &'a [ed25519::PublicKey],
+        anonymous: bool,
     ) -> Result<PreparedMessage<'a, F, Link, LS, keyload::ContentWrap<'a, F, Link, Psks, KePks>>>
     where
         Psks: Clone + ExactSizeIterator<Item = psk::IPsk<'a>>,
@@ -410,8 +917,16 @@ where
             link: link_to,
             nonce: nonce,
             key: key,
+            max_messages: Uint32(self.rate_limiter.policy.max_messages),
+            period_ms: Uint64(self.rate_limiter.policy.period_ms),
+            branch_policy: Uint8(self.branch_policy.as_u8()),
+            policies: has_keyload_policies(header.version),
             psks: psks,
             ke_pks: ke_pks,
+            read_only,
+            anonymous,
+            #[cfg(feature = "pq-kem")]
+            kem_pks: if self.is_hybrid_kem() { Some(&self.kem_pks[..]) } else { None },
             sig_kp: &self.sig_kp,
             _phantom: core::marker::PhantomData,
         };
@@ -438,17 +953,42 @@ where
             >,
         >,
     > {
-        let seq_no = self.get_seq_no().ok_or(anyhow!("Internal error: bad seq num"))?;
-        let msg_link = self
-            .link_gen
-            .link_from(&self.sig_kp.public, Cursor::new_at(link_to, 0, seq_no));
+        self.prepare_keyload_with_read_only(link_to, psk_ids, pks, &[])
+    }
+
+    /// Prepare keyload, recipients in `read_only` may not publish in its branch.
+    pub fn prepare_keyload_with_read_only<'a>(
+        &'a mut self,
+        link_to: &'a <Link as HasLink>::Rel,
+        psk_ids: &psk::PskIds,
+        pks: &'a Vec<ed25519::PublicKey>,
+        read_only: &'a [ed25519::PublicKey],
+    ) -> Result<
+        PreparedMessage<
+            'a,
+            F,
+            Link,
+            LS,
+            keyload::ContentWrap<
+                'a,
+                F,
+                Link,
+                vec::IntoIter<psk::IPsk<'a>>,
+                vec::IntoIter<(ed25519::IPk<'a>, x25519::IPk<'a>)>,
+            >,
+        >,
+    > {
+        let seq_no = self.next_seq_no(link_to).ok_or(anyhow!("Internal error: bad seq num"))?;
+        let msg_link = self.derive_msg_link(&self.sig_kp.public, link_to, seq_no);
         let header = HDF::new(msg_link)
             .with_content_type(KEYLOAD)?
             .with_payload_length(1)?
             .with_seq_num(seq_no);
+        let header = self.with_header_ext(header)?;
         let psks = self.psk_store.filter(psk_ids);
+        ensure!(psks.len() == psk_ids.len(), "Unknown pre-shared key id in keyload recipients");
         let ke_pks = self.pk_store.filter(pks);
-        self.do_prepare_keyload(header, link_to, psks.into_iter(), ke_pks.into_iter())
+        self.do_prepare_keyload(header, link_to, psks.into_iter(), ke_pks.into_iter(), read_only, false)
     }
 
     pub fn prepare_keyload_for_everyone<'a>(
@@ -469,17 +1009,48 @@ where
             >,
         >,
     > {
-        let seq_no = self.get_seq_no().ok_or(anyhow!("Internal error: bad seq num"))?;
-        let msg_link = self
-            .link_gen
-            .link_from(&self.sig_kp.public, Cursor::new_at(link_to, 0, seq_no));
+        let seq_no = self.next_seq_no(link_to).ok_or(anyhow!("Internal error: bad seq num"))?;
+        let msg_link = self.derive_msg_link(&self.sig_kp.public, link_to, seq_no);
         let header = hdf::HDF::new(msg_link)
             .with_content_type(KEYLOAD)?
             .with_payload_length(1)?
             .with_seq_num(seq_no);
+        let header = self.with_header_ext(header)?;
         let ipsks = self.psk_store.iter();
         let ike_pks = self.pk_store.keys();
-        self.do_prepare_keyload(header, link_to, ipsks.into_iter(), ike_pks.into_iter())
+        self.do_prepare_keyload(header, link_to, ipsks.into_iter(), ike_pks.into_iter(), &[], false)
+    }
+
+    /// Prepare anonymous keyload, recipient public keys are not listed in the message.
+    pub fn prepare_anonymous_keyload<'a>(
+        &'a mut self,
+        link_to: &'a <Link as HasLink>::Rel,
+        pks: &'a Vec<ed25519::PublicKey>,
+    ) -> Result<
+        PreparedMessage<
+            'a,
+            F,
+            Link,
+            LS,
+            keyload::ContentWrap<
+                'a,
+                F,
+                Link,
+                vec::IntoIter<psk::IPsk<'a>>,
+                vec::IntoIter<(ed25519::IPk<'a>, x25519::IPk<'a>)>,
+            >,
+        >,
+    > {
+        let seq_no = self.next_seq_no(link_to).ok_or(anyhow!("Internal error: bad seq num"))?;
+        let msg_link = self.derive_msg_link(&self.sig_kp.public, link_to, seq_no);
+        let header = HDF::new(msg_link)
+            .with_content_type(ANONYMOUS_KEYLOAD)?
+            .with_payload_length(1)?
+            .with_seq_num(seq_no);
+        let header = self.with_header_ext(header)?;
+        let ke_pks = self.pk_store.filter(pks);
+        ensure!(ke_pks.len() == pks.len(), "Unknown public key in anonymous keyload recipients");
+        self.do_prepare_keyload(header, link_to, Vec::new().into_iter(), ke_pks.into_iter(), &[], true)
     }
 
     /// Create keyload message with a new session key shared with recipients
@@ -490,13 +1061,61 @@ where
         psk_ids: &psk::PskIds,
         ke_pks: &Vec<ed25519::PublicKey>,
     ) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_keyload(link_to, psk_ids, ke_pks)?.wrap()
+        self.prepare_keyload(link_to, psk_ids, ke_pks)?.wrap_pooled(&self.buffer_pool)
+    }
+
+    /// Create keyload message with a new session key, recipients in `read_only` may read
+    /// the branch but not publish in it. Tagged packets can't be attributed to their sender,
+    /// so the branch only accepts signed packets of other recipients.
+    pub fn share_keyload_with_read_only(
+        &mut self,
+        link_to: &<Link as HasLink>::Rel,
+        psk_ids: &psk::PskIds,
+        ke_pks: &Vec<ed25519::PublicKey>,
+        read_only: &Vec<ed25519::PublicKey>,
+    ) -> Result<WrappedMessage<F, Link>> {
+        let wrapped = self
+            .prepare_keyload_with_read_only(link_to, psk_ids, ke_pks, read_only)?
+            .wrap_pooled(&self.buffer_pool)?;
+        if !read_only.is_empty() {
+            self.read_only.push((wrapped.message.link.rel().clone(), read_only.clone()));
+        }
+        Ok(wrapped)
+    }
+
+    /// Create keyload message with a new session key shared with recipients `ke_pks`
+    /// without listing their public keys; recipients find their key slot by trial decryption.
+    pub fn share_anonymous_keyload(
+        &mut self,
+        link_to: &<Link as HasLink>::Rel,
+        ke_pks: &Vec<ed25519::PublicKey>,
+    ) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_anonymous_keyload(link_to, ke_pks)?.wrap_pooled(&self.buffer_pool)
     }
 
     /// Create keyload message with a new session key shared with all Subscribers
     /// known to Author.
     pub fn share_keyload_for_everyone(&mut self, link_to: &<Link as HasLink>::Rel) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_keyload_for_everyone(link_to)?.wrap()
+        self.prepare_keyload_for_everyone(link_to)?.wrap_pooled(&self.buffer_pool)
+    }
+
+    /// Store pre-shared key `psk` identified by `pskid`. Keyloads may be shared with several
+    /// PSK ids at once, recipients unwrap the session key with the PSK of a matching id.
+    pub fn store_psk(&mut self, pskid: psk::PskId, psk: psk::Psk) {
+        self.psk_store.insert(pskid, psk);
+    }
+
+    /// Generate and store `count` random pre-shared keys to be listed in keyloads as reserved
+    /// slots. Slot keys are handed out-of-band to recipients joining later, who unwrap the
+    /// keyload with the slot key without a new keyload being sent.
+    pub fn reserve_psk_slots(&mut self, count: usize) -> Vec<(psk::PskId, psk::Psk)> {
+        let slots: Vec<(psk::PskId, psk::Psk)> = (0..count)
+            .map(|_| (prng::random_nonce(), prng::random_key()))
+            .collect();
+        for (pskid, psk) in slots.iter() {
+            self.psk_store.insert(pskid.clone(), psk.clone());
+        }
+        slots
     }
 
     fn lookup_psk<'b>(&'b self, pskid: &psk::PskId) -> Option<&'b psk::Psk> {
@@ -530,7 +1149,7 @@ where
     > {
         self.ensure_appinst(&preparsed)?;
         if let Some(ref author_sig_pk) = self.author_sig_pk {
-            let content = keyload::ContentUnwrap::<
+            let mut content = keyload::ContentUnwrap::<
                 'b,
                 F,
                 Link,
@@ -538,6 +1157,18 @@ where
                 for<'c> fn(&'c Self, &psk::PskId) -> Option<&'c psk::Psk>,
                 for<'c> fn(&'c Self, &ed25519::PublicKey) -> Option<&'c x25519::StaticSecret>,
             >::new(self, Self::lookup_psk, Self::lookup_ke_sk, author_sig_pk);
+            if preparsed.content_type() == ANONYMOUS_KEYLOAD {
+                content = content.with_anonymous_recipient(self.sig_kp.public.clone());
+            }
+            #[cfg(feature = "pq-kem")]
+            {
+                if let Some(ref kem_kp) = self.kem_kp {
+                    content = content.with_kem_keypair(kem_kp);
+                }
+            }
+            content = content.with_delegates(self.delegations.clone());
+            content.link = self.default_rel();
+            content.policies = has_keyload_policies(preparsed.header.version);
             let unwrapped = preparsed.unwrap(&*self.link_store.borrow(), content)?;
             Ok(unwrapped)
         } else {
@@ -551,6 +1182,13 @@ where
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
     ) -> Result<GenericMessage<Link, bool>> {
+        let rel = msg.link.rel().clone();
+        // Same keyload handled again, see `SessionCache`.
+        if let Some(spongos) = self.session_cache.get(&rel) {
+            self.link_store.borrow_mut().update(&rel, spongos, info)?;
+            return Ok(GenericMessage::new(msg.link, true));
+        }
+
         let preparsed = msg.parse_header()?;
 
         let unwrapped = self
@@ -559,6 +1197,17 @@ where
         if unwrapped.pcf.content.key.is_some() {
             // Do not commit if key not found hence spongos state is invalid
             let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+            // Branches of keyloads without policies are sequenced as announced for the channel.
+            let branch_policy = if content.policies {
+                self.register_rate_policy(&rel, RatePolicy::new(content.max_messages.0, content.period_ms.0));
+                BranchPolicy::from_u8(content.branch_policy.0)
+            } else {
+                BranchPolicy::Channel
+            };
+            self.register_branch(&rel, branch_policy);
+            if !content.read_only.is_empty() {
+                self.read_only.push((msg.link.rel().clone(), content.read_only));
+            }
 
             // Presence of the key indicates the user is allowed
             // Unwrapped nonce and key in content are not used explicitly.
@@ -572,6 +1221,8 @@ where
                     }
                 }
             }
+            let (spongos, _info) = self.link_store.borrow().lookup(&rel)?;
+            self.session_cache.insert(rel, spongos);
             Ok(GenericMessage::new(msg.link, true))
         } else {
             Ok(GenericMessage::new(msg.link, false))
@@ -585,19 +1236,22 @@ where
         public_payload: &'a Bytes,
         masked_payload: &'a Bytes,
     ) -> Result<PreparedMessage<'a, F, Link, LS, signed_packet::ContentWrap<'a, F, Link>>> {
-        let seq_no = self.get_seq_no().ok_or(anyhow!("Internal error: bad seq num"))?;
-        let msg_link = self
-            .link_gen
-            .link_from(&self.sig_kp.public, Cursor::new_at(link_to, 0, seq_no));
+        let seq_no = self.next_seq_no(link_to).ok_or(anyhow!("Internal error: bad seq num"))?;
+        let msg_link = self.derive_msg_link(&self.sig_kp.public, link_to, seq_no);
         let header = HDF::new(msg_link)
             .with_content_type(SIGNED_PACKET)?
             .with_payload_length(1)?
             .with_seq_num(seq_no);
+        let header = self.with_header_ext(header)?;
+        let (header, signer) = self.own_signer(header)?;
         let content = signed_packet::ContentWrap {
             link: link_to,
             public_payload: public_payload,
             masked_payload: masked_payload,
-            sig_kp: &self.sig_kp,
+            siv: self.is_siv_masking(),
+            signer,
+            #[cfg(feature = "secp256k1")]
+            cosigner: None,
             _phantom: core::marker::PhantomData,
         };
         Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
@@ -610,32 +1264,498 @@ where
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_signed_packet(link_to, public_payload, masked_payload)?
-            .wrap()
-    }
-
-    pub fn unwrap_signed_packet<'a>(
-        &'a self,
-        preparsed: PreparsedMessage<'a, F, Link>,
-    ) -> Result<UnwrappedMessage<F, Link, signed_packet::ContentUnwrap<F, Link>>> {
-        self.ensure_appinst(&preparsed)?;
-        let content = signed_packet::ContentUnwrap::default();
-        preparsed.unwrap(&*self.link_store.borrow(), content)
+        let wrapped = self
+            .prepare_signed_packet(link_to, public_payload, masked_payload)?
+            .wrap_pooled(&self.buffer_pool)?;
+        self.track_packet(wrapped.message.link.rel(), link_to);
+        Ok(wrapped)
     }
 
-    /// Verify new Author's MSS public key and update Author's MSS public key.
+    /// Prepare SignedPacket message co-signed with secp256k1 key `cosigner`, it's wrapped with
+    /// `STREAMS_1_5_VER`. Packets of hybrid channel authors can't be co-signed.
+    #[cfg(feature = "secp256k1")]
+    pub fn prepare_cosigned_packet<'a>(
+        &'a mut self,
+        link_to: &'a <Link as HasLink>::Rel,
+        public_payload: &'a Bytes,
+        masked_payload: &'a Bytes,
+        cosigner: &'a secp256k1::SigningKey,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, signed_packet::ContentWrap<'a, F, Link>>> {
+        let mut prepared = self.prepare_signed_packet(link_to, public_payload, masked_payload)?;
+        ensure!(
+            !has_pq_signatures(prepared.header.version),
+            "Signed packets of hybrid channel authors can't be co-signed."
+        );
+        prepared.header = prepared.header.with_version(STREAMS_1_5_VER)?;
+        prepared.content.content.cosigner = Some(cosigner);
+        Ok(prepared)
+    }
+
+    /// Create a signed message with public and masked payload co-signed with secp256k1 key `cosigner`.
+    #[cfg(feature = "secp256k1")]
+    pub fn cosign_packet(
+        &mut self,
+        link_to: &<Link as HasLink>::Rel,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        cosigner: &secp256k1::SigningKey,
+    ) -> Result<WrappedMessage<F, Link>> {
+        let wrapped = self
+            .prepare_cosigned_packet(link_to, public_payload, masked_payload, cosigner)?
+            .wrap_pooled(&self.buffer_pool)?;
+        self.track_packet(wrapped.message.link.rel(), link_to);
+        Ok(wrapped)
+    }
+
+    /// Prepare SignedPacket message of the threshold group, `sig` is the group signature or
+    /// `None` to get the hash to be signed.
+    pub fn prepare_threshold_signed_packet<'a>(
+        &'a self,
+        link_to: &'a <Link as HasLink>::Rel,
+        public_payload: &'a Bytes,
+        masked_payload: &'a Bytes,
+        sig: Option<&'a ed25519::Signature>,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, signed_packet::ContentWrap<'a, F, Link>>> {
+        let group_pk = self.threshold_pk()?;
+        let seq_no = self.next_seq_no(link_to).ok_or(anyhow!("Internal error: bad seq num"))?;
+        let msg_link = self.derive_msg_link(group_pk, link_to, seq_no);
+        let header = HDF::new(msg_link)
+            .with_content_type(SIGNED_PACKET)?
+            .with_payload_length(1)?
+            .with_seq_num(seq_no);
+        let header = self.with_header_ext(header)?;
+        let content = signed_packet::ContentWrap {
+            link: link_to,
+            public_payload: public_payload,
+            masked_payload: masked_payload,
+            siv: self.is_siv_masking(),
+            signer: Signer::threshold(group_pk, sig),
+            #[cfg(feature = "secp256k1")]
+            cosigner: None,
+            _phantom: core::marker::PhantomData,
+        };
+        Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+    }
+
+    /// Hash of the next SignedPacket message of the threshold group to be signed by the group.
+    pub fn threshold_signed_packet_tag(
+        &self,
+        link_to: &<Link as HasLink>::Rel,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<[u8; 64]> {
+        let prepared = self.prepare_threshold_signed_packet(link_to, public_payload, masked_payload, None)?;
+        prepared.wrap()?;
+        prepared
+            .content
+            .content
+            .signer
+            .tag()
+            .ok_or_else(|| anyhow!("Internal error: no tag"))
+    }
+
+    /// Create a SignedPacket message of the threshold group with group signature `sig`.
+    pub fn threshold_sign_packet(
+        &self,
+        link_to: &<Link as HasLink>::Rel,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        sig: &ed25519::Signature,
+    ) -> Result<WrappedMessage<F, Link>> {
+        let prepared = self.prepare_threshold_signed_packet(link_to, public_payload, masked_payload, Some(sig))?;
+        let wrapped = prepared.wrap_pooled(&self.buffer_pool)?;
+        self.ensure_threshold_sig(&prepared.header, prepared.content.content.signer.tag(), sig)?;
+        Ok(wrapped)
+    }
+
+    fn signed_packet_content(&self, version: Uint8) -> Result<signed_packet::ContentUnwrap<F, Link>> {
+        self.ensure_signature_version(version)?;
+        let mut content = signed_packet::ContentUnwrap::default();
+        content.link = self.default_rel();
+        content.siv = self.is_siv_masking();
+        if self.defer_masked_payloads {
+            content.masked_payload = MaskedBytes::deferred();
+        }
+        #[cfg(feature = "pq-sig")]
+        {
+            if has_pq_signatures(version) {
+                content.mss_pk = Some(self.author_mss_pk.ok_or_else(|| anyhow!("Channel is not hybrid."))?);
+            }
+        }
+        ensure!(
+            cfg!(feature = "secp256k1") || !has_cosignatures(version),
+            "Secp256k1 co-signatures are not supported, enable the `secp256k1` feature."
+        );
+        #[cfg(feature = "secp256k1")]
+        {
+            content.cosigned = has_cosignatures(version);
+        }
+        Ok(content)
+    }
+
+    /// In hybrid channels signed packets of the author must carry post-quantum signatures,
+    /// otherwise forging the Ed25519 signature would be enough to impersonate the author.
+    fn ensure_hybrid_signed<Content>(
+        &self,
+        unwrapped: &UnwrappedMessage<F, Link, Content>,
+        sig_pk: &ed25519::PublicKey,
+    ) -> Result<()> {
+        ensure!(
+            !self.is_hybrid()
+                || self.author_sig_pk.as_ref() != Some(sig_pk)
+                || has_pq_signatures(Uint8(unwrapped.version())),
+            "Signed packet of the hybrid channel author has no post-quantum signature."
+        );
+        Ok(())
+    }
+
+    pub fn unwrap_signed_packet<'a>(
+        &'a self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, signed_packet::ContentUnwrap<F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        let content = self.signed_packet_content(preparsed.header.version)?;
+        let unwrapped = preparsed.unwrap(&*self.link_store.borrow(), content)?;
+        self.ensure_hybrid_signed(&unwrapped, &unwrapped.pcf.content.sig_pk)?;
+        Ok(unwrapped)
+    }
+
+    /// Unwrap SignedPacket message collecting its signature instead of verifying it.
+    fn unwrap_signed_packet_unverified<'a>(
+        &self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<(
+        UnwrappedMessage<F, Link, signed_packet::ContentUnwrap<F, Link>>,
+        Vec<ed25519::PrehashedSignature>,
+    )> {
+        self.ensure_appinst(&preparsed)?;
+        let content = self.signed_packet_content(preparsed.header.version)?;
+        let (unwrapped, signatures) = preparsed.unwrap_unverified(&*self.link_store.borrow(), content)?;
+        self.ensure_hybrid_signed(&unwrapped, &unwrapped.pcf.content.sig_pk)?;
+        Ok((unwrapped, signatures))
+    }
+
+    /// Verify signatures of SignedPacket messages `msgs` as a batch. If all signatures are
+    /// valid, `handle_signed_packet` reuses the unwrapped packets instead of unwrapping and
+    /// verifying them again; otherwise each packet is unwrapped and verified when handled.
+    /// Packets that can't be unwrapped yet, eg. because they are linked to a message in the
+    /// same batch, are left out. Returns the number of verified packets.
+    pub fn verify_signed_packets<'a, I>(&mut self, msgs: I) -> usize
+    where
+        I: IntoIterator<Item = &'a BinaryMessage<F, Link>>,
+        F: 'a,
+        Link: 'a,
+    {
+        self.verified_packets.clear();
+        let mut packets = Vec::new();
+        let mut signatures = Vec::new();
+        for msg in msgs {
+            let preparsed = match msg.parse_header() {
+                Ok(preparsed) if preparsed.check_content_type(SIGNED_PACKET) => preparsed,
+                _ => continue,
+            };
+            if let Ok((unwrapped, sigs)) = self.unwrap_signed_packet_unverified(preparsed) {
+                packets.push((msg.link.clone(), unwrapped));
+                signatures.extend(sigs);
+            }
+        }
+        if signatures.len() < 2 || unwrap::verify_signatures(&signatures).is_err() {
+            return 0;
+        }
+        self.verified_packets = packets;
+        self.verified_packets.len()
+    }
+
+    /// Verify new Author's MSS public key and update Author's MSS public key.
     pub fn handle_signed_packet<'a>(
         &'a mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
-    ) -> Result<GenericMessage<Link, (ed25519::PublicKey, Bytes, Bytes)>> {
+    ) -> Result<GenericMessage<Link, (ed25519::PublicKey, Bytes, MaskedBytes<F>)>> {
         // TODO: pass author_pk to unwrap
+        let unwrapped = match self.verified_packets.iter().position(|(link, _)| *link == msg.link) {
+            Some(i) => self.verified_packets.swap_remove(i).1,
+            None => self.unwrap_signed_packet(msg.parse_header()?)?,
+        };
+        self.ensure_write_permission(&unwrapped.pcf.content.link, Some(&unwrapped.pcf.content.sig_pk))?;
+        self.track_packet(msg.link.rel(), &unwrapped.pcf.content.link);
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        let body = (content.sig_pk, content.public_payload, content.masked_payload);
+        Ok(GenericMessage::new(msg.link, body))
+    }
+
+    /// Handle SignedPacket message and return its secp256k1 co-signature, if any.
+    #[cfg(feature = "secp256k1")]
+    pub fn handle_cosigned_packet<'a>(
+        &'a mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, (ed25519::PublicKey, Bytes, MaskedBytes<F>, Option<secp256k1::Cosignature>)>> {
+        let preparsed = msg.parse_header()?;
+        let unwrapped = self.unwrap_signed_packet(preparsed)?;
+        self.ensure_write_permission(&unwrapped.pcf.content.link, Some(&unwrapped.pcf.content.sig_pk))?;
+        self.track_packet(msg.link.rel(), &unwrapped.pcf.content.link);
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        let body = (
+            content.sig_pk,
+            content.public_payload,
+            content.masked_payload,
+            content.cosignature,
+        );
+        Ok(GenericMessage::new(msg.link, body))
+    }
+
+    /// Prepare BranchDelegation message.
+    pub fn prepare_branch_delegation<'a>(
+        &'a mut self,
+        link_to: &'a <Link as HasLink>::Rel,
+        delegate_pk: &'a ed25519::PublicKey,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, branch_delegation::ContentWrap<'a, F, Link>>> {
+        ensure!(self.is_author(), "Only the channel author can delegate branches.");
+        let seq_no = self.next_seq_no(link_to).ok_or(anyhow!("Internal error: bad seq num"))?;
+        let msg_link = self.derive_msg_link(&self.sig_kp.public, link_to, seq_no);
+        let header = HDF::new(msg_link)
+            .with_content_type(BRANCH_DELEGATION)?
+            .with_payload_length(1)?
+            .with_seq_num(seq_no);
+        let header = self.with_header_ext(header)?;
+        let content = branch_delegation::ContentWrap {
+            link: link_to,
+            delegate_pk,
+            sig_kp: &self.sig_kp,
+            _phantom: core::marker::PhantomData,
+        };
+        Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+    }
+
+    /// Create a message rooting a new branch, keyloads in the branch can be shared by `delegate_pk`.
+    pub fn delegate_branch(
+        &mut self,
+        link_to: &<Link as HasLink>::Rel,
+        delegate_pk: &ed25519::PublicKey,
+    ) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_branch_delegation(link_to, delegate_pk)?.wrap_pooled(&self.buffer_pool)
+    }
+
+    pub fn unwrap_branch_delegation<'a>(
+        &'a self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, branch_delegation::ContentUnwrap<F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        let author_sig_pk = if self.is_author() {
+            self.sig_kp.public.clone()
+        } else {
+            self.author_sig_pk
+                .clone()
+                .ok_or_else(|| anyhow!("Can't unwrap branch delegation, no author's public key"))?
+        };
+        let mut content = branch_delegation::ContentUnwrap::new(author_sig_pk);
+        content.link = self.default_rel();
+        preparsed.unwrap(&*self.link_store.borrow(), content)
+    }
+
+    /// Verify author's signature and remember the delegate of the branch rooted at the message.
+    pub fn handle_branch_delegation<'a>(
+        &'a mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, ed25519::PublicKey>> {
         let preparsed = msg.parse_header()?;
 
         let content = self
-            .unwrap_signed_packet(preparsed)?
+            .unwrap_branch_delegation(preparsed)?
             .commit(self.link_store.borrow_mut(), info)?;
-        let body = (content.sig_pk, content.public_payload, content.masked_payload);
+        let branch = msg.link.rel().clone();
+        self.delegations.retain(|(b, _)| *b != branch);
+        self.delegations.push((branch, content.delegate_pk.clone()));
+        Ok(GenericMessage::new(msg.link, content.delegate_pk))
+    }
+
+    /// Prepare Checkpoint message.
+    pub fn prepare_checkpoint<'a>(
+        &'a self,
+        link_to: &'a <Link as HasLink>::Rel,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, checkpoint::ContentWrap<'a, F, Link>>> {
+        ensure!(self.is_author(), "Only the channel author can publish checkpoints.");
+        let seq_no = self.next_seq_no(link_to).ok_or(anyhow!("Internal error: bad seq num"))?;
+        let msg_link = self.derive_msg_link(&self.sig_kp.public, link_to, seq_no);
+        let header = HDF::new(msg_link)
+            .with_content_type(CHECKPOINT)?
+            .with_payload_length(1)?
+            .with_seq_num(seq_no);
+        let header = self.with_header_ext(header)?;
+        let content = checkpoint::ContentWrap {
+            link: link_to,
+            states: self.pk_store.iter(),
+            sig_kp: &self.sig_kp,
+            _phantom: core::marker::PhantomData,
+        };
+        Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+    }
+
+    /// Create a message summarizing the sequencing state of all known publishers.
+    pub fn checkpoint(&mut self, link_to: &<Link as HasLink>::Rel) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_checkpoint(link_to)?.wrap_pooled(&self.buffer_pool)
+    }
+
+    pub fn unwrap_checkpoint<'a>(
+        &'a self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, checkpoint::ContentUnwrap<F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        let author_sig_pk = if self.is_author() {
+            self.sig_kp.public.clone()
+        } else {
+            self.author_sig_pk
+                .clone()
+                .ok_or_else(|| anyhow!("Can't unwrap checkpoint, no author's public key"))?
+        };
+        let mut content = checkpoint::ContentUnwrap::new(author_sig_pk);
+        content.link = self.default_rel();
+        preparsed.unwrap(&*self.link_store.borrow(), content)
+    }
+
+    /// Verify checkpoint signature and advance the sequencing state of publishers to it.
+    /// Cursors already ahead of the checkpoint are kept. Returns the number of publishers
+    /// in the checkpoint.
+    pub fn handle_checkpoint<'a>(
+        &'a mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, usize>> {
+        let preparsed = msg.parse_header()?;
+
+        let content = self
+            .unwrap_checkpoint(preparsed)?
+            .commit(self.link_store.borrow_mut(), info)?;
+        let publishers = content.states.len();
+        for (pk, cursor) in content.states {
+            let behind = self.pk_store.get(&pk).map_or(true, |current| current.seq_no < cursor.seq_no);
+            if behind {
+                self.pk_store.insert(pk, cursor);
+            }
+        }
+        Ok(GenericMessage::new(msg.link, publishers))
+    }
+
+    /// Delegate of the branch rooted at `link`, if any.
+    pub fn branch_delegate(&self, link: &<Link as HasLink>::Rel) -> Option<&ed25519::PublicKey> {
+        self.delegations.iter().find(|(b, _)| b == link).map(|(_, pk)| pk)
+    }
+
+    /// User is allowed to share keyloads linked to `link_to`: the author can do so anywhere,
+    /// delegates only at the root of their branches.
+    pub fn can_share_keyload(&self, link_to: &<Link as HasLink>::Rel) -> bool {
+        self.is_author() || self.branch_delegate(link_to) == Some(&self.sig_kp.public)
+    }
+
+    /// Make a user known as keyload recipient, eg. by a branch delegate, who doesn't process subscriptions.
+    pub fn insert_recipient(&mut self, pk: ed25519::PublicKey) -> Result<()> {
+        let appinst = self.appinst.as_ref().ok_or_else(|| anyhow!("No channel registered"))?;
+        if self.pk_store.get(&pk).is_none() {
+            // Store at state 2 since 0 and 1 are reserved states
+            self.pk_store.insert(pk, Cursor::new_at(appinst.rel().clone(), 0, 2));
+            self.membership_epoch += 1;
+        }
+        Ok(())
+    }
+
+    /// Forget subscriber `pk`, subsequent keyloads for everyone are not shared with it.
+    /// Returns whether the subscriber was known.
+    pub fn remove_subscriber(&mut self, pk: &ed25519::PublicKey) -> Result<bool> {
+        ensure!(self.is_author(), "Only the channel author can remove subscribers.");
+        ensure!(*pk != self.sig_kp.public, "The channel author can't be removed.");
+        #[cfg(feature = "pq-kem")]
+        self.kem_pks.retain(|(kem_pk, _)| kem_pk != pk);
+        if self.pk_store.remove(pk).is_none() {
+            return Ok(false);
+        }
+        self.membership_epoch += 1;
+        Ok(true)
+    }
+
+    /// Number of changes of the known subscribers: subscriptions, recipients and removals.
+    pub fn membership_epoch(&self) -> u64 {
+        self.membership_epoch
+    }
+
+    /// Prepare PrivatePacket message.
+    pub fn prepare_private_packet<'a>(
+        &'a mut self,
+        link_to: &'a <Link as HasLink>::Rel,
+        public_payload: &'a Bytes,
+        masked_payload: &'a Bytes,
+        recipient_pk: &'a ed25519::PublicKey,
+        private_payload: &'a Bytes,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, private_packet::ContentWrap<'a, F, Link>>> {
+        let seq_no = self.next_seq_no(link_to).ok_or(anyhow!("Internal error: bad seq num"))?;
+        let msg_link = self.derive_msg_link(&self.sig_kp.public, link_to, seq_no);
+        let header = HDF::new(msg_link)
+            .with_content_type(PRIVATE_PACKET)?
+            .with_payload_length(1)?
+            .with_seq_num(seq_no);
+        let header = self.with_header_ext(header)?;
+        let content = private_packet::ContentWrap {
+            link: link_to,
+            public_payload: public_payload,
+            masked_payload: masked_payload,
+            siv: self.is_siv_masking(),
+            recipient_pk: recipient_pk,
+            recipient_ke_pk: x25519::public_from_ed25519(recipient_pk),
+            private_payload: private_payload,
+            sig_kp: &self.sig_kp,
+            _phantom: core::marker::PhantomData,
+        };
+        Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+    }
+
+    /// Create a signed message with public and masked payload and a private payload
+    /// readable only by the owner of `recipient_pk`.
+    pub fn private_packet(
+        &mut self,
+        link_to: &<Link as HasLink>::Rel,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+        recipient_pk: &ed25519::PublicKey,
+        private_payload: &Bytes,
+    ) -> Result<WrappedMessage<F, Link>> {
+        let wrapped = self
+            .prepare_private_packet(link_to, public_payload, masked_payload, recipient_pk, private_payload)?
+            .wrap_pooled(&self.buffer_pool)?;
+        self.track_packet(wrapped.message.link.rel(), link_to);
+        Ok(wrapped)
+    }
+
+    pub fn unwrap_private_packet<'a>(
+        &'a self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, private_packet::ContentUnwrap<'a, F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        let mut content = private_packet::ContentUnwrap::new(&self.sig_kp.public, &self.ke_kp.0);
+        content.link = self.default_rel();
+        content.siv = self.is_siv_masking();
+        preparsed.unwrap(&*self.link_store.borrow(), content)
+    }
+
+    /// Verify signature and decrypt private payload if addressed to this user.
+    pub fn handle_private_packet<'a>(
+        &'a mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, (ed25519::PublicKey, Bytes, Bytes, Option<Bytes>)>> {
+        let preparsed = msg.parse_header()?;
+
+        let unwrapped = self.unwrap_private_packet(preparsed)?;
+        self.ensure_write_permission(&unwrapped.pcf.content.link, Some(&unwrapped.pcf.content.sig_pk))?;
+        let link_to = unwrapped.pcf.content.link.clone();
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        let body = (
+            content.sig_pk,
+            content.public_payload,
+            content.masked_payload,
+            content.private_payload,
+        );
+        self.track_packet(msg.link.rel(), &link_to);
         Ok(GenericMessage::new(msg.link, body))
     }
 
@@ -646,18 +1766,38 @@ where
         public_payload: &'a Bytes,
         masked_payload: &'a Bytes,
     ) -> Result<PreparedMessage<'a, F, Link, LS, tagged_packet::ContentWrap<'a, F, Link>>> {
-        let seq_no = self.get_seq_no().ok_or(anyhow!("Internal error: bad seq num"))?;
-        let msg_link = self
-            .link_gen
-            .link_from(&self.sig_kp.public, Cursor::new_at(link_to, 0, seq_no));
+        self.prepare_mac_packet(TAGGED_PACKET, link_to, public_payload, masked_payload)
+    }
+
+    /// Prepare DeniablePacket message, its content is the one of TaggedPacket.
+    pub fn prepare_deniable_packet<'a>(
+        &'a mut self,
+        link_to: &'a <Link as HasLink>::Rel,
+        public_payload: &'a Bytes,
+        masked_payload: &'a Bytes,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, tagged_packet::ContentWrap<'a, F, Link>>> {
+        self.prepare_mac_packet(DENIABLE_PACKET, link_to, public_payload, masked_payload)
+    }
+
+    fn prepare_mac_packet<'a>(
+        &'a mut self,
+        content_type: u8,
+        link_to: &'a <Link as HasLink>::Rel,
+        public_payload: &'a Bytes,
+        masked_payload: &'a Bytes,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, tagged_packet::ContentWrap<'a, F, Link>>> {
+        let seq_no = self.next_seq_no(link_to).ok_or(anyhow!("Internal error: bad seq num"))?;
+        let msg_link = self.derive_msg_link(&self.sig_kp.public, link_to, seq_no);
         let header = HDF::new(msg_link)
-            .with_content_type(TAGGED_PACKET)?
+            .with_content_type(content_type)?
             .with_payload_length(1)?
             .with_seq_num(seq_no);
+        let header = self.with_header_ext(header)?;
         let content = tagged_packet::ContentWrap {
             link: link_to,
             public_payload: public_payload,
             masked_payload: masked_payload,
+            siv: self.is_siv_masking(),
             _phantom: core::marker::PhantomData,
         };
         Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
@@ -671,8 +1811,11 @@ where
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_tagged_packet(link_to, public_payload, masked_payload)?
-            .wrap()
+        let wrapped = self
+            .prepare_tagged_packet(link_to, public_payload, masked_payload)?
+            .wrap_pooled(&self.buffer_pool)?;
+        self.track_packet(wrapped.message.link.rel(), link_to);
+        Ok(wrapped)
     }
 
     pub fn unwrap_tagged_packet<'a>(
@@ -680,7 +1823,12 @@ where
         preparsed: PreparsedMessage<'a, F, Link>,
     ) -> Result<UnwrappedMessage<F, Link, tagged_packet::ContentUnwrap<F, Link>>> {
         self.ensure_appinst(&preparsed)?;
-        let content = tagged_packet::ContentUnwrap::new();
+        let mut content = tagged_packet::ContentUnwrap::new();
+        content.link = self.default_rel();
+        content.siv = self.is_siv_masking();
+        if self.defer_masked_payloads {
+            content.masked_payload = MaskedBytes::deferred();
+        }
         preparsed.unwrap(&*self.link_store.borrow(), content)
     }
 
@@ -689,16 +1837,125 @@ where
         &mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
-    ) -> Result<GenericMessage<Link, (Bytes, Bytes)>> {
+    ) -> Result<GenericMessage<Link, (Bytes, MaskedBytes<F>)>> {
         let preparsed = msg.parse_header()?;
 
-        let content = self
-            .unwrap_tagged_packet(preparsed)?
-            .commit(self.link_store.borrow_mut(), info)?;
+        let unwrapped = self.unwrap_tagged_packet(preparsed)?;
+        self.ensure_write_permission(&unwrapped.pcf.content.link, None)?;
+        self.track_packet(msg.link.rel(), &unwrapped.pcf.content.link);
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        let body = (content.public_payload, content.masked_payload);
+        Ok(GenericMessage::new(msg.link, body))
+    }
+
+    /// Create a deniable message with public and masked payload, authenticated with the MAC of
+    /// the branch session key like a tagged message. It must be linked to a keyload or to
+    /// another deniable message, see `handle_deniable_packet`.
+    pub fn deniable_packet(
+        &mut self,
+        link_to: &<Link as HasLink>::Rel,
+        public_payload: &Bytes,
+        masked_payload: &Bytes,
+    ) -> Result<WrappedMessage<F, Link>> {
+        let wrapped = self
+            .prepare_deniable_packet(link_to, public_payload, masked_payload)?
+            .wrap_pooled(&self.buffer_pool)?;
+        self.track_packet(wrapped.message.link.rel(), link_to);
+        Ok(wrapped)
+    }
+
+    pub fn unwrap_deniable_packet<'a>(
+        &self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, tagged_packet::ContentUnwrap<F, Link>>> {
+        ensure!(preparsed.check_content_type(DENIABLE_PACKET), "Not a deniable packet.");
+        self.unwrap_tagged_packet(preparsed)
+    }
+
+    /// Get public payload, decrypt masked payload and verify MAC of a deniable message. The
+    /// message must be linked to a message stored with one of `keyed` infos, eg. a keyload the
+    /// user has the session key of or another deniable message, otherwise the MAC could be
+    /// computed from public states and the message would authenticate nothing.
+    pub fn handle_deniable_packet<'a>(
+        &mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+        keyed: &[<LS as LinkStore<F, <Link as HasLink>::Rel>>::Info],
+    ) -> Result<GenericMessage<Link, (Bytes, MaskedBytes<F>)>>
+    where
+        <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: PartialEq,
+    {
+        let preparsed = msg.parse_header()?;
+
+        let unwrapped = self.unwrap_deniable_packet(preparsed)?;
+        let (_spongos, link_info) = self.link_store.borrow().lookup(&unwrapped.pcf.content.link)?;
+        ensure!(
+            keyed.contains(&link_info),
+            "Deniable packet is not linked to a keyload branch the user has the session key of."
+        );
+        self.ensure_write_permission(&unwrapped.pcf.content.link, None)?;
+        self.track_packet(msg.link.rel(), &unwrapped.pcf.content.link);
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
         let body = (content.public_payload, content.masked_payload);
         Ok(GenericMessage::new(msg.link, body))
     }
 
+    /// Read-only recipients of the keyload whose branch `link_to` belongs to: the keyload itself
+    /// or a signed packet in its branch.
+    fn write_gated_recipients(&self, link_to: &<Link as HasLink>::Rel) -> Option<&Vec<ed25519::PublicKey>> {
+        let keyload = self
+            .write_gated
+            .iter()
+            .find(|(packet, _)| packet == link_to)
+            .map_or(link_to, |(_, keyload)| keyload);
+        self.read_only
+            .iter()
+            .find(|(rel, _)| rel == keyload)
+            .map(|(_, read_only)| read_only)
+    }
+
+    /// Reject a packet linked to `link_to` in the branch of a keyload with read-only recipients
+    /// unless it's signed by `sig_pk` of a recipient allowed to publish. Tagged packets, `None`,
+    /// are authenticated with the session key only and can't be attributed to their sender, so
+    /// they are rejected in such branches.
+    pub(crate) fn ensure_write_permission(
+        &self,
+        link_to: &<Link as HasLink>::Rel,
+        sig_pk: Option<&ed25519::PublicKey>,
+    ) -> Result<()> {
+        if let Some(read_only) = self.write_gated_recipients(link_to) {
+            match sig_pk {
+                Some(pk) => ensure!(
+                    !read_only.contains(pk),
+                    "Signer of the packet has read-only permission."
+                ),
+                None => return Err(anyhow!("Branch with read-only recipients accepts signed packets only.")),
+            }
+        }
+        Ok(())
+    }
+
+    /// Track the packet `link` linked to `link_to` in the branch of its keyload, see
+    /// `track_write_gated` and `RateLimiter::track`.
+    fn track_packet(&mut self, link: &<Link as HasLink>::Rel, link_to: &<Link as HasLink>::Rel) {
+        self.track_write_gated(link, link_to);
+        self.rate_limiter.track(link, link_to);
+    }
+
+    /// Track the signed packet `link` linked to `link_to` if it's in the branch of a keyload
+    /// with read-only recipients, so packets linked to it are checked as well.
+    fn track_write_gated(&mut self, link: &<Link as HasLink>::Rel, link_to: &<Link as HasLink>::Rel) {
+        let keyload = self
+            .write_gated
+            .iter()
+            .find(|(packet, _)| packet == link_to)
+            .map_or(link_to, |(_, keyload)| keyload)
+            .clone();
+        if self.read_only.iter().any(|(rel, _)| *rel == keyload) {
+            self.write_gated.push((link.clone(), keyload));
+        }
+    }
+
     pub fn prepare_sequence<'a>(
         &'a mut self,
         link_to: &'a <Link as HasLink>::Rel,
@@ -712,6 +1969,7 @@ where
             .with_content_type(SEQUENCE)?
             .with_payload_length(1)?
             .with_seq_num(SEQ_MESSAGE_NUM);
+        let header = self.with_header_ext(header)?;
 
         let content = sequence::ContentWrap {
             link: link_to,
@@ -724,10 +1982,16 @@ where
     }
 
     pub fn wrap_sequence(&self, ref_link: &<Link as HasLink>::Rel) -> Result<WrappedSequence<F, Link>> {
+        if self.is_linear_branch(ref_link) {
+            // See `advance_linear_branch`.
+            return Ok(WrappedSequence::new());
+        }
         match self.pk_store.get(&self.sig_kp.public) {
             Some(cursor) => {
                 let mut cursor = cursor.clone();
-                if (self.flags & FLAG_BRANCHING_MASK) != 0 {
+                if self.is_counter_sequencing() {
+                    Ok(WrappedSequence::new().with_cursor(cursor))
+                } else if (self.flags & FLAG_BRANCHING_MASK) != 0 {
                     let msg_link = self
                         .link_gen
                         .link_from(&self.sig_kp.public, Cursor::new_at(&cursor.link, 0, SEQ_MESSAGE_NUM));
@@ -735,6 +1999,7 @@ where
                         .with_content_type(SEQUENCE)?
                         .with_payload_length(1)?
                         .with_seq_num(SEQ_MESSAGE_NUM);
+                    let header = self.with_header_ext(header)?;
 
                     let content = sequence::ContentWrap::<Link> {
                         link: &cursor.link,
@@ -745,7 +2010,7 @@ where
 
                     let wrapped = {
                         let prepared = PreparedMessage::new(self.link_store.borrow(), header, content);
-                        prepared.wrap()?
+                        prepared.wrap_pooled(&self.buffer_pool)?
                     };
 
                     Ok(WrappedSequence::new().with_cursor(cursor).with_wrapped(wrapped))
@@ -773,6 +2038,11 @@ where
                 self.pk_store.insert(self.sig_kp.public.clone(), cursor);
                 Ok(Some(link))
             }
+            None if self.is_counter_sequencing() => {
+                cursor.next_seq();
+                self.pk_store.insert(self.sig_kp.public.clone(), cursor);
+                Ok(None)
+            }
             None => {
                 self.store_state_for_all(cursor.link, cursor.seq_no);
                 Ok(None)
@@ -820,30 +2090,454 @@ where
     // }
     // }
 
-    pub fn unwrap_sequence<'a>(
-        &self,
-        preparsed: PreparsedMessage<'a, F, Link>,
-    ) -> Result<UnwrappedMessage<F, Link, sequence::ContentUnwrap<Link>>> {
-        self.ensure_appinst(&preparsed)?;
-        let content = sequence::ContentUnwrap::default();
-        preparsed.unwrap(&*self.link_store.borrow(), content)
+    pub fn unwrap_sequence<'a>(
+        &self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, sequence::ContentUnwrap<Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        let mut content = sequence::ContentUnwrap::default();
+        content.link = self.default_rel();
+        content.ref_link = self.default_rel();
+        preparsed.unwrap(&*self.link_store.borrow(), content)
+    }
+
+    // Fetch unwrapped sequence message to fetch referenced message
+    pub fn handle_sequence<'a>(
+        &mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, sequence::ContentUnwrap<Link>>> {
+        let preparsed = msg.parse_header()?;
+        let content = self
+            .unwrap_sequence(preparsed)?
+            .commit(self.link_store.borrow_mut(), info)?;
+        Ok(GenericMessage::new(msg.link, content))
+    }
+
+    pub fn is_multi_branching(&self) -> bool {
+        (self.flags & FLAG_BRANCHING_MASK) != 0
+    }
+
+    /// Select counter sequencing: identifiers of messages of a publisher are derived from the
+    /// channel address and the publisher's message counter, so readers find the next message
+    /// of each publisher without sequence messages. Must be set before the channel is announced.
+    pub fn with_counter_sequencing(mut self, counter: bool) -> Self {
+        if counter {
+            self.flags |= FLAG_COUNTER_SEQUENCING_MASK;
+        } else {
+            self.flags &= !FLAG_COUNTER_SEQUENCING_MASK;
+        }
+        self
+    }
+
+    pub fn is_counter_sequencing(&self) -> bool {
+        (self.flags & FLAG_COUNTER_SEQUENCING_MASK) != 0
+    }
+
+    /// Select hybrid key encapsulation: session keys in keyloads are encapsulated for both
+    /// X25519 and ML-KEM keys of public key recipients, so keyloads recorded now can't be
+    /// decrypted even if X25519 gets broken. Keyloads grow by 1091 bytes per public key
+    /// recipient and subscribers publish their ML-KEM public keys in `Subscribe` messages.
+    /// Must be set before the channel is announced.
+    #[cfg(feature = "pq-kem")]
+    pub fn with_hybrid_kem(mut self, hybrid_kem: bool) -> Self {
+        if hybrid_kem {
+            self.flags |= FLAG_HYBRID_KEM_MASK;
+        } else {
+            self.flags &= !FLAG_HYBRID_KEM_MASK;
+        }
+        self.init_kem();
+        self
+    }
+
+    pub fn is_hybrid_kem(&self) -> bool {
+        (self.flags & FLAG_HYBRID_KEM_MASK) != 0
+    }
+
+    /// Select nonce-misuse-resistant masking: masked payloads of packets are preceded by a
+    /// synthetic IV derived from the session key and the payload, see `siv`. Protects masked
+    /// payloads when spongos states get reused, e.g. by devices with weak RNGs, at the cost of
+    /// 32 bytes per packet. Must be set before the channel is announced.
+    pub fn with_siv_masking(mut self, siv: bool) -> Self {
+        if siv {
+            self.flags |= FLAG_SIV_MASK;
+        } else {
+            self.flags &= !FLAG_SIV_MASK;
+        }
+        self
+    }
+
+    pub fn is_siv_masking(&self) -> bool {
+        (self.flags & FLAG_SIV_MASK) != 0
+    }
+
+    /// Derive own ML-KEM key pair from the Ed25519 secret key if the channel uses hybrid key
+    /// encapsulation.
+    #[cfg(feature = "pq-kem")]
+    fn init_kem(&mut self) {
+        let own_pk = self.sig_kp.public.clone();
+        self.kem_pks.retain(|(pk, _)| *pk != own_pk);
+        self.kem_kp = None;
+        if self.is_hybrid_kem() {
+            let kem_kp = kyber::Keypair::from_seed(self.sig_kp.secret.as_bytes());
+            self.kem_pks.push((own_pk, kem_kp.public.clone()));
+            self.kem_kp = Some(kem_kp);
+        }
+    }
+
+    /// ML-KEM public keys of recipients exported with user state.
+    fn kem_pks_bytes(&self) -> Vec<(ed25519::PublicKey, Bytes)> {
+        #[cfg(feature = "pq-kem")]
+        {
+            self.kem_pks
+                .iter()
+                .map(|(pk, kem_pk)| (pk.clone(), Bytes(kem_pk.as_bytes().to_vec())))
+                .collect()
+        }
+        #[cfg(not(feature = "pq-kem"))]
+        {
+            Vec::new()
+        }
+    }
+
+    /// Restore ML-KEM public keys of recipients and rederive own key pair, flags must be
+    /// restored first.
+    fn restore_kem(&mut self, kem_pks: Vec<(ed25519::PublicKey, Bytes)>) -> Result<()> {
+        ensure!(
+            cfg!(feature = "pq-kem") || (!self.is_hybrid_kem() && kem_pks.is_empty()),
+            "Hybrid key encapsulation is not supported, enable the `pq-kem` feature."
+        );
+        #[cfg(feature = "pq-kem")]
+        {
+            self.kem_pks = kem_pks
+                .into_iter()
+                .map(|(pk, kem_pk)| {
+                    kyber::PublicKey::from_bytes(&kem_pk.0)
+                        .map(|kem_pk| (pk, kem_pk))
+                        .ok_or_else(|| anyhow!("Bad ML-KEM public key."))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            self.init_kem();
+        }
+        Ok(())
+    }
+
+    /// Link of message `seq_no` of publisher `pk` linked to `link_to`. With counter sequencing
+    /// the link doesn't depend on `link_to`, in linear branches it follows the latest message
+    /// of the branch.
+    fn derive_msg_link(&self, pk: &ed25519::PublicKey, link_to: &<Link as HasLink>::Rel, seq_no: u32) -> Link {
+        if let Some(cursor) = self.linear_branch(link_to) {
+            return self.link_gen.link_from(pk, Cursor::new_at(&cursor.link, 0, seq_no));
+        }
+        match self.appinst {
+            Some(ref appinst) if self.is_counter_sequencing() => {
+                self.link_gen.link_from(pk, Cursor::new_at(appinst.rel(), 0, seq_no))
+            }
+            _ => self.link_gen.link_from(pk, Cursor::new_at(link_to, 0, seq_no)),
+        }
+    }
+
+    /// Own sequence number of the next message linked to `link_to`.
+    fn next_seq_no(&self, link_to: &<Link as HasLink>::Rel) -> Option<u32> {
+        match self.linear_branch(link_to) {
+            Some(cursor) => self.get_seq_no().map(|_| cursor.seq_no),
+            None => self.get_seq_no(),
+        }
+    }
+
+    /// Set branch policy declared in subsequent keyloads.
+    pub fn set_branch_policy(&mut self, policy: BranchPolicy) {
+        self.branch_policy = policy;
+    }
+
+    /// Branch policy of own keyloads.
+    pub fn branch_policy(&self) -> BranchPolicy {
+        self.branch_policy
+    }
+
+    /// Track the branch of keyload `keyload` if it's linear. Messages of linear branches are
+    /// linked to the keyload; only multi-branch channels track them, other channels are
+    /// linear already.
+    pub fn register_branch(&mut self, keyload: &<Link as HasLink>::Rel, policy: BranchPolicy) {
+        if policy == BranchPolicy::Linear
+            && self.is_multi_branching()
+            && !self.is_counter_sequencing()
+            && self.linear_branch(keyload).is_none()
+        {
+            // Start at state 2 since 0 and 1 are reserved states
+            self.linear_branches
+                .push((keyload.clone(), Cursor::new_at(keyload.clone(), 0, 2)));
+        }
+    }
+
+    fn linear_branch(&self, keyload: &<Link as HasLink>::Rel) -> Option<&Cursor<<Link as HasLink>::Rel>> {
+        self.linear_branches
+            .iter()
+            .find(|(k, _)| k == keyload)
+            .map(|(_, cursor)| cursor)
+    }
+
+    /// Messages linked to `link_to` belong to a linear branch.
+    pub fn is_linear_branch(&self, link_to: &<Link as HasLink>::Rel) -> bool {
+        self.linear_branch(link_to).is_some()
+    }
+
+    /// Keyload of the linear branch whose next message of publisher `pk` is at `link`.
+    pub fn linear_branch_of(&self, pk: &ed25519::PublicKey, link: &Link) -> Option<<Link as HasLink>::Rel> {
+        self.linear_branches
+            .iter()
+            .find(|(_, cursor)| {
+                self.link_gen
+                    .link_from(pk, Cursor::new_at(&cursor.link, 0, cursor.seq_no))
+                    .rel()
+                    == link.rel()
+            })
+            .map(|(keyload, _)| keyload.clone())
+    }
+
+    /// Advance the linear branch of keyload `keyload` past its message at `link`.
+    pub fn advance_linear_branch(&mut self, keyload: &<Link as HasLink>::Rel, link: <Link as HasLink>::Rel) {
+        if let Some((_, cursor)) = self.linear_branches.iter_mut().find(|(k, _)| k == keyload) {
+            cursor.link = link;
+            cursor.next_seq();
+        }
+    }
+
+    /// Set rate policy declared to publishers in subsequent keyloads.
+    pub fn set_rate_policy(&mut self, policy: RatePolicy) {
+        self.rate_limiter.policy = policy;
+    }
+
+    /// Rate policy declared in own keyloads.
+    pub fn rate_policy(&self) -> &RatePolicy {
+        &self.rate_limiter.policy
+    }
+
+    /// Limit messages in the branch of keyload `keyload` to the rate `policy` declared in it.
+    pub fn register_rate_policy(&mut self, keyload: &<Link as HasLink>::Rel, policy: RatePolicy) {
+        self.rate_limiter.register(keyload, policy);
+    }
+
+    /// Rate policy declared in keyload `keyload`, `None` if messages of its branch are not limited.
+    pub fn branch_rate_policy(&self, keyload: &<Link as HasLink>::Rel) -> Option<&RatePolicy> {
+        self.rate_limiter.branch_policy(keyload)
+    }
+
+    /// Select whether messages exceeding the rate policy are flagged or dropped.
+    pub fn set_rate_action(&mut self, action: RateAction) {
+        self.rate_limiter.action = action;
+    }
+
+    /// Account the handled packet `link` of publisher `pk` sent at `timestamp` in its branch, see
+    /// `RateLimiter::record`.
+    pub fn check_rate(
+        &mut self,
+        pk: &ed25519::PublicKey,
+        link: &<Link as HasLink>::Rel,
+        timestamp: u64,
+    ) -> Result<bool> {
+        self.rate_limiter.record(pk, link, timestamp)
+    }
+
+    /// Publishers which exceeded the rate policy.
+    pub fn flagged_publishers(&self) -> &[ed25519::PublicKey] {
+        self.rate_limiter.flagged()
+    }
+
+    /// Give the buffer of a sent message back for wrapping subsequent messages.
+    pub fn recycle(&self, msg: BinaryMessage<F, Link>) {
+        self.buffer_pool.give(msg.body.bytes);
+    }
+
+    /// Keep masked payloads of subsequently unwrapped signed and tagged packets encrypted
+    /// until accessed, see `MaskedBytes`.
+    pub fn set_defer_masked_payloads(&mut self, defer: bool) {
+        self.defer_masked_payloads = defer;
+    }
+
+    /// Cap the number of keyloads whose spongos state is cached, `0` disables caching.
+    pub fn set_session_cache_capacity(&mut self, capacity: usize) {
+        self.session_cache.set_capacity(capacity);
+    }
+
+    /// Forget cached keyloads, they are unwrapped again when handled next time.
+    pub fn clear_session_cache(&mut self) {
+        self.session_cache.clear();
+    }
+
+    /// Number of keyloads with cached spongos state.
+    pub fn cached_sessions(&self) -> usize {
+        self.session_cache.len()
+    }
+
+    /// Drop spongos states of messages stored before the checkpoint `link`. The announcement
+    /// and delegated branch roots are kept, new messages linked to them or to messages from
+    /// the checkpoint on can still be processed. Returns the number of dropped messages.
+    pub fn prune_before(&mut self, link: &<Link as HasLink>::Rel) -> Result<usize> {
+        let mut keep: Vec<<Link as HasLink>::Rel> = self.delegations.iter().map(|(rel, _)| rel.clone()).collect();
+        if let Some(appinst) = &self.appinst {
+            keep.push(appinst.rel().clone());
+        }
+        let pruned = self.link_store.borrow_mut().prune_before(link, &keep)?;
+
+        let link_store = self.link_store.borrow();
+        self.read_only.retain(|(rel, _)| link_store.lookup(rel).is_ok());
+        self.write_gated.retain(|(rel, _)| link_store.lookup(rel).is_ok());
+        self.rate_limiter.retain(|rel| link_store.lookup(rel).is_ok());
+        self.session_cache.clear();
+        Ok(pruned)
     }
 
-    // Fetch unwrapped sequence message to fetch referenced message
-    pub fn handle_sequence<'a>(
+    /// Approximate memory used by the user state.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        use core::mem::size_of;
+        type Rel<Link> = <Link as HasLink>::Rel;
+
+        let links = self.link_store.borrow().iter().len();
+        let cursors = self.pk_store.iter().len();
+        let psks = self.psk_store.iter().len();
+        let read_only: usize = self.read_only.iter().map(|(_, pks)| pks.len()).sum();
+        MemoryFootprint {
+            links,
+            link_store: links * size_of::<(Rel<Link>, (Inner<F>, <LS as LinkStore<F, Rel<Link>>>::Info))>(),
+            cursors,
+            pk_store: cursors * size_of::<(ed25519::PublicKey, x25519::PublicKey, Cursor<Rel<Link>>)>(),
+            psks,
+            psk_store: psks * size_of::<(psk::PskId, psk::Psk)>(),
+            session_cache: self.session_cache.footprint(),
+            buffer_pool: self.buffer_pool.footprint(),
+            tracking: self.delegations.len() * size_of::<(Rel<Link>, ed25519::PublicKey)>()
+                + self.read_only.len() * size_of::<(Rel<Link>, Vec<ed25519::PublicKey>)>()
+                + read_only * size_of::<ed25519::PublicKey>()
+                + self.write_gated.len() * size_of::<(Rel<Link>, Rel<Link>)>()
+                + self.verified_packets.len()
+                    * size_of::<(Link, UnwrappedMessage<F, Link, signed_packet::ContentUnwrap<F, Link>>)>()
+                + self.rate_limiter.footprint()
+                + self.sequence_guard.footprint(),
+        }
+    }
+
+    /// Select handling of out-of-order, missing and replayed messages.
+    pub fn set_sequence_policy(&mut self, policy: SequencePolicy) {
+        self.sequence_guard.policy = policy;
+    }
+
+    pub fn sequence_policy(&self) -> SequencePolicy {
+        self.sequence_guard.policy
+    }
+
+    /// Account message `seq_num` of publisher `pk`, see `SequenceGuard::check`. Publishers
+    /// of single-branch channels share sequence numbers, so gaps are expected there.
+    pub fn check_sequence(
         &mut self,
-        msg: BinaryMessage<F, Link>,
-        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
-    ) -> Result<GenericMessage<Link, sequence::ContentUnwrap<Link>>> {
+        pk: &ed25519::PublicKey,
+        seq_num: u64,
+    ) -> (Option<SequenceViolation>, bool) {
+        let detect_gaps = self.is_multi_branching() || self.is_counter_sequencing();
+        self.sequence_guard.check(pk, seq_num, detect_gaps)
+    }
+
+    /// User created the channel it is registered to, as opposed to having processed
+    /// an announcement of somebody else's channel.
+    pub fn is_author(&self) -> bool {
+        self.appinst.is_some()
+            && self
+                .author_sig_pk
+                .as_ref()
+                .map_or(true, |author_sig_pk| *author_sig_pk == self.sig_kp.public)
+    }
+
+    /// Unwrap a message again, without updating the user state, and report how it is
+    /// authenticated and which message it is linked to. Fails if the signature or MAC
+    /// doesn't verify or the linked message is unknown.
+    pub fn verify_message(&self, msg: BinaryMessage<F, Link>) -> Result<LineageStep<Link>> {
+        let link = msg.link.clone();
         let preparsed = msg.parse_header()?;
-        let content = self
-            .unwrap_sequence(preparsed)?
-            .commit(self.link_store.borrow_mut(), info)?;
-        Ok(GenericMessage::new(msg.link, content))
+        let content_type = preparsed.content_type();
+        let (link_to, authentication) = match content_type {
+            ANNOUNCE => {
+                let content = self.unwrap_announcement(preparsed)?.pcf.content;
+                (None, Authentication::Signature(content.sig_pk))
+            }
+            KEYLOAD | ANONYMOUS_KEYLOAD => {
+                let content = self.unwrap_keyload(preparsed)?.pcf.content;
+                let signer = content
+                    .delegates
+                    .iter()
+                    .find(|(branch, _)| branch == &content.link)
+                    .map_or(content.sig_pk, |(_, delegate_pk)| delegate_pk)
+                    .clone();
+                (Some(content.link), Authentication::Signature(signer))
+            }
+            SIGNED_PACKET => {
+                let content = self.unwrap_signed_packet(preparsed)?.pcf.content;
+                (Some(content.link), Authentication::Signature(content.sig_pk))
+            }
+            PRIVATE_PACKET => {
+                let content = self.unwrap_private_packet(preparsed)?.pcf.content;
+                (Some(content.link), Authentication::Signature(content.sig_pk))
+            }
+            TAGGED_PACKET | DENIABLE_PACKET => {
+                let content = self.unwrap_tagged_packet(preparsed)?.pcf.content;
+                (Some(content.link), Authentication::Mac)
+            }
+            BRANCH_DELEGATION => {
+                let content = self.unwrap_branch_delegation(preparsed)?.pcf.content;
+                (Some(content.link), Authentication::Signature(content.sig_pk))
+            }
+            CHECKPOINT => {
+                let content = self.unwrap_checkpoint(preparsed)?.pcf.content;
+                (Some(content.link), Authentication::Signature(content.sig_pk))
+            }
+            SUBSCRIBE => {
+                let content = self.unwrap_subscribe(preparsed)?.pcf.content;
+                (Some(content.link), Authentication::Linked)
+            }
+            SEQUENCE => {
+                let content = self.unwrap_sequence(preparsed)?.pcf.content;
+                (Some(content.link), Authentication::Linked)
+            }
+            unknown_content => return Err(anyhow!("Not a recognised message type: {}", unknown_content)),
+        };
+        Ok(LineageStep {
+            link,
+            content_type,
+            link_to,
+            authentication,
+        })
     }
 
-    pub fn is_multi_branching(&self) -> bool {
-        (self.flags & FLAG_BRANCHING_MASK) != 0
+    /// Check header integrity of a message and, if the state of the linked message is known,
+    /// its signature or MAC without updating the user state. Returns `None` if the message
+    /// is linked to a message the user could not unwrap, eg. a keyload it's not a recipient
+    /// of.
+    ///
+    /// Signatures can't be checked without the session key: they sign a hash squeezed from
+    /// the spongos state after absorbing the linked message, the session key and the masked
+    /// payload, not the ciphertext. There is no signature over the ciphertext in the message
+    /// format, so forged messages in branches the user can't read can't be told apart from
+    /// genuine ones, `None` means the message is unverified rather than valid.
+    pub fn verify_binary(&self, msg: BinaryMessage<F, Link>) -> Result<Option<Authentication>> {
+        let link_to = {
+            let preparsed = msg.parse_header()?;
+            self.ensure_appinst(&preparsed)?;
+            match preparsed.content_type() {
+                ANNOUNCE => None,
+                KEYLOAD | SEQUENCE | SIGNED_PACKET | TAGGED_PACKET | SUBSCRIBE | PRIVATE_PACKET | BRANCH_DELEGATION
+                | CHECKPOINT | ANONYMOUS_KEYLOAD | DENIABLE_PACKET => {
+                    let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
+                    let linked_to = preparsed.unwrap(&store, LinkedTo::<Link>(self.default_rel()))?;
+                    Some(linked_to.pcf.content.0)
+                }
+                unknown_content => return Err(anyhow!("Not a recognised message type: {}", unknown_content)),
+            }
+        };
+        if let Some(link_to) = link_to {
+            if self.link_store.borrow().lookup(&link_to).is_err() {
+                return Ok(None);
+            }
+        }
+        self.verify_message(msg).map(|step| Some(step.authentication))
     }
 
     // TODO: own seq_no should be stored outside of pk_store to avoid lookup and Option
@@ -851,6 +2545,14 @@ where
         self.pk_store.get(&self.sig_kp.public).map(|cursor| cursor.seq_no)
     }
 
+    /// Default relative link of the channel's width, links are read into it when unwrapping.
+    fn default_rel(&self) -> <Link as HasLink>::Rel {
+        match self.appinst {
+            Some(ref appinst) => Link::default_rel_with_width(Link::rel_width(appinst.rel())),
+            None => <Link as HasLink>::Rel::default(),
+        }
+    }
+
     pub fn ensure_appinst<'a>(&self, preparsed: &PreparsedMessage<'a, F, Link>) -> Result<()> {
         ensure!(self.appinst.is_some(), "No channel registered.");
         ensure!(
@@ -889,13 +2591,118 @@ where
     pub fn gen_next_msg_ids(&self, branching: bool) -> Vec<(ed25519::PublicKey, Cursor<Link>)> {
         let mut ids = Vec::new();
 
+        if self.is_counter_sequencing() {
+            for (pk, cursor) in self.pk_store.iter() {
+                let msg_id = self.derive_msg_link(pk, &cursor.link, cursor.seq_no);
+                ids.push((pk.clone(), Cursor::new_at(msg_id, 0, cursor.seq_no)));
+            }
+            return ids;
+        }
+
         // TODO: Do the same for self.sig_kp.public
         for pk_info in self.pk_store.iter() {
             Self::gen_next_msg_id(&mut ids, &self.link_gen, pk_info, branching);
         }
+        self.gen_linear_branch_ids(&mut ids, &self.linear_branches);
+        ids
+    }
+
+    /// Next message ids of each publisher in linear branches `branches`.
+    fn gen_linear_branch_ids(
+        &self,
+        ids: &mut Vec<(ed25519::PublicKey, Cursor<Link>)>,
+        branches: &[(<Link as HasLink>::Rel, Cursor<<Link as HasLink>::Rel>)],
+    ) {
+        for (_, cursor) in branches {
+            for (pk, _) in self.pk_store.iter() {
+                let msg_id = self.link_gen.link_from(pk, Cursor::new_at(&cursor.link, 0, cursor.seq_no));
+                ids.push((pk.clone(), Cursor::new_at(msg_id, 0, cursor.seq_no)));
+            }
+        }
+    }
+
+    /// Message ids `gen_next_msg_ids` returns once the messages `found` among the current
+    /// ones are handled, assuming handling succeeds and doesn't introduce new publishers.
+    /// Allows fetching the next messages before the current ones are unwrapped.
+    pub fn predict_next_msg_ids(
+        &self,
+        found: &[(ed25519::PublicKey, Cursor<Link>)],
+    ) -> Vec<(ed25519::PublicKey, Cursor<Link>)> {
+        let branching = self.is_multi_branching();
+        let mut cursors: Vec<(ed25519::PublicKey, Cursor<<Link as HasLink>::Rel>)> = self
+            .pk_store
+            .iter()
+            .into_iter()
+            .map(|(pk, cursor)| (pk.clone(), cursor.clone()))
+            .collect();
+        if self.is_counter_sequencing() {
+            // See `advance_counter`.
+            let mut ids = Vec::new();
+            for (pk, cursor) in cursors.iter_mut() {
+                if let Some((_, found)) = found.iter().find(|(p, _)| p == pk) {
+                    cursor.seq_no = core::cmp::max(cursor.seq_no, found.seq_no + 1);
+                }
+                let msg_id = self.derive_msg_link(pk, &cursor.link, cursor.seq_no);
+                ids.push((pk.clone(), Cursor::new_at(msg_id, 0, cursor.seq_no)));
+            }
+            return ids;
+        }
+
+        let mut branches = self.linear_branches.clone();
+        for (pk, found) in found {
+            let link = found.link.rel().clone();
+            let linear = branches.iter_mut().find(|(_, cursor)| {
+                self.link_gen
+                    .link_from(pk, Cursor::new_at(&cursor.link, 0, cursor.seq_no))
+                    .rel()
+                    == &link
+            });
+            if let Some((_, cursor)) = linear {
+                // See `advance_linear_branch`.
+                cursor.link = link;
+                cursor.next_seq();
+            } else if branching {
+                // See `store_state`.
+                if let Some((_, cursor)) = cursors.iter_mut().find(|(p, _)| p == pk) {
+                    cursor.link = link;
+                    cursor.next_seq();
+                }
+            } else {
+                // See `store_state_for_all`.
+                if !cursors.iter().any(|(p, _)| *p == self.sig_kp.public) {
+                    cursors.push((self.sig_kp.public.clone(), Cursor::new_at(link.clone(), 0, found.seq_no + 1)));
+                }
+                for (_, cursor) in cursors.iter_mut() {
+                    cursor.link = link.clone();
+                    cursor.seq_no = found.seq_no + 1;
+                }
+            }
+        }
+
+        let mut ids = Vec::new();
+        for (pk, cursor) in cursors.iter() {
+            Self::gen_next_msg_id(&mut ids, &self.link_gen, (pk, cursor), branching);
+        }
+        self.gen_linear_branch_ids(&mut ids, &branches);
         ids
     }
 
+    /// Current sequencing state of each known publisher: the latest link and cursor position.
+    pub fn fetch_state(&self) -> Result<Vec<(ed25519::PublicKey, Cursor<Link>)>> {
+        match self.appinst {
+            Some(ref appinst) => Ok(self
+                .pk_store
+                .iter()
+                .into_iter()
+                .map(|(pk, cursor)| {
+                    let link = Link::from_base_rel(appinst.base(), &cursor.link);
+                    (pk.clone(), Cursor::new_at(link, cursor.branch_no, cursor.seq_no))
+                })
+                .collect()),
+            None => Err(anyhow!("No channel registered")),
+        }
+    }
+
     pub fn store_state(&mut self, pk: ed25519::PublicKey, link: <Link as HasLink>::Rel) {
         let mut cursor = self.pk_store.get(&pk).unwrap().clone();
         cursor.link = link;
@@ -903,6 +2710,15 @@ where
         self.pk_store.insert(pk, cursor);
     }
 
+    /// Advance the message counter of publisher `pk` past message `seq_no`, see `with_counter_sequencing`.
+    pub fn advance_counter(&mut self, pk: &ed25519::PublicKey, seq_no: u32) {
+        if let Some(cursor) = self.pk_store.get_mut(pk) {
+            if cursor.seq_no <= seq_no {
+                cursor.seq_no = seq_no + 1;
+            }
+        }
+    }
+
     pub fn store_state_for_all(&mut self, link: <Link as HasLink>::Rel, seq_no: u32) {
         self.pk_store
             .insert(self.sig_kp.public.clone(), Cursor::new_at(link.clone(), 0, seq_no + 1));
@@ -913,7 +2729,7 @@ where
     }
 }
 
-impl<F, Link, LG, LS, PKS, PSKS> ContentSizeof<F> for User<F, Link, LG, LS, PKS, PSKS>
+impl<F, Link, LG, LS, PKS, PSKS> User<F, Link, LG, LS, PKS, PSKS>
 where
     F: PRP,
     Link: HasLink + AbsorbExternalFallback<F> + AbsorbFallback<F>,
@@ -925,7 +2741,13 @@ where
     PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>>,
     PSKS: PresharedKeyStore,
 {
-    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+    /// Size of the state serialized with groups of fields up to `fields`.
+    fn sizeof_state<'c>(
+        &self,
+        ctx: &'c mut sizeof::Context<F>,
+        fields: StateFields,
+        ext: &Bytes,
+    ) -> Result<&'c mut sizeof::Context<F>> {
         ctx
             .mask(<&NBytes::<U32>>::from(&self.sig_kp.secret.as_bytes()[..]))?
             .absorb(Uint8(self.flags))?
@@ -980,7 +2802,97 @@ where
                 ;
                 Ok(ctx)
             })?
+        ;
+
+        if fields >= StateFields::Delegations {
+            ctx
+                .absorb(Size(self.delegations.len()))?
+                .repeated(self.delegations.iter(), |ctx, (branch, delegate_pk)| {
+                    ctx
+                        .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(branch))?
+                        .absorb(delegate_pk)?
+                    ;
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        if fields >= StateFields::ReadOnly {
+            ctx
+                .absorb(Size(self.read_only.len()))?
+                .repeated(self.read_only.iter(), |ctx, (keyload, pks)| {
+                    ctx
+                        .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(keyload))?
+                        .absorb(Size(pks.len()))?
+                        .repeated(pks.iter(), |ctx, pk| ctx.absorb(pk))?
+                    ;
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        if fields >= StateFields::LinearBranches {
+            ctx
+                .absorb(Size(self.linear_branches.len()))?
+                .repeated(self.linear_branches.iter(), |ctx, (keyload, cursor)| {
+                    ctx
+                        .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(keyload))?
+                        .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(&cursor.link))?
+                        .absorb(Uint32(cursor.seq_no))?
+                    ;
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        if fields >= StateFields::Mss {
+            let (mss_height, mss_next) = self.mss_state();
+            let author_mss_pk = self.author_mss_pk_bytes();
+            let oneof_author_mss_pk = Uint8(if author_mss_pk.is_some() { 1 } else { 0 });
+            ctx
+                .absorb(mss_height)?
+                .absorb(mss_next)?
+                .absorb(&oneof_author_mss_pk)?
+            ;
+            if let Some(ref author_mss_pk) = author_mss_pk {
+                ctx.absorb(author_mss_pk)?;
+            }
+        }
+
+        if fields >= StateFields::Kem {
+            let kem_pks = self.kem_pks_bytes();
+            ctx
+                .absorb(Size(kem_pks.len()))?
+                .repeated(kem_pks.iter(), |ctx, (pk, kem_pk)| {
+                    ctx
+                        .absorb(pk)?
+                        .absorb(kem_pk)?
+                    ;
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        if fields >= StateFields::MsgidHash {
+            ctx.absorb(Uint8(self.link_gen.hash_id()))?;
+        }
 
+        if fields >= StateFields::Ext {
+            ctx
+                .absorb(Uint64(self.membership_epoch))?
+                .absorb(Size(self.write_gated.len()))?
+                .repeated(self.write_gated.iter(), |ctx, (packet, keyload)| {
+                    ctx
+                        .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(packet))?
+                        .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(keyload))?
+                    ;
+                    Ok(ctx)
+                })?
+                .mask(ext)?
+            ;
+        }
+
+        ctx
             .commit()?
             .squeeze(Mac(32))?
             ;
@@ -988,23 +2900,24 @@ where
     }
 }
 
-impl<F, Link, Store, LG, LS, PKS, PSKS> ContentWrap<F, Store> for User<F, Link, LG, LS, PKS, PSKS>
+impl<F, Link, LG, LS, PKS, PSKS> User<F, Link, LG, LS, PKS, PSKS>
 where
     F: PRP,
     Link: HasLink + AbsorbExternalFallback<F> + AbsorbFallback<F>,
     <Link as HasLink>::Base: Eq + fmt::Debug,
     <Link as HasLink>::Rel: Eq + fmt::Debug + SkipFallback<F> + AbsorbFallback<F>,
-    Store: LinkStore<F, <Link as HasLink>::Rel>,
     LG: LinkGenerator<Link>,
     LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
     <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: AbsorbFallback<F>,
     PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>>,
     PSKS: PresharedKeyStore,
 {
-    fn wrap<'c, OS: io::OStream>(
+    /// Serialize the state with groups of fields up to `fields`.
+    fn wrap_state<'c, OS: io::OStream>(
         &self,
-        _store: &Store,
         ctx: &'c mut wrap::Context<F, OS>,
+        fields: StateFields,
+        ext: &Bytes,
     ) -> Result<&'c mut wrap::Context<F, OS>> {
         ctx
             .mask(<&NBytes::<U32>>::from(&self.sig_kp.secret.as_bytes()[..]))?
@@ -1060,7 +2973,97 @@ where
                 ;
                 Ok(ctx)
             })?
+        ;
+
+        if fields >= StateFields::Delegations {
+            ctx
+                .absorb(Size(self.delegations.len()))?
+                .repeated(self.delegations.iter(), |ctx, (branch, delegate_pk)| {
+                    ctx
+                        .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(branch))?
+                        .absorb(delegate_pk)?
+                    ;
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        if fields >= StateFields::ReadOnly {
+            ctx
+                .absorb(Size(self.read_only.len()))?
+                .repeated(self.read_only.iter(), |ctx, (keyload, pks)| {
+                    ctx
+                        .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(keyload))?
+                        .absorb(Size(pks.len()))?
+                        .repeated(pks.iter(), |ctx, pk| ctx.absorb(pk))?
+                    ;
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        if fields >= StateFields::LinearBranches {
+            ctx
+                .absorb(Size(self.linear_branches.len()))?
+                .repeated(self.linear_branches.iter(), |ctx, (keyload, cursor)| {
+                    ctx
+                        .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(keyload))?
+                        .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(&cursor.link))?
+                        .absorb(Uint32(cursor.seq_no))?
+                    ;
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        if fields >= StateFields::Mss {
+            let (mss_height, mss_next) = self.mss_state();
+            let author_mss_pk = self.author_mss_pk_bytes();
+            let oneof_author_mss_pk = Uint8(if author_mss_pk.is_some() { 1 } else { 0 });
+            ctx
+                .absorb(mss_height)?
+                .absorb(mss_next)?
+                .absorb(&oneof_author_mss_pk)?
+            ;
+            if let Some(ref author_mss_pk) = author_mss_pk {
+                ctx.absorb(author_mss_pk)?;
+            }
+        }
+
+        if fields >= StateFields::Kem {
+            let kem_pks = self.kem_pks_bytes();
+            ctx
+                .absorb(Size(kem_pks.len()))?
+                .repeated(kem_pks.iter(), |ctx, (pk, kem_pk)| {
+                    ctx
+                        .absorb(pk)?
+                        .absorb(kem_pk)?
+                    ;
+                    Ok(ctx)
+                })?
+            ;
+        }
 
+        if fields >= StateFields::MsgidHash {
+            ctx.absorb(Uint8(self.link_gen.hash_id()))?;
+        }
+
+        if fields >= StateFields::Ext {
+            ctx
+                .absorb(Uint64(self.membership_epoch))?
+                .absorb(Size(self.write_gated.len()))?
+                .repeated(self.write_gated.iter(), |ctx, (packet, keyload)| {
+                    ctx
+                        .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(packet))?
+                        .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(keyload))?
+                    ;
+                    Ok(ctx)
+                })?
+                .mask(ext)?
+            ;
+        }
+
+        ctx
             .commit()?
             .squeeze(Mac(32))?
             ;
@@ -1068,23 +3071,24 @@ where
     }
 }
 
-impl<F, Link, Store, LG, LS, PKS, PSKS> ContentUnwrap<F, Store> for User<F, Link, LG, LS, PKS, PSKS>
+impl<F, Link, LG, LS, PKS, PSKS> User<F, Link, LG, LS, PKS, PSKS>
 where
     F: PRP,
     Link: HasLink + AbsorbExternalFallback<F> + AbsorbFallback<F>,
     <Link as HasLink>::Base: Eq + fmt::Debug,
     <Link as HasLink>::Rel: Eq + fmt::Debug + SkipFallback<F> + AbsorbFallback<F>,
-    Store: LinkStore<F, <Link as HasLink>::Rel>,
     LG: LinkGenerator<Link>,
     LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
     <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: Default + AbsorbFallback<F>,
     PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>> + Default,
     PSKS: PresharedKeyStore + Default,
 {
-    fn unwrap<'c, IS: io::IStream>(
+    /// Deserialize the state with groups of fields up to `fields`, missing groups are empty.
+    fn unwrap_state<'c, IS: io::IStream>(
         &mut self,
-        _store: &Store,
         ctx: &'c mut unwrap::Context<F, IS>,
+        fields: StateFields,
+        ext: &mut Bytes,
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
         let mut sig_sk_bytes = NBytes::<U32>::default();
         let mut flags = Uint8(0);
@@ -1097,6 +3101,7 @@ where
             .absorb(&mut message_encoding)?
             .absorb(&mut uniform_payload_length)?
         ;
+        let link_width = link_width_from_flags(flags.0)?;
 
         let mut oneof_appinst = Uint8(0);
         ctx
@@ -1104,7 +3109,10 @@ where
             .guard(oneof_appinst.0 < 2, "Bad appinst oneof.")?
         ;
         let appinst = if oneof_appinst.0 == 1 {
-            let mut appinst = Link::default();
+            let mut appinst = Link::from_base_rel(
+                &<Link as HasLink>::Base::default(),
+                &Link::default_rel_with_width(link_width),
+            );
             ctx.absorb(<&mut Fallback::<Link>>::from(&mut appinst))?;
             Some(appinst)
         } else {
@@ -1129,7 +3137,7 @@ where
         ctx
             .absorb(&mut repeated_links)?
             .repeated(repeated_links, |ctx| {
-                let mut link = Fallback(<Link as HasLink>::Rel::default());
+                let mut link = Fallback(Link::default_rel_with_width(link_width));
                 let mut s = NBytes::<F::CapacitySize>::default();
                 let mut info = Fallback(<LS as LinkStore<F, <Link as HasLink>::Rel>>::Info::default());
                 ctx
@@ -1165,7 +3173,7 @@ where
             .absorb(&mut repeated_pks)?
             .repeated(repeated_pks, |ctx| {
                 let mut pk = ed25519::PublicKey::default();
-                let mut link = Fallback(<Link as HasLink>::Rel::default());
+                let mut link = Fallback(Link::default_rel_with_width(link_width));
                 let mut branch_no = Uint32(0);
                 let mut seq_no = Uint32(0);
                 ctx
@@ -1177,6 +3185,134 @@ where
                 pk_store.insert(pk, Cursor::new_at(link.0, branch_no.0, seq_no.0));
                 Ok(ctx)
             })?
+        ;
+
+        let mut delegations = Vec::new();
+        if fields >= StateFields::Delegations {
+            let mut repeated_delegations = Size(0);
+            ctx
+                .absorb(&mut repeated_delegations)?
+                .repeated(repeated_delegations, |ctx| {
+                    let mut branch = Fallback(Link::default_rel_with_width(link_width));
+                    let mut delegate_pk = ed25519::PublicKey::default();
+                    ctx
+                        .absorb(&mut branch)?
+                        .absorb(&mut delegate_pk)?
+                    ;
+                    delegations.push((branch.0, delegate_pk));
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        let mut read_only = Vec::new();
+        if fields >= StateFields::ReadOnly {
+            let mut repeated_read_only = Size(0);
+            ctx
+                .absorb(&mut repeated_read_only)?
+                .repeated(repeated_read_only, |ctx| {
+                    let mut keyload = Fallback(Link::default_rel_with_width(link_width));
+                    let mut repeated_pks = Size(0);
+                    let mut pks = Vec::new();
+                    ctx
+                        .absorb(&mut keyload)?
+                        .absorb(&mut repeated_pks)?
+                        .repeated(repeated_pks, |ctx| {
+                            let mut pk = ed25519::PublicKey::default();
+                            ctx.absorb(&mut pk)?;
+                            pks.push(pk);
+                            Ok(ctx)
+                        })?
+                    ;
+                    read_only.push((keyload.0, pks));
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        let mut linear_branches = Vec::new();
+        if fields >= StateFields::LinearBranches {
+            let mut repeated_linear_branches = Size(0);
+            ctx
+                .absorb(&mut repeated_linear_branches)?
+                .repeated(repeated_linear_branches, |ctx| {
+                    let mut keyload = Fallback(Link::default_rel_with_width(link_width));
+                    let mut link = Fallback(Link::default_rel_with_width(link_width));
+                    let mut seq_no = Uint32(0);
+                    ctx
+                        .absorb(&mut keyload)?
+                        .absorb(&mut link)?
+                        .absorb(&mut seq_no)?
+                    ;
+                    linear_branches.push((keyload.0, Cursor::new_at(link.0, 0, seq_no.0)));
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        let mut mss_height = Uint8(0);
+        let mut mss_next = Uint32(0);
+        let mut author_mss_pk = None;
+        if fields >= StateFields::Mss {
+            let mut oneof_author_mss_pk = Uint8(0);
+            ctx
+                .absorb(&mut mss_height)?
+                .absorb(&mut mss_next)?
+                .absorb(&mut oneof_author_mss_pk)?
+                .guard(oneof_author_mss_pk.0 < 2, "Bad author_mss_pk oneof.")?
+            ;
+            if oneof_author_mss_pk.0 == 1 {
+                let mut mss_pk = NBytes::<U64>::default();
+                ctx.absorb(&mut mss_pk)?;
+                author_mss_pk = Some(mss_pk);
+            }
+        }
+
+        let mut kem_pks = Vec::new();
+        if fields >= StateFields::Kem {
+            let mut repeated_kem_pks = Size(0);
+            ctx
+                .absorb(&mut repeated_kem_pks)?
+                .repeated(repeated_kem_pks, |ctx| {
+                    let mut pk = ed25519::PublicKey::default();
+                    let mut kem_pk = Bytes::new();
+                    ctx
+                        .absorb(&mut pk)?
+                        .absorb(&mut kem_pk)?
+                    ;
+                    kem_pks.push((pk, kem_pk));
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        let mut msgid_hash = Uint8(0);
+        if fields >= StateFields::MsgidHash {
+            ctx.absorb(&mut msgid_hash)?;
+        }
+
+        let mut membership_epoch = Uint64(0);
+        let mut write_gated = Vec::new();
+        if fields >= StateFields::Ext {
+            let mut repeated_write_gated = Size(0);
+            ctx
+                .absorb(&mut membership_epoch)?
+                .absorb(&mut repeated_write_gated)?
+                .repeated(repeated_write_gated, |ctx| {
+                    let mut packet = Fallback(Link::default_rel_with_width(link_width));
+                    let mut keyload = Fallback(Link::default_rel_with_width(link_width));
+                    ctx
+                        .absorb(&mut packet)?
+                        .absorb(&mut keyload)?
+                    ;
+                    write_gated.push((packet.0, keyload.0));
+                    Ok(ctx)
+                })?
+                .mask(ext)?
+            ;
+        }
+
+        ctx
             .commit()?
             .squeeze(Mac(32))?
         ;
@@ -1192,17 +3328,86 @@ where
         self.psk_store = psk_store;
         self.pk_store = pk_store;
         self.author_sig_pk = author_sig_pk;
+        self.delegations = delegations;
+        self.read_only = read_only;
+        self.write_gated = write_gated;
+        self.linear_branches = linear_branches;
+        self.restore_mss(mss_height, mss_next, author_mss_pk)?;
         if let Some(ref seed) = appinst {
             self.link_gen.reset(seed.clone());
         }
+        self.link_gen.set_hash_id(msgid_hash.0)?;
+        self.membership_epoch = membership_epoch.0;
         self.appinst = appinst;
         self.flags = flags.0;
+        self.restore_kem(kem_pks)?;
         self.message_encoding = message_encoding.0;
         self.uniform_payload_length = uniform_payload_length.0 as usize;
         Ok(ctx)
     }
 }
 
+impl<F, Link, LG, LS, PKS, PSKS> ContentSizeof<F> for User<F, Link, LG, LS, PKS, PSKS>
+where
+    F: PRP,
+    Link: HasLink + AbsorbExternalFallback<F> + AbsorbFallback<F>,
+    <Link as HasLink>::Base: Eq + fmt::Debug,
+    <Link as HasLink>::Rel: Eq + fmt::Debug + SkipFallback<F> + AbsorbFallback<F>,
+    LG: LinkGenerator<Link>,
+    LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
+    <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: AbsorbFallback<F>,
+    PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>>,
+    PSKS: PresharedKeyStore,
+{
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        self.sizeof_state(ctx, StateFields::LATEST, &Bytes::new())
+    }
+}
+
+impl<F, Link, Store, LG, LS, PKS, PSKS> ContentWrap<F, Store> for User<F, Link, LG, LS, PKS, PSKS>
+where
+    F: PRP,
+    Link: HasLink + AbsorbExternalFallback<F> + AbsorbFallback<F>,
+    <Link as HasLink>::Base: Eq + fmt::Debug,
+    <Link as HasLink>::Rel: Eq + fmt::Debug + SkipFallback<F> + AbsorbFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+    LG: LinkGenerator<Link>,
+    LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
+    <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: AbsorbFallback<F>,
+    PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>>,
+    PSKS: PresharedKeyStore,
+{
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        _store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        self.wrap_state(ctx, StateFields::LATEST, &Bytes::new())
+    }
+}
+
+impl<F, Link, Store, LG, LS, PKS, PSKS> ContentUnwrap<F, Store> for User<F, Link, LG, LS, PKS, PSKS>
+where
+    F: PRP,
+    Link: HasLink + AbsorbExternalFallback<F> + AbsorbFallback<F>,
+    <Link as HasLink>::Base: Eq + fmt::Debug,
+    <Link as HasLink>::Rel: Eq + fmt::Debug + SkipFallback<F> + AbsorbFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+    LG: LinkGenerator<Link>,
+    LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
+    <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: Default + AbsorbFallback<F>,
+    PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>> + Default,
+    PSKS: PresharedKeyStore + Default,
+{
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        _store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        self.unwrap_state(ctx, StateFields::LATEST, &mut Bytes::new())
+    }
+}
+
 impl<F, Link, LG, LS, PKS, PSKS> User<F, Link, LG, LS, PKS, PSKS>
 where
     F: PRP,
@@ -1215,15 +3420,24 @@ where
     PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>>,
     PSKS: PresharedKeyStore,
 {
+    /// Serialize the state and encrypt it with `pwd`, see `state` for the format.
     pub fn export(&self, flag: u8, pwd: &str) -> Result<Vec<u8>> {
-        const VERSION: u8 = 0;
+        self.export_ext(flag, pwd, &[])
+    }
+
+    /// Serialize the state together with `ext`, the opaque state of a wrapper of the user, and
+    /// encrypt them with `pwd`.
+    pub fn export_ext(&self, flag: u8, pwd: &str, ext: &[u8]) -> Result<Vec<u8>> {
+        let ext = Bytes(ext.to_vec());
+        let magic = NBytes::<U4>(STATE_MAGIC.into());
         let buf_size = {
             let mut ctx = sizeof::Context::<F>::new();
             ctx
-                .absorb(Uint8(VERSION))?
+                .absorb(&magic)?
+                .absorb(Uint8(STATE_VERSION))?
                 .absorb(Uint8(flag))?
             ;
-            self.sizeof(&mut ctx)?;
+            self.sizeof_state(&mut ctx, StateFields::LATEST, &ext)?;
             ctx.get_size()
         };
 
@@ -1234,12 +3448,12 @@ where
             let prng = prng::from_seed::<F>("IOTA Streams Channels app", pwd);
             let key = NBytes::<U32>(prng.gen_arr("user export key"));
             ctx
-                .absorb(Uint8(VERSION))?
+                .absorb(&magic)?
+                .absorb(Uint8(STATE_VERSION))?
                 .absorb(Uint8(flag))?
                 .absorb(External(&key))?
             ;
-            let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
-            self.wrap(&store, &mut ctx)?;
+            self.wrap_state(&mut ctx, StateFields::LATEST, &ext)?;
             ensure!(ctx.stream.is_empty(), "OStream has not been exhausted.");
         }
 
@@ -1259,26 +3473,60 @@ where
     PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>> + Default,
     PSKS: PresharedKeyStore + Default,
 {
+    /// Decrypt with `pwd` and deserialize a state exported with `export` by this or an earlier
+    /// release, fails with `StateError` if the state version is not supported.
     pub fn import(bytes: &[u8], flag: u8, pwd: &str) -> Result<Self> {
-        const VERSION: u8 = 0;
+        Self::import_ext(bytes, flag, pwd).map(|(user, _ext)| user)
+    }
 
+    /// Decrypt and deserialize a state exported with `export_ext`, returns the user and the
+    /// opaque state of its wrapper, empty for states exported without one.
+    pub fn import_ext(bytes: &[u8], flag: u8, pwd: &str) -> Result<(Self, Vec<u8>)> {
+        let header = StateHeader::parse(bytes).map_err(anyhow::Error::msg)?;
+        ensure!(header.flag == flag, "Bad user flag");
+
+        // Only the layout the state was exported with passes the MAC check.
+        let (latest, older) = StateFields::of_version(header.version)
+            .split_last()
+            .ok_or_else(|| anyhow!("No state layout of version {}.", header.version))?;
+        for fields in older {
+            if let Ok(imported) = Self::import_fields(bytes, &header, pwd, *fields) {
+                return Ok(imported);
+            }
+        }
+        Self::import_fields(bytes, &header, pwd, *latest)
+    }
+
+    fn import_fields(bytes: &[u8], header: &StateHeader, pwd: &str, fields: StateFields) -> Result<(Self, Vec<u8>)> {
         let mut ctx = unwrap::Context::new(bytes);
         let prng = prng::from_seed::<F>("IOTA Streams Channels app", pwd);
         let key = NBytes::<U32>(prng.gen_arr("user export key"));
-        let mut version = Uint8(0);
-        let mut flag2 = Uint8(0);
+        if header.has_magic() {
+            let mut magic = NBytes::<U4>::default();
+            ctx.absorb(&mut magic)?;
+        }
         ctx
-            .absorb(&mut version)?
-            .guard(version.0 == VERSION, "Bad user version")?
-            .absorb(&mut flag2)?
-            .guard(flag2.0 == flag, "Bad user flag")?
+            .absorb(&mut Uint8(0))?
+            .absorb(&mut Uint8(0))?
             .absorb(External(&key))?
         ;
 
         let mut user = User::default();
-        let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
-        user.unwrap(&store, &mut ctx)?;
+        let mut ext = Bytes::new();
+        user.unwrap_state(&mut ctx, fields, &mut ext)?;
         ensure!(ctx.stream.is_empty(), "IStream has not been exhausted.");
-        Ok(user)
+        Ok((user, ext.0))
+    }
+
+    /// Upgrade a state exported by an earlier release to `STATE_VERSION`, states of the current
+    /// version are returned unchanged. Fails with `StateError` if the state version is not
+    /// supported, the original state should be kept then.
+    pub fn migrate_state(bytes: &[u8], flag: u8, pwd: &str) -> Result<Vec<u8>> {
+        let header = StateHeader::parse(bytes).map_err(anyhow::Error::msg)?;
+        if header.version == STATE_VERSION {
+            return Ok(bytes.to_vec());
+        }
+        let (user, ext) = Self::import_ext(bytes, flag, pwd)?;
+        user.export_ext(flag, pwd, &ext)
     }
 }
@@ -0,0 +1,24 @@
+//! REST and WebSocket gateway to channels, see `iota_streams_app_channels::api::tangle::Gateway`.
+//!
+//! Usage: `streams-gateway [LISTEN_ADDR] [NODE_URL]`, defaults to `127.0.0.1:8080` and a local node.
+
+use core::cell::RefCell;
+use std::{
+    env,
+    rc::Rc,
+    time::Duration,
+};
+
+use iota_streams_app::transport::tangle::client::Client;
+use iota_streams_app_channels::api::tangle::Gateway;
+
+fn main() -> anyhow::Result<()> {
+    let mut args = env::args().skip(1);
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let node = args.next().unwrap_or_else(|| "http://localhost:14265".to_string());
+
+    let transport = Rc::new(RefCell::new(Client::new_from_url(&node)));
+    let mut gateway = Gateway::new(transport);
+    println!("Serving channels of node {} on {}", node, addr);
+    gateway.serve(&addr, Duration::from_millis(500))
+}
@@ -6,6 +6,7 @@
 //! ```ddml
 //! message Announce {
 //!     absorb u8 ed25519pk[32];
+//!     absorb u8 flags;
 //!     commit;
 //!     squeeze external u8 tag[32];
 //!     ed25519(tag) sig;
@@ -16,15 +17,33 @@
 //!
 //! * `ed25519pk` -- channel owner's Ed25519 public key.
 //!
+//! * `flags` -- channel flags: branching mode and width of message links (see `hdf::FLAG_LINK_WIDTH_MASK`).
+//!
+//! * `msgid_hash` -- identifier of the hash function of message links, only present if
+//! `hdf::FLAG_MSGID_HASH_MASK` is set in `flags`: `absorb u8 msgid_hash` follows `flags`.
+//!
 //! * `tag` -- hash-value to be signed.
 //!
 //! * `sig` -- signature of `tag` field produced with the Ed25519 private key corresponding to ed25519pk`.
+//! Channels authored by a threshold group announce the group public key, the signature is
+//! aggregated from signature shares of the group members.
+//!
+//! Announcements of hybrid channels (`pq-sig` feature) are wrapped with a post-quantum version
+//! (see `pq_version`), absorb the author's MSS public key `absorb u8 msspk[64]` after `flags`
+//! and are additionally signed with it (see `signer`).
 //!
 
 use anyhow::Result;
 
-use iota_streams_app::message;
+use iota_streams_app::message::{
+    self,
+    hdf::FLAG_MSGID_HASH_MASK,
+};
+
+use super::signer::Signer;
 use iota_streams_core::sponge::prp::PRP;
+#[cfg(feature = "pq-sig")]
+use iota_streams_core_edsig::signature::mss;
 use iota_streams_core_edsig::{
     key_exchange::x25519,
     signature::ed25519,
@@ -36,27 +55,68 @@ use iota_streams_ddml::{
 };
 
 pub struct ContentWrap<'a, F> {
-    sig_kp: &'a ed25519::Keypair,
+    signer: Signer<'a>,
     flags: Uint8,
+    msgid_hash: Uint8,
     _phantom: core::marker::PhantomData<F>,
 }
 
 impl<'a, F> ContentWrap<'a, F> {
     pub fn new(sig_kp: &'a ed25519::Keypair, flags: u8) -> Self {
         Self {
-            sig_kp,
+            signer: Signer::Keypair(sig_kp),
+            flags: Uint8(flags),
+            msgid_hash: Uint8(0),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Announcement of a channel authored by a threshold group, see `Signer`.
+    pub fn new_threshold(group_pk: &'a ed25519::PublicKey, flags: u8, sig: Option<&'a ed25519::Signature>) -> Self {
+        Self {
+            signer: Signer::threshold(group_pk, sig),
             flags: Uint8(flags),
+            msgid_hash: Uint8(0),
             _phantom: core::marker::PhantomData,
         }
     }
+
+    pub(crate) fn with_signer(signer: Signer<'a>, flags: u8) -> Self {
+        Self {
+            signer,
+            flags: Uint8(flags),
+            msgid_hash: Uint8(0),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Announce the hash function of message links, see `LinkGenerator::hash_id`.
+    pub fn with_msgid_hash(mut self, msgid_hash: u8) -> Self {
+        self.msgid_hash = Uint8(msgid_hash);
+        self
+    }
+
+    /// Hash to be signed by the threshold group, available after wrapping.
+    pub fn tag(&self) -> Option<[u8; 64]> {
+        self.signer.tag()
+    }
 }
 
 impl<'a, F: PRP> message::ContentSizeof<F> for ContentWrap<'a, F>
 {
     fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
-        ctx.absorb(&self.sig_kp.public)?;
-        ctx.absorb(&self.flags)?;
-        ctx.ed25519(self.sig_kp, HashSig)?;
+        ctx.label("pk")?.absorb(self.signer.public_key())?;
+        ctx.label("flags")?.absorb(&self.flags)?;
+        if self.flags.0 & FLAG_MSGID_HASH_MASK != 0 {
+            ctx.label("msgid_hash")?.absorb(&self.msgid_hash)?;
+        }
+        #[cfg(feature = "pq-sig")]
+        {
+            if let Some(mss_pk) = self.signer.mss_public_key() {
+                ctx.label("mss_pk")?.absorb(<&NBytes<U64>>::from(&mss_pk.to_bytes()[..]))?;
+            }
+        }
+        self.signer.sizeof(ctx)?;
         Ok(ctx)
     }
 }
@@ -68,9 +128,18 @@ impl<'a, F: PRP, Store> message::ContentWrap<F, Store> for ContentWrap<'a, F>
         _store: &Store,
         ctx: &'c mut wrap::Context<F, OS>,
     ) -> Result<&'c mut wrap::Context<F, OS>> {
-        ctx.absorb(&self.sig_kp.public)?;
+        ctx.absorb(self.signer.public_key())?;
         ctx.absorb(&self.flags)?;
-        ctx.ed25519(self.sig_kp, HashSig)?;
+        if self.flags.0 & FLAG_MSGID_HASH_MASK != 0 {
+            ctx.absorb(&self.msgid_hash)?;
+        }
+        #[cfg(feature = "pq-sig")]
+        {
+            if let Some(mss_pk) = self.signer.mss_public_key() {
+                ctx.absorb(<&NBytes<U64>>::from(&mss_pk.to_bytes()[..]))?;
+            }
+        }
+        self.signer.wrap(ctx)?;
         Ok(ctx)
     }
 }
@@ -79,9 +148,23 @@ pub struct ContentUnwrap<F> {
     pub(crate) sig_pk: ed25519::PublicKey,
     pub(crate) ke_pk: x25519::PublicKey,
     pub(crate) flags: Uint8,
+    pub(crate) msgid_hash: Uint8,
+    /// Author's MSS public key, unwrapped if set before unwrapping.
+    #[cfg(feature = "pq-sig")]
+    pub(crate) mss_pk: Option<mss::PublicKey>,
     _phantom: core::marker::PhantomData<F>,
 }
 
+impl<F> ContentUnwrap<F> {
+    /// Unwrap the announcement of a hybrid channel.
+    #[cfg(feature = "pq-sig")]
+    pub fn hybrid() -> Self {
+        let mut content = Self::default();
+        content.mss_pk = Some(mss::PublicKey::default());
+        content
+    }
+}
+
 impl<F> Default for ContentUnwrap<F> {
     fn default() -> Self {
         let sig_pk = ed25519::PublicKey::default();
@@ -91,6 +174,9 @@ impl<F> Default for ContentUnwrap<F> {
             sig_pk,
             ke_pk,
             flags,
+            msgid_hash: Uint8(0),
+            #[cfg(feature = "pq-sig")]
+            mss_pk: None,
             _phantom: core::marker::PhantomData,
         }
     }
@@ -108,6 +194,20 @@ where
         ctx.absorb(&mut self.sig_pk)?;
         self.ke_pk = x25519::public_from_ed25519(&self.sig_pk);
         ctx.absorb(&mut self.flags)?;
+        if self.flags.0 & FLAG_MSGID_HASH_MASK != 0 {
+            ctx.absorb(&mut self.msgid_hash)?;
+        }
+        #[cfg(feature = "pq-sig")]
+        {
+            if let Some(ref mut mss_pk) = self.mss_pk {
+                let mut bytes = NBytes::<U64>::default();
+                ctx.absorb(&mut bytes)?;
+                *mss_pk = mss::PublicKey::from_bytes(bytes.as_slice())
+                    .ok_or_else(|| anyhow::anyhow!("Bad MSS public key."))?;
+                ctx.ed25519(&self.sig_pk, HashSig)?;
+                return super::signer::unwrap_mss(ctx, mss_pk);
+            }
+        }
         ctx.ed25519(&self.sig_pk, HashSig)?;
         Ok(ctx)
     }
@@ -0,0 +1,133 @@
+//! `BranchDelegation` message content. The message grants a subscriber the right to
+//! publish keyloads in the branch rooted at this message, ie. keyloads linked to it.
+//! The message can only be signed and published by channel owner, who keeps the right
+//! to publish keyloads in any branch.
+//!
+//! ```ddml
+//! message BranchDelegation {
+//!     join link msgid;
+//!     absorb u8 delegate_pk[32];
+//!     commit;
+//!     squeeze external u8 hash[64];
+//!     ed25519(hash) sig;
+//! }
+//! ```
+//!
+//! # Fields
+//!
+//! * `msgid` -- link to the base message.
+//!
+//! * `delegate_pk` -- Ed25519 public key of the delegate. Keyloads linked to this message
+//! are signed with the corresponding private key instead of the channel owner's one.
+//!
+//! * `hash` -- hash value to be signed.
+//!
+//! * `sig` -- message signature generated with channel owner's private key.
+//!
+
+use anyhow::Result;
+use iota_streams_app::message::{
+    self,
+    HasLink,
+};
+use iota_streams_core::sponge::prp::PRP;
+use iota_streams_core_edsig::signature::ed25519;
+use iota_streams_ddml::{
+    command::*,
+    io,
+    link_store::{
+        EmptyLinkStore,
+        LinkStore,
+    },
+    types::*,
+};
+
+pub struct ContentWrap<'a, F, Link>
+where
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a,
+{
+    pub(crate) link: &'a <Link as HasLink>::Rel,
+    pub(crate) delegate_pk: &'a ed25519::PublicKey,
+    pub(crate) sig_kp: &'a ed25519::Keypair,
+    pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
+}
+
+impl<'a, F, Link> message::ContentSizeof<F> for ContentWrap<'a, F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F>,
+{
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
+        ctx.label("link")?
+            .join(&store, self.link)?
+            .label("delegate_pk")?
+            .absorb(self.delegate_pk)?
+            .label("sig")?
+            .ed25519(self.sig_kp, HashSig)?;
+        Ok(ctx)
+    }
+}
+
+impl<'a, F, Link, Store> message::ContentWrap<F, Store> for ContentWrap<'a, F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+{
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        ctx.join(store, self.link)?
+            .absorb(self.delegate_pk)?
+            .ed25519(self.sig_kp, HashSig)?;
+        Ok(ctx)
+    }
+}
+
+pub struct ContentUnwrap<F, Link: HasLink> {
+    pub(crate) link: <Link as HasLink>::Rel,
+    pub(crate) delegate_pk: ed25519::PublicKey,
+    pub(crate) sig_pk: ed25519::PublicKey,
+    pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
+}
+
+impl<F, Link> ContentUnwrap<F, Link>
+where
+    Link: HasLink,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F>,
+{
+    /// Expect delegation signed by the channel owner with public key `sig_pk`.
+    pub fn new(sig_pk: ed25519::PublicKey) -> Self {
+        Self {
+            link: <<Link as HasLink>::Rel as Default>::default(),
+            delegate_pk: ed25519::PublicKey::default(),
+            sig_pk,
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<F, Link, Store> message::ContentUnwrap<F, Store> for ContentUnwrap<F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+{
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        ctx.join(store, &mut self.link)?
+            .absorb(&mut self.delegate_pk)?
+            .ed25519(&self.sig_pk, HashSig)?;
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,186 @@
+//! `Checkpoint` message content. The message summarizes the sequencing state of the channel,
+//! the latest link and cursor position of each known publisher, so that a new subscriber
+//! can continue from it instead of fetching all messages since the announcement.
+//! The message can only be signed and published by channel owner.
+//!
+//! ```ddml
+//! message Checkpoint {
+//!     join link msgid;
+//!     absorb size n;
+//!     repeated(n) {
+//!         absorb u8 pk[32];
+//!         absorb link cursor_link;
+//!         absorb u32 branch_no;
+//!         absorb u32 seq_no;
+//!     }
+//!     commit;
+//!     squeeze external u8 hash[64];
+//!     ed25519(hash) sig;
+//! }
+//! ```
+//!
+//! # Fields
+//!
+//! * `msgid` -- link to the base message.
+//!
+//! * `pk` -- Ed25519 public key of a publisher.
+//!
+//! * `cursor_link` -- link to the latest message of the publisher.
+//!
+//! * `branch_no` -- branch number of the publisher's cursor.
+//!
+//! * `seq_no` -- next sequence number of the publisher.
+//!
+//! * `hash` -- hash value to be signed.
+//!
+//! * `sig` -- message signature generated with channel owner's private key.
+//!
+
+use anyhow::Result;
+use iota_streams_app::message::{
+    self,
+    Cursor,
+    HasLink,
+};
+use iota_streams_core::{
+    prelude::Vec,
+    sponge::prp::PRP,
+};
+use iota_streams_core_edsig::signature::ed25519;
+use iota_streams_ddml::{
+    command::*,
+    io,
+    link_store::{
+        EmptyLinkStore,
+        LinkStore,
+    },
+    types::*,
+};
+
+/// Maximum number of publishers in a checkpoint. A checkpoint declaring more publishers
+/// is rejected without processing.
+pub const MAX_PUBLISHERS: usize = 1 << 16;
+
+pub struct ContentWrap<'a, F, Link>
+where
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a,
+{
+    pub(crate) link: &'a <Link as HasLink>::Rel,
+    pub(crate) states: Vec<(&'a ed25519::PublicKey, &'a Cursor<<Link as HasLink>::Rel>)>,
+    pub(crate) sig_kp: &'a ed25519::Keypair,
+    pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
+}
+
+impl<'a, F, Link> message::ContentSizeof<F> for ContentWrap<'a, F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F> + AbsorbFallback<F>,
+{
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
+        ctx.label("link")?
+            .join(&store, self.link)?
+            .label("publishers")?
+            .absorb(Size(self.states.len()))?
+            .repeated(Bounded(self.states.iter(), MAX_PUBLISHERS), |ctx, (pk, cursor)| {
+                ctx.label("pk")?
+                    .absorb(*pk)?
+                    .label("cursor_link")?
+                    .absorb(<&Fallback<<Link as HasLink>::Rel>>::from(&cursor.link))?
+                    .label("branch_no")?
+                    .absorb(Uint32(cursor.branch_no))?
+                    .label("seq_no")?
+                    .absorb(Uint32(cursor.seq_no))
+            })?
+            .commit()?
+            .label("sig")?
+            .ed25519(self.sig_kp, HashSig)?;
+        Ok(ctx)
+    }
+}
+
+impl<'a, F, Link, Store> message::ContentWrap<F, Store> for ContentWrap<'a, F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F> + AbsorbFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+{
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        ctx.join(store, self.link)?
+            .absorb(Size(self.states.len()))?
+            .repeated(Bounded(self.states.iter(), MAX_PUBLISHERS), |ctx, (pk, cursor)| {
+                ctx.absorb(*pk)?
+                    .absorb(<&Fallback<<Link as HasLink>::Rel>>::from(&cursor.link))?
+                    .absorb(Uint32(cursor.branch_no))?
+                    .absorb(Uint32(cursor.seq_no))
+            })?
+            .commit()?
+            .ed25519(self.sig_kp, HashSig)?;
+        Ok(ctx)
+    }
+}
+
+pub struct ContentUnwrap<F, Link: HasLink> {
+    pub(crate) link: <Link as HasLink>::Rel,
+    pub(crate) states: Vec<(ed25519::PublicKey, Cursor<<Link as HasLink>::Rel>)>,
+    pub(crate) sig_pk: ed25519::PublicKey,
+    pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
+}
+
+impl<F, Link> ContentUnwrap<F, Link>
+where
+    Link: HasLink,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F>,
+{
+    /// Expect checkpoint signed by the channel owner with public key `sig_pk`.
+    pub fn new(sig_pk: ed25519::PublicKey) -> Self {
+        Self {
+            link: <<Link as HasLink>::Rel as Default>::default(),
+            states: Vec::new(),
+            sig_pk,
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<F, Link, Store> message::ContentUnwrap<F, Store> for ContentUnwrap<F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F> + AbsorbFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+{
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        let mut repeated_states = Size(0);
+        ctx.join(store, &mut self.link)?.absorb(&mut repeated_states)?;
+        // Cursor links have the width of the joined link.
+        let rel = self.link.clone();
+        let states = &mut self.states;
+        ctx.repeated(Bounded(repeated_states, MAX_PUBLISHERS), |ctx| {
+            let mut pk = ed25519::PublicKey::default();
+            let mut link = Fallback(rel.clone());
+            let mut branch_no = Uint32(0);
+            let mut seq_no = Uint32(0);
+            ctx.absorb(&mut pk)?
+                .absorb(&mut link)?
+                .absorb(&mut branch_no)?
+                .absorb(&mut seq_no)?;
+            states.push((pk, Cursor::new_at(link.0, branch_no.0, seq_no.0)));
+            Ok(ctx)
+        })?
+        .commit()?
+        .ed25519(&self.sig_pk, HashSig)?;
+        Ok(ctx)
+    }
+}
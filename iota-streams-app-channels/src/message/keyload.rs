@@ -5,6 +5,9 @@
 //! message Keyload {
 //!     join link msgid;
 //!     absorb u8 nonce[16];
+//!     absorb u32 max_messages;  // Streams 1.1 and later
+//!     absorb u64 period_ms;  // Streams 1.1 and later
+//!     absorb u8 branch_policy;  // Streams 1.1 and later
 //!     skip repeated {
 //!         fork;
 //!         mask u8 id[16];
@@ -15,6 +18,7 @@
 //!     skip repeated {
 //!         fork;
 //!         mask u8 xpk[32];
+//!         absorb u8 permission;  // Streams 1.1 and later
 //!         absorb u8 eph_key[32];
 //!         x25519(eph_key) u8 xkey[32];
 //!         commit;
@@ -30,26 +34,80 @@
 //! * `nonce` -- A nonce to be used with the key encapsulated in the keyload.
 //! A unique nonce allows for session keys to be reused.
 //!
+//! * `max_messages`, `period_ms` -- Rate policy: publishers may send at most `max_messages`
+//! messages within `period_ms` milliseconds, zero means no limit.
+//!
+//! * `branch_policy` -- Sequencing of messages linked to the keyload, see `BranchPolicy`.
+//! Keyloads of Streams 1.0 declare neither a rate nor a branch policy, see `has_keyload_policies`.
+//!
 //! * `id` -- Key (PSK or X25519 public key) identifier.
 //!
 //! * `psk` -- Pre-shared key known to the author and to a legit recipient.
 //!
 //! * `xpk` -- Recipient's X25519 public key.
 //!
+//! * `permission` -- Whether the recipient may publish in the branch of the keyload
+//! (`PERMISSION_READ_WRITE`) or may only read it (`PERMISSION_READ`). Permissions are
+//! public, every recipient learns the read-only ones. Tagged packets can't be attributed to
+//! their sender, a branch with read-only recipients accepts signed packets only. Recipients
+//! of Streams 1.0 keyloads have no declared permission and may publish.
+//!
 //! * `eph_key` -- X25519 random ephemeral key.
 //!
 //! * `xkey` -- X25519 common key.
 //!
 //! * `key` -- Session key; a legit recipient gets it from corresponding fork.
 //!
-//! * `sig` -- Optional signature; allows to authenticate keyload.
+//! * `sig` -- Optional signature; allows to authenticate keyload. Keyloads linked to a
+//! `BranchDelegation` message are signed by the delegate rather than the author.
+//!
+//! Anonymous keyloads (content type `ANONYMOUS_KEYLOAD`) hide recipient identities: public
+//! key recipients are listed without `xpk` and `permission`, all recipients may publish,
+//! and each recipient trial-decrypts the slots until the masked zero `tag` matches:
+//!
+//! ```ddml
+//!     skip repeated {
+//!         fork;
+//!         absorb u8 eph_key[32];
+//!         x25519(eph_key) u8 xkey[32];
+//!         commit;
+//!         mask u8 key[32];
+//!         mask u8 tag[16];
+//!     }
+//! ```
+//!
+//! Channels with `hdf::FLAG_HYBRID_KEM_MASK` set (`pq-kem` feature) additionally encapsulate
+//! a secret with the recipient's ML-KEM public key (see `kyber`) at the start of each public
+//! key slot after `permission`, so the session key stays secret even if X25519 is broken
+//! later on, e.g. for traffic recorded now and attacked with a quantum computer:
+//!
+//! ```ddml
+//!         skip bytes kyber_ct;
+//!         absorb external u8 kyber_key[32];
+//! ```
+//!
+//! * `kyber_ct` -- ML-KEM-768 ciphertext encapsulating `kyber_key` for the recipient.
+//!
+//! * `kyber_key` -- Secret shared with the recipient, absorbed before the X25519 key.
+//!
+//! Each public key slot grows by 1091 bytes, i.e. from 97 to 1188 bytes. PSK slots are
+//! unaffected: symmetric keys are not at risk from quantum adversaries.
+//!
+//! Every key slot forks the spongos state of the keyload prefix, so slots are independent of
+//! each other. The X25519 exchanges with the recipients, the bulk of the wrapping time, are
+//! thus computed ahead of wrapping, in parallel with the `parallel` feature.
 //!
 //! Notes:
 //! 1) Keys identities are not encrypted and may be linked to recipients identities.
 //! 2) Keyload is not authenticated (signed). It can later be implicitly authenticated
 //!     via `SignedPacket`.
 
-use anyhow::Result;
+#[cfg(feature = "pq-kem")]
+use anyhow::anyhow;
+use anyhow::{
+    ensure,
+    Result,
+};
 use iota_streams_app::message::{
     self,
     HasLink,
@@ -59,12 +117,17 @@ use iota_streams_core::{
         Vec,
         typenum::Unsigned as _,
     },
+    prng,
     psk,
     sponge::{
         prp::PRP,
         spongos,
     },
 };
+#[cfg(feature = "pq-kem")]
+use iota_streams_core::prelude::vec;
+#[cfg(feature = "pq-kem")]
+use iota_streams_core_edsig::key_exchange::kyber;
 use iota_streams_core_edsig::{
     key_exchange::x25519,
     signature::ed25519,
@@ -79,16 +142,126 @@ use iota_streams_ddml::{
     types::*,
 };
 
+/// Maximum number of PSK and of public key recipients in a keyload. A keyload declaring
+/// more recipients is rejected without processing.
+pub const MAX_RECIPIENTS: usize = 1 << 16;
+
+/// Recipient may read and publish packets.
+pub const PERMISSION_READ_WRITE: u8 = 0;
+/// Recipient may only read packets.
+pub const PERMISSION_READ: u8 = 1;
+
+/// Size of the masked zero tag recipients of anonymous keyloads recognize their slot by.
+pub type AnonymousTagSize = U16;
+
 pub struct ContentWrap<'a, F, Link: HasLink, Psks, KePks> {
     pub(crate) link: &'a <Link as HasLink>::Rel,
     pub nonce: NBytes<U16>,
     pub key: NBytes<U32>,
+    pub(crate) max_messages: Uint32,
+    pub(crate) period_ms: Uint64,
+    pub(crate) branch_policy: Uint8,
+    /// Rate and branch policies and permissions are declared, see `has_keyload_policies`.
+    pub(crate) policies: bool,
     pub(crate) psks: Psks,
     pub(crate) ke_pks: KePks,
+    /// Recipients with read-only permission, others may also publish.
+    pub(crate) read_only: &'a [ed25519::PublicKey],
+    /// Recipient public keys are not listed, recipients trial-decrypt the key slots.
+    pub(crate) anonymous: bool,
+    /// ML-KEM public keys of recipients if hybrid key encapsulation is used.
+    #[cfg(feature = "pq-kem")]
+    pub(crate) kem_pks: Option<&'a [(ed25519::PublicKey, kyber::PublicKey)]>,
     pub(crate) sig_kp: &'a ed25519::Keypair,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
+impl<'a, F, Link: HasLink, Psks, KePks> ContentWrap<'a, F, Link, Psks, KePks> {
+    fn permission(&self, pk: &ed25519::PublicKey) -> Uint8 {
+        if self.read_only.contains(pk) {
+            Uint8(PERMISSION_READ)
+        } else {
+            Uint8(PERMISSION_READ_WRITE)
+        }
+    }
+}
+
+impl<'a, F, Link: HasLink, Psks, KePks> ContentWrap<'a, F, Link, Psks, KePks>
+where
+    KePks: Clone + ExactSizeIterator<Item = (ed25519::IPk<'a>, x25519::IPk<'a>)>,
+{
+    /// Key exchanges with the public key recipients, in order. Ephemeral secrets are drawn on
+    /// the calling thread, so `prng::with_rng` applies to them.
+    fn key_exchanges(&self) -> Vec<x25519::Exchange> {
+        let recipients: Vec<([u8; 32], x25519::IPk<'a>)> = self
+            .ke_pks
+            .clone()
+            .map(|(_, ke_pk)| {
+                let mut secret = [0_u8; 32];
+                secret.copy_from_slice(&prng::random_key());
+                (secret, ke_pk)
+            })
+            .collect();
+        map_recipients(&recipients, |(secret, ke_pk)| {
+            x25519::Exchange::ephemeral(*secret, ke_pk)
+        })
+    }
+}
+
+/// Map recipients with `f`, in parallel with the `parallel` feature.
+#[cfg(feature = "parallel")]
+fn map_recipients<T: Sync, U: Send>(recipients: &[T], f: impl Fn(&T) -> U + Sync + Send) -> Vec<U> {
+    use rayon::prelude::*;
+    recipients.par_iter().map(f).collect()
+}
+
+/// Map recipients with `f`, in parallel with the `parallel` feature.
+#[cfg(not(feature = "parallel"))]
+fn map_recipients<T, U>(recipients: &[T], f: impl Fn(&T) -> U) -> Vec<U> {
+    recipients.iter().map(f).collect()
+}
+
+impl<'a, F: PRP, Link: HasLink, Psks, KePks> ContentWrap<'a, F, Link, Psks, KePks> {
+    fn sizeof_kem<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        #[cfg(feature = "pq-kem")]
+        {
+            if self.kem_pks.is_some() {
+                let kem_key = NBytes::<U32>::default();
+                return ctx
+                    .skip(&Bytes(vec![0_u8; kyber::CIPHERTEXT_LENGTH]))?
+                    .absorb(External(&kem_key));
+            }
+        }
+        Ok(ctx)
+    }
+
+    /// Encapsulate a fresh secret for recipient `pk` if hybrid key encapsulation is used.
+    #[cfg_attr(not(feature = "pq-kem"), allow(unused_variables))]
+    fn wrap_kem<'c, OS: io::OStream>(
+        &self,
+        ctx: &'c mut wrap::Context<F, OS>,
+        pk: &ed25519::PublicKey,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        #[cfg(feature = "pq-kem")]
+        {
+            if let Some(kem_pks) = self.kem_pks {
+                let kem_pk = kem_pks
+                    .iter()
+                    .find(|(sig_pk, _)| sig_pk == pk)
+                    .map(|(_, kem_pk)| kem_pk)
+                    .ok_or_else(|| anyhow!("ML-KEM public key of recipient not found."))?;
+                let mut random = [0_u8; 32];
+                random.copy_from_slice(&prng::random_key());
+                let (kem_key, kem_ct) = kyber::encapsulate(kem_pk, &random);
+                return ctx
+                    .skip(&Bytes(kem_ct))?
+                    .absorb(External(<&NBytes<U32>>::from(&kem_key[..])));
+            }
+        }
+        Ok(ctx)
+    }
+}
+
 impl<'a, F, Link, Psks, KePks> message::ContentSizeof<F> for ContentWrap<'a, F, Link, Psks, KePks>
 where
     F: 'a + PRP, // weird 'a constraint, but compiler requires it somehow?!
@@ -101,22 +274,52 @@ where
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
         let repeated_psks = Size(self.psks.len());
         let repeated_ke_pks = Size(self.ke_pks.len());
-        ctx.join(&store, self.link)?
-            .absorb(&self.nonce)?
+        ctx.label("link")?
+            .join(&store, self.link)?
+            .label("nonce")?
+            .absorb(&self.nonce)?;
+        if self.policies {
+            ctx.label("max_messages")?
+                .absorb(self.max_messages)?
+                .label("period_ms")?
+                .absorb(self.period_ms)?
+                .label("branch_policy")?
+                .absorb(self.branch_policy)?;
+        }
+        ctx.label("psks")?
             .skip(repeated_psks)?
-            .repeated(self.psks.clone(), |ctx, (pskid, psk)| {
+            .repeated(Bounded(self.psks.clone(), MAX_RECIPIENTS), |ctx, (pskid, psk)| {
                 ctx.fork(|ctx| {
-                    ctx.mask(<&NBytes<psk::PskIdSize>>::from(pskid))?
+                    ctx.label("pskid")?
+                        .mask(<&NBytes<psk::PskIdSize>>::from(pskid))?
                         .absorb(External(<&NBytes<psk::PskSize>>::from(psk)))?
                         .commit()?
+                        .label("key")?
                         .mask(&self.key)
                 })
             })?
+            .label("ke_pks")?
             .skip(repeated_ke_pks)?
-            .repeated(self.ke_pks.clone(), |ctx, (sig_pk, ke_pk)| {
-                ctx.fork(|ctx| ctx.absorb(sig_pk)?.x25519(ke_pk, &self.key))
+            .repeated(Bounded(self.ke_pks.clone(), MAX_RECIPIENTS), |ctx, (sig_pk, ke_pk)| {
+                if self.anonymous {
+                    let tag = NBytes::<AnonymousTagSize>::default();
+                    return ctx.fork(|ctx| {
+                        self.sizeof_kem(ctx)?;
+                        ctx.x25519(ke_pk, &self.key)?.mask(&tag)
+                    });
+                }
+                let permission = self.permission(sig_pk);
+                ctx.fork(|ctx| {
+                    ctx.label("pk")?.absorb(sig_pk)?;
+                    if self.policies {
+                        ctx.label("permission")?.absorb(permission)?;
+                    }
+                    self.sizeof_kem(ctx)?;
+                    ctx.x25519(ke_pk, &self.key)
+                })
             })?
             .absorb(External(&self.key))?
+            .label("sig")?
             .ed25519(self.sig_kp, HashSig)?
             .commit()?;
         Ok(ctx)
@@ -139,11 +342,25 @@ where
     ) -> Result<&'c mut wrap::Context<F, OS>> {
         let repeated_psks = Size(self.psks.len());
         let repeated_ke_pks = Size(self.ke_pks.len());
-        ctx
-            .join(store, self.link)?
-            .absorb(&self.nonce)?
-            .skip(repeated_psks)?
-            .repeated(self.psks.clone().into_iter(), |ctx, (pskid, psk)| {
+        ensure!(
+            self.ke_pks.len() <= MAX_RECIPIENTS,
+            "Too many repeated values: {} > {}.",
+            self.ke_pks.len(),
+            MAX_RECIPIENTS
+        );
+        ensure!(
+            self.policies || self.read_only.is_empty(),
+            "Read-only recipients can't be declared in Streams 1.0 keyloads."
+        );
+        let exchanges = self.key_exchanges();
+        ctx.join(store, self.link)?.absorb(&self.nonce)?;
+        if self.policies {
+            ctx.absorb(self.max_messages)?
+                .absorb(self.period_ms)?
+                .absorb(self.branch_policy)?;
+        }
+        ctx.skip(repeated_psks)?
+            .repeated(Bounded(self.psks.clone(), MAX_RECIPIENTS), |ctx, (pskid, psk)| {
                 ctx.fork(|ctx| {
                     ctx.mask(<&NBytes<psk::PskIdSize>>::from(pskid))?
                         .absorb(External(<&NBytes<psk::PskSize>>::from(psk)))?
@@ -152,13 +369,27 @@ where
                 })
             })?
             .skip(repeated_ke_pks)?
-            .repeated(self.ke_pks.clone().into_iter(), |ctx, (sig_pk, ke_pk)| {
-                ctx.fork(|ctx|
-                         ctx
-                         .absorb(sig_pk)?
-                         .x25519(ke_pk, &self.key)
-                )
-            })?
+            .repeated(
+                Bounded(self.ke_pks.clone().zip(exchanges.iter()), MAX_RECIPIENTS),
+                |ctx, ((sig_pk, _), exchange)| {
+                    if self.anonymous {
+                        let tag = NBytes::<AnonymousTagSize>::default();
+                        return ctx.fork(|ctx| {
+                            self.wrap_kem(ctx, sig_pk)?;
+                            ctx.x25519(exchange, &self.key)?.mask(&tag)
+                        });
+                    }
+                    let permission = self.permission(sig_pk);
+                    ctx.fork(|ctx| {
+                        ctx.absorb(sig_pk)?;
+                        if self.policies {
+                            ctx.absorb(permission)?;
+                        }
+                        self.wrap_kem(ctx, sig_pk)?;
+                        ctx.x25519(exchange, &self.key)
+                    })
+                },
+            )?
             .absorb(External(&self.key))?
             .ed25519(self.sig_kp, HashSig)?
             .commit()?;
@@ -171,13 +402,27 @@ where
 pub struct ContentUnwrap<'a, F, Link: HasLink, LookupArg: 'a, LookupPsk, LookupKeSk> {
     pub link: <Link as HasLink>::Rel,
     pub nonce: NBytes<U16>, // TODO: unify with spongos::Spongos::<F>::NONCE_SIZE)
+    pub max_messages: Uint32,
+    pub period_ms: Uint64,
+    pub branch_policy: Uint8,
+    /// Rate and branch policies and permissions are declared, see `has_keyload_policies`.
+    pub(crate) policies: bool,
     pub(crate) lookup_arg: &'a LookupArg,
     pub(crate) lookup_psk: LookupPsk,
     pub(crate) ke_pk: ed25519::PublicKey,
     pub(crate) lookup_ke_sk: LookupKeSk,
     pub(crate) ke_pks: Vec<ed25519::PublicKey>,
+    /// Recipients with read-only permission.
+    pub(crate) read_only: Vec<ed25519::PublicKey>,
     pub key: Option<NBytes<U32>>, // TODO: unify with spongos::Spongos::<F>::KEY_SIZE
     pub(crate) sig_pk: &'a ed25519::PublicKey,
+    /// Delegated branches: keyloads linked to the branch root are signed by the delegate.
+    pub(crate) delegates: Vec<(<Link as HasLink>::Rel, ed25519::PublicKey)>,
+    /// Own public key to trial-decrypt the key slots of an anonymous keyload with.
+    pub(crate) anonymous: Option<ed25519::PublicKey>,
+    /// Own ML-KEM key pair if the channel uses hybrid key encapsulation.
+    #[cfg(feature = "pq-kem")]
+    pub(crate) kem_kp: Option<&'a kyber::Keypair>,
     _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
@@ -199,16 +444,69 @@ where
         Self {
             link: <<Link as HasLink>::Rel as Default>::default(),
             nonce: NBytes::default(),
+            max_messages: Uint32(0),
+            period_ms: Uint64(0),
+            branch_policy: Uint8(0),
+            policies: true,
             lookup_arg,
             lookup_psk,
             ke_pk: ed25519::PublicKey::default(),
             lookup_ke_sk,
             ke_pks: Vec::new(),
+            read_only: Vec::new(),
             key: None,
             sig_pk,
+            delegates: Vec::new(),
+            anonymous: None,
+            #[cfg(feature = "pq-kem")]
+            kem_kp: None,
             _phantom: core::marker::PhantomData,
         }
     }
+
+    /// Unwrap an anonymous keyload, trying the key slots with the key exchange key of `pk`.
+    pub fn with_anonymous_recipient(mut self, pk: ed25519::PublicKey) -> Self {
+        self.anonymous = Some(pk);
+        self
+    }
+
+    /// Accept keyloads signed by delegates in their branches, given as pairs of
+    /// branch root link and delegate public key.
+    pub fn with_delegates(mut self, delegates: Vec<(<Link as HasLink>::Rel, ed25519::PublicKey)>) -> Self {
+        self.delegates = delegates;
+        self
+    }
+
+    /// Expect hybrid key encapsulation, secrets encapsulated for own key pair `kem_kp`.
+    #[cfg(feature = "pq-kem")]
+    pub fn with_kem_keypair(mut self, kem_kp: &'a kyber::Keypair) -> Self {
+        self.kem_kp = Some(kem_kp);
+        self
+    }
+
+    /// Skip the ML-KEM ciphertext of a public key slot if hybrid key encapsulation is used and,
+    /// if `decapsulate`, absorb the secret encapsulated for own key pair.
+    #[cfg_attr(not(feature = "pq-kem"), allow(unused_variables))]
+    fn unwrap_kem<'c, IS: io::IStream>(
+        &self,
+        ctx: &'c mut unwrap::Context<F, IS>,
+        decapsulate: bool,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        #[cfg(feature = "pq-kem")]
+        {
+            if let Some(kem_kp) = self.kem_kp {
+                let mut kem_ct = Bytes::new();
+                ctx.skip(&mut kem_ct)?;
+                if decapsulate {
+                    let kem_key = kem_kp
+                        .decapsulate(&kem_ct.0)
+                        .ok_or_else(|| anyhow!("Bad ML-KEM ciphertext."))?;
+                    ctx.absorb(External(<&NBytes<U32>>::from(&kem_key[..])))?;
+                }
+            }
+        }
+        Ok(ctx)
+    }
 }
 
 impl<'a, F, Link, Store, LookupArg, LookupPsk, LookupKeSk> message::ContentUnwrap<F, Store>
@@ -230,12 +528,24 @@ where
         let mut repeated_psks = Size(0);
         let mut repeated_ke_pks = Size(0);
         let mut pskid = psk::PskId::default();
+        let anonymous = self.anonymous.clone();
+
+        ctx.join(store, &mut self.link)?;
+        let delegate_pk = self
+            .delegates
+            .iter()
+            .find(|(branch, _)| *branch == self.link)
+            .map(|(_, pk)| pk.clone());
 
+        ctx.absorb(&mut self.nonce)?;
+        if self.policies {
+            ctx.absorb(&mut self.max_messages)?
+                .absorb(&mut self.period_ms)?
+                .absorb(&mut self.branch_policy)?;
+        }
         ctx
-            .join(store, &mut self.link)?
-            .absorb(&mut self.nonce)?
             .skip(&mut repeated_psks)?
-            .repeated(repeated_psks, |ctx| {
+            .repeated(Bounded(repeated_psks, MAX_RECIPIENTS), |ctx| {
                 if self.key.is_none() {
                     ctx.fork(|ctx| {
                         ctx.mask(<&mut NBytes<psk::PskIdSize>>::from(&mut pskid))?;
@@ -259,12 +569,43 @@ where
                 }
             })?
             .skip(&mut repeated_ke_pks)?
-            .repeated(repeated_ke_pks, |ctx| {
+            .repeated(Bounded(repeated_ke_pks, MAX_RECIPIENTS), |ctx| {
+                if let Some(own_pk) = anonymous.as_ref() {
+                    let ke_sk = match (self.key.is_none(), (self.lookup_ke_sk)(self.lookup_arg, own_pk)) {
+                        (true, Some(ke_sk)) => ke_sk,
+                        _ => {
+                            // Drop entire slot.
+                            let n = Size(32 + 32 + 16);
+                            return self.unwrap_kem(ctx, false)?.drop(n);
+                        }
+                    };
+                    let mut key = NBytes::<U32>::default();
+                    let mut tag = NBytes::<AnonymousTagSize>::default();
+                    ctx.fork(|ctx| {
+                        self.unwrap_kem(ctx, true)?;
+                        ctx.x25519(ke_sk, &mut key)?.mask(&mut tag)
+                    })?;
+                    if tag == NBytes::default() {
+                        self.key = Some(key);
+                        self.ke_pk = own_pk.clone();
+                        self.ke_pks.push(own_pk.clone());
+                    }
+                    return Ok(ctx);
+                }
                 ctx.fork(|ctx| {
                     let mut ke_pk = ed25519::PublicKey::default();
+                    let mut permission = Uint8(PERMISSION_READ_WRITE);
                     ctx.absorb(&mut ke_pk)?;
+                    if self.policies {
+                        ctx.absorb(&mut permission)?
+                            .guard(permission.0 <= PERMISSION_READ, "Bad recipient permission.")?;
+                    }
+                    if permission.0 == PERMISSION_READ {
+                        self.read_only.push(ke_pk.clone());
+                    }
                     if let Some(ke_sk) = (self.lookup_ke_sk)(self.lookup_arg, &ke_pk) {
                         let mut key = NBytes::<U32>::default();
+                        self.unwrap_kem(ctx, true)?;
                         ctx.x25519(ke_sk, &mut key)?;
                         self.key = Some(key);
                         // Save the relevant public key
@@ -276,7 +617,7 @@ where
                         // Just drop the rest of the forked message so not to waste Spongos operations
                         // TODO: key length
                         let n = Size(64);
-                        ctx.drop(n)
+                        self.unwrap_kem(ctx, false)?.drop(n)
                     }
                 })
             })?
@@ -285,7 +626,7 @@ where
         if let Some(ref key) = self.key {
             ctx
                 .absorb(External(key))?
-                .ed25519(self.sig_pk, HashSig)?
+                .ed25519(delegate_pk.as_ref().unwrap_or(self.sig_pk), HashSig)?
                 .commit()?
             ;
         }
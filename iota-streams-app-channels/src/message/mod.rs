@@ -9,15 +9,30 @@ pub mod keyload;
 /// SignedPacket message.
 pub mod signed_packet;
 
-/// TaggedPacket message.
+/// TaggedPacket and DeniablePacket messages.
 pub mod tagged_packet;
 
+/// PrivatePacket message.
+pub mod private_packet;
+
 /// Subscribe message.
 pub mod subscribe;
 
 /// Sequence message.
 pub mod sequence;
 
+/// BranchDelegation message.
+pub mod branch_delegation;
+
+/// Checkpoint message.
+pub mod checkpoint;
+
+/// Signer of announcements and signed packets.
+pub mod signer;
+
+/// Synthetic IV of masked payloads.
+pub mod siv;
+
 pub const ANNOUNCE: u8 = 0;
 pub const KEYLOAD: u8 = 1;
 pub const SEQUENCE: u8 = 2;
@@ -25,6 +40,11 @@ pub const SIGNED_PACKET: u8 = 3;
 pub const TAGGED_PACKET: u8 = 4;
 pub const SUBSCRIBE: u8 = 5;
 pub const UNSUBSCRIBE: u8 = 6;
+pub const PRIVATE_PACKET: u8 = 7;
+pub const BRANCH_DELEGATION: u8 = 8;
+pub const CHECKPOINT: u8 = 9;
+pub const ANONYMOUS_KEYLOAD: u8 = 10;
+pub const DENIABLE_PACKET: u8 = 11;
 
 // Unsubscribe message.
 // pub mod unsubscribe;
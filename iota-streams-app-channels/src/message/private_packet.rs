@@ -0,0 +1,217 @@
+//! `PrivatePacket` message content. The message is similar to `SignedPacket` and in
+//! addition carries a private payload readable by a single recipient only. Public and
+//! masked payloads are shared with everyone who can read the branch, the private payload
+//! is encrypted for the recipient's X25519 key with an ephemeral key exchange and is
+//! placed into a section which other readers skip.
+//!
+//! ```ddml
+//! message PrivatePacket {
+//!     join link msgid;
+//!     absorb u8 ed25519pk[32];
+//!     absorb bytes public_payload;
+//!     mask bytes masked_payload;
+//!     section {
+//!         absorb u8 recipient_ed25519pk[32];
+//!         absorb u8 eph_x25519pk[32];
+//!         x25519(eph_x25519sk, recipient_x25519pk);
+//!         commit;
+//!         mask bytes private_payload;
+//!     }
+//!     commit;
+//!     squeeze external u8 hash[64];
+//!     ed25519(hash) sig;
+//! }
+//! ```
+//!
+//! # Fields
+//!
+//! * `msgid` -- link to the base message.
+//!
+//! * `ed25519pk` -- sender's Ed25519 public key.
+//!
+//! * `public_payload` -- public part of payload.
+//!
+//! * `masked_payload` -- masked part of payload.
+//!
+//! * `recipient_ed25519pk` -- Ed25519 public key of the private payload recipient.
+//!
+//! * `eph_x25519pk` -- ephemeral X25519 public key.
+//!
+//! * `private_payload` -- private part of payload, masked with the key derived from the
+//! X25519 shared secret.
+//!
+//! * `hash` -- hash value to be signed.
+//!
+//! * `sig` -- message signature generated with sender's private key.
+//!
+//! Channels with `hdf::FLAG_SIV_MASK` set absorb a synthetic IV before `masked_payload`,
+//! see `siv`. The private payload is masked with a fresh ephemeral key and is not affected.
+//!
+
+use anyhow::Result;
+use iota_streams_app::message::{
+    self,
+    HasLink,
+};
+use iota_streams_core::sponge::prp::PRP;
+use iota_streams_core_edsig::{
+    key_exchange::x25519,
+    signature::ed25519,
+};
+use iota_streams_ddml::{
+    command::*,
+    io,
+    link_store::{
+        EmptyLinkStore,
+        LinkStore,
+    },
+    types::*,
+};
+
+use super::siv::*;
+
+pub struct ContentWrap<'a, F, Link>
+where
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a,
+{
+    pub(crate) link: &'a <Link as HasLink>::Rel,
+    pub(crate) public_payload: &'a Bytes,
+    pub(crate) masked_payload: &'a Bytes,
+    /// Absorb a synthetic IV before masking the payload.
+    pub(crate) siv: bool,
+    pub(crate) recipient_pk: &'a ed25519::PublicKey,
+    pub(crate) recipient_ke_pk: x25519::PublicKey,
+    pub(crate) private_payload: &'a Bytes,
+    pub(crate) sig_kp: &'a ed25519::Keypair,
+    pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
+}
+
+impl<'a, F, Link> message::ContentSizeof<F> for ContentWrap<'a, F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F>,
+{
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
+        ctx.label("link")?
+            .join(&store, self.link)?
+            .label("pk")?
+            .absorb(&self.sig_kp.public)?
+            .label("public_payload")?
+            .absorb(self.public_payload)?;
+        sizeof_siv(ctx, self.siv)?
+            .label("masked_payload")?
+            .mask(self.masked_payload)?
+            .label("private_section")?
+            .section(|ctx| {
+                ctx.label("recipient_pk")?
+                    .absorb(self.recipient_pk)?
+                    .label("ephemeral_pk")?
+                    .x25519(&self.recipient_ke_pk, self.private_payload)
+            })?
+            .label("sig")?
+            .ed25519(self.sig_kp, HashSig)?;
+        Ok(ctx)
+    }
+}
+
+impl<'a, F, Link, Store> message::ContentWrap<F, Store> for ContentWrap<'a, F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+{
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        ctx.join(store, self.link)?
+            .absorb(&self.sig_kp.public)?
+            .absorb(self.public_payload)?;
+        wrap_siv(ctx, self.siv, self.masked_payload)?
+            .mask(self.masked_payload)?
+            .section(|ctx| {
+                ctx.absorb(self.recipient_pk)?
+                    .x25519(&self.recipient_ke_pk, self.private_payload)
+            })?
+            .ed25519(self.sig_kp, HashSig)?;
+        Ok(ctx)
+    }
+}
+
+pub struct ContentUnwrap<'a, F, Link: HasLink> {
+    pub(crate) link: <Link as HasLink>::Rel,
+    pub(crate) public_payload: Bytes,
+    pub(crate) masked_payload: Bytes,
+    /// Expect a synthetic IV before the masked payload.
+    pub(crate) siv: bool,
+    pub(crate) sig_pk: ed25519::PublicKey,
+    pub(crate) recipient_pk: ed25519::PublicKey,
+    /// Private payload, `None` unless the reader is the recipient.
+    pub(crate) private_payload: Option<Bytes>,
+    pub(crate) own_pk: &'a ed25519::PublicKey,
+    pub(crate) own_ke_sk: &'a x25519::StaticSecret,
+    pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
+}
+
+impl<'a, F, Link> ContentUnwrap<'a, F, Link>
+where
+    Link: HasLink,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F>,
+{
+    pub fn new(own_pk: &'a ed25519::PublicKey, own_ke_sk: &'a x25519::StaticSecret) -> Self {
+        Self {
+            link: <<Link as HasLink>::Rel as Default>::default(),
+            public_payload: Bytes::default(),
+            masked_payload: Bytes::default(),
+            siv: false,
+            sig_pk: ed25519::PublicKey::default(),
+            recipient_pk: ed25519::PublicKey::default(),
+            private_payload: None,
+            own_pk,
+            own_ke_sk,
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, F, Link, Store> message::ContentUnwrap<F, Store> for ContentUnwrap<'a, F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+{
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        ctx.join(store, &mut self.link)?
+            .absorb(&mut self.sig_pk)?
+            .absorb(&mut self.public_payload)?;
+        unwrap_siv(ctx, self.siv)?
+            .mask(&mut self.masked_payload)?;
+
+        let recipient_pk = &mut self.recipient_pk;
+        let private_payload = &mut self.private_payload;
+        let own_pk = self.own_pk;
+        let own_ke_sk = self.own_ke_sk;
+        ctx.section(|ctx| {
+            ctx.absorb(&mut *recipient_pk)?;
+            // Readers other than the recipient leave the rest of the section unread.
+            if *recipient_pk == *own_pk {
+                let mut payload = Bytes::default();
+                ctx.x25519(own_ke_sk, &mut payload)?;
+                *private_payload = Some(payload);
+            }
+            Ok(ctx)
+        })?
+        .ed25519(&self.sig_pk, HashSig)?;
+        Ok(ctx)
+    }
+}
@@ -58,9 +58,13 @@ where
 {
     fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
-        ctx.join(&store, self.link)?
+        ctx.label("link")?
+            .join(&store, self.link)?
+            .label("pk")?
             .absorb(self.pk)?
+            .label("seq_num")?
             .skip(Uint64(self.seq_num))?
+            .label("ref_link")?
             .absorb(<&Fallback<<Link as HasLink>::Rel>>::from(self.ref_link))?
             .commit()?;
         Ok(ctx)
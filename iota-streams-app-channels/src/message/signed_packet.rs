@@ -26,6 +26,16 @@
 //!
 //! * `sig` -- message signature generated with one of channel owner's private key.
 //!
+//! Channels with `hdf::FLAG_SIV_MASK` set absorb a synthetic IV before `masked_payload`,
+//! see `siv`.
+//!
+//! Packets of the author of a hybrid channel (`pq-sig` feature) are wrapped with
+//! a post-quantum version (see `pq_version`) and additionally signed with the author's MSS key
+//! pair (see `signer`).
+//!
+//! Packets co-signed with a secp256k1 key (`secp256k1` feature) are wrapped with
+//! `STREAMS_1_5_VER` and the Ed25519 signature is followed by the co-signature (see `signer`).
+//!
 
 use anyhow::Result;
 use iota_streams_app::message::{
@@ -34,6 +44,14 @@ use iota_streams_app::message::{
 };
 use iota_streams_core::sponge::prp::PRP;
 use iota_streams_core_edsig::signature::ed25519;
+#[cfg(feature = "pq-sig")]
+use iota_streams_core_edsig::signature::mss;
+#[cfg(feature = "secp256k1")]
+use iota_streams_core_edsig::signature::secp256k1;
+use super::{
+    signer::Signer,
+    siv::*,
+};
 use iota_streams_ddml::{
     command::*,
     io,
@@ -52,7 +70,12 @@ where
     pub(crate) link: &'a <Link as HasLink>::Rel,
     pub(crate) public_payload: &'a Bytes,
     pub(crate) masked_payload: &'a Bytes,
-    pub(crate) sig_kp: &'a ed25519::Keypair,
+    /// Absorb a synthetic IV before masking the payload.
+    pub(crate) siv: bool,
+    pub(crate) signer: Signer<'a>,
+    /// Secp256k1 key co-signing the packet, if any.
+    #[cfg(feature = "secp256k1")]
+    pub(crate) cosigner: Option<&'a secp256k1::SigningKey>,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
@@ -64,11 +87,22 @@ where
 {
     fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
-        ctx.join(&store, self.link)?
-            .absorb(&self.sig_kp.public)?
-            .absorb(self.public_payload)?
-            .mask(self.masked_payload)?
-            .ed25519(self.sig_kp, HashSig)?;
+        ctx.label("link")?
+            .join(&store, self.link)?
+            .label("pk")?
+            .absorb(self.signer.public_key())?
+            .label("public_payload")?
+            .absorb(self.public_payload)?;
+        sizeof_siv(ctx, self.siv)?
+            .label("masked_payload")?
+            .mask(self.masked_payload)?;
+        self.signer.sizeof(ctx)?;
+        #[cfg(feature = "secp256k1")]
+        {
+            if self.cosigner.is_some() {
+                super::signer::sizeof_cosig(ctx)?;
+            }
+        }
         // TODO: Is both public and masked payloads are ok? Leave public only or masked only?
         Ok(ctx)
     }
@@ -87,10 +121,17 @@ where
         ctx: &'c mut wrap::Context<F, OS>,
     ) -> Result<&'c mut wrap::Context<F, OS>> {
         ctx.join(store, self.link)?
-            .absorb(&self.sig_kp.public)?
-            .absorb(self.public_payload)?
-            .mask(self.masked_payload)?
-            .ed25519(self.sig_kp, HashSig)?;
+            .absorb(self.signer.public_key())?
+            .absorb(self.public_payload)?;
+        wrap_siv(ctx, self.siv, self.masked_payload)?
+            .mask(self.masked_payload)?;
+        self.signer.wrap(ctx)?;
+        #[cfg(feature = "secp256k1")]
+        {
+            if let Some(sk) = self.cosigner {
+                return super::signer::wrap_cosig(ctx, sk);
+            }
+        }
         Ok(ctx)
     }
 }
@@ -98,8 +139,19 @@ where
 pub struct ContentUnwrap<F, Link: HasLink> {
     pub(crate) link: <Link as HasLink>::Rel,
     pub(crate) public_payload: Bytes,
-    pub(crate) masked_payload: Bytes,
+    pub(crate) masked_payload: MaskedBytes<F>,
     pub(crate) sig_pk: ed25519::PublicKey,
+    /// Expect a synthetic IV before the masked payload.
+    pub(crate) siv: bool,
+    /// MSS public key of the author to verify the MSS signature with, if any.
+    #[cfg(feature = "pq-sig")]
+    pub(crate) mss_pk: Option<mss::PublicKey>,
+    /// Expect a secp256k1 co-signature after the Ed25519 signature.
+    #[cfg(feature = "secp256k1")]
+    pub(crate) cosigned: bool,
+    /// Unwrapped secp256k1 co-signature, if any.
+    #[cfg(feature = "secp256k1")]
+    pub(crate) cosignature: Option<secp256k1::Cosignature>,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
@@ -112,8 +164,15 @@ where
         Self {
             link: <<Link as HasLink>::Rel as Default>::default(),
             public_payload: Bytes::default(),
-            masked_payload: Bytes::default(),
+            masked_payload: MaskedBytes::default(),
             sig_pk: ed25519::PublicKey::default(),
+            siv: false,
+            #[cfg(feature = "pq-sig")]
+            mss_pk: None,
+            #[cfg(feature = "secp256k1")]
+            cosigned: false,
+            #[cfg(feature = "secp256k1")]
+            cosignature: None,
             _phantom: core::marker::PhantomData,
         }
     }
@@ -133,9 +192,22 @@ where
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
         ctx.join(store, &mut self.link)?
             .absorb(&mut self.sig_pk)?
-            .absorb(&mut self.public_payload)?
+            .absorb(&mut self.public_payload)?;
+        unwrap_siv(ctx, self.siv)?
             .mask(&mut self.masked_payload)?
             .ed25519(&self.sig_pk, HashSig)?;
+        #[cfg(feature = "pq-sig")]
+        {
+            if let Some(ref mss_pk) = self.mss_pk {
+                return super::signer::unwrap_mss(ctx, mss_pk);
+            }
+        }
+        #[cfg(feature = "secp256k1")]
+        {
+            if self.cosigned {
+                return super::signer::unwrap_cosig(ctx, &mut self.cosignature);
+            }
+        }
         Ok(ctx)
     }
 }
@@ -0,0 +1,213 @@
+//! Signer of `Announce` and `SignedPacket` messages: own Ed25519 key pair or a threshold group
+//! of the channel author (see `ed25519::frost`). Group signatures are made outside of wrapping:
+//! the message is wrapped once to get the hash to be signed and again with the aggregate
+//! signature. Both wraps must produce the same hash, so the user state must not change in
+//! between.
+//!
+//! With the `pq-sig` feature the author of a hybrid channel signs with both own Ed25519 and MSS
+//! key pairs (see `mss`). Messages are then wrapped with a post-quantum version (see `pq_version`) and the Ed25519
+//! signature is followed by:
+//!
+//! ```ddml
+//!     squeeze external u8 pq_tag[64];
+//!     commit;
+//!     skip bytes mss_sig;
+//! ```
+//!
+//! With the `secp256k1` feature signed packets can be co-signed with a secp256k1 key, eg. one
+//! known to an Ethereum contract (see `secp256k1`). Such packets are wrapped with
+//! `STREAMS_1_5_VER` and the Ed25519 signature is followed by:
+//!
+//! ```ddml
+//!     squeeze external u8 cosig_digest[32];
+//!     commit;
+//!     skip u8 cosig[65];
+//! ```
+
+use anyhow::Result;
+#[cfg(feature = "pq-sig")]
+use anyhow::anyhow;
+#[cfg(any(feature = "pq-sig", feature = "secp256k1"))]
+use anyhow::ensure;
+use core::cell::RefCell;
+
+#[cfg(feature = "pq-sig")]
+use iota_streams_core::prelude::vec;
+use iota_streams_app::message::{
+    signature_context,
+    STREAMS_VER,
+};
+use iota_streams_core::{
+    prelude::Vec,
+    sponge::prp::PRP,
+};
+use iota_streams_core_edsig::signature::ed25519;
+#[cfg(feature = "pq-sig")]
+use iota_streams_core_edsig::signature::mss;
+#[cfg(feature = "secp256k1")]
+use iota_streams_core_edsig::signature::secp256k1;
+use iota_streams_ddml::{
+    command::*,
+    io,
+    types::*,
+};
+
+/// Context of Ed25519ph signatures of messages of `content_type` wrapped with `STREAMS_VER`,
+/// group signatures must be made with it.
+pub fn threshold_signature_context(content_type: u8) -> Vec<u8> {
+    signature_context(STREAMS_VER, content_type)
+}
+
+pub enum Signer<'a> {
+    Keypair(&'a ed25519::Keypair),
+    Threshold {
+        group_pk: &'a ed25519::PublicKey,
+        /// Aggregate signature, zero bytes are written while it's not known.
+        sig: Option<&'a ed25519::Signature>,
+        /// Hash to be signed, set when wrapping.
+        tag: RefCell<[u8; 64]>,
+    },
+    /// Own Ed25519 and MSS key pairs, every wrap uses up one MSS leaf.
+    #[cfg(feature = "pq-sig")]
+    Hybrid(&'a ed25519::Keypair, &'a RefCell<mss::Keypair>),
+}
+
+impl<'a> Signer<'a> {
+    pub fn threshold(group_pk: &'a ed25519::PublicKey, sig: Option<&'a ed25519::Signature>) -> Self {
+        Signer::Threshold {
+            group_pk,
+            sig,
+            tag: RefCell::new([0_u8; 64]),
+        }
+    }
+
+    pub fn public_key(&self) -> &'a ed25519::PublicKey {
+        match self {
+            Signer::Keypair(kp) => &kp.public,
+            Signer::Threshold { group_pk, .. } => group_pk,
+            #[cfg(feature = "pq-sig")]
+            Signer::Hybrid(kp, _) => &kp.public,
+        }
+    }
+
+    /// Hash to be signed by the threshold group, available after wrapping.
+    pub fn tag(&self) -> Option<[u8; 64]> {
+        match self {
+            Signer::Threshold { tag, .. } => Some(*tag.borrow()),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn sizeof<'c, F: PRP>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        match self {
+            Signer::Keypair(kp) => ctx.label("sig")?.ed25519(*kp, HashSig),
+            Signer::Threshold { .. } => ctx.label("sig")?.skip(&NBytes::<U64>::default()),
+            #[cfg(feature = "pq-sig")]
+            Signer::Hybrid(kp, mss_kp) => {
+                let mss_sig = Bytes(vec![0_u8; mss_kp.borrow().signature_length()]);
+                ctx.label("sig")?
+                    .ed25519(*kp, HashSig)?
+                    .squeeze(&External(NBytes::<U64>::default()))?
+                    .commit()?
+                    .label("mss_sig")?
+                    .skip(&mss_sig)
+            }
+        }
+    }
+
+    pub(crate) fn wrap<'c, F: PRP, OS: io::OStream>(
+        &self,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        match self {
+            Signer::Keypair(kp) => ctx.ed25519(*kp, HashSig),
+            Signer::Threshold { sig, tag, .. } => {
+                // Same as `ed25519` with `HashSig`, the signature is just copied.
+                let mut hash = External(NBytes::<U64>::default());
+                ctx.squeeze(&mut hash)?.commit()?;
+                tag.borrow_mut().copy_from_slice((hash.0).as_slice());
+                let bytes = sig.map_or([0_u8; ed25519::SIGNATURE_LENGTH], |sig| sig.to_bytes());
+                ctx.skip(<&NBytes<U64>>::from(&bytes[..]))
+            }
+            #[cfg(feature = "pq-sig")]
+            Signer::Hybrid(kp, mss_kp) => {
+                let mut hash = External(NBytes::<U64>::default());
+                ctx.ed25519(*kp, HashSig)?.squeeze(&mut hash)?.commit()?;
+                let mss_sig = mss_kp
+                    .borrow_mut()
+                    .sign((hash.0).as_slice())
+                    .ok_or_else(|| anyhow!("MSS key pair is exhausted."))?;
+                ctx.skip(&Bytes(mss_sig))
+            }
+        }
+    }
+
+    /// MSS public key of hybrid signers.
+    #[cfg(feature = "pq-sig")]
+    pub fn mss_public_key(&self) -> Option<mss::PublicKey> {
+        match self {
+            Signer::Hybrid(_, mss_kp) => Some(mss_kp.borrow().public),
+            _ => None,
+        }
+    }
+}
+
+/// Unwrap and verify the MSS signature following the Ed25519 signature of a message.
+#[cfg(feature = "pq-sig")]
+pub(crate) fn unwrap_mss<'c, F: PRP, IS: io::IStream>(
+    ctx: &'c mut unwrap::Context<F, IS>,
+    mss_pk: &mss::PublicKey,
+) -> Result<&'c mut unwrap::Context<F, IS>> {
+    let mut hash = External(NBytes::<U64>::default());
+    let mut mss_sig = Bytes::new();
+    ctx.squeeze(&mut hash)?.commit()?.skip(&mut mss_sig)?;
+    ensure!(
+        mss::verify(mss_pk, (hash.0).as_slice(), &mss_sig.0),
+        "Bad MSS signature."
+    );
+    Ok(ctx)
+}
+
+/// Size of the secp256k1 co-signature following the Ed25519 signature of a message.
+#[cfg(feature = "secp256k1")]
+pub(crate) fn sizeof_cosig<'c, F: PRP>(ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+    ctx.squeeze(&External(NBytes::<U32>::default()))?
+        .commit()?
+        .label("cosig")?
+        .skip(&NBytes::<U65>::default())
+}
+
+/// Co-sign the message with secp256k1 key `sk` following its Ed25519 signature.
+#[cfg(feature = "secp256k1")]
+pub(crate) fn wrap_cosig<'c, F: PRP, OS: io::OStream>(
+    ctx: &'c mut wrap::Context<F, OS>,
+    sk: &secp256k1::SigningKey,
+) -> Result<&'c mut wrap::Context<F, OS>> {
+    let mut digest = External(NBytes::<U32>::default());
+    ctx.squeeze(&mut digest)?.commit()?;
+    let mut bytes = [0_u8; secp256k1::DIGEST_LENGTH];
+    bytes.copy_from_slice((digest.0).as_slice());
+    let sig = secp256k1::sign(sk, &bytes);
+    ctx.skip(<&NBytes<U65>>::from(&sig[..]))
+}
+
+/// Unwrap the secp256k1 co-signature following the Ed25519 signature of a message and check
+/// that a co-signer can be recovered from it.
+#[cfg(feature = "secp256k1")]
+pub(crate) fn unwrap_cosig<'c, F: PRP, IS: io::IStream>(
+    ctx: &'c mut unwrap::Context<F, IS>,
+    cosignature: &mut Option<secp256k1::Cosignature>,
+) -> Result<&'c mut unwrap::Context<F, IS>> {
+    let mut digest = External(NBytes::<U32>::default());
+    let mut sig = NBytes::<U65>::default();
+    ctx.squeeze(&mut digest)?.commit()?.skip(&mut sig)?;
+    let mut cosig = secp256k1::Cosignature {
+        digest: [0_u8; secp256k1::DIGEST_LENGTH],
+        signature: [0_u8; secp256k1::SIGNATURE_LENGTH],
+    };
+    cosig.digest.copy_from_slice((digest.0).as_slice());
+    cosig.signature.copy_from_slice(sig.as_slice());
+    ensure!(cosig.signer().is_some(), "Bad secp256k1 co-signature.");
+    *cosignature = Some(cosig);
+    Ok(ctx)
+}
@@ -0,0 +1,61 @@
+//! Synthetic IV of masked payloads in channels with `hdf::FLAG_SIV_MASK` set. Packets absorb
+//! the SIV right before masking the payload:
+//!
+//! ```ddml
+//!     absorb u8 siv[32];
+//!     mask bytes masked_payload;
+//! ```
+//!
+//! `siv` is expanded (see `kdf`) from a fork of the spongos state labeled with the length and
+//! the bytes of the plain `masked_payload`, i.e. it is a hash of the session key, the message link and
+//! the payload. Should a spongos state be reused, e.g. when a device with a weak RNG or a
+//! restored state publishes different payloads at the same message link, masked payloads are
+//! still encrypted with different key streams; only equal payloads produce equal ciphertexts.
+//! The SIV is authenticated by the MAC or the signature of the packet like the payload.
+
+use anyhow::Result;
+use iota_streams_core::{
+    kdf::Kdf,
+    sponge::prp::PRP,
+};
+use iota_streams_ddml::{
+    command::*,
+    io,
+    types::*,
+};
+
+/// Size of the synthetic IV.
+pub type SivSize = U32;
+
+pub(crate) fn sizeof_siv<F>(ctx: &mut sizeof::Context<F>, siv: bool) -> Result<&mut sizeof::Context<F>> {
+    if siv {
+        ctx.label("siv")?.absorb(&NBytes::<SivSize>::default())?;
+    }
+    Ok(ctx)
+}
+
+/// Absorb the synthetic IV of `masked_payload` if `siv` is set.
+pub(crate) fn wrap_siv<'c, F: PRP, OS: io::OStream>(
+    ctx: &'c mut wrap::Context<F, OS>,
+    siv: bool,
+    masked_payload: &Bytes,
+) -> Result<&'c mut wrap::Context<F, OS>> {
+    if siv {
+        let mut kdf = Kdf::from(ctx.spongos.fork());
+        kdf.absorb((masked_payload.0.len() as u64).to_be_bytes());
+        let iv = NBytes::<SivSize>::from(kdf.expand_arr::<SivSize>(&masked_payload.0));
+        ctx.absorb(&iv)?;
+    }
+    Ok(ctx)
+}
+
+pub(crate) fn unwrap_siv<F: PRP, IS: io::IStream>(
+    ctx: &mut unwrap::Context<F, IS>,
+    siv: bool,
+) -> Result<&mut unwrap::Context<F, IS>> {
+    if siv {
+        let mut iv = NBytes::<SivSize>::default();
+        ctx.absorb(&mut iv)?;
+    }
+    Ok(ctx)
+}
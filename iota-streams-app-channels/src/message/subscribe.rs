@@ -38,6 +38,13 @@
 //!
 //! Note, the `unsubscribe_key` is masked and verified in the `ntrukem` operation and
 //! thus is not additionally `absorb`ed in this message.
+//!
+//! In channels with `hdf::FLAG_HYBRID_KEM_MASK` set (`pq-kem` feature) the subscriber's ML-KEM
+//! public key follows the masked Ed25519 public key, adding 1187 bytes to the message:
+//!
+//! ```ddml
+//!     mask bytes kyberpk;
+//! ```
 
 use anyhow::Result;
 use iota_streams_app::message::{
@@ -45,6 +52,8 @@ use iota_streams_app::message::{
     HasLink,
 };
 use iota_streams_core::sponge::prp::PRP;
+#[cfg(feature = "pq-kem")]
+use iota_streams_core_edsig::key_exchange::kyber;
 use iota_streams_core_edsig::{
     key_exchange::x25519,
     signature::ed25519,
@@ -64,9 +73,24 @@ pub struct ContentWrap<'a, F, Link: HasLink> {
     pub unsubscribe_key: NBytes<U32>,
     pub(crate) subscriber_sig_kp: &'a ed25519::Keypair,
     pub(crate) author_ke_pk: &'a x25519::PublicKey,
+    /// Subscriber's ML-KEM public key in hybrid key encapsulation channels.
+    #[cfg(feature = "pq-kem")]
+    pub(crate) kem_pk: Option<&'a kyber::PublicKey>,
     pub(crate) _phantom: core::marker::PhantomData<(Link, F)>,
 }
 
+impl<'a, F, Link: HasLink> ContentWrap<'a, F, Link> {
+    fn kem_pk(&self) -> Option<Bytes> {
+        #[cfg(feature = "pq-kem")]
+        {
+            if let Some(kem_pk) = self.kem_pk {
+                return Some(Bytes(kem_pk.as_bytes().to_vec()));
+            }
+        }
+        None
+    }
+}
+
 impl<'a, F, Link> message::ContentSizeof<F> for ContentWrap<'a, F, Link>
 where
     F: PRP,
@@ -75,10 +99,16 @@ where
 {
     fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
-        ctx.join(&store, self.link)?
+        ctx.label("link")?
+            .join(&store, self.link)?
+            .label("unsubscribe_key")?
             .x25519(self.author_ke_pk, &self.unsubscribe_key)?
-            .mask(&self.subscriber_sig_kp.public)?
-            .ed25519(self.subscriber_sig_kp, HashSig)?;
+            .label("subscriber_pk")?
+            .mask(&self.subscriber_sig_kp.public)?;
+        if let Some(ref kem_pk) = self.kem_pk() {
+            ctx.label("kem_pk")?.mask(kem_pk)?;
+        }
+        ctx.label("sig")?.ed25519(self.subscriber_sig_kp, HashSig)?;
         Ok(ctx)
     }
 }
@@ -97,8 +127,11 @@ where
     ) -> Result<&'c mut wrap::Context<F, OS>> {
         ctx.join(store, self.link)?
             .x25519(self.author_ke_pk, &self.unsubscribe_key)?
-            .mask(&self.subscriber_sig_kp.public)?
-            .ed25519(self.subscriber_sig_kp, HashSig)?;
+            .mask(&self.subscriber_sig_kp.public)?;
+        if let Some(ref kem_pk) = self.kem_pk() {
+            ctx.mask(kem_pk)?;
+        }
+        ctx.ed25519(self.subscriber_sig_kp, HashSig)?;
         Ok(ctx)
     }
 }
@@ -108,6 +141,11 @@ pub struct ContentUnwrap<'a, F, Link: HasLink> {
     pub unsubscribe_key: NBytes<U32>,
     pub subscriber_sig_pk: ed25519::PublicKey,
     author_ke_sk: &'a x25519::StaticSecret,
+    /// Subscriber's ML-KEM public key, unwrapped in hybrid key encapsulation channels.
+    #[cfg(feature = "pq-kem")]
+    pub kem_pk: Option<kyber::PublicKey>,
+    #[cfg(feature = "pq-kem")]
+    hybrid_kem: bool,
     _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
@@ -123,9 +161,20 @@ where
             unsubscribe_key: NBytes::<U32>::default(),
             subscriber_sig_pk: ed25519::PublicKey::from_bytes(&[0_u8; ed25519::PUBLIC_KEY_LENGTH]).unwrap(),
             author_ke_sk,
+            #[cfg(feature = "pq-kem")]
+            kem_pk: None,
+            #[cfg(feature = "pq-kem")]
+            hybrid_kem: false,
             _phantom: core::marker::PhantomData,
         }
     }
+
+    /// Unwrap the subscriber's ML-KEM public key of a hybrid key encapsulation channel.
+    #[cfg(feature = "pq-kem")]
+    pub fn with_hybrid_kem(mut self) -> Self {
+        self.hybrid_kem = true;
+        self
+    }
 }
 
 impl<'a, F, Link, Store> message::ContentUnwrap<F, Store> for ContentUnwrap<'a, F, Link>
@@ -142,8 +191,17 @@ where
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
         ctx.join(store, &mut self.link)?
             .x25519(self.author_ke_sk, &mut self.unsubscribe_key)?
-            .mask(&mut self.subscriber_sig_pk)?
-            .ed25519(&self.subscriber_sig_pk, HashSig)?;
+            .mask(&mut self.subscriber_sig_pk)?;
+        #[cfg(feature = "pq-kem")]
+        {
+            if self.hybrid_kem {
+                let mut kem_pk = Bytes::new();
+                ctx.mask(&mut kem_pk)?;
+                self.kem_pk =
+                    Some(kyber::PublicKey::from_bytes(&kem_pk.0).ok_or_else(|| anyhow::anyhow!("Bad ML-KEM public key."))?);
+            }
+        }
+        ctx.ed25519(&self.subscriber_sig_pk, HashSig)?;
         Ok(ctx)
     }
 }
@@ -22,6 +22,14 @@
 //!
 //! * `mac` -- MAC of the message.
 //!
+//! Channels with `hdf::FLAG_SIV_MASK` set absorb a synthetic IV before `masked_payload`,
+//! see `siv`.
+//!
+//! `DeniablePacket` messages have the same content with content type `DENIABLE_PACKET`. They
+//! are only accepted when linked to a keyload the recipient has the session key of or to
+//! another deniable packet, so the MAC is keyed by the branch session key and any recipient
+//! of the keyload could have computed it.
+//!
 
 use anyhow::Result;
 use iota_streams_app::message::{
@@ -45,6 +53,8 @@ use iota_streams_ddml::{
     types::*,
 };
 
+use super::siv::*;
+
 pub struct ContentWrap<'a, F, Link>
 where
     Link: HasLink,
@@ -53,6 +63,8 @@ where
     pub(crate) link: &'a <Link as HasLink>::Rel,
     pub(crate) public_payload: &'a Bytes,
     pub(crate) masked_payload: &'a Bytes,
+    /// Absorb a synthetic IV before masking the payload.
+    pub(crate) siv: bool,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
@@ -65,10 +77,15 @@ where
     fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
         let mac = Mac(spongos::MacSize::<F>::USIZE);
-        ctx.join(&store, self.link)?
-            .absorb(self.public_payload)?
+        ctx.label("link")?
+            .join(&store, self.link)?
+            .label("public_payload")?
+            .absorb(self.public_payload)?;
+        sizeof_siv(ctx, self.siv)?
+            .label("masked_payload")?
             .mask(self.masked_payload)?
             .commit()?
+            .label("mac")?
             .squeeze(&mac)?;
         // TODO: Is bot public and masked payloads are ok? Leave public only or masked only?
         Ok(ctx)
@@ -90,7 +107,8 @@ where
         let mac = Mac(spongos::MacSize::<F>::USIZE);
         ctx
             .join(store, self.link)?
-            .absorb(self.public_payload)?
+            .absorb(self.public_payload)?;
+        wrap_siv(ctx, self.siv, self.masked_payload)?
             .mask(self.masked_payload)?
             .commit()?
             .squeeze(&mac)?;
@@ -102,7 +120,9 @@ where
 pub struct ContentUnwrap<F, Link: HasLink> {
     pub(crate) link: <Link as HasLink>::Rel,
     pub(crate) public_payload: Bytes,
-    pub(crate) masked_payload: Bytes,
+    pub(crate) masked_payload: MaskedBytes<F>,
+    /// Expect a synthetic IV before the masked payload.
+    pub(crate) siv: bool,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
@@ -115,7 +135,8 @@ where
         Self {
             link: <<Link as HasLink>::Rel as Default>::default(),
             public_payload: Bytes::default(),
-            masked_payload: Bytes::default(),
+            masked_payload: MaskedBytes::default(),
+            siv: false,
             _phantom: core::marker::PhantomData,
         }
     }
@@ -136,7 +157,8 @@ where
         let mac = Mac(spongos::MacSize::<F>::USIZE);
         ctx
             .join(store, &mut self.link)?
-            .absorb(&mut self.public_payload)?
+            .absorb(&mut self.public_payload)?;
+        unwrap_siv(ctx, self.siv)?
             .mask(&mut self.masked_payload)?
             .commit()?
             .squeeze(&mac)?;
@@ -38,7 +38,7 @@ use iota_streams_ddml::{
 
 pub struct ContentWrap<'a, TW, F, Link: HasLink> {
     pub(crate) link: &'a <Link as HasLink>::Rel,
-    pub(crate) _phantom: std::marker::PhantomData<(TW, F, Link)>,
+    pub(crate) _phantom: core::marker::PhantomData<(TW, F, Link)>,
 }
 
 impl<'a, TW, F, Link, Store> message::ContentWrap<TW, F, Store> for ContentWrap<'a, TW, F, Link>
@@ -69,7 +69,7 @@ where
 
 pub struct ContentUnwrap<TW, F, Link: HasLink> {
     pub link: <Link as HasLink>::Rel,
-    _phantom: std::marker::PhantomData<(TW, F, Link)>,
+    _phantom: core::marker::PhantomData<(TW, F, Link)>,
 }
 
 impl<TW, F, Link> ContentUnwrap<TW, F, Link>
@@ -80,7 +80,7 @@ where
     pub fn new() -> Self {
         Self {
             link: <<Link as HasLink>::Rel as Default>::default(),
-            _phantom: std::marker::PhantomData,
+            _phantom: core::marker::PhantomData,
         }
     }
 }
@@ -38,6 +38,14 @@ pub mod message;
 /// Transport-related abstractions.
 pub mod transport;
 
+/// Metrics of sends, receives, unwrap failures, PoW time and transport latency.
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+/// OpenTelemetry spans and trace context propagation.
+#[cfg(feature = "otel")]
+pub mod otel;
+
 // pub trait TrustProvider<PublicKey> {
 // fn check_trusted(pk: &PublicKey) -> Result<()>;
 // }
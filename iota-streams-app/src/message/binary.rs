@@ -69,6 +69,7 @@ where
         let mut header = HDF::<Link>::new(self.link().clone());
         let store = EmptyLinkStore::<F, Link, ()>::default();
         header.unwrap(&store, &mut ctx)?;
+        ctx.set_signature_context(&signature_context(header.version, header.content_type));
 
         Ok(PreparsedMessage {
             header: header,
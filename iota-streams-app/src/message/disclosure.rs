@@ -0,0 +1,156 @@
+//! Payload fields committed to in signed packets and disclosed one by one.
+//!
+//! A disclosable packet carries only salted hash commitments of its fields, covered by the
+//! packet signature. The publisher keeps the opening of every field: its index, salt and value.
+//! Handing an opening over proves the value of that field in the packet while the other fields
+//! stay hidden, the salts keep low-entropy values from being guessed from their commitments.
+
+use anyhow::{
+    anyhow,
+    ensure,
+    Result,
+};
+
+use iota_streams_core::{
+    prelude::{
+        typenum::{
+            U16,
+            U32,
+        },
+        Vec,
+    },
+    sponge::{
+        prp::PRP,
+        spongos::Spongos,
+    },
+};
+use iota_streams_ddml::types::NBytes;
+
+/// Salt of a field commitment.
+pub type FieldSalt = NBytes<U16>;
+
+/// Commitment to a field.
+pub type FieldCommitment = NBytes<U32>;
+
+/// Size of the encoded salt field.
+const FIELD_SALT_SIZE: usize = 16;
+
+/// Size of an encoded commitment.
+const FIELD_COMMITMENT_SIZE: usize = 32;
+
+/// Size of the encoded field index and count.
+const FIELD_INDEX_SIZE: usize = 4;
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Opening of a committed field, disclosing its value.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FieldOpening {
+    /// Index of the field in the packet.
+    pub index: u32,
+    /// Salt of the commitment.
+    pub salt: FieldSalt,
+    /// Value of the field.
+    pub value: Vec<u8>,
+}
+
+impl FieldOpening {
+    /// Commitment to the field: spongos hash of its salt, index and value.
+    pub fn commitment<F: PRP>(&self) -> FieldCommitment {
+        let mut s = Spongos::<F>::init();
+        s.absorb(&self.salt);
+        s.absorb(&self.index.to_be_bytes());
+        s.absorb(&self.value);
+        s.commit();
+        let mut commitment = FieldCommitment::default();
+        s.squeeze(commitment.as_mut());
+        commitment
+    }
+
+    /// Encode the opening as `index || salt || value`, `index` is a big-endian u32.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(FIELD_INDEX_SIZE + FIELD_SALT_SIZE + self.value.len());
+        bytes.extend_from_slice(&self.index.to_be_bytes());
+        bytes.extend_from_slice(self.salt.as_slice());
+        bytes.extend_from_slice(&self.value);
+        bytes
+    }
+
+    /// Decode the opening encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(
+            bytes.len() >= FIELD_INDEX_SIZE + FIELD_SALT_SIZE,
+            "Bad field opening length: {}",
+            bytes.len()
+        );
+        let (index, rest) = bytes.split_at(FIELD_INDEX_SIZE);
+        let (salt, value) = rest.split_at(FIELD_SALT_SIZE);
+        Ok(Self {
+            index: read_u32(index),
+            salt: *<&FieldSalt>::from(salt),
+            value: value.to_vec(),
+        })
+    }
+}
+
+/// Commitments to the fields of a disclosable packet, in field order.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct FieldCommitments {
+    pub commitments: Vec<FieldCommitment>,
+}
+
+impl FieldCommitments {
+    /// Commitments to the fields opened by `openings`, their indices must be their positions.
+    pub fn commit<F: PRP>(openings: &[FieldOpening]) -> Result<Self> {
+        let mut commitments = Vec::with_capacity(openings.len());
+        for (i, opening) in openings.iter().enumerate() {
+            ensure!(opening.index as usize == i, "Field opening {} has index {}.", i, opening.index);
+            commitments.push(opening.commitment::<F>());
+        }
+        Ok(Self { commitments })
+    }
+
+    /// Check that `opening` discloses the value of a committed field.
+    pub fn verify<F: PRP>(&self, opening: &FieldOpening) -> Result<()> {
+        let commitment = self
+            .commitments
+            .get(opening.index as usize)
+            .ok_or_else(|| anyhow!("No field with index {}.", opening.index))?;
+        ensure!(
+            *commitment == opening.commitment::<F>(),
+            "Field {} doesn't match its commitment.",
+            opening.index
+        );
+        Ok(())
+    }
+
+    /// Encode the commitments as `count || commitments`, `count` is a big-endian u32.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(FIELD_INDEX_SIZE + FIELD_COMMITMENT_SIZE * self.commitments.len());
+        bytes.extend_from_slice(&(self.commitments.len() as u32).to_be_bytes());
+        for commitment in &self.commitments {
+            bytes.extend_from_slice(commitment.as_slice());
+        }
+        bytes
+    }
+
+    /// Decode the commitments encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(bytes.len() >= FIELD_INDEX_SIZE, "Bad field commitments length: {}", bytes.len());
+        let (count, rest) = bytes.split_at(FIELD_INDEX_SIZE);
+        let count = read_u32(count) as usize;
+        ensure!(
+            count.checked_mul(FIELD_COMMITMENT_SIZE) == Some(rest.len()),
+            "Bad field commitments length: {}",
+            bytes.len()
+        );
+        Ok(Self {
+            commitments: rest
+                .chunks(FIELD_COMMITMENT_SIZE)
+                .map(|c| *<&FieldCommitment>::from(c))
+                .collect(),
+        })
+    }
+}
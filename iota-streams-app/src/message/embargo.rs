@@ -0,0 +1,158 @@
+//! Payloads embargoed until a later release message.
+//!
+//! The payload is encrypted with a one-time key and the embargoed packet carries only the
+//! ciphertext, the embargo identifier and optionally the milestone index the payload is due
+//! at. The publisher keeps the key until it sends the release: a message carrying the
+//! identifier and the key. When both are sent as masked payloads, only recipients of the
+//! branch can read the payload, and only once it's released.
+
+use anyhow::{
+    ensure,
+    Result,
+};
+
+use iota_streams_core::{
+    prelude::{
+        typenum::{
+            U16,
+            U32,
+        },
+        Vec,
+    },
+    sponge::{
+        prp::PRP,
+        spongos::Spongos,
+    },
+};
+use iota_streams_ddml::types::NBytes;
+
+/// Identifier of an embargo.
+pub type EmbargoId = NBytes<U16>;
+
+/// Size of the encoded identifier field.
+const EMBARGO_ID_SIZE: usize = 16;
+
+/// Size of the encoded key and tag fields.
+const EMBARGO_FIELD_SIZE: usize = 32;
+
+/// Size of the encoded milestone index field.
+const EMBARGO_MILESTONE_SIZE: usize = 4;
+
+/// Encrypted payload waiting for its release.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EmbargoedPayload {
+    /// Embargo identifier, repeated by the release.
+    pub id: EmbargoId,
+    /// Milestone index the payload is due to be released at, `None` if released at will.
+    pub release_at: Option<u32>,
+    /// Authentication tag of the payload.
+    pub tag: NBytes<U32>,
+    /// Encrypted payload.
+    pub ciphertext: Vec<u8>,
+}
+
+/// Key releasing an embargoed payload.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EmbargoRelease {
+    /// Identifier of the released embargo.
+    pub id: EmbargoId,
+    /// Payload encryption key.
+    pub key: NBytes<U32>,
+}
+
+fn embargo_spongos<F: PRP>(release: &EmbargoRelease) -> Spongos<F> {
+    let mut s = Spongos::<F>::init();
+    s.absorb(&release.key);
+    s.absorb(&release.id);
+    s.commit();
+    s
+}
+
+impl EmbargoedPayload {
+    /// Encrypt `payload` with the key of `release`.
+    pub fn seal<F: PRP>(payload: &[u8], release: &EmbargoRelease, release_at: Option<u32>) -> Self {
+        let mut s = embargo_spongos::<F>(release);
+        let ciphertext = s.encrypt_n(payload);
+        s.commit();
+        let mut tag = NBytes::<U32>::default();
+        s.squeeze(tag.as_mut());
+        Self {
+            id: release.id,
+            release_at,
+            tag,
+            ciphertext,
+        }
+    }
+
+    /// Decrypt the payload with the key of `release` and check its tag.
+    pub fn open<F: PRP>(&self, release: &EmbargoRelease) -> Result<Vec<u8>> {
+        ensure!(release.id == self.id, "Release is for another embargo.");
+        let mut s = embargo_spongos::<F>(release);
+        let payload = s.decrypt_n(&self.ciphertext);
+        s.commit();
+        let mut tag = NBytes::<U32>::default();
+        s.squeeze(tag.as_mut());
+        ensure!(tag == self.tag, "Embargoed payload tag mismatch.");
+        Ok(payload)
+    }
+
+    /// Whether the payload is due at milestone index `milestone`.
+    pub fn is_due(&self, milestone: u32) -> bool {
+        self.release_at.map_or(false, |release_at| release_at <= milestone)
+    }
+
+    /// Encode the payload as `id || release_at || tag || ciphertext`, `release_at` is a
+    /// big-endian u32 with 0 for no milestone.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes =
+            Vec::with_capacity(EMBARGO_ID_SIZE + EMBARGO_MILESTONE_SIZE + EMBARGO_FIELD_SIZE + self.ciphertext.len());
+        bytes.extend_from_slice(self.id.as_slice());
+        bytes.extend_from_slice(&self.release_at.unwrap_or(0).to_be_bytes());
+        bytes.extend_from_slice(self.tag.as_slice());
+        bytes.extend_from_slice(&self.ciphertext);
+        bytes
+    }
+
+    /// Decode the payload encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(
+            bytes.len() >= EMBARGO_ID_SIZE + EMBARGO_MILESTONE_SIZE + EMBARGO_FIELD_SIZE,
+            "Bad embargoed payload length: {}",
+            bytes.len()
+        );
+        let (id, rest) = bytes.split_at(EMBARGO_ID_SIZE);
+        let (release_at, rest) = rest.split_at(EMBARGO_MILESTONE_SIZE);
+        let (tag, ciphertext) = rest.split_at(EMBARGO_FIELD_SIZE);
+        let release_at = u32::from_be_bytes([release_at[0], release_at[1], release_at[2], release_at[3]]);
+        Ok(Self {
+            id: *<&EmbargoId>::from(id),
+            release_at: if release_at == 0 { None } else { Some(release_at) },
+            tag: *<&NBytes<U32>>::from(tag),
+            ciphertext: ciphertext.to_vec(),
+        })
+    }
+}
+
+impl EmbargoRelease {
+    /// Encode the release as `id || key`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(EMBARGO_ID_SIZE + EMBARGO_FIELD_SIZE);
+        bytes.extend_from_slice(self.id.as_slice());
+        bytes.extend_from_slice(self.key.as_slice());
+        bytes
+    }
+
+    /// Decode the release encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(
+            bytes.len() == EMBARGO_ID_SIZE + EMBARGO_FIELD_SIZE,
+            "Bad embargo release length: {}",
+            bytes.len()
+        );
+        let (id, key) = bytes.split_at(EMBARGO_ID_SIZE);
+        Ok(Self {
+            id: *<&EmbargoId>::from(id),
+            key: *<&NBytes<U32>>::from(key),
+        })
+    }
+}
@@ -3,6 +3,15 @@ use core::fmt;
 
 use super::LinkedMessage;
 
+/// Ledger milestone confirming a message, as reported by the transport.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Milestone {
+    /// Milestone index.
+    pub index: u32,
+    /// Milestone timestamp as issued by the coordinator.
+    pub timestamp: u64,
+}
+
 /// Binary network Message representation.
 #[derive(Clone, PartialEq)]
 pub struct GenericMessage<AbsLink, Body> {
@@ -11,23 +20,46 @@ pub struct GenericMessage<AbsLink, Body> {
 
     /// Message body -- header + content.
     pub body: Body,
+
+    /// Milestone confirming the message, if known.
+    pub milestone: Option<Milestone>,
 }
 
 impl<AbsLink, Body> GenericMessage<AbsLink, Body> {
     pub fn new(link: AbsLink, body: Body) -> Self {
-        Self { link, body }
+        Self {
+            link,
+            body,
+            milestone: None,
+        }
+    }
+
+    pub fn with_milestone(mut self, milestone: Option<Milestone>) -> Self {
+        self.milestone = milestone;
+        self
+    }
+
+    /// Milestone confirming the message. Unlike timestamps declared in the payload, it can't be
+    /// chosen by the publisher and orders messages confirmed by different milestones.
+    pub fn timestamp(&self) -> Option<Milestone> {
+        self.milestone
     }
 
     pub fn map<B, F: FnOnce(Body) -> B>(self, f: F) -> GenericMessage<AbsLink, B> {
         GenericMessage {
             link: self.link,
             body: f(self.body),
+            milestone: self.milestone,
         }
     }
 
     pub fn map_err<B, F: FnOnce(Body) -> Result<B>>(self, f: F) -> Result<GenericMessage<AbsLink, B>> {
         let body = f(self.body)?;
-        Ok(GenericMessage { link: self.link, body })
+        Ok(GenericMessage {
+            link: self.link,
+            body,
+            milestone: self.milestone,
+        })
     }
 }
 
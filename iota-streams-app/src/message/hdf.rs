@@ -1,4 +1,5 @@
 use anyhow::{
+    bail,
     ensure,
     Result,
 };
@@ -6,6 +7,7 @@ use core::fmt;
 
 use iota_streams_core::{
     format,
+    prelude::Vec,
     sponge::prp::PRP,
 };
 use iota_streams_ddml::{
@@ -24,12 +26,65 @@ use super::*;
 
 pub const FLAG_BRANCHING_MASK: u8 = 1;
 
+/// Maximum size in bytes of the application-specific header extension.
+pub const MAX_HDF_EXT_SIZE: usize = 256;
+
+/// Channel flags bits holding the width of relative links as an index into `LINK_WIDTHS`.
+pub const FLAG_LINK_WIDTH_MASK: u8 = 0b110;
+const FLAG_LINK_WIDTH_SHIFT: u8 = 1;
+
+/// Channel flag selecting counter sequencing: message identifiers are derived from the channel
+/// address and a counter of the publisher, no sequence messages are sent.
+pub const FLAG_COUNTER_SEQUENCING_MASK: u8 = 0b1_0000;
+
+/// Channel flag selecting hybrid key encapsulation: session keys in keyloads are protected by
+/// both X25519 and ML-KEM keys of the recipients.
+pub const FLAG_HYBRID_KEM_MASK: u8 = 0b10_0000;
+
+/// Channel flag selecting nonce-misuse-resistant masking: masked payloads of packets are
+/// preceded by a synthetic IV derived from the payload.
+pub const FLAG_SIV_MASK: u8 = 0b100_0000;
+
+/// Channel flag set when message identifiers are derived with a hash function other than the
+/// link generator's default; the announcement then carries the identifier of the hash function.
+pub const FLAG_MSGID_HASH_MASK: u8 = 0b1000_0000;
+
+/// Maximal message priority; priority occupies two bits next to the content type, higher
+/// values are more urgent and 0 is the default.
+pub const MAX_PRIORITY: u8 = 3;
+
+/// Relative link widths in bytes that can be carried in channel flags. Link types with
+/// fixed-width relative links (reporting width `0`) use the first entry.
+pub const LINK_WIDTHS: [usize; 3] = [12, 24, 32];
+
+/// Set relative link width bits in channel `flags`.
+pub fn flags_with_link_width(flags: u8, width: usize) -> Result<u8> {
+    let idx = if width == 0 {
+        0
+    } else {
+        match LINK_WIDTHS.iter().position(|w| *w == width) {
+            Some(idx) => idx as u8,
+            None => bail!("Unsupported link width: {}.", width),
+        }
+    };
+    Ok((flags & !FLAG_LINK_WIDTH_MASK) | (idx << FLAG_LINK_WIDTH_SHIFT))
+}
+
+/// Get relative link width from channel `flags`.
+pub fn link_width_from_flags(flags: u8) -> Result<usize> {
+    let idx = ((flags & FLAG_LINK_WIDTH_MASK) >> FLAG_LINK_WIDTH_SHIFT) as usize;
+    ensure!(idx < LINK_WIDTHS.len(), "Bad link width flags: {}.", flags);
+    Ok(LINK_WIDTHS[idx])
+}
+
 #[derive(Clone)]
 pub struct HDF<Link> {
     pub encoding: Uint8,
     pub version: Uint8,
     // message type is 4 bits
     pub content_type: u8,
+    // priority is 2 bits, version 1.1 and later
+    pub priority: u8,
     // payload length is 10 bits
     pub payload_length: usize,
     pub frame_type: Uint8,
@@ -37,22 +92,36 @@ pub struct HDF<Link> {
     pub payload_frame_count: u32,
     pub link: Link,
     pub seq_num: Uint64,
+    // application-specific extension, version 1.1 and later
+    pub ext: Bytes,
 }
 
 impl<Link> HDF<Link> {
     pub fn new(link: Link) -> Self {
         Self {
             encoding: UTF8,
-            version: STREAMS_1_VER,
+            version: STREAMS_VER,
             content_type: 0,
+            priority: 0,
             payload_length: 0,
             frame_type: HDF_ID,
             payload_frame_count: 0,
             link: link,
             seq_num: Uint64(0),
+            ext: Bytes::new(),
         }
     }
 
+    pub fn with_version(mut self, version: Uint8) -> Result<Self> {
+        ensure!(is_supported_version(version), "Message version not supported: {}", version);
+        self.version = version;
+        Ok(self)
+    }
+
+    pub fn get_version(&self) -> u8 {
+        self.version.0
+    }
+
     pub fn with_content_type(mut self, content_type: u8) -> Result<Self> {
         ensure!(content_type < 0x10, "Content type out of range: {}", content_type);
         self.content_type = content_type;
@@ -63,6 +132,18 @@ impl<Link> HDF<Link> {
         self.content_type
     }
 
+    /// Set delivery priority of the message, see `MAX_PRIORITY`. The priority is
+    /// authenticated together with the content type.
+    pub fn with_priority(mut self, priority: u8) -> Result<Self> {
+        ensure!(priority <= MAX_PRIORITY, "Priority out of range: {}", priority);
+        self.priority = priority;
+        Ok(self)
+    }
+
+    pub fn get_priority(&self) -> u8 {
+        self.priority
+    }
+
     pub fn with_payload_length(mut self, payload_length: usize) -> Result<Self> {
         ensure!(
             payload_length < 0x0400,
@@ -100,6 +181,22 @@ impl<Link> HDF<Link> {
         self.seq_num.0
     }
 
+    /// Set application-specific header extension. The extension is authenticated together
+    /// with the rest of the message and can be inspected once the header is parsed.
+    pub fn with_ext(mut self, ext: Vec<u8>) -> Result<Self> {
+        ensure!(
+            ext.len() <= MAX_HDF_EXT_SIZE,
+            "Header extension size out of range: {}",
+            ext.len()
+        );
+        self.ext = Bytes(ext);
+        Ok(self)
+    }
+
+    pub fn get_ext(&self) -> &[u8] {
+        &self.ext.0
+    }
+
     pub fn new_with_fields(link: Link, content_type: u8, payload_length: usize, seq_num: u64) -> Result<Self> {
         ensure!(content_type < 0x10, "Content type out of range: {}", content_type);
         ensure!(
@@ -109,13 +206,15 @@ impl<Link> HDF<Link> {
         );
         Ok(Self {
             encoding: UTF8,
-            version: STREAMS_1_VER,
+            version: STREAMS_VER,
             content_type,
+            priority: 0,
             payload_length,
             frame_type: HDF_ID,
             payload_frame_count: 0,
             link: link,
             seq_num: Uint64(seq_num),
+            ext: Bytes::new(),
         })
     }
 }
@@ -124,13 +223,15 @@ impl<Link: Default> Default for HDF<Link> {
     fn default() -> Self {
         Self {
             encoding: UTF8,
-            version: STREAMS_1_VER,
+            version: STREAMS_VER,
             content_type: 0,
+            priority: 0,
             payload_length: 0,
             frame_type: HDF_ID,
             payload_frame_count: 0,
             link: Link::default(),
             seq_num: Uint64(0),
+            ext: Bytes::new(),
         }
     }
 }
@@ -142,10 +243,11 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{{encoding: {:?}, version: {:?}, content_type: {:?}, payload_length: {:?}}}",
+            "{{encoding: {:?}, version: {:?}, content_type: {:?}, priority: {:?}, payload_length: {:?}}}",
             self.encoding,
             self.version,
             self.get_content_type(),
+            self.get_priority(),
             self.get_payload_length()
         )
     }
@@ -159,14 +261,29 @@ where
     fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
         let content_type_and_payload_length = NBytes::<U2>::default();
         let payload_frame_count = NBytes::<U3>::default();
-        ctx.absorb(self.encoding)?
+        ctx.label("encoding")?
+            .absorb(self.encoding)?
+            .label("version")?
             .absorb(self.version)?
+            .label("content_type_and_payload_length")?
             .skip(&content_type_and_payload_length)?
-            .absorb(External(Uint8(self.content_type << 4)))?
+            .absorb(External(Uint8(self.content_type << 4 | self.priority << 2)))?
+            .label("frame_type")?
             .absorb(self.frame_type)?
+            .label("payload_frame_count")?
             .skip(&payload_frame_count)?
-            .absorb(External(Fallback(&self.link)))?
-            .skip(self.seq_num)?;
+            .absorb(External(Fallback(&self.link)))?;
+        match self.version {
+            STREAMS_1_VER => {
+                ensure!(self.ext.0.is_empty(), "Header extension is not supported by version {}", self.version);
+                ensure!(self.priority == 0, "Priority is not supported by version {}", self.version);
+                ctx.label("seq_num")?.skip(self.seq_num)?
+            }
+            _ if is_supported_version(self.version) => ctx.section(|ctx| {
+                ctx.label("seq_num")?.skip(self.seq_num)?.label("ext")?.skip(&self.ext)
+            })?,
+            _ => bail!("Message version not supported: {}", self.version),
+        };
         Ok(ctx)
     }
 }
@@ -184,7 +301,7 @@ where
         let content_type_and_payload_length = {
             let mut nbytes = NBytes::<U2>::default();
             let v = nbytes.as_mut();
-            v[0] = (self.content_type << 4) | ((self.payload_length >> 8) as u8 & 0x03);
+            v[0] = (self.content_type << 4) | (self.priority << 2) | ((self.payload_length >> 8) as u8 & 0x03);
             v[1] = self.payload_length as u8;
             nbytes
         };
@@ -201,11 +318,21 @@ where
         ctx.absorb(self.encoding)?
             .absorb(self.version)?
             .skip(&content_type_and_payload_length)?
-            .absorb(External(Uint8(self.content_type << 4)))?
+            .absorb(External(Uint8(self.content_type << 4 | self.priority << 2)))?
             .absorb(self.frame_type)?
             .skip(&payload_frame_count)?
-            .absorb(External(Fallback(&self.link)))?
-            .skip(self.seq_num)?;
+            .absorb(External(Fallback(&self.link)))?;
+        match self.version {
+            STREAMS_1_VER => {
+                ensure!(self.ext.0.is_empty(), "Header extension is not supported by version {}", self.version);
+                ensure!(self.priority == 0, "Priority is not supported by version {}", self.version);
+                ctx.skip(self.seq_num)?
+            }
+            _ if is_supported_version(self.version) => ctx.section(|ctx| {
+                ctx.skip(self.seq_num)?.skip(&self.ext)
+            })?,
+            _ => bail!("Message version not supported: {}", self.version),
+        };
         Ok(ctx)
     }
 }
@@ -226,21 +353,22 @@ where
         ctx.absorb(&mut self.encoding)?
             .absorb(&mut self.version)?
             .guard(
-                self.version == STREAMS_1_VER,
-                &format!(
-                    "Message version not supported: expected {}, found {}.",
-                    STREAMS_1_VER, self.version
-                ),
+                is_supported_version(self.version),
+                &format!("Message version not supported: {}.", self.version),
             )?
             .skip(&mut content_type_and_payload_length)?;
         {
             let v = content_type_and_payload_length.as_ref();
-            ensure!(0 == v[0] & 0x0c, "Bad reserved bits");
             self.content_type = v[0] >> 4;
+            self.priority = (v[0] >> 2) & MAX_PRIORITY;
+            ensure!(
+                self.priority == 0 || self.version != STREAMS_1_VER,
+                "Bad reserved bits"
+            );
             self.payload_length = (((v[0] & 0x03) as usize) << 8) | (v[1] as usize);
         }
 
-        ctx.absorb(External(Uint8(self.content_type << 4)))?
+        ctx.absorb(External(Uint8(self.content_type << 4 | self.priority << 2)))?
             .absorb(&mut self.frame_type)?
             .guard(
                 self.frame_type == HDF_ID,
@@ -260,7 +388,23 @@ where
             self.payload_frame_count = u32::from_be_bytes(x);
         }
 
-        ctx.absorb(External(Fallback(&self.link)))?.skip(&mut self.seq_num)?;
+        ctx.absorb(External(Fallback(&self.link)))?;
+        let seq_num = &mut self.seq_num;
+        let ext = &mut self.ext;
+        match self.version {
+            STREAMS_1_VER => ctx.skip(seq_num)?,
+            _ if is_supported_version(self.version) => ctx.section(|ctx| {
+                ctx.skip(&mut *seq_num)?;
+                // Extension is always wrapped, headers wrapped before it was introduced end
+                // with `seq_num` and are read with an empty extension.
+                if !ctx.stream.is_empty() {
+                    ctx.skip(&mut *ext)?
+                        .guard(ext.0.len() <= MAX_HDF_EXT_SIZE, "Header extension size out of range")?;
+                }
+                Ok(ctx)
+            })?,
+            _ => bail!("Message version not supported: {}", self.version),
+        };
 
         Ok(ctx)
     }
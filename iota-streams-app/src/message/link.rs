@@ -1,3 +1,7 @@
+use anyhow::{
+    ensure,
+    Result,
+};
 use core::fmt;
 
 use iota_streams_core_edsig::signature::ed25519;
@@ -20,6 +24,17 @@ pub trait HasLink: Sized + Default + Clone + Eq {
 
     /// Construct absolute link from base and relative parts.
     fn from_base_rel(base: &Self::Base, rel: &Self::Rel) -> Self;
+
+    /// Width in bytes of a relative link, `0` if relative links have fixed width.
+    fn rel_width(_rel: &Self::Rel) -> usize {
+        0
+    }
+
+    /// Construct default relative link of the given width. Relative links are not
+    /// self-delimiting, a value of the right width is needed to read one from a stream.
+    fn default_rel_with_width(_width: usize) -> Self::Rel {
+        Self::Rel::default()
+    }
 }
 
 /// Represents an input state for message identifier generation.
@@ -97,6 +112,12 @@ impl<Link: fmt::Debug> fmt::Debug for Cursor<Link> {
 }
 
 /// Abstraction-helper to generate message links.
+///
+/// Link generator together with `HasLink` implementation defines addressing of messages.
+/// Tangle addressing is implemented in `transport::tangle`; other link schemes (eg. URL-based
+/// or UUID-based links) can be used with a custom `Link` type and link generator.
+/// Generated links must be deterministic: Subscribers derive links of the expected messages
+/// with their own instance of link generator reset to the announcement link.
 pub trait LinkGenerator<Link: HasLink>: Default {
     /// Used by Author to generate a new application instance: channels address and announcement message identifier
     fn gen(&mut self, pk: &ed25519::PublicKey, idx: u64);
@@ -113,6 +134,18 @@ pub trait LinkGenerator<Link: HasLink>: Default {
     /// Used by users to pseudo-randomly generate a new message link from a cursor
     fn link_from(&self, pk: &ed25519::PublicKey, cursor: Cursor<&<Link as HasLink>::Rel>) -> Link;
 
+    /// Identifier of the hash function deriving message links, carried in the announcement
+    /// (see `hdf::FLAG_MSGID_HASH_MASK`); `0` is the link generator's default.
+    fn hash_id(&self) -> u8 {
+        0
+    }
+
+    /// Used by Subscriber to derive message links with the hash function announced by Author
+    fn set_hash_id(&mut self, id: u8) -> Result<()> {
+        ensure!(id == 0, "Unsupported msgid hash: {}.", id);
+        Ok(())
+    }
+
     /// Derive a new link and construct a header with given content type.
     fn uniform_header_from(
         &self,
@@ -22,3 +22,13 @@ mod preparsed;
 pub use preparsed::*;
 mod unwrapped;
 pub use unwrapped::*;
+mod pool;
+pub use pool::*;
+#[cfg(not(feature = "async"))]
+mod offload;
+#[cfg(not(feature = "async"))]
+pub use offload::*;
+mod embargo;
+pub use embargo::*;
+mod disclosure;
+pub use disclosure::*;
@@ -0,0 +1,96 @@
+//! Payloads offloaded to a `BlobStore`.
+//!
+//! The payload is encrypted with a one-time key and stored in the blob store, the Streams
+//! message carries the reference: blob identifier, hash of the encrypted blob and the key.
+//! When the reference is sent as a masked payload, only recipients of the message can
+//! decrypt the blob, the blob store only ever sees ciphertext.
+
+use anyhow::{
+    ensure,
+    Result,
+};
+
+use iota_streams_core::{
+    prelude::{
+        typenum::U32,
+        Vec,
+    },
+    sponge::{
+        prp::PRP,
+        spongos::{
+            hash_data,
+            Spongos,
+        },
+    },
+};
+use iota_streams_ddml::types::{
+    Bytes,
+    NBytes,
+};
+
+use crate::transport::BlobStore;
+
+/// Size of the encoded key and hash fields.
+const OFFLOADED_FIELD_SIZE: usize = 32;
+
+/// Reference to an encrypted payload stored in a `BlobStore`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct OffloadedPayload {
+    /// Blob identifier as returned by the store.
+    pub id: Bytes,
+    /// Hash of the encrypted blob.
+    pub hash: NBytes<U32>,
+    /// Blob encryption key.
+    pub key: NBytes<U32>,
+}
+
+impl OffloadedPayload {
+    /// Encrypt `payload` with `key`, put it into `store` and return the reference to it.
+    pub fn offload<F: PRP, Store: BlobStore>(payload: &[u8], key: NBytes<U32>, store: &mut Store) -> Result<Self> {
+        let mut s = Spongos::<F>::init();
+        s.absorb(&key);
+        s.commit();
+        let blob = s.encrypt_n(payload);
+        let mut hash = NBytes::<U32>::default();
+        hash_data::<F>(&blob, hash.as_mut());
+        let id = store.put_blob(&blob)?;
+        Ok(Self { id: Bytes(id), hash, key })
+    }
+
+    /// Fetch the blob from `store`, check its hash and decrypt the payload.
+    pub fn fetch<F: PRP, Store: BlobStore>(&self, store: &mut Store) -> Result<Vec<u8>> {
+        let blob = store.get_blob(&self.id.0)?;
+        let mut hash = NBytes::<U32>::default();
+        hash_data::<F>(&blob, hash.as_mut());
+        ensure!(hash == self.hash, "Offloaded payload hash mismatch.");
+        let mut s = Spongos::<F>::init();
+        s.absorb(&self.key);
+        s.commit();
+        Ok(s.decrypt_n(&blob))
+    }
+
+    /// Encode the reference as `key || hash || id`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 * OFFLOADED_FIELD_SIZE + self.id.0.len());
+        bytes.extend_from_slice(self.key.as_slice());
+        bytes.extend_from_slice(self.hash.as_slice());
+        bytes.extend_from_slice(&self.id.0);
+        bytes
+    }
+
+    /// Decode the reference encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(
+            bytes.len() > 2 * OFFLOADED_FIELD_SIZE,
+            "Bad offloaded payload reference length: {}",
+            bytes.len()
+        );
+        let (key, rest) = bytes.split_at(OFFLOADED_FIELD_SIZE);
+        let (hash, id) = rest.split_at(OFFLOADED_FIELD_SIZE);
+        Ok(Self {
+            id: Bytes(id.to_vec()),
+            hash: *<&NBytes<U32>>::from(hash),
+            key: *<&NBytes<U32>>::from(key),
+        })
+    }
+}
@@ -112,7 +112,10 @@ where
     Content: ContentSizeof<F>,
 {
     fn sizeof<'c>(&self, mut ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
-        ctx.absorb(&self.frame_type)?.skip(&self.payload_frame_num)?;
+        ctx.label("frame_type")?
+            .absorb(&self.frame_type)?
+            .label("payload_frame_num")?
+            .skip(&self.payload_frame_num)?;
         self.content.sizeof(&mut ctx)?;
         Ok(ctx)
     }
@@ -0,0 +1,52 @@
+use core::cell::RefCell;
+
+use iota_streams_core::prelude::Vec;
+
+/// Default number of buffers kept by a pool.
+pub const DEFAULT_POOLED_BUFFERS: usize = 4;
+
+/// Pool of message buffers reused across wraps to avoid allocating a buffer per message.
+pub struct BufferPool {
+    buffers: RefCell<Vec<Vec<u8>>>,
+    max_buffers: usize,
+}
+
+impl BufferPool {
+    /// Pool keeping at most `max_buffers` buffers, `0` disables pooling.
+    pub fn new(max_buffers: usize) -> Self {
+        Self {
+            buffers: RefCell::new(Vec::new()),
+            max_buffers,
+        }
+    }
+
+    /// Take a buffer from the pool, or a new empty one if the pool is empty.
+    pub fn take(&self) -> Vec<u8> {
+        self.buffers.borrow_mut().pop().unwrap_or_default()
+    }
+
+    /// Return a buffer to the pool, it's dropped if the pool is full.
+    pub fn give(&self, mut buf: Vec<u8>) {
+        let mut buffers = self.buffers.borrow_mut();
+        if buffers.len() < self.max_buffers {
+            buf.clear();
+            buffers.push(buf);
+        }
+    }
+
+    /// Number of buffers in the pool.
+    pub fn len(&self) -> usize {
+        self.buffers.borrow().len()
+    }
+
+    /// Total capacity of buffers in the pool in bytes.
+    pub fn footprint(&self) -> usize {
+        self.buffers.borrow().iter().map(|buf| buf.capacity()).sum()
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_POOLED_BUFFERS)
+    }
+}
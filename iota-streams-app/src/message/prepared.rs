@@ -5,7 +5,10 @@ use anyhow::{
 use core::cell::Ref;
 
 use super::*;
-use iota_streams_core::sponge::prp::PRP;
+use iota_streams_core::{
+    prelude::Vec,
+    sponge::prp::PRP,
+};
 use iota_streams_ddml::{
     command::{
         sizeof,
@@ -49,6 +52,38 @@ where
     Content: ContentWrap<F, Store>,
 {
     pub fn wrap(&self) -> Result<WrappedMessage<F, Link>> {
+        let mut buf = Vec::new();
+        let wrapped = self.wrap_into(&mut buf)?;
+        Ok(WrappedMessage {
+            wrapped,
+            message: BinaryMessage::new(self.header.link.clone(), buf.into()),
+        })
+    }
+
+    /// Wrap into a buffer taken from `pool`, the buffer can be given back to the pool once
+    /// the message is sent.
+    pub fn wrap_pooled(&self, pool: &BufferPool) -> Result<WrappedMessage<F, Link>> {
+        let mut buf = pool.take();
+        let wrapped = self.wrap_into(&mut buf)?;
+        Ok(WrappedMessage {
+            wrapped,
+            message: BinaryMessage::new(self.header.link.clone(), buf.into()),
+        })
+    }
+
+    /// Fields of the header and content of the message as they would be wrapped, see
+    /// `sizeof::Context::with_schema`.
+    pub fn schema(&self) -> Result<Vec<sizeof::Field>> {
+        let mut ctx = sizeof::Context::<F>::with_schema();
+        self.header.sizeof(&mut ctx)?;
+        self.content.sizeof(&mut ctx)?;
+        Ok(ctx.into_schema())
+    }
+
+    /// Wrap into `buf`, reusing its allocation. The buffer is resized to the message size.
+    pub fn wrap_into(&self, buf: &mut Vec<u8>) -> Result<WrapState<F, Link>> {
+        #[cfg(feature = "otel")]
+        let _span = crate::otel::span("streams.wrap");
         let buf_size = {
             let mut ctx = sizeof::Context::<F>::new();
             self.header.sizeof(&mut ctx)?;
@@ -56,26 +91,22 @@ where
             ctx.get_size()
         };
 
-        let mut buf = vec![0; buf_size];
+        buf.clear();
+        buf.resize(buf_size, 0);
 
         let spongos = {
             let mut ctx = wrap::Context::new(&mut buf[..]);
             self.header.wrap(&*self.store, &mut ctx)?;
+            ctx.set_signature_context(&signature_context(self.header.version, self.header.content_type));
             self.content.wrap(&*self.store, &mut ctx)?;
             ensure!(ctx.stream.is_empty(), "OStream has not been exhausted.");
 
             ctx.spongos
         };
 
-        Ok(WrappedMessage {
-            wrapped: WrapState {
-                link: self.header.link.clone(),
-                spongos: spongos,
-            },
-            message: BinaryMessage {
-                link: self.header.link.clone(),
-                body: buf.into(),
-            },
+        Ok(WrapState {
+            link: self.header.link.clone(),
+            spongos,
         })
     }
 }
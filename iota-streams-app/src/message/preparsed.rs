@@ -3,7 +3,11 @@ use anyhow::Result;
 use core::fmt;
 
 use super::*;
-use iota_streams_core::sponge::prp::PRP;
+use iota_streams_core::{
+    prelude::Vec,
+    sponge::prp::PRP,
+};
+use iota_streams_core_edsig::signature::ed25519;
 use iota_streams_ddml::command::unwrap;
 
 /// Message context preparsed for unwrapping.
@@ -21,6 +25,15 @@ impl<'a, F, Link> PreparsedMessage<'a, F, Link> {
         self.header.get_content_type()
     }
 
+    pub fn version(&self) -> u8 {
+        self.header.get_version()
+    }
+
+    /// Application-specific header extension, available before the content is unwrapped.
+    pub fn header_ext(&self) -> &[u8] {
+        self.header.get_ext()
+    }
+
     pub fn unwrap<Store, Content>(
         mut self,
         store: &Store,
@@ -30,15 +43,44 @@ impl<'a, F, Link> PreparsedMessage<'a, F, Link> {
         Content: ContentUnwrap<F, Store>,
         F: PRP,
     {
+        #[cfg(feature = "otel")]
+        let _span = crate::otel::span("streams.unwrap");
         let mut pcf = pcf::PCF::default_with_content(content);
         pcf.unwrap(&store, &mut self.ctx)?;
         // Discard what's left of `self.ctx.stream`
         Ok(UnwrappedMessage {
+            version: self.header.version,
             link: self.header.link,
             pcf: pcf,
             spongos: self.ctx.spongos,
         })
     }
+
+    /// Unwrap without verifying Ed25519 signatures, the collected signatures must be verified
+    /// with `unwrap::verify_signatures` before the message is committed.
+    pub fn unwrap_unverified<Store, Content>(
+        mut self,
+        store: &Store,
+        content: Content,
+    ) -> Result<(UnwrappedMessage<F, Link, Content>, Vec<ed25519::PrehashedSignature>)>
+    where
+        Content: ContentUnwrap<F, Store>,
+        F: PRP,
+    {
+        self.ctx.collect_signatures();
+        #[cfg(feature = "otel")]
+        let _span = crate::otel::span("streams.unwrap");
+        let mut pcf = pcf::PCF::default_with_content(content);
+        pcf.unwrap(&store, &mut self.ctx)?;
+        let signatures = self.ctx.take_signatures();
+        let unwrapped = UnwrappedMessage {
+            version: self.header.version,
+            link: self.header.link,
+            pcf: pcf,
+            spongos: self.ctx.spongos,
+        };
+        Ok((unwrapped, signatures))
+    }
 }
 
 impl<'a, F, Link> Clone for PreparsedMessage<'a, F, Link>
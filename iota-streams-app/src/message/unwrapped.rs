@@ -6,15 +6,26 @@ use iota_streams_core::sponge::{
     prp::PRP,
     spongos::Spongos,
 };
-use iota_streams_ddml::link_store::LinkStore;
+use iota_streams_ddml::{
+    link_store::LinkStore,
+    types::Uint8,
+};
 
 /// Result of wrapping the message.
 pub struct UnwrappedMessage<F, Link, Content> {
     pub link: Link,
     pub pcf: PCF<Content>,
+    pub(crate) version: Uint8,
     pub(crate) spongos: Spongos<F>,
 }
 
+impl<F, Link, Content> UnwrappedMessage<F, Link, Content> {
+    /// Streams version the message was wrapped with.
+    pub fn version(&self) -> u8 {
+        self.version.0
+    }
+}
+
 impl<F, Link, Content> UnwrappedMessage<F, Link, Content>
 where
     F: PRP,
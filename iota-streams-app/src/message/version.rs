@@ -10,12 +10,93 @@
 //! Streams message syntax version is indicated as the first tryte in the trinary encoded message.
 //!
 //! Backwards compatibility of the Streams implementations is welcome and not mandatory.
+//! Messages of all the versions listed in `SUPPORTED_VERSIONS` can be unwrapped, new
+//! messages are wrapped with `STREAMS_VER` unless a different version is set explicitly.
 
-use iota_streams_ddml::types::Uint8;
+use iota_streams_core::prelude::Vec;
+use iota_streams_ddml::{
+    command::SIGNATURE_CONTEXT,
+    types::Uint8,
+};
 
 /// Streams version number.
 pub const STREAMS_1_VER: Uint8 = Uint8(0);
 
+/// Streams 1.1 version number. Header fields following the link are wrapped into a DDML
+/// section, new header fields can be appended to it without breaking older readers.
+pub const STREAMS_1_1_VER: Uint8 = Uint8(1);
+
+/// Streams 1.2 version number. The header is wrapped as in version 1.1, signatures in the
+/// content are followed by a post-quantum hash-based signature of the same message.
+pub const STREAMS_1_2_VER: Uint8 = Uint8(2);
+
+/// Streams 1.3 version number. Same as version 1.1, Ed25519 signatures are made with the
+/// domain-separated context returned by `signature_context`.
+pub const STREAMS_1_3_VER: Uint8 = Uint8(3);
+
+/// Streams 1.4 version number. Same as version 1.2 with signature contexts of version 1.3.
+pub const STREAMS_1_4_VER: Uint8 = Uint8(4);
+
+/// Streams 1.5 version number. Same as version 1.3, the Ed25519 signature of signed packets is
+/// followed by a secp256k1 co-signature of the same message.
+pub const STREAMS_1_5_VER: Uint8 = Uint8(5);
+
+/// Streams version used to wrap messages by default.
+pub const STREAMS_VER: Uint8 = STREAMS_1_3_VER;
+
+/// Streams versions that can be unwrapped.
+pub const SUPPORTED_VERSIONS: [Uint8; 6] = [
+    STREAMS_1_VER,
+    STREAMS_1_1_VER,
+    STREAMS_1_2_VER,
+    STREAMS_1_3_VER,
+    STREAMS_1_4_VER,
+    STREAMS_1_5_VER,
+];
+
+/// Check whether messages of Streams `version` can be wrapped and unwrapped.
+pub fn is_supported_version(version: Uint8) -> bool {
+    SUPPORTED_VERSIONS.contains(&version)
+}
+
+/// Check whether signatures in messages of Streams `version` are followed by post-quantum signatures.
+pub fn has_pq_signatures(version: Uint8) -> bool {
+    version == STREAMS_1_2_VER || version == STREAMS_1_4_VER
+}
+
+/// Check whether signatures in signed packets of Streams `version` are followed by secp256k1 co-signatures.
+pub fn has_cosignatures(version: Uint8) -> bool {
+    version == STREAMS_1_5_VER
+}
+
+/// Check whether keyloads of Streams `version` declare the rate and branch policies of their
+/// branch and the permissions of recipients, keyloads of version 1 don't.
+pub fn has_keyload_policies(version: Uint8) -> bool {
+    version != STREAMS_1_VER
+}
+
+/// Post-quantum version corresponding to `version`, ie. the one with the same signature contexts.
+pub fn pq_version(version: Uint8) -> Uint8 {
+    if version == STREAMS_1_3_VER {
+        STREAMS_1_4_VER
+    } else {
+        STREAMS_1_2_VER
+    }
+}
+
+/// Context of Ed25519ph signatures in messages of Streams `version` with `content_type`.
+/// Starting with version 1.3 the context is `SIGNATURE_CONTEXT || version || content_type`, so
+/// a signature of a Streams message can't be taken for a signature of another message type,
+/// version or protocol using the same key; older versions use `SIGNATURE_CONTEXT`.
+pub fn signature_context(version: Uint8, content_type: u8) -> Vec<u8> {
+    let mut context = SIGNATURE_CONTEXT.to_vec();
+    if version == STREAMS_1_3_VER || version == STREAMS_1_4_VER || version == STREAMS_1_5_VER {
+        context.push(version.0);
+        context.push(content_type);
+    }
+    context
+}
+
 /// Encoding Constants
 pub const UTF8: Uint8 = Uint8(0);
 
@@ -0,0 +1,51 @@
+//! Counters and histograms of Streams operations, recorded via the `metrics` facade.
+//!
+//! Metrics go to the recorder installed by the application, eg. `metrics-exporter-prometheus`
+//! serving them to Prometheus; without an installed recorder recording is a no-op. Sends,
+//! receives and transport latency are recorded by `transport::MeteredTransport`, PoW time by the
+//! client transport and unwrap failures by Channels users.
+
+use std::time::Duration;
+
+/// Messages sent, labeled with `result`.
+pub const MESSAGES_SENT: &str = "streams_messages_sent_total";
+/// Messages received.
+pub const MESSAGES_RECEIVED: &str = "streams_messages_received_total";
+/// Failed transport operations, labeled with `op`.
+pub const TRANSPORT_ERRORS: &str = "streams_transport_errors_total";
+/// Received messages that could not be unwrapped.
+pub const UNWRAP_FAILURES: &str = "streams_unwrap_failures_total";
+/// Duration of local proof-of-work per transaction in seconds.
+pub const POW_SECONDS: &str = "streams_pow_seconds";
+/// Duration of transport operations in seconds, labeled with `op`.
+pub const TRANSPORT_LATENCY_SECONDS: &str = "streams_transport_latency_seconds";
+
+/// Record a sent message.
+pub fn record_send() {
+    metrics::increment_counter!(MESSAGES_SENT);
+}
+
+/// Record `n` received messages.
+pub fn record_recv(n: usize) {
+    metrics::counter!(MESSAGES_RECEIVED, n as u64);
+}
+
+/// Record a failed transport operation `op` ("send" or "recv").
+pub fn record_transport_error(op: &'static str) {
+    metrics::increment_counter!(TRANSPORT_ERRORS, "op" => op);
+}
+
+/// Record a message that could not be unwrapped.
+pub fn record_unwrap_failure() {
+    metrics::increment_counter!(UNWRAP_FAILURES);
+}
+
+/// Record the duration of proof-of-work of a transaction.
+pub fn record_pow_time(duration: Duration) {
+    metrics::histogram!(POW_SECONDS, duration);
+}
+
+/// Record the duration of transport operation `op` ("send" or "recv").
+pub fn record_transport_latency(op: &'static str, duration: Duration) {
+    metrics::histogram!(TRANSPORT_LATENCY_SECONDS, duration, "op" => op);
+}
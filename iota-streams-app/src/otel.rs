@@ -0,0 +1,102 @@
+//! OpenTelemetry spans of Streams operations and propagation of trace context in messages.
+//!
+//! Spans are started with the global tracer provider installed by the application: `streams.wrap`
+//! and `streams.unwrap` around message processing, `streams.pow` around local proof-of-work of a
+//! transaction and `streams.send` / `streams.recv` around node round trips of the client
+//! transport. Spans are children of the current context.
+//!
+//! Trace context of the publisher is carried in the public payload of packets: the payload is
+//! prefixed with `TRACE_CONTEXT_MAGIC` and a W3C `traceparent`, see `inject_trace_context`.
+//! Consumers continue the trace with the context returned by `extract_trace_context`.
+
+use core::str;
+
+use opentelemetry::{
+    global,
+    trace::{
+        SpanContext,
+        SpanId,
+        TraceContextExt,
+        TraceFlags,
+        TraceId,
+        TraceState,
+        Tracer,
+    },
+    Context,
+};
+
+use iota_streams_core::prelude::Vec;
+
+/// Name of the tracer of Streams spans.
+pub const TRACER_NAME: &str = "iota-streams";
+
+/// Prefix of public payloads carrying trace context.
+pub const TRACE_CONTEXT_MAGIC: &[u8; 4] = b"\0otc";
+
+/// Size of a W3C `traceparent` of version 00: `00-<trace id>-<span id>-<flags>`.
+pub const TRACEPARENT_SIZE: usize = 55;
+
+/// Start span `name` as a child of the current context, the span ends when dropped.
+pub fn span(name: &'static str) -> global::BoxedSpan {
+    global::tracer(TRACER_NAME).start(name)
+}
+
+/// Span context of the current context, invalid if there is no active span.
+pub fn current_span_context() -> SpanContext {
+    Context::current().span().span_context().clone()
+}
+
+/// Prefix `public_payload` with trace context `span_context`. Invalid span contexts are not
+/// injected, the payload is returned as is.
+pub fn inject_trace_context(span_context: &SpanContext, public_payload: &[u8]) -> Vec<u8> {
+    if !span_context.is_valid() {
+        return public_payload.to_vec();
+    }
+    let traceparent = format!(
+        "00-{}-{}-{:02x}",
+        span_context.trace_id().to_hex(),
+        span_context.span_id().to_hex(),
+        span_context.trace_flags().to_u8()
+    );
+    let mut payload = Vec::with_capacity(TRACE_CONTEXT_MAGIC.len() + TRACEPARENT_SIZE + public_payload.len());
+    payload.extend_from_slice(TRACE_CONTEXT_MAGIC);
+    payload.extend_from_slice(traceparent.as_bytes());
+    payload.extend_from_slice(public_payload);
+    payload
+}
+
+/// Split trace context off `public_payload`. Returns the remote context to continue the trace
+/// with and the rest of the payload, or `None` if the payload carries no valid trace context.
+pub fn extract_trace_context(public_payload: &[u8]) -> Option<(Context, &[u8])> {
+    let header_size = TRACE_CONTEXT_MAGIC.len() + TRACEPARENT_SIZE;
+    if public_payload.len() < header_size || !public_payload.starts_with(TRACE_CONTEXT_MAGIC) {
+        return None;
+    }
+    let traceparent = str::from_utf8(&public_payload[TRACE_CONTEXT_MAGIC.len()..header_size]).ok()?;
+    let span_context = parse_traceparent(traceparent)?;
+    let cx = Context::current().with_remote_span_context(span_context);
+    Some((cx, &public_payload[header_size..]))
+}
+
+fn parse_traceparent(traceparent: &str) -> Option<SpanContext> {
+    let mut parts = traceparent.split('-');
+    let version = parts.next()?;
+    let trace_id = parts.next()?;
+    let span_id = parts.next()?;
+    let flags = parts.next()?;
+    if version != "00" || trace_id.len() != 32 || span_id.len() != 16 || flags.len() != 2 || parts.next().is_some() {
+        return None;
+    }
+    let span_context = SpanContext::new(
+        TraceId::from_hex(trace_id).ok()?,
+        SpanId::from_hex(span_id).ok()?,
+        TraceFlags::new(u8::from_str_radix(flags, 16).ok()?),
+        true,
+        TraceState::default(),
+    );
+    if span_context.is_valid() {
+        Some(span_context)
+    } else {
+        None
+    }
+}
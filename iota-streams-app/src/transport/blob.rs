@@ -0,0 +1,62 @@
+//! Storage for payloads offloaded from Streams messages, eg. IPFS.
+
+use super::*;
+
+use iota_streams_core::prelude::HashMap;
+
+/// Content-addressed blob storage.
+///
+/// Blobs are stored already encrypted, the store does not need to be trusted for confidentiality.
+#[cfg(not(feature = "async"))]
+pub trait BlobStore {
+    /// Store a blob and return the identifier it can be retrieved with, eg. IPFS CID.
+    fn put_blob(&mut self, blob: &[u8]) -> Result<Vec<u8>>;
+
+    /// Retrieve a blob by its identifier.
+    fn get_blob(&mut self, id: &[u8]) -> Result<Vec<u8>>;
+}
+
+#[cfg(not(feature = "async"))]
+impl<Store: BlobStore> BlobStore for Rc<RefCell<Store>> {
+    fn put_blob(&mut self, blob: &[u8]) -> Result<Vec<u8>> {
+        match (&*self).try_borrow_mut() {
+            Ok(mut store) => store.put_blob(blob),
+            Err(err) => Err(anyhow!("Blob store already borrowed: {}", err)),
+        }
+    }
+
+    fn get_blob(&mut self, id: &[u8]) -> Result<Vec<u8>> {
+        match (&*self).try_borrow_mut() {
+            Ok(mut store) => store.get_blob(id),
+            Err(err) => Err(anyhow!("Blob store already borrowed: {}", err)),
+        }
+    }
+}
+
+/// In-memory blob store, blobs are identified by their index.
+#[derive(Default)]
+pub struct BucketBlobStore {
+    bucket: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl BucketBlobStore {
+    pub fn new() -> Self {
+        Self { bucket: HashMap::new() }
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl BlobStore for BucketBlobStore {
+    fn put_blob(&mut self, blob: &[u8]) -> Result<Vec<u8>> {
+        let id = (self.bucket.len() as u64).to_be_bytes().to_vec();
+        self.bucket.insert(id.clone(), blob.to_vec());
+        Ok(id)
+    }
+
+    fn get_blob(&mut self, id: &[u8]) -> Result<Vec<u8>> {
+        self.bucket
+            .get(id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Blob not found."))
+    }
+}
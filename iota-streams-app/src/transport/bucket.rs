@@ -3,6 +3,7 @@ use crate::message::LinkedMessage;
 
 use iota_streams_core::prelude::HashMap;
 
+#[derive(Clone)]
 pub struct BucketTransport<Link, Msg> {
     bucket: HashMap<Link, Vec<Msg>>,
 }
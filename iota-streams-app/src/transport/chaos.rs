@@ -0,0 +1,187 @@
+//! Fault-injecting transport decorator for tests: drops, duplicates, delays and reorders messages.
+
+use super::*;
+
+/// Kinds and rates of misbehavior injected by `ChaosTransport`.
+#[derive(Clone, Copy, Debug)]
+pub struct ChaosOptions {
+    /// Probability in `[0, 1]` that a sent message is silently lost.
+    pub drop_rate: f64,
+    /// Probability in `[0, 1]` that a sent message is delivered twice.
+    pub duplicate_rate: f64,
+    /// Number of transport operations a sent message is held back before it is delivered.
+    pub latency: usize,
+    /// Maximal random extra delay in transport operations; messages sent close to each
+    /// other can be delivered in a different order.
+    pub reorder_window: usize,
+}
+
+impl Default for ChaosOptions {
+    fn default() -> Self {
+        Self {
+            drop_rate: 0.0,
+            duplicate_rate: 0.0,
+            latency: 0,
+            reorder_window: 0,
+        }
+    }
+}
+
+/// Transport decorator injecting faults into message delivery by the `inner` transport.
+///
+/// Faults are driven by a PRNG seeded at construction, the same seed and sequence of
+/// operations result in the same faults. Time is measured in transport operations, every
+/// send or receive advances it by one tick.
+pub struct ChaosTransport<Tsp, Msg> {
+    inner: Tsp,
+    opts: ChaosOptions,
+    rng: u64,
+    clock: usize,
+    pending: Vec<(usize, Msg)>,
+}
+
+impl<Tsp, Msg> ChaosTransport<Tsp, Msg> {
+    pub fn new(inner: Tsp, opts: ChaosOptions, seed: u64) -> Self {
+        Self {
+            inner,
+            opts,
+            // Xorshift state must be non-zero.
+            rng: seed | 1,
+            clock: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn options(&self) -> &ChaosOptions {
+        &self.opts
+    }
+
+    pub fn set_options(&mut self, opts: ChaosOptions) {
+        self.opts = opts;
+    }
+
+    pub fn inner(&self) -> &Tsp {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut Tsp {
+        &mut self.inner
+    }
+
+    /// Number of sent messages not yet delivered to the inner transport.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        // xorshift64*
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        self.rng.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn roll(&mut self, rate: f64) -> bool {
+        let x = (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64;
+        x < rate
+    }
+
+    fn delay(&mut self) -> usize {
+        let window = self.opts.reorder_window;
+        let extra = if window == 0 {
+            0
+        } else {
+            (self.next_u64() % (window as u64 + 1)) as usize
+        };
+        self.opts.latency + extra
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Tsp, Msg> ChaosTransport<Tsp, Msg>
+where
+    Tsp: TransportOptions,
+{
+    fn deliver_due<Link>(&mut self) -> Result<()>
+    where
+        Tsp: Transport<Link, Msg>,
+    {
+        let clock = self.clock;
+        let mut i = 0;
+        while i < self.pending.len() {
+            if self.pending[i].0 <= clock {
+                let (_, msg) = self.pending.remove(i);
+                self.inner.send_message(&msg)?;
+            } else {
+                i += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn tick<Link>(&mut self) -> Result<()>
+    where
+        Tsp: Transport<Link, Msg>,
+    {
+        self.clock += 1;
+        self.deliver_due::<Link>()
+    }
+
+    /// Deliver all held back messages to the inner transport.
+    pub fn flush<Link>(&mut self) -> Result<()>
+    where
+        Tsp: Transport<Link, Msg>,
+    {
+        for (_, msg) in core::mem::replace(&mut self.pending, Vec::new()) {
+            self.inner.send_message(&msg)?;
+        }
+        Ok(())
+    }
+}
+
+impl<Tsp: TransportOptions, Msg> TransportOptions for ChaosTransport<Tsp, Msg> {
+    type SendOptions = <Tsp as TransportOptions>::SendOptions;
+    fn get_send_options(&self) -> Self::SendOptions {
+        self.inner.get_send_options()
+    }
+    fn set_send_options(&mut self, opt: Self::SendOptions) {
+        self.inner.set_send_options(opt)
+    }
+
+    type RecvOptions = <Tsp as TransportOptions>::RecvOptions;
+    fn get_recv_options(&self) -> Self::RecvOptions {
+        self.inner.get_recv_options()
+    }
+    fn set_recv_options(&mut self, opt: Self::RecvOptions) {
+        self.inner.set_recv_options(opt)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link, Msg, Tsp> Transport<Link, Msg> for ChaosTransport<Tsp, Msg>
+where
+    Msg: Clone,
+    Tsp: Transport<Link, Msg>,
+{
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.tick::<Link>()?;
+        if self.roll(self.opts.drop_rate) {
+            return Ok(());
+        }
+        let copies = if self.roll(self.opts.duplicate_rate) { 2 } else { 1 };
+        for _ in 0..copies {
+            let delay = self.delay();
+            if delay == 0 {
+                self.inner.send_message(msg)?;
+            } else {
+                self.pending.push((self.clock + delay, msg.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        self.tick::<Link>()?;
+        self.inner.recv_messages(link)
+    }
+}
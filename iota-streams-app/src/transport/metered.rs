@@ -0,0 +1,116 @@
+//! Transport decorator recording metrics of the inner transport, see `crate::metrics`.
+
+use super::*;
+
+use std::time::Instant;
+
+use crate::metrics;
+
+/// Transport decorator counting messages sent and received by the `inner` transport, its
+/// failures and latency.
+pub struct MeteredTransport<Tsp> {
+    inner: Tsp,
+}
+
+impl<Tsp> MeteredTransport<Tsp> {
+    pub fn new(inner: Tsp) -> Self {
+        Self { inner }
+    }
+
+    pub fn inner(&self) -> &Tsp {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut Tsp {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> Tsp {
+        self.inner
+    }
+}
+
+fn record_send(started: Instant, result: &Result<()>) {
+    metrics::record_transport_latency("send", started.elapsed());
+    match result {
+        Ok(()) => metrics::record_send(),
+        Err(_) => metrics::record_transport_error("send"),
+    }
+}
+
+fn record_recv<Msg>(started: Instant, result: &Result<Vec<Msg>>) {
+    metrics::record_transport_latency("recv", started.elapsed());
+    match result {
+        Ok(msgs) => metrics::record_recv(msgs.len()),
+        Err(_) => metrics::record_transport_error("recv"),
+    }
+}
+
+impl<Tsp: TransportOptions> TransportOptions for MeteredTransport<Tsp> {
+    type SendOptions = <Tsp as TransportOptions>::SendOptions;
+    fn get_send_options(&self) -> Self::SendOptions {
+        self.inner.get_send_options()
+    }
+    fn set_send_options(&mut self, opt: Self::SendOptions) {
+        self.inner.set_send_options(opt)
+    }
+
+    type RecvOptions = <Tsp as TransportOptions>::RecvOptions;
+    fn get_recv_options(&self) -> Self::RecvOptions {
+        self.inner.get_recv_options()
+    }
+    fn set_recv_options(&mut self, opt: Self::RecvOptions) {
+        self.inner.set_recv_options(opt)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link, Msg, Tsp: Transport<Link, Msg>> Transport<Link, Msg> for MeteredTransport<Tsp> {
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.send_message(msg);
+        record_send(started, &result);
+        result
+    }
+
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        let started = Instant::now();
+        let result = self.inner.recv_messages(link);
+        record_recv(started, &result);
+        result
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait]
+impl<Link, Msg, Tsp> Transport<Link, Msg> for MeteredTransport<Tsp>
+where
+    Link: Send + Sync,
+    Msg: Send + Sync,
+    Tsp: Transport<Link, Msg> + Send,
+{
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.send_message(msg).await;
+        record_send(started, &result);
+        result
+    }
+
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        let started = Instant::now();
+        let result = self.inner.recv_messages(link).await;
+        record_recv(started, &result);
+        result
+    }
+
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let started = Instant::now();
+        let result = self.inner.recv_message(link).await;
+        metrics::record_transport_latency("recv", started.elapsed());
+        match &result {
+            Ok(_) => metrics::record_recv(1),
+            Err(_) => metrics::record_transport_error("recv"),
+        }
+        result
+    }
+}
@@ -131,5 +131,27 @@ impl<Link, Msg, Tsp: Transport<Link, Msg>> Transport<Link, Msg> for Rc<RefCell<T
 mod bucket;
 pub use bucket::BucketTransport;
 
+mod chaos;
+pub use chaos::{
+    ChaosOptions,
+    ChaosTransport,
+};
+
+mod priority;
+pub use priority::{
+    Prioritized,
+    PriorityTransport,
+};
+
+#[cfg(feature = "metrics")]
+mod metered;
+#[cfg(feature = "metrics")]
+pub use metered::MeteredTransport;
+
+mod blob;
+#[cfg(not(feature = "async"))]
+pub use blob::BlobStore;
+pub use blob::BucketBlobStore;
+
 #[cfg(feature = "tangle")]
 pub mod tangle;
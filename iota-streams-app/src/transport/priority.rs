@@ -0,0 +1,145 @@
+//! Transport decorator sending queued messages in order of their priority when the
+//! underlying transport is rate-limited.
+
+use super::*;
+use crate::message::hdf::MAX_PRIORITY;
+
+/// Messages with a delivery priority, see `hdf::MAX_PRIORITY`.
+pub trait Prioritized {
+    fn priority(&self) -> u8;
+}
+
+/// Transport decorator queueing sent messages per priority.
+///
+/// The inner transport is allowed to send `budget` messages, which is replenished with
+/// `refill`. Messages that do not fit into the budget wait in queues, higher priority
+/// queues are drained first and messages of the same priority are sent in order. An
+/// unlimited budget makes the decorator transparent.
+pub struct PriorityTransport<Tsp, Msg> {
+    inner: Tsp,
+    budget: Option<usize>,
+    queues: Vec<Vec<Msg>>,
+}
+
+impl<Tsp, Msg> PriorityTransport<Tsp, Msg> {
+    /// Create decorator allowing `budget` messages to be sent, `None` for no limit.
+    pub fn new(inner: Tsp, budget: Option<usize>) -> Self {
+        Self {
+            inner,
+            budget,
+            queues: (0..=MAX_PRIORITY).map(|_| Vec::new()).collect(),
+        }
+    }
+
+    pub fn inner(&self) -> &Tsp {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut Tsp {
+        &mut self.inner
+    }
+
+    /// Remaining number of messages the inner transport may send, `None` if not limited.
+    pub fn budget(&self) -> Option<usize> {
+        self.budget
+    }
+
+    /// Limit number of messages the inner transport may send, `None` for no limit.
+    /// Queued messages are sent on the next `pump`.
+    pub fn set_budget(&mut self, budget: Option<usize>) {
+        self.budget = budget;
+    }
+
+    /// Number of queued messages of each priority, lowest priority first.
+    pub fn queued(&self) -> Vec<usize> {
+        self.queues.iter().map(|q| q.len()).collect()
+    }
+
+    fn pop(&mut self) -> Option<(usize, Msg)> {
+        let p = self.queues.iter().rposition(|q| !q.is_empty())?;
+        Some((p, self.queues[p].remove(0)))
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Tsp, Msg> PriorityTransport<Tsp, Msg>
+where
+    Tsp: TransportOptions,
+{
+    /// Send queued messages, most urgent first, while budget allows.
+    /// A message the inner transport fails to send stays at the head of its queue.
+    pub fn pump<Link>(&mut self) -> Result<()>
+    where
+        Tsp: Transport<Link, Msg>,
+    {
+        while self.budget != Some(0) {
+            let (p, msg) = match self.pop() {
+                Some(queued) => queued,
+                None => break,
+            };
+            if let Err(e) = self.inner.send_message(&msg) {
+                self.queues[p].insert(0, msg);
+                return Err(e);
+            }
+            if let Some(budget) = self.budget.as_mut() {
+                *budget -= 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Allow `n` more messages to be sent and send queued ones.
+    pub fn refill<Link>(&mut self, n: usize) -> Result<()>
+    where
+        Tsp: Transport<Link, Msg>,
+    {
+        if let Some(budget) = self.budget.as_mut() {
+            *budget += n;
+        }
+        self.pump::<Link>()
+    }
+
+    /// Lift the limit and send all queued messages.
+    pub fn unlimit<Link>(&mut self) -> Result<()>
+    where
+        Tsp: Transport<Link, Msg>,
+    {
+        self.budget = None;
+        self.pump::<Link>()
+    }
+}
+
+impl<Tsp: TransportOptions, Msg> TransportOptions for PriorityTransport<Tsp, Msg> {
+    type SendOptions = <Tsp as TransportOptions>::SendOptions;
+    fn get_send_options(&self) -> Self::SendOptions {
+        self.inner.get_send_options()
+    }
+    fn set_send_options(&mut self, opt: Self::SendOptions) {
+        self.inner.set_send_options(opt)
+    }
+
+    type RecvOptions = <Tsp as TransportOptions>::RecvOptions;
+    fn get_recv_options(&self) -> Self::RecvOptions {
+        self.inner.get_recv_options()
+    }
+    fn set_recv_options(&mut self, opt: Self::RecvOptions) {
+        self.inner.set_recv_options(opt)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link, Msg, Tsp> Transport<Link, Msg> for PriorityTransport<Tsp, Msg>
+where
+    Msg: Clone + Prioritized,
+    Tsp: Transport<Link, Msg>,
+{
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let p = core::cmp::min(msg.priority(), MAX_PRIORITY) as usize;
+        self.queues[p].push(msg.clone());
+        self.pump::<Link>()
+    }
+
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        self.inner.recv_messages(link)
+    }
+}
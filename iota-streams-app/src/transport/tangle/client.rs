@@ -24,7 +24,7 @@ use bee_transaction::Vertex;
 use bee_transaction::bundled::{
     Address, Bundle, BundledTransactionBuilder as TransactionBuilder, BundledTransactionField,
     BundledTransaction as Transaction, Index, Nonce, OutgoingBundleBuilder, Payload, Tag, Timestamp, Value, 
-    PAYLOAD_TRIT_LEN, TAG_TRIT_LEN, ADDRESS_TRIT_LEN
+    PAYLOAD_TRIT_LEN, TAG_TRIT_LEN, ADDRESS_TRIT_LEN, TRANSACTION_TRIT_LEN
 };
 
 use iota_streams_core::prelude::{
@@ -34,15 +34,23 @@ use iota_streams_core::prelude::{
 };
 
 use crate::{
-    message::BinaryMessage,
+    message::{
+        BinaryMessage,
+        Milestone,
+    },
     transport::{
         tangle::*,
         *,
     },
 };
 
+use super::pow;
+
 const TRYTE_CHARS: &str = "9ABCDEFGHIJKLMNOPQRSTUVWXYZ";
 
+/// Largest attachment timestamp, (3^27 - 1) / 2.
+const MAX_ATTACHMENT_TIMESTAMP: u64 = 3_812_798_742_493;
+
 fn pad_tritbuf(
     n: usize,
     mut s: iota_ternary::TritBuf<iota_ternary::T1B1Buf>,
@@ -253,13 +261,31 @@ pub fn bundles_from_trytes(mut txs: Vec<Transaction>) -> Vec<Bundle> {
         .collect()
 }
 
+/// Bytes of `msgid` carried by transaction tags and the rest of msgids wider than `MSGID_SIZE`,
+/// carried at the start of bundle bodies.
+fn split_msgid(msgid: &MsgId) -> Result<(&[u8], &[u8])> {
+    ensure!(
+        msgid.size() >= MSGID_SIZE,
+        "Msgid is too short for tangle transactions: {} bytes.",
+        msgid.size()
+    );
+    Ok(msgid.as_ref().split_at(MSGID_SIZE))
+}
+
+/// Link of the message carried by transaction `tx`, msgids are `MSGID_SIZE` bytes wide.
+fn link_from_tx(tx: &Transaction) -> TangleAddress {
+    let appinst = AppInst::from(bytes_from_trits(tx.address().to_inner()).as_ref());
+    let msgid = MsgId::from(&bytes_from_trits(tx.tag().to_inner())[..MSGID_SIZE]);
+    TangleAddress { appinst, msgid }
+}
+
 /// Reconstruct Streams Message from bundle. The input bundle is not checked (for validity of
 /// the hash, consistency of indices, etc.). Checked bundles are returned by `bundles_from_trytes`.
+/// The link has a `MSGID_SIZE` bytes wide msgid, messages with wider msgids are reconstructed
+/// with `msg_from_bundle_at`.
 pub fn msg_from_bundle<F>(bundle: &Bundle) -> TangleMessage<F> {
     // TODO: Check bundle is not empty.
     let tx = bundle.head();
-    let appinst = AppInst::from(bytes_from_trits(tx.address().to_inner()).as_ref());
-    let msgid = MsgId::from(bytes_from_trits(tx.tag().to_inner()).as_ref());
     let mut body = Vec::new();
     for tx in bundle.into_iter() {
         let mut payload = bytes_from_trits(tx.payload().to_inner());
@@ -267,11 +293,30 @@ pub fn msg_from_bundle<F>(bundle: &Bundle) -> TangleMessage<F> {
         body.extend_from_slice(&payload);
     }
 
-    let binary = BinaryMessage::new(TangleAddress { appinst, msgid }, body.into());
+    let binary = BinaryMessage::new(link_from_tx(tx), body.into());
     // let timestamp: u64 = *(tx.timestamp() as *const iota::bundle::Timestamp) as *const u64;
     let timestamp: u64 = unsafe { core::mem::transmute(tx.timestamp().clone()) };
 
-    TangleMessage { binary, timestamp }
+    TangleMessage {
+        binary,
+        timestamp,
+        milestone: None,
+    }
+}
+
+/// Reconstruct Streams Message at `link` from bundle, see `msg_from_bundle`. The rest of msgids
+/// wider than `MSGID_SIZE` is taken from the start of the body, `None` if it doesn't match `link`.
+pub fn msg_from_bundle_at<F>(bundle: &Bundle, link: &TangleAddress) -> Option<TangleMessage<F>> {
+    let (_, msgid_rest) = split_msgid(&link.msgid).ok()?;
+    let mut msg = msg_from_bundle(bundle);
+    if !msgid_rest.is_empty() {
+        if !msg.binary.body.bytes.starts_with(msgid_rest) {
+            return None;
+        }
+        msg.binary.body.bytes.drain(..msgid_rest.len());
+        msg.binary.link = link.clone();
+    }
+    Some(msg)
 }
 
 /// As Streams Message are packed into a bundle, and different bundles can have the same hash
@@ -281,27 +326,29 @@ pub fn msg_from_bundle<F>(bundle: &Bundle) -> TangleMessage<F> {
 /// So this function also takes into account `address` and `tag` fields.
 /// As STREAMS Messages can have the same message id (ie. `tag`) it is advised that STREAMS Message
 /// bundles have distinct nonces and/or timestamps.
+///
+/// Transaction tags carry the first `MSGID_SIZE` bytes of the msgid, the rest of wider msgids
+/// is prepended to the body and checked by `msg_from_bundle_at`.
 pub fn msg_to_bundle<F>(
     msg: &BinaryMessage<F, TangleAddress>,
     timestamp: u64,
     trunk: Hash,
     branch: Hash,
 ) -> Result<Bundle> {
-    make_bundle(
-        msg.link.appinst.as_ref(),
-        msg.link.msgid.as_ref(),
-        &msg.body.bytes,
-        timestamp,
-        trunk,
-        branch,
-    )
+    let (tag, msgid_rest) = split_msgid(&msg.link.msgid)?;
+    let mut body = Vec::with_capacity(msgid_rest.len() + msg.body.bytes.len());
+    body.extend_from_slice(msgid_rest);
+    body.extend_from_slice(&msg.body.bytes);
+    make_bundle(msg.link.appinst.as_ref(), tag, &body, timestamp, trunk, branch)
 }
 
 #[derive(Clone, Copy)]
 pub struct SendTrytesOptions {
     pub depth: u8,
     pub min_weight_magnitude: u8,
+    /// Do proof-of-work locally instead of on the node, off by default.
     pub local_pow: bool,
+    /// Number of threads searching for a nonce in local proof-of-work.
     pub threads: usize,
 }
 
@@ -310,7 +357,7 @@ impl Default for SendTrytesOptions {
         Self {
             depth: 3,
             min_weight_magnitude: 14,
-            local_pow: true,
+            local_pow: false,
             threads: num_cpus::get(),
         }
     }
@@ -320,7 +367,15 @@ fn handle_client_result<T>(result: iota_client::Result<T>) -> Result<T> {
     result.map_err(|err| anyhow!("Failed iota_client: {}", err))
 }
 
-async fn get_bundles(client: &iota_client::Client, tx_address: Address, tx_tag: Tag) -> Result<Vec<Transaction>> {
+/// Hashes and transactions at `tx_address` and `tx_tag`. Transactions found in `cache` are not
+/// fetched again, the hashes are always asked from the node so that transactions attached later
+/// are found.
+async fn get_bundles(
+    client: &iota_client::Client,
+    cache: &mut MessageCache<Hash, Transaction>,
+    tx_address: Address,
+    tx_tag: Tag,
+) -> Result<(Vec<Hash>, Vec<Transaction>)> {
     let find_bundles = handle_client_result(
         client.find_transactions()
             .tags(&vec![tx_tag][..])
@@ -330,9 +385,145 @@ async fn get_bundles(client: &iota_client::Client, tx_address: Address, tx_tag:
     )?;
     ensure!(!find_bundles.hashes.is_empty(), "Transaction hashes not found.");
 
-    let get_resp = handle_client_result(client.get_trytes(&find_bundles.hashes).await)?;
-    ensure!(!get_resp.trytes.is_empty(), "Transactions not found.");
-    Ok(get_resp.trytes)
+    let mut cached: Vec<Option<Transaction>> = find_bundles.hashes.iter().map(|hash| cache.get(hash)).collect();
+    let missing: Vec<Hash> = find_bundles
+        .hashes
+        .iter()
+        .zip(cached.iter())
+        .filter(|(_, tx)| tx.is_none())
+        .map(|(hash, _)| *hash)
+        .collect();
+    if !missing.is_empty() {
+        let get_resp = handle_client_result(client.get_trytes(&missing).await)?;
+        ensure!(get_resp.trytes.len() == missing.len(), "Transactions not found.");
+        let mut fetched = missing.into_iter().zip(get_resp.trytes.into_iter());
+        for tx in cached.iter_mut().filter(|tx| tx.is_none()) {
+            let (hash, fetched_tx) = fetched.next().expect("A transaction is fetched for each missing one.");
+            cache.insert(hash, fetched_tx.clone());
+            *tx = Some(fetched_tx);
+        }
+    }
+    Ok((find_bundles.hashes, cached.into_iter().flatten().collect()))
+}
+
+/// Transactions of the bundle with head `head` chained by their trunks, head first, `None` if
+/// some of them are not in `txs`.
+fn bundle_from_head<'a>(
+    head: &'a (Hash, Transaction),
+    txs: &'a [(Hash, Transaction)],
+) -> Option<Vec<&'a (Hash, Transaction)>> {
+    let mut chain = vec![head];
+    loop {
+        let (_, tx) = chain[chain.len() - 1];
+        if tx.index().to_inner() == tx.last_index().to_inner() {
+            return Some(chain);
+        }
+        chain.push(txs.iter().find(|(hash, _)| hash == tx.trunk())?);
+    }
+}
+
+/// Number of milestones before the latest solid one searched for the milestone referencing a
+/// message, messages referenced earlier are returned without milestone.
+pub const MILESTONE_SEARCH_DEPTH: u32 = 64;
+
+/// Tag of the transactions of milestone `index`, the coordinator tags milestones with their
+/// index in balanced ternary.
+fn milestone_tag(index: u32) -> Result<Tag> {
+    let mut trits = iota_ternary::TritBuf::<iota_ternary::T1B1Buf>::zeros(TAG_TRIT_LEN);
+    let mut n = u64::from(index);
+    let mut i = 0;
+    while n != 0 {
+        let trit = match n % 3 {
+            0 => iota_ternary::Btrit::Zero,
+            1 => iota_ternary::Btrit::PlusOne,
+            _ => iota_ternary::Btrit::NegOne,
+        };
+        trits.set(i, trit);
+        n = (n + 1) / 3;
+        i += 1;
+    }
+    Tag::try_from_inner(trits).map_err(|e| anyhow!("Bad milestone tag: {:?}.", e))
+}
+
+/// Head hash and transactions, head first, of milestone `index` issued by `coordinator`.
+async fn get_milestone_bundle(
+    client: &iota_client::Client,
+    coordinator: &Address,
+    index: u32,
+) -> Result<(Hash, Vec<Transaction>)> {
+    let found = handle_client_result(
+        client.find_transactions()
+            .tags(&vec![milestone_tag(index)?][..])
+            .addresses(&vec![coordinator.clone()][..])
+            .send()
+            .await,
+    )?;
+    ensure!(!found.hashes.is_empty(), "Milestone {} not found.", index);
+    let get_resp = handle_client_result(client.get_trytes(&found.hashes).await)?;
+    let txs: Vec<(Hash, Transaction)> = found.hashes.into_iter().zip(get_resp.trytes.into_iter()).collect();
+    txs.iter()
+        .filter(|(_, tx)| *tx.index().to_inner() == 0)
+        .find_map(|head| bundle_from_head(head, &txs))
+        .map(|chain| (chain[0].0, chain.iter().map(|(_, tx)| tx.clone()).collect()))
+        .ok_or_else(|| anyhow!("Milestone {} is incomplete.", index))
+}
+
+/// Whether one of the transactions `heads` is referenced by the milestone with head `milestone`.
+async fn is_referenced(client: &iota_client::Client, heads: &[Hash], milestone: &Hash) -> Result<bool> {
+    let inclusion = handle_client_result(
+        client.get_inclusion_states()
+            .transactions(heads)
+            .tips(&[*milestone])
+            .send()
+            .await,
+    )?;
+    Ok(inclusion.states.iter().any(|referenced| *referenced))
+}
+
+/// Milestone referencing the bundle with head transactions `heads`, reattachments of a bundle
+/// have distinct heads, and the transactions of the milestone, head first. `None` if the bundle
+/// is not confirmed yet or was referenced more than `MILESTONE_SEARCH_DEPTH` milestones before
+/// the latest solid one.
+///
+/// Milestones reference everything their predecessors do: the bundle is referenced by the latest
+/// solid milestone and its predecessors down to the referencing one, which is found by bisecting
+/// the searched milestones with inclusion states against each of them.
+async fn get_milestone(client: &iota_client::Client, heads: &[Hash]) -> Result<Option<(Milestone, Vec<Transaction>)>> {
+    let info = handle_client_result(client.get_node_info().await)?;
+    if heads.is_empty() || !is_referenced(client, heads, &info.latest_solid_subtangle_milestone).await? {
+        return Ok(None);
+    }
+    let get_resp = handle_client_result(client.get_trytes(&[info.latest_solid_subtangle_milestone]).await)?;
+    let coordinator = get_resp
+        .trytes
+        .first()
+        .ok_or_else(|| anyhow!("Milestone transaction not found."))?
+        .address()
+        .clone();
+
+    // Milestone `low` doesn't reference the bundle unless it's the first one, milestone `high` does.
+    let mut high = info.latest_solid_subtangle_milestone_index as u32;
+    let mut low = high.saturating_sub(MILESTONE_SEARCH_DEPTH);
+    let (low_hash, _) = get_milestone_bundle(client, &coordinator, low).await?;
+    if is_referenced(client, heads, &low_hash).await? {
+        if low != 0 {
+            return Ok(None);
+        }
+        high = low;
+    }
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        let (mid_hash, _) = get_milestone_bundle(client, &coordinator, mid).await?;
+        if is_referenced(client, heads, &mid_hash).await? {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+
+    let (_, txs) = get_milestone_bundle(client, &coordinator, high).await?;
+    let timestamp: u64 = unsafe { core::mem::transmute(txs[0].timestamp().clone()) };
+    Ok(Some((Milestone { index: high, timestamp }, txs)))
 }
 
 async fn send_trytes(client: &iota_client::Client, opt: &SendTrytesOptions, txs: Vec<Transaction>) -> Result<Vec<Transaction>> {
@@ -347,7 +538,66 @@ async fn send_trytes(client: &iota_client::Client, opt: &SendTrytesOptions, txs:
     Ok(attached_txs)
 }
 
+/// Chain bundle transactions onto `trunk` and `branch` from the last transaction to the head,
+/// as the node would, and do proof-of-work for each of them.
+fn attach_to_tangle(txs: Vec<Transaction>, trunk: Hash, branch: Hash, opt: &SendTrytesOptions) -> Result<Vec<Transaction>> {
+    let attachment_ts = Timestamp::try_from_inner(chrono::Utc::now().timestamp_millis() as u64)
+        .map_err(|e| anyhow!("Bad attachment timestamp: {:?}.", e))?;
+    let mut attached = Vec::with_capacity(txs.len());
+    let mut next: Option<Hash> = None;
+    for tx in txs.into_iter().rev() {
+        let (tx_trunk, tx_branch) = match next {
+            Some(next) => (next, trunk.clone()),
+            None => (trunk.clone(), branch.clone()),
+        };
+        let tx = TransactionBuilder::new()
+            .with_payload(tx.payload().clone())
+            .with_address(tx.address().clone())
+            .with_value(tx.value().clone())
+            .with_obsolete_tag(tx.obsolete_tag().clone())
+            .with_timestamp(tx.timestamp().clone())
+            .with_index(tx.index().clone())
+            .with_last_index(tx.last_index().clone())
+            .with_tag(tx.tag().clone())
+            .with_attachment_ts(attachment_ts.clone())
+            .with_bundle(tx.bundle().clone())
+            .with_trunk(tx_trunk)
+            .with_branch(tx_branch)
+            .with_attachment_lbts(Timestamp::from_inner_unchecked(0))
+            .with_attachment_ubts(Timestamp::from_inner_unchecked(MAX_ATTACHMENT_TIMESTAMP))
+            .with_nonce(Nonce::zeros())
+            .build()
+            .map_err(|e| anyhow!("Failed to build transaction: {:?}.", e))?;
+
+        let mut trits = iota_ternary::TritBuf::<iota_ternary::T1B1Buf>::zeros(TRANSACTION_TRIT_LEN);
+        tx.as_trits_allocated(&mut trits);
+        #[cfg(feature = "metrics")]
+        let started = std::time::Instant::now();
+        #[cfg(feature = "otel")]
+        let span = crate::otel::span("streams.pow");
+        pow::pow(&mut trits, opt.min_weight_magnitude, opt.threads)?;
+        #[cfg(feature = "otel")]
+        drop(span);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_pow_time(started.elapsed());
+        next = Some(pow::hash(&trits)?);
+        attached.push(Transaction::from_trits(&trits).map_err(|e| anyhow!("Bad transaction trits: {:?}.", e))?);
+    }
+    attached.reverse();
+    Ok(attached)
+}
+
+async fn attach_local(client: &iota_client::Client, opt: &SendTrytesOptions, txs: Vec<Transaction>) -> Result<Vec<Transaction>> {
+    let tips = handle_client_result(client.get_transactions_to_approve().depth(opt.depth).send().await)?;
+    let attached = attach_to_tangle(txs, tips.trunk_transaction, tips.branch_transaction, opt)?;
+    handle_client_result(client.store_transactions(&attached).await)?;
+    handle_client_result(client.broadcast_transactions(&attached).await)?;
+    Ok(attached)
+}
+
 pub async fn async_send_message_with_options<F>(client: &iota_client::Client, msg: &TangleMessage<F>, opt: &SendTrytesOptions) -> Result<()> {
+    #[cfg(feature = "otel")]
+    let _span = crate::otel::span("streams.send");
     // TODO: Get trunk and branch hashes. Although, `send_trytes` should get these hashes.
     let trunk = Hash::zeros();
     let branch = Hash::zeros();
@@ -355,25 +605,95 @@ pub async fn async_send_message_with_options<F>(client: &iota_client::Client, ms
     // TODO: Get transactions from bundle without copying.
     let txs = bundle.into_iter().collect::<Vec<Transaction>>();
     // Ignore attached transactions.
-    send_trytes(client, opt, txs).await?;
+    if opt.local_pow {
+        attach_local(client, opt, txs).await?;
+    } else {
+        send_trytes(client, opt, txs).await?;
+    }
     Ok(())
 }
 
-pub async fn async_recv_messages<F>(client: &iota_client::Client, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
+/// Transaction address and tag of messages at `link`.
+fn address_and_tag(link: &TangleAddress) -> Result<(Address, Tag)> {
     let tx_address = Address::try_from_inner(pad_tritbuf(ADDRESS_TRIT_LEN, bytes_to_tritbuf(link.appinst.as_ref())))
         .map_err(|e| anyhow!("Bad tx address: {:?}.", e))?;
-    let tx_tag = Tag::try_from_inner(pad_tritbuf(TAG_TRIT_LEN, bytes_to_tritbuf(link.msgid.as_ref())))
+    let (tag, _) = split_msgid(&link.msgid)?;
+    let tx_tag = Tag::try_from_inner(pad_tritbuf(TAG_TRIT_LEN, bytes_to_tritbuf(tag)))
         .map_err(|e| anyhow!("Bad tx tag: {:?}.", e))?;
+    Ok((tx_address, tx_tag))
+}
 
-    match get_bundles(client, tx_address, tx_tag).await {
-        Ok(txs) => Ok(bundles_from_trytes(txs)
-            .into_iter()
-            .map(|b| msg_from_bundle(&b))
-            .collect()),
+pub async fn async_recv_messages<F>(client: &iota_client::Client, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
+    recv_messages_cached(client, &mut MessageCache::new(0), link).await
+}
+
+async fn recv_messages_cached<F>(
+    client: &iota_client::Client,
+    cache: &mut MessageCache<Hash, Transaction>,
+    link: &TangleAddress,
+) -> Result<Vec<TangleMessage<F>>> {
+    #[cfg(feature = "otel")]
+    let _span = crate::otel::span("streams.recv");
+    let (tx_address, tx_tag) = address_and_tag(link)?;
+
+    match get_bundles(client, cache, tx_address, tx_tag).await {
+        Ok((hashes, txs)) => {
+            // Bundle and head hashes of the bundles, the bundles rebuilt from `txs` lose the latter.
+            let heads: Vec<(Hash, Hash)> = hashes
+                .iter()
+                .zip(txs.iter())
+                .filter(|(_, tx)| *tx.index().to_inner() == 0)
+                .map(|(hash, tx)| (*tx.bundle(), *hash))
+                .collect();
+            let mut msgs = Vec::new();
+            for bundle in bundles_from_trytes(txs).iter() {
+                if let Some(mut msg) = msg_from_bundle_at(bundle, link) {
+                    let bundle_heads: Vec<Hash> = heads
+                        .iter()
+                        .filter(|(bundle_hash, _)| bundle_hash == bundle.head().bundle())
+                        .map(|(_, head)| *head)
+                        .collect();
+                    // Messages are returned without milestone if it can't be determined.
+                    msg.milestone = get_milestone(client, &bundle_heads)
+                        .await
+                        .unwrap_or(None)
+                        .map(|(milestone, _)| milestone);
+                    msgs.push(msg);
+                }
+            }
+            Ok(msgs)
+        }
         Err(_) => Ok(Vec::new()), // Just ignore the error?
     }
 }
 
+/// Fetch the inclusion proof of the message at `link`: the transactions of the first complete
+/// bundle found at the link and the milestone referencing them, see `get_milestone`. The proof
+/// has no milestone if the bundle is not confirmed yet.
+pub async fn async_fetch_inclusion_proof(client: &iota_client::Client, link: &TangleAddress) -> Result<InclusionProof> {
+    let (tx_address, tx_tag) = address_and_tag(link)?;
+    let (hashes, txs) = get_bundles(client, &mut MessageCache::new(0), tx_address, tx_tag).await?;
+    let txs: Vec<(Hash, Transaction)> = hashes.into_iter().zip(txs.into_iter()).collect();
+
+    // Follow trunks from each head transaction, reattachments of the same bundle share its
+    // transactions but not their hashes.
+    let chain = txs
+        .iter()
+        .filter(|(_, tx)| *tx.index().to_inner() == 0)
+        .find_map(|head| bundle_from_head(head, &txs))
+        .ok_or_else(|| anyhow!("No complete bundle found at {}.", link))?;
+    let (milestone, milestone_txs) = match get_milestone(client, &[chain[0].0]).await? {
+        Some((milestone, txs)) => (Some(milestone), txs),
+        None => (None, Vec::new()),
+    };
+    Ok(InclusionProof {
+        link: link.clone(),
+        transactions: chain.iter().map(|(_, tx)| tx_to_trytes(tx)).collect(),
+        milestone,
+        milestone_transactions: milestone_txs.iter().map(tx_to_trytes).collect(),
+    })
+}
+
 #[cfg(not(feature = "async"))]
 pub fn sync_send_message_with_options<F>(client: &iota_client::Client, msg: &TangleMessage<F>, opt: &SendTrytesOptions) -> Result<()> {
     block_on(async_send_message_with_options(client, msg, opt))
@@ -384,11 +704,316 @@ pub fn sync_recv_messages<F>(client: &iota_client::Client, link: &TangleAddress)
     block_on(async_recv_messages(client, link))
 }
 
+#[cfg(not(feature = "async"))]
+pub fn sync_fetch_inclusion_proof(client: &iota_client::Client, link: &TangleAddress) -> Result<InclusionProof> {
+    block_on(async_fetch_inclusion_proof(client, link))
+}
+
+fn tx_to_trytes(tx: &Transaction) -> String {
+    let mut trits = iota_ternary::TritBuf::<iota_ternary::T1B1Buf>::zeros(TRANSACTION_TRIT_LEN);
+    tx.as_trits_allocated(&mut trits);
+    trits
+        .encode::<iota_ternary::T3B1Buf>()
+        .as_trytes()
+        .iter()
+        .map(|tryte| char::from(*tryte))
+        .collect()
+}
+
+/// Check that transactions `txs`, head first, are at `address` and `tag`, form one bundle chained
+/// by their trunks and carry proof-of-work of `min_weight_magnitude`.
+fn check_bundle(txs: &[(Transaction, Hash)], address: &Address, tag: &Tag, min_weight_magnitude: u8) -> Result<()> {
+    let last_index = txs.len() - 1;
+    for (i, (tx, hash)) in txs.iter().enumerate() {
+        ensure!(
+            tx.address() == address && tx.tag() == tag,
+            "Transaction {} is not at the bundle address and tag.",
+            i
+        );
+        ensure!(
+            *tx.index().to_inner() == i && *tx.last_index().to_inner() == last_index,
+            "Bad index of transaction {}.",
+            i
+        );
+        ensure!(tx.bundle() == txs[0].0.bundle(), "Transaction {} is of another bundle.", i);
+        ensure!(
+            hash.as_trits()
+                .iter()
+                .rev()
+                .take(min_weight_magnitude as usize)
+                .all(|t| t == iota_ternary::Btrit::Zero),
+            "Transaction {} lacks proof-of-work.",
+            i
+        );
+        if i < last_index {
+            ensure!(tx.trunk() == &txs[i + 1].1, "Transaction {} is not chained to the next one.", i);
+        }
+    }
+    Ok(())
+}
+
+/// Parse transaction `trytes` and hash them.
+fn tx_from_trytes(trytes: &str) -> Result<(Transaction, Hash)> {
+    let trits = iota_ternary::TryteBuf::try_from_str(trytes)
+        .map_err(|e| anyhow!("Bad transaction trytes: {:?}.", e))?
+        .as_trits()
+        .encode::<iota_ternary::T1B1Buf>();
+    let tx = Transaction::from_trits(&trits).map_err(|e| anyhow!("Bad transaction trits: {:?}.", e))?;
+    Ok((tx, pow::hash(&trits)?))
+}
+
+/// Inclusion proof of a message: the transactions carrying it, head first, and the milestone
+/// confirming them with the transactions of the milestone bundle if confirmed.
+///
+/// `verify` checks offline that the transactions are at the message link, form one bundle
+/// chained by their trunks and carry proof-of-work, and returns the message they encode. The
+/// milestone bundle is checked the same way and must carry the milestone index and timestamp.
+/// The coordinator signature of the milestone and the approvals from the milestone down to the
+/// message transactions are node-provided evidence, they aren't checked.
+///
+/// Proofs are exchanged as text: the link `<appinst hex>:<msgid hex>`, the milestone
+/// `<index> <timestamp> <number of milestone transactions>` or `- -` if not confirmed, and the
+/// trytes of the transactions followed by the trytes of the milestone transactions, one per
+/// line.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct InclusionProof {
+    /// Address of the message
+    pub link: TangleAddress,
+    /// Trytes of the bundle transactions, head first
+    pub transactions: Vec<String>,
+    /// Milestone confirming the transactions, `None` if not confirmed
+    pub milestone: Option<Milestone>,
+    /// Trytes of the milestone bundle transactions, head first, empty if not confirmed
+    pub milestone_transactions: Vec<String>,
+}
+
+impl InclusionProof {
+    /// Proof of the message carried by `bundle`, eg. attached and confirmed by `milestone` with
+    /// its bundle. The link has a `MSGID_SIZE` bytes wide msgid, proofs of messages with wider
+    /// msgids are made with `from_message`.
+    pub fn from_bundle(bundle: &Bundle, milestone: Option<(Milestone, &Bundle)>) -> Self {
+        Self {
+            link: link_from_tx(bundle.head()),
+            transactions: bundle.into_iter().map(tx_to_trytes).collect(),
+            milestone: milestone.map(|(milestone, _)| milestone),
+            milestone_transactions: milestone
+                .map_or(Vec::new(), |(_, bundle)| bundle.into_iter().map(tx_to_trytes).collect()),
+        }
+    }
+
+    /// Proof of `msg` attached locally onto zero trunk and branch, as by a transport without a
+    /// node, eg. in tests. The proof has no milestone.
+    ///
+    /// # Arguments
+    /// * `min_weight_magnitude` - Proof-of-work done for the transactions
+    pub fn from_message<F>(msg: &TangleMessage<F>, min_weight_magnitude: u8) -> Result<Self> {
+        let bundle = msg_to_bundle(&msg.binary, msg.timestamp, Hash::zeros(), Hash::zeros())?;
+        let opt = SendTrytesOptions {
+            min_weight_magnitude,
+            ..SendTrytesOptions::default()
+        };
+        let txs = attach_to_tangle(bundle.into_iter().collect(), Hash::zeros(), Hash::zeros(), &opt)?;
+        Ok(Self {
+            link: msg.binary.link.clone(),
+            transactions: txs.iter().map(tx_to_trytes).collect(),
+            milestone: None,
+            milestone_transactions: Vec::new(),
+        })
+    }
+
+    /// Verify the proof and return the message it carries, see the type docs.
+    ///
+    /// # Arguments
+    /// * `min_weight_magnitude` - Proof-of-work the transactions must carry
+    pub fn verify<F>(&self, min_weight_magnitude: u8) -> Result<TangleMessage<F>> {
+        ensure!(!self.transactions.is_empty(), "Inclusion proof has no transactions.");
+        let (address, tag) = address_and_tag(&self.link)?;
+        let txs = self
+            .transactions
+            .iter()
+            .map(|trytes| tx_from_trytes(trytes))
+            .collect::<Result<Vec<_>>>()?;
+        check_bundle(&txs, &address, &tag, min_weight_magnitude)?;
+
+        match self.milestone {
+            Some(milestone) => {
+                ensure!(
+                    !self.milestone_transactions.is_empty(),
+                    "Inclusion proof milestone has no transactions."
+                );
+                let milestone_txs = self
+                    .milestone_transactions
+                    .iter()
+                    .map(|trytes| tx_from_trytes(trytes))
+                    .collect::<Result<Vec<_>>>()?;
+                let coordinator = milestone_txs[0].0.address().clone();
+                check_bundle(
+                    &milestone_txs,
+                    &coordinator,
+                    &milestone_tag(milestone.index)?,
+                    min_weight_magnitude,
+                )
+                .map_err(|e| anyhow!("Bad bundle of milestone {}: {}", milestone.index, e))?;
+                let timestamp: u64 = unsafe { core::mem::transmute(milestone_txs[0].0.timestamp().clone()) };
+                ensure!(
+                    timestamp == milestone.timestamp,
+                    "Bad timestamp of milestone {}: {}.",
+                    milestone.index,
+                    timestamp
+                );
+            }
+            None => ensure!(
+                self.milestone_transactions.is_empty(),
+                "Inclusion proof has milestone transactions but no milestone."
+            ),
+        }
+
+        let mut bundles = bundles_from_trytes(txs.into_iter().map(|(tx, _)| tx).collect());
+        ensure!(bundles.len() == 1, "Inclusion proof transactions are not one bundle.");
+        let mut msg = msg_from_bundle_at(&bundles.remove(0), &self.link)
+            .ok_or_else(|| anyhow!("Inclusion proof message is not at {}.", self.link))?;
+        msg.milestone = self.milestone;
+        Ok(msg)
+    }
+
+    /// Text encoding of the proof, see the type docs.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}:{}\n", self.link.appinst, self.link.msgid);
+        match self.milestone {
+            Some(milestone) => text.push_str(&format!(
+                "{} {} {}\n",
+                milestone.index,
+                milestone.timestamp,
+                self.milestone_transactions.len()
+            )),
+            None => text.push_str("- -\n"),
+        }
+        for trytes in self.transactions.iter().chain(self.milestone_transactions.iter()) {
+            text.push_str(trytes);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Parse a proof encoded with `to_text`.
+    pub fn from_text(text: &str) -> Result<Self> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let link = lines.next().ok_or_else(|| anyhow!("Inclusion proof link is missing."))?;
+        let (appinst, msgid) = link
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Bad inclusion proof link: {}.", link))?;
+        let link = TangleAddress::from_str(appinst, msgid).map_err(|_| anyhow!("Bad inclusion proof link: {}.", link))?;
+        let milestone = lines
+            .next()
+            .ok_or_else(|| anyhow!("Inclusion proof milestone is missing."))?;
+        let fields: Vec<&str> = milestone.split_whitespace().collect();
+        let (milestone, milestone_txs_count) = match fields[..] {
+            ["-", "-"] => (None, 0),
+            [index, timestamp, count] => (
+                Some(Milestone {
+                    index: index.parse().map_err(|_| anyhow!("Bad milestone index: {}.", index))?,
+                    timestamp: timestamp
+                        .parse()
+                        .map_err(|_| anyhow!("Bad milestone timestamp: {}.", timestamp))?,
+                }),
+                count
+                    .parse::<usize>()
+                    .map_err(|_| anyhow!("Bad number of milestone transactions: {}.", count))?,
+            ),
+            _ => return Err(anyhow!("Bad inclusion proof milestone: {}.", milestone)),
+        };
+        let mut transactions: Vec<String> = lines.map(ToString::to_string).collect();
+        ensure!(
+            milestone_txs_count <= transactions.len(),
+            "Inclusion proof lacks milestone transactions."
+        );
+        let milestone_transactions = transactions.split_off(transactions.len() - milestone_txs_count);
+        Ok(Self {
+            link,
+            transactions,
+            milestone,
+            milestone_transactions,
+        })
+    }
+}
+
+/// Transports fetching inclusion proofs of messages.
+#[cfg(not(feature = "async"))]
+pub trait InclusionProofs {
+    /// Fetch the inclusion proof of the message at `link`.
+    fn fetch_inclusion_proof(&mut self, link: &TangleAddress) -> Result<InclusionProof>;
+}
+
+#[cfg(not(feature = "async"))]
+impl<T: InclusionProofs> InclusionProofs for iota_streams_core::prelude::Rc<core::cell::RefCell<T>> {
+    fn fetch_inclusion_proof(&mut self, link: &TangleAddress) -> Result<InclusionProof> {
+        match (&*self).try_borrow_mut() {
+            Ok(mut tsp) => tsp.fetch_inclusion_proof(link),
+            Err(err) => Err(anyhow!("Transport already borrowed: {}", err)),
+        }
+    }
+}
+
+/// Default number of transactions kept in the cache of a client.
+pub const DEFAULT_MESSAGE_CACHE_CAPACITY: usize = 1024;
+
+/// Least recently used cache of fetched values by key.
+#[derive(Clone)]
+struct MessageCache<K, V> {
+    capacity: usize,
+    /// Entries from least to most recently used.
+    entries: Vec<(K, V)>,
+}
+
+impl<K: PartialEq, V: Clone> MessageCache<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::new(),
+        }
+    }
+
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        if self.entries.len() > self.capacity {
+            let excess = self.entries.len() - self.capacity;
+            self.entries.drain(..excess);
+        }
+    }
+
+    fn get(&mut self, key: &K) -> Option<V> {
+        let i = self.entries.iter().position(|(k, _)| k == key)?;
+        let entry = self.entries.remove(i);
+        let value = entry.1.clone();
+        self.entries.push(entry);
+        Some(value)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries.retain(|(k, _)| *k != key);
+        self.entries.push((key, value));
+        self.evict();
+    }
+}
+
 /// Stub type for iota_client::Client.  Removed: Copy, Default
+///
+/// Transactions of received messages are cached by hash, at most `DEFAULT_MESSAGE_CACHE_CAPACITY`
+/// transactions by default. Messages received again are assembled from cached transactions,
+/// only the hashes of the transactions at the link are asked from the node, so that messages
+/// attached later at the same link are found.
 #[derive(Clone)]
 pub struct Client {
     send_opt: SendTrytesOptions,
     client: iota_client::Client,
+    cache: MessageCache<Hash, Transaction>,
 }
 
 impl Default for Client {
@@ -396,7 +1021,8 @@ impl Default for Client {
     fn default() -> Self {
         Self {
             send_opt: SendTrytesOptions::default(),
-            client: iota_client::ClientBuilder::new().node("http://localhost:14265").unwrap().build().unwrap()
+            client: iota_client::ClientBuilder::new().node("http://localhost:14265").unwrap().build().unwrap(),
+            cache: MessageCache::new(DEFAULT_MESSAGE_CACHE_CAPACITY),
         }
     }
 }
@@ -406,7 +1032,8 @@ impl Client {
     pub fn new(options: SendTrytesOptions, client: iota_client::Client) -> Self {
         Self {
             send_opt: options,
-            client: client
+            client: client,
+            cache: MessageCache::new(DEFAULT_MESSAGE_CACHE_CAPACITY),
         }
     }
     
@@ -414,13 +1041,29 @@ impl Client {
     pub fn new_from_url(url: &str) -> Self {
         Self {
             send_opt: SendTrytesOptions::default(),
-            client: iota_client::ClientBuilder::new().node(url).unwrap().build().unwrap()
+            client: iota_client::ClientBuilder::new().node(url).unwrap().build().unwrap(),
+            cache: MessageCache::new(DEFAULT_MESSAGE_CACHE_CAPACITY),
         }
     }
 
     pub fn add_node(&mut self, url: &str) -> Result<bool> {
         self.client.add_node(url).map_err(|e| anyhow!("iota_client error {}:", e))
     }
+
+    pub fn cache_capacity(&self) -> usize {
+        self.cache.capacity
+    }
+
+    /// Cache at most `capacity` transactions, least recently used transactions are evicted first,
+    /// `0` disables caching.
+    pub fn set_cache_capacity(&mut self, capacity: usize) {
+        self.cache.set_capacity(capacity);
+    }
+
+    /// Forget cached transactions.
+    pub fn clear_cache(&mut self) {
+        self.cache.entries.clear();
+    }
 }
 
 impl TransportOptions for Client {
@@ -446,7 +1089,14 @@ impl<F> Transport<TangleAddress, TangleMessage<F>> for Client {
 
     /// Receive a message.
     fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
-        sync_recv_messages(&self.client, link)
+        block_on(recv_messages_cached(&self.client, &mut self.cache, link))
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl InclusionProofs for Client {
+    fn fetch_inclusion_proof(&mut self, link: &TangleAddress) -> Result<InclusionProof> {
+        sync_fetch_inclusion_proof(&self.client, link)
     }
 }
 
@@ -463,7 +1113,7 @@ where
 
     /// Receive a message.
     async fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
-        async_recv_messages(&self.client, link).await
+        recv_messages_cached(&self.client, &mut self.cache, link).await
     }
 
     async fn recv_message(&mut self, link: &TangleAddress) -> Result<TangleMessage<F>> {
@@ -476,3 +1126,137 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::BinaryBody;
+
+    #[test]
+    fn msg_bundle_wide_msgid() {
+        let appinst = AppInst::from(&[1_u8; APPINST_SIZE][..]);
+        let link = TangleAddress::new(appinst.clone(), MsgId::from(&[2_u8; 32][..]));
+        let body = vec![3_u8; 2 * PAYLOAD_BYTES];
+        let msg = BinaryMessage::<(), _>::new(link.clone(), BinaryBody::from(body.clone()));
+        let bundle = msg_to_bundle(&msg, 0, Hash::zeros(), Hash::zeros()).unwrap();
+
+        let unwrapped = msg_from_bundle_at::<()>(&bundle, &link).unwrap();
+        assert_eq!(link, unwrapped.binary.link);
+        assert!(unwrapped.binary.body.bytes.starts_with(&body));
+
+        let other = TangleAddress::new(appinst, MsgId::from(&[[2_u8; 12], [4_u8; 12]].concat()[..]));
+        assert!(msg_from_bundle_at::<()>(&bundle, &other).is_none());
+    }
+
+    #[test]
+    fn milestone_tag_index() {
+        for index in [0, 1, 5, 13, 14, 1_000_000, u32::MAX].iter() {
+            let tag = milestone_tag(*index).unwrap();
+            let value = tag
+                .to_inner()
+                .iter()
+                .take(21)
+                .enumerate()
+                .fold(0_i64, |n, (i, t)| n + i64::from(i8::from(t)) * 3_i64.pow(i as u32));
+            assert_eq!(i64::from(*index), value);
+        }
+    }
+
+    /// Trytes of a one transaction bundle of milestone `index` attached locally.
+    fn local_milestone(index: u32, timestamp: u64) -> Vec<String> {
+        let coordinator = Address::try_from_inner(pad_tritbuf(ADDRESS_TRIT_LEN, bytes_to_tritbuf(&[5; 16]))).unwrap();
+        let payload = Payload::try_from_inner(pad_tritbuf(PAYLOAD_TRIT_LEN, bytes_to_tritbuf(&[]))).unwrap();
+        let timestamp = Timestamp::try_from_inner(timestamp).unwrap();
+        let mut bundle_builder = OutgoingBundleBuilder::default();
+        bundle_builder.push(make_tx(coordinator, milestone_tag(index).unwrap(), timestamp, payload));
+        let bundle = bundle_builder
+            .seal()
+            .unwrap()
+            .attach_remote(Hash::zeros(), Hash::zeros())
+            .unwrap()
+            .build()
+            .unwrap();
+        let opt = SendTrytesOptions {
+            min_weight_magnitude: 1,
+            ..SendTrytesOptions::default()
+        };
+        attach_to_tangle(bundle.into_iter().collect(), Hash::zeros(), Hash::zeros(), &opt)
+            .unwrap()
+            .iter()
+            .map(tx_to_trytes)
+            .collect()
+    }
+
+    #[test]
+    fn inclusion_proof_milestone() {
+        let link = TangleAddress::new(
+            AppInst::from(&[1_u8; APPINST_SIZE][..]),
+            MsgId::from(&[2_u8; MSGID_SIZE][..]),
+        );
+        let msg = BinaryMessage::<(), _>::new(link, BinaryBody::from(vec![3_u8; 10]));
+        let mut proof = InclusionProof::from_message(&TangleMessage::with_timestamp(msg, 0), 1).unwrap();
+        let milestone = Milestone {
+            index: 42,
+            timestamp: 1_600_000_000,
+        };
+        proof.milestone = Some(milestone);
+        assert!(proof.verify::<()>(1).is_err());
+
+        proof.milestone_transactions = local_milestone(42, 1_600_000_000);
+        let proof = InclusionProof::from_text(&proof.to_text()).unwrap();
+        assert_eq!(Some(milestone), proof.verify::<()>(1).unwrap().milestone);
+
+        let mut other = proof.clone();
+        other.milestone_transactions = local_milestone(43, 1_600_000_000);
+        assert!(other.verify::<()>(1).is_err());
+        other.milestone_transactions = local_milestone(42, 1_600_000_001);
+        assert!(other.verify::<()>(1).is_err());
+        other.milestone = None;
+        assert!(other.verify::<()>(1).is_err());
+    }
+
+    #[test]
+    fn message_cache_hit() {
+        let mut cache = MessageCache::new(2);
+        assert_eq!(None, cache.get(&1));
+        cache.insert(1, "a");
+        cache.insert(2, "b");
+        assert_eq!(Some("a"), cache.get(&1));
+        assert_eq!(Some("b"), cache.get(&2));
+
+        cache.insert(1, "c");
+        assert_eq!(Some("c"), cache.get(&1));
+        assert_eq!(2, cache.entries.len());
+    }
+
+    #[test]
+    fn message_cache_eviction() {
+        let mut cache = MessageCache::new(2);
+        cache.insert(1, "a");
+        cache.insert(2, "b");
+        // Using 1 makes 2 the least recently used.
+        assert_eq!(Some("a"), cache.get(&1));
+        cache.insert(3, "c");
+        assert_eq!(None, cache.get(&2));
+        assert_eq!(Some("a"), cache.get(&1));
+        assert_eq!(Some("c"), cache.get(&3));
+
+        cache.set_capacity(1);
+        assert_eq!(None, cache.get(&1));
+        assert_eq!(Some("c"), cache.get(&3));
+    }
+
+    #[test]
+    fn message_cache_capacity_0() {
+        let mut cache = MessageCache::new(0);
+        cache.insert(1, "a");
+        assert_eq!(None, cache.get(&1));
+
+        let mut cache = MessageCache::new(1);
+        cache.insert(1, "a");
+        cache.set_capacity(0);
+        assert_eq!(None, cache.get(&1));
+        cache.insert(1, "a");
+        assert!(cache.entries.is_empty());
+    }
+}
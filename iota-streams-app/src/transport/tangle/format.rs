@@ -0,0 +1,341 @@
+//! Textual representations of Tangle addresses.
+//!
+//! Addresses are encoded as `<appinst>:<msgid>:<checksum>`. The checksum is the first
+//! `CHECKSUM_SIZE` bytes of SHA-256 of the address bytes, encoded in trytes for the `Trytes`
+//! format and in hex otherwise. Since message identifiers of different widths are valid, a
+//! truncated address could otherwise decode into another message link.
+//!
+//! Identity-centric applications refer to channels by DID and to messages by DID URL:
+//! `did:iota:streams:<appinst>` and `did:iota:streams:<appinst>#<msgid>:<checksum>`, all parts
+//! in hex. The fragment carries the same checksum as the address.
+
+use anyhow::{
+    anyhow,
+    ensure,
+    Result,
+};
+use bech32::{
+    FromBase32,
+    ToBase32,
+    Variant,
+};
+
+use core::str::FromStr;
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+use iota_streams_core::prelude::{
+    hex,
+    String,
+    Vec,
+};
+
+use super::{
+    AppInst,
+    AppInstSize,
+    MsgId,
+    MSGID_SIZES,
+    TangleAddress,
+};
+use iota_streams_ddml::types::{
+    NBytes,
+    Unsigned as _,
+};
+
+const TRYTE_CHARS: &[u8] = b"9ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Size of address checksums in bytes.
+pub const CHECKSUM_SIZE: usize = 4;
+
+/// Prefix of channel DIDs, followed by the application instance in hex.
+pub const CHANNEL_DID_PREFIX: &str = "did:iota:streams:";
+
+/// Textual format of `AppInst`, `MsgId` and `TangleAddress`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AddressFormat<'a> {
+    /// Two trytes per byte, the format of IOTA transaction `address` and `tag` fields.
+    Trytes,
+    /// Lowercase hex, the format used by `Display`.
+    Hex,
+    /// Bech32 with the human-readable part.
+    Bech32(&'a str),
+}
+
+impl<'a> Default for AddressFormat<'a> {
+    fn default() -> Self {
+        AddressFormat::Hex
+    }
+}
+
+impl<'a> AddressFormat<'a> {
+    /// Encode bytes into a string.
+    pub fn encode(&self, bytes: &[u8]) -> Result<String> {
+        match self {
+            AddressFormat::Trytes => {
+                let mut s = String::with_capacity(bytes.len() * 2);
+                for b in bytes {
+                    s.push(TRYTE_CHARS[(b % 27) as usize] as char);
+                    s.push(TRYTE_CHARS[(b / 27) as usize] as char);
+                }
+                Ok(s)
+            }
+            AddressFormat::Hex => Ok(hex::encode(bytes)),
+            AddressFormat::Bech32(hrp) => {
+                bech32::encode(hrp, bytes.to_base32(), Variant::Bech32).map_err(|e| anyhow!("Bech32 error: {}", e))
+            }
+        }
+    }
+
+    /// Decode bytes from a string.
+    pub fn decode(&self, s: &str) -> Result<Vec<u8>> {
+        match self {
+            AddressFormat::Trytes => {
+                ensure!(s.len() % 2 == 0, "Odd number of trytes: {}", s.len());
+                let tryte = |c: u8| {
+                    TRYTE_CHARS
+                        .iter()
+                        .position(|t| *t == c)
+                        .ok_or_else(|| anyhow!("Bad tryte: {}", c as char))
+                };
+                s.as_bytes()
+                    .chunks(2)
+                    .map(|c| -> Result<u8> {
+                        let x = tryte(c[0])? + tryte(c[1])? * 27;
+                        ensure!(x < 256, "Tryte pair out of range: {}", x);
+                        Ok(x as u8)
+                    })
+                    .collect()
+            }
+            AddressFormat::Hex => hex::decode(s).map_err(|e| anyhow!("Hex error: {}", e)),
+            AddressFormat::Bech32(hrp) => {
+                let (shrp, data, variant) = bech32::decode(s).map_err(|e| anyhow!("Bech32 error: {}", e))?;
+                ensure!(variant == Variant::Bech32, "Bad bech32 variant.");
+                ensure!(shrp.as_str() == *hrp, "Bad bech32 human-readable part: expected {}, found {}.", hrp, shrp);
+                Vec::<u8>::from_base32(&data).map_err(|e| anyhow!("Bech32 error: {}", e))
+            }
+        }
+    }
+
+    /// Format of address checksums, Bech32 parts carry their own checksums.
+    fn checksum_format(&self) -> AddressFormat<'static> {
+        match self {
+            AddressFormat::Trytes => AddressFormat::Trytes,
+            _ => AddressFormat::Hex,
+        }
+    }
+}
+
+impl AppInst {
+    /// Encode application instance in `format`.
+    pub fn to_string_with(&self, format: AddressFormat) -> Result<String> {
+        format.encode(self.id.as_ref())
+    }
+
+    /// Decode application instance in `format`.
+    pub fn from_str_with(s: &str, format: AddressFormat) -> Result<Self> {
+        let bytes = format.decode(s)?;
+        ensure!(
+            bytes.len() == AppInstSize::USIZE,
+            "Bad application instance length: {}",
+            bytes.len()
+        );
+        Ok(AppInst {
+            id: *<&NBytes<AppInstSize>>::from(&bytes[..]),
+        })
+    }
+
+    /// DID of the channel, `did:iota:streams:<appinst>`.
+    pub fn to_did(&self) -> String {
+        let mut s = String::from(CHANNEL_DID_PREFIX);
+        s.push_str(&hex::encode(self.id.as_ref()));
+        s
+    }
+
+    /// Decode the channel DID encoded with `to_did`.
+    pub fn from_did(did: &str) -> Result<Self> {
+        let id = did
+            .strip_prefix(CHANNEL_DID_PREFIX)
+            .ok_or_else(|| anyhow!("Not a channel DID: {}", did))?;
+        Self::from_str_with(id, AddressFormat::Hex)
+    }
+}
+
+impl MsgId {
+    /// Encode message identifier in `format`.
+    pub fn to_string_with(&self, format: AddressFormat) -> Result<String> {
+        format.encode(self.id.as_ref())
+    }
+
+    /// Decode message identifier in `format`.
+    pub fn from_str_with(s: &str, format: AddressFormat) -> Result<Self> {
+        let bytes = format.decode(s)?;
+        ensure!(
+            MSGID_SIZES.contains(&bytes.len()),
+            "Bad message identifier length: {}",
+            bytes.len()
+        );
+        Ok(MsgId { id: bytes })
+    }
+}
+
+impl TangleAddress {
+    /// Checksum of the address, see the module docs.
+    pub fn checksum(&self) -> [u8; CHECKSUM_SIZE] {
+        let mut h = Sha256::new();
+        h.update(self.appinst.id.as_ref());
+        h.update(&self.msgid.id);
+        let mut checksum = [0_u8; CHECKSUM_SIZE];
+        checksum.copy_from_slice(&h.finalize()[..CHECKSUM_SIZE]);
+        checksum
+    }
+
+    /// Encode address in `format` as `<appinst>:<msgid>:<checksum>`.
+    pub fn to_string_with(&self, format: AddressFormat) -> Result<String> {
+        let mut s = self.appinst.to_string_with(format)?;
+        s.push(':');
+        s.push_str(&self.msgid.to_string_with(format)?);
+        s.push(':');
+        s.push_str(&format.checksum_format().encode(&self.checksum())?);
+        Ok(s)
+    }
+
+    /// Decode address in `format` from `<appinst>:<msgid>:<checksum>` and verify the checksum.
+    pub fn from_str_with(s: &str, format: AddressFormat) -> Result<Self> {
+        let mut parts = s.splitn(3, ':');
+        let appinst = parts.next().ok_or_else(|| anyhow!("Bad address: {}", s))?;
+        let msgid = parts.next().ok_or_else(|| anyhow!("Bad address, msgid not found: {}", s))?;
+        let checksum = parts
+            .next()
+            .ok_or_else(|| anyhow!("Bad address, checksum not found (truncated?): {}", s))?;
+        let addr = TangleAddress {
+            appinst: AppInst::from_str_with(appinst, format)?,
+            msgid: MsgId::from_str_with(msgid, format)?,
+        };
+        ensure!(
+            format.checksum_format().decode(checksum)? == addr.checksum(),
+            "Bad address checksum (truncated or mistyped?): {}",
+            s
+        );
+        Ok(addr)
+    }
+
+    /// DID URL of the message, `did:iota:streams:<appinst>#<msgid>:<checksum>`.
+    pub fn to_did_url(&self) -> String {
+        let mut s = self.appinst.to_did();
+        s.push('#');
+        s.push_str(&hex::encode(&self.msgid.id));
+        s.push(':');
+        s.push_str(&hex::encode(&self.checksum()));
+        s
+    }
+
+    /// Decode the DID URL encoded with `to_did_url` and verify the checksum.
+    pub fn from_did_url(url: &str) -> Result<Self> {
+        let mut parts = url.splitn(2, '#');
+        let did = parts.next().unwrap_or_default();
+        let fragment = parts
+            .next()
+            .ok_or_else(|| anyhow!("DID URL without message fragment: {}", url))?;
+        let id = did
+            .strip_prefix(CHANNEL_DID_PREFIX)
+            .ok_or_else(|| anyhow!("Not a channel DID URL: {}", url))?;
+        Self::from_str_with(&[id, fragment].join(":"), AddressFormat::Hex)
+    }
+}
+
+impl FromStr for TangleAddress {
+    type Err = anyhow::Error;
+
+    /// Decode address in the `Hex` format, see `from_str_with`.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_str_with(s, AddressFormat::Hex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::tangle::APPINST_SIZE;
+
+    const FORMATS: [AddressFormat<'static>; 3] = [
+        AddressFormat::Trytes,
+        AddressFormat::Hex,
+        AddressFormat::Bech32("streams"),
+    ];
+
+    fn address(msgid_size: usize) -> TangleAddress {
+        let appinst: Vec<u8> = (0..APPINST_SIZE as u8).collect();
+        let msgid: Vec<u8> = (100..100 + msgid_size as u8).collect();
+        TangleAddress::new(AppInst::from(&appinst[..]), MsgId::from(&msgid[..]))
+    }
+
+    #[test]
+    fn test_address_round_trip() {
+        for format in FORMATS.iter() {
+            for size in MSGID_SIZES.iter() {
+                let addr = address(*size);
+                let s = addr.to_string_with(*format).unwrap();
+                let decoded = TangleAddress::from_str_with(&s, *format).unwrap();
+                assert!(addr == decoded, "{:?} {}", format, size);
+            }
+        }
+        let addr = address(MSGID_SIZES[0]);
+        let hex = addr.to_string_with(AddressFormat::Hex).unwrap();
+        assert!(addr == hex.parse::<TangleAddress>().unwrap());
+        assert!(addr == TangleAddress::from_did_url(&addr.to_did_url()).unwrap());
+        assert!(addr.appinst == AppInst::from_did(&addr.appinst.to_did()).unwrap());
+    }
+
+    #[test]
+    fn test_trytes_encoding() {
+        let trytes = AddressFormat::Trytes;
+        assert_eq!("99Z99ALI", trytes.encode(&[0, 26, 27, 255]).unwrap());
+        assert_eq!(vec![0, 26, 27, 255], trytes.decode("99Z99ALI").unwrap());
+        assert!(trytes.decode("99Z").is_err());
+        assert!(trytes.decode("9a").is_err());
+        // 26 + 26 * 27 is out of byte range.
+        assert!(trytes.decode("ZZ").is_err());
+    }
+
+    #[test]
+    fn test_bad_encodings() {
+        assert!(AddressFormat::Hex.decode("0g").is_err());
+        assert!(AddressFormat::Hex.decode("012").is_err());
+
+        let bech32 = AddressFormat::Bech32("streams");
+        let s = bech32.encode(&[1, 2, 3]).unwrap();
+        assert!(AddressFormat::Bech32("iota").decode(&s).is_err());
+        let mut bad = s.clone().into_bytes();
+        let last = bad.len() - 1;
+        bad[last] = if bad[last] == b'q' { b'p' } else { b'q' };
+        assert!(bech32.decode(core::str::from_utf8(&bad).unwrap()).is_err());
+        let m = bech32::encode("streams", [1_u8, 2, 3].to_base32(), Variant::Bech32m).unwrap();
+        assert!(bech32.decode(&m).is_err());
+    }
+
+    #[test]
+    fn test_bad_addresses() {
+        for format in FORMATS.iter() {
+            let s = address(MSGID_SIZES[0]).to_string_with(*format).unwrap();
+            let mut parts: Vec<&str> = s.split(':').collect();
+
+            // Truncated, no checksum.
+            assert!(TangleAddress::from_str_with(&parts[..2].join(":"), *format).is_err());
+
+            // Checksum of another address.
+            let other = address(MSGID_SIZES[1]).to_string_with(*format).unwrap();
+            let other_checksum = other.rsplit(':').next().unwrap();
+            parts[2] = other_checksum;
+            assert!(TangleAddress::from_str_with(&parts.join(":"), *format).is_err());
+
+            // Unsupported msgid width and bad appinst length.
+            let msgid = format.encode(&[0_u8; 16]).unwrap();
+            assert!(MsgId::from_str_with(&msgid, *format).is_err());
+            assert!(AppInst::from_str_with(&msgid, *format).is_err());
+        }
+        assert!(AppInst::from_did("did:iota:other:00").is_err());
+        assert!(TangleAddress::from_did_url(&address(MSGID_SIZES[0]).appinst.to_did()).is_err());
+    }
+}
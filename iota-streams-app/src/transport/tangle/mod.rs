@@ -1,6 +1,10 @@
 //! Tangle-specific transport definitions.
 
-use anyhow::Result;
+use anyhow::{
+    bail,
+    ensure,
+    Result,
+};
 use core::{
     convert::{
         AsMut,
@@ -12,14 +16,16 @@ use core::{
 };
 
 use iota_streams_core::{
-    prelude::typenum::{
-        U12,
-        U40,
-    },
-    sponge::{
-        prp::PRP,
-        spongos::Spongos,
+    prelude::{
+        typenum::{
+            U12,
+            U24,
+            U32,
+            U40,
+        },
+        Vec,
     },
+    sponge::prp::PRP,
 };
 use iota_streams_core_edsig::signature::ed25519;
 use iota_streams_ddml::{
@@ -34,7 +40,9 @@ use crate::message::{
     HasLink,
     LinkGenerator,
     LinkedMessage,
+    Milestone,
 };
+use crate::transport::Prioritized;
 
 /// Number of bytes to be placed in each transaction (Maximum HDF Payload Count)
 pub const PAYLOAD_BYTES: usize = 1090;
@@ -47,6 +55,9 @@ pub struct TangleMessage<F> {
     /// Timestamp is not an intrinsic part of Streams message; it's a part of the bundle.
     /// Timestamp is checked with Kerl as part of bundle essense trits.
     pub timestamp: u64,
+
+    /// Milestone confirming the bundle, set by the client transport on receive.
+    pub milestone: Option<Milestone>,
 }
 
 impl<F> LinkedMessage<TangleAddress> for TangleMessage<F> {
@@ -63,6 +74,7 @@ impl<F> TangleMessage<F> {
         Self {
             binary: msg,
             timestamp: chrono::Utc::now().timestamp_millis() as u64,
+            milestone: None,
         }
     }
 }
@@ -74,6 +86,7 @@ impl<F> TangleMessage<F> {
         Self {
             binary: msg,
             timestamp: 0_u64,
+            milestone: None,
         }
     }
 }
@@ -81,7 +94,18 @@ impl<F> TangleMessage<F> {
 impl<F> TangleMessage<F> {
     /// Create TangleMessage from BinaryMessage and an explicit timestamp.
     pub fn with_timestamp(msg: BinaryMessage<F, TangleAddress>, timestamp: u64) -> Self {
-        Self { binary: msg, timestamp }
+        Self {
+            binary: msg,
+            timestamp,
+            milestone: None,
+        }
+    }
+}
+
+impl<F: PRP> Prioritized for TangleMessage<F> {
+    /// Priority from the message header, messages with malformed header are least urgent.
+    fn priority(&self) -> u8 {
+        self.binary.parse_header().map_or(0, |preparsed| preparsed.header.get_priority())
     }
 }
 
@@ -157,11 +181,21 @@ impl HasLink for TangleAddress {
             msgid: rel.clone(),
         }
     }
+
+    fn rel_width(rel: &MsgId) -> usize {
+        rel.size()
+    }
+
+    fn default_rel_with_width(width: usize) -> MsgId {
+        MsgId::new(width).unwrap_or_default()
+    }
 }
 
 #[derive(Clone)]
 pub struct DefaultTangleLinkGenerator<F> {
     addr: TangleAddress,
+    msgid_size: usize,
+    msgid_hash: MsgIdHash,
     _phantom: core::marker::PhantomData<F>,
 }
 
@@ -169,39 +203,59 @@ impl<F> Default for DefaultTangleLinkGenerator<F> {
     fn default() -> Self {
         Self {
             addr: TangleAddress::default(),
+            msgid_size: MSGID_SIZE,
+            msgid_hash: MsgIdHash::default(),
             _phantom: core::marker::PhantomData,
         }
     }
 }
 
 impl<F> DefaultTangleLinkGenerator<F> {
+    /// Generate message identifiers `size` bytes wide for new channels, see `MSGID_SIZES`.
+    pub fn with_msgid_size(mut self, size: usize) -> Result<Self> {
+        ensure!(MSGID_SIZES.contains(&size), "Unsupported msgid size: {}.", size);
+        self.msgid_size = size;
+        Ok(self)
+    }
+
+    /// Derive message identifiers of new channels with `hash`.
+    pub fn with_msgid_hash(mut self, hash: MsgIdHash) -> Self {
+        self.msgid_hash = hash;
+        self
+    }
+
     pub fn reset_addr(&mut self, addr: TangleAddress) {
+        self.msgid_size = addr.msgid.size();
         self.addr = addr;
     }
 }
 
 impl<F: PRP> DefaultTangleLinkGenerator<F> {
     fn gen_uniform_msgid(&self, cursor: Cursor<&MsgId>) -> MsgId {
-        let mut s = Spongos::<F>::init();
-        s.absorb(self.addr.appinst.id.as_ref());
-        s.absorb(cursor.link.id.as_ref());
-        s.absorb(&cursor.branch_no.to_be_bytes());
-        s.absorb(&cursor.seq_no.to_be_bytes());
-        s.commit();
-        let mut new = MsgId::default();
-        s.squeeze(new.id.as_mut());
+        let mut new = MsgId {
+            id: vec![0; self.msgid_size],
+        };
+        let inputs: [&[u8]; 4] = [
+            self.addr.appinst.id.as_ref(),
+            &cursor.link.id[..],
+            &cursor.branch_no.to_be_bytes(),
+            &cursor.seq_no.to_be_bytes(),
+        ];
+        self.msgid_hash.hash::<F>(&inputs, &mut new.id[..]);
         new
     }
     fn gen_msgid(&self, pk: &ed25519::PublicKey, cursor: Cursor<&MsgId>) -> MsgId {
-        let mut s = Spongos::<F>::init();
-        s.absorb(self.addr.appinst.id.as_ref());
-        s.absorb(pk.as_ref());
-        s.absorb(cursor.link.id.as_ref());
-        s.absorb(&cursor.branch_no.to_be_bytes());
-        s.absorb(&cursor.seq_no.to_be_bytes());
-        s.commit();
-        let mut new = MsgId::default();
-        s.squeeze(new.id.as_mut());
+        let mut new = MsgId {
+            id: vec![0; self.msgid_size],
+        };
+        let inputs: [&[u8]; 5] = [
+            self.addr.appinst.id.as_ref(),
+            pk.as_ref(),
+            &cursor.link.id[..],
+            &cursor.branch_no.to_be_bytes(),
+            &cursor.seq_no.to_be_bytes(),
+        ];
+        self.msgid_hash.hash::<F>(&inputs, &mut new.id[..]);
         new
     }
 }
@@ -220,6 +274,7 @@ impl<F: PRP> LinkGenerator<TangleAddress> for DefaultTangleLinkGenerator<F> {
 
     /// Used by Subscriber to initialize link generator with the same state as Author
     fn reset(&mut self, announcement_link: TangleAddress) {
+        self.msgid_size = announcement_link.msgid.size();
         self.addr = announcement_link;
     }
 
@@ -238,6 +293,15 @@ impl<F: PRP> LinkGenerator<TangleAddress> for DefaultTangleLinkGenerator<F> {
             msgid: self.gen_msgid(pk, cursor),
         }
     }
+
+    fn hash_id(&self) -> u8 {
+        self.msgid_hash.id()
+    }
+
+    fn set_hash_id(&mut self, id: u8) -> Result<()> {
+        self.msgid_hash = MsgIdHash::from_id(id)?;
+        Ok(())
+    }
 }
 
 // ed25519 public key size in bytes + 64-bit additional index
@@ -319,24 +383,62 @@ impl hash::Hash for AppInst {
     }
 }
 
+/// Run `$body` with `$nb` bound to the msgid bytes `$id` viewed as `NBytes` of the matching width.
+macro_rules! msgid_nbytes {
+    ($id:expr, $nb:ident => $body:expr) => {
+        match $id.len() {
+            12 => {
+                let $nb = <&NBytes<U12>>::from(&$id[..]);
+                $body
+            }
+            24 => {
+                let $nb = <&NBytes<U24>>::from(&$id[..]);
+                $body
+            }
+            32 => {
+                let $nb = <&NBytes<U32>>::from(&$id[..]);
+                $body
+            }
+            n => bail!("Unsupported msgid size: {}.", n),
+        }
+    };
+    (mut $id:expr, $nb:ident => $body:expr) => {
+        match $id.len() {
+            12 => {
+                let $nb = <&mut NBytes<U12>>::from(&mut $id[..]);
+                $body
+            }
+            24 => {
+                let $nb = <&mut NBytes<U24>>::from(&mut $id[..]);
+                $body
+            }
+            32 => {
+                let $nb = <&mut NBytes<U32>>::from(&mut $id[..]);
+                $body
+            }
+            n => bail!("Unsupported msgid size: {}.", n),
+        }
+    };
+}
+
 /// (appinst+msgid) is (address+tag) in terms of IOTA transaction which are stored
 /// externally of message body, ie. in transaction header fields.
 /// Thus the trait implemntation absorbs appinst+msgid as `external`.
 impl<F: PRP> AbsorbExternalFallback<F> for TangleAddress
 {
     fn sizeof_absorb_external(&self, ctx: &mut sizeof::Context<F>) -> Result<()> {
-        ctx.absorb(External(&self.appinst.id))?
-            .absorb(External(&self.msgid.id))?;
+        ctx.absorb(External(&self.appinst.id))?;
+        msgid_nbytes!(self.msgid.id, id => ctx.absorb(External(id))?);
         Ok(())
     }
     fn wrap_absorb_external<OS: io::OStream>(&self, ctx: &mut wrap::Context<F, OS>) -> Result<()> {
-        ctx.absorb(External(&self.appinst.id))?
-            .absorb(External(&self.msgid.id))?;
+        ctx.absorb(External(&self.appinst.id))?;
+        msgid_nbytes!(self.msgid.id, id => ctx.absorb(External(id))?);
         Ok(())
     }
     fn unwrap_absorb_external<IS: io::IStream>(&self, ctx: &mut unwrap::Context<F, IS>) -> Result<()> {
-        ctx.absorb(External(&self.appinst.id))?
-            .absorb(External(&self.msgid.id))?;
+        ctx.absorb(External(&self.appinst.id))?;
+        msgid_nbytes!(self.msgid.id, id => ctx.absorb(External(id))?);
         Ok(())
     }
 }
@@ -345,17 +447,17 @@ impl<F: PRP> AbsorbFallback<F> for TangleAddress
 {
     fn sizeof_absorb(&self, ctx: &mut sizeof::Context<F>) -> Result<()> {
         ctx.absorb(&self.appinst.id)?
-            .absorb(&self.msgid.id)?;
+            .absorb(<&Fallback<MsgId>>::from(&self.msgid))?;
         Ok(())
     }
     fn wrap_absorb<OS: io::OStream>(&self, ctx: &mut wrap::Context<F, OS>) -> Result<()> {
         ctx.absorb(&self.appinst.id)?
-            .absorb(&self.msgid.id)?;
+            .absorb(<&Fallback<MsgId>>::from(&self.msgid))?;
         Ok(())
     }
     fn unwrap_absorb<IS: io::IStream>(&mut self, ctx: &mut unwrap::Context<F, IS>) -> Result<()> {
         ctx.absorb(&mut self.appinst.id)?
-            .absorb(&mut self.msgid.id)?;
+            .absorb(<&mut Fallback<MsgId>>::from(&mut self.msgid))?;
         Ok(())
     }
 }
@@ -363,18 +465,46 @@ impl<F: PRP> AbsorbFallback<F> for TangleAddress
 pub type MsgIdSize = U12;
 pub const MSGID_SIZE: usize = 12;
 
+/// Message identifier widths in bytes a channel can be created with.
+pub const MSGID_SIZES: [usize; 3] = [12, 24, 32];
+
 /// Message identifier unique within application instance.
 /// Currently, 27-byte string stored in `tag` transaction field.
-#[derive(Clone, Default)]
+///
+/// The width of message identifiers is chosen at channel creation, the default is `MSGID_SIZE`.
+/// As the width is not encoded along with the identifier, an identifier read from a stream
+/// takes the width of the value it is decoded into.
+#[derive(Clone)]
 pub struct MsgId {
-    pub(crate) id: NBytes<MsgIdSize>,
+    pub(crate) id: Vec<u8>,
+}
+
+impl MsgId {
+    /// Create zero message identifier of the given width.
+    pub fn new(size: usize) -> Result<Self> {
+        ensure!(MSGID_SIZES.contains(&size), "Unsupported msgid size: {}.", size);
+        Ok(Self { id: vec![0; size] })
+    }
+
+    /// Width of the message identifier in bytes.
+    pub fn size(&self) -> usize {
+        self.id.len()
+    }
+}
+
+impl Default for MsgId {
+    fn default() -> Self {
+        Self {
+            id: vec![0; MSGID_SIZE],
+        }
+    }
 }
 
 impl<'a> From<&'a [u8]> for MsgId {
     fn from(v: &[u8]) -> MsgId {
         MsgId {
             // TODO: Implement safer TryFrom or force check for length at call site.
-            id: *<&NBytes<MsgIdSize>>::from(v),
+            id: v.to_vec(),
         }
     }
 }
@@ -385,10 +515,8 @@ impl FromStr for MsgId {
         // TODO: format for `s`: Bech32 (https://github.com/rust-bitcoin/rust-bech32)
         // currently lowercase hex
         hex::decode(s).map_or(Err(()), |x| {
-            if x.len() == MsgIdSize::USIZE {
-                Ok(MsgId {
-                    id: *<&NBytes<MsgIdSize>>::from(&x[..]),
-                })
+            if MSGID_SIZES.contains(&x.len()) {
+                Ok(MsgId { id: x })
             } else {
                 Err(())
             }
@@ -398,19 +526,21 @@ impl FromStr for MsgId {
 
 impl From<NBytes<MsgIdSize>> for MsgId {
     fn from(b: NBytes<MsgIdSize>) -> Self {
-        Self { id: b }
+        Self {
+            id: b.as_slice().to_vec(),
+        }
     }
 }
 
 impl fmt::Debug for MsgId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", hex::encode(self.id))
+        write!(f, "{}", hex::encode(&self.id))
     }
 }
 
 impl fmt::Display for MsgId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", hex::encode(self.id))
+        write!(f, "{}", hex::encode(&self.id))
     }
 }
 
@@ -437,33 +567,52 @@ impl hash::Hash for MsgId {
 /// encoded (`skip`ped).
 impl<F> SkipFallback<F> for MsgId {
     fn sizeof_skip(&self, ctx: &mut sizeof::Context<F>) -> Result<()> {
-        ctx.skip(&self.id)?;
+        msgid_nbytes!(self.id, id => ctx.skip(id)?);
         Ok(())
     }
     fn wrap_skip<OS: io::OStream>(&self, ctx: &mut wrap::Context<F, OS>) -> Result<()> {
-        ctx.skip(&self.id)?;
+        msgid_nbytes!(self.id, id => ctx.skip(id)?);
         Ok(())
     }
     fn unwrap_skip<IS: io::IStream>(&mut self, ctx: &mut unwrap::Context<F, IS>) -> Result<()> {
-        ctx.skip(&mut self.id)?;
+        msgid_nbytes!(mut self.id, id => ctx.skip(id)?);
         Ok(())
     }
 }
 
 impl<F: PRP> AbsorbFallback<F> for MsgId {
     fn sizeof_absorb(&self, ctx: &mut sizeof::Context<F>) -> Result<()> {
-        ctx.absorb(&self.id)?;
+        msgid_nbytes!(self.id, id => ctx.absorb(id)?);
         Ok(())
     }
     fn wrap_absorb<OS: io::OStream>(&self, ctx: &mut wrap::Context<F, OS>) -> Result<()> {
-        ctx.absorb(&self.id)?;
+        msgid_nbytes!(self.id, id => ctx.absorb(id)?);
         Ok(())
     }
     fn unwrap_absorb<IS: io::IStream>(&mut self, ctx: &mut unwrap::Context<F, IS>) -> Result<()> {
-        ctx.absorb(&mut self.id)?;
+        msgid_nbytes!(mut self.id, id => ctx.absorb(id)?);
         Ok(())
     }
 }
 
+mod format;
+pub use format::{
+    AddressFormat,
+    CHANNEL_DID_PREFIX,
+    CHECKSUM_SIZE,
+};
+
+mod msgid_hash;
+pub use msgid_hash::{
+    Blake2bHasher,
+    MsgIdHash,
+    MsgIdHasher,
+    Sha256Hasher,
+    SpongosHasher,
+};
+
 #[cfg(any(feature = "sync-client", feature = "async-client"))]
 pub mod client;
+
+#[cfg(any(feature = "sync-client", feature = "async-client"))]
+mod pow;
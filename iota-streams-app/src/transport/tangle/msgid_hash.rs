@@ -0,0 +1,125 @@
+//! Hash functions deriving message identifiers of Tangle channels.
+//!
+//! Message identifiers are hashes of the channel address, the publisher's public key and the
+//! cursor of the message. The spongos-based hash is the default; BLAKE2b and SHA-256 can be
+//! chosen at channel creation for compatibility with indexing infrastructure deriving the
+//! identifiers of expected messages by itself. The hash is carried in the announcement, see
+//! `hdf::FLAG_MSGID_HASH_MASK`.
+
+use anyhow::{
+    bail,
+    Result,
+};
+
+use iota_streams_core::sponge::{
+    prp::PRP,
+    spongos::Spongos,
+};
+
+/// Hash function of message identifiers.
+pub trait MsgIdHasher {
+    /// Hash the concatenation of `inputs` into `msgid`, at most 32 bytes wide.
+    fn hash(&self, inputs: &[&[u8]], msgid: &mut [u8]);
+}
+
+/// Spongos with the PRP `F` of the channel.
+pub struct SpongosHasher<F>(core::marker::PhantomData<F>);
+
+impl<F> Default for SpongosHasher<F> {
+    fn default() -> Self {
+        Self(core::marker::PhantomData)
+    }
+}
+
+impl<F: PRP> MsgIdHasher for SpongosHasher<F> {
+    fn hash(&self, inputs: &[&[u8]], msgid: &mut [u8]) {
+        let mut s = Spongos::<F>::init();
+        for input in inputs {
+            s.absorb(input);
+        }
+        s.commit();
+        s.squeeze(msgid);
+    }
+}
+
+/// BLAKE2b with the digest size set to the width of message identifiers.
+#[derive(Default)]
+pub struct Blake2bHasher;
+
+impl MsgIdHasher for Blake2bHasher {
+    fn hash(&self, inputs: &[&[u8]], msgid: &mut [u8]) {
+        use blake2::{
+            digest::{
+                Update,
+                VariableOutput,
+            },
+            VarBlake2b,
+        };
+        let mut h = VarBlake2b::new(msgid.len()).unwrap();
+        for input in inputs {
+            h.update(input);
+        }
+        h.finalize_variable(|digest| msgid.copy_from_slice(digest));
+    }
+}
+
+/// SHA-256 truncated to the width of message identifiers.
+#[derive(Default)]
+pub struct Sha256Hasher;
+
+impl MsgIdHasher for Sha256Hasher {
+    fn hash(&self, inputs: &[&[u8]], msgid: &mut [u8]) {
+        use sha2::{
+            Digest,
+            Sha256,
+        };
+        let mut h = Sha256::new();
+        for input in inputs {
+            h.update(input);
+        }
+        let n = msgid.len();
+        msgid.copy_from_slice(&h.finalize()[..n]);
+    }
+}
+
+/// Hash function of message identifiers of a channel as carried in the announcement.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MsgIdHash {
+    Spongos,
+    Blake2b,
+    Sha256,
+}
+
+impl MsgIdHash {
+    /// Identifier of the hash function in announcements.
+    pub fn id(self) -> u8 {
+        match self {
+            MsgIdHash::Spongos => 0,
+            MsgIdHash::Blake2b => 1,
+            MsgIdHash::Sha256 => 2,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Result<Self> {
+        match id {
+            0 => Ok(MsgIdHash::Spongos),
+            1 => Ok(MsgIdHash::Blake2b),
+            2 => Ok(MsgIdHash::Sha256),
+            _ => bail!("Unsupported msgid hash: {}.", id),
+        }
+    }
+
+    pub fn hash<F: PRP>(self, inputs: &[&[u8]], msgid: &mut [u8]) {
+        match self {
+            MsgIdHash::Spongos => SpongosHasher::<F>::default().hash(inputs, msgid),
+            MsgIdHash::Blake2b => Blake2bHasher.hash(inputs, msgid),
+            MsgIdHash::Sha256 => Sha256Hasher.hash(inputs, msgid),
+        }
+    }
+}
+
+impl Default for MsgIdHash {
+    fn default() -> Self {
+        MsgIdHash::Spongos
+    }
+}
@@ -0,0 +1,170 @@
+//! Local proof-of-work for transactions, searched for in parallel.
+//!
+//! Only the last CurlP block of a transaction holds the nonce, so the sponge state after the
+//! preceding blocks is computed once and each candidate nonce costs a single block.
+
+use anyhow::{
+    anyhow,
+    Result,
+};
+use std::{
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        mpsc,
+        Arc,
+    },
+    thread,
+};
+
+use bee_crypto::ternary::{
+    sponge::{
+        CurlP81,
+        Sponge,
+    },
+    Hash,
+};
+use iota::ternary as iota_ternary;
+use iota_ternary::{
+    Btrit,
+    T1B1Buf,
+    TritBuf,
+    Trits,
+};
+
+/// Number of trailing transaction trits holding the nonce.
+const NONCE_TRITS: usize = 81;
+
+/// Number of leading nonce trits fixed per thread, enough to tell 3^4 threads apart.
+const THREAD_TRITS: usize = 4;
+
+/// Number of trits CurlP absorbs at once.
+const BLOCK_TRITS: usize = 243;
+
+/// Add one to balanced ternary number `trits`, least significant trit first. Returns `false`
+/// on overflow.
+fn increment(trits: &mut Trits) -> bool {
+    for i in 0..trits.len() {
+        match trits.get(i) {
+            Some(Btrit::PlusOne) => trits.set(i, Btrit::NegOne),
+            Some(Btrit::Zero) => {
+                trits.set(i, Btrit::PlusOne);
+                return true;
+            }
+            _ => {
+                trits.set(i, Btrit::Zero);
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn digest(trits: &Trits) -> Result<TritBuf<T1B1Buf>> {
+    CurlP81::new()
+        .digest(trits)
+        .map_err(|e| anyhow!("Failed to hash transaction: {:?}.", e))
+}
+
+/// Sponge state after absorbing `trits`, a whole number of blocks.
+fn midstate(trits: &Trits) -> Result<CurlP81> {
+    let mut curl = CurlP81::new();
+    curl.absorb(trits)
+        .map_err(|e| anyhow!("Failed to hash transaction: {:?}.", e))?;
+    Ok(curl)
+}
+
+/// Whether hash of the transaction with the last block `tail` absorbed into `midstate` ends
+/// with `min_weight_magnitude` zero trits.
+fn has_weight(midstate: &CurlP81, tail: &Trits, min_weight_magnitude: usize) -> Result<bool> {
+    let mut curl = midstate.clone();
+    let mut hash = TritBuf::<T1B1Buf>::zeros(BLOCK_TRITS);
+    curl.absorb(tail)
+        .and_then(|_| curl.squeeze_into(&mut hash))
+        .map_err(|e| anyhow!("Failed to hash transaction: {:?}.", e))?;
+    Ok(hash.iter().rev().take(min_weight_magnitude).all(|t| t == Btrit::Zero))
+}
+
+/// Hash of transaction `trits`.
+pub fn hash(trits: &Trits) -> Result<Hash> {
+    let mut hash = Hash::zeros();
+    hash.as_trits_mut().copy_from(&digest(trits)?);
+    Ok(hash)
+}
+
+/// Search for a nonce such that hash of transaction `trits` ends with `min_weight_magnitude`
+/// zero trits. The nonce space is split between `threads` threads; the nonce of the first
+/// thread to succeed is written to `trits`.
+pub fn pow(trits: &mut TritBuf<T1B1Buf>, min_weight_magnitude: u8, threads: usize) -> Result<()> {
+    let nonce_offset = trits
+        .len()
+        .checked_sub(NONCE_TRITS)
+        .ok_or_else(|| anyhow!("Transaction is shorter than its nonce."))?;
+    let mwm = min_weight_magnitude as usize;
+    let threads = core::cmp::max(1, core::cmp::min(threads, 81));
+    // Blocks before the one with the nonce are the same for every candidate.
+    let tail_offset = nonce_offset / BLOCK_TRITS * BLOCK_TRITS;
+    let midstate = midstate(trits.subslice(0..tail_offset))?;
+
+    let found = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = mpsc::channel();
+    for t in 0..threads {
+        let mut candidate = trits.clone();
+        let midstate = midstate.clone();
+        let found = found.clone();
+        let sender = sender.clone();
+        thread::spawn(move || {
+            // Thread index in the leading nonce trits, the rest is the counter.
+            let mut index = t;
+            for i in 0..THREAD_TRITS {
+                let trit = match index % 3 {
+                    0 => Btrit::Zero,
+                    1 => Btrit::PlusOne,
+                    _ => Btrit::NegOne,
+                };
+                candidate.set(nonce_offset + i, trit);
+                index /= 3;
+            }
+
+            let len = candidate.len();
+            loop {
+                if found.load(Ordering::Relaxed) {
+                    return;
+                }
+                match has_weight(&midstate, candidate.subslice(tail_offset..len), mwm) {
+                    Ok(true) => {
+                        found.store(true, Ordering::Relaxed);
+                        let _ = sender.send(Ok(candidate));
+                        return;
+                    }
+                    Ok(false) => {}
+                    Err(e) => {
+                        let _ = sender.send(Err(e));
+                        return;
+                    }
+                }
+                if !increment(candidate.subslice_mut(nonce_offset + THREAD_TRITS..len)) {
+                    let _ = sender.send(Err(anyhow!("Nonce space exhausted.")));
+                    return;
+                }
+            }
+        });
+    }
+    drop(sender);
+
+    // Threads report errors independently, the first found nonce wins.
+    let mut error = anyhow!("Proof-of-work threads failed.");
+    for result in receiver {
+        match result {
+            Ok(candidate) => {
+                found.store(true, Ordering::Relaxed);
+                *trits = candidate;
+                return Ok(());
+            }
+            Err(e) => error = e,
+        }
+    }
+    Err(error)
+}
@@ -0,0 +1,138 @@
+//! Module-lattice key encapsulation ML-KEM-768 (FIPS 203), the standardized CRYSTALS-Kyber.
+//! The scheme is believed to resist quantum adversaries; combined with X25519 the shared key
+//! stays secret as long as either of the schemes is unbroken.
+//!
+//! The scheme is provided by the `ml-kem` crate, checked against the NIST known answer tests;
+//! this module only derives key pairs from seeds and adapts the byte encodings.
+//!
+//! Public keys are 1184 bytes, ciphertexts are 1088 bytes, shared secrets are 32 bytes.
+
+use core::convert::TryFrom;
+use iota_streams_core::prelude::Vec;
+use ml_kem::{
+    kem::Decapsulate,
+    Ciphertext,
+    EncapsulateDeterministic,
+    Encoded,
+    EncodedSizeUser,
+    KemCore,
+    MlKem768,
+    B32,
+};
+use sha3::{
+    Digest,
+    Sha3_512,
+};
+
+const Q: u16 = 3329;
+const K: usize = 3;
+
+const POLY_BYTES: usize = 384;
+pub const PUBLIC_KEY_LENGTH: usize = K * POLY_BYTES + 32;
+pub const CIPHERTEXT_LENGTH: usize = 1088;
+pub const SHARED_SECRET_LENGTH: usize = 32;
+
+type DecapsulationKey = <MlKem768 as KemCore>::DecapsulationKey;
+type EncapsulationKey = <MlKem768 as KemCore>::EncapsulationKey;
+
+/// Whether the 12-bit coefficients of the polynomials encoded in `bytes` are reduced modulo q,
+/// the modulus check of FIPS 203 encapsulation keys.
+fn is_reduced(bytes: &[u8]) -> bool {
+    bytes.chunks(3).all(|c| {
+        let c0 = c[0] as u16 | ((c[1] as u16 & 0x0f) << 8);
+        let c1 = (c[1] as u16 >> 4) | ((c[2] as u16) << 4);
+        c0 < Q && c1 < Q
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey(Vec<u8>);
+
+impl PublicKey {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Public key from `bytes`, `None` if the length is wrong or coefficients are not reduced.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != PUBLIC_KEY_LENGTH || !is_reduced(&bytes[..K * POLY_BYTES]) {
+            return None;
+        }
+        Some(Self(bytes.to_vec()))
+    }
+
+    fn encapsulation_key(&self) -> EncapsulationKey {
+        let encoded = Encoded::<EncapsulationKey>::try_from(&self.0[..]).expect("Public key length is checked.");
+        EncapsulationKey::from_bytes(&encoded)
+    }
+}
+
+pub struct Keypair {
+    pub public: PublicKey,
+    dk: DecapsulationKey,
+}
+
+impl Keypair {
+    /// Derive a key pair from `seed`: the key generation seeds `d` and `z` of FIPS 203 are
+    /// SHA3-512 of the domain separator and `seed`.
+    pub fn from_seed(seed: &[u8]) -> Self {
+        let mut g = Sha3_512::new();
+        g.update(b"IOTAStreams ML-KEM");
+        g.update(seed);
+        let dz = g.finalize();
+        let d = B32::try_from(&dz[..32]).expect("SHA3-512 output is 64 bytes.");
+        let z = B32::try_from(&dz[32..]).expect("SHA3-512 output is 64 bytes.");
+        let (dk, ek) = MlKem768::generate_deterministic(&d, &z);
+        Self {
+            public: PublicKey(ek.as_bytes().to_vec()),
+            dk,
+        }
+    }
+
+    /// Shared secret encapsulated in `ciphertext`, `None` if the length is wrong. Tampered
+    /// ciphertexts yield a pseudorandom secret unrelated to the encapsulated one.
+    pub fn decapsulate(&self, ciphertext: &[u8]) -> Option<[u8; SHARED_SECRET_LENGTH]> {
+        let ciphertext = Ciphertext::<MlKem768>::try_from(ciphertext).ok()?;
+        let shared = self.dk.decapsulate(&ciphertext).ok()?;
+        Some(shared.into())
+    }
+}
+
+/// Encapsulate a shared secret for `pk` using fresh `random` bytes. Returns the shared secret
+/// and its ciphertext.
+pub fn encapsulate(pk: &PublicKey, random: &[u8; 32]) -> ([u8; SHARED_SECRET_LENGTH], Vec<u8>) {
+    let (ciphertext, shared) = pk
+        .encapsulation_key()
+        .encapsulate_deterministic(&B32::from(*random))
+        .expect("ML-KEM encapsulation is infallible.");
+    (shared.into(), ciphertext.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kyber_encapsulate_decapsulate() {
+        let kp = Keypair::from_seed(b"KYBER9SEED");
+        let pk = PublicKey::from_bytes(kp.public.as_bytes()).unwrap();
+        let (shared, mut ciphertext) = encapsulate(&pk, &[7_u8; 32]);
+        assert_eq!(CIPHERTEXT_LENGTH, ciphertext.len());
+        assert_eq!(Some(shared), kp.decapsulate(&ciphertext));
+
+        ciphertext[0] ^= 1;
+        assert_ne!(Some(shared), kp.decapsulate(&ciphertext));
+        assert!(kp.decapsulate(&ciphertext[1..]).is_none());
+    }
+
+    #[test]
+    fn test_kyber_unreduced_public_key() {
+        let kp = Keypair::from_seed(b"KYBER9SEED");
+        let mut bytes = kp.public.as_bytes().to_vec();
+        // First coefficient 0xfff >= q.
+        bytes[0] = 0xff;
+        bytes[1] |= 0x0f;
+        assert!(PublicKey::from_bytes(&bytes).is_none());
+        assert!(PublicKey::from_bytes(&bytes[1..]).is_none());
+    }
+}
@@ -1 +1,3 @@
+#[cfg(feature = "pq")]
+pub mod kyber;
 pub mod x25519;
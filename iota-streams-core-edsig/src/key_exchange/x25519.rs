@@ -60,6 +60,26 @@ impl<'a> From<&'a mut PublicKey> for &'a mut PublicKeyWrap {
     }
 }
 
+/// Key exchange of a sender with a recipient computed ahead of wrapping, so that the exchanges
+/// with many recipients, the costly part of keyloads, can be computed in parallel.
+pub struct Exchange {
+    /// Public key of the ephemeral secret of the sender.
+    pub ephemeral_pk: PublicKey,
+    /// Secret shared by the sender and the recipient.
+    pub shared: SharedSecret,
+}
+
+impl Exchange {
+    /// Exchange with recipient `pk` using an ephemeral secret made of the random bytes `secret`.
+    pub fn ephemeral(secret: [u8; 32], pk: &PublicKey) -> Self {
+        let sk = StaticSecret::from(secret);
+        Self {
+            ephemeral_pk: PublicKey::from(&sk),
+            shared: sk.diffie_hellman(pk),
+        }
+    }
+}
+
 pub type Pks = HashSet<PublicKeyWrap>;
 pub type IPk<'a> = &'a PublicKey;
 
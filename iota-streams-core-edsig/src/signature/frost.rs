@@ -0,0 +1,322 @@
+//! Threshold Ed25519 signatures in the style of FROST: any `threshold` of `n` signers holding
+//! shares of a group secret key produce an Ed25519ph signature verifiable with the group public
+//! key, while no signer knows the group secret key.
+//!
+//! Signing takes two rounds. Each participating signer publishes a `NonceCommitment` obtained
+//! with `commit`, then signs the prehash with `sign` given the commitments of all participants.
+//! A coordinator combines the signature shares into the signature with `aggregate`.
+
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_TABLE,
+    edwards::{
+        CompressedEdwardsY,
+        EdwardsPoint,
+    },
+    scalar::Scalar,
+    traits::Identity,
+};
+use iota_streams_core::prelude::Vec;
+use sha2::{
+    Digest,
+    Sha512,
+};
+
+use super::ed25519::{
+    PublicKey,
+    Signature,
+    SIGNATURE_LENGTH,
+};
+
+pub const NONCE_COMMITMENT_LENGTH: usize = 2 + 32 + 32;
+pub const SIGNATURE_SHARE_LENGTH: usize = 2 + 32;
+
+fn scalar_from_hash(domain: &[u8], data: &[&[u8]]) -> Scalar {
+    let mut hash = Sha512::new();
+    hash.update(b"IOTAStreams FROST");
+    hash.update(&[domain.len() as u8]);
+    hash.update(domain);
+    for d in data {
+        hash.update(&(d.len() as u64).to_le_bytes());
+        hash.update(*d);
+    }
+    Scalar::from_hash(hash)
+}
+
+/// Share of the group secret key of signer `index`, indices start at 1.
+#[derive(Clone)]
+pub struct SecretShare {
+    pub index: u16,
+    secret: Scalar,
+    pub group_pk: PublicKey,
+}
+
+/// Generate a group key from `random` and split its secret into `n` shares, any `threshold`
+/// of which can sign. The dealer learns the group secret key and must erase `random`.
+pub fn deal(random: &[u8; 64], threshold: u16, n: u16) -> Option<(PublicKey, Vec<SecretShare>)> {
+    if threshold == 0 || threshold > n {
+        return None;
+    }
+    let coefficients: Vec<Scalar> = (0..threshold)
+        .map(|k| scalar_from_hash(b"coefficient", &[&random[..], &k.to_le_bytes()]))
+        .collect();
+    let group_point = &coefficients[0] * &ED25519_BASEPOINT_TABLE;
+    let group_pk = PublicKey::from_bytes(group_point.compress().as_bytes()).ok()?;
+    let shares = (1..=n)
+        .map(|index| {
+            let x = Scalar::from(index as u64);
+            let secret = coefficients.iter().rev().fold(Scalar::zero(), |acc, c| acc * x + c);
+            SecretShare {
+                index,
+                secret,
+                group_pk: group_pk.clone(),
+            }
+        })
+        .collect();
+    Some((group_pk, shares))
+}
+
+/// Secret nonces of a signer for one signature, consumed by `sign`.
+pub struct SigningNonces {
+    index: u16,
+    hiding: Scalar,
+    binding: Scalar,
+}
+
+/// Commitment to the nonces of signer `index`, sent to the other participants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonceCommitment {
+    pub index: u16,
+    hiding: CompressedEdwardsY,
+    binding: CompressedEdwardsY,
+}
+
+impl NonceCommitment {
+    pub fn to_bytes(&self) -> [u8; NONCE_COMMITMENT_LENGTH] {
+        let mut bytes = [0_u8; NONCE_COMMITMENT_LENGTH];
+        bytes[..2].copy_from_slice(&self.index.to_le_bytes());
+        bytes[2..34].copy_from_slice(self.hiding.as_bytes());
+        bytes[34..].copy_from_slice(self.binding.as_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != NONCE_COMMITMENT_LENGTH {
+            return None;
+        }
+        let mut index = [0_u8; 2];
+        index.copy_from_slice(&bytes[..2]);
+        let hiding = CompressedEdwardsY::from_slice(&bytes[2..34]);
+        let binding = CompressedEdwardsY::from_slice(&bytes[34..]);
+        hiding.decompress()?;
+        binding.decompress()?;
+        Some(Self {
+            index: u16::from_le_bytes(index),
+            hiding,
+            binding,
+        })
+    }
+}
+
+/// First round: generate nonces for signing with `share`. Nonces are derived from the share
+/// and `random`, which must be fresh for every signature.
+pub fn commit(share: &SecretShare, random: &[u8; 64]) -> (SigningNonces, NonceCommitment) {
+    let secret = share.secret.to_bytes();
+    let hiding = scalar_from_hash(b"hiding", &[&secret[..], &random[..]]);
+    let binding = scalar_from_hash(b"binding", &[&secret[..], &random[..]]);
+    let commitment = NonceCommitment {
+        index: share.index,
+        hiding: (&hiding * &ED25519_BASEPOINT_TABLE).compress(),
+        binding: (&binding * &ED25519_BASEPOINT_TABLE).compress(),
+    };
+    let nonces = SigningNonces {
+        index: share.index,
+        hiding,
+        binding,
+    };
+    (nonces, commitment)
+}
+
+/// Signature share of signer `index`, sent to the coordinator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignatureShare {
+    pub index: u16,
+    z: Scalar,
+}
+
+impl SignatureShare {
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_SHARE_LENGTH] {
+        let mut bytes = [0_u8; SIGNATURE_SHARE_LENGTH];
+        bytes[..2].copy_from_slice(&self.index.to_le_bytes());
+        bytes[2..].copy_from_slice(self.z.as_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != SIGNATURE_SHARE_LENGTH {
+            return None;
+        }
+        let mut index = [0_u8; 2];
+        index.copy_from_slice(&bytes[..2]);
+        let mut z = [0_u8; 32];
+        z.copy_from_slice(&bytes[2..]);
+        Some(Self {
+            index: u16::from_le_bytes(index),
+            z: Scalar::from_canonical_bytes(z)?,
+        })
+    }
+}
+
+/// Commitments ordered by signer index, `None` if an index is repeated.
+fn sorted(commitments: &[NonceCommitment]) -> Option<Vec<NonceCommitment>> {
+    let mut sorted = commitments.to_vec();
+    sorted.sort_by_key(|c| c.index);
+    if sorted.is_empty() || sorted[0].index == 0 || sorted.windows(2).any(|w| w[0].index == w[1].index) {
+        return None;
+    }
+    Some(sorted)
+}
+
+/// Group commitment and binding factor of each signer.
+fn group_commitment(
+    commitments: &[NonceCommitment],
+    context: &[u8],
+    prehash: &[u8; 64],
+) -> Option<(EdwardsPoint, Vec<Scalar>)> {
+    let mut encoded = Vec::with_capacity(commitments.len() * NONCE_COMMITMENT_LENGTH);
+    for c in commitments {
+        encoded.extend_from_slice(&c.to_bytes());
+    }
+    let mut r = EdwardsPoint::identity();
+    let mut rhos = Vec::with_capacity(commitments.len());
+    for c in commitments {
+        let rho = scalar_from_hash(b"rho", &[&c.index.to_le_bytes(), context, &prehash[..], &encoded]);
+        r += c.hiding.decompress()? + c.binding.decompress()? * rho;
+        rhos.push(rho);
+    }
+    Some((r, rhos))
+}
+
+/// Lagrange coefficient of signer `index` among signers `indices` at zero.
+fn lagrange(index: u16, indices: impl Iterator<Item = u16>) -> Scalar {
+    let x = Scalar::from(index as u64);
+    let mut num = Scalar::one();
+    let mut den = Scalar::one();
+    for j in indices.filter(|j| *j != index) {
+        let xj = Scalar::from(j as u64);
+        num *= xj;
+        den *= xj - x;
+    }
+    num * den.invert()
+}
+
+/// Same challenge as `PublicKey::verify_prehashed`.
+fn challenge(r: &CompressedEdwardsY, group_pk: &PublicKey, context: &[u8], prehash: &[u8; 64]) -> Scalar {
+    Scalar::from_hash(
+        Sha512::new()
+            .chain(b"SigEd25519 no Ed25519 collisions")
+            .chain(&[1_u8])
+            .chain(&[context.len() as u8])
+            .chain(context)
+            .chain(r.as_bytes())
+            .chain(group_pk.as_bytes())
+            .chain(&prehash[..]),
+    )
+}
+
+/// Second round: sign `prehash` with `context` using `share` and the nonces committed to in
+/// the first round. `commitments` are those of all participating signers, including own one.
+pub fn sign(
+    share: &SecretShare,
+    nonces: SigningNonces,
+    commitments: &[NonceCommitment],
+    context: &[u8],
+    prehash: &[u8; 64],
+) -> Option<SignatureShare> {
+    if nonces.index != share.index || context.len() > 255 {
+        return None;
+    }
+    let commitments = sorted(commitments)?;
+    let own = commitments.iter().position(|c| c.index == share.index)?;
+    if commitments[own].hiding != (&nonces.hiding * &ED25519_BASEPOINT_TABLE).compress()
+        || commitments[own].binding != (&nonces.binding * &ED25519_BASEPOINT_TABLE).compress()
+    {
+        return None;
+    }
+
+    let (r, rhos) = group_commitment(&commitments, context, prehash)?;
+    let c = challenge(&r.compress(), &share.group_pk, context, prehash);
+    let lambda = lagrange(share.index, commitments.iter().map(|c| c.index));
+    Some(SignatureShare {
+        index: share.index,
+        z: nonces.hiding + nonces.binding * rhos[own] + lambda * share.secret * c,
+    })
+}
+
+/// Combine signature shares of all signers of `commitments` into an Ed25519ph signature of
+/// `prehash` with `context`. Fails if a share is missing or invalid.
+pub fn aggregate(
+    group_pk: &PublicKey,
+    commitments: &[NonceCommitment],
+    shares: &[SignatureShare],
+    context: &[u8],
+    prehash: &[u8; 64],
+) -> Option<Signature> {
+    let commitments = sorted(commitments)?;
+    if shares.len() != commitments.len() || !commitments.iter().all(|c| shares.iter().any(|s| s.index == c.index)) {
+        return None;
+    }
+
+    let (r, _) = group_commitment(&commitments, context, prehash)?;
+    let r = r.compress();
+    let z = shares.iter().fold(Scalar::zero(), |acc, s| acc + s.z);
+
+    // Check `z B == R + c A`.
+    let a = CompressedEdwardsY(group_pk.to_bytes()).decompress()?;
+    let c = challenge(&r, group_pk, context, prehash);
+    if (&z * &ED25519_BASEPOINT_TABLE).compress() != (r.decompress()? + a * c).compress() {
+        return None;
+    }
+
+    let mut bytes = [0_u8; SIGNATURE_LENGTH];
+    bytes[..32].copy_from_slice(r.as_bytes());
+    bytes[32..].copy_from_slice(z.as_bytes());
+    Some(Signature::new(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature::ed25519::{
+        verify_prehashed_batch,
+        verify_prehashed_cofactored,
+        PrehashedSignature,
+    };
+
+    #[test]
+    fn test_frost_2_of_3() {
+        let (group_pk, shares) = deal(&[7_u8; 64], 2, 3).unwrap();
+        let context = b"IOTAStreams";
+        let prehash = [11_u8; 64];
+
+        let (nonces1, commitment1) = commit(&shares[0], &[1_u8; 64]);
+        let (nonces3, commitment3) = commit(&shares[2], &[3_u8; 64]);
+        let commitments = [commitment3, commitment1];
+        let share1 = sign(&shares[0], nonces1, &commitments, context, &prehash).unwrap();
+        let share3 = sign(&shares[2], nonces3, &commitments, context, &prehash).unwrap();
+
+        let signature = aggregate(&group_pk, &commitments, &[share1, share3], context, &prehash).unwrap();
+        let sig = PrehashedSignature {
+            pk: group_pk,
+            context: context.to_vec(),
+            hash: prehash,
+            signature,
+        };
+        assert!(verify_prehashed_cofactored(&sig));
+        assert!(verify_prehashed_batch(&[sig.clone(), sig]));
+
+        // A single signer can't sign.
+        let (nonces2, commitment2) = commit(&shares[1], &[2_u8; 64]);
+        let share2 = sign(&shares[1], nonces2, &[commitment2], context, &prehash).unwrap();
+        assert!(aggregate(&group_pk, &[commitment2], &[share2], context, &prehash).is_none());
+    }
+}
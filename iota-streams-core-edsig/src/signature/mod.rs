@@ -1 +1,6 @@
 pub mod ed25519;
+pub mod frost;
+#[cfg(feature = "pq")]
+pub mod mss;
+#[cfg(feature = "secp256k1")]
+pub mod secp256k1;
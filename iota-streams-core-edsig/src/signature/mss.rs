@@ -0,0 +1,296 @@
+//! Hash-based Merkle signature scheme: Winternitz one-time signatures of the leaves of a Merkle
+//! tree authenticated by the tree root. Security only relies on SHA-256, signatures stay
+//! unforgeable by quantum adversaries at the cost of 2.2 KB signatures and a limited number of
+//! signatures per key pair.
+//!
+//! The scheme is stateful: `Keypair` tracks the next unused leaf and a leaf must never sign
+//! twice. The key pair is derived from a seed, the index of the next leaf must be kept along
+//! with the seed to restore it.
+//!
+//! **Signing twice with a leaf breaks the scheme**: two one-time signatures of a leaf reveal
+//! enough of its hash chains to forge signatures of other messages. Restoring a key pair with
+//! a stale index, eg. from a backup taken before later signatures, reuses leaves.
+
+use iota_streams_core::prelude::{
+    vec,
+    Vec,
+};
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+/// Size of hash values in bytes.
+pub const N: usize = 32;
+
+/// Winternitz parameter, one-time signatures sign 4-bit digits.
+const W: usize = 16;
+
+/// Number of digits of a message hash.
+const LEN1: usize = 2 * N;
+
+/// Number of digits of the checksum, the checksum is at most `LEN1 * (W - 1) < W^3`.
+const LEN2: usize = 3;
+
+/// Number of hash chains of a one-time key.
+pub const WOTS_LEN: usize = LEN1 + LEN2;
+
+/// Maximal height of the Merkle tree. Key generation hashes `WOTS_LEN * W` times per leaf.
+pub const MAX_HEIGHT: usize = 16;
+
+pub const PUBLIC_KEY_LENGTH: usize = 2 * N;
+
+/// Size in bytes of signatures of a key pair with Merkle tree of `height`.
+pub fn signature_length(height: usize) -> usize {
+    4 + (WOTS_LEN + height) * N
+}
+
+type Hash = [u8; N];
+
+const DOMAIN_SECRET: u8 = 0;
+const DOMAIN_CHAIN: u8 = 1;
+const DOMAIN_LEAF: u8 = 2;
+const DOMAIN_NODE: u8 = 3;
+const DOMAIN_MESSAGE: u8 = 4;
+const DOMAIN_PUBLIC_SEED: u8 = 5;
+const DOMAIN_SEED: u8 = 6;
+
+fn hash(domain: u8, key: &Hash, data: &[&[u8]]) -> Hash {
+    let mut h = Sha256::new();
+    h.update(b"IOTAStreams MSS");
+    h.update(&[domain]);
+    h.update(key);
+    for d in data {
+        h.update(*d);
+    }
+    let mut out = [0_u8; N];
+    out.copy_from_slice(&h.finalize());
+    out
+}
+
+/// Advance hash chain `chain` of one-time key `leaf` from position `start` by `steps`.
+fn chain(pub_seed: &Hash, leaf: u32, chain: usize, start: usize, steps: usize, mut x: Hash) -> Hash {
+    for step in start..start + steps {
+        x = hash(
+            DOMAIN_CHAIN,
+            pub_seed,
+            &[&leaf.to_le_bytes(), &[chain as u8, step as u8], &x],
+        );
+    }
+    x
+}
+
+/// Digits of message hash `msg` followed by digits of their checksum.
+fn digits(msg: &Hash) -> [usize; WOTS_LEN] {
+    let mut digits = [0_usize; WOTS_LEN];
+    for (i, byte) in msg.iter().enumerate() {
+        digits[2 * i] = (byte >> 4) as usize;
+        digits[2 * i + 1] = (byte & 0x0f) as usize;
+    }
+    let checksum: usize = digits[..LEN1].iter().map(|d| W - 1 - d).sum();
+    digits[LEN1] = (checksum >> 8) & 0x0f;
+    digits[LEN1 + 1] = (checksum >> 4) & 0x0f;
+    digits[LEN1 + 2] = checksum & 0x0f;
+    digits
+}
+
+fn leaf_hash(pub_seed: &Hash, leaf: u32, ends: &[u8]) -> Hash {
+    hash(DOMAIN_LEAF, pub_seed, &[&leaf.to_le_bytes(), ends])
+}
+
+/// Parent of nodes `left` and `right` at `level` (leaves are at level 0) and `index`.
+fn node_hash(pub_seed: &Hash, level: usize, index: u32, left: &Hash, right: &Hash) -> Hash {
+    hash(
+        DOMAIN_NODE,
+        pub_seed,
+        &[&[level as u8], &index.to_le_bytes(), left, right],
+    )
+}
+
+fn message_hash(pk: &PublicKey, leaf: u32, msg: &[u8]) -> Hash {
+    hash(DOMAIN_MESSAGE, &pk.pub_seed, &[&pk.root, &leaf.to_le_bytes(), msg])
+}
+
+/// Public key: public seed of hashes and Merkle tree root.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PublicKey {
+    pub_seed: Hash,
+    root: Hash,
+}
+
+impl PublicKey {
+    pub fn to_bytes(&self) -> [u8; PUBLIC_KEY_LENGTH] {
+        let mut bytes = [0_u8; PUBLIC_KEY_LENGTH];
+        bytes[..N].copy_from_slice(&self.pub_seed);
+        bytes[N..].copy_from_slice(&self.root);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != PUBLIC_KEY_LENGTH {
+            return None;
+        }
+        let mut pk = Self::default();
+        pk.pub_seed.copy_from_slice(&bytes[..N]);
+        pk.root.copy_from_slice(&bytes[N..]);
+        Some(pk)
+    }
+}
+
+pub struct Keypair {
+    seed: Hash,
+    pub public: PublicKey,
+    /// Merkle tree levels from the leaves to the root.
+    tree: Vec<Vec<Hash>>,
+    next: u32,
+}
+
+impl Keypair {
+    /// Derive a key pair with Merkle tree of `height` from `seed`, the key pair can produce
+    /// `2^height` signatures. `None` if `height` is zero or above `MAX_HEIGHT`.
+    pub fn from_seed(seed: &[u8], height: usize) -> Option<Self> {
+        if height == 0 || height > MAX_HEIGHT {
+            return None;
+        }
+        let seed = hash(DOMAIN_SEED, &[0_u8; N], &[seed]);
+        let pub_seed = hash(DOMAIN_PUBLIC_SEED, &seed, &[]);
+
+        let mut leaves = Vec::with_capacity(1 << height);
+        let mut ends = vec![0_u8; WOTS_LEN * N];
+        for leaf in 0..(1_u32 << height) {
+            for j in 0..WOTS_LEN {
+                let sk = hash(DOMAIN_SECRET, &seed, &[&leaf.to_le_bytes(), &[j as u8]]);
+                ends[j * N..(j + 1) * N].copy_from_slice(&chain(&pub_seed, leaf, j, 0, W - 1, sk));
+            }
+            leaves.push(leaf_hash(&pub_seed, leaf, &ends));
+        }
+
+        let mut tree = vec![leaves];
+        for level in 1..=height {
+            let nodes = tree[level - 1]
+                .chunks(2)
+                .enumerate()
+                .map(|(i, pair)| node_hash(&pub_seed, level, i as u32, &pair[0], &pair[1]))
+                .collect();
+            tree.push(nodes);
+        }
+        let public = PublicKey {
+            pub_seed,
+            root: tree[height][0],
+        };
+        Some(Self {
+            seed,
+            public,
+            tree,
+            next: 0,
+        })
+    }
+
+    pub fn height(&self) -> usize {
+        self.tree.len() - 1
+    }
+
+    /// Index of the next unused leaf.
+    pub fn next_index(&self) -> u32 {
+        self.next
+    }
+
+    /// Skip leaves used before the key pair was restored from its seed.
+    pub fn set_next_index(&mut self, next: u32) {
+        self.next = core::cmp::max(self.next, next);
+    }
+
+    /// Number of signatures the key pair can still produce.
+    pub fn remaining(&self) -> u32 {
+        (1_u32 << self.height()) - self.next
+    }
+
+    pub fn signature_length(&self) -> usize {
+        signature_length(self.height())
+    }
+
+    /// Sign `msg` with the next unused leaf, `None` if all leaves are used.
+    pub fn sign(&mut self, msg: &[u8]) -> Option<Vec<u8>> {
+        if self.remaining() == 0 {
+            return None;
+        }
+        let leaf = self.next;
+        self.next += 1;
+
+        let digits = digits(&message_hash(&self.public, leaf, msg));
+        let mut sig = Vec::with_capacity(self.signature_length());
+        sig.extend_from_slice(&leaf.to_le_bytes());
+        for (j, digit) in digits.iter().enumerate() {
+            let sk = hash(DOMAIN_SECRET, &self.seed, &[&leaf.to_le_bytes(), &[j as u8]]);
+            sig.extend_from_slice(&chain(&self.public.pub_seed, leaf, j, 0, *digit, sk));
+        }
+        for level in 0..self.height() {
+            let sibling = ((leaf >> level) ^ 1) as usize;
+            sig.extend_from_slice(&self.tree[level][sibling]);
+        }
+        Some(sig)
+    }
+}
+
+/// Verify signature `sig` of `msg` with public key `pk`.
+pub fn verify(pk: &PublicKey, msg: &[u8], sig: &[u8]) -> bool {
+    if sig.len() < signature_length(1) || (sig.len() - 4) % N != 0 {
+        return false;
+    }
+    let height = (sig.len() - 4) / N - WOTS_LEN;
+    if height > MAX_HEIGHT {
+        return false;
+    }
+    let mut leaf = [0_u8; 4];
+    leaf.copy_from_slice(&sig[..4]);
+    let leaf = u32::from_le_bytes(leaf);
+    if leaf >> height != 0 {
+        return false;
+    }
+
+    let digits = digits(&message_hash(pk, leaf, msg));
+    let mut ends = vec![0_u8; WOTS_LEN * N];
+    let mut x = [0_u8; N];
+    for (j, digit) in digits.iter().enumerate() {
+        x.copy_from_slice(&sig[4 + j * N..4 + (j + 1) * N]);
+        ends[j * N..(j + 1) * N].copy_from_slice(&chain(&pk.pub_seed, leaf, j, *digit, W - 1 - digit, x));
+    }
+
+    let mut node = leaf_hash(&pk.pub_seed, leaf, &ends);
+    let path = &sig[4 + WOTS_LEN * N..];
+    for level in 0..height {
+        let mut sibling = [0_u8; N];
+        sibling.copy_from_slice(&path[level * N..(level + 1) * N]);
+        let index = leaf >> (level + 1);
+        node = if (leaf >> level) & 1 == 0 {
+            node_hash(&pk.pub_seed, level + 1, index, &node, &sibling)
+        } else {
+            node_hash(&pk.pub_seed, level + 1, index, &sibling, &node)
+        };
+    }
+    node == pk.root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mss_sign_verify() {
+        let mut kp = Keypair::from_seed(b"MSS9SEED", 2).unwrap();
+        let pk = PublicKey::from_bytes(&kp.public.to_bytes()).unwrap();
+        for i in 0..4_u8 {
+            let msg = [i; 64];
+            let sig = kp.sign(&msg).unwrap();
+            assert_eq!(kp.signature_length(), sig.len());
+            assert!(verify(&pk, &msg, &sig));
+            assert!(!verify(&pk, &[i + 1; 64], &sig));
+        }
+        assert!(kp.sign(&[0_u8; 64]).is_none());
+
+        let mut restored = Keypair::from_seed(b"MSS9SEED", 2).unwrap();
+        assert_eq!(kp.public, restored.public);
+        restored.set_next_index(3);
+        assert_eq!(1, restored.remaining());
+    }
+}
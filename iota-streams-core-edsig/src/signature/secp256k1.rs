@@ -0,0 +1,82 @@
+//! ECDSA secp256k1 co-signatures of message digests, verifiable by Ethereum contracts.
+//!
+//! A digest is signed as Ethereum does: it is hashed with Keccak-256 and signed with a
+//! recoverable signature `r || s || v`, where `v` is the recovery id 0 or 1. A contract checks
+//! the co-signature with `ecrecover(keccak256(digest), v + 27, r, s)` against the address of
+//! the co-signer, see `eth_address`.
+
+use k256::ecdsa::{
+    recoverable,
+    signature::{
+        Signature as _,
+        Signer as _,
+    },
+};
+pub use k256::ecdsa::{
+    SigningKey,
+    VerifyingKey,
+};
+use sha3::{
+    Digest,
+    Keccak256,
+};
+
+/// Size in bytes of co-signed digests.
+pub const DIGEST_LENGTH: usize = 32;
+
+/// Size in bytes of recoverable signatures `r || s || v`.
+pub const SIGNATURE_LENGTH: usize = 65;
+
+/// Size in bytes of Ethereum addresses.
+pub const ADDRESS_LENGTH: usize = 20;
+
+/// Recoverable signature of `digest` with `sk`.
+pub fn sign(sk: &SigningKey, digest: &[u8; DIGEST_LENGTH]) -> [u8; SIGNATURE_LENGTH] {
+    let sig: recoverable::Signature = sk.sign(digest);
+    let mut bytes = [0_u8; SIGNATURE_LENGTH];
+    bytes.copy_from_slice(sig.as_bytes());
+    bytes
+}
+
+/// Public key of the signer of `digest`, `None` if `sig` is malformed.
+pub fn recover(digest: &[u8; DIGEST_LENGTH], sig: &[u8; SIGNATURE_LENGTH]) -> Option<VerifyingKey> {
+    let sig = recoverable::Signature::from_bytes(sig).ok()?;
+    sig.recover_verifying_key(digest).ok()
+}
+
+/// Ethereum address of `pk`: the last 20 bytes of the Keccak-256 hash of the uncompressed key.
+pub fn eth_address(pk: &VerifyingKey) -> [u8; ADDRESS_LENGTH] {
+    let point = pk.to_encoded_point(false);
+    let hash = Keccak256::digest(&point.as_bytes()[1..]);
+    let mut address = [0_u8; ADDRESS_LENGTH];
+    address.copy_from_slice(&hash[32 - ADDRESS_LENGTH..]);
+    address
+}
+
+/// Co-signature of a message digest.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cosignature {
+    /// Co-signed digest of the message
+    pub digest: [u8; DIGEST_LENGTH],
+    /// Recoverable signature `r || s || v` of the digest
+    pub signature: [u8; SIGNATURE_LENGTH],
+}
+
+impl Cosignature {
+    /// Public key of the co-signer, `None` if the signature is malformed.
+    pub fn signer(&self) -> Option<VerifyingKey> {
+        recover(&self.digest, &self.signature)
+    }
+
+    /// Whether the digest is co-signed by `pk`.
+    pub fn verify(&self, pk: &VerifyingKey) -> bool {
+        self.signer().map_or(false, |signer| signer == *pk)
+    }
+
+    /// Signature with `v` shifted to 27 or 28 as expected by `ecrecover`.
+    pub fn to_eth_bytes(&self) -> [u8; SIGNATURE_LENGTH] {
+        let mut bytes = self.signature;
+        bytes[SIGNATURE_LENGTH - 1] += 27;
+        bytes
+    }
+}
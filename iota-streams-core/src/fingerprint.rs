@@ -0,0 +1,46 @@
+//! Fingerprints of secrets for diagnostics.
+//!
+//! A fingerprint identifies a secret, eg. to check that two parties hold the same pre-shared
+//! key, without revealing it: it is a short hash of the secret under `FINGERPRINT_DOMAIN`.
+//! `Debug` implementations of types holding secrets print fingerprints instead of raw bytes.
+
+use core::fmt;
+
+use crate::{
+    kdf::Kdf,
+    sponge::prp::PRP,
+};
+
+/// Domain of fingerprints.
+pub const FINGERPRINT_DOMAIN: &str = "IOTA Streams fingerprint";
+
+/// Size of fingerprints in bytes.
+pub const FINGERPRINT_SIZE: usize = 8;
+
+/// Fingerprint of a secret, displayed as hex.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Fingerprint(pub [u8; FINGERPRINT_SIZE]);
+
+impl Fingerprint {
+    /// Fingerprint of `secret` hashed with spongos over `F`.
+    pub fn of<F: PRP>(secret: impl AsRef<[u8]>) -> Self {
+        let mut fp = [0_u8; FINGERPRINT_SIZE];
+        Kdf::<F>::extract(secret).expand(FINGERPRINT_DOMAIN, &mut fp);
+        Self(fp)
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in &self.0 {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fingerprint({})", self)
+    }
+}
@@ -0,0 +1,88 @@
+//! Spongos-based key derivation in extract/expand style.
+//!
+//! `Kdf::extract` absorbs input key material of arbitrary quality and commits; `Kdf::with_key`
+//! starts from a uniformly random key and skips the commit. Keys are expanded from the
+//! resulting state under labels: `expand` forks the state, absorbs the label, commits and
+//! squeezes output key material, so keys expanded under different labels are independent and
+//! the state can be reused for further derivations.
+//!
+//! ```text
+//! extract(ikm).expand(label) = init; absorb ikm; commit; absorb label; commit; squeeze okm
+//! with_key(key).expand(label) = init; absorb key; absorb label; commit; squeeze okm
+//! ```
+//!
+//! Derivations are used for long-lived keys (eg. user key pairs derived from seeds), their
+//! output must not change.
+
+use crate::{
+    prelude::{
+        generic_array::{
+            ArrayLength,
+            GenericArray,
+        },
+        Vec,
+    },
+    sponge::{
+        prp::PRP,
+        spongos::Spongos,
+    },
+};
+
+/// Key derivation state.
+#[derive(Clone)]
+pub struct Kdf<F> {
+    s: Spongos<F>,
+}
+
+impl<F: PRP> Kdf<F> {
+    /// Extract pseudo-random state from input key material `ikm`.
+    pub fn extract(ikm: impl AsRef<[u8]>) -> Self {
+        let mut s = Spongos::init();
+        s.absorb(ikm);
+        s.commit();
+        Self { s }
+    }
+
+    /// Start from a uniformly random `key`, no extraction is needed.
+    pub fn with_key(key: impl AsRef<[u8]>) -> Self {
+        let mut s = Spongos::init();
+        s.absorb(key);
+        Self { s }
+    }
+
+    /// Bind the state to additional context `info`, expanded keys depend on it as if it was
+    /// prepended to labels.
+    pub fn absorb(&mut self, info: impl AsRef<[u8]>) -> &mut Self {
+        self.s.absorb(info);
+        self
+    }
+
+    /// Expand output key material labeled `label` into `okm`.
+    pub fn expand(&self, label: impl AsRef<[u8]>, okm: impl AsMut<[u8]>) {
+        let mut s = self.s.fork();
+        s.absorb(label);
+        s.commit();
+        s.squeeze(okm);
+    }
+
+    /// Expand output key material labeled `label`, length inferred from output type.
+    pub fn expand_arr<N: ArrayLength<u8>>(&self, label: impl AsRef<[u8]>) -> GenericArray<u8, N> {
+        let mut okm = GenericArray::default();
+        self.expand(label, &mut okm);
+        okm
+    }
+
+    /// Expand `n` bytes of output key material labeled `label`.
+    pub fn expand_n(&self, label: impl AsRef<[u8]>, n: usize) -> Vec<u8> {
+        let mut okm = vec![0; n];
+        self.expand(label, &mut okm);
+        okm
+    }
+}
+
+impl<F: PRP> From<Spongos<F>> for Kdf<F> {
+    /// Derive keys from a (forked) spongos state, eg. of a message being wrapped.
+    fn from(s: Spongos<F>) -> Self {
+        Self { s }
+    }
+}
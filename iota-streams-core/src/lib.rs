@@ -38,7 +38,10 @@ pub use alloc::format;
 #[cfg(feature = "std")]
 pub use std::format;
 
+pub mod fingerprint;
+pub mod kdf;
 pub mod prelude;
 pub mod prng;
 pub mod psk;
+pub mod seed;
 pub mod sponge;
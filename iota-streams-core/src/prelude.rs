@@ -65,3 +65,14 @@ pub use digest::{
 };
 
 pub use hex;
+
+/// Bounded vectors with capacities fixed at compile time, for deterministic memory use of DDML
+/// output buffers on embedded targets. The prelude collections themselves remain allocating,
+/// message payloads and user state have no size bound known at compile time.
+#[cfg(feature = "heapless")]
+pub mod bounded {
+    pub use heapless;
+
+    /// Vector of at most `N` elements.
+    pub type Vec<T, const N: usize> = heapless::Vec<T, N>;
+}
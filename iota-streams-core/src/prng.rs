@@ -1,9 +1,21 @@
 //! Spongos-based pseudo-random number generator.
 
+use core::fmt;
+#[cfg(feature = "test-vectors")]
+use core::cell::RefCell;
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
+
 use crate::{
+    fingerprint::Fingerprint,
+    kdf::Kdf,
     prelude::{
         generic_array::{
             typenum::{
+                Unsigned,
                 U16,
                 U32,
             },
@@ -14,12 +26,15 @@ use crate::{
     },
     sponge::{
         prp::PRP,
-        spongos::{
-            self,
-            Spongos,
-        },
+        spongos,
     },
 };
+use rand::{
+    distributions::uniform::SampleUniform,
+    seq::SliceRandom,
+};
+#[cfg(feature = "test-vectors")]
+use crate::prelude::Box;
 
 /// Generate cryptographically secure bytes.
 /// Suitable for generating session and ephemeral keys.
@@ -32,34 +47,101 @@ where
     rnd
 }
 
-pub type Nonce = GenericArray<u8, U16>;
+/// Entropy source of `SystemRng` in `no_std` environments, stored as `fn(&mut [u8])`.
+#[cfg(not(feature = "std"))]
+static ENTROPY_SOURCE: AtomicUsize = AtomicUsize::new(0);
 
-/// Generate a random nonce.
-#[cfg(feature = "std")]
-pub fn random_nonce() -> Nonce {
-    random_bytes::<rand::rngs::ThreadRng, U16>(&mut rand::thread_rng())
+/// Set the entropy source of `SystemRng` in `no_std` environments, eg. a hardware RNG.
+/// `fill` must fill the buffer with cryptographically secure random bytes.
+#[cfg(not(feature = "std"))]
+pub fn set_entropy_source(fill: fn(&mut [u8])) {
+    ENTROPY_SOURCE.store(fill as usize, Ordering::Release);
 }
 
-#[cfg(not(feature = "std"))]
+#[cfg(feature = "test-vectors")]
+thread_local! {
+    /// RNG of `SystemRng` on the current thread set with `with_rng`.
+    static RNG_OVERRIDE: RefCell<Option<Box<dyn rand::RngCore>>> = RefCell::new(None);
+}
+
+/// Run `f` with `SystemRng` of the current thread drawing from `rng` instead of the system
+/// RNG, eg. from a seeded `Rng` to reproduce nonces and session keys of test vectors. Only
+/// available with the `test-vectors` feature, which must not be enabled in production builds.
+#[cfg(feature = "test-vectors")]
+pub fn with_rng<R: rand::RngCore + 'static, T>(rng: R, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Box<dyn rand::RngCore>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let prev = self.0.take();
+            RNG_OVERRIDE.with(|o| *o.borrow_mut() = prev);
+        }
+    }
+
+    let _restore = Restore(RNG_OVERRIDE.with(|o| o.replace(Some(Box::new(rng)))));
+    f()
+}
+
+/// Default cryptographically secure RNG: `rand::thread_rng` with `std` (or the RNG set with
+/// `with_rng` with `test-vectors`), otherwise the entropy source set with `set_entropy_source`. Used for nonces,
+/// session and ephemeral keys.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct SystemRng;
+
+impl rand::RngCore for SystemRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut v = [0_u8; 4];
+        self.fill_bytes(&mut v);
+        u32::from_le_bytes(v)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut v = [0_u8; 8];
+        self.fill_bytes(&mut v);
+        u64::from_le_bytes(v)
+    }
+
+    #[cfg(feature = "test-vectors")]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        RNG_OVERRIDE.with(|o| match o.borrow_mut().as_mut() {
+            Some(rng) => rng.fill_bytes(dest),
+            None => rand::thread_rng().fill_bytes(dest),
+        })
+    }
+
+    #[cfg(all(feature = "std", not(feature = "test-vectors")))]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand::thread_rng().fill_bytes(dest)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let fill = ENTROPY_SOURCE.load(Ordering::Acquire);
+        assert!(fill != 0, "No entropy source set, see `prng::set_entropy_source`.");
+        // Safe: only `fn(&mut [u8])` pointers are stored in `ENTROPY_SOURCE`.
+        let fill = unsafe { core::mem::transmute::<usize, fn(&mut [u8])>(fill) };
+        fill(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl rand::CryptoRng for SystemRng {}
+
+pub type Nonce = GenericArray<u8, U16>;
+
+/// Generate a random nonce.
 pub fn random_nonce() -> Nonce {
-    // TODO: Set default global RNG for `no_std` environment.
-    // Use Rng and init with entropy.
-    panic!("No default global RNG present.");
+    random_bytes::<SystemRng, U16>(&mut SystemRng)
 }
 
 pub type Key = GenericArray<u8, U32>;
 
 /// Generate a random key.
-#[cfg(feature = "std")]
-pub fn random_key() -> Key {
-    random_bytes::<rand::rngs::ThreadRng, U32>(&mut rand::thread_rng())
-}
-
-#[cfg(not(feature = "std"))]
 pub fn random_key() -> Key {
-    // TODO: Set default global RNG for `no_std` environment.
-    // Use Rng and init with entropy.
-    panic!("No default global RNG present.");
+    random_bytes::<SystemRng, U32>(&mut SystemRng)
 }
 
 /// Prng fixed key size.
@@ -85,23 +167,9 @@ impl<G: PRP> Prng<G> {
     }
 
     // TODO: PRNG randomness hierarchy via nonce: domain (seed, ed/x25519, session key, etc.), secret, counter.
-    fn gen_with_spongos<'a>(&self, s: &mut Spongos<G>, nonces: &[&'a [u8]], rnds: &mut [&'a mut [u8]]) {
-        // TODO: `dst` byte?
-        // TODO: Reimplement PRNG with DDML?
-        s.absorb(&self.secret_key[..]);
-        for nonce in nonces {
-            s.absorb(nonce);
-        }
-        s.commit();
-        for rnd in rnds {
-            s.squeeze(rnd);
-        }
-    }
-
     /// Generate randomness with a unique nonce for the current PRNG instance.
-    pub fn gen(&self, nonce: impl AsRef<[u8]>, mut rnd: impl AsMut<[u8]>) {
-        let mut s = Spongos::<G>::init();
-        self.gen_with_spongos(&mut s, &[nonce.as_ref()], &mut [rnd.as_mut()]);
+    pub fn gen(&self, nonce: impl AsRef<[u8]>, rnd: impl AsMut<[u8]>) {
+        Kdf::<G>::with_key(&self.secret_key[..]).expand(nonce, rnd);
     }
 
     pub fn gen_arr<N: ArrayLength<u8>>(&self, nonce: impl AsRef<[u8]>) -> GenericArray<u8, N> {
@@ -116,6 +184,17 @@ impl<G: PRP> Prng<G> {
         self.gen(nonce, &mut rnd);
         rnd
     }
+
+    /// Fingerprint of the secret key.
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::of::<G>(&self.secret_key[..])
+    }
+}
+
+impl<G: PRP> fmt::Debug for Prng<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Prng").field("fingerprint", &self.fingerprint()).finish()
+    }
 }
 
 pub fn init<G: PRP>(secret_key: KeyType<G>) -> Prng<G> {
@@ -123,12 +202,12 @@ pub fn init<G: PRP>(secret_key: KeyType<G>) -> Prng<G> {
 }
 
 pub fn from_seed<G: PRP>(domain: &str, seed: &str) -> Prng<G> {
-    let mut s = Spongos::<G>::init();
-    s.absorb(seed.as_bytes());
-    s.commit();
-    s.absorb(domain.as_bytes());
-    s.commit();
-    Prng::init(s.squeeze_arr())
+    from_seed_bytes(domain, seed.as_bytes())
+}
+
+/// Init prng with a binary seed, the same as `from_seed` for the UTF-8 encoding of a string seed.
+pub fn from_seed_bytes<G: PRP>(domain: &str, seed: &[u8]) -> Prng<G> {
+    Prng::init(Kdf::<G>::extract(seed).expand_arr(domain.as_bytes()))
 }
 
 pub fn dbg_init_str<G: PRP>(secret_key: &str) -> Prng<G> {
@@ -140,18 +219,30 @@ pub type NonceSize<F> = spongos::NonceSize<F>;
 //pub type NonceType<F> = spongos::NonceType<F>;
 pub type NonceType = Vec<u8>;
 
+/// Deterministic RNG over `Prng` with a counter nonce, each output is generated with the next
+/// nonce. Implements the `rand` traits so it can be used with `rand::Rng` and `rand::seq`
+/// helpers, eg. in property tests and protocol simulations.
 pub struct Rng<G: PRP> {
     prng: Prng<G>,
     nonce: NonceType,
 }
 
+impl<G: PRP> fmt::Debug for Rng<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rng")
+            .field("prng", &self.prng)
+            .field("nonce", &self.nonce.len())
+            .finish()
+    }
+}
+
 impl<G: PRP> Rng<G> {
     pub fn new(prng: Prng<G>, nonce: NonceType) -> Self {
         Self { prng, nonce }
     }
     fn inc(&mut self) -> bool {
         for i in self.nonce.iter_mut() {
-            *i = *i + 1;
+            *i = i.wrapping_add(1);
             if *i != 0 {
                 return true;
             }
@@ -159,6 +250,28 @@ impl<G: PRP> Rng<G> {
         //self.nonce.push(0);
         false
     }
+
+    /// Generate a value uniformly distributed in `[low, high)`.
+    pub fn gen_range<T: SampleUniform>(&mut self, low: T, high: T) -> T {
+        rand::Rng::gen_range(self, low, high)
+    }
+
+    /// Generate `N` random bytes.
+    pub fn gen_arr<N: ArrayLength<u8>>(&mut self) -> GenericArray<u8, N> {
+        let mut rnd = GenericArray::default();
+        rand::RngCore::fill_bytes(self, rnd.as_mut_slice());
+        rnd
+    }
+
+    /// Shuffle `values` in place.
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        values.shuffle(self)
+    }
+
+    /// Choose a random element of `values`, `None` if it's empty.
+    pub fn choose<'a, T>(&mut self, values: &'a [T]) -> Option<&'a T> {
+        values.choose(self)
+    }
 }
 
 impl<G: PRP> rand::RngCore for Rng<G> {
@@ -185,3 +298,13 @@ impl<G: PRP> rand::RngCore for Rng<G> {
 }
 
 impl<G: PRP> rand::CryptoRng for Rng<G> {}
+
+impl<G: PRP> rand::SeedableRng for Rng<G> {
+    /// Secret key of the underlying `Prng`.
+    type Seed = KeyType<G>;
+
+    /// Create RNG with `Prng` key `seed` and zero nonce of `NonceSize` bytes.
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(Prng::init(seed), vec![0; NonceSize::<G>::USIZE])
+    }
+}
@@ -1,16 +1,30 @@
 //! Pre-shared key is a secret symmetric key shared between two parties and is used for
 //! (session) key exchange.
+//!
+//! With the `passphrase` feature pre-shared keys can be derived from human-enterable
+//! passphrases with Argon2id, see `from_passphrase`.
 
-use crate::prelude::{
-    generic_array::{
-        typenum::{
-            U16,
-            U32,
+#[cfg(feature = "passphrase")]
+use anyhow::{
+    anyhow,
+    Result,
+};
+
+use crate::{
+    fingerprint::Fingerprint,
+    kdf::Kdf,
+    prelude::{
+        generic_array::{
+            typenum::{
+                U16,
+                U32,
+            },
+            GenericArray,
         },
-        GenericArray,
+        HashMap,
+        Vec,
     },
-    HashMap,
-    Vec,
+    sponge::prp::PRP,
 };
 
 /// Size of pre-shared key identifier.
@@ -37,6 +51,61 @@ pub type Psks = HashMap<PskId, Psk>;
 /// Container (set) of pre-shared key identifiers.
 pub type PskIds = Vec<PskId>;
 
+/// Argon2id cost parameters of passphrase-derived keys, all parties must use the same ones.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PassphraseParams {
+    /// Memory size in KiB.
+    pub m_cost: u32,
+    /// Number of passes.
+    pub t_cost: u32,
+    /// Degree of parallelism.
+    pub parallelism: u32,
+}
+
+impl Default for PassphraseParams {
+    /// 19 MiB, 2 passes, no parallelism: affordable for constrained devices.
+    fn default() -> Self {
+        Self {
+            m_cost: 19 * 1024,
+            t_cost: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+/// Derive a pre-shared key from `passphrase` with Argon2id. `salt` must be at least 8 bytes
+/// long and should be unique to the channel, eg. the channel address.
+#[cfg(feature = "passphrase")]
+pub fn from_passphrase(passphrase: &str, salt: &[u8], params: PassphraseParams) -> Result<Psk> {
+    use argon2::{
+        Algorithm,
+        Argon2,
+        Version,
+    };
+    let argon2 = Argon2::new(None, params.t_cost, params.m_cost, params.parallelism, Version::V0x13)
+        .map_err(|e| anyhow!("Bad Argon2 parameters: {:?}.", e))?;
+    let mut psk = Psk::default();
+    argon2
+        .hash_password_into(Algorithm::Argon2id, passphrase.as_bytes(), salt, &[], psk.as_mut_slice())
+        .map_err(|e| anyhow!("Argon2 failed: {:?}.", e))?;
+    Ok(psk)
+}
+
+/// Domain of pre-shared key identifiers derived with `pskid_from_psk`.
+pub const PSKID_DOMAIN: &str = "IOTA Streams pskid";
+
+/// Derive the identifier of `psk`: `psk` is extracted (see `kdf`) and the identifier is expanded
+/// under `PSKID_DOMAIN`. Bindings must derive identifiers the same way for keyloads to resolve
+/// keys across SDKs.
+pub fn pskid_from_psk<F: PRP>(psk: &Psk) -> PskId {
+    Kdf::<F>::extract(psk).expand_arr(PSKID_DOMAIN)
+}
+
+/// Fingerprint of `psk` for diagnostics, unlike `pskid_from_psk` it's not sent in keyloads.
+pub fn fingerprint<F: PRP>(psk: &Psk) -> Fingerprint {
+    Fingerprint::of::<F>(psk)
+}
+
 /// Select only pre-shared keys with given identifiers.
 pub fn filter_psks<'a>(psks: &'a Psks, psk_ids: &'_ PskIds) -> Vec<IPsk<'a>> {
     psk_ids
@@ -0,0 +1,338 @@
+//! User seeds and their Shamir secret sharing for backup with threshold recovery.
+//!
+//! `Seed` holds validated seed bytes and wipes them when dropped. String seeds consist of
+//! `MIN_STR_SEED_LEN` to `MAX_STR_SEED_LEN` characters A-Z and 9, so eg. URLs or empty strings
+//! passed by mistake are rejected; binary seeds are `MIN_SEED_SIZE` to `MAX_SEED_SIZE` bytes.
+//! With the `mnemonic` feature seeds can also be recovered from BIP-39 mnemonics.
+//!
+//! A seed is split into `n` shares, any `k` of them recover the seed while fewer reveal
+//! nothing about it. Each byte of the seed is shared independently with a random polynomial
+//! of degree `k - 1` over GF(2^8).
+//!
+//! Combining fewer than `k` shares, or shares of different seeds, results in a wrong seed
+//! without an error, it's up to the caller to check the recovered seed, eg. by comparing
+//! the derived public key.
+
+use anyhow::{
+    ensure,
+    Result,
+};
+#[cfg(feature = "mnemonic")]
+use anyhow::anyhow;
+
+use core::{
+    fmt,
+    str::FromStr,
+};
+use zeroize::Zeroize;
+
+use crate::prelude::Vec;
+
+/// Minimal length of string seeds in characters.
+pub const MIN_STR_SEED_LEN: usize = 8;
+
+/// Maximal length of string seeds in characters.
+pub const MAX_STR_SEED_LEN: usize = 81;
+
+/// Minimal size of binary seeds in bytes.
+pub const MIN_SEED_SIZE: usize = 16;
+
+/// Maximal size of binary seeds in bytes.
+pub const MAX_SEED_SIZE: usize = 64;
+
+/// Validated user seed, zeroized on drop. `Debug` doesn't print the seed and comparison is
+/// constant-time.
+#[derive(Clone)]
+pub struct Seed(Vec<u8>);
+
+impl Seed {
+    /// Binary seed of `MIN_SEED_SIZE` to `MAX_SEED_SIZE` bytes.
+    pub fn from_bytes(seed: &[u8]) -> Result<Self> {
+        ensure!(
+            MIN_SEED_SIZE <= seed.len() && seed.len() <= MAX_SEED_SIZE,
+            "Seed size out of range: {} bytes.",
+            seed.len()
+        );
+        Ok(Self(seed.to_vec()))
+    }
+
+    /// Seed recovered from a BIP-39 `mnemonic` and optional `passphrase`.
+    #[cfg(feature = "mnemonic")]
+    pub fn from_mnemonic(mnemonic: &str, passphrase: &str) -> Result<Self> {
+        let mnemonic = bip39::Mnemonic::parse_normalized(mnemonic).map_err(|e| anyhow!("Bad mnemonic: {}.", e))?;
+        let mut seed = mnemonic.to_seed_normalized(passphrase);
+        let s = Self::from_bytes(&seed);
+        seed.zeroize();
+        s
+    }
+
+    /// Seed bytes, a string seed gives its UTF-8 bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl FromStr for Seed {
+    type Err = anyhow::Error;
+
+    /// String seed of `MIN_STR_SEED_LEN` to `MAX_STR_SEED_LEN` characters A-Z and 9.
+    fn from_str(seed: &str) -> Result<Self> {
+        ensure!(
+            MIN_STR_SEED_LEN <= seed.len() && seed.len() <= MAX_STR_SEED_LEN,
+            "Seed length out of range: {} characters.",
+            seed.len()
+        );
+        ensure!(
+            seed.bytes().all(|c| c.is_ascii_uppercase() || c == b'9'),
+            "Seed must consist of characters A-Z and 9."
+        );
+        Ok(Self(seed.as_bytes().to_vec()))
+    }
+}
+
+impl PartialEq for Seed {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len() && self.0.iter().zip(&other.0).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+}
+
+impl Eq for Seed {}
+
+impl Drop for Seed {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Seed(<{} bytes redacted>)", self.0.len())
+    }
+}
+
+/// Share of a seed: x-coordinate `index` and values of the sharing polynomials at it.
+/// `Debug` doesn't print the values.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Share {
+    pub index: u8,
+    pub value: Vec<u8>,
+}
+
+impl fmt::Debug for Share {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Share")
+            .field("index", &self.index)
+            .field("value", &format_args!("<{} bytes redacted>", self.value.len()))
+            .finish()
+    }
+}
+
+impl Share {
+    /// Encode share as `index || value`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + self.value.len());
+        bytes.push(self.index);
+        bytes.extend_from_slice(&self.value);
+        bytes
+    }
+
+    /// Decode share encoded with `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(bytes.len() > 1, "Share is too short.");
+        ensure!(bytes[0] != 0, "Bad share index.");
+        Ok(Self {
+            index: bytes[0],
+            value: bytes[1..].to_vec(),
+        })
+    }
+}
+
+/// Multiplication in GF(2^8) with the AES polynomial `x^8 + x^4 + x^3 + x + 1`.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut r = 0_u8;
+    while b != 0 {
+        if b & 1 != 0 {
+            r ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    r
+}
+
+/// Multiplicative inverse in GF(2^8), `a^254`; `a` must be non-zero.
+fn gf_inv(a: u8) -> u8 {
+    let mut r = 1_u8;
+    let mut p = a;
+    let mut e = 254_u8;
+    while e != 0 {
+        if e & 1 != 0 {
+            r = gf_mul(r, p);
+        }
+        p = gf_mul(p, p);
+        e >>= 1;
+    }
+    r
+}
+
+/// Evaluate polynomial with coefficients `coefs` (constant term first) at `x`.
+fn eval(coefs: &[u8], x: u8) -> u8 {
+    coefs.iter().rev().fold(0, |acc, c| gf_mul(acc, x) ^ c)
+}
+
+/// Split `seed` into `n` shares with recovery threshold `k` using random coefficients from `rng`.
+pub fn split_with_rng<R>(seed: &[u8], k: u8, n: u8, rng: &mut R) -> Result<Vec<Share>>
+where
+    R: rand::RngCore + rand::CryptoRng,
+{
+    ensure!(!seed.is_empty(), "Seed is empty.");
+    ensure!(0 < k && k <= n, "Bad threshold {} of {} shares.", k, n);
+
+    let mut shares: Vec<Share> = (1..=n)
+        .map(|index| Share {
+            index,
+            value: Vec::with_capacity(seed.len()),
+        })
+        .collect();
+    let mut coefs = vec![0_u8; k as usize];
+    for s in seed {
+        coefs[0] = *s;
+        rng.fill_bytes(&mut coefs[1..]);
+        for share in shares.iter_mut() {
+            share.value.push(eval(&coefs, share.index));
+        }
+    }
+    for c in coefs.iter_mut() {
+        *c = 0;
+    }
+    Ok(shares)
+}
+
+/// Split `seed` into `n` shares with recovery threshold `k`.
+pub fn split(seed: &[u8], k: u8, n: u8) -> Result<Vec<Share>> {
+    split_with_rng(seed, k, n, &mut crate::prng::SystemRng)
+}
+
+/// Recover seed from at least threshold number of `shares`.
+pub fn combine(shares: &[Share]) -> Result<Vec<u8>> {
+    ensure!(!shares.is_empty(), "No shares.");
+    let len = shares[0].value.len();
+    for (i, share) in shares.iter().enumerate() {
+        ensure!(share.index != 0, "Bad share index.");
+        ensure!(share.value.len() == len, "Shares of different length.");
+        ensure!(
+            shares[..i].iter().all(|s| s.index != share.index),
+            "Duplicate share index {}.",
+            share.index
+        );
+    }
+
+    // Lagrange basis polynomials at zero: l_j(0) = prod_{m != j} x_m / (x_m - x_j).
+    let basis: Vec<u8> = shares
+        .iter()
+        .map(|sj| {
+            shares
+                .iter()
+                .filter(|sm| sm.index != sj.index)
+                .fold(1, |acc, sm| gf_mul(acc, gf_mul(sm.index, gf_inv(sm.index ^ sj.index))))
+        })
+        .collect();
+
+    Ok((0..len)
+        .map(|i| {
+            shares
+                .iter()
+                .zip(basis.iter())
+                .fold(0, |acc, (s, l)| acc ^ gf_mul(s.value[i], *l))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RNG returning the same byte, to fix the sharing polynomials.
+    struct ConstRng(u8);
+
+    impl rand::RngCore for ConstRng {
+        fn next_u32(&mut self) -> u32 {
+            u32::from_le_bytes([self.0; 4])
+        }
+        fn next_u64(&mut self) -> u64 {
+            u64::from_le_bytes([self.0; 8])
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.iter_mut().for_each(|b| *b = self.0);
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> core::result::Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl rand::CryptoRng for ConstRng {}
+
+    #[test]
+    fn test_gf_known_values() {
+        // FIPS 197, section 4.2.
+        assert_eq!(0xc1, gf_mul(0x57, 0x83));
+        assert_eq!(0xfe, gf_mul(0x57, 0x13));
+        assert_eq!(0xca, gf_inv(0x53));
+        for a in 1..=255 {
+            assert_eq!(1, gf_mul(a, gf_inv(a)), "inverse of {}", a);
+        }
+    }
+
+    #[test]
+    fn test_split_known_shares() {
+        // Polynomial 0x42 + 0x57 x.
+        let shares = split_with_rng(&[0x42], 2, 3, &mut ConstRng(0x57)).unwrap();
+        let values: Vec<(u8, u8)> = shares.iter().map(|s| (s.index, s.value[0])).collect();
+        assert_eq!(vec![(1, 0x15), (2, 0xec), (3, 0xbb)], values);
+        assert_eq!(vec![0x42], combine(&shares[1..]).unwrap());
+    }
+
+    #[test]
+    fn test_split_combine_3_of_5() {
+        let seed: Vec<u8> = (0..32).collect();
+        let shares = split(&seed, 3, 5).unwrap();
+        assert_eq!(seed, combine(&shares).unwrap());
+        for i in 0..5 {
+            for j in i + 1..5 {
+                for m in j + 1..5 {
+                    let subset = [shares[m].clone(), shares[i].clone(), shares[j].clone()];
+                    assert_eq!(seed, combine(&subset).unwrap());
+                }
+                assert_ne!(seed, combine(&[shares[i].clone(), shares[j].clone()]).unwrap());
+            }
+        }
+
+        let encoded = shares[0].to_bytes();
+        assert_eq!(shares[0], Share::from_bytes(&encoded).unwrap());
+    }
+
+    #[test]
+    fn test_combine_bad_shares() {
+        let shares = split(b"SEED", 2, 3).unwrap();
+        assert!(combine(&[]).is_err());
+        assert!(combine(&[shares[0].clone(), shares[0].clone()]).is_err());
+        assert!(combine(&[shares[0].clone(), shares[1].clone(), shares[0].clone()]).is_err());
+
+        let mut zero = shares[1].clone();
+        zero.index = 0;
+        assert!(combine(&[shares[0].clone(), zero]).is_err());
+
+        let mut short = shares[1].clone();
+        short.value.pop();
+        assert!(combine(&[shares[0].clone(), short]).is_err());
+
+        assert!(split(b"SEED", 0, 3).is_err());
+        assert!(split(b"SEED", 4, 3).is_err());
+        assert!(split(b"", 2, 3).is_err());
+    }
+}
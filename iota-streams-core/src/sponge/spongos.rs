@@ -39,6 +39,13 @@ fn decrypt_xor(s: &mut [u8], y: &[u8], x: &mut [u8]) {
     }
 }
 
+fn absorb_xor_copy(s: &mut [u8], x: &[u8], y: &mut [u8]) {
+    for (si, (xi, yi)) in s.iter_mut().zip(x.iter().zip(y.iter_mut())) {
+        *yi = *xi;
+        *si ^= *xi;
+    }
+}
+
 fn encrypt_xor_mut(s: &mut [u8], x: &mut [u8]) {
     for (si, xi) in s.iter_mut().zip(x.iter_mut()) {
         *xi ^= *si;
@@ -131,6 +138,22 @@ impl<F: PRP> Spongos<F> {
         }
     }
 
+    /// Absorb a slice and copy it into `yr` in the same pass, eg. directly into the
+    /// output buffer of a message.
+    pub fn absorb_in_place(&mut self, xr: impl AsRef<[u8]>, mut yr: impl AsMut<[u8]>) {
+        let mut x = xr.as_ref();
+        let mut y = yr.as_mut();
+        assert_eq!(x.len(), y.len());
+        while !x.is_empty() {
+            let s = self.outer_min_mut(x.len());
+            let n = s.len();
+            absorb_xor_copy(s, &x[..n], &mut y[..n]);
+            x = &x[n..];
+            y = &mut y[n..];
+            self.update(n);
+        }
+    }
+
     /// Squeeze a trit slice from Spongos object.
     pub fn squeeze(&mut self, mut yr: impl AsMut<[u8]>) {
         let mut y = yr.as_mut();
@@ -187,8 +210,8 @@ impl<F: PRP> Spongos<F> {
         }
     }
 
-    /// Encrypt in-place a trit slice with Spongos object.
-    pub fn encrypt_mut(&mut self, mut xyr: impl AsMut<[u8]>) {
+    /// Encrypt in-place a byte slice with Spongos object.
+    pub fn encrypt_in_place(&mut self, mut xyr: impl AsMut<[u8]>) {
         let mut xy = xyr.as_mut();
         while !xy.is_empty() {
             let s = self.outer_min_mut(xy.len());
@@ -199,6 +222,11 @@ impl<F: PRP> Spongos<F> {
         }
     }
 
+    /// Same as `encrypt_in_place`.
+    pub fn encrypt_mut(&mut self, xyr: impl AsMut<[u8]>) {
+        self.encrypt_in_place(xyr)
+    }
+
     /// Encrypt buf.
     pub fn encrypt_arr<N: ArrayLength<u8>>(&mut self, x: &GenericArray<u8, N>) -> GenericArray<u8, N> {
         let mut y = GenericArray::default();
@@ -228,8 +256,20 @@ impl<F: PRP> Spongos<F> {
         }
     }
 
+    /// Update state with ciphertext as `decrypt` does, without recovering the plaintext.
+    pub fn decrypt_skip(&mut self, yr: impl AsRef<[u8]>) {
+        let mut y = yr.as_ref();
+        while !y.is_empty() {
+            let s = self.outer_min_mut(y.len());
+            let n = s.len();
+            copy(&y[..n], s);
+            y = &y[n..];
+            self.update(n);
+        }
+    }
+
     /// Decrypt in-place a byte slice with Spongos object.
-    pub fn decrypt_mut(&mut self, mut xyr: impl AsMut<[u8]>) {
+    pub fn decrypt_in_place(&mut self, mut xyr: impl AsMut<[u8]>) {
         let mut xy = xyr.as_mut();
         while !xy.is_empty() {
             let s = self.outer_min_mut(xy.len());
@@ -240,6 +280,11 @@ impl<F: PRP> Spongos<F> {
         }
     }
 
+    /// Same as `decrypt_in_place`.
+    pub fn decrypt_mut(&mut self, xyr: impl AsMut<[u8]>) {
+        self.decrypt_in_place(xyr)
+    }
+
     /// Decrypt buf.
     pub fn decrypt_arr<N: ArrayLength<u8>>(&mut self, y: impl AsRef<[u8]>) -> GenericArray<u8, N> {
         let mut x = GenericArray::default();
@@ -168,5 +168,24 @@ pub fn encrypt_decrypt_n<F: PRP>(n: usize)
         assert_eq!(x, x2);
         s4.commit();
         assert_eq!(tag, s4.squeeze_n(rate));
+
+        let mut s5 = s4.clone();
+        let mut s6 = s4.clone();
+        let mut x3 = x.clone();
+        s5.encrypt_in_place(&mut x3);
+        s6.decrypt_in_place(&mut x3);
+        assert_eq!(x, x3);
+        s5.commit();
+        s6.commit();
+        assert_eq!(s5.squeeze_n(rate), s6.squeeze_n(rate));
+
+        let mut s7 = s4.clone();
+        let mut y = vec![0_u8; n];
+        s4.absorb(&x);
+        s7.absorb_in_place(&x, &mut y);
+        assert_eq!(x, y);
+        s4.commit();
+        s7.commit();
+        assert_eq!(s4.squeeze_n(rate), s7.squeeze_n(rate));
     }
 }
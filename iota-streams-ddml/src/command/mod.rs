@@ -25,6 +25,46 @@ use anyhow::Result;
 
 use iota_streams_core::println;
 
+/// Default context of Ed25519ph signatures of messages, see `set_signature_context` of wrap and
+/// unwrap contexts.
+pub const SIGNATURE_CONTEXT: &[u8] = b"IOTAStreams";
+
+/// Maximum size of Ed25519ph signature contexts.
+pub const MAX_SIGNATURE_CONTEXT_SIZE: usize = 255;
+
+/// Signature context stored inline in wrap and unwrap contexts, setting it does not allocate.
+#[derive(Copy, Clone)]
+pub(crate) struct SignatureContext {
+    bytes: [u8; MAX_SIGNATURE_CONTEXT_SIZE],
+    len: usize,
+}
+
+impl SignatureContext {
+    pub(crate) fn new(context: &[u8]) -> Self {
+        assert!(
+            context.len() <= MAX_SIGNATURE_CONTEXT_SIZE,
+            "Signature context too long: {}.",
+            context.len()
+        );
+        let mut bytes = [0_u8; MAX_SIGNATURE_CONTEXT_SIZE];
+        bytes[..context.len()].copy_from_slice(context);
+        Self {
+            bytes,
+            len: context.len(),
+        }
+    }
+
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl Default for SignatureContext {
+    fn default() -> Self {
+        Self::new(SIGNATURE_CONTEXT)
+    }
+}
+
 /// Absorb command. Trinary representation of the field is absorbed into Spongos state.
 /// External fields are not encoded in the trinary stream. Non-trinary field is
 /// an input argument in Wrap command and an output argument in Unwrap command.
@@ -85,6 +125,17 @@ pub trait Join<L, S> {
     fn join(&mut self, store: S, link: L) -> Result<&mut Self>;
 }
 
+/// Section modifier. Fields processed by `cont` are encoded as a length-delimited section.
+/// The encoded section is absorbed as `bytes` and the fields themselves are processed with
+/// a fork of the Spongos state taken before the section size is absorbed.
+///
+/// A reader processes the leading fields it knows of and ignores the unrecognized trailing
+/// ones. New fields can thus be appended to a section in later versions of a message
+/// without breaking older readers.
+pub trait Section<F> {
+    fn section(&mut self, cont: F) -> Result<&mut Self>;
+}
+
 /// Repeated modifier.
 pub trait Repeated<I, F> {
     /// `values_iter` provides some iterated values or counter.
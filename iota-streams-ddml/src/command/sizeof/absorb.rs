@@ -8,6 +8,7 @@ use crate::{
         AbsorbFallback,
         ArrayLength,
         Bytes,
+        BytesBuf,
         Fallback,
         NBytes,
         Size,
@@ -26,7 +27,7 @@ use iota_streams_core_edsig::{
 /// All Uint8 values are encoded with 1 byte.
 impl<F> Absorb<&Uint8> for Context<F> {
     fn absorb(&mut self, _u: &Uint8) -> Result<&mut Self> {
-        self.size += 1;
+        self.record("absorb", "u8", 1);
         Ok(self)
     }
 }
@@ -41,7 +42,7 @@ impl<F> Absorb<Uint8> for Context<F> {
 /// All Uint16 values are encoded with 2 bytes.
 impl<F> Absorb<&Uint16> for Context<F> {
     fn absorb(&mut self, _u: &Uint16) -> Result<&mut Self> {
-        self.size += 2;
+        self.record("absorb", "u16", 2);
         Ok(self)
     }
 }
@@ -56,7 +57,7 @@ impl<F> Absorb<Uint16> for Context<F> {
 /// All Uint32 values are encoded with 4 bytes.
 impl<F> Absorb<&Uint32> for Context<F> {
     fn absorb(&mut self, _u: &Uint32) -> Result<&mut Self> {
-        self.size += 4;
+        self.record("absorb", "u32", 4);
         Ok(self)
     }
 }
@@ -71,7 +72,7 @@ impl<F> Absorb<Uint32> for Context<F> {
 /// All Uint64 values are encoded with 8 bytes.
 impl<F> Absorb<&Uint64> for Context<F> {
     fn absorb(&mut self, _u: &Uint64) -> Result<&mut Self> {
-        self.size += 8;
+        self.record("absorb", "u64", 8);
         Ok(self)
     }
 }
@@ -86,7 +87,7 @@ impl<F> Absorb<Uint64> for Context<F> {
 /// Size has var-size encoding.
 impl<F> Absorb<&Size> for Context<F> {
     fn absorb(&mut self, size: &Size) -> Result<&mut Self> {
-        self.size += sizeof_sizet(size.0);
+        self.record("absorb", "size_t", sizeof_sizet(size.0));
         Ok(self)
     }
 }
@@ -102,7 +103,7 @@ impl<F> Absorb<Size> for Context<F> {
 impl<'a, F> Absorb<&'a Bytes> for Context<F> {
     fn absorb(&mut self, bytes: &'a Bytes) -> Result<&mut Self> {
         let s = sizeof_sizet((bytes.0).len());
-        self.size += s + (bytes.0).len();
+        self.record("absorb", "bytes", s + (bytes.0).len());
         Ok(self)
     }
 }
@@ -114,10 +115,18 @@ impl<F> Absorb<Bytes> for Context<F> {
     }
 }
 
+/// `bytes` has variable size thus the size is encoded before the content bytes.
+impl<'a, 'b, F> Absorb<&'a BytesBuf<'b>> for Context<F> {
+    fn absorb(&mut self, bytes: &'a BytesBuf<'b>) -> Result<&mut Self> {
+        self.record("absorb", "bytes", sizeof_sizet(bytes.len()) + bytes.len());
+        Ok(self)
+    }
+}
+
 /// `byte [n]` is fixed-size and is encoded with `n` bytes.
 impl<'a, F, N: ArrayLength<u8>> Absorb<&'a NBytes<N>> for Context<F> {
     fn absorb(&mut self, _nbytes: &'a NBytes<N>) -> Result<&mut Self> {
-        self.size += N::USIZE;
+        self.record("absorb", "nbytes", N::USIZE);
         Ok(self)
     }
 }
@@ -132,7 +141,7 @@ impl<F, N: ArrayLength<u8>> Absorb<NBytes<N>> for Context<F> {
 /// MSS public key has fixed size.
 impl<'a, F> Absorb<&'a ed25519::PublicKey> for Context<F> {
     fn absorb(&mut self, _pk: &'a ed25519::PublicKey) -> Result<&mut Self> {
-        self.size += ed25519::PUBLIC_KEY_LENGTH;
+        self.record("absorb", "ed25519_pk", ed25519::PUBLIC_KEY_LENGTH);
         Ok(self)
     }
 }
@@ -140,7 +149,7 @@ impl<'a, F> Absorb<&'a ed25519::PublicKey> for Context<F> {
 /// NTRU public key has fixed size.
 impl<'a, F> Absorb<&'a x25519::PublicKey> for Context<F> {
     fn absorb(&mut self, _pk: &'a x25519::PublicKey) -> Result<&mut Self> {
-        self.size += x25519::PUBLIC_KEY_LENGTH;
+        self.record("absorb", "x25519_pk", x25519::PUBLIC_KEY_LENGTH);
         Ok(self)
     }
 }
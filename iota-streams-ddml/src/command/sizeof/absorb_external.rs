@@ -19,21 +19,25 @@ use crate::{
 // External values are not encoded in the stream.
 impl<F> Absorb<External<Uint8>> for Context<F> {
     fn absorb(&mut self, _external: External<Uint8>) -> Result<&mut Self> {
+        self.record("absorb", "external_u8", 0);
         Ok(self)
     }
 }
 impl<F> Absorb<External<Uint16>> for Context<F> {
     fn absorb(&mut self, _external: External<Uint16>) -> Result<&mut Self> {
+        self.record("absorb", "external_u16", 0);
         Ok(self)
     }
 }
 impl<F> Absorb<External<Uint32>> for Context<F> {
     fn absorb(&mut self, _external: External<Uint32>) -> Result<&mut Self> {
+        self.record("absorb", "external_u32", 0);
         Ok(self)
     }
 }
 impl<F> Absorb<External<Uint64>> for Context<F> {
     fn absorb(&mut self, _external: External<Uint64>) -> Result<&mut Self> {
+        self.record("absorb", "external_u64", 0);
         Ok(self)
     }
 }
@@ -41,6 +45,7 @@ impl<F> Absorb<External<Uint64>> for Context<F> {
 /// External values are not encoded in the trinary stream.
 impl<'a, F, N: ArrayLength<u8>> Absorb<External<&'a NBytes<N>>> for Context<F> {
     fn absorb(&mut self, _external: External<&'a NBytes<N>>) -> Result<&mut Self> {
+        self.record("absorb", "external_nbytes", 0);
         Ok(self)
     }
 }
@@ -6,6 +6,7 @@ use crate::command::Commit;
 /// Commit costs nothing in the trinary stream.
 impl<F> Commit for Context<F> {
     fn commit(&mut self) -> Result<&mut Self> {
+        self.record("commit", "none", 0);
         Ok(self)
     }
 }
@@ -7,6 +7,7 @@ use crate::{
         sizeof_sizet,
         ArrayLength,
         Bytes,
+        BytesBuf,
         NBytes,
         Size,
         Uint16,
@@ -23,7 +24,7 @@ use iota_streams_core_edsig::{
 /// Mask Uint8.
 impl<F> Mask<&Uint8> for Context<F> {
     fn mask(&mut self, _val: &Uint8) -> Result<&mut Self> {
-        self.size += 1;
+        self.record("mask", "u8", 1);
         Ok(self)
     }
 }
@@ -38,7 +39,7 @@ impl<F> Mask<Uint8> for Context<F> {
 /// Mask Uint16.
 impl<F> Mask<&Uint16> for Context<F> {
     fn mask(&mut self, _val: &Uint16) -> Result<&mut Self> {
-        self.size += 2;
+        self.record("mask", "u16", 2);
         Ok(self)
     }
 }
@@ -53,7 +54,7 @@ impl<F> Mask<Uint16> for Context<F> {
 /// Mask Uint32.
 impl<F> Mask<&Uint32> for Context<F> {
     fn mask(&mut self, _val: &Uint32) -> Result<&mut Self> {
-        self.size += 4;
+        self.record("mask", "u32", 4);
         Ok(self)
     }
 }
@@ -68,7 +69,7 @@ impl<F> Mask<Uint32> for Context<F> {
 /// Mask Uint64.
 impl<F> Mask<&Uint64> for Context<F> {
     fn mask(&mut self, _val: &Uint64) -> Result<&mut Self> {
-        self.size += 8;
+        self.record("mask", "u64", 8);
         Ok(self)
     }
 }
@@ -83,7 +84,7 @@ impl<F> Mask<Uint64> for Context<F> {
 /// Mask Size.
 impl<F> Mask<&Size> for Context<F> {
     fn mask(&mut self, val: &Size) -> Result<&mut Self> {
-        self.size += sizeof_sizet(val.0);
+        self.record("mask", "size_t", sizeof_sizet(val.0));
         Ok(self)
     }
 }
@@ -98,7 +99,7 @@ impl<F> Mask<Size> for Context<F> {
 /// Mask `n` bytes.
 impl<F, N: ArrayLength<u8>> Mask<&NBytes<N>> for Context<F> {
     fn mask(&mut self, _val: &NBytes<N>) -> Result<&mut Self> {
-        self.size += N::USIZE;
+        self.record("mask", "nbytes", N::USIZE);
         Ok(self)
     }
 }
@@ -106,23 +107,30 @@ impl<F, N: ArrayLength<u8>> Mask<&NBytes<N>> for Context<F> {
 /// Mask bytes, the size prefixed before the content bytes is also masked.
 impl<F> Mask<&Bytes> for Context<F> {
     fn mask(&mut self, bytes: &Bytes) -> Result<&mut Self> {
-        let size = Size((bytes.0).len());
-        self.mask(&size)?;
-        self.size += (bytes.0).len();
+        let n = (bytes.0).len();
+        self.record("mask", "bytes", sizeof_sizet(n) + n);
+        Ok(self)
+    }
+}
+
+/// Mask bytes, the size prefixed before the content bytes is also masked.
+impl<'a, F> Mask<&BytesBuf<'a>> for Context<F> {
+    fn mask(&mut self, bytes: &BytesBuf<'a>) -> Result<&mut Self> {
+        self.record("mask", "bytes", sizeof_sizet(bytes.len()) + bytes.len());
         Ok(self)
     }
 }
 
 impl<F> Mask<&x25519::PublicKey> for Context<F> {
     fn mask(&mut self, _pk: &x25519::PublicKey) -> Result<&mut Self> {
-        self.size += x25519::PUBLIC_KEY_LENGTH;
+        self.record("mask", "x25519_pk", x25519::PUBLIC_KEY_LENGTH);
         Ok(self)
     }
 }
 
 impl<F> Mask<&ed25519::PublicKey> for Context<F> {
     fn mask(&mut self, _pk: &ed25519::PublicKey) -> Result<&mut Self> {
-        self.size += ed25519::PUBLIC_KEY_LENGTH;
+        self.record("mask", "ed25519_pk", ed25519::PUBLIC_KEY_LENGTH);
         Ok(self)
     }
 }
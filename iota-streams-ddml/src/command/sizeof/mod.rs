@@ -1,10 +1,50 @@
 //! Implementation of command traits for calculating the size for output buffer in Wrap operation.
+//!
+//! A context created with `Context::with_schema` also records the encoded fields, giving a
+//! machine-readable schema of the message for dissectors and code generators.
+
+use iota_streams_core::prelude::{
+    String,
+    Vec,
+};
+
+/// Field of a message recorded by a schema context.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Field {
+    /// Name set with `Context::label` before the field, if any
+    pub name: Option<&'static str>,
+    /// DDML command, eg. `absorb`, `mask`, `commit`
+    pub command: &'static str,
+    /// Codec of the value, eg. `u8`, `size_t`, `bytes`, `external_nbytes`
+    pub codec: &'static str,
+    /// Encoded size in bytes, 0 for commands and values not encoded in the stream
+    pub size: usize,
+}
+
+/// JSON array of `fields`: objects with `name` (string or null), `command`, `codec` and `size`.
+pub fn schema_to_json(fields: &[Field]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|f| {
+            let name = f.name.map_or_else(|| String::from("null"), |name| format!("\"{}\"", name));
+            format!(
+                "{{\"name\":{},\"command\":\"{}\",\"codec\":\"{}\",\"size\":{}}}",
+                name, f.command, f.codec, f.size
+            )
+        })
+        .collect();
+    format!("[{}]", fields.join(","))
+}
 
 /// Message size counting context.
 #[derive(Debug)]
 pub struct Context<F> {
     /// The current message size in trits.
     size: usize,
+    /// Recorded fields, if any.
+    schema: Option<Vec<Field>>,
+    /// Name of the next recorded field.
+    label: Option<&'static str>,
     _phantom: core::marker::PhantomData<F>,
 }
 
@@ -13,13 +53,49 @@ impl<F> Context<F> {
     pub fn new() -> Self {
         Self {
             size: 0,
+            schema: None,
+            label: None,
             _phantom: core::marker::PhantomData,
         }
     }
+    /// Creates a new Context<F> recording the fields of the message.
+    pub fn with_schema() -> Self {
+        Self {
+            schema: Some(Vec::new()),
+            ..Self::new()
+        }
+    }
     /// Returns calculated message size.
     pub fn get_size(&self) -> usize {
         self.size
     }
+    /// Name the next field, no-op unless the fields are recorded.
+    pub fn label(&mut self, name: &'static str) -> anyhow::Result<&mut Self> {
+        if self.schema.is_some() {
+            self.label = Some(name);
+        }
+        Ok(self)
+    }
+    /// Returns recorded fields, empty unless created with `with_schema`.
+    pub fn schema(&self) -> &[Field] {
+        self.schema.as_deref().unwrap_or(&[])
+    }
+    /// Take recorded fields.
+    pub fn into_schema(self) -> Vec<Field> {
+        self.schema.unwrap_or_default()
+    }
+
+    fn record(&mut self, command: &'static str, codec: &'static str, size: usize) {
+        self.size += size;
+        if let Some(schema) = self.schema.as_mut() {
+            schema.push(Field {
+                name: self.label.take(),
+                command,
+                codec,
+                size,
+            });
+        }
+    }
 }
 
 mod absorb;
@@ -30,6 +106,7 @@ mod fork;
 mod join;
 mod mask;
 mod repeated;
+mod section;
 mod skip;
 mod squeeze;
 mod squeeze_external;
@@ -45,6 +122,7 @@ pub use fork::*;
 pub use join::*;
 pub use mask::*;
 pub use repeated::*;
+pub use section::*;
 pub use skip::*;
 pub use squeeze::*;
 pub use squeeze_external::*;
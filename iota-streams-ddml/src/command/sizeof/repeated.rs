@@ -1,8 +1,14 @@
-use anyhow::Result;
+use anyhow::{
+    ensure,
+    Result,
+};
 use core::iter;
 
 use super::Context;
-use crate::command::Repeated;
+use crate::{
+    command::Repeated,
+    types::Bounded,
+};
 
 /// Repeated modifier. The actual number of repetitions must be wrapped
 /// (absorbed/masked/skipped) explicitly.
@@ -20,3 +26,21 @@ where
         })
     }
 }
+
+/// Bounded repeated modifier. The number of values must not exceed the bound.
+impl<F, I, C> Repeated<Bounded<I>, C> for Context<F>
+where
+    I: iter::ExactSizeIterator,
+    C: for<'a> FnMut(&'a mut Self, <I as iter::Iterator>::Item) -> Result<&'a mut Self>,
+{
+    fn repeated(&mut self, values_iter: Bounded<I>, value_handle: C) -> Result<&mut Self> {
+        let Bounded(values_iter, max) = values_iter;
+        ensure!(
+            values_iter.len() <= max,
+            "Too many repeated values: {} > {}.",
+            values_iter.len(),
+            max
+        );
+        self.repeated(values_iter, value_handle)
+    }
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+
+use super::Context;
+use iota_streams_core::prelude::Vec;
+use crate::{
+    command::Section,
+    types::sizeof_sizet,
+};
+
+/// Section size is encoded as `size_t` followed by the section fields.
+impl<F, C> Section<C> for Context<F>
+where
+    C: for<'a> FnMut(&'a mut Self) -> Result<&'a mut Self>,
+{
+    fn section(&mut self, mut cont: C) -> Result<&mut Self> {
+        let mut section = Self::new();
+        section.schema = self.schema.as_ref().map(|_| Vec::new());
+        cont(&mut section)?;
+        let n = section.get_size();
+        self.record("section", "size_t", sizeof_sizet(n));
+        self.size += n;
+        if let (Some(schema), Some(fields)) = (self.schema.as_mut(), section.schema) {
+            schema.extend(fields);
+        }
+        Ok(self)
+    }
+}
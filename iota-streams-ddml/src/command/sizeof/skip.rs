@@ -7,6 +7,7 @@ use crate::{
         sizeof_sizet,
         ArrayLength,
         Bytes,
+        BytesBuf,
         Fallback,
         NBytes,
         Size,
@@ -22,7 +23,7 @@ use crate::{
 /// All Uint8 values are encoded with 3 trits.
 impl<F> Skip<&Uint8> for Context<F> {
     fn skip(&mut self, _u: &Uint8) -> Result<&mut Self> {
-        self.size += 1;
+        self.record("skip", "u8", 1);
         Ok(self)
     }
 }
@@ -38,7 +39,7 @@ impl<F> Skip<Uint8> for Context<F> {
 /// All Uint16 values are encoded with 3 trits.
 impl<F> Skip<&Uint16> for Context<F> {
     fn skip(&mut self, _u: &Uint16) -> Result<&mut Self> {
-        self.size += 2;
+        self.record("skip", "u16", 2);
         Ok(self)
     }
 }
@@ -54,7 +55,7 @@ impl<F> Skip<Uint16> for Context<F> {
 /// All Uint32 values are encoded with 3 trits.
 impl<F> Skip<&Uint32> for Context<F> {
     fn skip(&mut self, _u: &Uint32) -> Result<&mut Self> {
-        self.size += 4;
+        self.record("skip", "u32", 4);
         Ok(self)
     }
 }
@@ -70,7 +71,7 @@ impl<F> Skip<Uint32> for Context<F> {
 /// All Uint64 values are encoded with 3 trits.
 impl<F> Skip<&Uint64> for Context<F> {
     fn skip(&mut self, _u: &Uint64) -> Result<&mut Self> {
-        self.size += 8;
+        self.record("skip", "u64", 8);
         Ok(self)
     }
 }
@@ -85,7 +86,7 @@ impl<F> Skip<Uint64> for Context<F> {
 /// Size has var-size encoding.
 impl<F> Skip<&Size> for Context<F> {
     fn skip(&mut self, size: &Size) -> Result<&mut Self> {
-        self.size += sizeof_sizet(size.0);
+        self.record("skip", "size_t", sizeof_sizet(size.0));
         Ok(self)
     }
 }
@@ -100,7 +101,7 @@ impl<F> Skip<Size> for Context<F> {
 /// `trytes` is encoded with `sizeof_sizet(n) + 3 * n` trits.
 impl<'a, F> Skip<&'a Bytes> for Context<F> {
     fn skip(&mut self, trytes: &'a Bytes) -> Result<&mut Self> {
-        self.size += sizeof_sizet((trytes.0).len()) + (trytes.0).len();
+        self.record("skip", "bytes", sizeof_sizet((trytes.0).len()) + (trytes.0).len());
         Ok(self)
     }
 }
@@ -112,10 +113,18 @@ impl<F> Skip<Bytes> for Context<F> {
     }
 }
 
+/// `bytes` has variable size thus the size is encoded before the content bytes.
+impl<'a, 'b, F> Skip<&'a BytesBuf<'b>> for Context<F> {
+    fn skip(&mut self, bytes: &'a BytesBuf<'b>) -> Result<&mut Self> {
+        self.record("skip", "bytes", sizeof_sizet(bytes.len()) + bytes.len());
+        Ok(self)
+    }
+}
+
 /// `tryte [n]` is encoded with `3 * n` trits.
 impl<'a, F, N: ArrayLength<u8>> Skip<&'a NBytes<N>> for Context<F> {
     fn skip(&mut self, _nbytes: &'a NBytes<N>) -> Result<&mut Self> {
-        self.size += N::USIZE;
+        self.record("skip", "nbytes", N::USIZE);
         Ok(self)
     }
 }
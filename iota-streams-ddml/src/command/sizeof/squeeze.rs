@@ -9,7 +9,7 @@ use crate::{
 /// Mac is just like NBytes.
 impl<F> Squeeze<&Mac> for Context<F> {
     fn squeeze(&mut self, mac: &Mac) -> Result<&mut Self> {
-        self.size += mac.0;
+        self.record("squeeze", "mac", mac.0);
         Ok(self)
     }
 }
@@ -14,6 +14,7 @@ use crate::{
 /// External values are not encoded.
 impl<'a, F, N: ArrayLength<u8>> Squeeze<&'a External<NBytes<N>>> for Context<F> {
     fn squeeze(&mut self, _external_nbytes: &'a External<NBytes<N>>) -> Result<&mut Self> {
+        self.record("squeeze", "external_nbytes", 0);
         Ok(self)
     }
 }
@@ -21,6 +22,7 @@ impl<'a, F, N: ArrayLength<u8>> Squeeze<&'a External<NBytes<N>>> for Context<F>
 /// External values are not encoded.
 impl<F> Squeeze<&External<Mac>> for Context<F> {
     fn squeeze(&mut self, _mac: &External<Mac>) -> Result<&mut Self> {
+        self.record("squeeze", "external_mac", 0);
         Ok(self)
     }
 }
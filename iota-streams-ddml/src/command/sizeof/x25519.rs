@@ -2,9 +2,13 @@ use anyhow::Result;
 
 use super::Context;
 use crate::{
-    command::X25519,
+    command::{
+        Mask,
+        X25519,
+    },
     types::{
         ArrayLength,
+        Bytes,
         NBytes,
     },
 };
@@ -13,7 +17,7 @@ use iota_streams_core_edsig::key_exchange::x25519;
 impl<'a, F> X25519<&'a x25519::StaticSecret, &'a x25519::PublicKey> for Context<F> {
     fn x25519(&mut self, _sk: &x25519::StaticSecret, _pk: &x25519::PublicKey) -> Result<&mut Self> {
         // Only shared secret is absorbed externally.
-        self.size += 0;
+        self.record("x25519", "x25519_shared", 0);
         Ok(self)
     }
 }
@@ -21,14 +25,22 @@ impl<'a, F> X25519<&'a x25519::StaticSecret, &'a x25519::PublicKey> for Context<
 impl<'a, F> X25519<&'a x25519::EphemeralSecret, &'a x25519::PublicKey> for Context<F> {
     fn x25519(&mut self, _sk: &x25519::EphemeralSecret, _pk: &x25519::PublicKey) -> Result<&mut Self> {
         // Shared secret is absorbed externally.
-        self.size += 0;
+        self.record("x25519", "x25519_shared", 0);
         Ok(self)
     }
 }
 
 impl<'a, F, N: ArrayLength<u8>> X25519<&'a x25519::PublicKey, &'a NBytes<N>> for Context<F> {
     fn x25519(&mut self, _pk: &x25519::PublicKey, _key: &NBytes<N>) -> Result<&mut Self> {
-        self.size += 32 + N::USIZE;
+        self.record("x25519", "x25519_ke", 32 + N::USIZE);
         Ok(self)
     }
 }
+
+impl<'a, F> X25519<&'a x25519::PublicKey, &'a Bytes> for Context<F> {
+    fn x25519(&mut self, _pk: &x25519::PublicKey, bytes: &Bytes) -> Result<&mut Self> {
+        // Ephemeral public key followed by masked bytes.
+        self.record("x25519", "x25519_pk", 32);
+        self.mask(bytes)
+    }
+}
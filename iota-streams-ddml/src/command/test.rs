@@ -277,6 +277,91 @@ fn test_ed25519() {
     assert!(dbg!(absorb_ed25519::<KeccakF1600>()).is_ok());
 }
 
+fn ed25519_batch<F: PRP>() -> Result<()> {
+    let secret = ed25519::SecretKey::from_bytes(&[7; ed25519::SECRET_KEY_LENGTH]).unwrap();
+    let public = ed25519::PublicKey::from(&secret);
+    let kp = ed25519::Keypair { secret, public };
+
+    let ta = Bytes([3_u8; 17].to_vec());
+    let mut uta = Bytes(Vec::new());
+
+    let buf_size = {
+        let mut ctx = sizeof::Context::<F>::new();
+        ctx.absorb(&ta)?.ed25519(&kp, HashSig)?.ed25519(&kp, HashSig)?;
+        ctx.get_size()
+    };
+
+    let mut buf = vec![0_u8; buf_size];
+
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.absorb(&ta)?.ed25519(&kp, HashSig)?.ed25519(&kp, HashSig)?;
+        ensure!(ctx.stream.is_empty(), "Output stream is not exhausted.");
+    }
+
+    let mut sigs = {
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        ctx.collect_signatures();
+        ctx.absorb(&mut uta)?
+            .ed25519(&public, HashSig)?
+            .ed25519(&public, HashSig)?;
+        ensure!(ctx.stream.is_empty(), "Input stream is not exhausted.");
+        ctx.take_signatures()
+    };
+
+    ensure!(sigs.len() == 2, "Expected 2 collected signatures, found {}", sigs.len());
+    ensure!(unwrap::verify_signatures(&sigs).is_ok(), "Batch verification failed.");
+
+    sigs[1].hash[0] ^= 1;
+    ensure!(unwrap::verify_signatures(&sigs).is_err(), "Batch with a bad signature verified.");
+    ensure!(unwrap::verify_signatures(&sigs[..1]).is_ok(), "Signature verification failed.");
+    ensure!(unwrap::verify_signatures(&sigs[1..]).is_err(), "Bad signature verified.");
+
+    Ok(())
+}
+
+#[test]
+fn test_ed25519_batch() {
+    assert!(dbg!(ed25519_batch::<KeccakF1600>()).is_ok());
+}
+
+fn ed25519_context<F: PRP>() -> Result<()> {
+    let secret = ed25519::SecretKey::from_bytes(&[7; ed25519::SECRET_KEY_LENGTH]).unwrap();
+    let public = ed25519::PublicKey::from(&secret);
+    let kp = ed25519::Keypair { secret, public };
+
+    let buf_size = {
+        let mut ctx = sizeof::Context::<F>::new();
+        ctx.ed25519(&kp, HashSig)?;
+        ctx.get_size()
+    };
+
+    let mut buf = vec![0_u8; buf_size];
+
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.set_signature_context(b"IOTAStreams\x03\x05");
+        ctx.ed25519(&kp, HashSig)?;
+    }
+
+    let unwrap_with = |context: &[u8]| -> Result<()> {
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        ctx.set_signature_context(context);
+        ctx.ed25519(&public, HashSig)?;
+        Ok(())
+    };
+    ensure!(unwrap_with(b"IOTAStreams\x03\x05").is_ok(), "Signature with context not verified.");
+    ensure!(unwrap_with(b"IOTAStreams\x03\x06").is_err(), "Signature verified with another context.");
+    ensure!(unwrap_with(SIGNATURE_CONTEXT).is_err(), "Signature verified with the default context.");
+
+    Ok(())
+}
+
+#[test]
+fn test_ed25519_context() {
+    assert!(dbg!(ed25519_context::<KeccakF1600>()).is_ok());
+}
+
 fn x25519_static<F: PRP>() -> Result<()> {
     let secret_a = x25519::StaticSecret::from([11; 32]);
     let secret_b = x25519::StaticSecret::from([13; 32]);
@@ -397,11 +482,343 @@ fn x25519_transport<F: PRP>() -> Result<()> {
     Ok(())
 }
 
+fn x25519_exchange<F: PRP>() -> Result<()> {
+    let secret_a = x25519::StaticSecret::new(&mut rand::thread_rng());
+    let public_a = x25519::PublicKey::from(&secret_a);
+
+    let key = NBytes::<U32>::default();
+
+    // Precomputed exchanges unwrap as transported keys.
+    let exchange = x25519::Exchange::ephemeral([7; 32], &public_a);
+    let mut buf = vec![0_u8; 64];
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.x25519(&exchange, &key)?;
+        ensure!(ctx.stream.is_empty(), "Output stream is not exhausted.");
+    }
+
+    let mut ukey = NBytes::<U32>::default();
+    let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+    ctx.x25519(&secret_a, &mut ukey)?;
+    ensure!(ctx.stream.is_empty(), "Input stream is not exhausted.");
+    ensure!(key == ukey, "Invalid unwrapped key value: {:?} != {:?}", key, ukey);
+
+    Ok(())
+}
+
+fn x25519_private_field<F: PRP>() -> Result<()> {
+    let secret_a = x25519::StaticSecret::new(&mut rand::thread_rng());
+    let public_a = x25519::PublicKey::from(&secret_a);
+
+    let shared = Bytes(b"SHAREDFIELD".to_vec());
+    let private = Bytes(b"PRIVATEFIELD".to_vec());
+    let mut tag_wrap = External(NBytes::<U32>::default());
+
+    let buf_size = {
+        let mut ctx = sizeof::Context::<F>::new();
+        ctx.mask(&shared)?
+            .section(|ctx| ctx.x25519(&public_a, &private))?
+            .commit()?
+            .squeeze(&tag_wrap)?;
+        ctx.get_size()
+    };
+
+    let mut buf = vec![0_u8; buf_size];
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.mask(&shared)?
+            .section(|ctx| ctx.x25519(&public_a, &private))?
+            .commit()?
+            .squeeze(&mut tag_wrap)?;
+        ensure!(ctx.stream.is_empty(), "Output stream is not exhausted.");
+    }
+
+    // Recipient decrypts the private field.
+    {
+        let mut ushared = Bytes::default();
+        let mut uprivate = Bytes::default();
+        let mut tag_unwrap = External(NBytes::<U32>::default());
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        ctx.mask(&mut ushared)?
+            .section(|ctx| ctx.x25519(&secret_a, &mut uprivate))?
+            .commit()?
+            .squeeze(&mut tag_unwrap)?;
+        ensure!(ctx.stream.is_empty(), "Input stream is not exhausted.");
+        ensure!(shared == ushared, "Invalid unwrapped shared field");
+        ensure!(private == uprivate, "Invalid unwrapped private field");
+        ensure!(tag_wrap == tag_unwrap, "Invalid squeezed tags");
+    }
+
+    // Other readers skip the private field.
+    {
+        let mut ushared = Bytes::default();
+        let mut tag_unwrap = External(NBytes::<U32>::default());
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        ctx.mask(&mut ushared)?
+            .section(|ctx| Ok(ctx))?
+            .commit()?
+            .squeeze(&mut tag_unwrap)?;
+        ensure!(ctx.stream.is_empty(), "Input stream is not exhausted.");
+        ensure!(shared == ushared, "Invalid unwrapped shared field");
+        ensure!(tag_wrap == tag_unwrap, "Invalid squeezed tags");
+    }
+
+    Ok(())
+}
+
+/// Layout of `PrivatePacket`: the recipient's key is absorbed in the section before the
+/// encrypted field, readers other than the recipient stop after the key.
+fn x25519_private_section<F: PRP>() -> Result<()> {
+    let secret = ed25519::SecretKey::from_bytes(&[9; ed25519::SECRET_KEY_LENGTH]).unwrap();
+    let public = ed25519::PublicKey::from(&secret);
+    let kp = ed25519::Keypair { secret, public };
+    let (ke_sk, ke_pk) = x25519::keypair_from_ed25519(&kp);
+
+    let masked = Bytes(b"MASKEDFIELD".to_vec());
+    let private = Bytes(b"PRIVATEFIELD".to_vec());
+    let mut tag_wrap = External(NBytes::<U32>::default());
+
+    let buf_size = {
+        let mut ctx = sizeof::Context::<F>::new();
+        ctx.mask(&masked)?
+            .section(|ctx| ctx.absorb(&kp.public)?.x25519(&ke_pk, &private))?
+            .commit()?
+            .squeeze(&tag_wrap)?;
+        ctx.get_size()
+    };
+
+    let mut buf = vec![0_u8; buf_size];
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.mask(&masked)?
+            .section(|ctx| ctx.absorb(&kp.public)?.x25519(&ke_pk, &private))?
+            .commit()?
+            .squeeze(&mut tag_wrap)?;
+        ensure!(ctx.stream.is_empty(), "Output stream is not exhausted.");
+    }
+
+    for recipient in [true, false].iter() {
+        let mut umasked = Bytes::default();
+        let mut upk = ed25519::PublicKey::default();
+        let mut uprivate = Bytes::default();
+        let mut tag_unwrap = External(NBytes::<U32>::default());
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        ctx.mask(&mut umasked)?
+            .section(|ctx| {
+                ctx.absorb(&mut upk)?;
+                if *recipient {
+                    ctx.x25519(&ke_sk, &mut uprivate)?;
+                }
+                Ok(ctx)
+            })?
+            .commit()?
+            .squeeze(&mut tag_unwrap)?;
+        ensure!(ctx.stream.is_empty(), "Input stream is not exhausted.");
+        ensure!(masked == umasked, "Invalid unwrapped masked field");
+        ensure!(kp.public == upk, "Invalid unwrapped recipient key");
+        ensure!(!*recipient || private == uprivate, "Invalid unwrapped private field");
+        ensure!(tag_wrap == tag_unwrap, "Invalid squeezed tags");
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_x25519() {
     assert!(dbg!(x25519_static::<KeccakF1600>()).is_ok());
     assert!(dbg!(x25519_ephemeral::<KeccakF1600>()).is_ok());
     assert!(dbg!(x25519_transport::<KeccakF1600>()).is_ok());
+    assert!(dbg!(x25519_exchange::<KeccakF1600>()).is_ok());
+    assert!(dbg!(x25519_private_field::<KeccakF1600>()).is_ok());
+    assert!(dbg!(x25519_private_section::<KeccakF1600>()).is_ok());
+}
+
+fn repeated_bounded<F: PRP>() -> Result<()> {
+    let values = [Uint8(1), Uint8(2), Uint8(3)];
+    let n = Size(values.len());
+
+    let buf_size = sizeof::Context::<F>::new()
+        .absorb(n)?
+        .repeated(Bounded(values.iter(), 3), |ctx, v| ctx.mask(v))?
+        .get_size();
+    ensure!(
+        sizeof::Context::<F>::new()
+            .repeated(Bounded(values.iter(), 2), |ctx, v| ctx.mask(v))
+            .is_err(),
+        "Bound is not enforced in sizeof."
+    );
+
+    let mut buf = vec![0_u8; buf_size];
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.absorb(n)?
+            .repeated(Bounded(values.iter(), 3), |ctx, v| ctx.mask(v))?;
+        ensure!(ctx.stream.is_empty(), "Output stream is not exhausted.");
+    }
+
+    let mut uvalues = Vec::new();
+    {
+        let mut un = Size(0);
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        ctx.absorb(&mut un)?.repeated(Bounded(un, 3), |ctx| {
+            let mut v = Uint8(0);
+            ctx.mask(&mut v)?;
+            uvalues.push(v);
+            Ok(ctx)
+        })?;
+        ensure!(ctx.stream.is_empty(), "Input stream is not exhausted.");
+    }
+    ensure!(values[..] == uvalues[..], "Invalid unwrapped values: {:?} != {:?}", values, uvalues);
+
+    {
+        let mut un = Size(0);
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        ensure!(
+            ctx.absorb(&mut un)?.repeated(Bounded(un, 2), |ctx| Ok(ctx)).is_err(),
+            "Bound is not enforced in unwrap."
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_repeated_bounded() {
+    assert!(dbg!(repeated_bounded::<KeccakF1600>()).is_ok());
+}
+
+fn section_skip_unknown<F: PRP>() -> Result<()> {
+    let known = Uint8(1);
+    let unknown = Bytes([2_u8; 17].to_vec());
+    let after = Uint8(3);
+    let mut tag_wrap = External(NBytes::<U32>::default());
+    let mut tag_unwrap = External(NBytes::<U32>::default());
+
+    let buf_size = {
+        let mut ctx = sizeof::Context::<F>::new();
+        ctx.section(|ctx| ctx.mask(&known)?.mask(&unknown))?
+            .mask(&after)?
+            .commit()?
+            .squeeze(&tag_wrap)?;
+        ctx.get_size()
+    };
+
+    let mut buf = vec![0_u8; buf_size];
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.section(|ctx| ctx.mask(&known)?.mask(&unknown))?
+            .mask(&after)?
+            .commit()?
+            .squeeze(&mut tag_wrap)?;
+        ensure!(ctx.stream.is_empty(), "Output stream is not exhausted.");
+    }
+
+    // Reader is not aware of the `unknown` field in the section.
+    let mut uknown = Uint8(0);
+    let mut uafter = Uint8(0);
+    {
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..]);
+        ctx.section(|ctx| ctx.mask(&mut uknown))?
+            .mask(&mut uafter)?
+            .commit()?
+            .squeeze(&mut tag_unwrap)?;
+        ensure!(ctx.stream.is_empty(), "Input stream is not exhausted.");
+    }
+
+    ensure!(known == uknown, "Invalid unwrapped section value: {} != {}", known, uknown);
+    ensure!(after == uafter, "Invalid unwrapped value: {} != {}", after, uafter);
+    ensure!(tag_wrap == tag_unwrap, "Invalid squeezed tags");
+    Ok(())
+}
+
+#[test]
+fn test_section() {
+    assert!(dbg!(section_skip_unknown::<KeccakF1600>()).is_ok());
+}
+
+fn bytes_buf_fixed<F: PRP>() -> Result<()> {
+    const MAX_SIZE: usize = 64;
+    let mut payload = [7_u8; 40];
+    let mut public = [8_u8; 3];
+    let payload = BytesBuf::full(&mut payload[..]);
+    let public = BytesBuf::full(&mut public[..]);
+    let mut tag_wrap = External(NBytes::<U32>::default());
+    let mut tag_unwrap = External(NBytes::<U32>::default());
+
+    let buf_size = sizeof::Context::<F>::new()
+        .absorb(&public)?
+        .mask(&payload)?
+        .skip(&public)?
+        .commit()?
+        .squeeze(&tag_wrap)?
+        .get_size();
+    ensure!(buf_size <= MAX_SIZE, "Unexpected buf size: {}.", buf_size);
+
+    let mut buf = [0_u8; MAX_SIZE];
+    {
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..buf_size]);
+        ctx.absorb(&public)?
+            .mask(&payload)?
+            .skip(&public)?
+            .commit()?
+            .squeeze(&mut tag_wrap)?;
+        ensure!(ctx.stream.is_empty(), "Output stream is not exhausted.");
+    }
+
+    let mut payload_buf = [0_u8; MAX_SIZE];
+    let mut public_buf = [0_u8; MAX_SIZE];
+    let mut public_buf2 = [0_u8; MAX_SIZE];
+    let mut upayload = BytesBuf::new(&mut payload_buf[..]);
+    let mut upublic = BytesBuf::new(&mut public_buf[..]);
+    let mut upublic2 = BytesBuf::new(&mut public_buf2[..]);
+    {
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..buf_size]);
+        ctx.absorb(&mut upublic)?
+            .mask(&mut upayload)?
+            .skip(&mut upublic2)?
+            .commit()?
+            .squeeze(&mut tag_unwrap)?;
+        ensure!(ctx.stream.is_empty(), "Input stream is not exhausted.");
+    }
+    ensure!(payload == upayload, "Invalid unmasked bytes: {} != {}", payload, upayload);
+    ensure!(public == upublic, "Invalid unwrapped bytes: {} != {}", public, upublic);
+    ensure!(public == upublic2, "Invalid unwrapped bytes: {} != {}", public, upublic2);
+    ensure!(tag_wrap == tag_unwrap, "Invalid squeezed tags");
+
+    // Fields longer than the buffer are rejected.
+    let mut short_buf = [0_u8; 16];
+    let mut short = BytesBuf::new(&mut short_buf[..]);
+    let mut public_buf3 = [0_u8; MAX_SIZE];
+    let mut upublic3 = BytesBuf::new(&mut public_buf3[..]);
+    let mut ctx = unwrap::Context::<F, &[u8]>::new(&buf[..buf_size]);
+    ensure!(
+        ctx.absorb(&mut upublic3)?.mask(&mut short).is_err(),
+        "Bytes longer than the buffer unwrapped."
+    );
+    Ok(())
+}
+
+#[test]
+fn test_bytes_buf() {
+    assert!(dbg!(bytes_buf_fixed::<KeccakF1600>()).is_ok());
+}
+
+#[cfg(feature = "heapless")]
+fn bounded_ostream<F: PRP>() -> Result<()> {
+    use iota_streams_core::prelude::bounded;
+
+    let n = NBytes::<U32>::default();
+    let mut ctx = wrap::Context::<F, bounded::Vec<u8, 40>>::new(bounded::Vec::new());
+    ctx.absorb(&n)?;
+    ensure!(ctx.absorb(&n).is_err(), "Output buffer capacity exceeded.");
+    ensure!(ctx.stream.len() == 32, "Unexpected buf size: {}.", ctx.stream.len());
+    Ok(())
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn test_bounded_ostream() {
+    assert!(dbg!(bounded_ostream::<KeccakF1600>()).is_ok());
 }
 
 // use crate::io;
@@ -612,3 +1029,45 @@ fn test_x25519() {
 // fn join_link() {
 // assert!(dbg!(run_join_link()).is_ok());
 // }
+
+fn sizeof_schema<F: PRP>() -> Result<()> {
+    let payload = Bytes(vec![1_u8; 3]);
+    let mut ctx = sizeof::Context::<F>::with_schema();
+    ctx.label("flags")?
+        .absorb(Uint8(1))?
+        .label("payload")?
+        .mask(&payload)?
+        .section(|ctx| ctx.label("seq_num")?.skip(Uint64(2)))?
+        .commit()?
+        .squeeze(&Mac(32))?;
+    let size = ctx.get_size();
+    ensure!(size == 48, "Unexpected buf size: {} != 48.", size);
+
+    let fields = ctx.into_schema();
+    let summary: Vec<_> = fields.iter().map(|f| (f.name, f.command, f.codec, f.size)).collect();
+    ensure!(
+        summary
+            == vec![
+                (Some("flags"), "absorb", "u8", 1),
+                (Some("payload"), "mask", "bytes", 5),
+                (None, "section", "size_t", 2),
+                (Some("seq_num"), "skip", "u64", 8),
+                (None, "commit", "none", 0),
+                (None, "squeeze", "mac", 32),
+            ],
+        "Bad schema: {:?}.",
+        summary
+    );
+    ensure!(fields.iter().map(|f| f.size).sum::<usize>() == size, "Schema sizes don't add up.");
+    ensure!(
+        sizeof::schema_to_json(&fields[..1]) == r#"[{"name":"flags","command":"absorb","codec":"u8","size":1}]"#,
+        "Bad schema JSON."
+    );
+    ensure!(sizeof::Context::<F>::new().label("flags")?.absorb(Uint8(1))?.schema().is_empty(), "Schema recorded.");
+    Ok(())
+}
+
+#[test]
+fn test_sizeof_schema() {
+    assert!(dbg!(sizeof_schema::<KeccakF1600>()).is_ok());
+}
@@ -15,6 +15,7 @@ use crate::{
         AbsorbFallback,
         ArrayLength,
         Bytes,
+        BytesBuf,
         Fallback,
         NBytes,
         Size,
@@ -53,8 +54,7 @@ impl<F: PRP, IS: io::IStream> Unwrap for AbsorbContext<F, IS> {
     }
     fn unwrapn(&mut self, bytes: &mut [u8]) -> Result<&mut Self> {
         let slice = self.ctx.stream.try_advance(bytes.len())?;
-        bytes.copy_from_slice(slice);
-        self.ctx.spongos.absorb(bytes);
+        self.ctx.spongos.absorb_in_place(slice, bytes);
         Ok(self)
     }
 }
@@ -141,6 +141,16 @@ impl<'a, F: PRP, IS: io::IStream> Absorb<&'a mut Bytes> for Context<F, IS> {
     }
 }
 
+/// The size is checked against the capacity of the buffer before the content bytes are read.
+impl<'a, 'b, F: PRP, IS: io::IStream> Absorb<&'a mut BytesBuf<'b>> for Context<F, IS> {
+    fn absorb(&mut self, bytes: &'a mut BytesBuf<'b>) -> Result<&mut Self> {
+        let mut size = Size(0);
+        self.absorb(&mut size)?;
+        bytes.set_len(size.0)?;
+        Ok(unwrap_absorb_bytes(self.as_mut(), bytes.as_mut_slice())?.as_mut())
+    }
+}
+
 impl<'a, F: PRP, IS: io::IStream> Absorb<&'a mut ed25519::PublicKey> for Context<F, IS> {
     fn absorb(&mut self, pk: &'a mut ed25519::PublicKey) -> Result<&mut Self> {
         let mut pk_bytes = [0_u8; 32];
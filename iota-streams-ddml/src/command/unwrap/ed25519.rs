@@ -1,5 +1,5 @@
 use anyhow::{
-    bail,
+    ensure,
     Result,
 };
 
@@ -15,27 +15,55 @@ use crate::{
         External,
         HashSig,
         NBytes,
-        Prehashed,
         U64,
     },
 };
 use iota_streams_core::sponge::prp::PRP;
 use iota_streams_core_edsig::signature::ed25519;
 
+/// Signatures are verified with the cofactored equation whether they are verified one by one
+/// or as a batch, so a signature is accepted regardless of the batch it's in.
+fn verify_signature(sig: &ed25519::PrehashedSignature) -> Result<()> {
+    ensure!(ed25519::verify_prehashed_cofactored(sig), "bad signature");
+    Ok(())
+}
+
+/// Verify signatures collected with `Context::collect_signatures`, several signatures are
+/// verified as a batch.
+pub fn verify_signatures(sigs: &[ed25519::PrehashedSignature]) -> Result<()> {
+    match sigs {
+        [] => Ok(()),
+        [sig] => verify_signature(sig),
+        _ => {
+            ensure!(
+                ed25519::verify_prehashed_batch(sigs),
+                "bad signature in batch of {}",
+                sigs.len()
+            );
+            Ok(())
+        }
+    }
+}
+
 /// Recover public key.
 impl<'a, F: PRP, IS: io::IStream> Ed25519<&'a ed25519::PublicKey, &'a External<NBytes<U64>>> for Context<F, IS> {
     fn ed25519(&mut self, pk: &'a ed25519::PublicKey, hash: &'a External<NBytes<U64>>) -> Result<&mut Self> {
-        let context = "IOTAStreams".as_bytes();
-        let mut prehashed = Prehashed::default();
-        prehashed.0.as_mut_slice().copy_from_slice((hash.0).as_slice());
         let mut bytes = [0_u8; ed25519::SIGNATURE_LENGTH];
         let slice = self.stream.try_advance(ed25519::SIGNATURE_LENGTH)?;
         bytes.copy_from_slice(slice);
-        let signature = ed25519::Signature::new(bytes);
-        match pk.verify_prehashed(prehashed, Some(context), &signature) {
-            Ok(()) => Ok(self),
-            Err(err) => bail!("bad signature: {}", err),
+        let mut prehash = [0_u8; 64];
+        prehash.copy_from_slice((hash.0).as_slice());
+        let sig = ed25519::PrehashedSignature {
+            pk: pk.clone(),
+            context: self.sig_context.as_slice().to_vec(),
+            hash: prehash,
+            signature: ed25519::Signature::new(bytes),
+        };
+        match &mut self.signatures {
+            Some(signatures) => signatures.push(sig),
+            None => verify_signature(&sig)?,
         }
+        Ok(self)
     }
 }
 
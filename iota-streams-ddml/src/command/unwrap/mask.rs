@@ -14,6 +14,8 @@ use crate::{
     types::{
         ArrayLength,
         Bytes,
+        BytesBuf,
+        MaskedBytes,
         NBytes,
         Size,
         Uint16,
@@ -45,9 +47,8 @@ impl<F, IS> AsMut<Context<F, IS>> for MaskContext<F, IS> {
 impl<F: PRP, IS: io::IStream> Unwrap for MaskContext<F, IS> {
     fn unwrap_u8(&mut self, u: &mut u8) -> Result<&mut Self> {
         let y = self.ctx.stream.try_advance(1)?;
-        let mut x = [0_u8; 1];
-        self.ctx.spongos.decrypt(y, &mut x);
-        *u = x[0];
+        *u = y[0];
+        self.ctx.spongos.decrypt_in_place(core::slice::from_mut(u));
         Ok(self)
     }
     fn unwrapn(&mut self, bytes: &mut [u8]) -> Result<&mut Self> {
@@ -139,6 +140,31 @@ impl<'a, F: PRP, IS: io::IStream> Mask<&'a mut Bytes> for Context<F, IS> {
     }
 }
 
+/// The size is checked against the capacity of the buffer before the content bytes are read.
+impl<'a, 'b, F: PRP, IS: io::IStream> Mask<&'a mut BytesBuf<'b>> for Context<F, IS> {
+    fn mask(&mut self, bytes: &'a mut BytesBuf<'b>) -> Result<&mut Self> {
+        let mut size = Size(0);
+        self.mask(&mut size)?;
+        bytes.set_len(size.0)?;
+        Ok(unwrap_mask_bytes(self.as_mut(), bytes.as_mut_slice())?.as_mut())
+    }
+}
+
+impl<'a, F: PRP, IS: io::IStream> Mask<&'a mut MaskedBytes<F>> for Context<F, IS> {
+    fn mask(&mut self, masked: &'a mut MaskedBytes<F>) -> Result<&mut Self> {
+        if let MaskedBytes::Decrypted(bytes) = masked {
+            return self.mask(bytes);
+        }
+        let mut size = Size(0);
+        self.mask(&mut size)?;
+        let spongos = self.spongos.clone();
+        let ciphertext = self.stream.try_advance(size.0)?.to_vec();
+        self.spongos.decrypt_skip(&ciphertext);
+        *masked = MaskedBytes::new_deferred(ciphertext, spongos);
+        Ok(self)
+    }
+}
+
 impl<'a, F: PRP, IS: io::IStream> Mask<&'a mut x25519::PublicKey> for Context<F, IS> {
     fn mask(&mut self, pk: &'a mut x25519::PublicKey) -> Result<&mut Self> {
         let mut bytes = [0_u8; 32];
@@ -8,14 +8,24 @@ use crate::{
     io,
     types::Size,
 };
-use iota_streams_core::sponge::{
-    prp::PRP,
-    spongos::*,
+use iota_streams_core::{
+    prelude::Vec,
+    sponge::{
+        prp::PRP,
+        spongos::*,
+    },
 };
+use iota_streams_core_edsig::signature::ed25519::PrehashedSignature;
+
+use super::SignatureContext;
 
 pub struct Context<F, IS> {
     pub spongos: Spongos<F>,
     pub stream: IS,
+    /// Ed25519 signatures collected instead of verified, see `collect_signatures`.
+    signatures: Option<Vec<PrehashedSignature>>,
+    /// Context of Ed25519ph signatures.
+    sig_context: SignatureContext,
 }
 
 impl<F: PRP, IS> Context<F, IS> {
@@ -23,10 +33,33 @@ impl<F: PRP, IS> Context<F, IS> {
         Self {
             spongos: Spongos::<F>::init(),
             stream: stream,
+            signatures: None,
+            sig_context: SignatureContext::default(),
         }
     }
 }
 
+impl<F, IS> Context<F, IS> {
+    /// Verify subsequent `ed25519` commands with `context` instead of `SIGNATURE_CONTEXT`, at most
+    /// `MAX_SIGNATURE_CONTEXT_SIZE` bytes long.
+    pub fn set_signature_context(&mut self, context: &[u8]) {
+        self.sig_context = SignatureContext::new(context);
+    }
+
+    /// Collect subsequently unwrapped Ed25519 signatures without verifying them. The caller
+    /// must verify signatures returned by `take_signatures`, eg. with `verify_signatures`.
+    pub fn collect_signatures(&mut self) {
+        if self.signatures.is_none() {
+            self.signatures = Some(Vec::new());
+        }
+    }
+
+    /// Collected signatures, signatures are verified again from now on.
+    pub fn take_signatures(&mut self) -> Vec<PrehashedSignature> {
+        self.signatures.take().unwrap_or_default()
+    }
+}
+
 impl<F, IS: io::IStream> Context<F, IS> {
     pub fn drop(&mut self, n: Size) -> Result<&mut Self> {
         self.stream.try_advance(n.0)?;
@@ -50,6 +83,8 @@ where
         Self {
             spongos: self.spongos.clone(),
             stream: self.stream.clone(),
+            signatures: self.signatures.clone(),
+            sig_context: self.sig_context,
         }
     }
 }
@@ -65,6 +100,7 @@ mod guard;
 mod join;
 mod mask;
 mod repeated;
+mod section;
 mod skip;
 mod squeeze;
 mod squeeze_external;
@@ -81,6 +117,7 @@ pub use guard::*;
 pub use join::*;
 pub use mask::*;
 pub use repeated::*;
+pub use section::*;
 pub use skip::*;
 pub use squeeze::*;
 pub use squeeze_external::*;
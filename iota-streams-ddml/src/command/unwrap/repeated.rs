@@ -1,10 +1,16 @@
-use anyhow::Result;
+use anyhow::{
+    ensure,
+    Result,
+};
 
 use super::Context;
 use crate::{
     command::Repeated,
     io,
-    types::Size,
+    types::{
+        Bounded,
+        Size,
+    },
 };
 use iota_streams_core::sponge::prp::PRP;
 
@@ -19,3 +25,17 @@ where
         Ok(self)
     }
 }
+
+/// Bounded repeated modifier. The number of repetitions is checked against the bound
+/// before any value is handled, so a malformed message declaring a huge number of values
+/// is rejected early.
+impl<C, F: PRP, IS: io::IStream> Repeated<Bounded<Size>, C> for Context<F, IS>
+where
+    C: for<'a> FnMut(&'a mut Self) -> Result<&'a mut Self>,
+{
+    fn repeated(&mut self, n: Bounded<Size>, value_handle: C) -> Result<&mut Self> {
+        let Bounded(n, max) = n;
+        ensure!(n.0 <= max, "Too many repeated values: {} > {}.", n.0, max);
+        self.repeated(n, value_handle)
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+
+use super::Context;
+use crate::{
+    command::{
+        Absorb,
+        Section,
+    },
+    io,
+    types::Size,
+};
+use iota_streams_core::sponge::prp::PRP;
+
+/// Section fields are unwrapped from the section buffer with forked Spongos.
+/// Unrecognized trailing fields left in the section buffer are ignored.
+impl<C, F: PRP, IS: io::IStream> Section<C> for Context<F, IS>
+where
+    C: for<'a, 'b> FnMut(&'a mut Context<F, &'b [u8]>) -> Result<&'a mut Context<F, &'b [u8]>>,
+{
+    fn section(&mut self, mut cont: C) -> Result<&mut Self> {
+        // Fork before the size is absorbed, as the section is wrapped before its size is known.
+        let spongos = self.spongos.fork();
+        let mut size = Size(0);
+        self.absorb(&mut size)?;
+        let bytes = self.stream.try_advance(size.0)?;
+        let mut section = Context {
+            spongos,
+            stream: bytes,
+            signatures: self.signatures.take(),
+            sig_context: self.sig_context,
+        };
+        let result = cont(&mut section);
+        self.signatures = section.signatures.take();
+        result?;
+        self.spongos.absorb(bytes);
+        Ok(self)
+    }
+}
@@ -11,6 +11,7 @@ use crate::{
     types::{
         ArrayLength,
         Bytes,
+        BytesBuf,
         Fallback,
         NBytes,
         Size,
@@ -131,6 +132,16 @@ impl<'a, F, IS: io::IStream> Skip<&'a mut Bytes> for Context<F, IS> {
     }
 }
 
+/// The size is checked against the capacity of the buffer before the content bytes are read.
+impl<'a, 'b, F, IS: io::IStream> Skip<&'a mut BytesBuf<'b>> for Context<F, IS> {
+    fn skip(&mut self, bytes: &'a mut BytesBuf<'b>) -> Result<&mut Self> {
+        let mut size = Size(0);
+        self.skip(&mut size)?;
+        bytes.set_len(size.0)?;
+        Ok(unwrap_skip_bytes(self.as_mut(), bytes.as_mut_slice())?.as_mut())
+    }
+}
+
 impl<'a, F, T: 'a + SkipFallback<F>, IS: io::IStream> Skip<&'a mut Fallback<T>> for Context<F, IS> {
     fn skip(&mut self, val: &'a mut Fallback<T>) -> Result<&mut Self> {
         (val.0).unwrap_skip(self)?;
@@ -11,6 +11,7 @@ use crate::{
     io,
     types::{
         ArrayLength,
+        Bytes,
         NBytes,
     },
 };
@@ -45,3 +46,14 @@ impl<'a, F: PRP, N: ArrayLength<u8>, IS: io::IStream> X25519<&'a x25519::StaticS
             .mask(key)
     }
 }
+
+impl<'a, F: PRP, IS: io::IStream> X25519<&'a x25519::StaticSecret, &'a mut Bytes> for Context<F, IS> {
+    fn x25519(&mut self, sk: &x25519::StaticSecret, bytes: &mut Bytes) -> Result<&mut Self> {
+        let mut ephemeral_ke_pk = x25519::PublicKey::from([0_u8; 32]);
+        (*self)
+            .absorb(&mut ephemeral_ke_pk)?
+            .x25519(sk, &ephemeral_ke_pk)?
+            .commit()?
+            .mask(bytes)
+    }
+}
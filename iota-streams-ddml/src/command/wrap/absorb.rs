@@ -12,6 +12,7 @@ use crate::{
         AbsorbFallback,
         ArrayLength,
         Bytes,
+        BytesBuf,
         Fallback,
         NBytes,
         Size,
@@ -49,8 +50,8 @@ impl<F: PRP, OS: io::OStream> Wrap for AbsorbContext<F, OS> {
         Ok(self)
     }
     fn wrapn(&mut self, bytes: &[u8]) -> Result<&mut Self> {
-        self.ctx.spongos.absorb(bytes);
-        self.ctx.stream.try_advance(bytes.len())?.copy_from_slice(bytes);
+        let slice = self.ctx.stream.try_advance(bytes.len())?;
+        self.ctx.spongos.absorb_in_place(bytes, slice);
         Ok(self)
     }
 }
@@ -165,6 +166,13 @@ impl<'a, F: PRP, OS: io::OStream> Absorb<&'a Bytes> for Context<F, OS> {
     }
 }
 
+impl<'a, 'b, F: PRP, OS: io::OStream> Absorb<&'a BytesBuf<'b>> for Context<F, OS> {
+    fn absorb(&mut self, bytes: &'a BytesBuf<'b>) -> Result<&mut Self> {
+        self.absorb(Size(bytes.len()))?;
+        Ok(wrap_absorb_bytes(self.as_mut(), bytes.as_slice())?.as_mut())
+    }
+}
+
 impl<'a, F: PRP, OS: io::OStream> Absorb<&'a ed25519::PublicKey> for Context<F, OS> {
     fn absorb(&mut self, pk: &'a ed25519::PublicKey) -> Result<&mut Self> {
         Ok(wrap_absorb_bytes(self.as_mut(), &pk.to_bytes()[..])?.as_mut())
@@ -25,10 +25,9 @@ use iota_streams_core_edsig::signature::ed25519;
 /// Signature size depends on Merkle tree height.
 impl<F: PRP, OS: io::OStream> Ed25519<&ed25519::Keypair, &External<NBytes<U64>>> for Context<F, OS> {
     fn ed25519(&mut self, kp: &ed25519::Keypair, hash: &External<NBytes<U64>>) -> Result<&mut Self> {
-        let context = "IOTAStreams".as_bytes();
         let mut prehashed = Prehashed::default();
         prehashed.0.as_mut_slice().copy_from_slice((hash.0).as_slice());
-        match kp.sign_prehashed(prehashed, Some(&context[..])) {
+        match kp.sign_prehashed(prehashed, Some(self.sig_context.as_slice())) {
             Ok(signature) => {
                 self.stream
                     .try_advance(ed25519::SIGNATURE_LENGTH)?
@@ -11,6 +11,7 @@ use crate::{
     types::{
         ArrayLength,
         Bytes,
+        BytesBuf,
         NBytes,
         Size,
         Uint16,
@@ -43,7 +44,7 @@ impl<F: PRP, OS: io::OStream> Wrap for MaskContext<F, OS> {
     fn wrap_u8(&mut self, u: u8) -> Result<&mut Self> {
         let slice = self.ctx.stream.try_advance(1)?;
         slice[0] = u;
-        self.ctx.spongos.encrypt_mut(slice);
+        self.ctx.spongos.encrypt_in_place(slice);
         Ok(self)
     }
     fn wrapn(&mut self, bytes: &[u8]) -> Result<&mut Self> {
@@ -134,6 +135,13 @@ impl<'a, F: PRP, OS: io::OStream> Mask<&'a Bytes> for Context<F, OS> {
     }
 }
 
+impl<'a, 'b, F: PRP, OS: io::OStream> Mask<&'a BytesBuf<'b>> for Context<F, OS> {
+    fn mask(&mut self, bytes: &'a BytesBuf<'b>) -> Result<&mut Self> {
+        self.mask(&Size(bytes.len()))?;
+        Ok(wrap_mask_bytes(self.as_mut(), bytes.as_slice())?.as_mut())
+    }
+}
+
 impl<'a, F: PRP, OS: io::OStream> Mask<&'a x25519::PublicKey> for Context<F, OS> {
     fn mask(&mut self, pk: &'a x25519::PublicKey) -> Result<&mut Self> {
         Ok(wrap_mask_bytes(self.as_mut(), &pk.as_bytes()[..])?.as_mut())
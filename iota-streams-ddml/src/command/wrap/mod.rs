@@ -5,9 +5,13 @@ use iota_streams_core::sponge::{
     spongos::*,
 };
 
+use super::SignatureContext;
+
 pub struct Context<F, OS> {
     pub spongos: Spongos<F>,
     pub stream: OS,
+    /// Context of Ed25519ph signatures.
+    sig_context: SignatureContext,
 }
 
 impl<F: PRP, OS> Context<F, OS> {
@@ -15,10 +19,19 @@ impl<F: PRP, OS> Context<F, OS> {
         Self {
             spongos: Spongos::<F>::init(),
             stream: stream,
+            sig_context: SignatureContext::default(),
         }
     }
 }
 
+impl<F, OS> Context<F, OS> {
+    /// Sign subsequent `ed25519` commands with `context` instead of `SIGNATURE_CONTEXT`, at most
+    /// `MAX_SIGNATURE_CONTEXT_SIZE` bytes long.
+    pub fn set_signature_context(&mut self, context: &[u8]) {
+        self.sig_context = SignatureContext::new(context);
+    }
+}
+
 mod wrap;
 pub(crate) use wrap::*;
 
@@ -31,6 +44,7 @@ mod guard;
 mod join;
 mod mask;
 mod repeated;
+mod section;
 mod skip;
 mod squeeze;
 mod squeeze_external;
@@ -47,6 +61,7 @@ pub use guard::*;
 pub use join::*;
 pub use mask::*;
 pub use repeated::*;
+pub use section::*;
 pub use skip::*;
 pub use squeeze::*;
 pub use squeeze_external::*;
@@ -1,10 +1,14 @@
-use anyhow::Result;
+use anyhow::{
+    ensure,
+    Result,
+};
 use core::iter;
 
 use super::Context;
 use crate::{
     command::Repeated,
     io,
+    types::Bounded,
 };
 use iota_streams_core::sponge::prp::PRP;
 
@@ -22,3 +26,22 @@ where
         })
     }
 }
+
+/// Bounded repeated modifier. Fails if there are more values than allowed
+/// so that the wrapped message can be unwrapped with the same bound.
+impl<I, C, F: PRP, OS: io::OStream> Repeated<Bounded<I>, C> for Context<F, OS>
+where
+    I: iter::ExactSizeIterator,
+    C: for<'a> FnMut(&'a mut Self, <I as iter::Iterator>::Item) -> Result<&'a mut Self>,
+{
+    fn repeated(&mut self, values_iter: Bounded<I>, value_handle: C) -> Result<&mut Self> {
+        let Bounded(values_iter, max) = values_iter;
+        ensure!(
+            values_iter.len() <= max,
+            "Too many repeated values: {} > {}.",
+            values_iter.len(),
+            max
+        );
+        self.repeated(values_iter, value_handle)
+    }
+}
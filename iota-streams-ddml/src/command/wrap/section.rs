@@ -0,0 +1,32 @@
+use anyhow::Result;
+
+use super::Context;
+use crate::{
+    command::{
+        Absorb,
+        Section,
+    },
+    io,
+    types::Bytes,
+};
+use iota_streams_core::{
+    prelude::Vec,
+    sponge::prp::PRP,
+};
+
+/// Section fields are wrapped into a separate buffer with forked Spongos,
+/// the buffer is then absorbed as `bytes`.
+impl<C, F: PRP, OS: io::OStream> Section<C> for Context<F, OS>
+where
+    C: for<'a> FnMut(&'a mut Context<F, Vec<u8>>) -> Result<&'a mut Context<F, Vec<u8>>>,
+{
+    fn section(&mut self, mut cont: C) -> Result<&mut Self> {
+        let mut section = Context {
+            spongos: self.spongos.fork(),
+            stream: Vec::new(),
+            sig_context: self.sig_context,
+        };
+        cont(&mut section)?;
+        self.absorb(<&Bytes>::from(&section.stream))
+    }
+}
@@ -11,6 +11,7 @@ use crate::{
     types::{
         ArrayLength,
         Bytes,
+        BytesBuf,
         Fallback,
         NBytes,
         Size,
@@ -158,6 +159,13 @@ impl<'a, F, OS: io::OStream> Skip<&'a Bytes> for Context<F, OS> {
     }
 }
 
+impl<'a, 'b, F, OS: io::OStream> Skip<&'a BytesBuf<'b>> for Context<F, OS> {
+    fn skip(&mut self, bytes: &'a BytesBuf<'b>) -> Result<&mut Self> {
+        wrap_skip_size(self.as_mut(), Size(bytes.len()))?;
+        Ok(wrap_skip_trits(self.as_mut(), bytes.as_slice())?.as_mut())
+    }
+}
+
 impl<'a, F, T: 'a + SkipFallback<F>, OS: io::OStream> Skip<&'a Fallback<T>> for Context<F, OS> {
     fn skip(&mut self, val: &'a Fallback<T>) -> Result<&mut Self> {
         (val.0).wrap_skip(self)?;
@@ -1,9 +1,6 @@
-#[cfg(not(feature = "std"))]
-use anyhow::anyhow;
 use anyhow::Result;
 
 use super::Context;
-#[cfg(feature = "std")]
 use crate::command::{
     Absorb,
     Commit,
@@ -14,11 +11,15 @@ use crate::{
     io,
     types::{
         ArrayLength,
+        Bytes,
         NBytes,
     },
 };
 
-use iota_streams_core::sponge::prp::PRP;
+use iota_streams_core::{
+    prng::SystemRng,
+    sponge::prp::PRP,
+};
 use iota_streams_core_edsig::key_exchange::x25519;
 
 impl<'a, F: PRP, OS: io::OStream> X25519<&'a x25519::StaticSecret, &'a x25519::PublicKey> for Context<F, OS> {
@@ -37,10 +38,9 @@ impl<'a, F: PRP, OS: io::OStream> X25519<x25519::EphemeralSecret, &'a x25519::Pu
     }
 }
 
-#[cfg(feature = "std")]
 impl<'a, F: PRP, N: ArrayLength<u8>, OS: io::OStream> X25519<&'a x25519::PublicKey, &'a NBytes<N>> for Context<F, OS> {
     fn x25519(&mut self, pk: &x25519::PublicKey, key: &NBytes<N>) -> Result<&mut Self> {
-        let ephemeral_ke_sk = x25519::EphemeralSecret::new(&mut rand::thread_rng());
+        let ephemeral_ke_sk = x25519::EphemeralSecret::new(&mut SystemRng);
         let ephemeral_ke_pk = x25519::PublicKey::from(&ephemeral_ke_sk);
         self.absorb(&ephemeral_ke_pk)?
             .x25519(ephemeral_ke_sk, pk)?
@@ -49,10 +49,25 @@ impl<'a, F: PRP, N: ArrayLength<u8>, OS: io::OStream> X25519<&'a x25519::PublicK
     }
 }
 
-#[cfg(not(feature = "std"))]
-impl<'a, F: PRP, N: ArrayLength<u8>, OS: io::OStream> X25519<&'a x25519::PublicKey, &'a NBytes<N>> for Context<F, OS> {
-    fn x25519(&mut self, _pk: &x25519::PublicKey, _key: &NBytes<N>) -> Result<&mut Self> {
-        // TODO: no_std make default rng
-        Err(anyhow!("no_std default RNG not implemented"))
+/// Mask `key` with a key exchange computed ahead of wrapping, equivalent to the command above
+/// with the ephemeral secret of the exchange.
+impl<'a, F: PRP, N: ArrayLength<u8>, OS: io::OStream> X25519<&'a x25519::Exchange, &'a NBytes<N>> for Context<F, OS> {
+    fn x25519(&mut self, exchange: &x25519::Exchange, key: &NBytes<N>) -> Result<&mut Self> {
+        self.absorb(&exchange.ephemeral_pk)?;
+        self.spongos.absorb(exchange.shared.as_bytes());
+        self.commit()?.mask(key)
+    }
+}
+
+/// Encrypt `bytes` for the owner of `pk` with a fresh ephemeral key exchange. The field is
+/// readable by the recipient only, wrap it into a `section` to let other readers skip it.
+impl<'a, F: PRP, OS: io::OStream> X25519<&'a x25519::PublicKey, &'a Bytes> for Context<F, OS> {
+    fn x25519(&mut self, pk: &x25519::PublicKey, bytes: &Bytes) -> Result<&mut Self> {
+        let ephemeral_ke_sk = x25519::EphemeralSecret::new(&mut SystemRng);
+        let ephemeral_ke_pk = x25519::PublicKey::from(&ephemeral_ke_sk);
+        self.absorb(&ephemeral_ke_pk)?
+            .x25519(ephemeral_ke_sk, pk)?
+            .commit()?
+            .mask(bytes)
     }
 }
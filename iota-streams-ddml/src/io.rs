@@ -6,7 +6,9 @@ use anyhow::{
     Result,
 };
 
-use iota_streams_core::prelude::{hex, String, };
+use iota_streams_core::prelude::{hex, String, Vec, };
+#[cfg(feature = "heapless")]
+use iota_streams_core::prelude::bounded;
 
 /// Write
 pub trait OStream {
@@ -50,6 +52,7 @@ pub trait IStream {
     }
 }
 
+/// Fixed-size output stream over a caller-provided buffer, advancing does not allocate.
 impl<'b> OStream for &'b mut [u8] {
     fn try_advance<'a>(&'a mut self, n: usize) -> Result<&'a mut [u8]> {
         ensure!(n <= self.len(), "Output slice too short.");
@@ -65,6 +68,7 @@ impl<'b> OStream for &'b mut [u8] {
     }
 }
 
+/// Input stream over a caller-provided buffer, advancing does not allocate.
 impl<'b> IStream for &'b [u8] {
     fn try_advance<'a>(&'a mut self, n: usize) -> Result<&'a [u8]> {
         ensure!(n <= self.len(), "Input slice too short.");
@@ -80,6 +84,38 @@ impl<'b> IStream for &'b [u8] {
     }
 }
 
+/// Growable output stream, used when the size of the output is not known in advance.
+impl OStream for Vec<u8> {
+    fn try_advance<'a>(&'a mut self, n: usize) -> Result<&'a mut [u8]> {
+        let m = self.len();
+        self.resize(m + n, 0);
+        Ok(&mut self[m..])
+    }
+    fn commit(&mut self) {}
+    fn dump(&self) -> String {
+        format!("{}", hex::encode(self))
+    }
+}
+
+/// Output stream of at most `N` bytes, advancing past the capacity fails.
+#[cfg(feature = "heapless")]
+impl<const N: usize> OStream for bounded::Vec<u8, N> {
+    fn try_advance<'a>(&'a mut self, n: usize) -> Result<&'a mut [u8]> {
+        let m = self.len();
+        ensure!(
+            self.resize(m + n, 0).is_ok(),
+            "Output buffer capacity exceeded: {} > {}.",
+            m + n,
+            N
+        );
+        Ok(&mut self[m..])
+    }
+    fn commit(&mut self) {}
+    fn dump(&self) -> String {
+        format!("{}", hex::encode(self))
+    }
+}
+
 pub struct NoOStream;
 
 impl OStream for NoOStream {
@@ -1,5 +1,10 @@
 //! DDML is a data description language extended with commands for cryptographic processing.
 //! DDML is implemented as an EDSL in rust.
+//!
+//! Messages can be wrapped and unwrapped without a heap allocator: contexts over `&mut [u8]` and
+//! `&[u8]` streams sized with `sizeof::Context`, fixed-size types (`NBytes`, `Uint*`, keys) and
+//! `BytesBuf` fields over caller-provided buffers do not allocate. `Bytes`, `section`s, `dump`
+//! and collected signatures allocate.
 
 #![no_std]
 
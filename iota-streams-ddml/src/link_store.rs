@@ -48,6 +48,12 @@ pub trait LinkStore<F, Link> {
     /// Remove link and associated info from the store.
     fn erase(&mut self, _link: &Link) {}
 
+    /// Remove links stored before `link`, except links in `keep`. Returns the number of
+    /// removed links. Lookups of removed links fail afterwards.
+    fn prune_before(&mut self, _link: &Link, _keep: &[Link]) -> Result<usize> {
+        Err(anyhow!("Link store does not support pruning."))
+    }
+
     fn iter(&self) -> Vec<(&Link, &(Inner<F>, Self::Info))> where F: PRP;
 }
 
@@ -72,6 +78,9 @@ impl<F, Link, Info> LinkStore<F, Link> for EmptyLinkStore<F, Link, Info> {
     fn insert(&mut self, _link: &Link, _spongos: Inner<F>, _info: Self::Info) -> Result<()> where F: PRP {
         Ok(())
     }
+    fn prune_before(&mut self, _link: &Link, _keep: &[Link]) -> Result<usize> {
+        Ok(0)
+    }
 }
 
 /// Link store that contains a single link.
@@ -125,6 +134,11 @@ where
 
 pub struct DefaultLinkStore<F: PRP, Link, Info> {
     map: HashMap<Link, (Inner<F>, Info)>,
+    /// Order in which links were stored, links inserted in bulk share the order.
+    order: HashMap<Link, u64>,
+    next_order: u64,
+    /// Number of links removed by `prune_before`.
+    pruned: usize,
     _phantom: core::marker::PhantomData<F>,
 }
 
@@ -135,11 +149,27 @@ where
     fn default() -> Self {
         Self {
             map: HashMap::new(),
+            order: HashMap::new(),
+            next_order: 0,
+            pruned: 0,
             _phantom: core::marker::PhantomData,
         }
     }
 }
 
+impl<F: PRP, Link, Info> DefaultLinkStore<F, Link, Info>
+where
+    Link: Eq + hash::Hash + Clone,
+{
+    fn not_found(&self) -> anyhow::Error {
+        if self.pruned == 0 {
+            anyhow!("Link not found")
+        } else {
+            anyhow!("Link not found, {} links older than a checkpoint were pruned", self.pruned)
+        }
+    }
+}
+
 impl<F: PRP, Link, Info> LinkStore<F, Link> for DefaultLinkStore<F, Link, Info>
 where
     Link: Eq + hash::Hash + Clone,
@@ -152,7 +182,7 @@ where
         if let Some((inner, info)) = self.map.get(link) {
             Ok((inner.into(), info.clone()))
         } else {
-            Err(anyhow!("Link not found"))
+            Err(self.not_found())
         }
     }
 
@@ -160,17 +190,41 @@ where
     fn update(&mut self, link: &Link, spongos: Spongos<F>, info: Info) -> Result<()> {
         let inner = spongos.to_inner();
         self.map.insert(link.clone(), (inner, info));
+        if !self.order.contains_key(link) {
+            self.next_order += 1;
+            self.order.insert(link.clone(), self.next_order);
+        }
         Ok(())
     }
 
     fn insert(&mut self, link: &Link, inner: Inner<F>, info: Self::Info) -> Result<()> {
         self.map.insert(link.clone(), (inner, info));
+        self.order.entry(link.clone()).or_insert(self.next_order);
         Ok(())
     }
 
     /// Remove info for the link.
     fn erase(&mut self, link: &Link) {
         self.map.remove(link);
+        self.order.remove(link);
+    }
+
+    fn prune_before(&mut self, link: &Link, keep: &[Link]) -> Result<usize> {
+        let checkpoint = match self.order.get(link) {
+            Some(order) => *order,
+            None => return Err(self.not_found()),
+        };
+        let pruned: Vec<Link> = self
+            .order
+            .iter()
+            .filter(|(l, order)| **order < checkpoint && !keep.contains(l))
+            .map(|(l, _)| l.clone())
+            .collect();
+        for l in pruned.iter() {
+            self.erase(l);
+        }
+        self.pruned += pruned.len();
+        Ok(pruned.len())
     }
 
     fn iter(&self) -> Vec<(&Link, &(Inner<F>, Self::Info))> {
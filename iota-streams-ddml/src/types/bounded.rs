@@ -0,0 +1,7 @@
+/// DDML `repeated` bound. The first component is the repetitions argument (values iterator
+/// or counter), the second one is the maximum allowed number of repetitions.
+///
+/// In Unwrap command the number of repetitions is decoded from the message and is untrusted,
+/// the bound is checked before any repetition is processed.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Bounded<T>(pub T, pub usize);
@@ -0,0 +1,82 @@
+use anyhow::{
+    ensure,
+    Result,
+};
+use core::fmt;
+
+use iota_streams_core::prelude::hex;
+
+/// Variable-size bytes stored in a caller-provided buffer, encoded like `Bytes`.
+///
+/// `BytesBuf` is the alloc-free counterpart of `Bytes`: the capacity of the buffer is the maximum
+/// size of the field, unwrapping a longer field fails instead of growing the buffer.
+pub struct BytesBuf<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> BytesBuf<'a> {
+    /// Empty bytes to be unwrapped into `buf`.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// Bytes to be wrapped, filling the whole `buf`.
+    pub fn full(buf: &'a mut [u8]) -> Self {
+        let len = buf.len();
+        Self { buf, len }
+    }
+
+    /// Maximum size of the bytes.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Resize to `len` bytes within the capacity, new bytes are unspecified.
+    pub fn set_len(&mut self, len: usize) -> Result<()> {
+        ensure!(
+            len <= self.buf.len(),
+            "Bytes buffer too short: {} > {}.",
+            len,
+            self.buf.len()
+        );
+        self.len = len;
+        Ok(())
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.buf[..self.len]
+    }
+}
+
+impl<'a> PartialEq for BytesBuf<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<'a> Eq for BytesBuf<'a> {}
+
+impl<'a> fmt::Display for BytesBuf<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.as_slice()))
+    }
+}
+
+impl<'a> fmt::Debug for BytesBuf<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BytesBuf({})", self)
+    }
+}
@@ -0,0 +1,75 @@
+use core::cell::RefCell;
+
+use iota_streams_core::{
+    prelude::Vec,
+    sponge::{
+        prp::PRP,
+        spongos::Spongos,
+    },
+};
+
+use super::Bytes;
+
+/// Masked bytes as unwrapped: decrypted right away, or kept encrypted together with the
+/// spongos state preceding them and decrypted on first access.
+///
+/// The initial value selects the behaviour of `mask` on unwrap, see `MaskedBytes::deferred`.
+#[derive(Clone)]
+pub enum MaskedBytes<F> {
+    Decrypted(Bytes),
+    Deferred {
+        ciphertext: Vec<u8>,
+        spongos: Spongos<F>,
+        plaintext: RefCell<Option<Bytes>>,
+    },
+}
+
+impl<F: PRP> MaskedBytes<F> {
+    /// Placeholder for masked bytes to be kept encrypted on unwrap.
+    pub fn deferred() -> Self {
+        Self::new_deferred(Vec::new(), Spongos::init())
+    }
+
+    pub(crate) fn new_deferred(ciphertext: Vec<u8>, spongos: Spongos<F>) -> Self {
+        Self::Deferred {
+            ciphertext,
+            spongos,
+            plaintext: RefCell::new(None),
+        }
+    }
+
+    pub fn is_deferred(&self) -> bool {
+        match self {
+            Self::Decrypted(_) => false,
+            Self::Deferred { .. } => true,
+        }
+    }
+
+    /// Plain bytes, decrypted on the first call if deferred.
+    pub fn decrypt(&self) -> Bytes {
+        match self {
+            Self::Decrypted(bytes) => bytes.clone(),
+            Self::Deferred {
+                ciphertext,
+                spongos,
+                plaintext,
+            } => plaintext
+                .borrow_mut()
+                .get_or_insert_with(|| Bytes(spongos.clone().decrypt_n(ciphertext)))
+                .clone(),
+        }
+    }
+
+    pub fn into_bytes(self) -> Bytes {
+        match self {
+            Self::Decrypted(bytes) => bytes,
+            deferred => deferred.decrypt(),
+        }
+    }
+}
+
+impl<F> Default for MaskedBytes<F> {
+    fn default() -> Self {
+        Self::Decrypted(Bytes::default())
+    }
+}
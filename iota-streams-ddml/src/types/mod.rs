@@ -13,8 +13,12 @@ pub use iota_streams_core::prelude::{
     },
 };
 
+mod bounded;
+pub use bounded::*;
 mod bytes;
 pub use bytes::*;
+mod bytes_buf;
+pub use bytes_buf::*;
 mod external;
 pub use external::*;
 mod fallback;
@@ -23,6 +27,8 @@ mod hashsig;
 pub use hashsig::*;
 mod mac;
 pub use mac::*;
+mod masked;
+pub use masked::*;
 mod nbytes;
 pub use nbytes::*;
 mod prehashed;